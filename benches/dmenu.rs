@@ -0,0 +1,66 @@
+//! `--dmenu` benchmark: scoring a large standard input
+//!
+//! Filters 100,000 path-like lines, read in chunks as `grunner --dmenu`
+//! reads them, with queries of different selectivity. Every keystroke costs
+//! one such run, so the median should stay well under 100 ms in a release
+//! build:
+//!
+//! ```text
+//! cargo bench --features bench --bench dmenu
+//! ```
+
+use grunner::core::config::FuzzyCase;
+use grunner::dmenu::{CHUNK_LINES, Lines, filter_lines};
+use grunner::providers::matcher::build_matcher;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const LINE_COUNT: usize = 100_000;
+const LIMIT: usize = 50;
+const SAMPLES: usize = 20;
+
+/// Deterministic paths, in chunks of [`CHUNK_LINES`]
+fn synthetic_lines() -> Lines {
+    let mut lines = Lines::default();
+    let mut chunk = Vec::with_capacity(CHUNK_LINES);
+    for i in 0..LINE_COUNT {
+        chunk.push(format!(
+            "/usr/share/doc/package-{i}/changelog-{}.txt",
+            i % 977
+        ));
+        if chunk.len() == CHUNK_LINES {
+            lines.push(std::mem::replace(
+                &mut chunk,
+                Vec::with_capacity(CHUNK_LINES),
+            ));
+        }
+    }
+    lines.push(chunk);
+    lines
+}
+
+fn main() {
+    let lines = synthetic_lines();
+    let matcher = build_matcher(FuzzyCase::Smart, 0);
+    let never = || false;
+
+    for query in ["p", "pkg", "pack42chlog", "changelog-976"] {
+        let mut samples: Vec<Duration> = (0..SAMPLES)
+            .map(|_| {
+                let start = Instant::now();
+                black_box(filter_lines(&lines, query, &matcher, LIMIT, &never));
+                start.elapsed()
+            })
+            .collect();
+        samples.sort_unstable();
+        let matched = filter_lines(&lines, query, &matcher, LIMIT, &never)
+            .map_or(0, |filtered| filtered.matched);
+        println!(
+            "{:<40} time: [{:>10.1?} {:>10.1?} {:>10.1?}]  matches: {matched}",
+            format!("dmenu-100k/{query}"),
+            samples[0],
+            samples[SAMPLES / 2],
+            samples[SAMPLES - 1],
+        );
+    }
+}
@@ -0,0 +1,102 @@
+//! Matcher benchmark: fuzzy ranking vs. the substring scorer
+//!
+//! Runs both app scorers over 2000 synthetic applications so changes to the
+//! matcher or its `[search]` settings come with numbers. Criterion-style
+//! output (median time per iteration) without the dependency:
+//!
+//! ```text
+//! cargo bench --features bench --bench matcher
+//! ```
+
+use grunner::core::config::FuzzyCase;
+use grunner::launcher::DesktopApp;
+use grunner::providers::match_substring;
+use grunner::providers::matcher::build_matcher;
+use grunner::providers::ranking::{FieldWeights, query_words, rank_apps};
+use std::hint::black_box;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+const APP_COUNT: usize = 2000;
+const SAMPLES: usize = 30;
+const ITERATIONS_PER_SAMPLE: u32 = 10;
+
+const WORDS: &[&str] = &[
+    "Files", "Terminal", "Text", "Editor", "Image", "Viewer", "Music", "Player", "Video", "Disk",
+    "Usage", "Web", "Browser", "Mail", "Calendar", "Office", "Writer", "Settings", "System",
+    "Monitor",
+];
+
+/// Deterministic list of apps with two- or three-word names
+fn synthetic_apps() -> Vec<DesktopApp> {
+    (0..APP_COUNT)
+        .map(|i| {
+            let name = format!(
+                "{} {} {}",
+                WORDS[i % WORDS.len()],
+                WORDS[(i / WORDS.len()) % WORDS.len()],
+                i
+            );
+            DesktopApp {
+                desktop_id: format!("org.example.App{i}"),
+                name_lower: name.to_lowercase(),
+                description: format!("Synthetic application number {i}"),
+                generic_name: format!("{} Tool", WORDS[(i * 3) % WORDS.len()]),
+                keywords: vec![WORDS[(i * 7) % WORDS.len()].to_lowercase()],
+                exec: format!("app{i}"),
+                icon: String::new(),
+                terminal: false,
+                actions: Vec::new(),
+                startup_wm_class: String::new(),
+                path: PathBuf::from(format!("/usr/share/applications/app{i}.desktop")),
+                name,
+            }
+        })
+        .collect()
+}
+
+/// Print the fastest, median and slowest time of one call of `f`
+fn bench(name: &str, mut f: impl FnMut() -> usize) {
+    let mut samples: Vec<Duration> = (0..SAMPLES)
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..ITERATIONS_PER_SAMPLE {
+                black_box(f());
+            }
+            start.elapsed() / ITERATIONS_PER_SAMPLE
+        })
+        .collect();
+    samples.sort_unstable();
+    let median = samples[SAMPLES / 2];
+    println!(
+        "{name:<40} time: [{:>10.1?} {:>10.1?} {:>10.1?}]  matches: {}",
+        samples[0],
+        median,
+        samples[SAMPLES - 1],
+        f()
+    );
+}
+
+fn main() {
+    let apps = synthetic_apps();
+    let weights = FieldWeights::default();
+
+    for query in ["te", "disk", "web brows", "mntr"] {
+        let query_lower = query.to_lowercase();
+        bench(&format!("substring/{query}"), || {
+            match_substring(&query_lower, &apps).len()
+        });
+
+        let words = query_words(query);
+        for (label, case) in [("smart", FuzzyCase::Smart), ("ignore", FuzzyCase::Ignore)] {
+            let matcher = build_matcher(case, 0);
+            bench(&format!("skim-{label}/{query}"), || {
+                rank_apps(&matcher, &words, &apps, weights).len()
+            });
+        }
+        let limited = build_matcher(FuzzyCase::Smart, 200);
+        bench(&format!("skim-limit-200/{query}"), || {
+            rank_apps(&limited, &words, &apps, weights).len()
+        });
+    }
+}
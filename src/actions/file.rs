@@ -1,115 +1,293 @@
 use crate::utils::clipboard::copy_text;
 use gtk4::gio;
-use gtk4::prelude::AppInfoExt;
+use gtk4::prelude::{AppInfoExt, DisplayExt, FileExt};
 use log::{debug, error, info, warn};
+use std::ffi::{OsStr, OsString};
+use std::os::unix::ffi::OsStrExt;
 use std::path::Path;
 
-/// Parse a `file:line:content` pattern (like grep -n output)
+/// A grep result line split into its fields
 ///
-/// Returns (`file_path`, `line_number`) if the input matches "path:line:" format
-/// where `line_number` is a positive integer.
-pub(crate) fn parse_file_line(line: &str) -> Option<(&str, u32)> {
-    // Find the first colon that separates file path from line number
-    // We look for pattern: file_path:line_number:rest
-    // file_path cannot contain colon on Unix systems
-    let mut parts = line.splitn(3, ':');
-    let file = parts.next()?;
-    if file.is_empty() {
-        return None; // File path cannot be empty
+/// Produced by [`parse_grep_line`] from `file:line:text` (grep) or
+/// `file:line:column:text` (`rg --column`) output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct GrepMatch<'a> {
+    /// Path of the matching file, byte for byte
+    pub file: &'a Path,
+    /// 1-based line number
+    pub line: u32,
+    /// 1-based byte column of the match, if the tool reported one
+    pub column: Option<u32>,
+    /// The matching line's content
+    pub text: String,
+}
+
+/// Parse a `file:line[:column]:content` grep result line
+///
+/// `line` holds the exact bytes printed by the search command. With `rg
+/// --null` or `grep -Z` the file name ends at a NUL byte instead of a colon,
+/// so it may contain colons; otherwise it ends at the first colon. Only the
+/// fields after the file name are decoded (lossily). `with_column` tells
+/// whether the command was run with `--column`: only then is the field after
+/// the line number a column, otherwise it is part of the content (e.g. grep
+/// output whose content starts with `12:30`).
+///
+/// Returns `None` unless the line has `file:line:`, or `file:line:column:`
+/// with `with_column`, with an absolute file path and positive numbers.
+pub(crate) fn parse_grep_line(line: &[u8], with_column: bool) -> Option<GrepMatch<'_>> {
+    let end = line
+        .iter()
+        .position(|&b| b == 0)
+        .or_else(|| line.iter().position(|&b| b == b':'))?;
+    let file = Path::new(OsStr::from_bytes(&line[..end]));
+    if !file.is_absolute() {
+        return None; // Searches always print absolute paths
+    }
+    let fields = String::from_utf8_lossy(&line[end + 1..]);
+    let (line_num, column, text) = parse_grep_fields(&fields, with_column)?;
+    Some(GrepMatch {
+        file,
+        line: line_num,
+        column,
+        text: text.to_string(),
+    })
+}
+
+/// Parse the `line[:column]:content` fields that follow the file name
+///
+/// The column field is only read `with_column`, see [`parse_grep_line`].
+fn parse_grep_fields(fields: &str, with_column: bool) -> Option<(u32, Option<u32>, &str)> {
+    let (line_num, rest) = fields.split_once(':')?;
+    let line_num = positive_number(line_num)?;
+    if !with_column {
+        return Some((line_num, None, rest));
+    }
+    let (column, text) = rest.split_once(':')?;
+    Some((line_num, Some(positive_number(column)?), text))
+}
+
+/// The value of a line or column field: digits only, starting at 1
+fn positive_number(field: &str) -> Option<u32> {
+    if field.is_empty() || !field.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
     }
-    let line_str = parts.next()?;
-    // There must be a third part (the content after second colon)
-    parts.next()?;
+    field.parse::<u32>().ok().filter(|&n| n > 0)
+}
+
+/// Editors that run inside a terminal and have no window of their own
+const TERMINAL_EDITORS: &[&str] = &[
+    "vi", "vim", "nvim", "nano", "hx", "helix", "micro", "kak", "joe", "ne", "mcedit",
+];
+
+/// Program name of an editor command such as `/usr/bin/nvim` or `code --wait`
+fn editor_name(editor: &str) -> &str {
+    let program = editor.split_whitespace().next().unwrap_or(editor);
+    Path::new(program)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(program)
+}
 
-    // Parse line number
-    let line_num = line_str.parse::<u32>().ok()?;
-    if line_num == 0 {
-        return None; // Line numbers start at 1
+/// Whether `editor` has to be run inside a terminal emulator
+fn is_terminal_editor(editor: &str) -> bool {
+    let name = editor_name(editor);
+    if name == "emacs" {
+        return editor.split_whitespace().any(|arg| arg == "-nw");
     }
+    TERMINAL_EDITORS.contains(&name)
+}
+
+/// An editor picked by [`resolve_editor`]
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct EditorChoice<'a> {
+    /// Editor command line, possibly with arguments
+    pub command: &'a str,
+    /// Whether it must be wrapped in the terminal emulator
+    pub terminal: bool,
+}
+
+/// Pick the editor to open a file in
+///
+/// Candidates are tried in order: the configured editor, `$VISUAL`, then
+/// `$EDITOR`. Empty values are skipped, and so are terminal editors when no
+/// terminal emulator was found. `None` means the file should go to the
+/// default application instead.
+pub(crate) fn resolve_editor<'a>(
+    configured: Option<&'a str>,
+    visual: Option<&'a str>,
+    editor: Option<&'a str>,
+    has_terminal: bool,
+) -> Option<EditorChoice<'a>> {
+    [configured, visual, editor]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .filter(|command| !command.is_empty())
+        .map(|command| EditorChoice {
+            command,
+            terminal: is_terminal_editor(command),
+        })
+        .find(|choice| !choice.terminal || has_terminal)
+}
 
-    Some((file, line_num))
+/// [`resolve_editor`] with `$VISUAL`, `$EDITOR` and the detected terminal
+fn resolve_editor_from_env(configured: Option<&str>) -> Option<(String, bool)> {
+    let visual = std::env::var("VISUAL").ok();
+    let editor = std::env::var("EDITOR").ok();
+    let has_terminal = crate::actions::launcher::terminal().is_some();
+    resolve_editor(
+        configured,
+        visual.as_deref(),
+        editor.as_deref(),
+        has_terminal,
+    )
+    .map(|choice| (choice.command.to_string(), choice.terminal))
 }
 
-/// Open a file or `<file:line>` combination
+/// Build the argument vector that opens `file` at `line` (and `column`) in
+/// `editor`
 ///
-/// # Arguments
-/// * `line` - Either a file path or `<file:line>` format
+/// `editor` is split like a shell would, so it may carry arguments (`code
+/// --wait`). Editors that accept a column get one; the rest only jump to the
+/// line. The file name stays a single argument with its exact bytes. Returns
+/// `None` if `editor` cannot be split, e.g. for an unclosed quote.
+fn editor_argv(editor: &str, file: &Path, line: u32, column: Option<u32>) -> Option<Vec<OsString>> {
+    let mut argv = glib::shell_parse_argv(editor).ok()?;
+    let file_at = |position: String| {
+        let mut arg = file.as_os_str().to_owned();
+        arg.push(position);
+        arg
+    };
+    let (option, file_arg): (Option<String>, OsString) = match (editor_name(editor), column) {
+        ("code" | "codium", Some(col)) => {
+            (Some("--goto".into()), file_at(format!(":{line}:{col}")))
+        }
+        ("code" | "codium", None) => (Some("--goto".into()), file_at(format!(":{line}"))),
+        ("hx" | "helix", Some(col)) => (None, file_at(format!(":{line}:{col}"))),
+        ("hx" | "helix", None) => (None, file_at(format!(":{line}"))),
+        ("nvim" | "vim", Some(col)) => (
+            Some(format!("+call cursor({line},{col})")),
+            file.as_os_str().to_owned(),
+        ),
+        ("nano", Some(col)) => (Some(format!("+{line},{col}")), file.as_os_str().to_owned()),
+        _ => (Some(format!("+{line}")), file.as_os_str().to_owned()),
+    };
+    argv.extend(option.map(OsString::from));
+    argv.push(file_arg);
+    Some(argv)
+}
+
+/// Open `file`, at `line_num` and `column` of a grep match if given
 ///
-/// If no display is available, falls back to silently ignoring clipboard copy.
+/// A position opens in `$VISUAL` or `$EDITOR`, the whole file via GIO. If
+/// the file doesn't exist, its path is copied to the clipboard instead.
+pub fn open_file(file: &Path, position: Option<(u32, Option<u32>)>) {
+    debug!("Opening file {} at {position:?}", file.display());
+    if !file.exists() {
+        let display = file.to_string_lossy();
+        warn!("Path does not exist, copying to clipboard: {display}");
+        copy_text(&display);
+        info!("Copied text to clipboard: {display}");
+        return;
+    }
+    match position {
+        Some((line_num, column)) => open_file_at_line(file, line_num, column),
+        None => open_file_via_gio(file),
+    }
+}
+
+/// Open `file` at `line_num` in `$VISUAL` or `$EDITOR`, or via GIO if
+/// neither is set
+fn open_file_at_line(file: &Path, line_num: u32, column: Option<u32>) {
+    info!("Opening file {} at line {line_num}", file.display());
+    if !open_in_editor(None, file, line_num, column) {
+        // No usable editor, open via GIO (default app for file type)
+        open_file_via_gio(file);
+    }
+}
+
+/// Open `file` at `line_num` in the editor picked by [`resolve_editor`]
 ///
-/// If the input matches `<file:line:content>` format (like grep output),
-/// opens the file at the specified line using the system EDITOR or GIO.
-/// If it's just a file path, opens the file via GIO.
-/// If the path doesn't exist, copies the text to clipboard as a fallback.
-pub fn open_file_or_line(line: &str) {
-    debug!("Opening file or line: {line}");
-    // Check if input matches "file:line:content" pattern (like grep -n output)
-    if let Some((file, line_num)) = parse_file_line(line) {
-        // Verify file exists before attempting to open
-        if Path::new(file).exists() {
-            info!("Opening file {file} at line {line_num}");
-            let editor = std::env::var("EDITOR").unwrap_or_else(|_| String::new());
-            debug!("Using editor: {editor}");
-
-            if editor.is_empty() {
-                // No EDITOR set, open via GIO (default app for file type)
-                open_file_via_gio(file);
-                return;
-            }
-
-            let cmdline = match editor.as_str() {
-                "code" | "codium" | "hx" | "helix" => {
-                    format!("{editor} {file}:{line_num}")
-                }
-                _ => format!("{editor} +{line_num} {file}"),
-            };
-
-            debug!("Launching editor via AppInfo: {cmdline}");
-            match gio::AppInfo::create_from_commandline(
-                &cmdline,
-                Some(&editor),
-                gio::AppInfoCreateFlags::SUPPORTS_STARTUP_NOTIFICATION,
-            ) {
-                Ok(app_info) => {
-                    if let Err(e) =
-                        app_info.launch(&[] as &[gio::File], gio::AppLaunchContext::NONE)
-                    {
-                        error!("Failed to open file {file} at line {line_num}: {e}");
-                    } else {
-                        info!("Successfully opened file {file} at line {line_num}");
-                    }
-                }
-                Err(e) => {
-                    error!("Failed to create AppInfo for editor: {e}");
-                }
-            }
-            return;
+/// Terminal editors are run inside the detected terminal emulator, others
+/// are spawned with their arguments directly. Returns `false` if no editor
+/// is set or it could not be launched.
+pub(crate) fn open_in_editor(
+    configured: Option<&str>,
+    file: &Path,
+    line_num: u32,
+    column: Option<u32>,
+) -> bool {
+    let shown = file.display();
+    let Some((editor, terminal)) = resolve_editor_from_env(configured) else {
+        debug!("No editor set");
+        return false;
+    };
+    debug!("Using editor: {editor} (terminal: {terminal})");
+
+    let Some(argv) = editor_argv(&editor, file, line_num, column) else {
+        warn!("Cannot parse the editor command: {editor}");
+        return false;
+    };
+    if terminal {
+        crate::actions::launcher::run_argv_in_terminal(&argv);
+        return true;
+    }
+
+    // The arguments go to the editor as they are: a command line would be
+    // split on whitespace and have its `%` field codes expanded by GIO
+    debug!("Launching editor: {argv:?}");
+    let args: Vec<&OsStr> = argv.iter().map(OsString::as_os_str).collect();
+    match gio::Subprocess::newv(&args, gio::SubprocessFlags::NONE) {
+        Ok(_) => {
+            info!("Successfully opened file {shown} at line {line_num}");
+            true
+        }
+        Err(e) => {
+            error!("Failed to open file {shown} at line {line_num}: {e}");
+            false
         }
     }
+}
 
-    // If not a file:line pattern or file doesn't exist, try opening as plain file
-    if Path::new(line).exists() {
-        open_file_via_gio(line);
-    } else {
-        // Path doesn't exist - copy text to clipboard as fallback
-        warn!("Path does not exist, copying to clipboard: {line}");
-        copy_text(line);
-        info!("Copied text to clipboard: {line}");
+/// Open `file` in the default application for plain text
+///
+/// Unlike [`open_file_via_gio`], this ignores the association of the file's
+/// own type, so e.g. a `.toml` file mapped to a web browser still opens in
+/// a text editor. Returns `false` if there is no such application.
+pub(crate) fn open_file_as_text(file: &Path) -> bool {
+    let Some(app_info) = gio::AppInfo::default_for_type("text/plain", false) else {
+        debug!("No default application for text/plain");
+        return false;
+    };
+    let ctx = gtk4::gdk::Display::default().map(|d| d.app_launch_context());
+    match app_info.launch(&[gio::File::for_path(file)], ctx.as_ref()) {
+        Ok(()) => {
+            info!(
+                "Opened {} with {}",
+                file.display(),
+                app_info.name().as_str()
+            );
+            true
+        }
+        Err(e) => {
+            error!("Failed to open {} as text: {e}", file.display());
+            false
+        }
     }
 }
 
 /// Open a file with the default application via GIO
-fn open_file_via_gio(path: &str) {
-    info!("Opening file: {path}");
-    let file_uri = if path.starts_with('/') {
-        format!("file://{path}")
-    } else {
-        path.to_string()
-    };
+///
+/// The URI is built by GIO, which percent-encodes bytes that are not valid
+/// UTF-8 so the exact file is opened.
+fn open_file_via_gio(path: &Path) {
+    let shown = path.display();
+    info!("Opening file: {shown}");
+    let file_uri = gio::File::for_path(path).uri();
     if let Err(e) = gio::AppInfo::launch_default_for_uri(&file_uri, gio::AppLaunchContext::NONE) {
-        error!("Failed to open file {path}: {e}");
+        error!("Failed to open file {shown}: {e}");
     } else {
-        info!("Successfully opened file: {path}");
+        info!("Successfully opened file: {shown}");
     }
 }
 
@@ -117,6 +295,11 @@ fn open_file_via_gio(path: &str) {
 mod tests {
     use super::*;
 
+    /// The file and line of a grep result line
+    fn parse_file_line(line: &str) -> Option<(&str, u32)> {
+        parse_grep_line(line.as_bytes(), false).map(|m| (m.file.to_str().unwrap(), m.line))
+    }
+
     #[test]
     fn test_parse_file_line_valid() {
         let result = parse_file_line("/path/to/file.rs:42:some content");
@@ -166,6 +349,23 @@ mod tests {
         assert_eq!(result, None);
     }
 
+    #[test]
+    fn test_parse_file_line_relative_or_windows_path() {
+        assert_eq!(parse_file_line("src/main.rs:3:fn main"), None);
+        assert_eq!(parse_file_line(r"C:\Users\me\a.txt:3:hello"), None);
+    }
+
+    #[test]
+    fn test_parse_file_line_colon_in_file_name() {
+        // rg --null / grep -Z output
+        assert_eq!(
+            parse_file_line("/notes/12:30 standup.md\u{0}4:agenda"),
+            Some(("/notes/12:30 standup.md", 4))
+        );
+        // Without them the first colon ends the file name
+        assert_eq!(parse_file_line("/notes/12:30 standup.md:4:agenda"), None);
+    }
+
     #[test]
     fn test_parse_file_line_large_line() {
         let result = parse_file_line("/file:999999:content");
@@ -177,4 +377,209 @@ mod tests {
         let result = parse_file_line("/file:10:");
         assert_eq!(result, Some(("/file", 10)));
     }
+
+    #[test]
+    fn test_parse_grep_line_with_column() {
+        let m = parse_grep_line("/src/main.rs:12:5:fn main() {".as_bytes(), true).unwrap();
+        assert_eq!(m.file, Path::new("/src/main.rs"));
+        assert_eq!(m.line, 12);
+        assert_eq!(m.column, Some(5));
+        assert_eq!(m.text, "fn main() {");
+    }
+
+    #[test]
+    fn test_parse_grep_line_without_column() {
+        let m = parse_grep_line("/notes/todo.md:3:buy milk".as_bytes(), false).unwrap();
+        assert_eq!(m.column, None);
+        assert_eq!(m.text, "buy milk");
+
+        // Without --column the third field is content, even if it is a number
+        let m = parse_grep_line("/src/main.rs:12:5:fn main() {".as_bytes(), false).unwrap();
+        assert_eq!(m.column, None);
+        assert_eq!(m.text, "5:fn main() {");
+    }
+
+    #[test]
+    fn test_parse_grep_line_colons_in_text() {
+        let m = parse_grep_line("/etc/hosts:7:1:127.0.0.1 localhost ::1".as_bytes(), true).unwrap();
+        assert_eq!(m.column, Some(1));
+        assert_eq!(m.text, "127.0.0.1 localhost ::1");
+
+        let m = parse_grep_line("/a.rs:9:3:let x: Vec<u8> = a::b::c();".as_bytes(), true).unwrap();
+        assert_eq!(m.column, Some(3));
+        assert_eq!(m.text, "let x: Vec<u8> = a::b::c();");
+    }
+
+    #[test]
+    fn test_parse_grep_line_time_in_text_is_not_a_column() {
+        // grep output (no column) whose content starts with a time
+        let m = parse_grep_line("/notes/day.md:4:12:30 standup".as_bytes(), false).unwrap();
+        assert_eq!(m.column, None);
+        assert_eq!(m.text, "12:30 standup");
+
+        // A time that would fit in the text as a column
+        let m = parse_grep_line("/notes/notes.md:4:1:30 standup".as_bytes(), false).unwrap();
+        assert_eq!(m.column, None);
+        assert_eq!(m.text, "1:30 standup");
+    }
+
+    #[test]
+    fn test_parse_grep_line_invalid_column_field() {
+        // rg --column always prints a positive column
+        for line in [
+            "/f.txt:2:+3:text",
+            "/f.txt:2::text",
+            "/f.txt:2:0:text",
+            "/f.txt:2:text",
+        ] {
+            assert_eq!(parse_grep_line(line.as_bytes(), true), None, "{line}");
+        }
+        assert_eq!(parse_grep_line("/f.txt:+2:text".as_bytes(), false), None);
+    }
+
+    #[test]
+    fn test_parse_grep_line_multibyte_text() {
+        // rg columns are byte offsets: "é" is two bytes
+        let m = parse_grep_line("/f.md:1:4:éa match".as_bytes(), true).unwrap();
+        assert_eq!(m.column, Some(4));
+        assert_eq!(m.text, "éa match");
+    }
+
+    #[test]
+    fn test_parse_grep_line_empty_text_with_column() {
+        let m = parse_grep_line("/f.md:1:1:".as_bytes(), true).unwrap();
+        assert_eq!(m.column, Some(1));
+        assert_eq!(m.text, "");
+    }
+
+    /// The argument vector of `editor_argv`, as UTF-8 strings
+    fn argv(editor: &str, file: &str, line: u32, column: Option<u32>) -> Vec<String> {
+        editor_argv(editor, Path::new(file), line, column)
+            .unwrap()
+            .into_iter()
+            .map(|arg| arg.into_string().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_editor_argv_with_column() {
+        assert_eq!(
+            argv("code", "/a.rs", 3, Some(7)),
+            ["code", "--goto", "/a.rs:3:7"]
+        );
+        assert_eq!(argv("hx", "/a.rs", 3, Some(7)), ["hx", "/a.rs:3:7"]);
+        assert_eq!(
+            argv("/usr/bin/nvim", "/a.rs", 3, Some(7)),
+            ["/usr/bin/nvim", "+call cursor(3,7)", "/a.rs"]
+        );
+        assert_eq!(argv("nano", "/a.rs", 3, Some(7)), ["nano", "+3,7", "/a.rs"]);
+        assert_eq!(argv("emacs", "/a.rs", 3, Some(7)), ["emacs", "+3", "/a.rs"]);
+    }
+
+    #[test]
+    fn test_editor_argv_without_column() {
+        assert_eq!(
+            argv("codium", "/a.rs", 3, None),
+            ["codium", "--goto", "/a.rs:3"]
+        );
+        assert_eq!(argv("nvim", "/a.rs", 3, None), ["nvim", "+3", "/a.rs"]);
+        assert_eq!(argv("vi", "/a.rs", 3, None), ["vi", "+3", "/a.rs"]);
+    }
+
+    #[test]
+    fn test_editor_argv_keeps_hostile_file_names_whole() {
+        let names = [
+            "/tmp/my notes.md",
+            "/tmp/$(rm -rf ~).md",
+            "/tmp/a;curl x|sh.md",
+            "/tmp/it's \"quoted\" `id`.md",
+            "/tmp/100%f %u.md",
+        ];
+        for name in names {
+            let args = argv("nvim", name, 2, None);
+            assert_eq!(args, ["nvim", "+2", name]);
+        }
+        assert_eq!(
+            argv("code", "/tmp/a b.md", 1, None),
+            ["code", "--goto", "/tmp/a b.md:1"]
+        );
+    }
+
+    #[test]
+    fn test_editor_argv_unparsable_editor() {
+        assert_eq!(
+            editor_argv("code 'unclosed", Path::new("/a.md"), 1, None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_resolve_editor_order() {
+        let pick = |configured, visual, editor| {
+            resolve_editor(configured, visual, editor, true).map(|c| c.command)
+        };
+        assert_eq!(
+            pick(Some("gedit"), Some("code"), Some("vim")),
+            Some("gedit")
+        );
+        assert_eq!(pick(None, Some("code"), Some("vim")), Some("code"));
+        assert_eq!(pick(Some("  "), Some(""), Some("vim")), Some("vim"));
+        assert_eq!(pick(None, None, None), None);
+        assert_eq!(pick(Some(""), Some(""), Some("")), None);
+    }
+
+    #[test]
+    fn test_resolve_editor_terminal_editors() {
+        assert_eq!(
+            resolve_editor(None, Some("/usr/bin/nvim"), None, true),
+            Some(EditorChoice {
+                command: "/usr/bin/nvim",
+                terminal: true
+            })
+        );
+        assert_eq!(
+            resolve_editor(Some("code --wait"), None, None, true),
+            Some(EditorChoice {
+                command: "code --wait",
+                terminal: false
+            })
+        );
+        assert!(resolve_editor(Some("emacs -nw"), None, None, true).is_some_and(|c| c.terminal));
+        assert!(resolve_editor(Some("emacs"), None, None, true).is_some_and(|c| !c.terminal));
+
+        // Without a terminal emulator, terminal editors are passed over
+        assert_eq!(
+            resolve_editor(Some("hx"), Some("nano"), Some("kate"), false).map(|c| c.command),
+            Some("kate")
+        );
+        assert_eq!(resolve_editor(None, Some("vim"), None, false), None);
+    }
+
+    #[test]
+    fn test_editor_argv_with_arguments() {
+        assert_eq!(
+            argv("code --wait", "/a.toml", 1, Some(1)),
+            ["code", "--wait", "--goto", "/a.toml:1:1"]
+        );
+        assert_eq!(
+            argv("'/opt/My Editor/edit' -n", "/a.toml", 1, None),
+            ["/opt/My Editor/edit", "-n", "+1", "/a.toml"]
+        );
+    }
+
+    #[test]
+    fn test_parse_grep_line_non_utf8_file_name() {
+        // As listed by `rg --column --null`
+        let m = parse_grep_line(b"/tmp/not\xffes.md\x002:6:line two", true).unwrap();
+        assert_eq!(m.file.as_os_str().as_bytes(), b"/tmp/not\xffes.md");
+        assert_eq!((m.line, m.column), (2, Some(6)));
+        assert_eq!(m.text, "line two");
+    }
+
+    #[test]
+    fn test_editor_argv_non_utf8_file() {
+        let file = Path::new(OsStr::from_bytes(b"/n\xffte.md"));
+        let args = editor_argv("code", file, 4, Some(2)).unwrap();
+        assert_eq!(args[2].as_bytes(), b"/n\xffte.md:4:2");
+    }
 }
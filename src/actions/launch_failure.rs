@@ -0,0 +1,312 @@
+//! Desktop notifications for applications that fail to start
+//!
+//! A launch fails either when the process cannot be spawned, or when it
+//! exits with an error right away, e.g. a wrapper script whose program is
+//! missing. Processes spawned by Grunner are checked once
+//! [`STARTUP_GRACE`] after the launch: one still running, or gone with
+//! status 0 after handing over to another process, counts as started.
+//! Processes launched through GIO are detached from Grunner, so only the
+//! spawn errors GIO reports are seen for them.
+//!
+//! The notification goes through the application's `gio::Notification`
+//! when it is registered, and through `notify-send` otherwise. When the
+//! `.desktop` file of the application is known, it carries an "Open
+//! .desktop file" button, so a broken `Exec=` line can be fixed at once.
+
+use gtk4::gio;
+use gtk4::prelude::*;
+use log::{debug, info, warn};
+use std::os::unix::process::ExitStatusExt;
+use std::path::{Path, PathBuf};
+use std::process::{Child, ExitStatus};
+use std::time::Duration;
+
+/// Time a launched process gets before an exit counts as a failure
+pub const STARTUP_GRACE: Duration = Duration::from_secs(3);
+
+/// Application action opening the `.desktop` file given as a string target
+pub const OPEN_DESKTOP_FILE_ACTION: &str = "open-desktop-file";
+
+/// Label of the notification button opening the `.desktop` file
+const OPEN_DESKTOP_FILE_LABEL: &str = "Open .desktop file";
+
+/// An application that did not start
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LaunchFailure {
+    /// Name of the application, or the program run
+    pub name: String,
+    /// Why it failed, e.g. `No such file or directory`
+    pub reason: String,
+    /// The `.desktop` file the application comes from, if any
+    pub desktop_file: Option<PathBuf>,
+}
+
+impl LaunchFailure {
+    /// `Failed to launch Foo: No such file or directory`
+    #[must_use]
+    pub fn message(&self) -> String {
+        format!("Failed to launch {}: {}", self.name, self.reason)
+    }
+}
+
+/// Where launch failures are reported
+pub trait Notifier {
+    fn notify(&self, failure: &LaunchFailure);
+}
+
+/// Reports failures as desktop notifications
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DesktopNotifier;
+
+impl Notifier for DesktopNotifier {
+    fn notify(&self, failure: &LaunchFailure) {
+        warn!("{}", failure.message());
+        match gio::Application::default().filter(ApplicationExt::is_registered) {
+            Some(app) => send_gio_notification(&app, failure),
+            None => send_with_notify_send(failure),
+        }
+    }
+}
+
+/// Why a process checked [`STARTUP_GRACE`] after its launch failed
+///
+/// `status` is `None` while it runs, which is a successful start, as is a
+/// clean exit. Any other exit is a failure: `exited with status 127` or
+/// `killed by signal 11`.
+#[must_use]
+pub fn exit_failure(status: Option<ExitStatus>) -> Option<String> {
+    let status = status?;
+    if status.success() {
+        return None;
+    }
+    match (status.code(), status.signal()) {
+        (Some(code), _) => Some(format!("exited with status {code}")),
+        (None, Some(signal)) => Some(format!("killed by signal {signal}")),
+        (None, None) => Some(status.to_string()),
+    }
+}
+
+/// The reason of a spawn error, without the `(os error N)` suffix
+#[must_use]
+pub fn spawn_error_reason(error: &std::io::Error) -> String {
+    let text = error.to_string();
+    match text.rfind(" (os error ") {
+        Some(end) if text.ends_with(')') => text[..end].to_string(),
+        _ => text,
+    }
+}
+
+/// Report `failure` if the process ended with `status` at the check
+///
+/// Returns whether a failure was reported.
+pub fn check_startup(
+    status: Option<ExitStatus>,
+    failure: LaunchFailure,
+    notifier: &impl Notifier,
+) -> bool {
+    match exit_failure(status) {
+        Some(reason) => {
+            notifier.notify(&LaunchFailure { reason, ..failure });
+            true
+        }
+        None => false,
+    }
+}
+
+/// Check `child` [`STARTUP_GRACE`] after its launch
+///
+/// `failure` describes the launch, its reason is filled in if the process
+/// exited with an error. A process still running is then waited for on a
+/// thread of its own, so it does not linger as a zombie once it exits.
+///
+/// The application is held until the check, so a launcher that is not
+/// resident does not quit with the check still pending.
+pub fn watch_startup(mut child: Child, failure: LaunchFailure) {
+    let hold = gio::Application::default().map(|app| app.hold());
+    glib::timeout_add_local_once(STARTUP_GRACE, move || {
+        let _hold = hold;
+        let status = child.try_wait().unwrap_or_else(|e| {
+            debug!("Could not check {}: {e}", failure.name);
+            None
+        });
+        check_startup(status, failure, &DesktopNotifier);
+        if status.is_none() {
+            std::thread::spawn(move || child.wait());
+        }
+    });
+}
+
+fn send_gio_notification(app: &gio::Application, failure: &LaunchFailure) {
+    let notification = gio::Notification::new("Launch Failed");
+    notification.set_body(Some(&failure.message()));
+    if let Some(file) = &failure.desktop_file {
+        notification.add_button_with_target_value(
+            OPEN_DESKTOP_FILE_LABEL,
+            &format!("app.{OPEN_DESKTOP_FILE_ACTION}"),
+            Some(&file.to_string_lossy().as_ref().to_variant()),
+        );
+    }
+    app.send_notification(Some("launch-error"), &notification);
+}
+
+/// Arguments of `notify-send` for `failure`
+///
+/// With `--action`, `notify-send` waits for the notification to close and
+/// prints `open` if the button was clicked.
+#[must_use]
+pub fn notify_send_args(failure: &LaunchFailure) -> Vec<String> {
+    let mut args = vec![
+        "--app-name=Grunner".to_string(),
+        "--icon=dialog-error".to_string(),
+    ];
+    if failure.desktop_file.is_some() {
+        args.push(format!("--action=open={OPEN_DESKTOP_FILE_LABEL}"));
+    }
+    args.push("Launch Failed".to_string());
+    args.push(failure.message());
+    args
+}
+
+/// Fallback when the application is not registered on the session bus
+fn send_with_notify_send(failure: &LaunchFailure) {
+    let args = notify_send_args(failure);
+    let desktop_file = failure.desktop_file.clone();
+    std::thread::spawn(move || {
+        let output = match std::process::Command::new("notify-send")
+            .args(&args)
+            .output()
+        {
+            Ok(output) => output,
+            Err(e) => {
+                warn!("Could not run notify-send: {e}");
+                return;
+            }
+        };
+        if let Some(file) = desktop_file
+            && String::from_utf8_lossy(&output.stdout).trim() == "open"
+        {
+            glib::MainContext::default().invoke(move || open_desktop_file(&file));
+        }
+    });
+}
+
+/// Open a `.desktop` file in the text editor
+///
+/// The default application of `.desktop` files may be the application
+/// itself, so the one for plain text is used.
+pub fn open_desktop_file(path: &Path) {
+    info!("Opening desktop file {}", path.display());
+    let file = gio::File::for_path(path);
+    let ctx = gtk4::gdk::Display::default().map(|d| d.app_launch_context());
+    let opened = match gio::AppInfo::default_for_type("text/plain", false) {
+        Some(editor) => editor
+            .launch(&[file], ctx.as_ref())
+            .map_err(|e| e.to_string()),
+        None => super::open_uri(&file.uri()).map_err(|e| e.to_string()),
+    };
+    if let Err(e) = opened {
+        warn!("Could not open {}: {e}", path.display());
+    }
+}
+
+/// Register the action behind the "Open .desktop file" button
+pub fn register_open_desktop_file_action(app: &impl IsA<gio::ActionMap>) {
+    let action = gio::SimpleAction::new(OPEN_DESKTOP_FILE_ACTION, Some(glib::VariantTy::STRING));
+    action.connect_activate(|_, target| {
+        if let Some(path) = target.and_then(glib::Variant::str) {
+            open_desktop_file(Path::new(path));
+        }
+    });
+    app.add_action(&action);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    struct Recorder(RefCell<Vec<LaunchFailure>>);
+
+    impl Notifier for Recorder {
+        fn notify(&self, failure: &LaunchFailure) {
+            self.0.borrow_mut().push(failure.clone());
+        }
+    }
+
+    fn launch(desktop_file: Option<&str>) -> LaunchFailure {
+        LaunchFailure {
+            name: "Foo".to_string(),
+            reason: String::new(),
+            desktop_file: desktop_file.map(PathBuf::from),
+        }
+    }
+
+    /// A wait status as `waitpid` returns it
+    fn exited(code: i32) -> ExitStatus {
+        ExitStatus::from_raw(code << 8)
+    }
+
+    #[test]
+    fn test_exit_failure() {
+        assert_eq!(exit_failure(None), None);
+        assert_eq!(exit_failure(Some(exited(0))), None);
+        assert_eq!(
+            exit_failure(Some(exited(127))).as_deref(),
+            Some("exited with status 127")
+        );
+        assert_eq!(
+            exit_failure(Some(ExitStatus::from_raw(11))).as_deref(),
+            Some("killed by signal 11")
+        );
+    }
+
+    #[test]
+    fn test_check_startup_immediate_exit_vs_running() {
+        let recorder = Recorder::default();
+        assert!(!check_startup(None, launch(None), &recorder));
+        assert!(!check_startup(Some(exited(0)), launch(None), &recorder));
+        assert!(recorder.0.borrow().is_empty());
+
+        assert!(check_startup(
+            Some(exited(1)),
+            launch(Some("/usr/share/applications/foo.desktop")),
+            &recorder
+        ));
+        let reported = recorder.0.borrow();
+        assert_eq!(reported.len(), 1);
+        assert_eq!(
+            reported[0].message(),
+            "Failed to launch Foo: exited with status 1"
+        );
+        assert_eq!(
+            reported[0].desktop_file.as_deref(),
+            Some(Path::new("/usr/share/applications/foo.desktop"))
+        );
+    }
+
+    #[test]
+    fn test_spawn_error_reason() {
+        let missing = std::io::Error::from_raw_os_error(2);
+        assert_eq!(spawn_error_reason(&missing), "No such file or directory");
+        let other = std::io::Error::other("bad (thing)");
+        assert_eq!(spawn_error_reason(&other), "bad (thing)");
+    }
+
+    #[test]
+    fn test_notify_send_args() {
+        let mut failure = launch(None);
+        failure.reason = "No such file or directory".to_string();
+        let args = notify_send_args(&failure);
+        assert!(!args.iter().any(|a| a.starts_with("--action")));
+        assert_eq!(
+            args.last().map(String::as_str),
+            Some("Failed to launch Foo: No such file or directory")
+        );
+
+        failure.desktop_file = Some(PathBuf::from("/tmp/foo.desktop"));
+        let args = notify_send_args(&failure);
+        assert!(args.contains(&"--action=open=Open .desktop file".to_string()));
+        assert_eq!(args[args.len() - 2], "Launch Failed");
+    }
+}
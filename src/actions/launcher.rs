@@ -1,7 +1,15 @@
+use crate::actions::launch_failure::{
+    DesktopNotifier, LaunchFailure, Notifier, spawn_error_reason, watch_startup,
+};
 use crate::actions::show_error_notification;
+use crate::launcher::DesktopApp;
 use gtk4::gio;
 use gtk4::gio::prelude::AppInfoExt;
+use gtk4::prelude::DisplayExt;
 use log::{debug, error, info, warn};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
 use std::path::PathBuf;
 use std::sync::OnceLock;
 
@@ -80,35 +88,143 @@ pub fn find_terminal() -> Option<String> {
     terminal().cloned()
 }
 
+/// What [`launch_app`] launches, beyond its command
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LaunchSource {
+    /// Name for the failure notification; the program's when empty
+    pub name: String,
+    /// Desktop entry ID of the application, if the command is its `Exec=`
+    /// line
+    pub desktop_id: Option<String>,
+    /// The `.desktop` file the command comes from, offered in the failure
+    /// notification
+    pub desktop_file: Option<PathBuf>,
+}
+
+impl LaunchSource {
+    /// The source of an application's `Exec=` line
+    #[must_use]
+    pub fn for_app(app: &DesktopApp) -> Self {
+        Self {
+            name: app.name.clone(),
+            desktop_id: Some(app.desktop_id.clone()),
+            desktop_file: Some(app.path.clone()),
+        }
+    }
+
+    /// A failure of this launch, named after `program` if there is no name
+    fn failure(&self, program: &str, reason: String) -> LaunchFailure {
+        let name = if self.name.is_empty() {
+            program.to_string()
+        } else {
+            self.name.clone()
+        };
+        LaunchFailure {
+            name,
+            reason,
+            desktop_file: self.desktop_file.clone(),
+        }
+    }
+}
+
 /// Launch an application with optional terminal
 ///
 /// # Arguments
 /// * `exec` - Command string to execute
 /// * `terminal` - Whether to run the command inside a terminal emulator
 /// * `working_dir` - Optional working directory (None = current directory)
-/// * `desktop_id` - Optional desktop entry ID (unused currently, reserved for future GIO integration)
+/// * `source` - Desktop entry of the application, for GIO and for the
+///   failure notification
 ///
-/// Uses `gio::AppInfo::create_from_commandline()` for non-terminal apps, which
-/// detaches the launched process from Grunner's process tree. Falls back to
-/// `Command::spawn()` if GIO fails.
+/// Non-terminal apps with a desktop ID are launched through their entry in
+/// GIO's application registry, which handles `Exec=` quoting and field codes,
+/// `Path=`, startup notification and `DBusActivatable=true`.
+///
+/// Otherwise, or if GIO does not know the entry (e.g. it comes from an
+/// `app_dirs` folder outside `XDG_DATA_DIRS`) or fails to launch it, the
+/// cleaned `exec` goes through `gio::AppInfo::create_from_commandline()`,
+/// which detaches the launched process from Grunner's process tree, and
+/// then `Command::spawn()` if that fails too.
 ///
 /// For terminal apps, uses `Command::spawn()` directly since terminal emulators
 /// require specific argument syntax.
+///
+/// A launch that fails, or whose process spawned by Grunner exits with an
+/// error right away, raises a desktop notification, see
+/// [`crate::actions::launch_failure`].
 #[allow(clippy::needless_pass_by_value)]
-pub fn launch_app(
-    exec: &str,
-    terminal: bool,
-    working_dir: Option<String>,
-    _desktop_id: Option<&str>,
-) {
+pub fn launch_app(exec: &str, terminal: bool, working_dir: Option<String>, source: &LaunchSource) {
     debug!("Launching application: {exec} (terminal: {terminal}, working_dir: {working_dir:?})");
+    if !terminal
+        && working_dir.is_none()
+        && let Some(id) = source.desktop_id.as_deref().filter(|id| !id.is_empty())
+        && launch_desktop_entry(id)
+    {
+        return;
+    }
+
     let clean = crate::launcher::clean_exec(exec);
     debug!("Cleaned execution command: {clean}");
 
     if terminal {
-        launch_in_terminal(&clean, working_dir);
+        launch_in_terminal(&clean, working_dir, source);
     } else {
-        launch_via_app_info(&clean, &working_dir);
+        launch_via_app_info(&clean, &working_dir, source);
+    }
+}
+
+thread_local! {
+    /// GIO's applications by desktop file ID, listed on first use
+    static DESKTOP_ENTRIES: RefCell<Option<HashMap<String, gio::AppInfo>>> =
+        const { RefCell::new(None) };
+
+    /// Drops [`DESKTOP_ENTRIES`] whenever the registry changes
+    static APP_INFO_MONITOR: gio::AppInfoMonitor = {
+        let monitor = gio::AppInfoMonitor::get();
+        monitor.connect_changed(|_| DESKTOP_ENTRIES.with_borrow_mut(|entries| *entries = None));
+        monitor
+    };
+}
+
+/// The application `file_id` of GIO's registry
+///
+/// The registry is listed once and kept until it changes, rather than on
+/// every launch.
+fn desktop_entry(file_id: &str) -> Option<gio::AppInfo> {
+    APP_INFO_MONITOR.with(|_| ());
+    DESKTOP_ENTRIES.with_borrow_mut(|entries| {
+        entries
+            .get_or_insert_with(|| {
+                gio::AppInfo::all()
+                    .into_iter()
+                    .filter_map(|info| Some((info.id()?.to_string(), info)))
+                    .collect()
+            })
+            .get(file_id)
+            .cloned()
+    })
+}
+
+/// Launch the entry `{desktop_id}.desktop` of GIO's application registry
+///
+/// Returns `false` if GIO does not know the entry or cannot launch it.
+fn launch_desktop_entry(desktop_id: &str) -> bool {
+    let file_id = format!("{desktop_id}.desktop");
+    let Some(app_info) = desktop_entry(&file_id) else {
+        debug!("{file_id} is not in GIO's application registry");
+        return false;
+    };
+
+    let ctx = gtk4::gdk::Display::default().map(|d| d.app_launch_context());
+    match app_info.launch(&[] as &[gio::File], ctx.as_ref()) {
+        Ok(()) => {
+            info!("Successfully launched desktop entry: {file_id}");
+            true
+        }
+        Err(e) => {
+            warn!("Failed to launch desktop entry {file_id}: {e}; falling back to Exec");
+            false
+        }
     }
 }
 
@@ -116,7 +232,7 @@ pub fn launch_app(
 ///
 /// This detaches the child process from Grunner's process tree,
 /// preventing zombie processes and memory aggregation issues.
-fn launch_via_app_info(clean: &str, working_dir: &Option<String>) {
+fn launch_via_app_info(clean: &str, working_dir: &Option<String>, source: &LaunchSource) {
     let parts: Vec<&str> = clean.split_whitespace().collect();
     if let Some((prog, args)) = parts.split_first() {
         let cmdline = clean.to_string();
@@ -136,7 +252,7 @@ fn launch_via_app_info(clean: &str, working_dir: &Option<String>) {
                 if let Err(e) = app_info.launch(&[] as &[gio::File], Some(&ctx)) {
                     error!("Failed to launch via AppInfo: {e}");
                     warn!("Falling back to Command::spawn");
-                    launch_via_command(prog, args, working_dir);
+                    launch_via_command(prog, args, working_dir, source);
                 } else {
                     info!("Successfully launched via AppInfo: {cmdline}");
                 }
@@ -144,7 +260,7 @@ fn launch_via_app_info(clean: &str, working_dir: &Option<String>) {
             Err(e) => {
                 error!("Failed to create AppInfo for '{cmdline}': {e}");
                 warn!("Falling back to Command::spawn");
-                launch_via_command(prog, args, working_dir);
+                launch_via_command(prog, args, working_dir, source);
             }
         }
     } else {
@@ -154,25 +270,90 @@ fn launch_via_app_info(clean: &str, working_dir: &Option<String>) {
 }
 
 /// Fallback: launch via `std::process::Command::spawn()`
-fn launch_via_command(prog: &str, args: &[&str], working_dir: &Option<String>) {
+fn launch_via_command(
+    prog: &str,
+    args: &[&str],
+    working_dir: &Option<String>,
+    source: &LaunchSource,
+) {
     let mut cmd = std::process::Command::new(prog);
     cmd.args(args);
     if let Some(dir) = working_dir {
         cmd.current_dir(dir);
     }
     debug!("Spawning command directly: {cmd:?}");
-    if let Err(e) = cmd.spawn() {
-        error!("Failed to launch command '{prog}': {e}");
-        show_error_notification(&format!("Failed to launch: {prog}"));
-    } else {
-        info!("Successfully launched application: {prog}");
+    match cmd.spawn() {
+        Ok(child) => {
+            info!("Successfully launched application: {prog}");
+            watch_startup(child, source.failure(prog, String::new()));
+        }
+        Err(e) => {
+            error!("Failed to launch command '{prog}': {e}");
+            DesktopNotifier.notify(&source.failure(prog, spawn_error_reason(&e)));
+        }
+    }
+}
+
+/// Run a shell command line in the terminal emulator exactly as typed
+///
+/// Unlike [`launch_app`], field codes such as `%f` are kept, so the line can
+/// use any shell feature (pipes, `date +%F`, …).
+pub fn run_in_terminal(command: &str) {
+    info!("Running in terminal: {command}");
+    launch_in_terminal(command, None, &LaunchSource::default());
+}
+
+/// Run a shell command line exactly as typed, without a terminal
+///
+/// Like [`run_in_terminal`], the line goes to `sh -c` unchanged. It is
+/// launched through GIO so the process is detached from Grunner.
+pub fn run_detached(command: &str) {
+    info!("Running: {command}");
+    // GIO expands field codes in command lines, so `%` is escaped as `%%`
+    let quoted = glib::shell_quote(command.replace('%', "%%"));
+    let cmdline = format!("sh -c {}", quoted.to_string_lossy());
+    let launched =
+        gio::AppInfo::create_from_commandline(&cmdline, Some("sh"), gio::AppInfoCreateFlags::NONE)
+            .and_then(|app_info| {
+                app_info.launch(&[] as &[gio::File], None::<&gio::AppLaunchContext>)
+            });
+    if let Err(e) = launched {
+        error!("Failed to run '{command}': {e}");
+        show_error_notification(&format!("Failed to run: {command}"));
     }
 }
 
-/// Launch a command inside a terminal emulator
+/// Open an interactive shell in `dir` in the terminal emulator
+pub fn open_terminal_in(dir: &str) {
+    info!("Opening terminal in {dir}");
+    launch_in_terminal(
+        "exec \"${SHELL:-sh}\"",
+        Some(dir.to_string()),
+        &LaunchSource::default(),
+    );
+}
+
+/// Run a program with its arguments in the terminal emulator, without a
+/// shell
+///
+/// The arguments are passed on byte for byte, so they may hold any file
+/// name, including shell syntax and bytes that are not UTF-8.
+pub fn run_argv_in_terminal(argv: &[OsString]) {
+    info!("Running in terminal: {argv:?}");
+    let args: Vec<&OsStr> = argv.iter().map(OsString::as_os_str).collect();
+    spawn_in_terminal(&args, None, &LaunchSource::default());
+}
+
+/// Launch a shell command line inside a terminal emulator
+fn launch_in_terminal(clean: &str, working_dir: Option<String>, source: &LaunchSource) {
+    let args = [OsStr::new("sh"), OsStr::new("-c"), OsStr::new(clean)];
+    spawn_in_terminal(&args, working_dir, source);
+}
+
+/// Spawn the terminal emulator running the program and arguments `args`
 ///
 /// Terminal emulators have varying argument syntax, so we handle them individually.
-fn launch_in_terminal(clean: &str, working_dir: Option<String>) {
+fn spawn_in_terminal(args: &[&OsStr], working_dir: Option<String>, source: &LaunchSource) {
     debug!("Looking for terminal emulator");
     if let Some(term) = find_terminal() {
         info!("Using terminal emulator: {term}");
@@ -183,26 +364,30 @@ fn launch_in_terminal(clean: &str, working_dir: Option<String>) {
         match term.as_str() {
             // GNOME and XFCE terminals use "--" separator
             "gnome-terminal" | "xfce4-terminal" => {
-                cmd.arg("--").arg("sh").arg("-c").arg(clean);
+                cmd.arg("--").args(args);
             }
             // Kitty uses "--" separator and supports --hold
             "kitty" => {
-                cmd.arg("--hold").arg("--").arg("sh").arg("-c").arg(clean);
+                cmd.arg("--hold").arg("--").args(args);
             }
             // Default to "-e" for unknown terminals
             _ => {
-                cmd.arg("-e").arg("sh").arg("-c").arg(clean);
+                cmd.arg("-e").args(args);
             }
         }
         debug!("Spawning terminal command: {cmd:?}");
-        if let Err(e) = cmd.spawn() {
-            error!("Failed to launch terminal {term} with command '{clean}': {e}");
-            show_error_notification(&format!("Failed to launch: {clean}"));
-        } else {
-            info!("Successfully launched application in terminal {term}: {clean}");
+        match cmd.spawn() {
+            Ok(child) => {
+                info!("Successfully launched application in terminal {term}: {args:?}");
+                watch_startup(child, source.failure(&term, String::new()));
+            }
+            Err(e) => {
+                error!("Failed to launch terminal {term} with command {args:?}: {e}");
+                DesktopNotifier.notify(&source.failure(&term, spawn_error_reason(&e)));
+            }
         }
     } else {
-        warn!("No terminal emulator found for command: {clean}");
+        warn!("No terminal emulator found for command: {args:?}");
         show_error_notification("No terminal emulator found");
     }
 }
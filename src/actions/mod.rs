@@ -2,16 +2,20 @@
 //!
 //! This module handles all external actions performed by the application:
 //! - Launching applications (with or without terminal)
+//! - Notifying when a launched application fails to start
 //! - Power management actions (logout, suspend, reboot, shutdown)
+//! - Finding the graphical login session to log out
 //! - File and line opening operations
 //! - Obsidian vault and note management
 //! - Settings management
 //! - Workspace window operations
 
 pub mod file;
+pub mod launch_failure;
 pub mod launcher;
 pub mod obsidian;
 pub mod power;
+pub mod session;
 pub mod settings;
 pub mod workspace;
 
@@ -53,10 +57,7 @@ pub fn open_uri(uri: &str) -> Result<(), std::io::Error> {
         }
         Err(e) => {
             error!("Failed to open URI '{uri}': {e}");
-            Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                e.to_string(),
-            ))
+            Err(std::io::Error::other(e.to_string()))
         }
     }
 }
@@ -1,35 +1,134 @@
-use crate::actions::open_uri;
-use crate::core::config::ObsidianConfig;
+use crate::actions::{open_uri, show_error_notification};
+use crate::core::obsidian::{ObsidianContext, daily_note_file_name, new_note_file_name};
 use crate::model::items::ObsidianAction;
-use crate::utils::expand_home;
+use crate::utils::path_containment;
 use chrono::Local;
 use log::{debug, error, info};
 use std::fs;
 use std::fs::File;
-use std::io::Write;
-use std::path::PathBuf;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Accept a path resolved inside the vault, refusing anything that escapes it
+///
+/// Errors are logged and shown as a notification since the launcher window
+/// is already hidden when actions run.
+fn inside_vault(resolved: io::Result<PathBuf>) -> Option<PathBuf> {
+    match resolved {
+        Ok(path) => Some(path),
+        Err(e) => {
+            error!("Refusing to write outside the Obsidian vault: {e}");
+            show_error_notification(&format!("Obsidian path outside the vault: {e}"));
+            None
+        }
+    }
+}
+
+/// What `:ob` / `:obg` can do about the configured vault directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VaultSetup {
+    /// The vault exists and can be used
+    Exists(PathBuf),
+    /// The vault is missing and may be created at this path
+    Missing(PathBuf),
+    /// The vault is missing and will not be created; the message says why
+    Refused(String),
+}
+
+/// Decide whether the configured vault exists or may be created
+///
+/// A missing vault is only offered for creation when it is an absolute path
+/// (after `~` expansion) that resolves inside `home`, unless
+/// `allow_vault_outside_home` is set.
+#[must_use]
+pub fn vault_setup(ctx: &ObsidianContext, home: &Path) -> VaultSetup {
+    if ctx.config().vault.trim().is_empty() {
+        return VaultSetup::Refused("Obsidian vault path is empty - edit config".to_string());
+    }
+    let vault = ctx.vault().to_path_buf();
+    if vault.exists() {
+        return VaultSetup::Exists(vault);
+    }
+    if !vault.is_absolute() {
+        return VaultSetup::Refused(format!(
+            "Vault path does not exist and is not absolute: {}",
+            vault.display()
+        ));
+    }
+    if !ctx.config().allow_vault_outside_home && path_containment(home, &vault).is_err() {
+        return VaultSetup::Refused(format!(
+            "Vault path does not exist: {} (outside your home directory; set \
+             obsidian.allow_vault_outside_home = true to create it)",
+            vault.display()
+        ));
+    }
+    VaultSetup::Missing(vault)
+}
+
+/// Create the configured vault and its daily and new-note folders
+///
+/// The same checks as [`vault_setup`] are applied first, so nothing is created
+/// for a refused path. An existing vault is left as it is.
+///
+/// # Errors
+/// Returns a message suitable for showing in the result list if the vault
+/// may not be created or a directory cannot be created.
+pub fn create_vault(ctx: &ObsidianContext, home: &Path) -> Result<PathBuf, String> {
+    let vault = match vault_setup(ctx, home) {
+        VaultSetup::Exists(vault) => return Ok(vault),
+        VaultSetup::Missing(vault) => vault,
+        VaultSetup::Refused(msg) => return Err(msg),
+    };
+    info!("Creating Obsidian vault at {}", vault.display());
+    fs::create_dir_all(&vault).map_err(|e| format!("Could not create {}: {e}", vault.display()))?;
+
+    let cfg = ctx.config();
+    for folder in [&cfg.daily_notes_folder, &cfg.new_notes_folder] {
+        if folder.is_empty() {
+            continue;
+        }
+        let dir = ctx
+            .resolve(folder)
+            .map_err(|e| format!("Obsidian path outside the vault: {e}"))?;
+        fs::create_dir_all(&dir).map_err(|e| format!("Could not create {}: {e}", dir.display()))?;
+    }
+    Ok(vault)
+}
+
+/// Check that the note path `action` writes to resolves inside the vault
+///
+/// # Errors
+/// Returns a message naming the setting, shown as an error row instead of
+/// running the action.
+pub fn check_action_path(action: ObsidianAction, ctx: &ObsidianContext) -> Result<(), String> {
+    let (key, resolved) = match action {
+        ObsidianAction::OpenVault => return Ok(()),
+        ObsidianAction::NewNote => ("new_notes_folder", ctx.new_notes_dir()),
+        ObsidianAction::DailyNote => ("daily_notes_folder", ctx.daily_notes_dir()),
+        ObsidianAction::QuickNote => ("quick_note", ctx.quick_note_path()),
+    };
+    resolved
+        .map(drop)
+        .map_err(|e| format!("Invalid obsidian.{key}: {e}"))
+}
 
 /// Perform an Obsidian-related action
 ///
 /// # Arguments
 /// * `action` - The `ObsidianAction` to perform
 /// * `text` - Optional text content for note actions
-/// * `cfg` - Obsidian configuration for vault paths and settings
+/// * `ctx` - Resolved Obsidian settings for vault paths
 ///
 /// Handles all Obsidian operations: opening vault, creating new notes,
-/// daily notes, and quick notes.
+/// daily notes, and quick notes. Every note path is checked to resolve
+/// inside the vault before anything is created or written.
 #[allow(clippy::unnecessary_debug_formatting, clippy::too_many_lines)]
-pub fn perform_obsidian_action(action: ObsidianAction, text: Option<&str>, cfg: &ObsidianConfig) {
+pub fn perform_obsidian_action(action: ObsidianAction, text: Option<&str>, ctx: &ObsidianContext) {
     debug!("Performing Obsidian action: {action:?} with text: {text:?}");
-    let vault_path = expand_home(&cfg.vault);
-    debug!("Obsidian vault path: {}", vault_path.display());
+    debug!("Obsidian vault path: {}", ctx.vault().display());
 
     // Validate vault path exists
-    if !vault_path.exists() {
-        error!(
-            "Obsidian vault path does not exist: {}",
-            vault_path.display()
-        );
+    if ctx.existing_vault().is_none() {
         return;
     }
 
@@ -37,8 +136,10 @@ pub fn perform_obsidian_action(action: ObsidianAction, text: Option<&str>, cfg:
         ObsidianAction::OpenVault => {
             // Open entire vault in Obsidian
             info!("Opening Obsidian vault");
-            let vault_name = vault_path.file_name().unwrap_or_default().to_string_lossy();
-            let uri = format!("obsidian://open?vault={}", urlencoding::encode(&vault_name));
+            let uri = format!(
+                "obsidian://open?vault={}",
+                urlencoding::encode(&ctx.vault_name())
+            );
             if let Err(e) = open_uri(&uri) {
                 error!("Failed to open Obsidian vault: {e}");
             }
@@ -46,7 +147,9 @@ pub fn perform_obsidian_action(action: ObsidianAction, text: Option<&str>, cfg:
         ObsidianAction::NewNote => {
             // Create a new note with timestamp in the configured folder
             info!("Creating new Obsidian note");
-            let folder = vault_path.join(&cfg.new_notes_folder);
+            let Some(folder) = inside_vault(ctx.new_notes_dir()) else {
+                return;
+            };
             debug!("New note folder: {}", folder.display());
             if let Err(e) = fs::create_dir_all(&folder) {
                 error!("Failed to create new note folder {}: {e}", folder.display());
@@ -54,9 +157,10 @@ pub fn perform_obsidian_action(action: ObsidianAction, text: Option<&str>, cfg:
             }
 
             // Generate filename with current timestamp
-            let now = Local::now();
-            let filename = format!("New Note {}.md", now.format("%Y-%m-%d %H-%M-%S"));
-            let path = folder.join(filename);
+            let filename = new_note_file_name(&Local::now().naive_local());
+            let Some(path) = inside_vault(ctx.resolve(folder.join(filename))) else {
+                return;
+            };
 
             // Create the note file
             debug!("Creating note file: {}", path.display());
@@ -90,7 +194,9 @@ pub fn perform_obsidian_action(action: ObsidianAction, text: Option<&str>, cfg:
         ObsidianAction::DailyNote => {
             // Open or create today's daily note
             info!("Opening/creating daily Obsidian note");
-            let folder = vault_path.join(&cfg.daily_notes_folder);
+            let Some(folder) = inside_vault(ctx.daily_notes_dir()) else {
+                return;
+            };
             debug!("Daily notes folder: {}", folder.display());
             if let Err(e) = fs::create_dir_all(&folder) {
                 error!(
@@ -101,8 +207,10 @@ pub fn perform_obsidian_action(action: ObsidianAction, text: Option<&str>, cfg:
             }
 
             // Use today's date for filename
-            let today = Local::now().format("%Y-%m-%d").to_string();
-            let path = folder.join(format!("{today}.md"));
+            let filename = daily_note_file_name(&Local::now().naive_local());
+            let Some(path) = inside_vault(ctx.resolve(folder.join(filename))) else {
+                return;
+            };
 
             // Open in append mode to preserve existing content
             debug!("Opening daily note file: {}", path.display());
@@ -139,36 +247,14 @@ pub fn perform_obsidian_action(action: ObsidianAction, text: Option<&str>, cfg:
         ObsidianAction::QuickNote => {
             // Append text to the configured quick note file
             info!("Updating quick Obsidian note");
-            let path = vault_path.join(&cfg.quick_note);
-            debug!("Quick note path: {}", path.display());
-
-            // Ensure parent directory exists
-            if let Some(parent) = path.parent()
-                && let Err(e) = fs::create_dir_all(parent)
-            {
-                error!(
-                    "Failed to create quick note parent directory {}: {e}",
-                    parent.display()
-                );
-                return;
-            }
-
-            // Append text to quick note if provided
-            if let Some(t) = text
-                && !t.is_empty()
-            {
-                debug!("Appending {} characters to quick note", t.len());
-                let mut file = match fs::OpenOptions::new().create(true).append(true).open(&path) {
-                    Ok(f) => f,
-                    Err(e) => {
-                        error!("Failed to open quick note file {}: {e}", path.display());
-                        return;
-                    }
-                };
-                if let Err(e) = writeln!(file, "{t}") {
-                    error!("Failed to write to quick note {}: {e}", path.display());
+            let path = match append_quick_note(ctx, text) {
+                Ok(path) => path,
+                Err(e) => {
+                    error!("Failed to update the quick note: {e}");
+                    show_error_notification(&format!("Quick note not saved: {e}"));
+                    return;
                 }
-            }
+            };
 
             // Open the quick note in Obsidian
             let uri = format!(
@@ -182,23 +268,47 @@ pub fn perform_obsidian_action(action: ObsidianAction, text: Option<&str>, cfg:
     }
 }
 
+/// Append `text`, if any, to the quick note, creating its folder if needed
+///
+/// Returns the path of the quick note.
+///
+/// # Errors
+/// Returns an error, before anything is created, if the quick note resolves
+/// outside the vault, or if it cannot be written.
+fn append_quick_note(ctx: &ObsidianContext, text: Option<&str>) -> io::Result<PathBuf> {
+    let path = ctx.quick_note_path()?;
+    debug!("Quick note path: {}", path.display());
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    if let Some(t) = text
+        && !t.is_empty()
+    {
+        debug!("Appending {} characters to quick note", t.len());
+        // Checked again now that the folder exists, in case of symlinks
+        ctx.resolve(&path)?;
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+        writeln!(file, "{t}")?;
+    }
+    Ok(path)
+}
+
 /// Open an Obsidian file by its path
 ///
 /// # Arguments
 /// * `file_path` - Path to the file within the Obsidian vault
-/// * `cfg` - Obsidian configuration for vault location
+/// * `ctx` - Resolved Obsidian settings for the vault location
 ///
 /// Opens the specified file in Obsidian using the obsidian:// URI scheme.
-pub fn open_obsidian_file_path(file_path: &str, cfg: &ObsidianConfig) {
+pub fn open_obsidian_file_path(file_path: &str, ctx: &ObsidianContext) {
     debug!("Opening Obsidian file path: {file_path}");
-    let vault_path = expand_home(&cfg.vault);
 
     // Validate vault exists
-    if !vault_path.exists() {
-        error!(
-            "Obsidian vault path does not exist: {}",
-            vault_path.display()
-        );
+    if ctx.existing_vault().is_none() {
         return;
     }
 
@@ -214,28 +324,19 @@ pub fn open_obsidian_file_path(file_path: &str, cfg: &ObsidianConfig) {
 /// # Arguments
 /// * `file_path` - Path to the file within the Obsidian vault
 /// * `line` - Line number to jump to
-/// * `cfg` - Obsidian configuration for vault location
+/// * `ctx` - Resolved Obsidian settings for the vault location
 ///
 /// Opens the specified file in Obsidian and jumps to the given line number.
-pub fn open_obsidian_file_line(file_path: &str, line: &str, cfg: &ObsidianConfig) {
+pub fn open_obsidian_file_line(file_path: &str, line: &str, ctx: &ObsidianContext) {
     debug!("Opening Obsidian file at line: {file_path}:{line}");
-    let vault_path = expand_home(&cfg.vault);
 
     // Validate vault exists
-    if !vault_path.exists() {
-        error!(
-            "Obsidian vault path does not exist: {}",
-            vault_path.display()
-        );
+    if ctx.existing_vault().is_none() {
         return;
     }
 
     // Handle both absolute and relative paths
-    let path = if file_path.starts_with('/') {
-        PathBuf::from(file_path)
-    } else {
-        vault_path.join(file_path)
-    };
+    let path = ctx.result_path(file_path);
     debug!("Resolved path: {}", path.display());
 
     // Construct Obsidian URI with line parameter
@@ -248,3 +349,146 @@ pub fn open_obsidian_file_line(file_path: &str, line: &str, cfg: &ObsidianConfig
         error!("Failed to open Obsidian file at line: {e}");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::ObsidianConfig;
+
+    fn temp_home(name: &str) -> PathBuf {
+        let home =
+            std::env::temp_dir().join(format!("grunner_test_vault_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&home);
+        fs::create_dir_all(&home).unwrap();
+        home
+    }
+
+    fn config_for(vault: &Path) -> ObsidianConfig {
+        ObsidianConfig {
+            vault: vault.to_string_lossy().into_owned(),
+            daily_notes_folder: "Journal/Daily".to_string(),
+            new_notes_folder: "Inbox".to_string(),
+            quick_note: "Quick.md".to_string(),
+            allow_vault_outside_home: false,
+            grep_exclude_folders: None,
+        }
+    }
+
+    fn context(cfg: &ObsidianConfig) -> ObsidianContext {
+        ObsidianContext::new(cfg.clone())
+    }
+
+    #[test]
+    fn test_vault_setup_decision() {
+        let home = temp_home("decision");
+        let vault = home.join("Notes");
+        let mut cfg = config_for(&vault);
+        assert_eq!(
+            vault_setup(&context(&cfg), &home),
+            VaultSetup::Missing(vault.clone())
+        );
+
+        fs::create_dir_all(&vault).unwrap();
+        assert_eq!(
+            vault_setup(&context(&cfg), &home),
+            VaultSetup::Exists(vault)
+        );
+
+        cfg.vault = "relative/Notes".to_string();
+        assert!(matches!(
+            vault_setup(&context(&cfg), &home),
+            VaultSetup::Refused(_)
+        ));
+        cfg.vault = String::new();
+        assert!(matches!(
+            vault_setup(&context(&cfg), &home),
+            VaultSetup::Refused(_)
+        ));
+        let _ = fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn test_vault_outside_home_needs_flag() {
+        let home = temp_home("outside_home");
+        let outside = temp_home("outside_target").join("Vault");
+        let mut cfg = config_for(&outside);
+        // Escaping through `..` counts as outside too
+        let mut sneaky = config_for(&home.join("../elsewhere/Vault"));
+        assert!(matches!(
+            vault_setup(&context(&sneaky), &home),
+            VaultSetup::Refused(_)
+        ));
+        sneaky.allow_vault_outside_home = true;
+        assert!(matches!(
+            vault_setup(&context(&sneaky), &home),
+            VaultSetup::Missing(_)
+        ));
+
+        assert!(create_vault(&context(&cfg), &home).is_err());
+        assert!(!outside.exists());
+
+        cfg.allow_vault_outside_home = true;
+        assert_eq!(create_vault(&context(&cfg), &home), Ok(outside.clone()));
+        assert!(outside.is_dir());
+        let _ = fs::remove_dir_all(&home);
+        let _ = fs::remove_dir_all(outside.parent().unwrap());
+    }
+
+    #[test]
+    fn test_append_quick_note() {
+        let home = temp_home("quick_note");
+        let vault = home.join("Vault");
+        fs::create_dir_all(&vault).unwrap();
+        let mut cfg = config_for(&vault);
+        cfg.quick_note = "Inbox/Quick.md".to_string();
+        let ctx = context(&cfg);
+
+        let path = append_quick_note(&ctx, Some("first")).unwrap();
+        append_quick_note(&ctx, Some("second")).unwrap();
+        assert_eq!(path, vault.join("Inbox/Quick.md"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "first\nsecond\n");
+        let _ = fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn test_escaping_quick_note_is_refused() {
+        let home = temp_home("escaping_quick_note");
+        let vault = home.join("Vault");
+        fs::create_dir_all(&vault).unwrap();
+        let mut cfg = config_for(&vault);
+        cfg.quick_note = "../Quick.md".to_string();
+        let ctx = context(&cfg);
+
+        let err = check_action_path(ObsidianAction::QuickNote, &ctx).unwrap_err();
+        assert!(err.contains("obsidian.quick_note"));
+        assert!(check_action_path(ObsidianAction::NewNote, &ctx).is_ok());
+        assert!(check_action_path(ObsidianAction::OpenVault, &ctx).is_ok());
+
+        assert!(append_quick_note(&ctx, Some("secret")).is_err());
+        assert!(!home.join("Quick.md").exists());
+        assert_eq!(fs::read_dir(&vault).unwrap().count(), 0);
+        let _ = fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn test_create_vault_with_note_folders() {
+        let home = temp_home("create");
+        let vault = home.join("Documents/Notes");
+        let cfg = config_for(&vault);
+        assert_eq!(create_vault(&context(&cfg), &home), Ok(vault.clone()));
+        assert!(vault.join("Journal/Daily").is_dir());
+        assert!(vault.join("Inbox").is_dir());
+        // The quick note is a file and is left for the quick-note action
+        assert!(!vault.join("Quick.md").exists());
+
+        // Running it again on the existing vault is harmless
+        assert_eq!(create_vault(&context(&cfg), &home), Ok(vault));
+
+        // Creation errors are reported instead of panicking
+        let blocked = home.join("blocked");
+        fs::write(&blocked, "").unwrap();
+        let err = create_vault(&context(&config_for(&blocked.join("Vault"))), &home).unwrap_err();
+        assert!(err.starts_with("Could not create"));
+        let _ = fs::remove_dir_all(&home);
+    }
+}
@@ -1,4 +1,8 @@
 use crate::actions::launcher::which;
+use crate::actions::session::{
+    LogoutError, current_user, find_graphical_session, terminate_session,
+};
+use gtk4::prelude::ApplicationExt;
 use log::{debug, error, info, warn};
 
 /// Perform a power management action
@@ -7,7 +11,8 @@ use log::{debug, error, info, warn};
 /// * `action` - The action to perform: "logout", "suspend", "reboot", or "poweroff"
 ///
 /// Uses systemctl for suspend, reboot, and poweroff actions.
-/// `logout_action()` handles logout with various methods.
+/// [`logout()`] handles logout with various methods. Failures are shown as
+/// a notification.
 pub fn power_action(action: &str) {
     debug!("Performing power action: {action}");
     let run_systemctl = |subcmd: &str| {
@@ -17,6 +22,7 @@ pub fn power_action(action: &str) {
         cmd.arg("-i").arg(subcmd);
         if let Err(e) = cmd.spawn() {
             error!("Failed to run systemctl {subcmd}: {e}");
+            show_power_error(&format!("Failed to run systemctl {subcmd}: {e}"));
         } else {
             info!("Successfully initiated systemctl {subcmd}");
         }
@@ -25,7 +31,10 @@ pub fn power_action(action: &str) {
     match action {
         "logout" => {
             info!("Logging out current session");
-            logout_action();
+            if let Err(e) = logout() {
+                error!("Logout failed: {e}");
+                show_power_error(&format!("Could not log out: {e}"));
+            }
         }
         "suspend" => {
             info!("Suspending system");
@@ -47,43 +56,62 @@ pub fn power_action(action: &str) {
 
 /// Log out the current user session
 ///
-/// Attempts multiple logout methods in order:
-/// 1. Use loginctl with `XDG_SESSION_ID`
-/// 2. Use gnome-session-quit for GNOME sessions
-/// 3. Use loginctl with current username as fallback
-fn logout_action() {
+/// Attempts these methods in order:
+/// 1. `loginctl terminate-session` with `XDG_SESSION_ID`
+/// 2. `loginctl terminate-session` with the graphical session found through
+///    logind (see [`find_graphical_session`])
+/// 3. `gnome-session-quit --logout` for GNOME sessions
+///
+/// All sessions of the user are never ended here; the caller may offer
+/// [`terminate_user`](crate::actions::session::terminate_user) after an
+/// explicit confirmation instead.
+///
+/// # Errors
+/// Returns why the graphical session could not be found if every method
+/// failed.
+pub fn logout() -> Result<(), LogoutError> {
     debug!("Attempting to log out current session");
     // First try: Use XDG_SESSION_ID if available
-    if let Ok(session_id) = std::env::var("XDG_SESSION_ID") {
-        if session_id.is_empty() {
-            debug!("XDG_SESSION_ID is empty");
-        } else {
+    match std::env::var("XDG_SESSION_ID") {
+        Ok(session_id) if !session_id.is_empty() => {
             debug!("Using XDG_SESSION_ID {session_id} for logout");
-            let status = std::process::Command::new("loginctl")
-                .args(["terminate-session", &session_id])
-                .status();
-            if let Ok(status) = status {
-                if status.success() {
+            match terminate_session(&session_id) {
+                Ok(()) => {
                     info!("Successfully logged out via loginctl with XDG_SESSION_ID");
-                    return;
+                    return Ok(());
                 }
-                warn!("loginctl terminate-session failed with status: {status}");
-            } else {
-                error!("Failed to execute loginctl terminate-session command");
+                Err(e) => warn!("Logout with XDG_SESSION_ID failed: {e}"),
             }
         }
-    } else {
-        debug!("XDG_SESSION_ID environment variable not set");
+        _ => debug!("XDG_SESSION_ID not set"),
     }
 
-    // Second try: Use GNOME session quit command
+    // Second try: Ask logind for the graphical session of the user
+    let lookup = current_user()
+        .ok_or(LogoutError::NoSession)
+        .and_then(|user| find_graphical_session(&user));
+    let lookup_error = match lookup.and_then(|session| {
+        debug!("Logging out graphical session {}", session.id);
+        terminate_session(&session.id)
+    }) {
+        Ok(()) => {
+            info!("Successfully logged out the graphical session via loginctl");
+            return Ok(());
+        }
+        Err(e) => {
+            warn!("Could not log out through logind: {e}");
+            e
+        }
+    };
+
+    // Third try: Use GNOME session quit command
     if let Some(path) = which("gnome-session-quit") {
         debug!("Using gnome-session-quit at {} for logout", path.display());
         let status = std::process::Command::new(path).arg("--logout").status();
         if let Ok(status) = status {
             if status.success() {
                 info!("Successfully logged out via gnome-session-quit");
-                return;
+                return Ok(());
             }
             warn!("gnome-session-quit failed with status: {status}");
         } else {
@@ -93,22 +121,16 @@ fn logout_action() {
         debug!("gnome-session-quit not found in PATH");
     }
 
-    // Final fallback: Terminate user session via loginctl
-    debug!("Falling back to loginctl terminate-user");
-    let user = std::env::var("USER")
-        .or_else(|_| std::env::var("LOGNAME"))
-        .unwrap_or_default();
-    if user.is_empty() {
-        warn!("Cannot determine current user for logout");
-    } else {
-        info!("Logging out user {user} via loginctl terminate-user");
-        if let Err(e) = std::process::Command::new("loginctl")
-            .args(["terminate-user", &user])
-            .spawn()
-        {
-            error!("Failed to execute loginctl terminate-user: {e}");
-        } else {
-            info!("Successfully initiated logout for user {user}");
+    Err(lookup_error)
+}
+
+/// Tell the user that a power action failed
+pub fn show_power_error(message: &str) {
+    if gtk4::gdk::Display::default().is_some() {
+        let notification = gtk4::gio::Notification::new("Power Action Failed");
+        notification.set_body(Some(message));
+        if let Some(app) = gtk4::gio::Application::default() {
+            app.send_notification(Some("power-error"), &notification);
         }
     }
 }
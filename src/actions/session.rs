@@ -0,0 +1,289 @@
+//! Finding the user's graphical login session for logout
+//!
+//! When `XDG_SESSION_ID` is not set, logout asks logind (through `loginctl`)
+//! for the sessions of the current user and picks the active, local Wayland
+//! or X11 session on the current seat. Only that session is terminated, so
+//! terminal and SSH sessions of the same user keep running. Ending every
+//! session of the user (`loginctl terminate-user`) is never done implicitly.
+
+use log::{debug, info};
+use serde::Deserialize;
+use std::process::Command;
+
+/// Why the graphical session could not be logged out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogoutError {
+    /// No active graphical session of the user was found
+    NoSession,
+    /// Several seats have an active graphical session of the user
+    Ambiguous(Vec<String>),
+    /// `loginctl` could not be run, failed or printed something unexpected
+    Loginctl(String),
+}
+
+impl std::fmt::Display for LogoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogoutError::NoSession => write!(f, "No active graphical session found"),
+            LogoutError::Ambiguous(ids) => write!(
+                f,
+                "Several graphical sessions are active ({}); not sure which one to end",
+                ids.join(", ")
+            ),
+            LogoutError::Loginctl(msg) => write!(f, "loginctl failed: {msg}"),
+        }
+    }
+}
+
+/// One entry of `loginctl list-sessions --output=json`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ListedSession {
+    pub session: String,
+    #[serde(default)]
+    pub user: String,
+}
+
+/// Properties of a session, from `loginctl show-session`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionInfo {
+    pub id: String,
+    pub user: String,
+    /// `Type=`: `wayland`, `x11`, `tty`, …
+    pub kind: String,
+    /// `State=`: `active`, `online` or `closing`
+    pub state: String,
+    /// `Seat=`, empty for remote and some tty sessions
+    pub seat: String,
+    pub remote: bool,
+}
+
+/// Properties requested from `loginctl show-session`
+const SESSION_PROPERTIES: &[&str] = &["Id", "Name", "Type", "State", "Seat", "Remote"];
+
+/// Parse the output of `loginctl list-sessions --output=json`
+///
+/// # Errors
+/// Returns a message if the output is not the expected JSON array.
+pub fn parse_session_list(json: &str) -> Result<Vec<ListedSession>, String> {
+    serde_json::from_str(json).map_err(|e| format!("unexpected list-sessions output: {e}"))
+}
+
+/// Parse the `Key=Value` lines of `loginctl show-session`
+#[must_use]
+pub fn parse_session_properties(output: &str) -> SessionInfo {
+    let mut info = SessionInfo::default();
+    for (key, value) in output.lines().filter_map(|l| l.split_once('=')) {
+        let value = value.trim().to_string();
+        match key.trim() {
+            "Id" => info.id = value,
+            "Name" => info.user = value,
+            "Type" => info.kind = value,
+            "State" => info.state = value,
+            "Seat" => info.seat = value,
+            "Remote" => info.remote = value == "yes",
+            _ => {}
+        }
+    }
+    info
+}
+
+/// Pick the session to log out among `sessions`
+///
+/// Only active, local `wayland` or `x11` sessions of `user` are considered,
+/// and only those on `seat` when it is known. A seat has at most one active
+/// session, so without a seat several candidates on different seats are
+/// ambiguous.
+///
+/// # Errors
+/// Returns [`LogoutError::NoSession`] if no session qualifies and
+/// [`LogoutError::Ambiguous`] if several do.
+pub fn pick_graphical_session<'a>(
+    sessions: &'a [SessionInfo],
+    user: &str,
+    seat: Option<&str>,
+) -> Result<&'a SessionInfo, LogoutError> {
+    let candidates: Vec<&SessionInfo> = sessions
+        .iter()
+        .filter(|s| {
+            s.user == user
+                && matches!(s.kind.as_str(), "wayland" | "x11")
+                && s.state == "active"
+                && !s.remote
+                && seat.map_or(!s.seat.is_empty(), |seat| s.seat == seat)
+        })
+        .collect();
+    match candidates.as_slice() {
+        [] => Err(LogoutError::NoSession),
+        [session] => Ok(session),
+        many => Err(LogoutError::Ambiguous(
+            many.iter().map(|s| s.id.clone()).collect(),
+        )),
+    }
+}
+
+/// Name of the current user, from `USER` or `LOGNAME`
+#[must_use]
+pub fn current_user() -> Option<String> {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("LOGNAME"))
+        .ok()
+        .filter(|u| !u.is_empty())
+}
+
+/// Run `loginctl` with `args` and return its standard output
+fn loginctl(args: &[&str]) -> Result<String, LogoutError> {
+    let output = Command::new("loginctl")
+        .args(args)
+        .output()
+        .map_err(|e| LogoutError::Loginctl(e.to_string()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(LogoutError::Loginctl(format!(
+            "{} exited with {}: {}",
+            args.first().unwrap_or(&""),
+            output.status,
+            stderr.trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Find the graphical session of `user` through logind
+///
+/// The seat is taken from `XDG_SEAT` when it is set.
+///
+/// # Errors
+/// Returns an error if `loginctl` fails or no single session qualifies.
+pub fn find_graphical_session(user: &str) -> Result<SessionInfo, LogoutError> {
+    let listed = parse_session_list(&loginctl(&["list-sessions", "--output=json"])?)
+        .map_err(LogoutError::Loginctl)?;
+    let mut sessions = Vec::new();
+    for entry in listed.iter().filter(|s| s.user == user) {
+        let mut args = vec!["show-session", entry.session.as_str()];
+        let properties: Vec<String> = SESSION_PROPERTIES
+            .iter()
+            .map(|p| format!("--property={p}"))
+            .collect();
+        args.extend(properties.iter().map(String::as_str));
+        sessions.push(parse_session_properties(&loginctl(&args)?));
+    }
+    debug!("Sessions of {user}: {sessions:?}");
+
+    let seat = std::env::var("XDG_SEAT").ok().filter(|s| !s.is_empty());
+    pick_graphical_session(&sessions, user, seat.as_deref()).cloned()
+}
+
+/// Terminate one session with `loginctl terminate-session`
+///
+/// # Errors
+/// Returns an error if `loginctl` fails.
+pub fn terminate_session(id: &str) -> Result<(), LogoutError> {
+    info!("Terminating session {id}");
+    loginctl(&["terminate-session", id]).map(|_| ())
+}
+
+/// Terminate every session of `user`, including terminal and SSH ones
+///
+/// Only call this after the user explicitly confirmed it.
+///
+/// # Errors
+/// Returns an error if `loginctl` fails.
+pub fn terminate_user(user: &str) -> Result<(), LogoutError> {
+    info!("Terminating all sessions of user {user}");
+    loginctl(&["terminate-user", user]).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIST_SESSIONS: &str = r#"[
+        {"session":"2","uid":1000,"user":"alice","seat":"seat0","tty":"tty2","state":"active","idle":false,"since":null},
+        {"session":"5","uid":1000,"user":"alice","seat":null,"tty":"pts/0","state":"active","idle":false,"since":null},
+        {"session":"c1","uid":120,"user":"gdm","seat":"seat0","tty":"tty1"},
+        {"session":"7","uid":1000,"user":"alice","seat":"seat0","tty":"tty3"}
+    ]"#;
+
+    fn session(id: &str, kind: &str, state: &str, seat: &str, remote: bool) -> SessionInfo {
+        SessionInfo {
+            id: id.to_string(),
+            user: "alice".to_string(),
+            kind: kind.to_string(),
+            state: state.to_string(),
+            seat: seat.to_string(),
+            remote,
+        }
+    }
+
+    #[test]
+    fn test_parse_session_list() {
+        let sessions = parse_session_list(LIST_SESSIONS).unwrap();
+        let ids: Vec<&str> = sessions.iter().map(|s| s.session.as_str()).collect();
+        assert_eq!(ids, vec!["2", "5", "c1", "7"]);
+        assert_eq!(sessions[2].user, "gdm");
+
+        // Older systemd versions print fewer fields
+        let old = parse_session_list(
+            r#"[{"session":"3","uid":1000,"user":"bob","seat":"seat0","tty":"tty2"}]"#,
+        )
+        .unwrap();
+        assert_eq!(old[0].user, "bob");
+
+        assert!(parse_session_list("SESSION  UID USER").is_err());
+    }
+
+    #[test]
+    fn test_parse_session_properties() {
+        let info = parse_session_properties(
+            "Id=2\nName=alice\nType=wayland\nState=active\nSeat=seat0\nRemote=no\n",
+        );
+        assert_eq!(info, session("2", "wayland", "active", "seat0", false));
+
+        let ssh = parse_session_properties(
+            "Id=5\nName=alice\nType=tty\nState=active\nSeat=\nRemote=yes\n",
+        );
+        assert!(ssh.remote);
+        assert!(ssh.seat.is_empty());
+    }
+
+    #[test]
+    fn test_pick_graphical_session_skips_tty_and_remote() {
+        let sessions = [
+            session("5", "tty", "active", "", true),
+            session("3", "tty", "active", "seat0", false),
+            session("2", "wayland", "active", "seat0", false),
+            session("7", "x11", "online", "seat0", false),
+        ];
+        let picked = pick_graphical_session(&sessions, "alice", Some("seat0")).unwrap();
+        assert_eq!(picked.id, "2");
+        let picked = pick_graphical_session(&sessions, "alice", None).unwrap();
+        assert_eq!(picked.id, "2");
+    }
+
+    #[test]
+    fn test_pick_graphical_session_errors() {
+        let sessions = [
+            session("2", "wayland", "active", "seat0", false),
+            session("8", "x11", "active", "seat1", false),
+        ];
+        assert_eq!(
+            pick_graphical_session(&sessions, "alice", None),
+            Err(LogoutError::Ambiguous(vec![
+                "2".to_string(),
+                "8".to_string()
+            ]))
+        );
+        assert_eq!(
+            pick_graphical_session(&sessions, "alice", Some("seat1")).map(|s| s.id.as_str()),
+            Ok("8")
+        );
+        assert_eq!(
+            pick_graphical_session(&sessions, "bob", None),
+            Err(LogoutError::NoSession)
+        );
+        assert_eq!(
+            pick_graphical_session(&sessions, "alice", Some("seat2")),
+            Err(LogoutError::NoSession)
+        );
+    }
+}
@@ -1,14 +1,55 @@
+use crate::actions::file::{open_file_as_text, open_in_editor};
+use crate::actions::open_uri;
 use crate::core::callbacks::AppCallbacks;
-use log::info;
+use crate::core::config;
+use gtk4::gio;
+use gtk4::prelude::FileExt;
+use log::{info, warn};
 
 /// Open the settings GUI window
 ///
 /// Opens a graphical interface for editing Grunner's configuration settings.
+/// The dialog needs libadwaita; without the `adwaita` feature the
+/// configuration file is opened in the editor instead.
 pub fn open_settings(
-    window: &libadwaita::ApplicationWindow,
-    entry: &gtk4::Entry,
+    window: &crate::ui::compat::ApplicationWindow,
+    entry: &gtk4::SearchEntry,
     callbacks: &AppCallbacks,
 ) {
-    info!("Opening GUI settings window");
-    crate::settings_window::open_settings_window(window, entry, callbacks);
+    #[cfg(feature = "adwaita")]
+    {
+        info!("Opening GUI settings window");
+        crate::settings_window::open_settings_window(window, entry, callbacks);
+    }
+    #[cfg(not(feature = "adwaita"))]
+    {
+        let _ = (entry, callbacks);
+        match open_config_file(config::load().settings_editor.as_deref()) {
+            Ok(()) => gtk4::prelude::WidgetExt::set_visible(window, false),
+            Err(e) => warn!("Could not open the config file: {e}"),
+        }
+    }
+}
+
+/// Open the configuration file for editing
+///
+/// The editor is picked in this order:
+/// 1. `[launch] settings_editor`
+/// 2. `$VISUAL`, then `$EDITOR` (terminal editors run in the detected terminal)
+/// 3. The default application for plain text
+/// 4. The default application for the file's own type
+///
+/// The default configuration is written first if there is none, and the
+/// editor starts at the top of the file.
+pub fn open_config_file(settings_editor: Option<&str>) -> Result<(), std::io::Error> {
+    if let Err(e) = config::init_config() {
+        warn!("Could not create the default config: {e}");
+    }
+    let path = config::config_path();
+    info!("Opening config file: {}", path.display());
+
+    if open_in_editor(settings_editor, &path, 1, Some(1)) || open_file_as_text(&path) {
+        return Ok(());
+    }
+    open_uri(&gio::File::for_path(&path).uri())
 }
@@ -2,12 +2,19 @@
 //!
 //! Provides D-Bus communication with the window-calls GNOME Shell extension
 //! to enumerate, activate, and close windows on the current workspace.
+//!
+//! It also lists the windows of every workspace as [`RunningWindow`]s, so
+//! application rows can tell which apps are already running and focus their
+//! window instead of starting a second instance, and as [`ListedWindow`]s
+//! for the `:w` window switcher.
 
-use crate::utils::desktop::resolve_desktop_info;
+use crate::utils::desktop::DesktopIndex;
 use futures::future::join_all;
 use log::{debug, warn};
 use serde::Deserialize;
+use std::path::Path;
 use std::sync::OnceLock;
+use std::time::{Duration, Instant};
 use zbus::{Connection, proxy};
 
 async fn get_workspace_conn() -> zbus::Result<Connection> {
@@ -50,9 +57,93 @@ struct RawWindowEntry {
     title: Option<String>,
 }
 
-pub async fn fetch_workspace_windows() -> Option<Vec<WindowInfo>> {
-    let our_pid = std::process::id();
+/// How long a fetched list of running windows is reused
+pub const RUNNING_WINDOWS_TTL: Duration = Duration::from_secs(2);
+
+/// Window class of Grunner's own window, never listed
+pub const OWN_WM_CLASS: &str = "org.nihmar.grunner";
+
+/// A window open on any workspace, for matching against applications
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunningWindow {
+    pub id: u32,
+    pub wm_class: String,
+    pub wm_class_instance: String,
+}
+
+/// A window open on any workspace, with its title, for the `:w` switcher
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ListedWindow {
+    pub id: u32,
+    pub title: String,
+    pub wm_class: String,
+    pub wm_class_instance: String,
+    pub in_current_workspace: bool,
+}
+
+/// Running windows fetched at one point in time
+#[derive(Debug, Clone)]
+pub struct RunningWindows {
+    pub fetched: Instant,
+    pub windows: Vec<RunningWindow>,
+}
 
+impl RunningWindows {
+    /// Whether the list is younger than [`RUNNING_WINDOWS_TTL`] at `now`
+    #[must_use]
+    pub fn is_fresh(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.fetched) < RUNNING_WINDOWS_TTL
+    }
+}
+
+/// Program name of a desktop `Exec=` line
+///
+/// Leading `env` and `VAR=value` words are skipped and only the file name
+/// of the program is kept, so `env GDK_BACKEND=x11 /usr/bin/gimp %U` gives
+/// `gimp`.
+#[must_use]
+pub fn exec_basename(exec: &str) -> Option<&str> {
+    let program = exec
+        .split_whitespace()
+        .find(|word| *word != "env" && !word.contains('='))?;
+    Path::new(program)
+        .file_name()
+        .and_then(|name| name.to_str())
+}
+
+/// The window of an application, if it has one open
+///
+/// Window classes are compared case-insensitively, first with the app's
+/// `StartupWMClass`, then with its desktop id (the Wayland app id, e.g.
+/// `org.gnome.Nautilus`), then with the program name of its `Exec=` line.
+/// Every window is checked with one heuristic before the next is tried, so
+/// a declared window class wins over a program name shared by another app.
+#[must_use]
+pub fn find_running_window<'a>(
+    windows: &'a [RunningWindow],
+    startup_wm_class: &str,
+    desktop_id: &str,
+    exec: &str,
+) -> Option<&'a RunningWindow> {
+    let candidates = [
+        Some(startup_wm_class),
+        Some(desktop_id),
+        exec_basename(exec),
+    ];
+    candidates
+        .into_iter()
+        .flatten()
+        .filter(|name| !name.is_empty())
+        .find_map(|name| {
+            windows.iter().find(|w| {
+                w.wm_class.eq_ignore_ascii_case(name)
+                    || w.wm_class_instance.eq_ignore_ascii_case(name)
+            })
+        })
+}
+
+/// Windows listed by the window-calls extension, unfiltered
+async fn list_windows() -> Option<Vec<RawWindowEntry>> {
     let conn = get_workspace_conn()
         .await
         .map_err(|e| warn!("[workspace] D-Bus session connect failed: {e}"))
@@ -73,9 +164,51 @@ pub async fn fetch_workspace_windows() -> Option<Vec<WindowInfo>> {
         .map_err(|e| warn!("[workspace] WindowCalls.List failed: {e}"))
         .ok()?;
 
-    let raw_windows: Vec<RawWindowEntry> = serde_json::from_str(&json)
+    serde_json::from_str(&json)
         .map_err(|e| warn!("[workspace] Failed to parse window list JSON: {e}"))
-        .ok()?;
+        .ok()
+}
+
+/// Windows of every workspace except Grunner's own
+pub async fn fetch_running_windows() -> Option<RunningWindows> {
+    let windows = list_windows()
+        .await?
+        .into_iter()
+        .map(|raw| RunningWindow {
+            id: raw.id,
+            wm_class: raw.wm_class.unwrap_or_default(),
+            wm_class_instance: raw.wm_class_instance.unwrap_or_default(),
+        })
+        .filter(|w| w.wm_class != OWN_WM_CLASS && w.wm_class_instance != OWN_WM_CLASS)
+        .collect::<Vec<_>>();
+    debug!("[workspace] {} running window(s)", windows.len());
+    Some(RunningWindows {
+        fetched: Instant::now(),
+        windows,
+    })
+}
+
+/// Windows of every workspace except Grunner's own, with their titles
+pub async fn fetch_listed_windows() -> Option<Vec<ListedWindow>> {
+    let windows = list_windows()
+        .await?
+        .into_iter()
+        .map(|raw| ListedWindow {
+            id: raw.id,
+            title: raw.title.unwrap_or_default(),
+            wm_class: raw.wm_class.unwrap_or_default(),
+            wm_class_instance: raw.wm_class_instance.unwrap_or_default(),
+            in_current_workspace: raw.in_current_workspace,
+        })
+        .filter(|w| w.wm_class != OWN_WM_CLASS && w.wm_class_instance != OWN_WM_CLASS)
+        .collect::<Vec<_>>();
+    debug!("[workspace] {} window(s) listed", windows.len());
+    Some(windows)
+}
+
+pub async fn fetch_workspace_windows() -> Option<Vec<WindowInfo>> {
+    let our_pid = std::process::id();
+    let raw_windows = list_windows().await?;
 
     debug!(
         "[workspace] List returned {} entries, our_pid={}",
@@ -84,6 +217,7 @@ pub async fn fetch_workspace_windows() -> Option<Vec<WindowInfo>> {
     );
 
     let mut result = Vec::new();
+    let index = DesktopIndex::build();
 
     for raw in raw_windows {
         if !raw.in_current_workspace {
@@ -93,12 +227,13 @@ pub async fn fetch_workspace_windows() -> Option<Vec<WindowInfo>> {
         let wm_class = raw.wm_class.as_deref().unwrap_or("");
         let wm_class_instance = raw.wm_class_instance.as_deref().unwrap_or("");
 
-        if wm_class == "org.nihmar.grunner" || wm_class_instance == "org.nihmar.grunner" {
+        if wm_class == OWN_WM_CLASS || wm_class_instance == OWN_WM_CLASS {
             continue;
         }
 
-        let (title, icon_from_desktop) = resolve_desktop_info(wm_class)
-            .or_else(|| resolve_desktop_info(wm_class_instance))
+        let (title, icon_from_desktop) = index
+            .info(wm_class)
+            .or_else(|| index.info(wm_class_instance))
             .map_or_else(|| (String::new(), None), |info| (info.name, info.icon));
 
         let title = if title.is_empty() {
@@ -144,16 +279,27 @@ pub async fn fetch_workspace_windows() -> Option<Vec<WindowInfo>> {
 }
 
 pub async fn activate_window(id: u32) {
+    focus_window(id).await;
+}
+
+/// Focus the window `id`, returning whether the compositor accepted it
+///
+/// Fails when the extension is missing or the window was closed since the
+/// window list was fetched.
+pub async fn focus_window(id: u32) -> bool {
     let Ok(conn) = get_workspace_conn().await else {
-        return;
+        return false;
     };
     let Ok(windows) = WindowCallsProxy::new(&conn).await else {
-        return;
+        return false;
     };
 
-    let result = windows.activate(id).await;
-    if let Err(e) = result {
-        warn!("[workspace] Activate({id}) failed: {e}");
+    match windows.activate(id).await {
+        Ok(()) => true,
+        Err(e) => {
+            warn!("[workspace] Activate({id}) failed: {e}");
+            false
+        }
     }
 }
 
@@ -175,3 +321,87 @@ pub async fn close_all_windows(ids: Vec<u32>) {
     let futs: Vec<_> = ids.into_iter().map(close_window).collect();
     join_all(futs).await;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(id: u32, wm_class: &str, instance: &str) -> RunningWindow {
+        RunningWindow {
+            id,
+            wm_class: wm_class.to_string(),
+            wm_class_instance: instance.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_exec_basename() {
+        assert_eq!(exec_basename("firefox %u"), Some("firefox"));
+        assert_eq!(exec_basename("/usr/bin/gimp-2.10 %U"), Some("gimp-2.10"));
+        assert_eq!(
+            exec_basename("env GDK_BACKEND=x11 /opt/app/bin/app --flag"),
+            Some("app")
+        );
+        assert_eq!(exec_basename(""), None);
+    }
+
+    #[test]
+    fn test_find_running_window_heuristics() {
+        let windows = [
+            window(1, "firefox", "Navigator"),
+            window(2, "org.gnome.Nautilus", "org.gnome.Nautilus"),
+            window(3, "Code", "code"),
+        ];
+
+        // StartupWMClass
+        let found = find_running_window(&windows, "code", "code", "/usr/share/code/code %F");
+        assert_eq!(found.map(|w| w.id), Some(3));
+        // Desktop id as Wayland app id
+        let found =
+            find_running_window(&windows, "", "org.gnome.Nautilus", "nautilus --new-window");
+        assert_eq!(found.map(|w| w.id), Some(2));
+        // Program name of the Exec line
+        let found = find_running_window(&windows, "", "firefox-esr", "/usr/bin/firefox %u");
+        assert_eq!(found.map(|w| w.id), Some(1));
+
+        assert!(find_running_window(&windows, "", "gimp", "gimp %U").is_none());
+        assert!(find_running_window(&[], "Code", "code", "code").is_none());
+    }
+
+    #[test]
+    fn test_find_running_window_prefers_wm_class() {
+        // Two apps sharing a launcher binary, told apart by StartupWMClass
+        let windows = [
+            window(1, "chromium", "chromium"),
+            window(2, "crx_mail", "crx_mail"),
+        ];
+        let found = find_running_window(
+            &windows,
+            "crx_mail",
+            "chrome-mail",
+            "chromium --app-id=mail",
+        );
+        assert_eq!(found.map(|w| w.id), Some(2));
+
+        // A declared class that matches nothing falls back to the program name
+        let found = find_running_window(
+            &windows,
+            "crx_calendar",
+            "chrome-cal",
+            "chromium --app-id=cal",
+        );
+        assert_eq!(found.map(|w| w.id), Some(1));
+    }
+
+    #[test]
+    fn test_running_windows_freshness() {
+        let fetched = Instant::now();
+        let list = RunningWindows {
+            fetched,
+            windows: Vec::new(),
+        };
+        assert!(list.is_fresh(fetched));
+        assert!(list.is_fresh(fetched + Duration::from_millis(1500)));
+        assert!(!list.is_fresh(fetched + RUNNING_WINDOWS_TTL));
+    }
+}
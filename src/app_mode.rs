@@ -9,6 +9,9 @@
 //!   Used by the list factory to determine how to display command results
 //!   (e.g., Obsidian actions vs file paths vs custom scripts).
 //!
+//! Built-in colon command names live in one registry, [`ColonCommand`], which
+//! both the UI mode detection and the command handler consult.
+//!
 //! The mapping is typically: `AppMode::Obsidian` → `ActiveMode::ObsidianAction` or
 //! `ActiveMode::ObsidianFile` depending on whether results are actions or files.
 
@@ -19,9 +22,10 @@
 /// - What data sources to query
 /// - What UI elements to show
 /// - What actions are available
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum AppMode {
     /// Default mode - searches desktop applications and commands
+    #[default]
     Normal,
     /// File search mode triggered by `:f` prefix
     FileSearch,
@@ -32,6 +36,30 @@ pub enum AppMode {
     ObsidianGrep,
     /// Custom script mode triggered by `:sh` prefix
     CustomScript,
+    /// Browser bookmark search mode triggered by `:b`
+    Bookmarks,
+    /// Clipboard history mode triggered by `:c`
+    Clipboard,
+    /// Open window switcher triggered by `:w`
+    Windows,
+    /// Emoji picker triggered by `:e`
+    Emoji,
+    /// Process killer triggered by `:kill`
+    Processes,
+    /// Recent project launcher triggered by `:proj`
+    Projects,
+    /// SSH host launcher triggered by `:ssh`
+    Ssh,
+    /// Systemd unit control triggered by `:unit`
+    Units,
+    /// Manual page search triggered by `:man`
+    Manual,
+    /// Recent documents triggered by `:r`
+    Recent,
+    /// Text snippets triggered by `:snip`
+    Snippets,
+    /// Password store entries triggered by `:pw`
+    Pass,
 }
 
 /// Enum representing the rendering mode for list items
@@ -44,6 +72,18 @@ pub enum AppMode {
 /// - `AppMode::Obsidian` + with arg → `ActiveMode::ObsidianFile` (file list)
 /// - `AppMode::ObsidianGrep` → `ActiveMode::ObsidianGrep`
 /// - `AppMode::CustomScript` → `ActiveMode::CustomScript`
+/// - `AppMode::Bookmarks` → `ActiveMode::Bookmarks`
+/// - `AppMode::Clipboard` → `ActiveMode::Clipboard`
+/// - `AppMode::Windows` → `ActiveMode::Windows`
+/// - `AppMode::Emoji` → `ActiveMode::Emoji`
+/// - `AppMode::Processes` → `ActiveMode::Processes`
+/// - `AppMode::Projects` → `ActiveMode::Projects`
+/// - `AppMode::Ssh` → `ActiveMode::Ssh`
+/// - `AppMode::Units` → `ActiveMode::Units`
+/// - `AppMode::Manual` → `ActiveMode::Manual`
+/// - `AppMode::Recent` → `ActiveMode::Recent`
+/// - `AppMode::Snippets` → `ActiveMode::Snippets`
+/// - `AppMode::Pass` → `ActiveMode::Pass`
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ActiveMode {
     /// Default mode - no special rendering
@@ -57,6 +97,198 @@ pub enum ActiveMode {
     ObsidianGrep,
     /// Custom script mode for :sh command
     CustomScript,
+    /// Local usage statistics shown by the :stats command
+    Stats,
+    /// Browser bookmarks shown by the :b command
+    Bookmarks,
+    /// Clipboard history shown by the :c command
+    Clipboard,
+    /// Open windows shown by the :w command
+    Windows,
+    /// Emoji shown by the :e command
+    Emoji,
+    /// Processes shown by the :kill command
+    Processes,
+    /// Projects shown by the :proj command
+    Projects,
+    /// SSH hosts shown by the :ssh command
+    Ssh,
+    /// Systemd units shown by the :unit command
+    Units,
+    /// Manual pages shown by the :man command
+    Manual,
+    /// Recent documents shown by the :r command
+    Recent,
+    /// Snippets shown by the :snip command
+    Snippets,
+    /// Password store entries shown by the :pw command
+    Pass,
+}
+
+/// Built-in colon commands
+///
+/// The one registry of built-in command names: [`AppMode::from_text`], the
+/// command handler and the config check of `[web_searches]` keywords all look
+/// names up here, so a name means the same thing everywhere. Names match
+/// exactly, never as prefixes, and a built-in always wins over a custom
+/// keyword of the same name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColonCommand {
+    /// `:ob` — Obsidian notes and actions
+    Obsidian,
+    /// `:obg` — grep in the Obsidian vault
+    ObsidianGrep,
+    /// `:f` — file search
+    FileSearch,
+    /// `:fg` — grep in the home directory
+    FileGrep,
+    /// `:sh` — custom scripts
+    Shell,
+    /// `:b` — browser bookmarks
+    Bookmarks,
+    /// `:c` — clipboard history
+    Clipboard,
+    /// `:w` — open windows
+    Windows,
+    /// `:e` — emoji picker
+    Emoji,
+    /// `:kill` — process killer
+    Kill,
+    /// `:proj` — recent projects
+    Projects,
+    /// `:ssh` — SSH hosts
+    Ssh,
+    /// `:unit` — systemd units
+    Unit,
+    /// `:man` — manual pages
+    Man,
+    /// `:r` — recent documents
+    Recent,
+    /// `:snip` — text snippets
+    Snip,
+    /// `:pw` — password store entries
+    Pass,
+    /// `:stats` — usage statistics
+    Stats,
+    /// `:grunner` — settings of grunner itself, such as starting at login
+    Grunner,
+}
+
+impl ColonCommand {
+    /// Every built-in command
+    pub const ALL: [Self; 19] = [
+        Self::Obsidian,
+        Self::ObsidianGrep,
+        Self::FileSearch,
+        Self::FileGrep,
+        Self::Shell,
+        Self::Bookmarks,
+        Self::Clipboard,
+        Self::Windows,
+        Self::Emoji,
+        Self::Kill,
+        Self::Projects,
+        Self::Ssh,
+        Self::Unit,
+        Self::Man,
+        Self::Recent,
+        Self::Snip,
+        Self::Pass,
+        Self::Stats,
+        Self::Grunner,
+    ];
+
+    /// Name typed after the colon
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Obsidian => "ob",
+            Self::ObsidianGrep => "obg",
+            Self::FileSearch => "f",
+            Self::FileGrep => "fg",
+            Self::Shell => "sh",
+            Self::Bookmarks => "b",
+            Self::Clipboard => "c",
+            Self::Windows => "w",
+            Self::Emoji => "e",
+            Self::Kill => "kill",
+            Self::Projects => "proj",
+            Self::Ssh => "ssh",
+            Self::Unit => "unit",
+            Self::Man => "man",
+            Self::Recent => "r",
+            Self::Snip => "snip",
+            Self::Pass => "pw",
+            Self::Stats => "stats",
+            Self::Grunner => "grunner",
+        }
+    }
+
+    /// The built-in command called exactly `name`
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.name() == name)
+    }
+
+    /// The built-in command a query like `":obg todo"` runs
+    ///
+    /// The name ends at the first space, as in the command handler's parser.
+    #[must_use]
+    pub fn from_query(text: &str) -> Option<Self> {
+        let rest = text.strip_prefix(':')?;
+        let name = rest.split_once(' ').map_or(rest, |(name, _)| name);
+        Self::from_name(name)
+    }
+
+    /// What the command does, for the colon-command palette
+    #[must_use]
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Obsidian => "Obsidian notes and actions",
+            Self::ObsidianGrep => "Search the Obsidian vault",
+            Self::FileSearch => "Find files by name",
+            Self::FileGrep => "Search file contents",
+            Self::Shell => "Custom scripts and shell commands",
+            Self::Bookmarks => "Browser bookmarks",
+            Self::Clipboard => "Clipboard history",
+            Self::Windows => "Switch to an open window",
+            Self::Emoji => "Emoji picker",
+            Self::Kill => "Stop a running process",
+            Self::Projects => "Recent projects",
+            Self::Ssh => "SSH hosts",
+            Self::Unit => "Start and stop systemd units",
+            Self::Man => "Manual pages",
+            Self::Recent => "Recently used documents",
+            Self::Snip => "Text snippets",
+            Self::Pass => "Password store",
+            Self::Stats => "Mode usage statistics",
+            Self::Grunner => "Grunner settings, such as starting at login",
+        }
+    }
+
+    /// Input mode the command puts the UI in
+    #[must_use]
+    pub fn mode(self) -> AppMode {
+        match self {
+            Self::Obsidian => AppMode::Obsidian,
+            Self::ObsidianGrep => AppMode::ObsidianGrep,
+            Self::FileSearch | Self::FileGrep => AppMode::FileSearch,
+            Self::Shell => AppMode::CustomScript,
+            Self::Bookmarks => AppMode::Bookmarks,
+            Self::Clipboard => AppMode::Clipboard,
+            Self::Windows => AppMode::Windows,
+            Self::Emoji => AppMode::Emoji,
+            Self::Kill => AppMode::Processes,
+            Self::Projects => AppMode::Projects,
+            Self::Ssh => AppMode::Ssh,
+            Self::Unit => AppMode::Units,
+            Self::Man => AppMode::Manual,
+            Self::Recent => AppMode::Recent,
+            Self::Snip => AppMode::Snippets,
+            Self::Pass => AppMode::Pass,
+            Self::Stats | Self::Grunner => AppMode::Normal,
+        }
+    }
 }
 
 impl AppMode {
@@ -69,26 +301,29 @@ impl AppMode {
     /// The appropriate `AppMode` based on the prefix in the text.
     ///
     /// # Mode Triggers
-    /// - `:obg` prefix → `ObsidianGrep` (grep search within Obsidian notes)
-    /// - `:ob` prefix → `Obsidian` (simple Obsidian note search)
-    /// - `:f` or `:fg` prefix → `FileSearch` (file system search or content grep)
-    /// - `:sh` prefix → `CustomScript` (run custom scripts/commands)
-    /// - No prefix or unrecognized prefix → `Normal` (default application search)
+    /// - `:obg` → `ObsidianGrep` (grep search within Obsidian notes)
+    /// - `:ob` → `Obsidian` (simple Obsidian note search)
+    /// - `:f` or `:fg` → `FileSearch` (file system search or content grep)
+    /// - `:sh` → `CustomScript` (run custom scripts/commands)
+    /// - `:b` → `Bookmarks` (browser bookmarks)
+    /// - `:c` → `Clipboard` (clipboard history)
+    /// - `:w` → `Windows` (open windows)
+    /// - `:e` → `Emoji` (emoji picker)
+    /// - `:kill` → `Processes` (process killer)
+    /// - `:proj` → `Projects` (recent projects)
+    /// - `:ssh` → `Ssh` (SSH hosts)
+    /// - `:unit` → `Units` (systemd units)
+    /// - `:man` → `Manual` (manual pages)
+    /// - `:r` → `Recent` (recent documents)
+    /// - `:snip` → `Snippets` (text snippets)
+    /// - `:pw` → `Pass` (password store)
+    /// - Anything else, including `[web_searches]` keywords → `Normal`
     ///
-    /// Note: Order matters - `:obg` must be checked before `:ob` since both start with `:ob`
+    /// The command name must be typed exactly, alone or followed by a space
+    /// (see [`ColonCommand::from_query`]): `:obx` is not `:ob`.
     #[must_use]
     pub fn from_text(text: &str) -> Self {
-        if text.starts_with(":obg") {
-            Self::ObsidianGrep
-        } else if text.starts_with(":ob") {
-            Self::Obsidian
-        } else if text.starts_with(":f") {
-            Self::FileSearch
-        } else if text.starts_with(":sh") {
-            Self::CustomScript
-        } else {
-            Self::Normal
-        }
+        ColonCommand::from_query(text).map_or(Self::Normal, ColonCommand::mode)
     }
 
     /// Get the icon name for the current mode
@@ -104,6 +339,18 @@ impl AppMode {
     /// - `FileSearch` → "text-x-generic" (generic text file icon)
     /// - `Obsidian`/`ObsidianGrep` → Uses the provided `obsidian_icon`
     /// - `CustomScript` → "utilities-terminal" (terminal icon)
+    /// - `Bookmarks` → "user-bookmarks" (bookmark icon)
+    /// - `Clipboard` → "edit-paste" (clipboard icon)
+    /// - `Windows` → "preferences-system-windows" (window icon)
+    /// - `Emoji` → "face-smile" (emoji icon)
+    /// - `Processes` → "process-stop" (stop icon)
+    /// - `Projects` → "folder-open" (folder icon)
+    /// - `Ssh` → "network-server" (server icon)
+    /// - `Units` → "system-run" (run icon)
+    /// - `Manual` → "help-contents" (help icon)
+    /// - `Recent` → "document-open-recent" (recent documents icon)
+    /// - `Snippets` → "insert-text" (text icon)
+    /// - `Pass` → "changes-prevent" (lock icon)
     /// - `Normal` → `None` (no special icon)
     #[must_use]
     pub fn icon_name(self, obsidian_icon: &str) -> Option<&str> {
@@ -111,10 +358,46 @@ impl AppMode {
             Self::FileSearch => Some("text-x-generic"),
             Self::Obsidian | Self::ObsidianGrep => Some(obsidian_icon),
             Self::CustomScript => Some("utilities-terminal"),
+            Self::Bookmarks => Some("user-bookmarks"),
+            Self::Clipboard => Some("edit-paste"),
+            Self::Windows => Some("preferences-system-windows"),
+            Self::Emoji => Some("face-smile"),
+            Self::Processes => Some("process-stop"),
+            Self::Projects => Some("folder-open"),
+            Self::Ssh => Some("network-server"),
+            Self::Units => Some("system-run"),
+            Self::Manual => Some("help-contents"),
+            Self::Recent => Some("document-open-recent"),
+            Self::Snippets => Some("insert-text"),
+            Self::Pass => Some("changes-prevent"),
             Self::Normal => None,
         }
     }
 
+    /// Name of the mode, shown in the status footer
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Normal => "Apps",
+            Self::FileSearch => "Files",
+            Self::Obsidian => "Obsidian",
+            Self::ObsidianGrep => "Obsidian grep",
+            Self::CustomScript => "Commands",
+            Self::Bookmarks => "Bookmarks",
+            Self::Clipboard => "Clipboard",
+            Self::Windows => "Windows",
+            Self::Emoji => "Emoji",
+            Self::Processes => "Processes",
+            Self::Projects => "Projects",
+            Self::Ssh => "SSH hosts",
+            Self::Units => "Units",
+            Self::Manual => "Manual pages",
+            Self::Recent => "Recent documents",
+            Self::Snippets => "Snippets",
+            Self::Pass => "Passwords",
+        }
+    }
+
     /// Check if the Obsidian action bar should be shown in this mode
     ///
     /// # Returns
@@ -127,6 +410,17 @@ impl AppMode {
     pub fn show_obsidian_bar(self) -> bool {
         matches!(self, Self::Obsidian | Self::ObsidianGrep)
     }
+
+    /// Whether results in this mode are files or grep matches
+    ///
+    /// These rows follow the `[ui] file_results_layout` setting.
+    #[must_use]
+    pub fn shows_file_results(self) -> bool {
+        matches!(
+            self,
+            Self::FileSearch | Self::Obsidian | Self::ObsidianGrep | Self::Recent
+        )
+    }
 }
 
 #[cfg(test)]
@@ -173,6 +467,137 @@ mod tests {
         assert!(!AppMode::Normal.show_obsidian_bar());
     }
 
+    #[test]
+    fn test_app_mode_from_text_bookmarks() {
+        assert_eq!(AppMode::from_text(":b"), AppMode::Bookmarks);
+        assert_eq!(AppMode::from_text(":b github"), AppMode::Bookmarks);
+        // Only the exact command, not other words starting with b
+        assert_eq!(AppMode::from_text(":bx"), AppMode::Normal);
+        assert_eq!(
+            AppMode::Bookmarks.icon_name("my-icon"),
+            Some("user-bookmarks")
+        );
+        assert!(!AppMode::Bookmarks.show_obsidian_bar());
+    }
+
+    #[test]
+    fn test_app_mode_from_text_clipboard() {
+        assert_eq!(AppMode::from_text(":c"), AppMode::Clipboard);
+        assert_eq!(AppMode::from_text(":c ssh"), AppMode::Clipboard);
+        assert_eq!(AppMode::from_text(":code"), AppMode::Normal);
+        assert_eq!(AppMode::Clipboard.icon_name("my-icon"), Some("edit-paste"));
+        assert!(!AppMode::Clipboard.shows_file_results());
+    }
+
+    #[test]
+    fn test_app_mode_from_text_windows() {
+        assert_eq!(AppMode::from_text(":w"), AppMode::Windows);
+        assert_eq!(AppMode::from_text(":w firefox"), AppMode::Windows);
+        assert_eq!(AppMode::from_text(":wiki"), AppMode::Normal);
+        assert_eq!(
+            AppMode::Windows.icon_name("my-icon"),
+            Some("preferences-system-windows")
+        );
+        assert!(!AppMode::Windows.shows_file_results());
+    }
+
+    #[test]
+    fn test_app_mode_from_text_emoji() {
+        assert_eq!(AppMode::from_text(":e"), AppMode::Emoji);
+        assert_eq!(AppMode::from_text(":e heart"), AppMode::Emoji);
+        assert_eq!(AppMode::from_text(":emoji"), AppMode::Normal);
+        assert_eq!(AppMode::Emoji.icon_name("my-icon"), Some("face-smile"));
+        assert!(!AppMode::Emoji.shows_file_results());
+    }
+
+    #[test]
+    fn test_app_mode_from_text_processes() {
+        assert_eq!(AppMode::from_text(":kill"), AppMode::Processes);
+        assert_eq!(AppMode::from_text(":kill firefox"), AppMode::Processes);
+        assert_eq!(AppMode::from_text(":killall"), AppMode::Normal);
+        assert_eq!(
+            AppMode::Processes.icon_name("my-icon"),
+            Some("process-stop")
+        );
+        assert!(!AppMode::Processes.shows_file_results());
+    }
+
+    #[test]
+    fn test_app_mode_from_text_projects() {
+        assert_eq!(AppMode::from_text(":proj"), AppMode::Projects);
+        assert_eq!(AppMode::from_text(":proj grunner"), AppMode::Projects);
+        assert_eq!(AppMode::from_text(":project"), AppMode::Normal);
+        assert_eq!(AppMode::Projects.icon_name("my-icon"), Some("folder-open"));
+        assert!(!AppMode::Projects.shows_file_results());
+    }
+
+    #[test]
+    fn test_app_mode_from_text_ssh() {
+        assert_eq!(AppMode::from_text(":ssh"), AppMode::Ssh);
+        assert_eq!(AppMode::from_text(":ssh prod"), AppMode::Ssh);
+        assert_eq!(AppMode::from_text(":sshfs"), AppMode::Normal);
+        assert_eq!(AppMode::Ssh.icon_name("my-icon"), Some("network-server"));
+        assert!(!AppMode::Ssh.shows_file_results());
+    }
+
+    #[test]
+    fn test_app_mode_from_text_units() {
+        assert_eq!(AppMode::from_text(":unit"), AppMode::Units);
+        assert_eq!(AppMode::from_text(":unit sshd"), AppMode::Units);
+        assert_eq!(AppMode::from_text(":units"), AppMode::Normal);
+        assert_eq!(AppMode::Units.icon_name("my-icon"), Some("system-run"));
+        assert!(!AppMode::Units.shows_file_results());
+    }
+
+    #[test]
+    fn test_app_mode_from_text_manual() {
+        assert_eq!(AppMode::from_text(":man"), AppMode::Manual);
+        assert_eq!(AppMode::from_text(":man printf"), AppMode::Manual);
+        assert_eq!(AppMode::from_text(":mandb"), AppMode::Normal);
+        assert_eq!(AppMode::Manual.icon_name("my-icon"), Some("help-contents"));
+        assert!(!AppMode::Manual.shows_file_results());
+    }
+
+    #[test]
+    fn test_app_mode_from_text_recent() {
+        assert_eq!(AppMode::from_text(":r"), AppMode::Recent);
+        assert_eq!(AppMode::from_text(":r report"), AppMode::Recent);
+        assert_eq!(AppMode::from_text(":rm"), AppMode::Normal);
+        assert_eq!(
+            AppMode::Recent.icon_name("my-icon"),
+            Some("document-open-recent")
+        );
+        assert!(AppMode::Recent.shows_file_results());
+    }
+
+    #[test]
+    fn test_app_mode_from_text_snippets() {
+        assert_eq!(AppMode::from_text(":snip"), AppMode::Snippets);
+        assert_eq!(AppMode::from_text(":snip sig"), AppMode::Snippets);
+        assert_eq!(AppMode::from_text(":snippets"), AppMode::Normal);
+        assert_eq!(AppMode::Snippets.icon_name("my-icon"), Some("insert-text"));
+        assert!(!AppMode::Snippets.shows_file_results());
+    }
+
+    #[test]
+    fn test_app_mode_from_text_pass() {
+        assert_eq!(AppMode::from_text(":pw"), AppMode::Pass);
+        assert_eq!(AppMode::from_text(":pw github"), AppMode::Pass);
+        assert_eq!(AppMode::from_text(":pwd"), AppMode::Normal);
+        assert_eq!(AppMode::Pass.icon_name("my-icon"), Some("changes-prevent"));
+        assert!(!AppMode::Pass.shows_file_results());
+    }
+
+    #[test]
+    fn test_app_mode_shows_file_results() {
+        assert!(AppMode::FileSearch.shows_file_results());
+        assert!(AppMode::Obsidian.shows_file_results());
+        assert!(AppMode::ObsidianGrep.shows_file_results());
+        assert!(!AppMode::CustomScript.shows_file_results());
+        assert!(!AppMode::Bookmarks.shows_file_results());
+        assert!(!AppMode::Normal.shows_file_results());
+    }
+
     #[test]
     fn test_app_mode_from_text_obg_with_arg() {
         assert_eq!(
@@ -209,6 +634,46 @@ mod tests {
         assert_eq!(AppMode::from_text("   "), AppMode::Normal);
     }
 
+    #[test]
+    fn test_app_mode_from_text_exact_names() {
+        let cases = [
+            (":ob", AppMode::Obsidian),
+            (":ob notes", AppMode::Obsidian),
+            (":obg", AppMode::ObsidianGrep),
+            (":obg todo", AppMode::ObsidianGrep),
+            // Longer names are not prefixed by a built-in
+            (":obg2 x", AppMode::Normal),
+            (":obsidian", AppMode::Normal),
+            (":fx", AppMode::Normal),
+            (":fgrep x", AppMode::Normal),
+            (":shop shoes", AppMode::Normal),
+            (":o", AppMode::Normal),
+            (":stats", AppMode::Normal),
+            ("ob", AppMode::Normal),
+            (": ob", AppMode::Normal),
+        ];
+        for (text, mode) in cases {
+            assert_eq!(AppMode::from_text(text), mode, "{text:?}");
+        }
+    }
+
+    #[test]
+    fn test_colon_command_registry() {
+        for command in ColonCommand::ALL {
+            assert_eq!(ColonCommand::from_name(command.name()), Some(command));
+        }
+        assert_eq!(
+            ColonCommand::from_query(":fg todo"),
+            Some(ColonCommand::FileGrep)
+        );
+        assert_eq!(
+            ColonCommand::from_query(":fg"),
+            Some(ColonCommand::FileGrep)
+        );
+        assert_eq!(ColonCommand::from_query("fg"), None);
+        assert_eq!(ColonCommand::from_name(""), None);
+    }
+
     #[test]
     fn test_app_mode_icon_name_empty_obsidian() {
         assert_eq!(AppMode::Obsidian.icon_name(""), Some(""));
@@ -227,4 +692,12 @@ mod tests {
         );
         assert_eq!(AppMode::Normal.icon_name(icon), None);
     }
+
+    #[test]
+    fn test_app_mode_label() {
+        assert_eq!(AppMode::from_text("fire").label(), "Apps");
+        assert_eq!(AppMode::from_text(":fg todo").label(), "Files");
+        assert_eq!(AppMode::from_text(":obg todo").label(), "Obsidian grep");
+        assert_eq!(AppMode::from_text(":pw").label(), "Passwords");
+    }
 }
@@ -0,0 +1,455 @@
+//! Where desktop applications were installed from
+//!
+//! An app's origin is read from its desktop entry: Flatpak exports carry
+//! `X-Flatpak` and live below the ref they belong to, so the branch is part
+//! of the resolved path; snaps carry `X-SnapInstanceName` and are looked up
+//! with `snap list`; anything else is asked of the native package manager
+//! (`pacman -Qo`, `dpkg -S` or `rpm -qf`). The lookups run commands, so they
+//! happen off the main thread, only for apps that are shown, and once per
+//! desktop file until it changes. An origin that cannot be found is simply
+//! left out.
+
+use crate::model::worker::spawn_worker_with_updates;
+use crate::providers::subprocess::{
+    CommandSpec, build_command, command_output_lines, find_program,
+};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+use std::time::SystemTime;
+
+/// Where an application was installed from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppOrigin {
+    /// Flatpak app, with the branch of its ref if the export could be
+    /// followed
+    Flatpak { branch: Option<String> },
+    /// Snap, with its version and tracked channel if `snap list` knew it
+    Snap {
+        version: Option<String>,
+        channel: Option<String>,
+    },
+    /// File owned by a package of the native package manager
+    Package {
+        manager: &'static str,
+        name: String,
+        version: String,
+    },
+}
+
+impl AppOrigin {
+    /// Short text such as "Flatpak · stable" or "pacman · firefox 126.0-1"
+    #[must_use]
+    pub fn label(&self) -> String {
+        match self {
+            Self::Flatpak { branch } => match branch {
+                Some(branch) => format!("Flatpak · {branch}"),
+                None => "Flatpak".to_string(),
+            },
+            Self::Snap { version, channel } => std::iter::once("Snap")
+                .chain(channel.as_deref())
+                .chain(version.as_deref())
+                .collect::<Vec<_>>()
+                .join(" · "),
+            Self::Package {
+                manager,
+                name,
+                version,
+            } => format!("{manager} · {name} {version}"),
+        }
+    }
+}
+
+/// Value of `key` in the `[Desktop Entry]` group of a desktop file
+#[must_use]
+pub fn desktop_entry_value<'a>(content: &'a str, key: &str) -> Option<&'a str> {
+    let mut in_entry = false;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+        } else if in_entry
+            && let Some((k, value)) = line.split_once('=')
+            && k.trim() == key
+        {
+            return Some(value.trim()).filter(|value| !value.is_empty());
+        }
+    }
+    None
+}
+
+/// Branch of the Flatpak ref that `resolved`, the desktop file with its
+/// export symlink followed, belongs to
+///
+/// Deployed apps live in `…/app/<id>/<arch>/<branch>/<commit>/…`.
+#[must_use]
+pub fn flatpak_branch(resolved: &Path, app_id: &str) -> Option<String> {
+    let parts: Vec<&str> = resolved
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => part.to_str(),
+            _ => None,
+        })
+        .collect();
+    parts
+        .windows(4)
+        .find(|w| w[0] == "app" && w[1] == app_id)
+        .map(|w| w[3].to_string())
+}
+
+/// Package name and version from `pacman -Qo` output:
+/// `<path> is owned by <name> <version>`
+#[must_use]
+pub fn parse_pacman_owner(line: &str) -> Option<(String, String)> {
+    let (_, owner) = line.rsplit_once(" is owned by ")?;
+    let (name, version) = owner.trim().split_once(' ')?;
+    Some((name.to_string(), version.trim().to_string()))
+}
+
+/// Package name from `dpkg -S` output: `<package>[:<arch>][, …]: <path>`
+///
+/// Diversion lines are skipped; of several owners the first is taken.
+#[must_use]
+pub fn parse_dpkg_owner(lines: &[&str]) -> Option<String> {
+    lines
+        .iter()
+        .filter(|line| !line.starts_with("diversion "))
+        .find_map(|line| {
+            let (packages, _) = line.split_once(": ")?;
+            let package = packages.split(", ").next()?.trim();
+            let name = package.split(':').next()?;
+            (!name.is_empty()).then(|| name.to_string())
+        })
+}
+
+/// Package name and version from `rpm -qf` output in the
+/// `%{NAME} %{VERSION}-%{RELEASE}` format
+///
+/// rpm reports unowned files on stdout, so those lines are rejected.
+#[must_use]
+pub fn parse_rpm_owner(line: &str) -> Option<(String, String)> {
+    if line.contains(" not owned by ") {
+        return None;
+    }
+    let (name, version) = line.trim().split_once(' ')?;
+    Some((name.to_string(), version.trim().to_string()))
+}
+
+/// Version and tracked channel from `snap list <name>` output
+///
+/// The first line is a header; `Tracking` is `-` for locally installed
+/// snaps.
+#[must_use]
+pub fn parse_snap_list(lines: &[&str], name: &str) -> Option<(String, Option<String>)> {
+    lines.iter().skip(1).find_map(|line| {
+        let mut columns = line.split_whitespace();
+        if columns.next()? != name {
+            return None;
+        }
+        let version = columns.next()?.to_string();
+        let channel = columns.nth(1).filter(|c| *c != "-").map(str::to_string);
+        Some((version, channel))
+    })
+}
+
+/// Output lines of `spec`, or nothing if its program is not installed
+fn run(spec: CommandSpec, max_lines: usize) -> Vec<String> {
+    if find_program(&spec.program).is_none() {
+        return Vec::new();
+    }
+    command_output_lines(build_command(&spec), max_lines)
+        .into_iter()
+        .map(|line| line.display)
+        .collect()
+}
+
+/// The native package owning `path`, asking each installed package manager
+/// in turn
+fn package_owner(path: &Path) -> Option<AppOrigin> {
+    let package = |manager, (name, version)| AppOrigin::Package {
+        manager,
+        name,
+        version,
+    };
+    if let Some(owner) = run(CommandSpec::new("pacman").arg("-Qo").arg(path), 1)
+        .first()
+        .and_then(|line| parse_pacman_owner(line))
+    {
+        return Some(package("pacman", owner));
+    }
+    let lines = run(CommandSpec::new("dpkg").arg("-S").arg(path), 8);
+    let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+    if let Some(name) = parse_dpkg_owner(&lines) {
+        let version = run(
+            CommandSpec::new("dpkg-query")
+                .arg("-W")
+                .arg("-f=${Version}")
+                .arg(&name),
+            1,
+        );
+        if let Some(version) = version.first().filter(|v| !v.is_empty()) {
+            return Some(package("dpkg", (name, version.clone())));
+        }
+    }
+    run(
+        CommandSpec::new("rpm")
+            .arg("-qf")
+            .arg("--qf")
+            .arg("%{NAME} %{VERSION}-%{RELEASE}\\n")
+            .arg(path),
+        1,
+    )
+    .first()
+    .and_then(|line| parse_rpm_owner(line))
+    .map(|owner| package("rpm", owner))
+}
+
+/// Find where the app of the desktop file `path` came from
+///
+/// Blocks on the package manager; call it off the main thread.
+#[must_use]
+pub fn resolve_origin(path: &Path) -> Option<AppOrigin> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let resolved = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    if let Some(app_id) = desktop_entry_value(&content, "X-Flatpak") {
+        return Some(AppOrigin::Flatpak {
+            branch: flatpak_branch(&resolved, app_id),
+        });
+    }
+    if let Some(name) = desktop_entry_value(&content, "X-SnapInstanceName") {
+        let lines = run(CommandSpec::new("snap").arg("list").arg(name), 2);
+        let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let (version, channel) = parse_snap_list(&lines, name).unzip();
+        return Some(AppOrigin::Snap {
+            version,
+            channel: channel.flatten(),
+        });
+    }
+    package_owner(&resolved)
+}
+
+/// Callback waiting for an origin that is being looked up
+type Waiter = Box<dyn FnOnce(&AppOrigin)>;
+
+/// Lookup state of one desktop file
+enum Entry {
+    /// Lookup running; the callbacks run once it finds an origin
+    Pending(Vec<Waiter>),
+    /// Lookup done for the file as last modified at `modified`
+    Resolved {
+        modified: Option<SystemTime>,
+        origin: Option<AppOrigin>,
+    },
+}
+
+/// Origins of desktop files, looked up on demand and cached per path
+///
+/// Clones share the cache. A file modified since its lookup, as happens when
+/// the package is upgraded, is looked up again.
+#[derive(Clone, Default)]
+pub struct AppOrigins {
+    entries: Rc<RefCell<HashMap<PathBuf, Entry>>>,
+}
+
+impl AppOrigins {
+    /// The origin of the desktop file `path` if it is known
+    ///
+    /// Otherwise a lookup is started, unless one is running already, and
+    /// `on_resolved` is called on the main thread once it finds an origin.
+    /// Nothing is called if the origin cannot be found.
+    pub fn get_or_resolve(
+        &self,
+        path: &Path,
+        on_resolved: impl FnOnce(&AppOrigin) + 'static,
+    ) -> Option<AppOrigin> {
+        let modified = modified_time(path);
+        let mut entries = self.entries.borrow_mut();
+        match entries.get_mut(path) {
+            Some(Entry::Resolved {
+                modified: looked_up,
+                origin,
+            }) if *looked_up == modified => return origin.clone(),
+            Some(Entry::Pending(waiters)) => {
+                waiters.push(Box::new(on_resolved));
+                return None;
+            }
+            _ => {}
+        }
+        entries.insert(
+            path.to_path_buf(),
+            Entry::Pending(vec![Box::new(on_resolved)]),
+        );
+        drop(entries);
+
+        let entries = self.entries.clone();
+        let key = path.to_path_buf();
+        let lookup = path.to_path_buf();
+        spawn_worker_with_updates(
+            move |tx| {
+                let _ = tx.send(resolve_origin(&lookup));
+            },
+            || true,
+            move |origin: Option<AppOrigin>| {
+                let resolved = Entry::Resolved {
+                    modified,
+                    origin: origin.clone(),
+                };
+                let previous = entries.borrow_mut().insert(key.clone(), resolved);
+                if let (Some(origin), Some(Entry::Pending(waiters))) = (origin, previous) {
+                    for waiter in waiters {
+                        waiter(&origin);
+                    }
+                }
+            },
+        );
+        None
+    }
+}
+
+/// Modification time of `path`, following symlinks
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FLATPAK_ENTRY: &str = "\
+[Desktop Entry]
+Name=Firefox
+Exec=/usr/bin/flatpak run --branch=stable org.mozilla.firefox
+X-Flatpak=org.mozilla.firefox
+
+[Desktop Action new-window]
+Name=New Window
+X-Flatpak=elsewhere
+";
+
+    #[test]
+    fn test_desktop_entry_value() {
+        assert_eq!(
+            desktop_entry_value(FLATPAK_ENTRY, "X-Flatpak"),
+            Some("org.mozilla.firefox")
+        );
+        assert_eq!(
+            desktop_entry_value(FLATPAK_ENTRY, "X-SnapInstanceName"),
+            None
+        );
+        // Keys of other groups do not count
+        let action_only = "[Desktop Action a]\nX-SnapInstanceName=firefox\n";
+        assert_eq!(desktop_entry_value(action_only, "X-SnapInstanceName"), None);
+    }
+
+    #[test]
+    fn test_flatpak_branch() {
+        let system = Path::new(
+            "/var/lib/flatpak/app/org.mozilla.firefox/x86_64/stable/5f0c1d2e/export/share/applications/org.mozilla.firefox.desktop",
+        );
+        assert_eq!(
+            flatpak_branch(system, "org.mozilla.firefox").as_deref(),
+            Some("stable")
+        );
+        let user = Path::new(
+            "/home/u/.local/share/flatpak/app/org.gnome.Builder/aarch64/master/ab12/export/share/applications/org.gnome.Builder.desktop",
+        );
+        assert_eq!(
+            flatpak_branch(user, "org.gnome.Builder").as_deref(),
+            Some("master")
+        );
+        // An export that was not followed names no branch
+        let export =
+            Path::new("/var/lib/flatpak/exports/share/applications/org.mozilla.firefox.desktop");
+        assert_eq!(flatpak_branch(export, "org.mozilla.firefox"), None);
+    }
+
+    #[test]
+    fn test_parse_pacman_owner() {
+        assert_eq!(
+            parse_pacman_owner(
+                "/usr/share/applications/firefox.desktop is owned by firefox 126.0-1"
+            ),
+            Some(("firefox".to_string(), "126.0-1".to_string()))
+        );
+        assert_eq!(
+            parse_pacman_owner("error: No package owns /usr/share/applications/x.desktop"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_dpkg_owner() {
+        assert_eq!(
+            parse_dpkg_owner(&["firefox-esr: /usr/share/applications/firefox-esr.desktop"]),
+            Some("firefox-esr".to_string())
+        );
+        assert_eq!(
+            parse_dpkg_owner(&[
+                "libreoffice-common:amd64, libreoffice-core: /usr/share/applications/x.desktop"
+            ]),
+            Some("libreoffice-common".to_string())
+        );
+        assert_eq!(
+            parse_dpkg_owner(&[
+                "diversion by foo from: /usr/share/applications/x.desktop",
+                "diversion by foo to: /usr/share/applications/x.desktop.real",
+                "bar: /usr/share/applications/x.desktop",
+            ]),
+            Some("bar".to_string())
+        );
+        assert_eq!(parse_dpkg_owner(&[]), None);
+    }
+
+    #[test]
+    fn test_parse_rpm_owner() {
+        assert_eq!(
+            parse_rpm_owner("firefox 126.0-1.fc40"),
+            Some(("firefox".to_string(), "126.0-1.fc40".to_string()))
+        );
+        assert_eq!(
+            parse_rpm_owner("file /usr/share/applications/x.desktop is not owned by any package"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_snap_list() {
+        let output = [
+            "Name     Version  Rev   Tracking       Publisher   Notes",
+            "firefox  126.0-1  4336  latest/stable  mozilla✓    -",
+        ];
+        assert_eq!(
+            parse_snap_list(&output, "firefox"),
+            Some(("126.0-1".to_string(), Some("latest/stable".to_string())))
+        );
+        let local = [
+            "Name   Version  Rev  Tracking  Publisher  Notes",
+            "mysnap 0.1      x1   -         -          -",
+        ];
+        assert_eq!(
+            parse_snap_list(&local, "mysnap"),
+            Some(("0.1".to_string(), None))
+        );
+        assert_eq!(parse_snap_list(&output[..1], "firefox"), None);
+    }
+
+    #[test]
+    fn test_labels() {
+        let flatpak = AppOrigin::Flatpak {
+            branch: Some("stable".to_string()),
+        };
+        assert_eq!(flatpak.label(), "Flatpak · stable");
+        assert_eq!(AppOrigin::Flatpak { branch: None }.label(), "Flatpak");
+        let snap = AppOrigin::Snap {
+            version: Some("126.0-1".to_string()),
+            channel: Some("latest/stable".to_string()),
+        };
+        assert_eq!(snap.label(), "Snap · latest/stable · 126.0-1");
+        let package = AppOrigin::Package {
+            manager: "pacman",
+            name: "firefox".to_string(),
+            version: "126.0-1".to_string(),
+        };
+        assert_eq!(package.label(), "pacman · firefox 126.0-1");
+    }
+}
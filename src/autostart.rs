@@ -0,0 +1,281 @@
+//! Starting a resident grunner with the session
+//!
+//! `grunner --install-autostart` writes an XDG autostart entry that runs
+//! `grunner --daemon` at login, and `--remove-autostart` deletes it; the
+//! "Start at login" row of `:grunner` does the same. The entry lives in
+//! `$XDG_CONFIG_HOME/autostart`, or `~/.config/autostart`.
+//!
+//! Entries written here end in [`GENERATED_MARKER`]. An entry without it
+//! was written or edited by hand, and is only replaced or deleted with
+//! `--force`.
+
+use crate::core::global_state::get_home_dir;
+use std::ffi::OsString;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// File name of the autostart entry
+pub const AUTOSTART_FILE: &str = "org.nihmar.grunner.desktop";
+
+/// Last line of the entries grunner writes
+pub const GENERATED_MARKER: &str = "X-Grunner-Generated=true";
+
+/// Command the entry runs at login
+pub const AUTOSTART_EXEC: &str = "grunner --daemon";
+
+/// The autostart folder: `config_home/autostart` if `config_home` is a
+/// non-empty absolute path, as the XDG spec asks, else `~/.config/autostart`
+#[must_use]
+pub fn autostart_dir(config_home: Option<OsString>, home: &str) -> PathBuf {
+    match config_home.map(PathBuf::from) {
+        Some(dir) if dir.is_absolute() => dir.join("autostart"),
+        _ => PathBuf::from(home).join(".config").join("autostart"),
+    }
+}
+
+/// Path of the autostart entry of the current user
+#[must_use]
+pub fn autostart_path() -> PathBuf {
+    autostart_dir(std::env::var_os("XDG_CONFIG_HOME"), get_home_dir()).join(AUTOSTART_FILE)
+}
+
+/// Contents of the autostart entry
+#[must_use]
+pub fn desktop_entry() -> String {
+    format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=grunner\n\
+         Comment=Keep the grunner launcher running in the background\n\
+         Exec={AUTOSTART_EXEC}\n\
+         Icon=org.nihmar.grunner\n\
+         Terminal=false\n\
+         NoDisplay=true\n\
+         X-GNOME-Autostart-enabled=true\n\
+         {GENERATED_MARKER}\n"
+    )
+}
+
+/// Whether `contents` is an entry grunner wrote, i.e. has the marker line
+#[must_use]
+pub fn is_generated(contents: &str) -> bool {
+    contents.lines().any(|line| line.trim() == GENERATED_MARKER)
+}
+
+/// Whether an autostart entry exists at `path`
+#[must_use]
+pub fn is_installed(path: &Path) -> bool {
+    path.exists()
+}
+
+/// What [`install`] or [`remove`] did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// A new entry was written
+    Installed,
+    /// An existing entry was written over
+    Replaced,
+    /// The entry was already up to date
+    Unchanged,
+    /// The entry was deleted
+    Removed,
+    /// There was no entry to delete
+    NotInstalled,
+}
+
+impl Outcome {
+    /// What happened to the entry at `path`, for the command line
+    #[must_use]
+    pub fn message(self, path: &Path) -> String {
+        let path = path.display();
+        match self {
+            Self::Installed => format!("Created {path}"),
+            Self::Replaced => format!("Replaced {path}"),
+            Self::Unchanged => format!("{path} is already installed"),
+            Self::Removed => format!("Removed {path}"),
+            Self::NotInstalled => format!("{path} does not exist"),
+        }
+    }
+}
+
+/// Why the autostart entry could not be changed
+#[derive(Debug)]
+pub enum AutostartError {
+    /// The entry has no [`GENERATED_MARKER`] and `force` was not given
+    Modified(PathBuf),
+    /// Reading, writing or deleting the entry failed
+    Io(PathBuf, std::io::Error),
+}
+
+impl fmt::Display for AutostartError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Modified(path) => write!(
+                f,
+                "{} was not written by grunner or was edited; use --force to replace or remove it",
+                path.display()
+            ),
+            Self::Io(path, e) => write!(f, "{}: {e}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for AutostartError {}
+
+/// Contents of the entry at `path`, or `None` if there is none
+fn read_entry(path: &Path) -> Result<Option<String>, AutostartError> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(AutostartError::Io(path.to_path_buf(), e)),
+    }
+}
+
+/// Write the autostart entry to `path`
+///
+/// An entry without the marker is only replaced with `force`.
+///
+/// # Errors
+/// Returns [`AutostartError::Modified`] for a hand-written entry without
+/// `force`, and [`AutostartError::Io`] if the file cannot be read or
+/// written.
+pub fn install(path: &Path, force: bool) -> Result<Outcome, AutostartError> {
+    let entry = desktop_entry();
+    let outcome = match read_entry(path)? {
+        None => Outcome::Installed,
+        Some(contents) if contents == entry => return Ok(Outcome::Unchanged),
+        Some(contents) if is_generated(&contents) || force => Outcome::Replaced,
+        Some(_) => return Err(AutostartError::Modified(path.to_path_buf())),
+    };
+    let io_error = |e| AutostartError::Io(path.to_path_buf(), e);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(io_error)?;
+    }
+    std::fs::write(path, entry).map_err(io_error)?;
+    Ok(outcome)
+}
+
+/// Delete the autostart entry at `path`
+///
+/// An entry without the marker is only deleted with `force`.
+///
+/// # Errors
+/// Returns [`AutostartError::Modified`] for a hand-written entry without
+/// `force`, and [`AutostartError::Io`] if the file cannot be read or
+/// deleted.
+pub fn remove(path: &Path, force: bool) -> Result<Outcome, AutostartError> {
+    match read_entry(path)? {
+        None => Ok(Outcome::NotInstalled),
+        Some(contents) if is_generated(&contents) || force => {
+            std::fs::remove_file(path).map_err(|e| AutostartError::Io(path.to_path_buf(), e))?;
+            Ok(Outcome::Removed)
+        }
+        Some(_) => Err(AutostartError::Modified(path.to_path_buf())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_config(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "grunner_test_autostart_{name}_{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_autostart_dir() {
+        assert_eq!(
+            autostart_dir(Some("/xdg/config".into()), "/home/u"),
+            Path::new("/xdg/config/autostart")
+        );
+        // Empty and relative values are ignored, as the spec asks
+        assert_eq!(
+            autostart_dir(Some("".into()), "/home/u"),
+            Path::new("/home/u/.config/autostart")
+        );
+        assert_eq!(
+            autostart_dir(Some("config".into()), "/home/u"),
+            Path::new("/home/u/.config/autostart")
+        );
+        assert_eq!(
+            autostart_dir(None, "/home/u"),
+            Path::new("/home/u/.config/autostart")
+        );
+    }
+
+    #[test]
+    fn test_desktop_entry() {
+        let entry = desktop_entry();
+        assert!(entry.starts_with("[Desktop Entry]\n"));
+        assert!(entry.contains("\nExec=grunner --daemon\n"));
+        assert!(entry.ends_with("X-Grunner-Generated=true\n"));
+        assert!(is_generated(&entry));
+        assert!(!is_generated("[Desktop Entry]\nExec=grunner --daemon\n"));
+    }
+
+    #[test]
+    fn test_install_and_remove() {
+        let dir = temp_config("install");
+        let path = autostart_dir(Some(dir.clone().into()), "/nonexistent").join(AUTOSTART_FILE);
+
+        assert!(!is_installed(&path));
+        assert_eq!(install(&path, false).unwrap(), Outcome::Installed);
+        assert!(is_installed(&path));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), desktop_entry());
+        assert_eq!(install(&path, false).unwrap(), Outcome::Unchanged);
+
+        // An older entry grunner wrote is replaced without --force
+        std::fs::write(&path, format!("Exec=grunner\n{GENERATED_MARKER}\n")).unwrap();
+        assert_eq!(install(&path, false).unwrap(), Outcome::Replaced);
+
+        assert_eq!(remove(&path, false).unwrap(), Outcome::Removed);
+        assert!(!is_installed(&path));
+        assert_eq!(remove(&path, false).unwrap(), Outcome::NotInstalled);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_modified_entry_needs_force() {
+        let dir = temp_config("force");
+        let path = dir.join("autostart").join(AUTOSTART_FILE);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let edited = "[Desktop Entry]\nExec=grunner --daemon --simple\n";
+        std::fs::write(&path, edited).unwrap();
+
+        assert!(matches!(
+            install(&path, false),
+            Err(AutostartError::Modified(_))
+        ));
+        assert!(matches!(
+            remove(&path, false),
+            Err(AutostartError::Modified(_))
+        ));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), edited);
+
+        assert_eq!(install(&path, true).unwrap(), Outcome::Replaced);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), desktop_entry());
+        std::fs::write(&path, edited).unwrap();
+        assert_eq!(remove(&path, true).unwrap(), Outcome::Removed);
+        assert!(!is_installed(&path));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_outcome_message() {
+        let path = Path::new("/home/u/.config/autostart/org.nihmar.grunner.desktop");
+        assert_eq!(
+            Outcome::Installed.message(path),
+            "Created /home/u/.config/autostart/org.nihmar.grunner.desktop"
+        );
+        assert!(
+            AutostartError::Modified(path.to_path_buf())
+                .to_string()
+                .contains("--force")
+        );
+    }
+}
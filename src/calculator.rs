@@ -8,14 +8,24 @@
 //! - Basic arithmetic operations (+, -, *, /, %)
 //! - Parentheses for grouping
 //! - Exponentiation (^)
-//! - Trigonometric functions (sin, cos, tan)
-//! - Square root (sqrt)
-//! - Mathematical constants (pi, e)
+//! - Trigonometric functions (sin, cos, tan, asin, acos, atan), in radians
+//!   or degrees per `[calculator] angle_unit`
+//! - Square root, logarithms and rounding (sqrt, ln, log, log2, exp, abs,
+//!   floor, ceil, round)
+//! - Mathematical constants (pi, e, tau)
 //! - Floating point numbers
+//! - Hexadecimal (`0xff`) and binary (`0b1010`) literals; integer results of
+//!   such expressions are shown in decimal, hex and binary
+//! - Unit conversions: `<number> <unit> to <unit>` for length, mass,
+//!   temperature, time and data sizes (`12 km to mi`, `1 GiB in MB`)
 //! - Error handling for invalid expressions
 
+use crate::core::config::AngleUnit;
 use log::debug;
 
+/// Separates the decimal, hex and binary forms of an integer result
+pub const BASE_SEPARATOR: &str = "  •  ";
+
 /// Check whether a character is valid inside a calculator expression.
 pub(crate) fn is_valid_calc_char(c: char) -> bool {
     c.is_ascii_digit()
@@ -51,6 +61,13 @@ pub(crate) fn is_valid_calc_char(c: char) -> bool {
 /// ```
 #[must_use]
 pub fn evaluate(expr: &str) -> Option<String> {
+    evaluate_with(expr, AngleUnit::Radians)
+}
+
+/// Evaluate an expression like [`evaluate`], with trigonometric functions
+/// taking and returning angles in `angle_unit`
+#[must_use]
+pub fn evaluate_with(expr: &str, angle_unit: AngleUnit) -> Option<String> {
     // Trim whitespace
     let expr = expr.trim();
 
@@ -107,12 +124,22 @@ pub fn evaluate(expr: &str) -> Option<String> {
         return None;
     }
 
+    if let Some(converted) = convert_units(expr) {
+        debug!("Converted {expr} to {converted}");
+        return Some(converted);
+    }
+
     debug!("Evaluating expression: {expr}");
 
     // Parse and evaluate using shunting yard algorithm
-    match evaluate_expression(expr) {
+    match evaluate_expression(expr, angle_unit) {
         Ok(result) => {
             debug!("Expression evaluated to: {result}");
+            if has_radix_literal(expr)
+                && let Some(bases) = format_bases(result)
+            {
+                return Some(bases);
+            }
             Some(format_result(result))
         }
         Err(e) => {
@@ -122,6 +149,212 @@ pub fn evaluate(expr: &str) -> Option<String> {
     }
 }
 
+/// Kind of quantity a unit measures; only units of one kind convert
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dimension {
+    Length,
+    Mass,
+    Temperature,
+    Time,
+    Data,
+}
+
+/// A unit: `value * factor + offset` gives the base unit of its dimension
+///
+/// Base units are metres, grams, kelvins, seconds and bytes. Only
+/// temperatures have an offset.
+#[derive(Debug, Clone, Copy)]
+struct Unit {
+    symbol: &'static str,
+    dimension: Dimension,
+    factor: f64,
+    offset: f64,
+}
+
+const fn unit(symbol: &'static str, dimension: Dimension, factor: f64) -> Unit {
+    Unit {
+        symbol,
+        dimension,
+        factor,
+        offset: 0.0,
+    }
+}
+
+/// Look up a unit by name, ignoring case
+///
+/// `kB` is 1000 bytes and `KiB` 1024; `m` is metres and `min` minutes.
+fn parse_unit(name: &str) -> Option<Unit> {
+    use Dimension::{Data, Length, Mass, Temperature, Time};
+    let unit = match name.to_ascii_lowercase().as_str() {
+        "mm" | "millimeter" | "millimeters" | "millimetre" | "millimetres" => {
+            unit("mm", Length, 0.001)
+        }
+        "cm" | "centimeter" | "centimeters" | "centimetre" | "centimetres" => {
+            unit("cm", Length, 0.01)
+        }
+        "m" | "meter" | "meters" | "metre" | "metres" => unit("m", Length, 1.0),
+        "km" | "kilometer" | "kilometers" | "kilometre" | "kilometres" => {
+            unit("km", Length, 1000.0)
+        }
+        "in" | "inch" | "inches" => unit("in", Length, 0.0254),
+        "ft" | "foot" | "feet" => unit("ft", Length, 0.3048),
+        "yd" | "yard" | "yards" => unit("yd", Length, 0.9144),
+        "mi" | "mile" | "miles" => unit("mi", Length, 1609.344),
+        "mg" | "milligram" | "milligrams" => unit("mg", Mass, 0.001),
+        "g" | "gram" | "grams" => unit("g", Mass, 1.0),
+        "kg" | "kilogram" | "kilograms" => unit("kg", Mass, 1000.0),
+        "t" | "tonne" | "tonnes" => unit("t", Mass, 1_000_000.0),
+        "oz" | "ounce" | "ounces" => unit("oz", Mass, 28.349_523_125),
+        "lb" | "lbs" | "pound" | "pounds" => unit("lb", Mass, 453.592_37),
+        "c" | "celsius" => Unit {
+            symbol: "°C",
+            dimension: Temperature,
+            factor: 1.0,
+            offset: 273.15,
+        },
+        "f" | "fahrenheit" => Unit {
+            symbol: "°F",
+            dimension: Temperature,
+            factor: 5.0 / 9.0,
+            offset: 273.15 - 32.0 * 5.0 / 9.0,
+        },
+        "k" | "kelvin" => unit("K", Temperature, 1.0),
+        "ms" | "millisecond" | "milliseconds" => unit("ms", Time, 0.001),
+        "s" | "sec" | "second" | "seconds" => unit("s", Time, 1.0),
+        "min" | "minute" | "minutes" => unit("min", Time, 60.0),
+        "h" | "hr" | "hour" | "hours" => unit("h", Time, 3600.0),
+        "d" | "day" | "days" => unit("d", Time, 86_400.0),
+        "wk" | "week" | "weeks" => unit("wk", Time, 604_800.0),
+        "b" | "byte" | "bytes" => unit("B", Data, 1.0),
+        "kb" => unit("kB", Data, 1e3),
+        "mb" => unit("MB", Data, 1e6),
+        "gb" => unit("GB", Data, 1e9),
+        "tb" => unit("TB", Data, 1e12),
+        "kib" => unit("KiB", Data, 1024.0),
+        "mib" => unit("MiB", Data, 1_048_576.0),
+        "gib" => unit("GiB", Data, 1_073_741_824.0),
+        "tib" => unit("TiB", Data, 1_099_511_627_776.0),
+        _ => return None,
+    };
+    Some(unit)
+}
+
+/// Evaluate `<number> <unit> to <unit>` (or `in`), e.g. `12 km to mi`
+///
+/// The number may touch its unit (`72f to c`). Returns the converted value
+/// with the target unit's symbol, or `None` if the query has another shape,
+/// a unit is unknown, or the units measure different things.
+fn convert_units(expr: &str) -> Option<String> {
+    let number_end = expr
+        .char_indices()
+        .find(|&(i, c)| !(c.is_ascii_digit() || c == '.' || (i == 0 && c == '-')))
+        .map_or(expr.len(), |(i, _)| i);
+    let value: f64 = expr[..number_end].parse().ok()?;
+
+    let words: Vec<&str> = expr[number_end..].split_whitespace().collect();
+    let [from, keyword, to] = words.as_slice() else {
+        return None;
+    };
+    if !matches!(keyword.to_ascii_lowercase().as_str(), "to" | "in") {
+        return None;
+    }
+    let (from, to) = (parse_unit(from)?, parse_unit(to)?);
+    if from.dimension != to.dimension {
+        return None;
+    }
+
+    let base = value * from.factor + from.offset;
+    let converted = (base - to.offset) / to.factor;
+    converted
+        .is_finite()
+        .then(|| format!("{} {}", format_converted(converted), to.symbol))
+}
+
+/// Format a converted value with 4 decimals, or 4 significant digits below 1
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn format_converted(value: f64) -> String {
+    let magnitude = value.abs();
+    let decimals = if magnitude >= 1.0 || magnitude == 0.0 {
+        4
+    } else {
+        (3.0 - magnitude.log10().floor()).clamp(4.0, 15.0) as usize
+    };
+    let s = format!("{value:.decimals$}");
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" { "0" } else { s }.to_string()
+}
+
+/// The decimal form of a result, the part copied when it is activated
+///
+/// Results with several bases keep only their first, decimal form; other
+/// results are returned unchanged.
+#[must_use]
+pub fn decimal_form(result: &str) -> &str {
+    result
+        .split_once(BASE_SEPARATOR)
+        .map_or(result, |(decimal, _)| decimal)
+}
+
+/// The hex form of a result shown in several bases
+#[must_use]
+pub fn hex_form(result: &str) -> Option<&str> {
+    result.split(BASE_SEPARATOR).nth(1)
+}
+
+/// Whether the expression contains a `0x` or `0b` literal
+fn has_radix_literal(expr: &str) -> bool {
+    let bytes = expr.as_bytes();
+    bytes.windows(2).enumerate().any(|(i, pair)| {
+        pair[0] == b'0'
+            && matches!(pair[1], b'x' | b'X' | b'b' | b'B')
+            && (i == 0 || !bytes[i - 1].is_ascii_alphanumeric())
+    })
+}
+
+/// `256  •  0x100  •  0b100000000` for an integer result, else `None`
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+fn format_bases(result: f64) -> Option<String> {
+    if result.fract() != 0.0 || result.abs() >= i64::MAX as f64 {
+        return None;
+    }
+    let value = result as i64;
+    let sign = if value < 0 { "-" } else { "" };
+    let magnitude = value.unsigned_abs();
+    Some(format!(
+        "{value}{BASE_SEPARATOR}{sign}{magnitude:#x}{BASE_SEPARATOR}{sign}{magnitude:#b}"
+    ))
+}
+
+/// Parse the `0x` or `0b` literal at the start of `chars`, if there is one
+///
+/// # Errors
+/// Returns an error if the prefix is followed by no digit of its base.
+fn parse_radix_literal(
+    chars: &mut std::iter::Peekable<std::str::Chars<'_>>,
+) -> Result<Option<f64>, String> {
+    let mut ahead = chars.clone();
+    let radix = match (ahead.next(), ahead.next()) {
+        (Some('0'), Some('x' | 'X')) => 16,
+        (Some('0'), Some('b' | 'B')) => 2,
+        _ => return Ok(None),
+    };
+    chars.nth(1);
+
+    let mut digits = String::new();
+    while let Some(&ch) = chars.peek() {
+        if ch.is_ascii_alphanumeric() {
+            digits.push(ch);
+            chars.next();
+        } else {
+            break;
+        }
+    }
+    let value = u64::from_str_radix(&digits, radix)
+        .map_err(|_| format!("Invalid base {radix} literal: {digits}"))?;
+    #[allow(clippy::cast_precision_loss)]
+    Ok(Some(value as f64))
+}
+
 /// Format the result for display
 ///
 /// Removes trailing zeros from floating point numbers
@@ -141,10 +374,10 @@ fn format_result(result: f64) -> String {
 ///
 /// # Errors
 /// Returns an error if the expression is invalid or contains mismatched parentheses.
-fn evaluate_expression(expr: &str) -> Result<f64, String> {
+fn evaluate_expression(expr: &str, angle_unit: AngleUnit) -> Result<f64, String> {
     let tokens = tokenize(expr)?;
     let rpn = shunting_yard(&tokens)?;
-    evaluate_rpn(&rpn)
+    evaluate_rpn(&rpn, angle_unit)
 }
 
 /// Tokenize the expression into numbers and operators
@@ -162,7 +395,10 @@ fn tokenize(expr: &str) -> Result<Vec<Token>, String> {
             continue;
         }
 
-        if c.is_ascii_digit() || c == '.' {
+        if let Some(num) = parse_radix_literal(&mut chars)? {
+            tokens.push(Token::Number(num));
+            last_token_was_operator_or_open_paren = false;
+        } else if c.is_ascii_digit() || c == '.' {
             // Parse number
             let mut num_str = String::new();
             while let Some(&ch) = chars.peek() {
@@ -185,10 +421,11 @@ fn tokenize(expr: &str) -> Result<Vec<Token>, String> {
             tokens.push(Token::Number(num));
             last_token_was_operator_or_open_paren = false;
         } else if c.is_alphabetic() {
-            // Parse identifier (function or constant)
+            // Parse identifier (function or constant); digits after the
+            // first letter allow names such as `log2`
             let mut ident = String::new();
             while let Some(&ch) = chars.peek() {
-                if ch.is_alphabetic() {
+                if ch.is_alphabetic() || (!ident.is_empty() && ch.is_ascii_digit()) {
                     ident.push(ch);
                     chars.next();
                 } else {
@@ -201,6 +438,18 @@ fn tokenize(expr: &str) -> Result<Vec<Token>, String> {
                 "cos" => tokens.push(Token::Function(FunctionType::Cos)),
                 "sqrt" => tokens.push(Token::Function(FunctionType::Sqrt)),
                 "tan" => tokens.push(Token::Function(FunctionType::Tan)),
+                "asin" => tokens.push(Token::Function(FunctionType::Asin)),
+                "acos" => tokens.push(Token::Function(FunctionType::Acos)),
+                "atan" => tokens.push(Token::Function(FunctionType::Atan)),
+                "ln" => tokens.push(Token::Function(FunctionType::Ln)),
+                "log" => tokens.push(Token::Function(FunctionType::Log)),
+                "log2" => tokens.push(Token::Function(FunctionType::Log2)),
+                "exp" => tokens.push(Token::Function(FunctionType::Exp)),
+                "abs" => tokens.push(Token::Function(FunctionType::Abs)),
+                "floor" => tokens.push(Token::Function(FunctionType::Floor)),
+                "ceil" => tokens.push(Token::Function(FunctionType::Ceil)),
+                "round" => tokens.push(Token::Function(FunctionType::Round)),
+                "tau" => tokens.push(Token::Number(std::f64::consts::TAU)),
                 "pi" => tokens.push(Token::Number(std::f64::consts::PI)),
                 "e" => tokens.push(Token::Number(std::f64::consts::E)),
                 _ => return Err(format!("Unknown identifier: {ident}")),
@@ -255,6 +504,19 @@ enum FunctionType {
     Cos,
     Sqrt,
     Tan,
+    Asin,
+    Acos,
+    Atan,
+    /// Natural logarithm
+    Ln,
+    /// Base-10 logarithm
+    Log,
+    Log2,
+    Exp,
+    Abs,
+    Floor,
+    Ceil,
+    Round,
 }
 
 /// Operator types with precedence
@@ -377,7 +639,16 @@ fn shunting_yard(tokens: &[Token]) -> Result<Vec<Token>, String> {
 ///
 /// # Errors
 /// Returns an error if there are insufficient operands or division by zero.
-fn evaluate_rpn(rpn: &[Token]) -> Result<f64, String> {
+fn evaluate_rpn(rpn: &[Token], angle_unit: AngleUnit) -> Result<f64, String> {
+    let to_radians = |a: f64| match angle_unit {
+        AngleUnit::Radians => a,
+        AngleUnit::Degrees => a.to_radians(),
+    };
+    let from_radians = |a: f64| match angle_unit {
+        AngleUnit::Radians => a,
+        AngleUnit::Degrees => a.to_degrees(),
+    };
+
     let mut stack = Vec::new();
 
     for &token in rpn {
@@ -391,15 +662,32 @@ fn evaluate_rpn(rpn: &[Token]) -> Result<f64, String> {
                     .pop()
                     .expect("stack invariant: guarded by is_empty check");
                 let result = match func {
-                    FunctionType::Sin => a.sin(),
-                    FunctionType::Cos => a.cos(),
+                    FunctionType::Sin => to_radians(a).sin(),
+                    FunctionType::Cos => to_radians(a).cos(),
                     FunctionType::Sqrt => {
                         if a < 0.0 {
                             return Err("Square root of negative number".to_string());
                         }
                         a.sqrt()
                     }
-                    FunctionType::Tan => a.tan(),
+                    FunctionType::Tan => to_radians(a).tan(),
+                    FunctionType::Asin | FunctionType::Acos if !(-1.0..=1.0).contains(&a) => {
+                        return Err("Inverse sine or cosine outside [-1, 1]".to_string());
+                    }
+                    FunctionType::Asin => from_radians(a.asin()),
+                    FunctionType::Acos => from_radians(a.acos()),
+                    FunctionType::Atan => from_radians(a.atan()),
+                    FunctionType::Ln | FunctionType::Log | FunctionType::Log2 if a <= 0.0 => {
+                        return Err("Logarithm of a non-positive number".to_string());
+                    }
+                    FunctionType::Ln => a.ln(),
+                    FunctionType::Log => a.log10(),
+                    FunctionType::Log2 => a.log2(),
+                    FunctionType::Exp => a.exp(),
+                    FunctionType::Abs => a.abs(),
+                    FunctionType::Floor => a.floor(),
+                    FunctionType::Ceil => a.ceil(),
+                    FunctionType::Round => a.round(),
                 };
                 stack.push(result);
             }
@@ -453,6 +741,82 @@ fn evaluate_rpn(rpn: &[Token]) -> Result<f64, String> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_unit_conversions() {
+        assert_eq!(evaluate("12 km to mi"), Some("7.4565 mi".to_string()));
+        assert_eq!(evaluate("250 g to oz"), Some("8.8185 oz".to_string()));
+        assert_eq!(evaluate("1.5 h to min"), Some("90 min".to_string()));
+        assert_eq!(evaluate("6 ft in cm"), Some("182.88 cm".to_string()));
+        assert_eq!(evaluate("3KG TO LB"), Some("6.6139 lb".to_string()));
+        assert_eq!(evaluate("1 mm to km"), Some("0.000001 km".to_string()));
+    }
+
+    #[test]
+    fn test_temperature_conversions_are_affine() {
+        assert_eq!(evaluate("72 f to c"), Some("22.2222 °C".to_string()));
+        assert_eq!(evaluate("100 c to f"), Some("212 °F".to_string()));
+        assert_eq!(evaluate("-40 c to f"), Some("-40 °F".to_string()));
+        assert_eq!(evaluate("0 c to k"), Some("273.15 K".to_string()));
+        assert_eq!(evaluate("32 f to c"), Some("0 °C".to_string()));
+    }
+
+    #[test]
+    fn test_data_size_conversions() {
+        assert_eq!(evaluate("1 KiB to kB"), Some("1.024 kB".to_string()));
+        assert_eq!(evaluate("1 kB to B"), Some("1000 B".to_string()));
+        assert_eq!(evaluate("1 GB to GiB"), Some("0.9313 GiB".to_string()));
+        assert_eq!(evaluate("2 MiB to KiB"), Some("2048 KiB".to_string()));
+    }
+
+    #[test]
+    fn test_unknown_or_incompatible_units() {
+        assert_eq!(evaluate("12 km to kg"), None);
+        assert_eq!(evaluate("5 parsecs to km"), None);
+        assert_eq!(evaluate("12 km"), None);
+        assert_eq!(evaluate("12 km mi"), None);
+        assert_eq!(evaluate("km to mi"), None);
+    }
+
+    #[test]
+    fn test_hex_and_binary_literals() {
+        assert_eq!(
+            evaluate("0xff + 1"),
+            Some("256  •  0x100  •  0b100000000".to_string())
+        );
+        assert_eq!(
+            evaluate("0b1010 * 0XA"),
+            Some("100  •  0x64  •  0b1100100".to_string())
+        );
+        assert_eq!(
+            evaluate("0x10"),
+            Some("16  •  0x10  •  0b10000".to_string())
+        );
+        assert_eq!(
+            evaluate("0x1 - 0x3"),
+            Some("-2  •  -0x2  •  -0b10".to_string())
+        );
+        // Fractional results only have a decimal form
+        assert_eq!(evaluate("0x10 / 3"), Some("5.3333333333".to_string()));
+        // Decimal-only expressions are unchanged
+        assert_eq!(evaluate("10 + 6"), Some("16".to_string()));
+    }
+
+    #[test]
+    fn test_invalid_radix_literals() {
+        assert_eq!(evaluate("0b102 + 1"), None);
+        assert_eq!(evaluate("0x + 1"), None);
+        assert_eq!(evaluate("0xfg"), None);
+    }
+
+    #[test]
+    fn test_result_forms() {
+        let result = "256  •  0x100  •  0b100000000";
+        assert_eq!(decimal_form(result), "256");
+        assert_eq!(hex_form(result), Some("0x100"));
+        assert_eq!(decimal_form("7.4565 mi"), "7.4565 mi");
+        assert_eq!(hex_form("4"), None);
+    }
+
     #[test]
     fn test_basic_arithmetic() {
         assert_eq!(evaluate("2 + 2"), Some("4".to_string()));
@@ -533,6 +897,46 @@ mod tests {
         assert_eq!(evaluate("sqrt(-1)"), None);
     }
 
+    #[test]
+    fn test_more_functions_and_constants() {
+        assert_eq!(evaluate("sin(pi/2)"), Some("1".to_string()));
+        assert_eq!(evaluate("log(100)"), Some("2".to_string()));
+        assert_eq!(evaluate("ln(e)"), Some("1".to_string()));
+        assert_eq!(evaluate("log2(1024)"), Some("10".to_string()));
+        assert_eq!(evaluate("exp(0)"), Some("1".to_string()));
+        assert_eq!(evaluate("abs(-3) + floor(2.7)"), Some("5".to_string()));
+        assert_eq!(evaluate("ceil(2.1) * round(2.5)"), Some("9".to_string()));
+        assert_eq!(evaluate("tau / pi"), Some("2".to_string()));
+        assert_eq!(evaluate("atan(1) * 4"), Some("3.1415926536".to_string()));
+
+        assert_eq!(evaluate("log(0)"), None);
+        assert_eq!(evaluate("ln(-1)"), None);
+        assert_eq!(evaluate("asin(2)"), None);
+    }
+
+    #[test]
+    fn test_angle_units() {
+        let deg = AngleUnit::Degrees;
+        assert_eq!(evaluate_with("sin(90)", deg), Some("1".to_string()));
+        assert_eq!(evaluate_with("cos(180)", deg), Some("-1".to_string()));
+        assert_eq!(evaluate_with("tan(45)", deg), Some("1".to_string()));
+        assert_eq!(evaluate_with("asin(1)", deg), Some("90".to_string()));
+        assert_eq!(evaluate_with("atan(1)", deg), Some("45".to_string()));
+        // Radians are the default
+        assert_eq!(evaluate("cos(pi)"), Some("-1".to_string()));
+        assert_eq!(
+            evaluate_with("cos(pi)", AngleUnit::Radians),
+            Some("-1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_app_names_are_not_calculations() {
+        for name in ["firefox", "gimp", "logseq", "calculator", "exp ense"] {
+            assert_eq!(evaluate(name), None, "{name}");
+        }
+    }
+
     #[test]
     fn test_division_by_zero() {
         assert_eq!(evaluate("10 / 0"), None);
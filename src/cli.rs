@@ -0,0 +1,154 @@
+//! Command-line options
+
+use lexopt::prelude::*;
+
+/// Options of a launcher run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RunOptions {
+    /// `-s`: only app search, no power bar
+    pub disable_modes: bool,
+    /// `--daemon`: stay resident with the window hidden
+    pub daemon: bool,
+    /// `--toggle`: show or hide the window of a resident instance
+    pub toggle: bool,
+    /// `--scratchpad`: a resident instance with its own app ID, for window
+    /// manager rules
+    pub scratchpad: bool,
+}
+
+impl RunOptions {
+    /// Whether the instance keeps running while its window is hidden
+    #[must_use]
+    pub fn resident(&self) -> bool {
+        self.daemon || self.toggle || self.scratchpad
+    }
+
+    /// Whether the window is shown at startup
+    #[must_use]
+    pub fn present(&self) -> bool {
+        !self.daemon && !self.scratchpad
+    }
+}
+
+/// What the command line asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Run(RunOptions),
+    Help,
+    Version,
+    ListProviders,
+    InitConfig,
+    /// `--dmenu`: pick a line of standard input
+    Dmenu,
+    /// `--install-autostart`, replacing a hand-written entry with `force`
+    InstallAutostart {
+        force: bool,
+    },
+    /// `--remove-autostart`, deleting a hand-written entry with `force`
+    RemoveAutostart {
+        force: bool,
+    },
+}
+
+/// Parse the arguments after the program name
+///
+/// # Errors
+/// Returns an error for unknown options and stray arguments, and for
+/// `--force` without an autostart option.
+pub fn parse<I>(args: I) -> Result<Command, lexopt::Error>
+where
+    I: IntoIterator,
+    I::Item: Into<std::ffi::OsString>,
+{
+    let mut parser = lexopt::Parser::from_args(args);
+    let mut options = RunOptions::default();
+    let mut autostart = None;
+    let mut force = false;
+
+    while let Some(arg) = parser.next()? {
+        match arg {
+            Short('h') | Long("help") => return Ok(Command::Help),
+            Short('v' | 'V') | Long("version") => return Ok(Command::Version),
+            Short('s') | Long("simple") => options.disable_modes = true,
+            Long("daemon") => options.daemon = true,
+            Long("toggle") => options.toggle = true,
+            Long("scratchpad") => options.scratchpad = true,
+            Long("list-providers") => return Ok(Command::ListProviders),
+            Long("init-config") => return Ok(Command::InitConfig),
+            Long("dmenu") => return Ok(Command::Dmenu),
+            Long("install-autostart") => autostart = Some(true),
+            Long("remove-autostart") => autostart = Some(false),
+            Long("force") => force = true,
+            _ => return Err(arg.unexpected()),
+        }
+    }
+    match autostart {
+        Some(true) => Ok(Command::InstallAutostart { force }),
+        Some(false) => Ok(Command::RemoveAutostart { force }),
+        None if force => Err("--force needs --install-autostart or --remove-autostart".into()),
+        None => Ok(Command::Run(options)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_run_options() {
+        assert_eq!(
+            parse(Vec::<String>::new()).unwrap(),
+            Command::Run(RunOptions::default())
+        );
+        let Command::Run(options) = parse(["-s", "--daemon"]).unwrap() else {
+            panic!("expected a run");
+        };
+        assert!(options.disable_modes && options.daemon);
+        assert!(options.resident());
+        assert!(!options.present());
+    }
+
+    #[test]
+    fn test_parse_scratchpad() {
+        let Command::Run(options) = parse(["--scratchpad"]).unwrap() else {
+            panic!("expected a run");
+        };
+        assert!(options.scratchpad);
+        assert!(options.resident());
+        assert!(!options.present());
+
+        let Command::Run(options) = parse(["--toggle"]).unwrap() else {
+            panic!("expected a run");
+        };
+        assert!(options.resident());
+        assert!(options.present());
+    }
+
+    #[test]
+    fn test_parse_commands_and_errors() {
+        assert_eq!(parse(["--help"]).unwrap(), Command::Help);
+        assert_eq!(parse(["-V"]).unwrap(), Command::Version);
+        assert_eq!(parse(["--list-providers"]).unwrap(), Command::ListProviders);
+        assert_eq!(parse(["--init-config"]).unwrap(), Command::InitConfig);
+        assert_eq!(parse(["--dmenu"]).unwrap(), Command::Dmenu);
+        assert!(parse(["--scratch"]).is_err());
+        assert!(parse(["--force"]).is_err());
+        assert!(parse(["stray"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_autostart() {
+        assert_eq!(
+            parse(["--install-autostart"]).unwrap(),
+            Command::InstallAutostart { force: false }
+        );
+        assert_eq!(
+            parse(["--force", "--install-autostart"]).unwrap(),
+            Command::InstallAutostart { force: true }
+        );
+        assert_eq!(
+            parse(["--remove-autostart", "--force"]).unwrap(),
+            Command::RemoveAutostart { force: true }
+        );
+    }
+}
@@ -0,0 +1,300 @@
+//! Clipboard history for the `:c` mode
+//!
+//! While the window is open, every text copied to the clipboard is added to
+//! `ClipboardHistory`, newest first, and saved as a small JSON file in the
+//! user's cache directory so it survives restarts. Like the usage history it
+//! is only ever read and written locally.
+//!
+//! Capturing is controlled by `[privacy] clipboard_history`; when that is off
+//! nothing is recorded and the saved history is deleted. Passwords are never
+//! recorded: capturing is paused while `:pw` copies one, see
+//! [`pause_capture`], and copies marked with [`PASSWORD_MANAGER_HINT`] are
+//! skipped.
+
+use crate::utils::json_cache;
+use fuzzy_matcher::FuzzyMatcher;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::path::{Path, PathBuf};
+
+/// Number of entries kept; older ones are dropped
+pub const MAX_CLIPBOARD_ENTRIES: usize = 100;
+
+/// Longest text kept per entry, in bytes
+const MAX_ENTRY_BYTES: usize = 64 * 1024;
+
+/// Characters shown in the title and preview of a row
+const PREVIEW_CHARS: usize = 80;
+
+/// Clipboard type password managers such as KeePassXC add to the secrets
+/// they copy, asking clipboard managers not to keep them
+pub const PASSWORD_MANAGER_HINT: &str = "x-kde-passwordManagerHint";
+
+thread_local! {
+    /// Number of live [`CapturePause`] guards
+    static PAUSES: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Keeps clipboard capturing paused until dropped
+#[must_use = "capturing resumes as soon as the guard is dropped"]
+pub struct CapturePause(());
+
+impl Drop for CapturePause {
+    fn drop(&mut self) {
+        PAUSES.with(|p| p.set(p.get().saturating_sub(1)));
+    }
+}
+
+/// Pause capturing until the returned guard is dropped
+///
+/// Held while something else puts a secret on the clipboard, such as
+/// `pass show -c`. Guards nest.
+pub fn pause_capture() -> CapturePause {
+    PAUSES.with(|p| p.set(p.get() + 1));
+    CapturePause(())
+}
+
+/// Whether a [`CapturePause`] is alive
+#[must_use]
+pub fn capture_paused() -> bool {
+    PAUSES.with(|p| p.get() > 0)
+}
+
+/// One copied text
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClipboardEntry {
+    pub text: String,
+    /// Unix time of the last copy, in seconds
+    #[serde(default)]
+    pub copied: u64,
+}
+
+impl ClipboardEntry {
+    /// Row title and description: the first line, and a preview of the rest
+    ///
+    /// Single-line entries have no preview. Both are shortened to a row's
+    /// width, ending in `…`.
+    #[must_use]
+    pub fn title_and_preview(&self) -> (String, String) {
+        let mut lines = self.text.lines().map(str::trim).filter(|l| !l.is_empty());
+        let title = truncate_chars(lines.next().unwrap_or_default(), PREVIEW_CHARS);
+        let rest: Vec<&str> = lines.collect();
+        let preview = if rest.is_empty() {
+            String::new()
+        } else {
+            truncate_chars(&rest.join(" ↵ "), PREVIEW_CHARS)
+        };
+        (title, preview)
+    }
+}
+
+/// Shorten `text` to `max` characters, marking the cut with `…`
+fn truncate_chars(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max.saturating_sub(1)) {
+        Some((end, _)) if text.chars().count() > max => format!("{}…", &text[..end]),
+        _ => text.to_string(),
+    }
+}
+
+/// Copied texts, newest first
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClipboardHistory {
+    #[serde(default)]
+    pub entries: Vec<ClipboardEntry>,
+}
+
+impl ClipboardHistory {
+    /// Load the history from `path`
+    ///
+    /// A missing or unreadable file yields an empty history.
+    #[must_use]
+    pub fn load_from(path: &Path) -> Self {
+        json_cache::load(path, "clipboard history")
+    }
+
+    /// Write the history to `path`
+    ///
+    /// # Errors
+    /// Returns an error if the directory cannot be created or the file
+    /// cannot be written.
+    pub fn save_to(&self, path: &Path) -> std::io::Result<()> {
+        json_cache::store(path, self)
+    }
+
+    /// Write the history to [`clipboard_history_path`], logging failures
+    pub fn save(&self) {
+        let path = clipboard_history_path();
+        debug!("Saving clipboard history to {}", path.display());
+        if let Err(e) = self.save_to(&path) {
+            warn!("Failed to save clipboard history {}: {e}", path.display());
+        }
+    }
+
+    /// Add `text` copied at Unix time `now` as the newest entry
+    ///
+    /// Blank texts and texts over 64 KiB are ignored. Copying a text that
+    /// is already in the history moves it to the front. Returns whether the
+    /// history changed.
+    pub fn push(&mut self, text: &str, now: u64) -> bool {
+        if text.trim().is_empty() || text.len() > MAX_ENTRY_BYTES {
+            return false;
+        }
+        if self.entries.first().is_some_and(|e| e.text == text) {
+            return false;
+        }
+        self.entries.retain(|e| e.text != text);
+        self.entries.insert(
+            0,
+            ClipboardEntry {
+                text: text.to_string(),
+                copied: now,
+            },
+        );
+        self.entries.truncate(MAX_CLIPBOARD_ENTRIES);
+        true
+    }
+
+    /// Entries matching `query`, at most `max`
+    ///
+    /// An empty query lists the newest entries. Otherwise every word has to
+    /// match; better matches come first and equal ones stay newest first.
+    #[must_use]
+    pub fn matches(
+        &self,
+        matcher: &impl FuzzyMatcher,
+        query: &str,
+        max: usize,
+    ) -> Vec<&ClipboardEntry> {
+        let words: Vec<&str> = query.split_whitespace().collect();
+        if words.is_empty() {
+            return self.entries.iter().take(max).collect();
+        }
+        let mut scored: Vec<(i64, &ClipboardEntry)> = self
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                words
+                    .iter()
+                    .map(|word| matcher.fuzzy_match(&entry.text, word))
+                    .sum::<Option<i64>>()
+                    .map(|score| (score, entry))
+            })
+            .collect();
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        scored.into_iter().take(max).map(|(_, e)| e).collect()
+    }
+}
+
+/// Path of the history file: `$HOME/.cache/grunner/clipboard.json`
+#[must_use]
+pub fn clipboard_history_path() -> PathBuf {
+    json_cache::path("clipboard.json")
+}
+
+/// Load the history from [`clipboard_history_path`]
+#[must_use]
+pub fn load_clipboard_history() -> ClipboardHistory {
+    ClipboardHistory::load_from(&clipboard_history_path())
+}
+
+/// Delete the saved history, if any
+pub fn delete_clipboard_history() {
+    let path = clipboard_history_path();
+    match std::fs::remove_file(&path) {
+        Ok(()) => debug!("Deleted clipboard history {}", path.display()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => warn!("Failed to delete clipboard history {}: {e}", path.display()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuzzy_matcher::skim::SkimMatcherV2;
+
+    fn texts(entries: &[&ClipboardEntry]) -> Vec<String> {
+        entries.iter().map(|e| e.text.clone()).collect()
+    }
+
+    #[test]
+    fn test_pause_capture_nests() {
+        assert!(!capture_paused());
+        let outer = pause_capture();
+        let inner = pause_capture();
+        drop(inner);
+        assert!(capture_paused());
+        drop(outer);
+        assert!(!capture_paused());
+    }
+
+    #[test]
+    fn test_push_orders_newest_first_and_dedups() {
+        let mut history = ClipboardHistory::default();
+        assert!(history.push("first", 1));
+        assert!(history.push("second", 2));
+        assert!(!history.push("second", 3));
+        assert!(!history.push("   \n", 4));
+        assert!(history.push("first", 5));
+
+        let order: Vec<&str> = history.entries.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(order, vec!["first", "second"]);
+        assert_eq!(history.entries[0].copied, 5);
+    }
+
+    #[test]
+    fn test_push_caps_history() {
+        let mut history = ClipboardHistory::default();
+        for i in 0..=MAX_CLIPBOARD_ENTRIES {
+            history.push(&format!("entry {i}"), 0);
+        }
+        assert_eq!(history.entries.len(), MAX_CLIPBOARD_ENTRIES);
+        assert_eq!(
+            history.entries[0].text,
+            format!("entry {MAX_CLIPBOARD_ENTRIES}")
+        );
+        assert!(!history.push(&"x".repeat(MAX_ENTRY_BYTES + 1), 0));
+    }
+
+    #[test]
+    fn test_matches() {
+        let matcher = SkimMatcherV2::default();
+        let mut history = ClipboardHistory::default();
+        history.push("git push origin main", 1);
+        history.push("https://example.com/invoice", 2);
+        history.push("ssh deploy@server", 3);
+
+        assert_eq!(
+            texts(&history.matches(&matcher, "", 2)),
+            vec!["ssh deploy@server", "https://example.com/invoice"]
+        );
+        assert_eq!(
+            texts(&history.matches(&matcher, "invoice", 10)),
+            vec!["https://example.com/invoice"]
+        );
+        assert_eq!(
+            texts(&history.matches(&matcher, "git main", 10)),
+            vec!["git push origin main"]
+        );
+        assert!(history.matches(&matcher, "zzz", 10).is_empty());
+    }
+
+    #[test]
+    fn test_title_and_preview() {
+        let entry = |text: &str| ClipboardEntry {
+            text: text.to_string(),
+            copied: 0,
+        };
+        assert_eq!(
+            entry("one line").title_and_preview(),
+            ("one line".to_string(), String::new())
+        );
+        assert_eq!(
+            entry("\n  fn main() {\n    run();\n}\n").title_and_preview(),
+            ("fn main() {".to_string(), "run(); ↵ }".to_string())
+        );
+        let (title, _) = entry(&"é".repeat(PREVIEW_CHARS + 5)).title_and_preview();
+        assert_eq!(title.chars().count(), PREVIEW_CHARS);
+        assert!(title.ends_with('…'));
+    }
+}
@@ -3,7 +3,7 @@
 //! This module extracts command handling logic from the `AppListModel`,
 //! separating concerns between data management and command execution.
 //!
-//! It handles colon-prefixed commands like `:ob`, `:f`, `:sh`, etc.
+//! It handles colon-prefixed commands like `:ob`, `:f`, `:sh`, `:b`, `:c`, `:stats`, `:grunner`, etc.
 //!
 //! ## Design
 //!
@@ -12,9 +12,23 @@
 //! `AppListModel` type, a companion `AppCommandHandler` type alias and extension
 //! trait are provided.
 
-use crate::app_mode::ActiveMode;
-use crate::model::items::CommandItem;
+use crate::actions::obsidian::{VaultSetup, vault_setup};
+use crate::app_mode::AppMode;
+use crate::app_mode::{ActiveMode, ColonCommand};
+use crate::autostart;
+use crate::command_safety::{CommandGuard, command_guard};
+use crate::core::global_state::get_home_dir;
+use crate::history::{LaunchHistory, history_path};
+use crate::model::command_row::CommandRow;
+use crate::model::items::{
+    ColonCommandItem, CommandItem, GrunnerAction, GrunnerActionItem, SuggestionItem, UrlItem,
+    VaultSetupItem,
+};
 use crate::model::list_model::{AppListModel, CommandSink};
+use crate::providers::ranking::suggest_command;
+use crate::providers::subprocess::find_program;
+use crate::providers::web_search;
+use crate::window_switcher;
 
 use log::debug;
 use std::path::{Path, PathBuf};
@@ -28,6 +42,123 @@ pub(crate) fn parse_colon_command(query: &str) -> (&str, &str) {
     }
 }
 
+/// Icon of palette rows whose mode has none, i.e. `:stats` and `:grunner`
+const PALETTE_ICON: &str = "utilities-system-monitor";
+
+/// Colon commands that are modes, i.e. every built-in except `:stats` and
+/// `:grunner`
+///
+/// The names come from the [`ColonCommand`] registry.
+pub(crate) const COLON_COMMANDS: &[&str] = &[
+    "ob", "obg", "f", "fg", "sh", "b", "c", "w", "e", "kill", "proj", "ssh", "unit", "man", "r",
+    "snip", "pw",
+];
+
+/// What the name of a colon command refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ColonTarget<'a> {
+    /// A built-in command
+    Builtin(ColonCommand),
+    /// A `[web_searches]` keyword
+    WebSearch(&'a str),
+    /// Nothing
+    Unknown,
+}
+
+/// Resolve the name of a colon command
+///
+/// Built-in names win over web search keywords; the config drops keywords
+/// that collide with one, so this only matters for a config not loaded
+/// through it.
+pub(crate) fn resolve_colon_command<'a>(
+    cmd: &'a str,
+    is_web_search: impl Fn(&str) -> bool,
+) -> ColonTarget<'a> {
+    if let Some(command) = ColonCommand::from_name(cmd) {
+        ColonTarget::Builtin(command)
+    } else if is_web_search(cmd) {
+        ColonTarget::WebSearch(cmd)
+    } else {
+        ColonTarget::Unknown
+    }
+}
+
+/// Commands the palette lists while `prefix` is typed after the colon
+///
+/// Built-ins come first in registry order, then the `keywords` of
+/// `[web_searches]`, both when their name starts with `prefix`; a bare `:`
+/// lists them all. Keywords named like a built-in are left out, as the
+/// built-in wins.
+pub(crate) fn palette_targets<'a>(
+    prefix: &str,
+    keywords: impl IntoIterator<Item = &'a str>,
+) -> Vec<ColonTarget<'a>> {
+    let builtins = ColonCommand::ALL
+        .into_iter()
+        .filter(|command| command.name().starts_with(prefix))
+        .map(ColonTarget::Builtin);
+    let searches = keywords
+        .into_iter()
+        .filter(|keyword| keyword.starts_with(prefix) && ColonCommand::from_name(keyword).is_none())
+        .map(ColonTarget::WebSearch);
+    builtins.chain(searches).collect()
+}
+
+/// The colon mode a query runs in, if it is one of [`COLON_COMMANDS`]
+///
+/// `":fg todo"` → `Some("fg")`; `":stats"` and plain queries → `None`.
+pub(crate) fn colon_mode(query: &str) -> Option<&'static str> {
+    if !query.starts_with(':') {
+        return None;
+    }
+    let (cmd, _) = parse_colon_command(query);
+    COLON_COMMANDS.iter().copied().find(|c| *c == cmd)
+}
+
+/// Rows of the `:stats` view as `title | description` lines
+///
+/// Nothing from `history` is shown while `usage_stats` is disabled.
+pub(crate) fn stats_lines(history: &LaunchHistory, usage_stats: bool) -> Vec<String> {
+    if !usage_stats {
+        return vec![
+            "Usage statistics are off | Set [privacy] usage_stats = true to record mode usage"
+                .to_string(),
+        ];
+    }
+    let usage = history.mode_usage();
+    if usage.is_empty() {
+        return vec![
+            "No mode usage recorded yet | Counted when a result is activated in a colon mode"
+                .to_string(),
+        ];
+    }
+    usage
+        .into_iter()
+        .map(|(mode, count)| {
+            let noun = if count == 1 {
+                "activation"
+            } else {
+                "activations"
+            };
+            format!(":{mode} | Mode usage · {count} {noun}")
+        })
+        .collect()
+}
+
+/// Correct a mistyped colon command, keeping its argument
+///
+/// Returns the known command and the full query to search instead, e.g.
+/// `("ob", ":ob notes")` for `og` with argument `notes`.
+pub(crate) fn corrected_command_query(cmd: &str, arg: &str) -> Option<(&'static str, String)> {
+    let known = suggest_command(cmd, COLON_COMMANDS)?;
+    let query = if arg.is_empty() {
+        format!(":{known}")
+    } else {
+        format!(":{known} {arg}")
+    };
+    Some((known, query))
+}
+
 /// Command handler generic over any `CommandSink` implementation.
 ///
 /// Provides the `:sh` command handler that works purely through the trait.
@@ -53,17 +184,18 @@ impl<T: CommandSink> CommandHandler<T> {
         debug!("handle_sh: arg='{arg}', commands={}", filtered.len());
 
         for cmd in &filtered {
-            let item_str = format!("{} | {}", cmd.name, cmd.command);
-            self.model.push(&CommandItem::new_with_options(
-                item_str,
+            let item = CommandItem::new_with_options(
+                CommandRow::shell(&cmd.name, &cmd.command),
                 cmd.working_dir.clone(),
                 cmd.keep_open,
-            ));
+            );
+            item.set_guard(command_guard(cmd));
+            self.model.push(&item);
         }
 
         if !arg.is_empty() {
             self.model.push(&CommandItem::new_with_options(
-                format!("Run: {arg}"),
+                CommandRow::run(arg),
                 None,
                 true,
             ));
@@ -72,9 +204,30 @@ impl<T: CommandSink> CommandHandler<T> {
         debug!("Final store count: {}", self.model.count());
     }
 
-    fn show_error(&self, msg: impl Into<String>) {
+    /// Replace the results with the confirmation row of a dangerous command
+    ///
+    /// The row runs the command on the next Enter; typing repopulates the
+    /// list and drops it.
+    pub(crate) fn show_confirmation(&self, item: &CommandItem) {
+        let confirm =
+            CommandItem::new_with_options(item.row(), item.working_dir(), item.keep_open());
+        confirm.set_guard(CommandGuard::Confirmed);
+        self.model.clear();
+        self.model.push(&confirm);
+        self.model.select(0);
+    }
+
+    pub(crate) fn show_error(&self, msg: impl Into<String>) {
+        self.model.clear();
+        self.model.push(&CommandItem::new(CommandRow::message(msg)));
+        self.model.select(0);
+    }
+
+    /// Replace the results with a single "Did you mean …?" row
+    fn show_suggestion(&self, replacement: String, label: String, description: String) {
         self.model.clear();
-        self.model.push(&CommandItem::new(msg.into()));
+        self.model
+            .push(&SuggestionItem::new(replacement, label, description));
         self.model.select(0);
     }
 
@@ -95,29 +248,234 @@ impl AppCommandHandler {
         let (cmd, arg) = parse_colon_command(query);
         debug!("handle_colon_command: query='{query}', cmd='{cmd}', arg='{arg}'");
 
-        match cmd {
-            "ob" | "obg" => self.handle_obsidian(cmd, arg),
-            "f" => self.handle_file_search(arg),
-            "fg" => self.handle_file_grep(arg),
-            "sh" => {
+        let target = {
+            let searches = self.model.config.web_searches.borrow();
+            resolve_colon_command(cmd, |keyword| searches.contains_key(keyword))
+        };
+        match target {
+            ColonTarget::Builtin(
+                command @ (ColonCommand::Obsidian | ColonCommand::ObsidianGrep),
+            ) => self.handle_obsidian(command.name(), arg),
+            ColonTarget::Builtin(ColonCommand::FileSearch) => self.handle_file_search(arg),
+            ColonTarget::Builtin(ColonCommand::FileGrep) => self.handle_file_grep(arg),
+            ColonTarget::Builtin(ColonCommand::Bookmarks) => self.handle_bookmarks(arg),
+            ColonTarget::Builtin(ColonCommand::Clipboard) => self.handle_clipboard(arg),
+            ColonTarget::Builtin(ColonCommand::Windows) => self.handle_windows(arg),
+            ColonTarget::Builtin(ColonCommand::Emoji) => self.handle_emoji(arg),
+            ColonTarget::Builtin(ColonCommand::Kill) => self.handle_kill(arg),
+            ColonTarget::Builtin(ColonCommand::Projects) => self.handle_projects(arg),
+            ColonTarget::Builtin(ColonCommand::Ssh) => self.handle_ssh(arg),
+            ColonTarget::Builtin(ColonCommand::Unit) => self.handle_unit(arg),
+            ColonTarget::Builtin(ColonCommand::Man) => self.handle_man(arg),
+            ColonTarget::Builtin(ColonCommand::Recent) => self.handle_recent(arg),
+            ColonTarget::Builtin(ColonCommand::Snip) => self.handle_snip(arg),
+            ColonTarget::Builtin(ColonCommand::Pass) => self.handle_pass(arg),
+            ColonTarget::Builtin(ColonCommand::Stats) => self.handle_stats(),
+            ColonTarget::Builtin(ColonCommand::Grunner) => self.handle_grunner(arg),
+            ColonTarget::Builtin(ColonCommand::Shell) => {
                 debug!("Calling handle_sh with arg: '{arg}'");
                 // Delegate to the generic method on CommandHandler<T>
                 CommandHandler::handle_sh(self, arg);
             }
-            _ => {
-                if !cmd.is_empty() {
+            ColonTarget::WebSearch(keyword) => self.handle_web_search(keyword, arg),
+            ColonTarget::Unknown => {
+                if !query.contains(' ') && self.show_palette(cmd) {
+                    return;
+                }
+                if let Some((known, query)) = corrected_command_query(cmd, arg) {
+                    self.show_suggestion(
+                        query,
+                        format!(":{known}"),
+                        format!("Unknown command: :{cmd}"),
+                    );
+                } else if !cmd.is_empty() {
                     self.show_error(format!("Unknown command: :{cmd}"));
                 }
             }
         }
     }
 
+    /// List the commands whose name starts with `prefix`, one row each
+    ///
+    /// Returns `false`, showing nothing, if no command does.
+    fn show_palette(&self, prefix: &str) -> bool {
+        let rows: Vec<ColonCommandItem> = {
+            let searches = self.model.config.web_searches.borrow();
+            palette_targets(prefix, searches.keys().map(String::as_str))
+                .into_iter()
+                .filter_map(|target| match target {
+                    ColonTarget::Builtin(command) => Some(ColonCommandItem::new(
+                        command.name(),
+                        command.description().to_string(),
+                        PALETTE_ICON.to_string(),
+                        command.mode(),
+                    )),
+                    ColonTarget::WebSearch(keyword) => {
+                        let search = searches.get(keyword)?;
+                        Some(ColonCommandItem::new(
+                            keyword,
+                            format!("Web search · {}", search.url()),
+                            web_search::icon(search),
+                            AppMode::Normal,
+                        ))
+                    }
+                    ColonTarget::Unknown => None,
+                })
+                .collect()
+        };
+        if rows.is_empty() {
+            return false;
+        }
+        self.clear_store();
+        for row in &rows {
+            self.model.push(row);
+        }
+        self.model.select(0);
+        true
+    }
+
+    /// Handle `:<keyword> <terms>` for a `[web_searches]` keyword
+    ///
+    /// Shows one row opening the search, or nothing until terms are typed.
+    fn handle_web_search(&self, cmd: &str, arg: &str) {
+        let searches = self.model.config.web_searches.borrow();
+        let Some(search) = searches.get(cmd) else {
+            return;
+        };
+        self.clear_store();
+        if !arg.is_empty() {
+            let url = web_search::search_url(search.url(), arg);
+            self.model.push(&UrlItem::web_search(
+                &url,
+                web_search::row_title(search, arg),
+                web_search::icon(search),
+            ));
+            self.model.select(0);
+        }
+    }
+
+    /// Handle `:b` — fuzzy-match browser bookmarks by title and URL
+    fn handle_bookmarks(&self, arg: &str) {
+        self.model.set_mode(ActiveMode::Bookmarks);
+        self.model.show_bookmarks(arg);
+    }
+
+    /// Handle `:c` — fuzzy-filter the clipboard history, newest first
+    fn handle_clipboard(&self, arg: &str) {
+        self.model.set_mode(ActiveMode::Clipboard);
+        self.model.show_clipboard(arg);
+    }
+
+    /// Handle `:w` — fuzzy-filter the open windows
+    fn handle_windows(&self, arg: &str) {
+        self.model.set_mode(ActiveMode::Windows);
+        let Some(backend) = window_switcher::backend() else {
+            self.show_error(
+                ":w needs GNOME Shell with the window-calls extension or a wlroots compositor",
+            );
+            return;
+        };
+        self.model.show_windows(backend, arg);
+    }
+
+    /// Handle `:e` — fuzzy-search emoji, recently used first
+    fn handle_emoji(&self, arg: &str) {
+        self.model.set_mode(ActiveMode::Emoji);
+        self.model.show_emoji(arg);
+    }
+
+    /// Handle `:kill` — fuzzy-filter the current user's processes
+    fn handle_kill(&self, arg: &str) {
+        self.model.set_mode(ActiveMode::Processes);
+        self.model.show_processes(arg);
+    }
+
+    /// Handle `:proj` — fuzzy-match recent projects by folder name
+    fn handle_projects(&self, arg: &str) {
+        self.model.set_mode(ActiveMode::Projects);
+        self.model.show_projects(arg);
+    }
+
+    /// Handle `:ssh` — fuzzy-match SSH hosts by alias or host name
+    fn handle_ssh(&self, arg: &str) {
+        self.model.set_mode(ActiveMode::Ssh);
+        self.model.show_ssh_hosts(arg);
+    }
+
+    /// Handle `:unit` — fuzzy-filter the systemd units
+    fn handle_unit(&self, arg: &str) {
+        self.model.set_mode(ActiveMode::Units);
+        self.model.show_units(arg);
+    }
+
+    /// Handle `:man` — fuzzy-filter the manual pages
+    fn handle_man(&self, arg: &str) {
+        self.model.set_mode(ActiveMode::Manual);
+        self.model.show_man_pages(arg);
+    }
+
+    /// Handle `:r` — fuzzy-filter the recently used documents
+    fn handle_recent(&self, arg: &str) {
+        self.model.set_mode(ActiveMode::Recent);
+        self.model.show_recent_documents(arg);
+    }
+
+    /// Handle `:snip` — fuzzy-match the snippets by name
+    fn handle_snip(&self, arg: &str) {
+        self.model.set_mode(ActiveMode::Snippets);
+        self.model.show_snippets(arg);
+    }
+
+    /// Handle `:pw` — fuzzy-match the password store entries by path
+    fn handle_pass(&self, arg: &str) {
+        self.model.set_mode(ActiveMode::Pass);
+        if find_program("pass").is_none() {
+            self.show_error("pass is not installed: :pw needs the pass password manager");
+            return;
+        }
+        self.model.show_pass_entries(arg);
+    }
+
+    /// Handle `:stats` — show the locally recorded usage counters
+    fn handle_stats(&self) {
+        self.model.set_mode(ActiveMode::Stats);
+        self.clear_store();
+        let history = LaunchHistory::load_from(&history_path());
+        for line in stats_lines(&history, self.model.config.usage_stats.get()) {
+            let (title, description) = line.split_once(" | ").unwrap_or((&line, ""));
+            self.model
+                .push(&CommandItem::new(CommandRow::stats(title, description)));
+        }
+    }
+
+    /// Handle `:grunner` — list the settings of grunner matching `arg`, each
+    /// with its current state
+    fn handle_grunner(&self, arg: &str) {
+        self.clear_store();
+        for action in GrunnerAction::matching(arg) {
+            let on = match action {
+                GrunnerAction::StartAtLogin => {
+                    autostart::is_installed(&autostart::autostart_path())
+                }
+            };
+            self.model.push(&GrunnerActionItem::new(action, on));
+        }
+        self.model.select(0);
+    }
+
     fn handle_obsidian(&self, cmd_name: &str, arg: &str) {
-        let Some(vault_path) = self.validated_vault_path() else {
+        let Some(vault_path) = self.validated_vault_path(cmd_name, arg) else {
             return;
         };
         let vault_str = vault_path.to_string_lossy().into_owned();
 
+        if cmd_name == "ob"
+            && let Some(Err(e)) = self.model.obsidian().map(|ctx| ctx.check_note_paths())
+        {
+            self.show_error(e);
+            return;
+        }
+
         if arg.is_empty() {
             let mode = if cmd_name == "ob" {
                 ActiveMode::ObsidianAction
@@ -137,6 +495,11 @@ impl AppCommandHandler {
 
         let arg = arg.to_string();
         let model = self.model.clone();
+        let exclude = self
+            .model
+            .obsidian()
+            .map(|ctx| ctx.excluded_folders())
+            .unwrap_or_default();
         self.model.set_mode(mode);
         self.model.bump_gen();
 
@@ -146,11 +509,17 @@ impl AppCommandHandler {
                     &model,
                     Path::new(&vault_str),
                     &arg,
+                    &exclude,
                 );
             });
         } else {
             self.model.schedule(move || {
-                crate::providers::file_search::run_rg_in_vault(&model, Path::new(&vault_str), &arg);
+                crate::providers::file_search::run_rg_in_vault(
+                    &model,
+                    Path::new(&vault_str),
+                    &arg,
+                    &exclude,
+                );
             });
         }
     }
@@ -179,21 +548,37 @@ impl AppCommandHandler {
         });
     }
 
-    fn validated_vault_path(&self) -> Option<PathBuf> {
-        use crate::utils::expand_home;
-        let Some(obs_cfg) = self.model.obsidian_config() else {
+    /// The vault path, or `None` after showing why it cannot be used
+    ///
+    /// A missing vault that may be created is shown as a row that creates it
+    /// and then runs `:{cmd_name} {arg}` again.
+    fn validated_vault_path(&self, cmd_name: &str, arg: &str) -> Option<PathBuf> {
+        use crate::utils::contract_home;
+        let Some(obsidian) = self.model.obsidian() else {
             self.show_error("Obsidian not configured - edit config");
             return None;
         };
-        let vault_path = expand_home(&obs_cfg.vault);
-        if !vault_path.exists() {
-            self.show_error(format!(
-                "Vault path does not exist: {}",
-                vault_path.display()
-            ));
-            return None;
+        match vault_setup(&obsidian, Path::new(get_home_dir())) {
+            VaultSetup::Exists(vault_path) => Some(vault_path),
+            VaultSetup::Missing(vault_path) => {
+                let retry_query = if arg.is_empty() {
+                    format!(":{cmd_name}")
+                } else {
+                    format!(":{cmd_name} {arg}")
+                };
+                self.model.clear();
+                self.model.push(&VaultSetupItem::new(
+                    contract_home(&vault_path),
+                    retry_query,
+                ));
+                self.model.select(0);
+                None
+            }
+            VaultSetup::Refused(msg) => {
+                self.show_error(msg);
+                None
+            }
         }
-        Some(vault_path)
     }
 }
 
@@ -245,4 +630,144 @@ mod tests {
     fn test_parse_colon_command_single_char() {
         assert_eq!(parse_colon_command(":x"), ("x", ""));
     }
+
+    #[test]
+    fn test_corrected_command_query() {
+        assert_eq!(
+            corrected_command_query("og", ""),
+            Some(("ob", ":ob".to_string()))
+        );
+        assert_eq!(
+            corrected_command_query("og", "meeting notes"),
+            Some(("ob", ":ob meeting notes".to_string()))
+        );
+        assert_eq!(corrected_command_query("q", "x"), None);
+        assert_eq!(corrected_command_query("xyz", ""), None);
+    }
+
+    #[test]
+    fn test_colon_mode() {
+        assert_eq!(colon_mode(":f notes"), Some("f"));
+        assert_eq!(colon_mode(":fg todo"), Some("fg"));
+        assert_eq!(colon_mode(":obg"), Some("obg"));
+        assert_eq!(colon_mode(":b github"), Some("b"));
+        assert_eq!(colon_mode(":c"), Some("c"));
+        assert_eq!(colon_mode(":w term"), Some("w"));
+        assert_eq!(colon_mode(":e smile"), Some("e"));
+        assert_eq!(colon_mode(":kill firefox"), Some("kill"));
+        assert_eq!(colon_mode(":proj api"), Some("proj"));
+        assert_eq!(colon_mode(":ssh prod"), Some("ssh"));
+        assert_eq!(colon_mode(":unit sshd"), Some("unit"));
+        assert_eq!(colon_mode(":man ls"), Some("man"));
+        assert_eq!(colon_mode(":r report"), Some("r"));
+        assert_eq!(colon_mode(":snip sig"), Some("snip"));
+        assert_eq!(colon_mode(":pw github"), Some("pw"));
+        assert_eq!(colon_mode(":stats"), None);
+        assert_eq!(colon_mode(":og"), None);
+        assert_eq!(colon_mode("firefox"), None);
+        assert_eq!(colon_mode(""), None);
+    }
+
+    #[test]
+    fn test_colon_commands_are_registered() {
+        let modes: Vec<&str> = ColonCommand::ALL
+            .into_iter()
+            .filter(|c| !matches!(c, ColonCommand::Stats | ColonCommand::Grunner))
+            .map(ColonCommand::name)
+            .collect();
+        assert_eq!(COLON_COMMANDS, modes.as_slice());
+    }
+
+    #[test]
+    fn test_resolve_colon_command_precedence() {
+        // (web search keywords, typed command, expected target)
+        let cases: &[(&[&str], &str, ColonTarget)] = &[
+            (&["ob"], "ob", ColonTarget::Builtin(ColonCommand::Obsidian)),
+            (
+                &["s", "sh"],
+                "sh",
+                ColonTarget::Builtin(ColonCommand::Shell),
+            ),
+            (&["s", "sh"], "s", ColonTarget::WebSearch("s")),
+            (&["o", "obg2"], "o", ColonTarget::WebSearch("o")),
+            (&["o", "obg2"], "obg2", ColonTarget::WebSearch("obg2")),
+            (
+                &["o", "obg2"],
+                "obg",
+                ColonTarget::Builtin(ColonCommand::ObsidianGrep),
+            ),
+            (&["o"], "ob", ColonTarget::Builtin(ColonCommand::Obsidian)),
+            (
+                &["stats"],
+                "stats",
+                ColonTarget::Builtin(ColonCommand::Stats),
+            ),
+            (&["fgx"], "fg", ColonTarget::Builtin(ColonCommand::FileGrep)),
+            (&[], "obx", ColonTarget::Unknown),
+            (&[], "", ColonTarget::Unknown),
+        ];
+        for (keywords, cmd, expected) in cases {
+            let target = resolve_colon_command(cmd, |k| keywords.contains(&k));
+            assert_eq!(target, *expected, "{cmd:?} with {keywords:?}");
+
+            // The UI shows a mode exactly for the built-ins the handler runs
+            let query = format!(":{cmd} term");
+            let mode = crate::app_mode::AppMode::from_text(&query);
+            match target {
+                ColonTarget::Builtin(command) => assert_eq!(mode, command.mode()),
+                _ => assert_eq!(mode, crate::app_mode::AppMode::Normal),
+            }
+        }
+    }
+
+    #[test]
+    fn test_palette_targets() {
+        let keywords = ["ddg", "obg", "sx"];
+        let all = palette_targets("", keywords);
+        assert_eq!(all.len(), ColonCommand::ALL.len() + 2);
+        assert_eq!(all[0], ColonTarget::Builtin(ColonCommand::Obsidian));
+        assert_eq!(all[all.len() - 2], ColonTarget::WebSearch("ddg"));
+
+        assert_eq!(
+            palette_targets("s", keywords),
+            [
+                ColonTarget::Builtin(ColonCommand::Shell),
+                ColonTarget::Builtin(ColonCommand::Ssh),
+                ColonTarget::Builtin(ColonCommand::Snip),
+                ColonTarget::Builtin(ColonCommand::Stats),
+                ColonTarget::WebSearch("sx"),
+            ]
+        );
+        // The built-in `obg` wins over the keyword of the same name
+        assert_eq!(
+            palette_targets("ob", keywords),
+            [
+                ColonTarget::Builtin(ColonCommand::Obsidian),
+                ColonTarget::Builtin(ColonCommand::ObsidianGrep),
+            ]
+        );
+        assert!(palette_targets("og", keywords).is_empty());
+    }
+
+    #[test]
+    fn test_stats_lines() {
+        let mut history = LaunchHistory::default();
+        history.record_mode("sh");
+        history.record_mode("f");
+        history.record_mode("f");
+        assert_eq!(
+            stats_lines(&history, true),
+            vec![
+                ":f | Mode usage · 2 activations".to_string(),
+                ":sh | Mode usage · 1 activation".to_string(),
+            ]
+        );
+
+        let disabled = stats_lines(&history, false);
+        assert_eq!(disabled.len(), 1);
+        assert!(disabled[0].starts_with("Usage statistics are off"));
+
+        let empty = stats_lines(&LaunchHistory::default(), true);
+        assert!(empty[0].starts_with("No mode usage recorded yet"));
+    }
 }
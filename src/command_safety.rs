@@ -0,0 +1,409 @@
+//! Guard against destructive `[commands]` entries
+//!
+//! Command snippets are often copied from elsewhere, and one bad template
+//! such as `rm -rf $1` turns a slip into data loss. Commands whose text
+//! contains an obviously destructive operation are flagged when the
+//! configuration is loaded. They never run unless the entry sets
+//! `dangerous = true`, and even then only from a confirmation row that has
+//! to be activated with Enter.
+//!
+//! The check is a short, reviewed list rather than a shell parser: each
+//! `;`, `&` or `|` separated part is looked at on its own, with `sudo`,
+//! `xargs` and similar wrappers skipped together with their options, and the
+//! script of `sh -c` and other shells checked like a command of its own.
+
+use crate::core::config::CommandConfig;
+use log::warn;
+
+/// Programs that run the command after them, skipped to find the program,
+/// with those of their options that take a value in the next word
+///
+/// Options given with their value in the same word (`--user=root`, `-n10`)
+/// are skipped like flags. `env -S` is not listed: its value is the command.
+const WRAPPERS: &[(&str, &[&str])] = &[
+    (
+        "sudo",
+        &[
+            "-u",
+            "--user",
+            "-g",
+            "--group",
+            "-h",
+            "--host",
+            "-p",
+            "--prompt",
+            "-C",
+            "--close-from",
+            "-D",
+            "--chdir",
+            "-r",
+            "--role",
+            "-t",
+            "--type",
+            "-T",
+            "--command-timeout",
+            "-U",
+            "--other-user",
+        ],
+    ),
+    ("doas", &["-u", "-C"]),
+    ("pkexec", &["--user"]),
+    ("exec", &["-a"]),
+    ("env", &["-u", "--unset", "-C", "--chdir"]),
+    ("nice", &["-n", "--adjustment"]),
+    ("nohup", &[]),
+    ("command", &[]),
+    ("time", &["-f", "--format", "-o", "--output"]),
+    (
+        "xargs",
+        &[
+            "-a",
+            "--arg-file",
+            "-d",
+            "--delimiter",
+            "-E",
+            "-I",
+            "-L",
+            "--max-lines",
+            "-n",
+            "--max-args",
+            "-P",
+            "--max-procs",
+            "-s",
+            "--max-chars",
+            "--process-slot-var",
+        ],
+    ),
+];
+
+/// Shells whose `-c` script is checked as a command line of its own
+const SHELLS: &[&str] = &["sh", "bash", "dash", "zsh", "ksh", "fish"];
+
+/// Arguments that make a command harmless when they are its only argument:
+/// it only prints its usage
+const ALLOWED_ARGS: &[&str] = &["--help", "--version"];
+
+/// Device name prefixes of whole disks, which `>` must not write to
+const DISK_DEVICES: &[&str] = &[
+    "/dev/sd",
+    "/dev/hd",
+    "/dev/vd",
+    "/dev/xvd",
+    "/dev/nvme",
+    "/dev/mmcblk",
+];
+
+/// What may happen when a configured command is activated
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CommandGuard {
+    /// Not destructive; runs on activation
+    #[default]
+    Run,
+    /// Destructive and not marked `dangerous`; never runs
+    Blocked(&'static str),
+    /// Destructive and marked `dangerous`; activation asks for confirmation
+    Confirm(&'static str),
+    /// The confirmation row of a dangerous command; activation runs it
+    Confirmed,
+}
+
+impl CommandGuard {
+    /// Whether activating the row runs the command
+    #[must_use]
+    pub fn may_run(self) -> bool {
+        matches!(self, Self::Run | Self::Confirmed)
+    }
+}
+
+/// Guard of a configured command
+#[must_use]
+pub fn command_guard(command: &CommandConfig) -> CommandGuard {
+    match destructive_operation(&command.command) {
+        None => CommandGuard::Run,
+        Some(operation) if command.dangerous => CommandGuard::Confirm(operation),
+        Some(operation) => CommandGuard::Blocked(operation),
+    }
+}
+
+/// Log every destructive command that is not marked `dangerous`
+pub fn warn_destructive_commands(commands: &[CommandConfig]) {
+    for command in commands {
+        if let CommandGuard::Blocked(operation) = command_guard(command) {
+            warn!(
+                "Command '{}' ({}) looks destructive ({operation}) and will not run; \
+                 set dangerous = true to run it after a confirmation",
+                command.name, command.command
+            );
+        }
+    }
+}
+
+/// The destructive operation in `command`, named for messages, or `None`
+///
+/// Flagged are `rm` with both recursive and force flags, `mkfs`, `dd` with
+/// an output file other than `/dev/null`, `shred`, and redirecting output
+/// to a whole disk, also when run through a wrapper such as `sudo -u root`
+/// or `xargs`, or in a `bash -c` script. A program given nothing but
+/// `--help` or `--version` is allowed.
+#[must_use]
+pub fn destructive_operation(command: &str) -> Option<&'static str> {
+    command
+        .split([';', '&', '|', '\n'])
+        .find_map(segment_operation)
+}
+
+/// The destructive operation of one simple command
+fn segment_operation(segment: &str) -> Option<&'static str> {
+    if writes_to_disk(segment) {
+        return Some("> /dev/sd…");
+    }
+    // Quotes are dropped, so `bash -c 'rm -rf x'` reads as `bash -c rm -rf x`
+    let words: Vec<&str> = segment
+        .split_whitespace()
+        .map(|word| word.trim_matches(['\'', '"']))
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    let start = program_start(&words)?;
+    let program = program_name(words[start]);
+    let args = &words[start + 1..];
+    if SHELLS.contains(&program)
+        && let Some(script) = shell_script(args)
+    {
+        return segment_operation(&script.join(" "));
+    }
+    if let [only] = args
+        && ALLOWED_ARGS.contains(only)
+    {
+        return None;
+    }
+    match program {
+        "rm" if is_recursive_force(args) => Some("rm -rf"),
+        "mkfs" => Some("mkfs"),
+        program if program.starts_with("mkfs.") => Some("mkfs"),
+        "dd" if args.iter().any(|arg| {
+            arg.strip_prefix("of=")
+                .is_some_and(|out| out != "/dev/null")
+        }) =>
+        {
+            Some("dd of=")
+        }
+        "shred" => Some("shred"),
+        _ => None,
+    }
+}
+
+/// Index of the word naming the program that `words` runs
+///
+/// Environment assignments are skipped, and so are wrappers with their
+/// options, the values of those listed in [`WRAPPERS`], and a `--` ending
+/// them.
+fn program_start(words: &[&str]) -> Option<usize> {
+    let mut i = 0;
+    while let Some(&word) = words.get(i) {
+        if is_assignment(word) {
+            i += 1;
+            continue;
+        }
+        let name = program_name(word);
+        let Some((_, valued)) = WRAPPERS.iter().find(|(wrapper, _)| *wrapper == name) else {
+            return Some(i);
+        };
+        // A wrapper: the program comes after its options
+        i += 1;
+        while let Some(&option) = words.get(i) {
+            if !option.starts_with('-') || option == "-" {
+                break;
+            }
+            i += 1;
+            if option == "--" {
+                break;
+            }
+            if valued.contains(&option) {
+                i += 1;
+            }
+        }
+    }
+    None
+}
+
+/// File name of a program word such as `/bin/rm`
+fn program_name(word: &str) -> &str {
+    word.rsplit('/').next().unwrap_or(word)
+}
+
+/// The words of the script a shell runs with `-c`, if it was given one
+///
+/// `-c` may be combined with other flags (`bash -ec`); the script is every
+/// word after the options.
+fn shell_script<'a>(args: &'a [&'a str]) -> Option<&'a [&'a str]> {
+    let options = args.iter().take_while(|arg| arg.starts_with('-')).count();
+    args[..options]
+        .iter()
+        .any(|option| !option.starts_with("--") && option.contains('c'))
+        .then(|| &args[options..])
+}
+
+/// Whether `word` is a `NAME=value` environment assignment before a program
+fn is_assignment(word: &str) -> bool {
+    word.split_once('=').is_some_and(|(name, _)| {
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+/// Whether the `rm` arguments ask for both recursion and force
+fn is_recursive_force(args: &[&str]) -> bool {
+    let (mut recursive, mut force) = (false, false);
+    for arg in args {
+        match *arg {
+            "--recursive" => recursive = true,
+            "--force" => force = true,
+            "--" => break,
+            flags if flags.starts_with('-') && !flags.starts_with("--") => {
+                recursive |= flags.contains(['r', 'R']);
+                force |= flags.contains('f');
+            }
+            _ => {}
+        }
+    }
+    recursive && force
+}
+
+/// Whether `segment` redirects output to a whole disk
+fn writes_to_disk(segment: &str) -> bool {
+    segment.match_indices('>').any(|(i, _)| {
+        let target = segment[i + 1..].trim_start_matches('>').trim_start();
+        DISK_DEVICES.iter().any(|device| target.starts_with(device))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(text: &str, dangerous: bool) -> CommandConfig {
+        CommandConfig {
+            name: "test".to_string(),
+            command: text.to_string(),
+            dangerous,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_destructive_operations_are_flagged() {
+        let cases = [
+            ("rm -rf $1", "rm -rf"),
+            ("rm -fr ~/build", "rm -rf"),
+            ("rm -r -f build", "rm -rf"),
+            ("/bin/rm --recursive --force out", "rm -rf"),
+            ("sudo rm -Rf /", "rm -rf"),
+            ("cd ~/src && rm -rf target", "rm -rf"),
+            ("mkfs.ext4 /dev/sdb1", "mkfs"),
+            ("sudo mkfs -t vfat /dev/sdc", "mkfs"),
+            ("dd if=image.iso of=/dev/sdb bs=4M", "dd of="),
+            ("shred -u secrets.txt", "shred"),
+            ("cat image.img > /dev/sda", "> /dev/sd…"),
+            ("echo x >/dev/nvme0n1", "> /dev/sd…"),
+            ("LC_ALL=C sudo shred disk.img", "shred"),
+            ("rm -rf $1 --version", "rm -rf"),
+            ("rm -rf --help", "rm -rf"),
+            ("dd if=/dev/zero of=$1 --help", "dd of="),
+            ("shred --help secrets.txt", "shred"),
+            ("cat --help > /dev/sda", "> /dev/sd…"),
+        ];
+        for (text, operation) in cases {
+            assert_eq!(destructive_operation(text), Some(operation), "{text}");
+        }
+    }
+
+    #[test]
+    fn test_wrapper_options_are_skipped() {
+        let cases = [
+            ("sudo -u root rm -rf /", "rm -rf"),
+            ("sudo --user=root rm -rf /", "rm -rf"),
+            ("sudo -E -u root -g wheel rm -rf /", "rm -rf"),
+            ("nice -n 10 rm -rf ~", "rm -rf"),
+            ("nice -10 rm -rf ~", "rm -rf"),
+            ("env -i rm -rf x", "rm -rf"),
+            ("env -u HOME -C /tmp rm -rf x", "rm -rf"),
+            ("env -S 'rm -rf x'", "rm -rf"),
+            ("sudo -- rm -rf /", "rm -rf"),
+            ("time -p rm -rf x", "rm -rf"),
+            ("time -o /tmp/t rm -rf x", "rm -rf"),
+            ("doas -u root dd if=/dev/zero of=/dev/sda", "dd of="),
+            ("sudo nice -n 5 env -i /usr/bin/shred f", "shred"),
+        ];
+        for (text, operation) in cases {
+            assert_eq!(destructive_operation(text), Some(operation), "{text}");
+        }
+    }
+
+    #[test]
+    fn test_xargs_runs_the_command_after_its_options() {
+        let cases = [
+            "find . -name '*.o' | xargs rm -rf",
+            "xargs -0 rm -rf < list",
+            "xargs -I {} rm -rf {}",
+            "xargs -n 1 -P 4 rm -rf",
+        ];
+        for text in cases {
+            assert_eq!(destructive_operation(text), Some("rm -rf"), "{text}");
+        }
+        assert_eq!(destructive_operation("ls | xargs -n 1 echo"), None);
+    }
+
+    #[test]
+    fn test_shell_scripts_are_checked() {
+        let cases = [
+            ("bash -c 'rm -rf $1'", "rm -rf"),
+            ("sh -c \"mkfs.ext4 /dev/sdb1\"", "mkfs"),
+            ("sudo bash -ec 'shred -u key'", "shred"),
+            ("bash --norc -c 'rm -rf x'", "rm -rf"),
+            ("/bin/sh -c 'cd /tmp && rm -rf x'", "rm -rf"),
+        ];
+        for (text, operation) in cases {
+            assert_eq!(destructive_operation(text), Some(operation), "{text}");
+        }
+        assert_eq!(destructive_operation("bash -c 'echo done'"), None);
+        assert_eq!(destructive_operation("bash ./cleanup.sh"), None);
+    }
+
+    #[test]
+    fn test_harmless_commands_are_allowed() {
+        let cases = [
+            "sudo apt update",
+            "rm -r build",
+            "rm -f stale.lock",
+            "rm -- -rf",
+            "ls -rf",
+            "dd if=/dev/zero of=/dev/null count=1",
+            "echo done > /dev/null",
+            "echo hi > ~/sda.txt",
+            "rm --help",
+            "mkfs --version",
+            "sudo dd --help",
+            "firefox --new-window",
+        ];
+        for text in cases {
+            assert_eq!(destructive_operation(text), None, "{text}");
+        }
+    }
+
+    #[test]
+    fn test_command_guard() {
+        assert_eq!(command_guard(&command("make", false)), CommandGuard::Run);
+        assert_eq!(
+            command_guard(&command("rm -rf $1", false)),
+            CommandGuard::Blocked("rm -rf")
+        );
+        assert_eq!(
+            command_guard(&command("rm -rf $1", true)),
+            CommandGuard::Confirm("rm -rf")
+        );
+        assert!(CommandGuard::Run.may_run());
+        assert!(CommandGuard::Confirmed.may_run());
+        assert!(!CommandGuard::Blocked("shred").may_run());
+        assert!(!CommandGuard::Confirm("shred").may_run());
+    }
+}
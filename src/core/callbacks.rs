@@ -2,7 +2,7 @@
 //!
 //! Provides `AppCallbacks`, a lightweight `GObject` that carries three
 //! notification signals emitted by the settings window after a
-//! successful save:
+//! successful save, and when the config file changes on disk:
 //!
 //! - `config-changed` — model configuration changed
 //! - `theme-changed`  — theme / appearance changed
@@ -11,11 +11,16 @@
 //! - Obsidian vault integration settings
 //! - Search provider filtering
 
+use crate::app_mode::ColonCommand;
+use crate::core::config_include::{INCLUDE_KEY, include_names, resolve_includes};
 use crate::core::global_state::get_home_dir;
-use crate::utils::expand_home;
+use crate::providers::ranking::{MAX_SUGGESTION_DISTANCE, bounded_edit_distance};
+use crate::query_history::DEFAULT_QUERY_HISTORY;
+use crate::utils::{expand_home, path_containment};
 use log::{debug, error, info, warn};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 /// Default window width in pixels
 pub const DEFAULT_WINDOW_WIDTH: i32 = 640;
@@ -25,6 +30,14 @@ pub const DEFAULT_WINDOW_HEIGHT: i32 = 480;
 pub const DEFAULT_MAX_RESULTS: usize = 64;
 /// Default debounce time in milliseconds for command execution
 pub const DEFAULT_COMMAND_DEBOUNCE_MS: u32 = 300;
+/// Default auto-launch score threshold (0 disables auto-launch)
+pub const DEFAULT_AUTO_LAUNCH_THRESHOLD: u8 = 0;
+/// Default time an app search may take before search work is cut
+pub const DEFAULT_LATENCY_BUDGET_MS: u32 = 50;
+/// Highest meaningful auto-launch threshold (match scores are percentages)
+pub const MAX_AUTO_LAUNCH_THRESHOLD: u8 = 100;
+/// Default radius of the window corners in pixels
+pub const DEFAULT_CORNER_RADIUS: u32 = 12;
 
 /// Get the default list of application directories to scan
 ///
@@ -35,13 +48,7 @@ pub const DEFAULT_COMMAND_DEBOUNCE_MS: u32 = 300;
 /// - Flatpak application directories (both system and user)
 #[must_use]
 pub fn default_app_dirs() -> Vec<String> {
-    vec![
-        "/usr/share/applications".into(),
-        "/usr/local/share/applications".into(),
-        "~/.local/share/applications".into(),
-        "/var/lib/flatpak/exports/share/applications".into(),
-        "~/.local/share/flatpak/exports/share/applications".into(),
-    ]
+    crate::utils::data_subdirs("applications")
 }
 
 /// Obsidian-specific configuration
@@ -49,6 +56,7 @@ pub fn default_app_dirs() -> Vec<String> {
 /// This struct holds all settings related to Obsidian integration,
 /// including vault location and note folder paths.
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(deny_unknown_fields)]
 pub struct ObsidianConfig {
     /// Path to the Obsidian vault (supports ~ for home directory)
     pub vault: String,
@@ -58,6 +66,86 @@ pub struct ObsidianConfig {
     pub new_notes_folder: String,
     /// Filename for the quick note file
     pub quick_note: String,
+    /// Allow offering to create a missing vault outside the home directory
+    #[serde(default)]
+    pub allow_vault_outside_home: bool,
+    /// Vault folders skipped by `:obg` and `:ob`, relative to the vault
+    ///
+    /// When unset, the templates folder declared in
+    /// `.obsidian/templates.json` is skipped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grep_exclude_folders: Option<Vec<String>>,
+}
+
+impl ObsidianConfig {
+    /// The settings that name a location inside the vault, by config key
+    #[must_use]
+    pub fn note_paths(&self) -> [(&'static str, &str); 3] {
+        [
+            ("daily_notes_folder", &self.daily_notes_folder),
+            ("new_notes_folder", &self.new_notes_folder),
+            ("quick_note", &self.quick_note),
+        ]
+    }
+
+    /// Folders of `vault` to leave out of `:obg` and `:ob` results
+    ///
+    /// These are `grep_exclude_folders` if set, else the vault's templates
+    /// folder if it declares one. Entries are relative to the vault, without
+    /// leading or trailing slashes; empty entries and entries containing
+    /// `..` are dropped.
+    #[must_use]
+    pub fn excluded_folders(&self, vault: &Path) -> Vec<String> {
+        let folders = match &self.grep_exclude_folders {
+            Some(folders) => folders.clone(),
+            None => templates_folder(vault).into_iter().collect(),
+        };
+        folders
+            .iter()
+            .filter_map(|folder| {
+                let folder = folder.trim().trim_matches('/');
+                let valid = !folder.is_empty() && !folder.split('/').any(|part| part == "..");
+                valid.then(|| folder.to_string())
+            })
+            .collect()
+    }
+
+    /// Warn about note paths that escape the vault
+    ///
+    /// They are kept as written: clearing them would silently write to the
+    /// vault root instead. Every write resolves them again and is refused,
+    /// and `:ob` and its actions show an error row naming the setting.
+    fn warn_escaping_paths(&self) {
+        if self.vault.is_empty() {
+            return; // Nothing to be relative to yet
+        }
+        let vault = expand_home(&self.vault);
+        for (key, value) in self.note_paths() {
+            if let Err(e) = path_containment(&vault, Path::new(value)) {
+                warn!("Invalid obsidian.{key} = {value:?}, its actions are refused: {e}");
+            }
+        }
+    }
+}
+
+/// The templates folder set in the vault's `.obsidian/templates.json`
+///
+/// That file is written by Obsidian's core Templates plugin as
+/// `{"folder": "Templates"}`. A missing or unreadable file, or one without
+/// a folder, yields `None`.
+#[must_use]
+pub fn templates_folder(vault: &Path) -> Option<String> {
+    #[derive(Deserialize)]
+    struct TemplatesJson {
+        folder: Option<String>,
+    }
+
+    let path = vault.join(".obsidian").join("templates.json");
+    let content = std::fs::read_to_string(&path).ok()?;
+    let templates: TemplatesJson = serde_json::from_str(&content)
+        .map_err(|e| debug!("Ignoring {}: {e}", path.display()))
+        .ok()?;
+    templates.folder.filter(|folder| !folder.trim().is_empty())
 }
 
 /// Custom script command configuration
@@ -65,6 +153,7 @@ pub struct ObsidianConfig {
 /// This struct holds a saved command with a name, the command to execute,
 /// optional working directory, and whether to keep the terminal open.
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(deny_unknown_fields)]
 pub struct CommandConfig {
     /// Name displayed in the launcher (e.g., "Update System")
     pub name: String,
@@ -76,6 +165,74 @@ pub struct CommandConfig {
     /// Whether to keep the terminal open after executing the command
     #[serde(default = "default_keep_open")]
     pub keep_open: bool,
+    /// Allow a command that looks destructive (`rm -rf`, `mkfs`, …) to run
+    /// after a confirmation, see [`crate::command_safety`]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dangerous: bool,
+}
+
+/// A `[web_searches]` entry, searched with `:<keyword> <terms>`
+///
+/// Either a bare URL template, `ddg = "https://duckduckgo.com/?q={}"`, or a
+/// table that also names the search and sets the row's icon:
+/// `ddg = { url = "https://duckduckgo.com/?q={}", name = "DuckDuckGo" }`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum WebSearchConfig {
+    /// URL template; `{}` is replaced by the search terms
+    Template(String),
+    /// URL template with an optional display name and icon
+    Full {
+        url: String,
+        /// Name shown in the row (default: the host of `url`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        /// Themed icon name or image path (default: "web-browser")
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        icon: Option<String>,
+    },
+}
+
+/// A `[snippets]` entry, copied with `:snip <name>`
+///
+/// Either the text itself, `sig = "Best regards,\nNihmar"`, or a file
+/// holding it, `reply = { file = "reply.txt" }`. A relative file is taken
+/// in the snippets folder, `~/.config/grunner/snippets`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum SnippetConfig {
+    /// Text of the snippet
+    Text(String),
+    /// File the text is read from each time the snippet is listed
+    File { file: String },
+}
+
+impl WebSearchConfig {
+    /// The URL template
+    #[must_use]
+    pub fn url(&self) -> &str {
+        match self {
+            Self::Template(url) | Self::Full { url, .. } => url,
+        }
+    }
+
+    /// The configured display name, if any
+    #[must_use]
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Self::Template(_) => None,
+            Self::Full { name, .. } => name.as_deref(),
+        }
+    }
+
+    /// The configured icon, if any
+    #[must_use]
+    pub fn icon(&self) -> Option<&str> {
+        match self {
+            Self::Template(_) => None,
+            Self::Full { icon, .. } => icon.as_deref(),
+        }
+    }
 }
 
 /// Theme mode selection
@@ -110,6 +267,166 @@ pub enum ThemeMode {
     Custom,
 }
 
+/// `[search.providers]`: priority and result caps of search providers
+///
+/// Providers are named by the `DesktopId` of their `.ini` file, with or
+/// without the `.desktop` suffix. Hiding a provider altogether is
+/// `[search] provider_blacklist`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProviderSettings {
+    /// Providers whose results come first, in this order; the others follow
+    /// in discovery order
+    pub order: Vec<String>,
+    /// Most results shown per provider; others show `[search] max_results`
+    pub max_results: BTreeMap<String, usize>,
+}
+
+impl ProviderSettings {
+    /// Rank of the provider `desktop_id`, lower first
+    #[must_use]
+    pub fn priority(&self, desktop_id: &str) -> usize {
+        self.order
+            .iter()
+            .position(|id| same_provider(id, desktop_id))
+            .unwrap_or(self.order.len())
+    }
+
+    /// Most results shown for the provider `desktop_id`
+    #[must_use]
+    pub fn max_results(&self, desktop_id: &str, default: usize) -> usize {
+        self.max_results
+            .iter()
+            .find(|(id, _)| same_provider(id, desktop_id))
+            .map_or(default, |(_, &max)| max)
+    }
+}
+
+/// Whether two provider desktop IDs name the same provider
+fn same_provider(a: &str, b: &str) -> bool {
+    a.strip_suffix(".desktop").unwrap_or(a) == b.strip_suffix(".desktop").unwrap_or(b)
+}
+
+/// How file and grep results are laid out
+///
+/// Applies to the `:f`, `:fg`, `:ob` and `:obg` result lists.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FileResultsLayout {
+    /// File name on the first line, folder or matched text on the second
+    #[default]
+    TwoLine,
+    /// `path:line — matched text` in a single ellipsized line
+    SingleLine,
+}
+
+impl FileResultsLayout {
+    /// How many rows fit in the height of one two-line row
+    #[must_use]
+    pub fn rows_per_two_line_row(self) -> u32 {
+        match self {
+            Self::TwoLine => 1,
+            Self::SingleLine => 2,
+        }
+    }
+}
+
+/// Where the launcher sits when it opens as a layer-shell overlay
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LayerAnchor {
+    /// Centered on the output, ignoring the margin
+    #[default]
+    Center,
+    Top,
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl LayerAnchor {
+    /// Whether the window is anchored to the top, bottom, left and right
+    /// edge of the output, in that order
+    ///
+    /// A window anchored to no edge of an axis is centered on it.
+    #[must_use]
+    pub fn edges(self) -> [bool; 4] {
+        match self {
+            Self::Center => [false, false, false, false],
+            Self::Top => [true, false, false, false],
+            Self::Bottom => [false, true, false, false],
+            Self::Left => [false, false, true, false],
+            Self::Right => [false, false, false, true],
+            Self::TopLeft => [true, false, true, false],
+            Self::TopRight => [true, false, false, true],
+            Self::BottomLeft => [false, true, true, false],
+            Self::BottomRight => [false, true, false, true],
+        }
+    }
+}
+
+/// Case sensitivity of fuzzy matching
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FuzzyCase {
+    /// Case-insensitive unless the query contains an uppercase letter
+    #[default]
+    Smart,
+    /// Always case-insensitive
+    Ignore,
+    /// Always case-sensitive
+    Respect,
+}
+
+/// Angle unit of the calculator's trigonometric functions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AngleUnit {
+    #[default]
+    Radians,
+    Degrees,
+}
+
+/// How `:proj` opens a project without a known IDE
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProjectFallback {
+    /// The default file manager
+    #[default]
+    FileManager,
+    /// A shell in the terminal emulator
+    Terminal,
+}
+
+/// Whether a part of the search is cut to keep within the latency budget
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Pin {
+    /// Cut while searches are slow, see [`crate::metrics::LatencyController`]
+    #[default]
+    Auto,
+    /// Never cut
+    On,
+    /// Always cut
+    Off,
+}
+
+impl Pin {
+    /// Whether the part is cut, given whether the controller cuts it
+    #[must_use]
+    pub fn cut(self, auto: bool) -> bool {
+        match self {
+            Self::Auto => auto,
+            Self::On => false,
+            Self::Off => true,
+        }
+    }
+}
+
 fn default_keep_open() -> bool {
     true
 }
@@ -121,10 +438,21 @@ fn default_keep_open() -> bool {
 /// customized via the TOML configuration file.
 #[derive(Debug, Clone, Serialize)]
 pub struct Config {
+    /// Files read before this one, see [`crate::core::config_include`]
+    pub include: Vec<String>,
     /// Window width in pixels
     pub window_width: i32,
     /// Window height in pixels
     pub window_height: i32,
+    /// Whether the window shrinks to its results, up to `window_height`
+    pub auto_height: bool,
+    /// Whether the window opens as a layer-shell overlay where the
+    /// compositor supports it, see [`crate::ui::layer_shell`]
+    pub layer_shell: bool,
+    /// Where the layer-shell overlay sits
+    pub layer_anchor: LayerAnchor,
+    /// Distance in pixels of the overlay from the edges it is anchored to
+    pub layer_margin: i32,
     /// Maximum number of search results to display
     pub max_results: usize,
     /// Directories to scan for .desktop files (raw paths, use `expanded_app_dirs()`)
@@ -135,10 +463,18 @@ pub struct Config {
     pub command_debounce_ms: u32,
     /// List of search provider IDs to exclude from results
     pub search_provider_blacklist: Vec<String>,
+    /// Order and result caps of search providers
+    pub provider_settings: ProviderSettings,
+    /// Desktop IDs or globs (`wine-*`) of applications left out of results
+    pub app_blacklist: Vec<String>,
     /// Whether the workspace window bar is enabled (default: true)
     pub workspace_bar_enabled: bool,
     /// List of custom script commands for :sh mode
     pub commands: Vec<CommandConfig>,
+    /// Web searches by keyword, e.g. `ddg` for `:ddg rust gtk`
+    pub web_searches: BTreeMap<String, WebSearchConfig>,
+    /// Text snippets by name for `:snip`, besides the snippet files
+    pub snippets: BTreeMap<String, SnippetConfig>,
     /// Disable all special modes (colon commands) and hide power bar
     /// Activated via --simple / -s command-line flag or `GRUNNER_SIMPLE` env var
     pub disable_modes: bool,
@@ -148,6 +484,67 @@ pub struct Config {
     pub custom_theme_path: Option<String>,
     /// List of pinned (favorite) application desktop entry IDs
     pub pinned_apps: Vec<String>,
+    /// Match score (0-100) at which the top app is launched automatically
+    /// once typing stops; 0 disables auto-launch
+    pub auto_launch_threshold: u8,
+    /// Case sensitivity of fuzzy matching
+    pub fuzzy_case: FuzzyCase,
+    /// Largest scoring matrix (query × text length) the fuzzy matcher fills;
+    /// longer texts are scored by a faster greedy match. 0 means no limit
+    pub fuzzy_element_limit: usize,
+    /// Whether a query starting with `!` runs the rest in a terminal
+    pub terminal_prefix: bool,
+    /// Whether activating an app that already has a window focuses that
+    /// window instead of starting another instance
+    pub prefer_focus_running: bool,
+    /// Whether copying a calculator result or a clipboard history entry
+    /// closes the window
+    pub close_on_copy: bool,
+    /// Editor command the configuration file is opened in, before
+    /// `$VISUAL` and `$EDITOR`
+    pub settings_editor: Option<String>,
+    /// Whether usage (colon modes, activated results, `:e` emoji) is recorded
+    /// locally
+    pub usage_stats: bool,
+    /// Whether copied texts are kept for the `:c` mode
+    pub clipboard_history: bool,
+    /// Number of queries kept for Alt+Up and Alt+Down, 0 to keep none
+    pub query_history: usize,
+    /// Layout of file and grep results
+    pub file_results_layout: FileResultsLayout,
+    /// Whether new rows fade in and Page Up/Down scroll smoothly
+    pub animations: bool,
+    /// Whether rows end in a badge naming their source ("app", "clip")
+    pub show_kind_badges: bool,
+    /// Whether app descriptions end in where the app was installed from
+    pub show_app_origin: bool,
+    /// Whether Escape clears a non-empty query before closing the window
+    pub escape_clears_first: bool,
+    /// Radius of the window corners in pixels
+    pub corner_radius: u32,
+    /// Opacity of the window background, from 0 (transparent) to 1
+    pub opacity: f64,
+    /// Whether the result count, mode and keys are shown below the results
+    pub show_footer: bool,
+    /// Angle unit of `sin`, `cos`, `tan` and their inverses
+    pub angle_unit: AngleUnit,
+    /// Folders searched for Git repositories by `:proj`
+    pub project_roots: Vec<String>,
+    /// How `:proj` opens a project no IDE has opened
+    pub project_open_with: ProjectFallback,
+    /// Whether `:ssh` also lists the hosts of `~/.ssh/known_hosts`
+    pub ssh_known_hosts: bool,
+    /// Whether `:unit` also lists the units of the user's systemd manager
+    pub units_user: bool,
+    /// Milliseconds an app search may take before search work is cut, 0 to
+    /// never cut
+    pub latency_budget_ms: u32,
+    /// Whether app descriptions are matched
+    pub match_descriptions: Pin,
+    /// Whether all `max_results` app results are listed
+    pub full_results: Pin,
+    /// Whether search providers are queried
+    pub search_providers: Pin,
 }
 
 impl Config {
@@ -172,67 +569,292 @@ impl Default for Config {
     /// - Obsidian configuration is None by default (must be explicitly configured)
     fn default() -> Self {
         Self {
+            include: Vec::new(),
             window_width: DEFAULT_WINDOW_WIDTH,
             window_height: DEFAULT_WINDOW_HEIGHT,
+            auto_height: false,
+            layer_shell: false,
+            layer_anchor: LayerAnchor::default(),
+            layer_margin: 0,
             max_results: DEFAULT_MAX_RESULTS,
             app_dirs: default_app_dirs(),
             obsidian: None,
             command_debounce_ms: DEFAULT_COMMAND_DEBOUNCE_MS,
             search_provider_blacklist: Vec::new(),
+            provider_settings: ProviderSettings::default(),
+            app_blacklist: Vec::new(),
             workspace_bar_enabled: true,
             commands: Vec::new(),
+            web_searches: BTreeMap::new(),
+            snippets: BTreeMap::new(),
             disable_modes: false,
             theme: ThemeMode::default(),
             custom_theme_path: None,
             pinned_apps: Vec::new(),
+            auto_launch_threshold: DEFAULT_AUTO_LAUNCH_THRESHOLD,
+            fuzzy_case: FuzzyCase::default(),
+            fuzzy_element_limit: 0,
+            terminal_prefix: false,
+            prefer_focus_running: false,
+            close_on_copy: true,
+            settings_editor: None,
+            usage_stats: true,
+            clipboard_history: true,
+            query_history: DEFAULT_QUERY_HISTORY,
+            file_results_layout: FileResultsLayout::default(),
+            animations: true,
+            show_kind_badges: false,
+            show_app_origin: false,
+            escape_clears_first: true,
+            corner_radius: DEFAULT_CORNER_RADIUS,
+            opacity: 1.0,
+            show_footer: true,
+            angle_unit: AngleUnit::default(),
+            project_roots: Vec::new(),
+            project_open_with: ProjectFallback::default(),
+            ssh_known_hosts: false,
+            units_user: false,
+            latency_budget_ms: DEFAULT_LATENCY_BUDGET_MS,
+            match_descriptions: Pin::default(),
+            full_results: Pin::default(),
+            search_providers: Pin::default(),
         }
     }
 }
 
 // ── Per-section structs used during TOML parsing ──────────────────────────
+//
+// Every section denies unknown keys so that typos are reported, see
+// `strip_unknown_keys`.
+
+/// Top-level tables of the configuration file
+const SECTIONS: &[&str] = &[
+    "window",
+    "search",
+    "launch",
+    "obsidian",
+    "commands",
+    "web_searches",
+    "snippets",
+    "calculator",
+    "projects",
+    "ssh",
+    "units",
+    "theme",
+    "ui",
+    "privacy",
+    "performance",
+];
 
 #[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
 struct WindowConfig {
     width: Option<i32>,
     height: Option<i32>,
+    auto_height: Option<bool>,
+    layer_shell: Option<bool>,
+    anchor: Option<LayerAnchor>,
+    margin: Option<i32>,
 }
 
 #[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
 struct SearchConfig {
     max_results: Option<usize>,
     app_dirs: Option<Vec<String>>,
     command_debounce_ms: Option<u32>,
     provider_blacklist: Option<Vec<String>>,
+    providers: Option<ProviderSettings>,
+    app_blacklist: Option<Vec<String>>,
     workspace_bar_enabled: Option<bool>,
     pinned_apps: Option<Vec<String>>,
+    auto_launch_threshold: Option<u32>,
+    fuzzy_case: Option<FuzzyCase>,
+    fuzzy_element_limit: Option<usize>,
+    terminal_prefix: Option<bool>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LaunchConfig {
+    prefer_focus_running: Option<bool>,
+    close_on_copy: Option<bool>,
+    settings_editor: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CalculatorConfig {
+    angle_unit: Option<AngleUnit>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProjectsConfig {
+    roots: Option<Vec<String>>,
+    open_with: Option<ProjectFallback>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SshConfig {
+    known_hosts: Option<bool>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UnitsConfig {
+    user: Option<bool>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PerformanceConfig {
+    latency_budget_ms: Option<u32>,
+    match_descriptions: Option<Pin>,
+    full_results: Option<Pin>,
+    search_providers: Option<Pin>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PrivacyConfig {
+    usage_stats: Option<bool>,
+    clipboard_history: Option<bool>,
+    query_history: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UiConfig {
+    file_results_layout: Option<FileResultsLayout>,
+    animations: Option<bool>,
+    show_kind_badges: Option<bool>,
+    show_app_origin: Option<bool>,
+    escape_clears_first: Option<bool>,
+    corner_radius: Option<u32>,
+    opacity: Option<f64>,
+    show_footer: Option<bool>,
 }
 
 #[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
 struct ThemeConfig {
     mode: Option<ThemeMode>,
     custom_theme_path: Option<String>,
 }
 
+/// Environment variable naming the configuration file to use instead of
+/// the default path
+///
+/// A file given this way is treated as read-only: Grunner never writes a
+/// default or corrected configuration to it, which suits configs generated
+/// by Nix/home-manager.
+pub const CONFIG_ENV: &str = "GRUNNER_CONFIG";
+
 /// Get the path to the user's configuration file
 ///
-/// The configuration file is located at:
-/// `$HOME/.config/grunner/grunner.toml`
+/// The configuration file is `$GRUNNER_CONFIG` if set and non-empty, else
+/// `$HOME/.config/grunner/grunner.toml`.
 ///
 /// Returns: `PathBuf` to the configuration file
 #[must_use]
 pub fn config_path() -> PathBuf {
-    let home = get_home_dir();
-    PathBuf::from(home)
-        .join(".config")
-        .join("grunner")
-        .join("grunner.toml")
+    resolve_config_path(std::env::var_os(CONFIG_ENV), get_home_dir())
+}
+
+/// Whether the configuration file was given with [`CONFIG_ENV`]
+#[must_use]
+pub fn config_path_overridden() -> bool {
+    std::env::var_os(CONFIG_ENV).is_some_and(|p| !p.is_empty())
+}
+
+/// Fail if the configuration file was given with [`CONFIG_ENV`]
+///
+/// Every writer of the configuration file calls this first, so a file
+/// managed elsewhere is never overwritten.
+///
+/// # Errors
+/// Returns a `PermissionDenied` error naming the file and the variable.
+pub fn ensure_config_writable() -> std::io::Result<()> {
+    check_config_writable(&config_path(), config_path_overridden())
+}
+
+/// [`ensure_config_writable`] for the configuration file `path`
+fn check_config_writable(path: &Path, overridden: bool) -> std::io::Result<()> {
+    if overridden {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            format!("{} is set by {CONFIG_ENV} and is read-only", path.display()),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// `override_path` if non-empty, else the default path under `home`
+fn resolve_config_path(override_path: Option<std::ffi::OsString>, home: &str) -> PathBuf {
+    match override_path {
+        Some(path) if !path.is_empty() => PathBuf::from(path),
+        _ => PathBuf::from(home)
+            .join(".config")
+            .join("grunner")
+            .join("grunner.toml"),
+    }
+}
+
+/// Write the default configuration file if there is none yet
+///
+/// Used when opening the settings window and by `--init-config`; loading the
+/// configuration never creates the file. Nothing is written when the path
+/// comes from [`CONFIG_ENV`] or its directory is read-only.
+///
+/// Returns the path if a file was written.
+///
+/// # Errors
+/// Returns an error if the directory cannot be created or the file cannot
+/// be written.
+pub fn init_config() -> std::io::Result<Option<PathBuf>> {
+    let path = config_path();
+    let written = write_default_config(&path, config_path_overridden())?;
+    Ok(written.then_some(path))
+}
+
+/// Write [`default_toml`] to `path` unless it exists, is `overridden` or
+/// its nearest existing ancestor directory is read-only
+///
+/// Returns whether the file was written.
+fn write_default_config(path: &Path, overridden: bool) -> std::io::Result<bool> {
+    if overridden || path.exists() {
+        return Ok(false);
+    }
+    let read_only = path
+        .ancestors()
+        .skip(1)
+        .find(|dir| dir.exists())
+        .is_some_and(|dir| rustix::fs::access(dir, rustix::fs::Access::WRITE_OK).is_err());
+    if read_only {
+        info!(
+            "Not creating default configuration: {} is in a read-only directory",
+            path.display()
+        );
+        return Ok(false);
+    }
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, default_toml())?;
+    info!("Created default configuration file at {}", path.display());
+    Ok(true)
 }
 
-/// Load configuration from file or create default configuration
+/// Load configuration from file, or defaults if there is none
 ///
 /// This function:
 /// 1. Checks if a configuration file exists at the expected path
-/// 2. If not, creates the directory and writes a default configuration file
+/// 2. If not, returns the defaults without creating the file (see
+///    [`init_config`])
 /// 3. Reads and parses the TOML configuration file
 /// 4. Merges file settings with defaults (file settings take precedence)
 /// 5. Returns the final configuration
@@ -242,25 +864,11 @@ pub fn config_path() -> PathBuf {
 pub fn load() -> Config {
     let path = config_path();
 
-    // If config file doesn't exist, create it with defaults
     if !path.exists() {
         info!(
-            "Configuration file not found at {}, creating default",
+            "Configuration file not found at {}, using defaults",
             path.display()
         );
-        if let Some(dir) = path.parent()
-            && std::fs::create_dir_all(dir).is_ok()
-        {
-            debug!("Created configuration directory: {}", dir.display());
-        }
-        if std::fs::write(&path, default_toml()).is_ok() {
-            info!("Created default configuration file at {}", path.display());
-        } else {
-            warn!(
-                "Failed to create default configuration file at {}",
-                path.display()
-            );
-        }
         return Config::default();
     }
 
@@ -283,9 +891,21 @@ pub fn load() -> Config {
         }
     };
 
-    // Parse TOML and apply to default configuration
+    // Parse TOML, merge the include files under it and apply the result to
+    // the default configuration
     debug!("Parsing configuration TOML ({} bytes)", content.len());
-    let (cfg, failed, table) = apply_toml(&content);
+    let table = parse_toml(&content);
+    let includes = table.contains_key(INCLUDE_KEY);
+    let merged = if includes {
+        let (merged, warnings) = resolve_includes(table.clone(), &path);
+        for warning in warnings {
+            warn!("{warning}");
+        }
+        merged
+    } else {
+        table.clone()
+    };
+    let (cfg, failed) = apply_table(&merged);
 
     // If sections were malformed, patch only those sections with defaults
     if !failed.is_empty() {
@@ -295,7 +915,12 @@ pub fn load() -> Config {
             failed.join(", ")
         );
         let corrected = patch_failed_sections(table, &failed);
-        if std::fs::write(&path, &corrected).is_ok() {
+        if config_path_overridden() {
+            debug!("Not patching {}: set by {CONFIG_ENV}", path.display());
+        } else if includes {
+            // The section may have come from an include file
+            debug!("Not patching {}: it has includes", path.display());
+        } else if std::fs::write(&path, &corrected).is_ok() {
             info!(
                 "Patched config file replacing sections [{}] with defaults at {}",
                 failed.join(", "),
@@ -307,34 +932,56 @@ pub fn load() -> Config {
     cfg
 }
 
-/// Parse TOML content and apply it to the default configuration
-///
-/// Each top-level section is deserialized independently so that a malformed
-/// section (e.g. legacy `commands = []` instead of `[[commands]]`) does not
-/// prevent the rest of the config from loading.
+/// Parse TOML content and apply it to the default configuration, without
+/// reading include files, see [`apply_table`]
 ///
 /// # Returns
 /// A tuple of `(Config, Vec<String>, toml::value::Table)` where the second
 /// element lists section names that failed to parse, and the third is the
 /// original parsed table (useful for patching).
+#[cfg(test)]
 fn apply_toml(content: &str) -> (Config, Vec<String>, toml::value::Table) {
-    let mut cfg = Config::default();
-    let mut failed: Vec<String> = Vec::new();
+    let table = parse_toml(content);
+    let (cfg, failed) = apply_table(&table);
+    (cfg, failed, table)
+}
 
-    let full: toml::Value = match toml::from_str(content) {
-        Ok(v) => v,
+/// Parse TOML content into a table, or an empty one if it is not valid TOML
+fn parse_toml(content: &str) -> toml::value::Table {
+    match toml::from_str(content) {
+        Ok(table) => table,
         Err(e) => {
             error!("Failed to parse TOML syntax: {e}");
-            return (cfg, failed, toml::value::Table::new());
+            toml::value::Table::new()
         }
-    };
+    }
+}
 
-    let toml::Value::Table(table) = full else {
-        return (cfg, failed, toml::value::Table::new());
-    };
+/// Apply a parsed configuration to the default configuration
+///
+/// Each top-level section is deserialized independently so that a malformed
+/// section (e.g. legacy `commands = []` instead of `[[commands]]`) does not
+/// prevent the rest of the config from loading. Unknown keys are logged as
+/// warnings and skipped, see [`strip_unknown_keys`].
+///
+/// Returns the configuration and the sections that failed to parse.
+fn apply_table(table: &toml::value::Table) -> (Config, Vec<String>) {
+    let mut cfg = Config::default();
+    let mut failed: Vec<String> = Vec::new();
+
+    if let Some(include) = include_names(table) {
+        debug!("Setting include to {include:?}");
+        cfg.include = include;
+    }
+
+    // Strict pass: report unknown keys, then parse the sections without them
+    let mut sections = table.clone();
+    for warning in strip_unknown_keys(&mut sections) {
+        warn!("{warning}");
+    }
 
     // [window]
-    if let Some(val) = table.get("window") {
+    if let Some(val) = sections.get("window") {
         match parse_section::<WindowConfig>(val) {
             Some(window) => {
                 if let Some(w) = window.width.filter(|&v| v > 0) {
@@ -345,13 +992,29 @@ fn apply_toml(content: &str) -> (Config, Vec<String>, toml::value::Table) {
                     debug!("Setting window height to {h}");
                     cfg.window_height = h;
                 }
+                if let Some(auto) = window.auto_height {
+                    debug!("Setting auto_height to {auto}");
+                    cfg.auto_height = auto;
+                }
+                if let Some(enabled) = window.layer_shell {
+                    debug!("Setting layer_shell to {enabled}");
+                    cfg.layer_shell = enabled;
+                }
+                if let Some(anchor) = window.anchor {
+                    debug!("Setting layer anchor to {anchor:?}");
+                    cfg.layer_anchor = anchor;
+                }
+                if let Some(margin) = window.margin.filter(|&v| v >= 0) {
+                    debug!("Setting layer margin to {margin}");
+                    cfg.layer_margin = margin;
+                }
             }
             None => failed.push("window".to_string()),
         }
     }
 
     // [search]
-    if let Some(val) = table.get("search") {
+    if let Some(val) = sections.get("search") {
         match parse_section::<SearchConfig>(val) {
             Some(search) => {
                 if let Some(m) = search.max_results.filter(|&v| v > 0) {
@@ -370,6 +1033,14 @@ fn apply_toml(content: &str) -> (Config, Vec<String>, toml::value::Table) {
                     debug!("Setting search_provider_blacklist to {blacklist:?}");
                     cfg.search_provider_blacklist = blacklist;
                 }
+                if let Some(providers) = search.providers {
+                    debug!("Setting search providers to {providers:?}");
+                    cfg.provider_settings = providers;
+                }
+                if let Some(blacklist) = search.app_blacklist {
+                    debug!("Setting app_blacklist to {blacklist:?}");
+                    cfg.app_blacklist = blacklist;
+                }
                 if let Some(enabled) = search.workspace_bar_enabled {
                     debug!("Setting workspace_bar_enabled to {enabled}");
                     cfg.workspace_bar_enabled = enabled;
@@ -378,16 +1049,62 @@ fn apply_toml(content: &str) -> (Config, Vec<String>, toml::value::Table) {
                     debug!("Setting pinned_apps to {pinned:?}");
                     cfg.pinned_apps = pinned;
                 }
+                if let Some(threshold) = search.auto_launch_threshold {
+                    if threshold > u32::from(MAX_AUTO_LAUNCH_THRESHOLD) {
+                        warn!(
+                            "auto_launch_threshold {threshold} exceeds {MAX_AUTO_LAUNCH_THRESHOLD}, clamping"
+                        );
+                    }
+                    let threshold =
+                        u8::try_from(threshold.min(u32::from(MAX_AUTO_LAUNCH_THRESHOLD)))
+                            .unwrap_or(MAX_AUTO_LAUNCH_THRESHOLD);
+                    debug!("Setting auto_launch_threshold to {threshold}");
+                    cfg.auto_launch_threshold = threshold;
+                }
+                if let Some(case) = search.fuzzy_case {
+                    debug!("Setting fuzzy_case to {case:?}");
+                    cfg.fuzzy_case = case;
+                }
+                if let Some(limit) = search.fuzzy_element_limit {
+                    debug!("Setting fuzzy_element_limit to {limit}");
+                    cfg.fuzzy_element_limit = limit;
+                }
+                if let Some(enabled) = search.terminal_prefix {
+                    debug!("Setting terminal_prefix to {enabled}");
+                    cfg.terminal_prefix = enabled;
+                }
             }
             None => failed.push("search".to_string()),
         }
     }
 
+    // [launch]
+    if let Some(val) = sections.get("launch") {
+        match parse_section::<LaunchConfig>(val) {
+            Some(launch) => {
+                if let Some(enabled) = launch.prefer_focus_running {
+                    debug!("Setting prefer_focus_running to {enabled}");
+                    cfg.prefer_focus_running = enabled;
+                }
+                if let Some(enabled) = launch.close_on_copy {
+                    debug!("Setting close_on_copy to {enabled}");
+                    cfg.close_on_copy = enabled;
+                }
+                if let Some(editor) = launch.settings_editor {
+                    debug!("Setting settings_editor to {editor}");
+                    cfg.settings_editor = Some(editor);
+                }
+            }
+            None => failed.push("launch".to_string()),
+        }
+    }
+
     // [obsidian]
-    if let Some(val) = table.get("obsidian") {
+    if let Some(val) = sections.get("obsidian") {
         match parse_section::<ObsidianConfig>(val) {
             Some(obs) => {
                 debug!("Setting Obsidian configuration");
+                obs.warn_escaping_paths();
                 cfg.obsidian = Some(obs);
             }
             None => failed.push("obsidian".to_string()),
@@ -395,18 +1112,97 @@ fn apply_toml(content: &str) -> (Config, Vec<String>, toml::value::Table) {
     }
 
     // [[commands]]
-    if let Some(val) = table.get("commands") {
+    if let Some(val) = sections.get("commands") {
         match parse_section::<Vec<CommandConfig>>(val) {
             Some(cmds) => {
                 debug!("Setting custom script commands: {} commands", cmds.len());
-                cfg.commands = cmds;
+                cfg.commands = reject_duplicate_commands(cmds);
+                crate::command_safety::warn_destructive_commands(&cfg.commands);
             }
             None => failed.push("commands".to_string()),
         }
     }
 
+    // [web_searches]
+    if let Some(val) = sections.get("web_searches") {
+        match val.as_table() {
+            Some(searches) => {
+                cfg.web_searches = reject_builtin_keywords(parse_web_searches(searches));
+                debug!("Setting {} web searches", cfg.web_searches.len());
+            }
+            None => failed.push("web_searches".to_string()),
+        }
+    }
+
+    // [snippets]
+    if let Some(val) = sections.get("snippets") {
+        match val.as_table() {
+            Some(snippets) => {
+                cfg.snippets = parse_snippets(snippets);
+                debug!("Setting {} snippets", cfg.snippets.len());
+            }
+            None => failed.push("snippets".to_string()),
+        }
+    }
+
+    // [calculator]
+    if let Some(val) = sections.get("calculator") {
+        match parse_section::<CalculatorConfig>(val) {
+            Some(calculator) => {
+                if let Some(unit) = calculator.angle_unit {
+                    debug!("Setting calculator angle unit to {unit:?}");
+                    cfg.angle_unit = unit;
+                }
+            }
+            None => failed.push("calculator".to_string()),
+        }
+    }
+
+    // [projects]
+    if let Some(val) = sections.get("projects") {
+        match parse_section::<ProjectsConfig>(val) {
+            Some(projects) => {
+                if let Some(roots) = projects.roots {
+                    debug!("Setting project roots to {roots:?}");
+                    cfg.project_roots = roots;
+                }
+                if let Some(open_with) = projects.open_with {
+                    debug!("Setting project open_with to {open_with:?}");
+                    cfg.project_open_with = open_with;
+                }
+            }
+            None => failed.push("projects".to_string()),
+        }
+    }
+
+    // [ssh]
+    if let Some(val) = sections.get("ssh") {
+        match parse_section::<SshConfig>(val) {
+            Some(ssh) => {
+                if let Some(enabled) = ssh.known_hosts {
+                    debug!("Setting ssh known_hosts to {enabled}");
+                    cfg.ssh_known_hosts = enabled;
+                }
+            }
+            None => failed.push("ssh".to_string()),
+        }
+    }
+
+    // [units]
+    if let Some(val) = sections.get("units") {
+        match parse_section::<UnitsConfig>(val) {
+            Some(units) => {
+                if let Some(enabled) = units.user {
+                    debug!("Setting units user to {enabled}");
+                    cfg.units_user = enabled;
+                }
+            }
+            None => failed.push("units".to_string()),
+        }
+    }
+
     // [theme]
-    if let Some(val) = table.get("theme") {
+    if let Some(val) = sections.get("theme") {
         match parse_section::<ThemeConfig>(val) {
             Some(theme) => {
                 if let Some(mode) = theme.mode {
@@ -422,21 +1218,375 @@ fn apply_toml(content: &str) -> (Config, Vec<String>, toml::value::Table) {
         }
     }
 
-    (cfg, failed, table)
-}
+    // [ui]
+    if let Some(val) = sections.get("ui") {
+        match parse_section::<UiConfig>(val) {
+            Some(ui) => {
+                if let Some(layout) = ui.file_results_layout {
+                    debug!("Setting file_results_layout to {layout:?}");
+                    cfg.file_results_layout = layout;
+                }
+                if let Some(animations) = ui.animations {
+                    debug!("Setting animations to {animations}");
+                    cfg.animations = animations;
+                }
+                if let Some(show) = ui.show_kind_badges {
+                    debug!("Setting show_kind_badges to {show}");
+                    cfg.show_kind_badges = show;
+                }
+                if let Some(show) = ui.show_app_origin {
+                    debug!("Setting show_app_origin to {show}");
+                    cfg.show_app_origin = show;
+                }
+                if let Some(clears) = ui.escape_clears_first {
+                    debug!("Setting escape_clears_first to {clears}");
+                    cfg.escape_clears_first = clears;
+                }
+                if let Some(radius) = ui.corner_radius {
+                    debug!("Setting corner_radius to {radius}");
+                    cfg.corner_radius = radius;
+                }
+                if let Some(opacity) = ui.opacity {
+                    if !(0.0..=1.0).contains(&opacity) {
+                        warn!("opacity {opacity} is outside 0 to 1, clamping");
+                    }
+                    let opacity = if opacity.is_nan() {
+                        1.0
+                    } else {
+                        opacity.clamp(0.0, 1.0)
+                    };
+                    debug!("Setting opacity to {opacity}");
+                    cfg.opacity = opacity;
+                }
+                if let Some(show) = ui.show_footer {
+                    debug!("Setting show_footer to {show}");
+                    cfg.show_footer = show;
+                }
+            }
+            None => failed.push("ui".to_string()),
+        }
+    }
 
-/// Try to deserialize a `toml::Value` into `T`, logging a warning on failure.
-fn parse_section<T: serde::de::DeserializeOwned>(val: &toml::Value) -> Option<T> {
-    match val.clone().try_into::<T>() {
-        Ok(v) => Some(v),
-        Err(e) => {
-            warn!("Failed to parse config section: {e}");
-            None
+    // [privacy]
+    if let Some(val) = sections.get("privacy") {
+        match parse_section::<PrivacyConfig>(val) {
+            Some(privacy) => {
+                if let Some(enabled) = privacy.usage_stats {
+                    debug!("Setting usage_stats to {enabled}");
+                    cfg.usage_stats = enabled;
+                }
+                if let Some(enabled) = privacy.clipboard_history {
+                    debug!("Setting clipboard_history to {enabled}");
+                    cfg.clipboard_history = enabled;
+                }
+                if let Some(length) = privacy.query_history {
+                    debug!("Setting query_history to {length}");
+                    cfg.query_history = length;
+                }
+            }
+            None => failed.push("privacy".to_string()),
         }
     }
-}
 
-/// Replace only the given failed sections in the original TOML table with
+    // [performance]
+    if let Some(val) = sections.get("performance") {
+        match parse_section::<PerformanceConfig>(val) {
+            Some(performance) => {
+                if let Some(ms) = performance.latency_budget_ms {
+                    debug!("Setting latency budget to {ms} ms");
+                    cfg.latency_budget_ms = ms;
+                }
+                if let Some(pin) = performance.match_descriptions {
+                    debug!("Setting match_descriptions to {pin:?}");
+                    cfg.match_descriptions = pin;
+                }
+                if let Some(pin) = performance.full_results {
+                    debug!("Setting full_results to {pin:?}");
+                    cfg.full_results = pin;
+                }
+                if let Some(pin) = performance.search_providers {
+                    debug!("Setting search_providers to {pin:?}");
+                    cfg.search_providers = pin;
+                }
+            }
+            None => failed.push("performance".to_string()),
+        }
+    }
+
+    (cfg, failed)
+}
+
+/// Drop commands whose name is already used by an earlier command
+///
+/// Commands keep the order of their `[[commands]]` entries. The first entry
+/// with a given name wins and every later one is skipped with a warning.
+fn reject_duplicate_commands(commands: Vec<CommandConfig>) -> Vec<CommandConfig> {
+    let mut seen = std::collections::HashSet::new();
+    commands
+        .into_iter()
+        .filter(|cmd| {
+            let first = seen.insert(cmd.name.clone());
+            if !first {
+                warn!(
+                    "Ignoring duplicate command '{}' ({}): the name is already used",
+                    cmd.name, cmd.command
+                );
+            }
+            first
+        })
+        .collect()
+}
+
+/// Drop web searches whose keyword is a built-in colon command
+///
+/// Built-in commands always win (see [`ColonCommand`]), so such a search
+/// could never run. The collisions are listed in one warning.
+fn reject_builtin_keywords(
+    mut searches: BTreeMap<String, WebSearchConfig>,
+) -> BTreeMap<String, WebSearchConfig> {
+    let collisions: Vec<String> = searches
+        .keys()
+        .filter(|keyword| ColonCommand::from_name(keyword).is_some())
+        .map(|keyword| format!(":{keyword}"))
+        .collect();
+    if !collisions.is_empty() {
+        warn!(
+            "Ignoring web searches shadowed by built-in commands: {}",
+            collisions.join(", ")
+        );
+        searches.retain(|keyword, _| ColonCommand::from_name(keyword).is_none());
+    }
+    searches
+}
+
+/// Parse the `[web_searches]` entries one by one
+///
+/// An entry that is neither a URL template nor a table with a `url` is
+/// skipped with a warning, keeping the other searches.
+fn parse_web_searches(table: &toml::value::Table) -> BTreeMap<String, WebSearchConfig> {
+    table
+        .iter()
+        .filter_map(
+            |(keyword, val)| match parse_section::<WebSearchConfig>(val) {
+                Some(search) if !search.url().trim().is_empty() => Some((keyword.clone(), search)),
+                _ => {
+                    warn!("Ignoring web search '{keyword}': expected a URL template");
+                    None
+                }
+            },
+        )
+        .collect()
+}
+
+/// Parse the `[snippets]` entries one by one
+///
+/// An entry that is neither a text nor a table with a `file` is skipped
+/// with a warning, keeping the other snippets.
+fn parse_snippets(table: &toml::value::Table) -> BTreeMap<String, SnippetConfig> {
+    table
+        .iter()
+        .filter_map(|(name, val)| match parse_section::<SnippetConfig>(val) {
+            Some(snippet) => Some((name.clone(), snippet)),
+            None => {
+                warn!("Ignoring snippet '{name}': expected a text or {{ file = \"…\" }}");
+                None
+            }
+        })
+        .collect()
+}
+
+/// Remove the keys that no section knows from `table`, returning a warning
+/// for each
+///
+/// This is the strict pass of the parsing: every section struct denies
+/// unknown keys, so a typo such as `max_result` is an error naming the key
+/// and the valid ones. The key is reported with the closest valid key and
+/// dropped until the section parses or fails for another reason, such as a
+/// wrong type, which is left to [`apply_table`]. Unknown sections are only
+/// reported, since nothing reads them.
+fn strip_unknown_keys(table: &mut toml::value::Table) -> Vec<String> {
+    let mut warnings = Vec::new();
+    for (section, val) in table.iter_mut() {
+        match section.as_str() {
+            "window" => strip_section::<WindowConfig>(section, val, &mut warnings),
+            "search" => strip_section::<SearchConfig>(section, val, &mut warnings),
+            "launch" => strip_section::<LaunchConfig>(section, val, &mut warnings),
+            "obsidian" => strip_section::<ObsidianConfig>(section, val, &mut warnings),
+            "commands" => strip_section::<Vec<CommandConfig>>(section, val, &mut warnings),
+            "calculator" => strip_section::<CalculatorConfig>(section, val, &mut warnings),
+            "projects" => strip_section::<ProjectsConfig>(section, val, &mut warnings),
+            "ssh" => strip_section::<SshConfig>(section, val, &mut warnings),
+            "units" => strip_section::<UnitsConfig>(section, val, &mut warnings),
+            "theme" => strip_section::<ThemeConfig>(section, val, &mut warnings),
+            "ui" => strip_section::<UiConfig>(section, val, &mut warnings),
+            "privacy" => strip_section::<PrivacyConfig>(section, val, &mut warnings),
+            "performance" => strip_section::<PerformanceConfig>(section, val, &mut warnings),
+            // Keys of [web_searches] are keywords, any name is valid
+            "web_searches" => {}
+            // Keys of [snippets] are snippet names
+            "snippets" => {}
+            // Read by `config_include`, which warns about bad values
+            INCLUDE_KEY => {}
+            _ if val.is_table() => warnings.push(format!(
+                "Unknown section [{section}] is ignored{}",
+                did_you_mean(section, SECTIONS, "[", "]")
+            )),
+            _ => warnings.push(format!(
+                "Unknown key `{section}` outside any section is ignored"
+            )),
+        }
+    }
+    warnings
+}
+
+/// Drop the unknown keys of one section as deserialized into `T`
+fn strip_section<T: serde::de::DeserializeOwned>(
+    section: &str,
+    val: &mut toml::Value,
+    warnings: &mut Vec<String>,
+) {
+    loop {
+        let Err(e) = val.clone().try_into::<T>() else {
+            return;
+        };
+        let Some((key, expected)) = unknown_field(e.message()) else {
+            return;
+        };
+        let Some(path) = remove_key(val, section, &key, &expected) else {
+            return;
+        };
+        let expected: Vec<&str> = expected.iter().map(String::as_str).collect();
+        warnings.push(format!(
+            "Unknown key `{key}` in [{path}] is ignored{}",
+            did_you_mean(&key, &expected, "`", "`")
+        ));
+    }
+}
+
+/// Key and valid keys of serde's "unknown field" error message
+///
+/// The message reads ``unknown field `key`, expected one of `a`, `b` ``,
+/// ``expected `a` `` or ``there are no fields``.
+fn unknown_field(message: &str) -> Option<(String, Vec<String>)> {
+    let rest = message.strip_prefix("unknown field `")?;
+    let (key, rest) = rest.split_once('`')?;
+    let expected = rest
+        .split('`')
+        .skip(1)
+        .step_by(2)
+        .map(String::from)
+        .collect();
+    Some((key.to_string(), expected))
+}
+
+/// Remove `key` from the table of `val` it was rejected in, returning the
+/// dotted path of that table
+///
+/// Serde's error does not say where the key is, so nested tables such as
+/// `[search.providers]` and `[[commands]]` entries are searched too,
+/// preferring a table whose other keys are all `expected`.
+fn remove_key(
+    val: &mut toml::Value,
+    section: &str,
+    key: &str,
+    expected: &[String],
+) -> Option<String> {
+    let mut steps = Vec::new();
+    let found =
+        find_key(val, key, Some(expected), &mut steps) || find_key(val, key, None, &mut steps);
+    if !found {
+        return None;
+    }
+    let mut path = vec![section];
+    let mut table = &mut *val;
+    for step in &steps {
+        table = match step {
+            KeyStep::Key(name) => {
+                path.push(name);
+                table.get_mut(name.as_str())?
+            }
+            KeyStep::Index(index) => table.get_mut(*index)?,
+        };
+    }
+    table.as_table_mut()?.remove(key)?;
+    Some(path.join("."))
+}
+
+/// A step from a section value to one of its nested values
+enum KeyStep {
+    Key(String),
+    Index(usize),
+}
+
+/// Depth-first search for a table holding `key`, and with `expected`, only
+/// other keys in `expected`; `steps` is left on the path to it
+fn find_key(
+    val: &toml::Value,
+    key: &str,
+    expected: Option<&[String]>,
+    steps: &mut Vec<KeyStep>,
+) -> bool {
+    match val {
+        toml::Value::Table(table) => {
+            let fits = table.contains_key(key)
+                && expected.is_none_or(|expected| {
+                    table
+                        .keys()
+                        .all(|other| other == key || expected.contains(other))
+                });
+            if fits {
+                return true;
+            }
+            for (name, child) in table {
+                steps.push(KeyStep::Key(name.clone()));
+                if find_key(child, key, expected, steps) {
+                    return true;
+                }
+                steps.pop();
+            }
+            false
+        }
+        toml::Value::Array(items) => {
+            for (index, child) in items.iter().enumerate() {
+                steps.push(KeyStep::Index(index));
+                if find_key(child, key, expected, steps) {
+                    return true;
+                }
+                steps.pop();
+            }
+            false
+        }
+        _ => false,
+    }
+}
+
+/// `", did you mean <open>name<close>?"` for the candidate closest to
+/// `key`, or nothing when none is close
+fn did_you_mean(key: &str, candidates: &[&str], open: &str, close: &str) -> String {
+    candidates
+        .iter()
+        .filter_map(|candidate| {
+            bounded_edit_distance(key, candidate, MAX_SUGGESTION_DISTANCE)
+                .map(|distance| (distance, *candidate))
+        })
+        .min_by_key(|(distance, _)| *distance)
+        .map_or_else(String::new, |(_, candidate)| {
+            format!(", did you mean {open}{candidate}{close}?")
+        })
+}
+
+/// Try to deserialize a `toml::Value` into `T`, logging a warning on failure.
+fn parse_section<T: serde::de::DeserializeOwned>(val: &toml::Value) -> Option<T> {
+    match val.clone().try_into::<T>() {
+        Ok(v) => Some(v),
+        Err(e) => {
+            warn!("Failed to parse config section: {e}");
+            None
+        }
+    }
+}
+
+/// Replace only the given failed sections in the original TOML table with
 /// their defaults, then re-serialize.  Sections that parsed correctly are
 /// left untouched so their values, ordering, and any surrounding content
 /// are preserved.
@@ -475,16 +1625,33 @@ fn patch_failed_sections(mut table: toml::value::Table, failed: &[String]) -> St
 pub fn config_to_toml(config: &Config) -> String {
     #[derive(Serialize)]
     struct TomlConfig<'a> {
+        // Keys outside a table must come before the first one
+        #[serde(skip_serializing_if = "<[String]>::is_empty")]
+        include: &'a [String],
         window: SerWindow,
         search: SerSearch<'a>,
+        launch: SerLaunch,
         obsidian: Option<&'a ObsidianConfig>,
         commands: &'a [CommandConfig],
+        web_searches: &'a BTreeMap<String, WebSearchConfig>,
+        snippets: &'a BTreeMap<String, SnippetConfig>,
+        calculator: SerCalculator,
+        projects: SerProjects<'a>,
+        ssh: SerSsh,
+        units: SerUnits,
         theme: SerTheme,
+        ui: SerUi,
+        privacy: SerPrivacy,
+        performance: SerPerformance,
     }
     #[derive(Serialize)]
     struct SerWindow {
         width: i32,
         height: i32,
+        auto_height: bool,
+        layer_shell: bool,
+        anchor: LayerAnchor,
+        margin: i32,
     }
     #[derive(Serialize)]
     struct SerSearch<'a> {
@@ -492,39 +1659,358 @@ pub fn config_to_toml(config: &Config) -> String {
         app_dirs: &'a [String],
         command_debounce_ms: u32,
         provider_blacklist: &'a [String],
+        app_blacklist: &'a [String],
         workspace_bar_enabled: bool,
         pinned_apps: &'a [String],
+        auto_launch_threshold: u8,
+        fuzzy_case: FuzzyCase,
+        fuzzy_element_limit: usize,
+        terminal_prefix: bool,
+        providers: &'a ProviderSettings,
+    }
+    #[derive(Serialize)]
+    struct SerLaunch {
+        prefer_focus_running: bool,
+        close_on_copy: bool,
+        settings_editor: Option<String>,
+    }
+    #[derive(Serialize)]
+    struct SerCalculator {
+        angle_unit: AngleUnit,
+    }
+    #[derive(Serialize)]
+    struct SerProjects<'a> {
+        roots: &'a [String],
+        open_with: ProjectFallback,
+    }
+    #[derive(Serialize)]
+    struct SerSsh {
+        known_hosts: bool,
+    }
+    #[derive(Serialize)]
+    struct SerUnits {
+        user: bool,
     }
     #[derive(Serialize)]
     struct SerTheme {
         mode: ThemeMode,
         custom_theme_path: Option<String>,
     }
+    #[derive(Serialize)]
+    struct SerUi {
+        file_results_layout: FileResultsLayout,
+        animations: bool,
+        show_kind_badges: bool,
+        show_app_origin: bool,
+        escape_clears_first: bool,
+        corner_radius: u32,
+        opacity: f64,
+        show_footer: bool,
+    }
+    #[derive(Serialize)]
+    struct SerPrivacy {
+        usage_stats: bool,
+        clipboard_history: bool,
+        query_history: usize,
+    }
+    #[derive(Serialize)]
+    struct SerPerformance {
+        latency_budget_ms: u32,
+        match_descriptions: Pin,
+        full_results: Pin,
+        search_providers: Pin,
+    }
 
     let tc = TomlConfig {
+        include: &config.include,
         window: SerWindow {
             width: config.window_width,
             height: config.window_height,
+            auto_height: config.auto_height,
+            layer_shell: config.layer_shell,
+            anchor: config.layer_anchor,
+            margin: config.layer_margin,
         },
         search: SerSearch {
             max_results: config.max_results,
             app_dirs: &config.app_dirs,
             command_debounce_ms: config.command_debounce_ms,
             provider_blacklist: &config.search_provider_blacklist,
+            app_blacklist: &config.app_blacklist,
             workspace_bar_enabled: config.workspace_bar_enabled,
             pinned_apps: &config.pinned_apps,
+            auto_launch_threshold: config.auto_launch_threshold,
+            fuzzy_case: config.fuzzy_case,
+            fuzzy_element_limit: config.fuzzy_element_limit,
+            terminal_prefix: config.terminal_prefix,
+            providers: &config.provider_settings,
+        },
+        launch: SerLaunch {
+            prefer_focus_running: config.prefer_focus_running,
+            close_on_copy: config.close_on_copy,
+            settings_editor: config.settings_editor.clone(),
         },
         obsidian: config.obsidian.as_ref(),
         commands: &config.commands,
+        web_searches: &config.web_searches,
+        snippets: &config.snippets,
+        calculator: SerCalculator {
+            angle_unit: config.angle_unit,
+        },
+        projects: SerProjects {
+            roots: &config.project_roots,
+            open_with: config.project_open_with,
+        },
+        ssh: SerSsh {
+            known_hosts: config.ssh_known_hosts,
+        },
+        units: SerUnits {
+            user: config.units_user,
+        },
         theme: SerTheme {
             mode: config.theme,
             custom_theme_path: config.custom_theme_path.clone(),
         },
+        ui: SerUi {
+            file_results_layout: config.file_results_layout,
+            animations: config.animations,
+            show_kind_badges: config.show_kind_badges,
+            show_app_origin: config.show_app_origin,
+            escape_clears_first: config.escape_clears_first,
+            corner_radius: config.corner_radius,
+            opacity: config.opacity,
+            show_footer: config.show_footer,
+        },
+        privacy: SerPrivacy {
+            usage_stats: config.usage_stats,
+            clipboard_history: config.clipboard_history,
+            query_history: config.query_history,
+        },
+        performance: SerPerformance {
+            latency_budget_ms: config.latency_budget_ms,
+            match_descriptions: config.match_descriptions,
+            full_results: config.full_results,
+            search_providers: config.search_providers,
+        },
     };
 
     toml::to_string_pretty(&tc).expect("config serialization should never fail")
 }
 
+/// The contents to save to the config file at `path` for `config`
+///
+/// Without includes this is [`config_to_toml`]. With includes, `config`
+/// holds the values of the include files too, and writing them all would
+/// copy them into this file, where they would override the shared files
+/// from then on. Only the values that differ from what the include files
+/// give, and those the file at `path` already sets, are written.
+#[must_use]
+pub fn config_to_own_toml(config: &Config, path: &Path) -> String {
+    if config.include.is_empty() {
+        return config_to_toml(config);
+    }
+    let own = std::fs::read_to_string(path)
+        .map(|content| parse_toml(&content))
+        .unwrap_or_default();
+
+    // What the include files alone give, as the settings would save it
+    let mut includes = toml::value::Table::new();
+    includes.insert(INCLUDE_KEY.to_string(), config.include.clone().into());
+    let (base, _) = resolve_includes(includes, path);
+    let (base, _) = apply_table(&base);
+    let base = parse_toml(&config_to_toml(&base));
+
+    let mut kept = own_values(parse_toml(&config_to_toml(config)), &base, &own);
+    kept.insert(INCLUDE_KEY.to_string(), config.include.clone().into());
+    toml::to_string(&kept).unwrap_or_else(|e| {
+        error!("Failed to serialize the configuration: {e}");
+        config_to_toml(config)
+    })
+}
+
+/// The top-level keys of `full` to write over the include files giving
+/// `base`, given the keys `own` already sets
+///
+/// Compared like [`crate::core::config_include::merge_config`] merges:
+/// sections key by key, commands by name, and snippets and web searches
+/// entry by entry.
+fn own_values(
+    full: toml::value::Table,
+    base: &toml::value::Table,
+    own: &toml::value::Table,
+) -> toml::value::Table {
+    let no_keys = toml::value::Table::new();
+    let mut kept = toml::value::Table::new();
+    for (key, val) in full {
+        let own_val = own.get(&key);
+        let own_table = own_val.and_then(toml::Value::as_table).unwrap_or(&no_keys);
+        let val = match (key.as_str(), val, base.get(&key)) {
+            ("commands", toml::Value::Array(commands), Some(toml::Value::Array(included))) => {
+                let own_names: Vec<&toml::Value> = own_val
+                    .and_then(toml::Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|command| command.get("name"))
+                    .collect();
+                let commands: Vec<toml::Value> = commands
+                    .into_iter()
+                    .filter(|command| {
+                        !included.contains(command)
+                            || command
+                                .get("name")
+                                .is_some_and(|name| own_names.contains(&name))
+                    })
+                    .collect();
+                if commands.is_empty() {
+                    continue;
+                }
+                toml::Value::Array(commands)
+            }
+            (
+                "snippets" | "web_searches",
+                toml::Value::Table(entries),
+                Some(toml::Value::Table(included)),
+            ) => {
+                let entries: toml::value::Table = entries
+                    .into_iter()
+                    .filter(|(name, entry)| {
+                        included.get(name) != Some(entry) || own_table.contains_key(name)
+                    })
+                    .collect();
+                if entries.is_empty() && own_val.is_none() {
+                    continue;
+                }
+                toml::Value::Table(entries)
+            }
+            (_, toml::Value::Table(section), Some(toml::Value::Table(included))) => {
+                let section = own_section(section, included, own_table);
+                if section.is_empty() && own_val.is_none() {
+                    continue;
+                }
+                toml::Value::Table(section)
+            }
+            (_, val, included) => {
+                if included == Some(&val) && own_val.is_none() {
+                    continue;
+                }
+                val
+            }
+        };
+        kept.insert(key, val);
+    }
+    kept
+}
+
+/// The keys of a `section` to write over the `included` one, given those of
+/// `own`, nested tables compared key by key
+fn own_section(
+    section: toml::value::Table,
+    included: &toml::value::Table,
+    own: &toml::value::Table,
+) -> toml::value::Table {
+    let no_keys = toml::value::Table::new();
+    let mut kept = toml::value::Table::new();
+    for (key, val) in section {
+        let own_val = own.get(&key);
+        match (val, included.get(&key)) {
+            (toml::Value::Table(nested), Some(toml::Value::Table(included))) => {
+                let own_nested = own_val.and_then(toml::Value::as_table).unwrap_or(&no_keys);
+                let nested = own_section(nested, included, own_nested);
+                if !nested.is_empty() || own_val.is_some() {
+                    kept.insert(key, toml::Value::Table(nested));
+                }
+            }
+            (val, included) => {
+                if included != Some(&val) || own_val.is_some() {
+                    kept.insert(key, val);
+                }
+            }
+        }
+    }
+    kept
+}
+
+/// Add a search provider to `search.provider_blacklist` in the config file
+///
+/// Only the blacklist entry is touched; every other key in the file keeps
+/// its current value. If the file does not exist yet it is created from the
+/// default template first.
+///
+/// # Arguments
+/// * `desktop_id` - Desktop ID of the provider, as in its `.ini` file
+///
+/// # Errors
+/// Returns an error if the file is set by [`CONFIG_ENV`], or cannot be read,
+/// parsed, or written.
+pub fn blacklist_provider(desktop_id: &str) -> Result<(), std::io::Error> {
+    add_to_search_list_file("provider_blacklist", desktop_id)
+}
+
+/// Add an application to `search.app_blacklist` in the config file
+///
+/// Like [`blacklist_provider`], only the blacklist entry is touched.
+///
+/// # Arguments
+/// * `desktop_id` - Desktop ID of the application, with its `.desktop` suffix
+///
+/// # Errors
+/// Returns an error if the file is set by [`CONFIG_ENV`], or cannot be read,
+/// parsed, or written.
+pub fn blacklist_app(desktop_id: &str) -> Result<(), std::io::Error> {
+    add_to_search_list_file("app_blacklist", desktop_id)
+}
+
+/// Append `value` to the `search.<key>` array of the config file
+fn add_to_search_list_file(key: &str, value: &str) -> Result<(), std::io::Error> {
+    ensure_config_writable()?;
+    let path = config_path();
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => default_toml(),
+        Err(e) => return Err(e),
+    };
+
+    let updated = add_to_search_list(&content, key, value)?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, updated)?;
+    info!("Added {value} to {key}");
+    Ok(())
+}
+
+/// Append `value` to the `search.<key>` array of a TOML document
+///
+/// Creates the `[search]` table and the array if they are missing, and leaves
+/// the document unchanged if the value is already listed. Comments, key
+/// order and formatting of everything else are kept.
+fn add_to_search_list(content: &str, key: &str, value: &str) -> Result<String, std::io::Error> {
+    let invalid = |msg: String| std::io::Error::new(std::io::ErrorKind::InvalidData, msg);
+
+    let mut doc: toml_edit::DocumentMut = content
+        .parse()
+        .map_err(|e| invalid(format!("invalid config file: {e}")))?;
+
+    let search = doc
+        .entry("search")
+        .or_insert_with(toml_edit::table)
+        .as_table_like_mut()
+        .ok_or_else(|| invalid("[search] is not a table".to_string()))?;
+
+    let list = search
+        .entry(key)
+        .or_insert(toml_edit::value(toml_edit::Array::new()))
+        .as_array_mut()
+        .ok_or_else(|| invalid(format!("{key} is not an array")))?;
+
+    if !list.iter().any(|v| v.as_str() == Some(value)) {
+        list.push(value);
+    }
+
+    Ok(doc.to_string())
+}
+
 /// Generate default TOML configuration content
 ///
 /// Creates a well-commented TOML template with all available options
@@ -544,10 +2030,26 @@ fn default_toml() -> String {
         r#"# grunner configuration
 # All values are optional — missing keys fall back to the built-in defaults.
 
+# Files read before this one, relative to this folder; this file overrides
+# them. Useful to share commands between machines.
+# include = ["commands-common.toml", "work.toml"]
+
 [window]
 # Width and height of the launcher window in pixels.
 width  = {width}
 height = {height}
+# Shrink the window to the results it shows, never taller than height.
+auto_height = false
+# Open as an overlay above all windows instead of a normal window on
+# compositors with the layer-shell protocol (Sway, Hyprland, ...), so tiling
+# rules leave it alone. Needs grunner built with --features layer-shell and
+# applies the next time grunner starts.
+layer_shell = false
+# Where the overlay sits: center, top, bottom, left, right, top-left,
+# top-right, bottom-left or bottom-right.
+anchor = "center"
+# Distance in pixels from the edges the overlay is anchored to.
+margin = 0
 
 [search]
 # Maximum number of fuzzy-search results shown (only when a query is active).
@@ -567,6 +2069,10 @@ app_dirs = [
 # Use the DesktopId as it appears in the provider's .ini file.
 provider_blacklist = []
 
+# Applications left out of the results, by desktop ID or glob,
+# e.g. ["avahi-discover.desktop", "wine-*"].
+app_blacklist = []
+
 # Enable workspace window bar (requires window-calls GNOME Shell extension).
 # Install from: https://extensions.gnome.org/extension/4724/window-calls/
 workspace_bar_enabled = true
@@ -576,11 +2082,62 @@ workspace_bar_enabled = true
 # Example: pinned_apps = ["firefox.desktop", "org.gnome.Terminal.desktop"]
 pinned_apps = []
 
+# Launch the top application automatically once you stop typing, if it is
+# the only result left, is named exactly like the query, or its match score
+# (0-100) reaches this threshold and clearly beats the next result.
+# A short highlight on the row counts down; any key press cancels it.
+# 0 disables auto-launch. Example: auto_launch_threshold = 95
+auto_launch_threshold = {auto_launch}
+
+# Case sensitivity of fuzzy matching: "smart" ignores case unless the query
+# contains an uppercase letter, "ignore" always ignores it, "respect" never.
+fuzzy_case = "smart"
+
+# Largest scoring matrix (query length x text length) filled by the fuzzy
+# matcher. Longer texts, such as big clipboard entries, are scored by a faster
+# but less precise greedy match instead. 0 means no limit.
+fuzzy_element_limit = 0
+
+# Run a query starting with "!" (e.g. "!htop") as a shell command in the
+# terminal emulator instead of searching. Off by default, so "!" text is
+# searched like any other query.
+terminal_prefix = false
+
+# Order and number of search provider results. Providers listed in order
+# come first, in that order, even if they answer last; the others follow.
+# max_results caps a provider's results, the others show search.max_results.
+# Providers are named by their DesktopId, like provider_blacklist.
+[search.providers]
+# order = ["org.gnome.Nautilus.desktop", "org.gnome.Calculator.desktop"]
+# max_results = {{ "org.gnome.Software.desktop" = 2, "org.gnome.Characters.desktop" = 3 }}
+
+[launch]
+# When an application already has a window open (on any workspace), show a
+# "running" badge on its row and focus that window on Enter instead of
+# starting another instance. Requires the window-calls GNOME Shell extension.
+prefer_focus_running = false
+
+# Close the window after Enter copies a calculator result or a clipboard
+# history entry. With false it stays open and says "Copied". Shift+Enter
+# always keeps the window open.
+close_on_copy = true
+
+# Editor the "Open Config File" button opens this file in. Without it,
+# $VISUAL or $EDITOR is used (terminal editors run in the terminal), then
+# the default application for plain text.
+# Example: settings_editor = "gnome-text-editor"
+
 [obsidian]
 vault = ""
 daily_notes_folder = ""
 new_notes_folder = ""
 quick_note = ""
+# When the vault does not exist, :ob offers to create it. Only paths inside
+# your home directory are created unless this is set to true.
+allow_vault_outside_home = false
+# Folders left out of :obg and :ob results. When unset, the templates folder
+# from .obsidian/templates.json is left out.
+# grep_exclude_folders = ["Templates", ".trash"]
 
 # Custom script commands for :sh mode
 # These commands will appear when you type :sh in the launcher
@@ -592,6 +2149,49 @@ quick_note = ""
 # [[commands]]
 # name = "Update Flatpaks"
 # command = "flatpak update"
+#
+# Commands that look destructive (rm -rf, mkfs, dd of=, shred, > /dev/sd...)
+# never run unless marked dangerous, and then only after a confirmation:
+# [[commands]]
+# name = "Clean Build"
+# command = "rm -rf ~/src/project/target"
+# dangerous = true
+
+# Web searches: ":ddg rust gtk" opens the URL with {{}} replaced by the search
+# terms. A table can also set the name and icon of the row. Built-in colon
+# commands (:f, :ob, ...) take precedence over these keywords.
+[web_searches]
+# ddg = "https://duckduckgo.com/?q={{}}"
+# aur = {{ url = "https://aur.archlinux.org/packages?K={{}}", name = "AUR", icon = "system-software-install" }}
+
+# Snippets: ":snip sig" copies the text to the clipboard, with {{date}} and
+# {{time}} replaced by the current date and time. A table reads the text from
+# a file, relative to ~/.config/grunner/snippets. Every *.txt file in that
+# folder is a snippet too, named after the file.
+[snippets]
+# sig = "Best regards,\nNihmar"
+# reply = {{ file = "reply.txt" }}
+
+[calculator]
+# Angle unit of sin, cos, tan, asin, acos and atan: "radians" or "degrees"
+angle_unit = "radians"
+
+[projects]
+# Folders searched for Git repositories, up to three levels deep, listed by
+# :proj after the folders recently opened in VS Code and JetBrains IDEs.
+roots = []
+# How a project no IDE has opened is opened: "file-manager" or "terminal"
+open_with = "file-manager"
+
+[ssh]
+# Also list the hosts of ~/.ssh/known_hosts in :ssh, after the Host aliases
+# of ~/.ssh/config. Hashed entries cannot be listed.
+known_hosts = false
+
+[units]
+# Also list the units of your user's systemd manager in :unit, as
+# `systemctl --user` shows them, after the system units.
+user = false
 
 [theme]
 # Theme mode selection
@@ -601,11 +2201,74 @@ mode = "system"
 
 # Path to custom theme CSS file (only used when mode = "custom")
 # Example: custom_theme_path = "~/.config/grunner/themes/my_theme.css"
+
+[ui]
+# Layout of :f, :fg, :ob and :obg results.
+# "two-line" shows the file name above its folder or matched text;
+# "single-line" shows "path/to/file.md:12 — matched text" on one line,
+# fitting about twice as many results on screen.
+file_results_layout = "two-line"
+# Fade in new results and scroll smoothly on Page Up/Down.
+# Also off when animations are disabled system-wide.
+animations = true
+# End each row in a small badge naming its source ("app", "clip", "calc",
+# "web", ...), to tell apart rows of different kinds in one list.
+show_kind_badges = false
+# End app descriptions in where the app was installed from, such as
+# "Flatpak · stable" or "pacman · firefox 126.0-1". Package managers are
+# only asked for rows that are shown.
+show_app_origin = false
+# The first Escape clears a typed query and the next one closes the window.
+# Set to false to close right away.
+escape_clears_first = true
+# Radius of the window corners in pixels.
+corner_radius = 12
+# Opacity of the window background, from 0.0 (transparent) to 1.0.
+# Without a compositor the window is always opaque with square corners.
+opacity = 1.0
+# Show the result count, the mode and what Enter does in it below the
+# results, e.g. "12 / 57 results  Files  Enter: open · Ctrl+Enter: open folder".
+show_footer = true
+
+[privacy]
+# Count locally how often each colon mode (:ob, :f, ...) is used and show it
+# in :stats, and remember which apps, files, notes and search provider
+# results you open to rank frequently used ones higher.
+# Nothing is ever transmitted. Setting this to false also deletes existing
+# counters the next time they would be saved.
+usage_stats = true
+
+# Keep texts copied while the launcher is open (newest first, up to 100)
+# for the :c mode, in ~/.cache/grunner/clipboard.json. Setting this to false
+# stops capturing and deletes the saved history.
+clipboard_history = true
+
+# Number of queries a result was activated from that are kept, newest
+# first, in ~/.cache/grunner/queries.json. Alt+Up and Alt+Down, or Up in an
+# empty query, bring them back. :pw queries are never kept. 0 keeps none
+# and deletes the saved queries.
+query_history = {query_history}
+
+[performance]
+# Milliseconds an app search may take. When several searches in a row take
+# longer, search work is cut step by step: app descriptions are no longer
+# matched, then fewer results are listed, then search providers are no
+# longer queried. Each step is undone once searches are fast again. The F12
+# overlay shows what is cut. 0 never cuts anything.
+latency_budget_ms = {budget}
+# Each step can be pinned: "auto" follows the searches, "on" never cuts it
+# and "off" always does.
+match_descriptions = "auto"
+full_results = "auto"
+search_providers = "auto"
 "#,
         width = DEFAULT_WINDOW_WIDTH,
         height = DEFAULT_WINDOW_HEIGHT,
         max = DEFAULT_MAX_RESULTS,
         debounce = DEFAULT_COMMAND_DEBOUNCE_MS,
+        auto_launch = DEFAULT_AUTO_LAUNCH_THRESHOLD,
+        budget = DEFAULT_LATENCY_BUDGET_MS,
+        query_history = DEFAULT_QUERY_HISTORY,
         dirs = dirs,
     )
 }
@@ -617,12 +2280,8 @@ mod tests {
     #[test]
     fn test_default_app_dirs() {
         let dirs = default_app_dirs();
-        assert_eq!(dirs.len(), 5);
-        assert!(dirs[0].contains("/usr/share/applications"));
-        assert!(dirs[1].contains("/usr/local/share/applications"));
-        assert!(dirs[2].contains("~/.local/share/applications"));
-        assert!(dirs[3].contains("/var/lib/flatpak/exports/share/applications"));
-        assert!(dirs[4].contains("~/.local/share/flatpak/exports/share/applications"));
+        assert!(!dirs.is_empty());
+        assert!(dirs.iter().all(|dir| dir.ends_with("/applications")));
     }
 
     #[test]
@@ -632,10 +2291,12 @@ mod tests {
         assert_eq!(config.window_height, DEFAULT_WINDOW_HEIGHT);
         assert_eq!(config.max_results, DEFAULT_MAX_RESULTS);
         assert_eq!(config.command_debounce_ms, DEFAULT_COMMAND_DEBOUNCE_MS);
-        assert!(config.app_dirs.len() > 0);
+        assert!(!config.app_dirs.is_empty());
         assert!(config.workspace_bar_enabled);
         assert!(config.obsidian.is_none());
         assert!(config.pinned_apps.is_empty());
+        assert_eq!(config.auto_launch_threshold, 0);
+        assert!(config.usage_stats);
     }
 
     #[test]
@@ -688,9 +2349,60 @@ mod tests {
         let (config, failed, _table) = apply_toml(toml);
         assert_eq!(config.window_width, 800);
         assert_eq!(config.window_height, 600);
+        assert!(!config.auto_height);
         assert!(failed.is_empty());
     }
 
+    #[test]
+    fn test_apply_toml_window_layer_shell() {
+        let (cfg, failed, _) = apply_toml(&default_toml());
+        assert!(failed.is_empty());
+        assert!(!cfg.layer_shell);
+        assert_eq!(cfg.layer_anchor, LayerAnchor::Center);
+        assert_eq!(cfg.layer_margin, 0);
+
+        let (cfg, failed, _) =
+            apply_toml("[window]\nlayer_shell = true\nanchor = \"top-right\"\nmargin = 24\n");
+        assert!(failed.is_empty());
+        assert!(cfg.layer_shell);
+        assert_eq!(cfg.layer_anchor, LayerAnchor::TopRight);
+        assert_eq!(cfg.layer_margin, 24);
+
+        let (cfg, _, _) = apply_toml(&config_to_toml(&cfg));
+        assert!(cfg.layer_shell);
+        assert_eq!(cfg.layer_anchor, LayerAnchor::TopRight);
+        assert_eq!(cfg.layer_margin, 24);
+
+        // A negative margin is ignored, an unknown anchor rejects the section
+        let (cfg, failed, _) = apply_toml("[window]\nmargin = -5\n");
+        assert!(failed.is_empty());
+        assert_eq!(cfg.layer_margin, 0);
+        let (_, failed, _) = apply_toml("[window]\nanchor = \"middle\"\n");
+        assert_eq!(failed, ["window"]);
+    }
+
+    #[test]
+    fn test_layer_anchor_edges() {
+        assert_eq!(LayerAnchor::Center.edges(), [false; 4]);
+        assert_eq!(LayerAnchor::Top.edges(), [true, false, false, false]);
+        assert_eq!(LayerAnchor::BottomLeft.edges(), [false, true, true, false]);
+        assert_eq!(LayerAnchor::TopRight.edges(), [true, false, false, true]);
+    }
+
+    #[test]
+    fn test_apply_toml_window_auto_height() {
+        let (cfg, failed, _) = apply_toml(&default_toml());
+        assert!(failed.is_empty());
+        assert!(!cfg.auto_height);
+
+        let (cfg, failed, _) = apply_toml("[window]\nauto_height = true\n");
+        assert!(failed.is_empty());
+        assert!(cfg.auto_height);
+
+        let (cfg, _, _) = apply_toml(&config_to_toml(&cfg));
+        assert!(cfg.auto_height);
+    }
+
     #[test]
     fn test_apply_toml_search_settings() {
         let toml = r#"
@@ -704,6 +2416,53 @@ mod tests {
         assert!(failed.is_empty());
     }
 
+    #[test]
+    fn test_apply_toml_search_providers() {
+        let toml = r#"
+            [search]
+            max_results = 20
+
+            [search.providers]
+            order = ["org.gnome.Nautilus.desktop", "org.gnome.Calculator"]
+            max_results = { "org.gnome.Software.desktop" = 2 }
+        "#;
+        let (config, failed, _table) = apply_toml(toml);
+        assert!(failed.is_empty());
+        assert_eq!(config.max_results, 20);
+        let providers = &config.provider_settings;
+        assert_eq!(providers.priority("org.gnome.Nautilus.desktop"), 0);
+        // The .desktop suffix is optional on either side
+        assert_eq!(providers.priority("org.gnome.Calculator.desktop"), 1);
+        assert_eq!(providers.priority("org.gnome.Software.desktop"), 2);
+        assert_eq!(providers.max_results("org.gnome.Software.desktop", 20), 2);
+        assert_eq!(providers.max_results("org.gnome.Nautilus.desktop", 20), 20);
+
+        let (written, _, _) = apply_toml(&config_to_toml(&config));
+        assert_eq!(written.provider_settings, config.provider_settings);
+    }
+
+    #[test]
+    fn test_apply_toml_auto_launch_threshold() {
+        let toml = r#"
+            [search]
+            auto_launch_threshold = 95
+        "#;
+        let (config, failed, _table) = apply_toml(toml);
+        assert_eq!(config.auto_launch_threshold, 95);
+        assert!(failed.is_empty());
+
+        // Out-of-range values are clamped rather than failing the section
+        let toml = r#"
+            [search]
+            max_results = 10
+            auto_launch_threshold = 250
+        "#;
+        let (config, failed, _table) = apply_toml(toml);
+        assert_eq!(config.auto_launch_threshold, MAX_AUTO_LAUNCH_THRESHOLD);
+        assert_eq!(config.max_results, 10);
+        assert!(failed.is_empty());
+    }
+
     #[test]
     fn test_apply_toml_invalid_values() {
         // Negative width should be ignored
@@ -730,6 +2489,8 @@ mod tests {
             daily_notes_folder: "daily".to_string(),
             new_notes_folder: "new".to_string(),
             quick_note: "quick.md".to_string(),
+            allow_vault_outside_home: false,
+            grep_exclude_folders: None,
         };
         assert_eq!(obsidian.vault, "~/obsidian");
         assert_eq!(obsidian.daily_notes_folder, "daily");
@@ -764,6 +2525,170 @@ mod tests {
         assert!(failed.is_empty());
     }
 
+    #[test]
+    fn test_apply_toml_command_dangerous() {
+        let toml = r#"
+            [[commands]]
+            name = "Clean"
+            command = "rm -rf ~/build"
+            dangerous = true
+
+            [[commands]]
+            name = "Wipe"
+            command = "shred -u secrets.txt"
+        "#;
+        let (config, failed, _table) = apply_toml(toml);
+        assert!(failed.is_empty());
+        assert!(config.commands[0].dangerous);
+        // Loaded but guarded, see command_safety
+        assert!(!config.commands[1].dangerous);
+
+        let written = config_to_toml(&config);
+        assert_eq!(written.matches("dangerous = true").count(), 1);
+    }
+
+    #[test]
+    fn test_apply_toml_commands_keep_definition_order() {
+        let toml = r#"
+            [[commands]]
+            name = "Zypper Update"
+            command = "sudo zypper up"
+
+            [[commands]]
+            name = "Backup"
+            command = "restic backup ~"
+
+            [[commands]]
+            name = "Mount NAS"
+            command = "mount /mnt/nas"
+        "#;
+        let (config, failed, _table) = apply_toml(toml);
+        let names: Vec<&str> = config.commands.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["Zypper Update", "Backup", "Mount NAS"]);
+        assert!(failed.is_empty());
+    }
+
+    #[test]
+    fn test_apply_toml_duplicate_command_names_rejected() {
+        let toml = r#"
+            [[commands]]
+            name = "Backup"
+            command = "restic backup ~"
+
+            [[commands]]
+            name = "Sync"
+            command = "rsync -a ~/docs nas:"
+
+            [[commands]]
+            name = "Backup"
+            command = "borg create"
+        "#;
+        let (config, failed, _table) = apply_toml(toml);
+        assert_eq!(config.commands.len(), 2);
+        assert_eq!(config.commands[0].name, "Backup");
+        assert_eq!(config.commands[0].command, "restic backup ~");
+        assert_eq!(config.commands[1].name, "Sync");
+        // A duplicate is a warning, not a failed section
+        assert!(failed.is_empty());
+    }
+
+    #[test]
+    fn test_apply_toml_web_searches() {
+        let toml = r#"
+            [web_searches]
+            ddg = "https://duckduckgo.com/?q={}"
+            aur = { url = "https://aur.archlinux.org/packages?K={}", name = "AUR", icon = "system-software-install" }
+            broken = 42
+        "#;
+        let (config, failed, _table) = apply_toml(toml);
+        assert!(failed.is_empty());
+        assert_eq!(config.web_searches.len(), 2);
+        assert_eq!(
+            config.web_searches["ddg"],
+            WebSearchConfig::Template("https://duckduckgo.com/?q={}".to_string())
+        );
+        let aur = &config.web_searches["aur"];
+        assert_eq!(aur.url(), "https://aur.archlinux.org/packages?K={}");
+        assert_eq!(aur.name(), Some("AUR"));
+        assert_eq!(aur.icon(), Some("system-software-install"));
+    }
+
+    #[test]
+    fn test_apply_toml_web_searches_shadowed_by_builtins() {
+        // (keywords in the config, keywords kept)
+        let cases: &[(&[&str], &[&str])] = &[
+            (&["ob", "ddg"], &["ddg"]),
+            (&["o", "obg2", "s"], &["o", "obg2", "s"]),
+            (&["f", "fg", "fgx"], &["fgx"]),
+            (&["sh", "stats", "b", "c"], &[]),
+            (&["OB"], &["OB"]),
+        ];
+        for (keywords, kept) in cases {
+            let toml: String = std::iter::once("[web_searches]\n".to_string())
+                .chain(
+                    keywords
+                        .iter()
+                        .map(|k| format!("{k} = \"https://example.com/?q={{}}\"\n")),
+                )
+                .collect();
+            let (config, failed, _table) = apply_toml(&toml);
+            // A collision is a warning, not a failed section
+            assert!(failed.is_empty());
+            let mut expected: Vec<&str> = kept.to_vec();
+            expected.sort_unstable();
+            let actual: Vec<&str> = config.web_searches.keys().map(String::as_str).collect();
+            assert_eq!(actual, expected, "{keywords:?}");
+        }
+    }
+
+    #[test]
+    fn test_web_searches_round_trip() {
+        let mut config = Config::default();
+        config.web_searches.insert(
+            "ddg".to_string(),
+            WebSearchConfig::Template("https://duckduckgo.com/?q={}".to_string()),
+        );
+        config.web_searches.insert(
+            "aur".to_string(),
+            WebSearchConfig::Full {
+                url: "https://aur.archlinux.org/packages?K={}".to_string(),
+                name: Some("AUR".to_string()),
+                icon: None,
+            },
+        );
+        let (parsed, failed, _table) = apply_toml(&config_to_toml(&config));
+        assert!(failed.is_empty());
+        assert_eq!(parsed.web_searches, config.web_searches);
+    }
+
+    #[test]
+    fn test_apply_toml_snippets() {
+        let toml = r#"
+            [snippets]
+            sig = "Best regards,\nNihmar"
+            reply = { file = "reply.txt" }
+            broken = 42
+        "#;
+        let (config, failed, _table) = apply_toml(toml);
+        assert!(failed.is_empty());
+        assert_eq!(config.snippets.len(), 2);
+        assert_eq!(
+            config.snippets["sig"],
+            SnippetConfig::Text("Best regards,\nNihmar".to_string())
+        );
+        assert_eq!(
+            config.snippets["reply"],
+            SnippetConfig::File {
+                file: "reply.txt".to_string()
+            }
+        );
+        assert!(unknown_key_warnings(toml).is_empty());
+
+        let (parsed, failed, _table) = apply_toml(&config_to_toml(&config));
+        assert!(failed.is_empty());
+        assert_eq!(parsed.snippets, config.snippets);
+    }
+
     #[test]
     fn test_config_default_has_empty_commands() {
         // Test that default config has empty commands Vec
@@ -850,6 +2775,209 @@ mod tests {
         assert!(!failed.contains(&"commands".to_string()));
     }
 
+    fn unknown_key_warnings(content: &str) -> Vec<String> {
+        let mut table: toml::value::Table = toml::from_str(content).unwrap();
+        strip_unknown_keys(&mut table)
+    }
+
+    #[test]
+    fn test_unknown_keys_name_the_nearest_valid_key() {
+        let toml = r#"
+            [window]
+            widht = 800
+
+            [search]
+            max_result = 20
+
+            [search.providers]
+            ordr = ["org.gnome.Nautilus"]
+
+            [launch]
+            settings_editr = "code"
+
+            [obsidan]
+            vault = "~/Notes"
+
+            [[commands]]
+            name = "Build"
+            command = "make"
+            keepopen = false
+
+            [calculator]
+            angle_units = "degrees"
+
+            [theme]
+            mod = "nord"
+
+            [ui]
+            animation = false
+
+            [privacy]
+            usage_stat = false
+        "#;
+        let warnings = unknown_key_warnings(toml);
+        for expected in [
+            "Unknown key `widht` in [window] is ignored, did you mean `width`?",
+            "Unknown key `max_result` in [search] is ignored, did you mean `max_results`?",
+            "Unknown key `ordr` in [search.providers] is ignored, did you mean `order`?",
+            "Unknown key `settings_editr` in [launch] is ignored, did you mean `settings_editor`?",
+            "Unknown section [obsidan] is ignored, did you mean [obsidian]?",
+            "Unknown key `keepopen` in [commands] is ignored, did you mean `keep_open`?",
+            "Unknown key `angle_units` in [calculator] is ignored, did you mean `angle_unit`?",
+            "Unknown key `mod` in [theme] is ignored, did you mean `mode`?",
+            "Unknown key `animation` in [ui] is ignored, did you mean `animations`?",
+            "Unknown key `usage_stat` in [privacy] is ignored, did you mean `usage_stats`?",
+        ] {
+            assert!(
+                warnings.iter().any(|w| w == expected),
+                "{expected}: {warnings:?}"
+            );
+        }
+        assert_eq!(warnings.len(), 10);
+    }
+
+    #[test]
+    fn test_unknown_keys_without_a_near_key() {
+        let warnings = unknown_key_warnings(
+            "top = 1\n[search]\ncolour = \"red\"\nbogus = 2\n[obsidian]\nvault = \"~/n\"\nzzz = 1\n",
+        );
+        assert!(warnings.contains(&"Unknown key `top` outside any section is ignored".to_string()));
+        assert!(warnings.contains(&"Unknown key `colour` in [search] is ignored".to_string()));
+        assert!(warnings.contains(&"Unknown key `bogus` in [search] is ignored".to_string()));
+        assert!(warnings.contains(&"Unknown key `zzz` in [obsidian] is ignored".to_string()));
+        assert_eq!(warnings.len(), 4);
+    }
+
+    #[test]
+    fn test_known_keys_have_no_warnings() {
+        assert!(unknown_key_warnings(&default_toml()).is_empty());
+        let config = Config {
+            obsidian: Some(ObsidianConfig::default()),
+            commands: vec![CommandConfig {
+                name: "Build".to_string(),
+                command: "make".to_string(),
+                dangerous: true,
+                ..Default::default()
+            }],
+            provider_settings: ProviderSettings {
+                order: vec!["org.gnome.Nautilus".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(unknown_key_warnings(&config_to_toml(&config)).is_empty());
+        // Any keyword is a valid web search
+        assert!(
+            unknown_key_warnings("[web_searches]\nddgo = \"https://d.com/?q={}\"\n").is_empty()
+        );
+    }
+
+    #[test]
+    fn test_apply_toml_skips_unknown_keys() {
+        let toml = r#"
+            [search]
+            max_result = 20
+            max_results = 10
+
+            [search.providers]
+            ordr = ["a"]
+            max_results = { "b" = 3 }
+
+            [[commands]]
+            name = "Build"
+            command = "make"
+            keepopen = false
+        "#;
+        let (config, failed, table) = apply_toml(toml);
+        assert!(failed.is_empty());
+        assert_eq!(config.max_results, 10);
+        assert_eq!(config.provider_settings.max_results.get("b"), Some(&3));
+        assert_eq!(config.commands.len(), 1);
+        assert!(config.commands[0].keep_open);
+        // The file keeps the unknown keys
+        assert!(table["search"].get("max_result").is_some());
+    }
+
+    #[test]
+    fn test_config_path_env_override() {
+        let default = resolve_config_path(None, "/home/user");
+        assert_eq!(
+            default,
+            PathBuf::from("/home/user/.config/grunner/grunner.toml")
+        );
+        assert_eq!(
+            resolve_config_path(Some("/nix/store/abc-grunner.toml".into()), "/home/user"),
+            PathBuf::from("/nix/store/abc-grunner.toml")
+        );
+        // An empty variable counts as unset
+        assert_eq!(resolve_config_path(Some("".into()), "/home/user"), default);
+    }
+
+    #[test]
+    fn test_write_default_config() {
+        use std::fs;
+
+        let dir = std::env::temp_dir().join(format!(
+            "grunner_test_default_config_{}",
+            std::process::id()
+        ));
+        let path = dir.join("grunner").join("grunner.toml");
+
+        // Nothing exists yet: the directory and default file are created
+        assert!(write_default_config(&path, false).unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), default_toml());
+
+        // An existing file is left alone
+        fs::write(&path, "[window]\nwidth = 900\n").unwrap();
+        assert!(!write_default_config(&path, false).unwrap());
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "[window]\nwidth = 900\n"
+        );
+
+        // A path from GRUNNER_CONFIG is never written
+        let other = dir.join("other.toml");
+        assert!(!write_default_config(&other, true).unwrap());
+        assert!(!other.exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_check_config_writable() {
+        let path = Path::new("/nix/store/grunner.toml");
+        assert!(check_config_writable(path, false).is_ok());
+        let err = check_config_writable(path, true).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+        assert!(err.to_string().contains("/nix/store/grunner.toml"));
+        assert!(err.to_string().contains(CONFIG_ENV));
+    }
+
+    #[test]
+    fn test_write_default_config_skips_read_only_dir() {
+        use std::fs;
+
+        let dir = std::env::temp_dir().join(format!(
+            "grunner_test_read_only_config_{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let mut perms = fs::metadata(&dir).unwrap().permissions();
+        perms.set_readonly(true);
+        fs::set_permissions(&dir, perms.clone()).unwrap();
+
+        // Root may write to it anyway, so the file is only skipped for others
+        let path = dir.join("grunner").join("grunner.toml");
+        let writable = rustix::fs::access(&dir, rustix::fs::Access::WRITE_OK).is_ok();
+        assert_eq!(write_default_config(&path, false).unwrap(), writable);
+        assert_eq!(path.exists(), writable);
+
+        #[allow(clippy::permissions_set_readonly_false)]
+        perms.set_readonly(false);
+        fs::set_permissions(&dir, perms).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_apply_toml_auto_corrects_and_saves() {
         use std::fs;
@@ -898,15 +3026,145 @@ mod tests {
         let _ = fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn test_add_to_provider_blacklist_preserves_other_settings() {
+        let toml = r#"
+            [window]
+            width = 900
+
+            [search]
+            max_results = 12
+            provider_blacklist = ["org.gnome.Characters.desktop"]
+
+            [[commands]]
+            name = "Up"
+            command = "uptime"
+        "#;
+        let updated =
+            add_to_search_list(toml, "provider_blacklist", "org.gnome.Software.desktop").unwrap();
+        let (cfg, failed, _table) = apply_toml(&updated);
+
+        assert!(failed.is_empty());
+        assert_eq!(cfg.window_width, 900);
+        assert_eq!(cfg.max_results, 12);
+        assert_eq!(cfg.commands.len(), 1);
+        assert_eq!(cfg.commands[0].command, "uptime");
+        assert_eq!(
+            cfg.search_provider_blacklist,
+            vec![
+                "org.gnome.Characters.desktop".to_string(),
+                "org.gnome.Software.desktop".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_add_to_search_list_keeps_comments_and_order() {
+        let toml = r#"# My launcher
+[window]
+# Wide enough for long paths
+width = 900
+height = 480
+
+[search]
+max_results = 12  # plenty
+provider_blacklist = ["org.gnome.Characters.desktop"]
+app_dirs = ["~/.local/share/applications"]
+
+[obsidian]
+vault = "~/notes"
+"#;
+        let updated =
+            add_to_search_list(toml, "provider_blacklist", "org.gnome.Software.desktop").unwrap();
+        assert_eq!(
+            updated,
+            toml.replace(
+                r#"["org.gnome.Characters.desktop"]"#,
+                r#"["org.gnome.Characters.desktop", "org.gnome.Software.desktop"]"#
+            )
+        );
+
+        // A listed value leaves the file as it was
+        assert_eq!(
+            add_to_search_list(&updated, "provider_blacklist", "org.gnome.Software.desktop")
+                .unwrap(),
+            updated
+        );
+    }
+
+    #[test]
+    fn test_add_to_provider_blacklist_is_idempotent() {
+        let toml = r#"
+            [search]
+            provider_blacklist = ["org.gnome.Software.desktop"]
+        "#;
+        let updated =
+            add_to_search_list(toml, "provider_blacklist", "org.gnome.Software.desktop").unwrap();
+        let (cfg, _failed, _table) = apply_toml(&updated);
+        assert_eq!(cfg.search_provider_blacklist.len(), 1);
+    }
+
+    #[test]
+    fn test_add_to_provider_blacklist_creates_missing_section() {
+        let toml = r#"
+            [window]
+            height = 300
+        "#;
+        let updated =
+            add_to_search_list(toml, "provider_blacklist", "org.gnome.Software.desktop").unwrap();
+        let (cfg, failed, _table) = apply_toml(&updated);
+        assert!(failed.is_empty());
+        assert_eq!(cfg.window_height, 300);
+        assert_eq!(
+            cfg.search_provider_blacklist,
+            vec!["org.gnome.Software.desktop".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_add_to_search_list_app_blacklist() {
+        let toml = r#"
+            [search]
+            provider_blacklist = ["org.gnome.Software.desktop"]
+        "#;
+        let updated = add_to_search_list(toml, "app_blacklist", "avahi-discover.desktop").unwrap();
+        let (cfg, failed, _table) = apply_toml(&updated);
+        assert!(failed.is_empty());
+        assert_eq!(
+            cfg.app_blacklist,
+            vec!["avahi-discover.desktop".to_string()]
+        );
+        assert_eq!(cfg.search_provider_blacklist.len(), 1);
+
+        let (cfg, _, _) = apply_toml(&config_to_toml(&cfg));
+        assert_eq!(
+            cfg.app_blacklist,
+            vec!["avahi-discover.desktop".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_add_to_provider_blacklist_rejects_malformed_input() {
+        assert!(add_to_search_list("not = [valid", "provider_blacklist", "x.desktop").is_err());
+        assert!(add_to_search_list("search = 5", "provider_blacklist", "x.desktop").is_err());
+        let toml = r#"
+            [search]
+            provider_blacklist = "oops"
+        "#;
+        assert!(add_to_search_list(toml, "provider_blacklist", "x.desktop").is_err());
+    }
+
     #[test]
     fn test_config_to_toml_round_trip() {
-        let mut config = Config::default();
-        config.window_width = 1024;
-        config.window_height = 768;
-        config.max_results = 128;
-        config.command_debounce_ms = 500;
-        config.workspace_bar_enabled = false;
-        config.pinned_apps = vec!["firefox.desktop".into()];
+        let config = Config {
+            window_width: 1024,
+            window_height: 768,
+            max_results: 128,
+            command_debounce_ms: 500,
+            workspace_bar_enabled: false,
+            pinned_apps: vec!["firefox.desktop".into()],
+            ..Default::default()
+        };
 
         let toml_str = config_to_toml(&config);
         let (parsed, failed, _table) = apply_toml(&toml_str);
@@ -970,6 +3228,94 @@ mod tests {
         assert_eq!(obs.daily_notes_folder, "Daily");
         assert_eq!(obs.new_notes_folder, "Inbox");
         assert_eq!(obs.quick_note, "Quick.md");
+        assert!(!obs.allow_vault_outside_home);
+    }
+
+    #[test]
+    fn test_apply_toml_obsidian_grep_exclude_folders() {
+        let toml = r#"
+            [obsidian]
+            vault = "~/vault"
+            daily_notes_folder = ""
+            new_notes_folder = ""
+            quick_note = ""
+            grep_exclude_folders = ["Templates", "/Archive/Old/", "", "../x"]
+        "#;
+        let (config, failed, _) = apply_toml(toml);
+        assert!(failed.is_empty());
+        let obs = config.obsidian.clone().unwrap();
+        assert_eq!(
+            obs.excluded_folders(Path::new("/nonexistent")),
+            ["Templates", "Archive/Old"]
+        );
+
+        // The setting survives a save, and stays unset when it was unset
+        let (saved, _, _) = apply_toml(&config_to_toml(&config));
+        assert_eq!(
+            saved.obsidian.unwrap().grep_exclude_folders,
+            obs.grep_exclude_folders
+        );
+        let (cfg, _, _) = apply_toml(&default_toml());
+        assert!(!config_to_toml(&cfg).contains("grep_exclude_folders"));
+    }
+
+    #[test]
+    fn test_templates_folder_detection() {
+        let vault = std::env::temp_dir().join(format!(
+            "grunner_test_templates_json_{}",
+            std::process::id()
+        ));
+        let dot = vault.join(".obsidian");
+        std::fs::create_dir_all(&dot).unwrap();
+        let obs = ObsidianConfig::default();
+
+        // No templates.json: nothing is excluded
+        assert_eq!(templates_folder(&vault), None);
+        assert!(obs.excluded_folders(&vault).is_empty());
+
+        std::fs::write(
+            dot.join("templates.json"),
+            r#"{"folder": "Meta/Templates"}"#,
+        )
+        .unwrap();
+        assert_eq!(templates_folder(&vault).as_deref(), Some("Meta/Templates"));
+        assert_eq!(obs.excluded_folders(&vault), ["Meta/Templates"]);
+
+        // An explicit list replaces the detected folder, even when empty
+        let explicit = ObsidianConfig {
+            grep_exclude_folders: Some(Vec::new()),
+            ..ObsidianConfig::default()
+        };
+        assert!(explicit.excluded_folders(&vault).is_empty());
+
+        std::fs::write(dot.join("templates.json"), "{}").unwrap();
+        assert_eq!(templates_folder(&vault), None);
+        std::fs::write(dot.join("templates.json"), "not json").unwrap();
+        assert_eq!(templates_folder(&vault), None);
+
+        let _ = std::fs::remove_dir_all(&vault);
+    }
+
+    #[test]
+    fn test_apply_toml_obsidian_keeps_paths_outside_vault_invalid() {
+        let toml = r#"
+            [obsidian]
+            vault = "/nonexistent/vault"
+            daily_notes_folder = "Journal/Daily"
+            new_notes_folder = "/tmp/elsewhere"
+            quick_note = "../../.ssh/authorized_keys"
+        "#;
+        let (config, failed, _table) = apply_toml(toml);
+        assert!(failed.is_empty());
+        let obs = config.obsidian.unwrap();
+        assert_eq!(obs.daily_notes_folder, "Journal/Daily");
+        // Kept, so that they are refused rather than replaced by the vault root
+        assert_eq!(obs.new_notes_folder, "/tmp/elsewhere");
+        assert_eq!(obs.quick_note, "../../.ssh/authorized_keys");
+        let ctx = crate::core::obsidian::ObsidianContext::new(obs);
+        let err = ctx.check_note_paths().unwrap_err();
+        assert!(err.contains("obsidian.new_notes_folder"));
+        assert!(ctx.quick_note_path().is_err());
     }
 
     #[test]
@@ -990,4 +3336,314 @@ mod tests {
         assert_eq!(re_parsed.window_width, DEFAULT_WINDOW_WIDTH);
         assert_eq!(re_parsed.max_results, 42);
     }
+
+    /// A condition on the parsed config
+    type Check = fn(&Config) -> bool;
+
+    #[test]
+    fn test_apply_toml_plain_keys() {
+        // (snippet, holds for the defaults, holds once the snippet is applied)
+        let cases: &[(&str, Check, Check)] = &[
+            (
+                "[privacy]\nusage_stats = false",
+                |c| c.usage_stats,
+                |c| !c.usage_stats,
+            ),
+            (
+                "[privacy]\nclipboard_history = false",
+                |c| c.clipboard_history,
+                |c| !c.clipboard_history,
+            ),
+            (
+                "[privacy]\nquery_history = 0",
+                |c| c.query_history == DEFAULT_QUERY_HISTORY,
+                |c| c.query_history == 0,
+            ),
+            (
+                "[search]\nfuzzy_case = \"respect\"\nfuzzy_element_limit = 500",
+                |c| c.fuzzy_case == FuzzyCase::Smart && c.fuzzy_element_limit == 0,
+                |c| c.fuzzy_case == FuzzyCase::Respect && c.fuzzy_element_limit == 500,
+            ),
+            (
+                "[search]\nterminal_prefix = true",
+                |c| !c.terminal_prefix,
+                |c| c.terminal_prefix,
+            ),
+            (
+                "[launch]\nprefer_focus_running = true",
+                |c| !c.prefer_focus_running,
+                |c| c.prefer_focus_running,
+            ),
+            (
+                "[launch]\nclose_on_copy = false",
+                |c| c.close_on_copy,
+                |c| !c.close_on_copy,
+            ),
+            (
+                "[launch]\nsettings_editor = \"code --wait\"",
+                |c| c.settings_editor.is_none(),
+                |c| c.settings_editor.as_deref() == Some("code --wait"),
+            ),
+            (
+                "[ui]\nfile_results_layout = \"single-line\"",
+                |c| c.file_results_layout == FileResultsLayout::TwoLine,
+                |c| c.file_results_layout == FileResultsLayout::SingleLine,
+            ),
+            (
+                "[ui]\nanimations = false",
+                |c| c.animations,
+                |c| !c.animations,
+            ),
+            (
+                "[ui]\nshow_kind_badges = true",
+                |c| !c.show_kind_badges,
+                |c| c.show_kind_badges,
+            ),
+            (
+                "[ui]\nshow_app_origin = true",
+                |c| !c.show_app_origin,
+                |c| c.show_app_origin,
+            ),
+            (
+                "[ui]\nescape_clears_first = false",
+                |c| c.escape_clears_first,
+                |c| !c.escape_clears_first,
+            ),
+            (
+                "[ui]\nshow_footer = false",
+                |c| c.show_footer,
+                |c| !c.show_footer,
+            ),
+            (
+                "[ui]\ncorner_radius = 0",
+                |c| c.corner_radius == DEFAULT_CORNER_RADIUS,
+                |c| c.corner_radius == 0,
+            ),
+            (
+                "[ui]\nopacity = 0.9",
+                |c| (c.opacity - 1.0).abs() < f64::EPSILON,
+                |c| (c.opacity - 0.9).abs() < f64::EPSILON,
+            ),
+            (
+                "[calculator]\nangle_unit = \"degrees\"",
+                |c| c.angle_unit == AngleUnit::Radians,
+                |c| c.angle_unit == AngleUnit::Degrees,
+            ),
+            (
+                "[projects]\nroots = [\"~/src\"]\nopen_with = \"terminal\"",
+                |c| {
+                    c.project_roots.is_empty()
+                        && c.project_open_with == ProjectFallback::FileManager
+                },
+                |c| {
+                    c.project_roots == ["~/src"] && c.project_open_with == ProjectFallback::Terminal
+                },
+            ),
+            (
+                "[ssh]\nknown_hosts = true",
+                |c| !c.ssh_known_hosts,
+                |c| c.ssh_known_hosts,
+            ),
+            ("[units]\nuser = true", |c| !c.units_user, |c| c.units_user),
+            (
+                "[performance]\nlatency_budget_ms = 0\nfull_results = \"on\"\nsearch_providers = \"off\"",
+                |c| {
+                    c.latency_budget_ms == DEFAULT_LATENCY_BUDGET_MS
+                        && c.full_results == Pin::Auto
+                        && c.search_providers == Pin::Auto
+                },
+                |c| {
+                    c.latency_budget_ms == 0
+                        && c.full_results == Pin::On
+                        && c.search_providers == Pin::Off
+                },
+            ),
+            (
+                "include = [\"common.toml\"]",
+                |c| c.include.is_empty(),
+                |c| c.include == ["common.toml"],
+            ),
+        ];
+
+        let (defaults, failed, _) = apply_toml(&default_toml());
+        assert!(failed.is_empty());
+        for (snippet, is_default, is_set) in cases {
+            assert!(is_default(&defaults), "default of {snippet}");
+            let (cfg, failed, _) = apply_toml(snippet);
+            assert!(failed.is_empty(), "{snippet}: {failed:?}");
+            assert!(is_set(&cfg), "{snippet}");
+            let (cfg, _, _) = apply_toml(&config_to_toml(&cfg));
+            assert!(is_set(&cfg), "{snippet} after saving");
+        }
+    }
+
+    #[test]
+    fn test_apply_toml_rejects_bad_values() {
+        // (snippet, section reported as failed, holds after the rejection)
+        let cases: &[(&str, &str, Check)] = &[
+            ("[privacy]\nusage_stats = \"no\"", "privacy", |c| {
+                c.usage_stats
+            }),
+            ("[search]\nfuzzy_case = \"upper\"", "search", |c| {
+                c.fuzzy_case == FuzzyCase::Smart
+            }),
+            ("[search]\nfuzzy_element_limit = -1", "search", |c| {
+                c.fuzzy_element_limit == 0
+            }),
+            ("[launch]\nprefer_focus_running = \"yes\"", "launch", |c| {
+                !c.prefer_focus_running
+            }),
+            ("[ui]\nfile_results_layout = \"three-line\"", "ui", |c| {
+                c.file_results_layout == FileResultsLayout::TwoLine
+            }),
+            (
+                "[calculator]\nangle_unit = \"gradians\"",
+                "calculator",
+                |c| c.angle_unit == AngleUnit::Radians,
+            ),
+            ("[projects]\nopen_with = \"editor\"", "projects", |c| {
+                c.project_open_with == ProjectFallback::FileManager
+            }),
+            (
+                "[performance]\nfull_results = \"never\"",
+                "performance",
+                |c| c.full_results == Pin::Auto,
+            ),
+        ];
+
+        for (snippet, section, holds) in cases {
+            let (cfg, failed, _) = apply_toml(snippet);
+            assert_eq!(failed, [section.to_string()], "{snippet}");
+            assert!(holds(&cfg), "{snippet}");
+        }
+    }
+
+    #[test]
+    fn test_pin_cut() {
+        assert!(Pin::Auto.cut(true));
+        assert!(!Pin::Auto.cut(false));
+        assert!(!Pin::On.cut(true));
+        assert!(Pin::Off.cut(false));
+    }
+
+    #[test]
+    fn test_apply_toml_ui_opacity_is_clamped() {
+        // Out-of-range opacities are clamped rather than failing the section
+        let (cfg, failed, _) = apply_toml("[ui]\nopacity = 1.5\n");
+        assert!(failed.is_empty());
+        assert!((cfg.opacity - 1.0).abs() < f64::EPSILON);
+        let (cfg, _, _) = apply_toml("[ui]\nopacity = -0.2\n");
+        assert!(cfg.opacity.abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_saved_includes_come_first() {
+        assert!(!config_to_toml(&Config::default()).contains("include"));
+
+        let (cfg, _, _) = apply_toml("include = [\"common.toml\"]\n[window]\nwidth = 700\n");
+        let saved = config_to_toml(&cfg);
+        assert!(
+            saved.starts_with("include = [\"common.toml\"]\n"),
+            "{saved}"
+        );
+    }
+
+    #[test]
+    fn test_included_commands_apply() {
+        let dir = std::env::temp_dir().join(format!(
+            "grunner_test_config_include_{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("commands-common.toml"),
+            "[[commands]]\nname = \"Update\"\ncommand = \"sudo pacman -Syu\"\n\
+             [[commands]]\nname = \"Logs\"\ncommand = \"journalctl -f\"\n",
+        )
+        .unwrap();
+        let path = dir.join("grunner.toml");
+        let table = parse_toml(
+            "include = [\"commands-common.toml\"]\n\
+             [[commands]]\nname = \"Update\"\ncommand = \"sudo apt upgrade\"\n",
+        );
+
+        let (merged, warnings) = resolve_includes(table, &path);
+        assert!(warnings.is_empty(), "{warnings:?}");
+        let (cfg, failed) = apply_table(&merged);
+        assert!(failed.is_empty());
+        let commands: Vec<(&str, &str)> = cfg
+            .commands
+            .iter()
+            .map(|c| (c.name.as_str(), c.command.as_str()))
+            .collect();
+        assert_eq!(
+            commands,
+            [("Update", "sudo apt upgrade"), ("Logs", "journalctl -f")]
+        );
+        assert_eq!(cfg.include, ["commands-common.toml"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_saving_keeps_included_values_in_the_include() {
+        let dir = std::env::temp_dir().join(format!(
+            "grunner_test_config_include_save_{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let common = dir.join("common.toml");
+        std::fs::write(
+            &common,
+            "[window]\nwidth = 900\n\
+             [[commands]]\nname = \"Logs\"\ncommand = \"journalctl -f\"\n\
+             [snippets]\nsig = \"Regards\"\n",
+        )
+        .unwrap();
+        let path = dir.join("grunner.toml");
+        std::fs::write(
+            &path,
+            "include = [\"common.toml\"]\n[search]\nmax_results = 20\n",
+        )
+        .unwrap();
+        let load = || {
+            let table = parse_toml(&std::fs::read_to_string(&path).unwrap());
+            let (merged, warnings) = resolve_includes(table, &path);
+            assert!(warnings.is_empty(), "{warnings:?}");
+            apply_table(&merged).0
+        };
+
+        // Change one setting, as the settings window would, and save
+        let mut cfg = load();
+        assert_eq!((cfg.window_width, cfg.commands.len()), (900, 1));
+        cfg.window_height = 700;
+        std::fs::write(&path, config_to_own_toml(&cfg, &path)).unwrap();
+
+        let saved = parse_toml(&std::fs::read_to_string(&path).unwrap());
+        assert_eq!(saved["include"].as_array().unwrap().len(), 1);
+        assert_eq!(
+            saved["window"]
+                .as_table()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>(),
+            ["height"]
+        );
+        assert_eq!(saved["search"]["max_results"].as_integer(), Some(20));
+        assert!(!saved.contains_key("commands"));
+        assert!(!saved.contains_key("snippets"));
+
+        // The include still applies, and later edits to it are picked up
+        std::fs::write(
+            &common,
+            "[window]\nwidth = 1000\n\
+             [[commands]]\nname = \"Logs\"\ncommand = \"journalctl -fe\"\n",
+        )
+        .unwrap();
+        let cfg = load();
+        assert_eq!((cfg.window_width, cfg.window_height), (1000, 700));
+        assert_eq!(cfg.max_results, 20);
+        assert_eq!(cfg.commands[0].command, "journalctl -fe");
+        assert!(cfg.snippets.is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
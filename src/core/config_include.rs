@@ -0,0 +1,493 @@
+//! Include files of the configuration
+//!
+//! A top-level `include = ["commands-common.toml", "work.toml"]` reads the
+//! listed files before the configuration itself, so that command sets can
+//! be shared between machines. Paths are relative to the folder of the file
+//! that lists them, and may start with `~`.
+//!
+//! The files are merged in order, each over the previous ones, and the
+//! file listing them over all of its includes:
+//!
+//! - Scalars and arrays are replaced, e.g. `search.pinned_apps`.
+//! - Tables are merged key by key, e.g. `[search]` or `[search.providers]`.
+//! - `[[commands]]` are merged by `name`: a command replaces the one of the
+//!   same name in place, other commands are appended.
+//! - Entries of `[snippets]` and `[web_searches]` are replaced whole, a
+//!   snippet `{ file = "…" }` is not mixed with a text one.
+//!
+//! Included files may include others, down to [`MAX_INCLUDE_DEPTH`] levels.
+//! A file including itself, directly or not, a missing file and a file that
+//! is not valid TOML are skipped with a warning. [`include_files`] lists
+//! the files to watch for changes besides the configuration.
+
+use crate::utils::expand_home;
+use std::path::{Path, PathBuf};
+use toml::value::Table;
+
+/// Top-level key listing the include files
+pub const INCLUDE_KEY: &str = "include";
+
+/// How deep includes may nest: files included by the configuration are at
+/// level 1
+pub const MAX_INCLUDE_DEPTH: usize = 3;
+
+/// `table`, read from `path`, merged over the files it includes
+///
+/// The `include` key of `table` is kept, those of the included files are
+/// not. Every include that was skipped gives a warning.
+#[must_use]
+pub fn resolve_includes(table: Table, path: &Path) -> (Table, Vec<String>) {
+    let mut chain = vec![canonical(path)];
+    let mut warnings = Vec::new();
+    let table = resolve(table, path, &mut chain, &mut warnings, &mut Vec::new());
+    (table, warnings)
+}
+
+/// Every include file of the configuration at `path`, nested ones too
+///
+/// Listed files that do not exist are part of it, so that creating them
+/// can be noticed; files nested too deep are not.
+#[must_use]
+pub fn include_files(path: &Path) -> Vec<PathBuf> {
+    let Some(table) = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| toml::from_str::<Table>(&content).ok())
+    else {
+        return Vec::new();
+    };
+    let mut files = Vec::new();
+    resolve(
+        table,
+        path,
+        &mut vec![canonical(path)],
+        &mut Vec::new(),
+        &mut files,
+    );
+    let mut seen = std::collections::HashSet::from([path.to_path_buf()]);
+    files.retain(|file| seen.insert(file.clone()));
+    files
+}
+
+/// The include file names of `table`, if it lists any
+#[must_use]
+pub fn include_names(table: &Table) -> Option<Vec<String>> {
+    table.get(INCLUDE_KEY)?.clone().try_into().ok()
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Path of the include `name` listed in the file at `path`
+fn include_path(name: &str, path: &Path) -> PathBuf {
+    let include = expand_home(name);
+    match path.parent() {
+        Some(dir) if include.is_relative() => dir.join(include),
+        _ => include,
+    }
+}
+
+fn resolve(
+    table: Table,
+    path: &Path,
+    chain: &mut Vec<PathBuf>,
+    warnings: &mut Vec<String>,
+    files: &mut Vec<PathBuf>,
+) -> Table {
+    let Some(include) = table.get(INCLUDE_KEY) else {
+        return table;
+    };
+    let Some(names) = include_names(&table) else {
+        warnings.push(format!(
+            "Ignoring `{INCLUDE_KEY}` in {}: expected a list of file names, got {include}",
+            path.display()
+        ));
+        return table;
+    };
+
+    let mut merged = Table::new();
+    for name in names {
+        let include = include_path(&name, path);
+        if chain.len() > MAX_INCLUDE_DEPTH {
+            warnings.push(format!(
+                "Ignoring include {} in {}: includes nest more than {MAX_INCLUDE_DEPTH} levels deep",
+                include.display(),
+                path.display()
+            ));
+            continue;
+        }
+        files.push(include.clone());
+        let Ok(canonical) = std::fs::canonicalize(&include) else {
+            warnings.push(format!(
+                "Ignoring include {} in {}: file not found",
+                include.display(),
+                path.display()
+            ));
+            continue;
+        };
+        if chain.contains(&canonical) {
+            warnings.push(format!(
+                "Ignoring include {} in {}: the includes form a cycle",
+                include.display(),
+                path.display()
+            ));
+            continue;
+        }
+        let content = match std::fs::read_to_string(&include) {
+            Ok(content) => content,
+            Err(e) => {
+                warnings.push(format!("Ignoring include {}: {e}", include.display()));
+                continue;
+            }
+        };
+        let included: Table = match toml::from_str(&content) {
+            Ok(included) => included,
+            Err(e) => {
+                warnings.push(format!("Ignoring include {}: {e}", include.display()));
+                continue;
+            }
+        };
+
+        chain.push(canonical);
+        let mut included = resolve(included, &include, chain, warnings, files);
+        chain.pop();
+        included.remove(INCLUDE_KEY);
+        merge_config(&mut merged, included);
+    }
+    merge_config(&mut merged, table);
+    merged
+}
+
+/// Merge the top-level keys of `over` into `base`, see the module docs
+pub fn merge_config(base: &mut Table, over: Table) {
+    for (key, val) in over {
+        match (key.as_str(), base.get_mut(&key), val) {
+            ("commands", Some(toml::Value::Array(commands)), toml::Value::Array(over)) => {
+                merge_commands(commands, over);
+            }
+            (
+                "snippets" | "web_searches",
+                Some(toml::Value::Table(entries)),
+                toml::Value::Table(over),
+            ) => entries.extend(over),
+            (_, Some(toml::Value::Table(section)), toml::Value::Table(over)) => {
+                merge_tables(section, over);
+            }
+            (_, _, val) => {
+                base.insert(key, val);
+            }
+        }
+    }
+}
+
+/// Merge `over` into `base`: tables key by key, anything else replaced
+fn merge_tables(base: &mut Table, over: Table) {
+    for (key, val) in over {
+        match (base.get_mut(&key), val) {
+            (Some(toml::Value::Table(table)), toml::Value::Table(over)) => {
+                merge_tables(table, over);
+            }
+            (_, val) => {
+                base.insert(key, val);
+            }
+        }
+    }
+}
+
+/// Replace the commands of `base` named as one of `over`, append the others
+fn merge_commands(base: &mut Vec<toml::Value>, over: Vec<toml::Value>) {
+    let name = |command: &toml::Value| command.get("name").cloned();
+    for command in over {
+        let existing = name(&command)
+            .and_then(|wanted| base.iter().position(|c| name(c).as_ref() == Some(&wanted)));
+        match existing {
+            Some(i) => base[i] = command,
+            None => base.push(command),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh folder holding the fixture `files`, as `(name, contents)`
+    fn fixture(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "grunner_test_include_{name}_{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        for (file, contents) in files {
+            let path = dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
+        dir
+    }
+
+    fn resolve_file(path: &Path) -> (Table, Vec<String>) {
+        let table = toml::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        resolve_includes(table, path)
+    }
+
+    fn parse(content: &str) -> Table {
+        toml::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn test_merge_replaces_scalars_and_arrays() {
+        let mut base = parse(
+            "[window]\nwidth = 800\nheight = 600\n\
+             [search]\npinned_apps = [\"a.desktop\", \"b.desktop\"]\n\
+             [search.providers]\norder = [\"x\"]\nmax_results = { x = 2 }\n",
+        );
+        merge_config(
+            &mut base,
+            parse(
+                "[window]\nwidth = 900\n\
+                 [search]\npinned_apps = [\"c.desktop\"]\n\
+                 [search.providers.max_results]\ny = 3\n",
+            ),
+        );
+        assert_eq!(
+            base,
+            parse(
+                "[window]\nwidth = 900\nheight = 600\n\
+                 [search]\npinned_apps = [\"c.desktop\"]\n\
+                 [search.providers]\norder = [\"x\"]\nmax_results = { x = 2, y = 3 }\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_merge_commands_by_name() {
+        let mut base = parse(
+            "[[commands]]\nname = \"build\"\ncommand = \"make\"\nkeep_open = false\n\
+             [[commands]]\nname = \"test\"\ncommand = \"make test\"\n",
+        );
+        merge_config(
+            &mut base,
+            parse(
+                "[[commands]]\nname = \"deploy\"\ncommand = \"./deploy\"\n\
+                 [[commands]]\nname = \"build\"\ncommand = \"cargo build\"\n",
+            ),
+        );
+        // A replaced command keeps its place but none of its old keys
+        assert_eq!(
+            base,
+            parse(
+                "[[commands]]\nname = \"build\"\ncommand = \"cargo build\"\n\
+                 [[commands]]\nname = \"test\"\ncommand = \"make test\"\n\
+                 [[commands]]\nname = \"deploy\"\ncommand = \"./deploy\"\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_merge_replaces_whole_snippets_and_web_searches() {
+        let mut base = parse(
+            "[snippets]\nsig = { file = \"sig.txt\" }\nhi = \"Hello\"\n\
+             [web_searches]\nddg = { url = \"https://duckduckgo.com/?q={}\", name = \"DDG\" }\n",
+        );
+        merge_config(
+            &mut base,
+            parse(
+                "[snippets]\nsig = \"-- Me\"\n\
+                 [web_searches]\nddg = { url = \"https://html.duckduckgo.com/?q={}\" }\n\
+                 gh = \"https://github.com/search?q={}\"\n",
+            ),
+        );
+        assert_eq!(
+            base,
+            parse(
+                "[snippets]\nsig = \"-- Me\"\nhi = \"Hello\"\n\
+                 [web_searches]\nddg = { url = \"https://html.duckduckgo.com/?q={}\" }\n\
+                 gh = \"https://github.com/search?q={}\"\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_includes_merge_in_order_under_the_main_file() {
+        let dir = fixture(
+            "order",
+            &[
+                (
+                    "grunner.toml",
+                    "include = [\"common.toml\", \"shared/work.toml\"]\n\
+                     [window]\nwidth = 1000\n",
+                ),
+                (
+                    "common.toml",
+                    "[window]\nwidth = 700\nheight = 500\n\
+                     [[commands]]\nname = \"build\"\ncommand = \"make\"\n\
+                     [[commands]]\nname = \"ssh\"\ncommand = \"ssh home\"\n",
+                ),
+                (
+                    "shared/work.toml",
+                    "[window]\nheight = 550\n\
+                     [[commands]]\nname = \"ssh\"\ncommand = \"ssh work\"\n",
+                ),
+            ],
+        );
+        let (table, warnings) = resolve_file(&dir.join("grunner.toml"));
+        assert!(warnings.is_empty(), "{warnings:?}");
+        assert_eq!(
+            table,
+            parse(
+                "include = [\"common.toml\", \"shared/work.toml\"]\n\
+                 [window]\nwidth = 1000\nheight = 550\n\
+                 [[commands]]\nname = \"build\"\ncommand = \"make\"\n\
+                 [[commands]]\nname = \"ssh\"\ncommand = \"ssh work\"\n",
+            )
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_nested_includes_are_relative_to_their_file() {
+        let dir = fixture(
+            "nested",
+            &[
+                ("grunner.toml", "include = [\"a/one.toml\"]\n"),
+                (
+                    "a/one.toml",
+                    "include = [\"two.toml\"]\n[ui]\nanimations = false\n",
+                ),
+                (
+                    "a/two.toml",
+                    "[ui]\nanimations = true\nshow_kind_badges = true\n",
+                ),
+            ],
+        );
+        let (table, warnings) = resolve_file(&dir.join("grunner.toml"));
+        assert!(warnings.is_empty(), "{warnings:?}");
+        assert_eq!(
+            table,
+            parse(
+                "include = [\"a/one.toml\"]\n\
+                 [ui]\nanimations = false\nshow_kind_badges = true\n",
+            )
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_include_depth_is_limited() {
+        let dir = fixture(
+            "depth",
+            &[
+                ("grunner.toml", "include = [\"1.toml\"]\n"),
+                ("1.toml", "include = [\"2.toml\"]\n[window]\nwidth = 1\n"),
+                ("2.toml", "include = [\"3.toml\"]\n[window]\nheight = 2\n"),
+                (
+                    "3.toml",
+                    "include = [\"4.toml\"]\n[ui]\nanimations = false\n",
+                ),
+                ("4.toml", "[ssh]\nknown_hosts = true\n"),
+            ],
+        );
+        let (table, warnings) = resolve_file(&dir.join("grunner.toml"));
+        assert_eq!(warnings.len(), 1, "{warnings:?}");
+        assert!(warnings[0].contains("4.toml"), "{warnings:?}");
+        assert_eq!(
+            table,
+            parse(
+                "include = [\"1.toml\"]\n\
+                 [ui]\nanimations = false\n[window]\nheight = 2\nwidth = 1\n",
+            )
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_include_cycles_and_missing_files_warn() {
+        let dir = fixture(
+            "cycle",
+            &[
+                (
+                    "grunner.toml",
+                    "include = [\"a.toml\", \"missing.toml\", \"broken.toml\"]\n",
+                ),
+                ("a.toml", "include = [\"b.toml\"]\n[window]\nwidth = 1\n"),
+                (
+                    "b.toml",
+                    "include = [\"a.toml\", \"grunner.toml\"]\n[window]\nheight = 2\n",
+                ),
+                ("broken.toml", "[window\n"),
+            ],
+        );
+        let (table, warnings) = resolve_file(&dir.join("grunner.toml"));
+        assert_eq!(warnings.len(), 4, "{warnings:?}");
+        assert!(warnings[0].contains("a.toml") && warnings[0].contains("cycle"));
+        assert!(warnings[1].contains("grunner.toml") && warnings[1].contains("cycle"));
+        assert!(warnings[2].contains("missing.toml") && warnings[2].contains("not found"));
+        assert!(warnings[3].contains("broken.toml"));
+        assert_eq!(
+            table,
+            parse(
+                "include = [\"a.toml\", \"missing.toml\", \"broken.toml\"]\n\
+                 [window]\nheight = 2\nwidth = 1\n",
+            )
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_include_files() {
+        let dir = fixture(
+            "files",
+            &[
+                (
+                    "grunner.toml",
+                    "include = [\"a.toml\", \"missing.toml\", \"b.toml\"]\n",
+                ),
+                ("a.toml", "include = [\"sub/c.toml\", \"b.toml\"]\n"),
+                ("b.toml", "include = [\"grunner.toml\"]\n"),
+                ("sub/c.toml", "[window]\nwidth = 1\n"),
+            ],
+        );
+        assert_eq!(
+            include_files(&dir.join("grunner.toml")),
+            vec![
+                dir.join("a.toml"),
+                dir.join("sub/c.toml"),
+                dir.join("b.toml"),
+                dir.join("missing.toml"),
+            ]
+        );
+        assert!(include_files(&dir.join("sub/c.toml")).is_empty());
+        assert!(include_files(&dir.join("none.toml")).is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_invalid_include_key_warns() {
+        let (table, warnings) = resolve_includes(
+            parse("include = \"a.toml\"\n"),
+            Path::new("/nonexistent/g.toml"),
+        );
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("expected a list"));
+        assert_eq!(table, parse("include = \"a.toml\"\n"));
+        assert_eq!(include_names(&table), None);
+        assert_eq!(
+            include_names(&parse("include = [\"a.toml\"]\n")),
+            Some(vec!["a.toml".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_include_path() {
+        assert_eq!(
+            include_path("work.toml", Path::new("/cfg/grunner.toml")),
+            Path::new("/cfg/work.toml")
+        );
+        assert_eq!(
+            include_path("/etc/grunner/common.toml", Path::new("/cfg/grunner.toml")),
+            Path::new("/etc/grunner/common.toml")
+        );
+        assert!(include_path("~/common.toml", Path::new("/cfg/grunner.toml")).is_absolute());
+    }
+}
@@ -0,0 +1,213 @@
+//! Obsidian settings resolved for use
+//!
+//! `ObsidianContext` is built once from the `[obsidian]` section each time
+//! the configuration is loaded. It expands the vault path, checks that note
+//! paths stay inside the vault and computes where each action reads or
+//! writes, so the actions, the command handler and the list rows all see the
+//! vault the same way.
+
+use crate::core::config::ObsidianConfig;
+use crate::utils::{expand_home, path_containment};
+use chrono::NaiveDateTime;
+use log::error;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The `[obsidian]` settings with the vault path expanded
+#[derive(Debug, Clone)]
+pub struct ObsidianContext {
+    config: ObsidianConfig,
+    /// `config.vault` with `~` expanded
+    vault: PathBuf,
+}
+
+impl ObsidianContext {
+    #[must_use]
+    pub fn new(config: ObsidianConfig) -> Self {
+        let vault = expand_home(&config.vault);
+        Self { config, vault }
+    }
+
+    /// The settings this context was built from
+    #[must_use]
+    pub fn config(&self) -> &ObsidianConfig {
+        &self.config
+    }
+
+    /// The vault directory, with `~` expanded
+    #[must_use]
+    pub fn vault(&self) -> &Path {
+        &self.vault
+    }
+
+    /// The vault directory if it exists, logging an error otherwise
+    ///
+    /// Checked on every call, since the vault may be created while the
+    /// launcher runs.
+    #[must_use]
+    pub fn existing_vault(&self) -> Option<&Path> {
+        if self.vault.exists() {
+            Some(&self.vault)
+        } else {
+            error!(
+                "Obsidian vault path does not exist: {}",
+                self.vault.display()
+            );
+            None
+        }
+    }
+
+    /// Name of the vault as Obsidian knows it, the last path component
+    #[must_use]
+    pub fn vault_name(&self) -> String {
+        self.vault
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    /// Resolve `relative` inside the vault
+    ///
+    /// # Errors
+    /// Returns an error if the path escapes the vault.
+    pub fn resolve(&self, relative: impl AsRef<Path>) -> io::Result<PathBuf> {
+        path_containment(&self.vault, relative.as_ref())
+    }
+
+    /// Check that every note path resolves inside the vault
+    ///
+    /// # Errors
+    /// Returns a message naming the first setting that escapes the vault.
+    pub fn check_note_paths(&self) -> Result<(), String> {
+        for (key, value) in self.config.note_paths() {
+            if let Err(e) = self.resolve(value) {
+                return Err(format!("Invalid obsidian.{key}: {e}"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Folders to leave out of `:obg` and `:ob` results, see
+    /// [`ObsidianConfig::excluded_folders`]
+    #[must_use]
+    pub fn excluded_folders(&self) -> Vec<String> {
+        self.config.excluded_folders(&self.vault)
+    }
+
+    /// Folder that new notes are created in
+    ///
+    /// # Errors
+    /// Returns an error if the folder escapes the vault.
+    pub fn new_notes_dir(&self) -> io::Result<PathBuf> {
+        self.resolve(&self.config.new_notes_folder)
+    }
+
+    /// Folder of the daily notes
+    ///
+    /// # Errors
+    /// Returns an error if the folder escapes the vault.
+    pub fn daily_notes_dir(&self) -> io::Result<PathBuf> {
+        self.resolve(&self.config.daily_notes_folder)
+    }
+
+    /// The quick note file
+    ///
+    /// # Errors
+    /// Returns an error if the file escapes the vault.
+    pub fn quick_note_path(&self) -> io::Result<PathBuf> {
+        self.resolve(&self.config.quick_note)
+    }
+
+    /// Path of a search result, which is absolute or relative to the vault
+    #[must_use]
+    pub fn result_path(&self, file_path: &str) -> PathBuf {
+        if file_path.starts_with('/') {
+            PathBuf::from(file_path)
+        } else {
+            self.vault.join(file_path)
+        }
+    }
+}
+
+/// File name of a note created at `now`
+#[must_use]
+pub fn new_note_file_name(now: &NaiveDateTime) -> String {
+    format!("New Note {}.md", now.format("%Y-%m-%d %H-%M-%S"))
+}
+
+/// File name of the daily note for the day of `now`
+#[must_use]
+pub fn daily_note_file_name(now: &NaiveDateTime) -> String {
+    format!("{}.md", now.format("%Y-%m-%d"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use std::fs;
+
+    fn config_for(vault: &str) -> ObsidianConfig {
+        ObsidianConfig {
+            vault: vault.to_string(),
+            daily_notes_folder: "Journal/Daily".to_string(),
+            new_notes_folder: "Inbox".to_string(),
+            quick_note: "Quick.md".to_string(),
+            allow_vault_outside_home: false,
+            grep_exclude_folders: None,
+        }
+    }
+
+    #[test]
+    fn test_context_expands_vault_once() {
+        let ctx = ObsidianContext::new(config_for("~/Notes/Work"));
+        assert_eq!(ctx.vault(), expand_home("~/Notes/Work"));
+        assert_eq!(ctx.vault_name(), "Work");
+        assert_eq!(ctx.config().vault, "~/Notes/Work");
+    }
+
+    #[test]
+    fn test_existing_vault_is_checked_on_each_call() {
+        let vault =
+            std::env::temp_dir().join(format!("grunner_test_obsidian_ctx_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&vault);
+        let ctx = ObsidianContext::new(config_for(&vault.to_string_lossy()));
+        assert!(ctx.existing_vault().is_none());
+        fs::create_dir_all(&vault).unwrap();
+        assert_eq!(ctx.existing_vault(), Some(vault.as_path()));
+        let _ = fs::remove_dir_all(&vault);
+    }
+
+    #[test]
+    fn test_action_paths() {
+        let ctx = ObsidianContext::new(config_for("/nonexistent/vault"));
+        let vault = Path::new("/nonexistent/vault");
+        assert_eq!(ctx.new_notes_dir().unwrap(), vault.join("Inbox"));
+        assert_eq!(ctx.daily_notes_dir().unwrap(), vault.join("Journal/Daily"));
+        assert_eq!(ctx.quick_note_path().unwrap(), vault.join("Quick.md"));
+        assert_eq!(ctx.result_path("Ideas.md"), vault.join("Ideas.md"));
+        assert_eq!(
+            ctx.result_path("/elsewhere/a.md"),
+            Path::new("/elsewhere/a.md")
+        );
+
+        let now = NaiveDate::from_ymd_opt(2026, 3, 7)
+            .and_then(|day| day.and_hms_opt(9, 5, 1))
+            .unwrap();
+        assert_eq!(new_note_file_name(&now), "New Note 2026-03-07 09-05-01.md");
+        assert_eq!(daily_note_file_name(&now), "2026-03-07.md");
+    }
+
+    #[test]
+    fn test_check_note_paths() {
+        let mut cfg = config_for("/nonexistent/vault");
+        assert!(ObsidianContext::new(cfg.clone()).check_note_paths().is_ok());
+
+        cfg.new_notes_folder = "Inbox/../../Outside".to_string();
+        let ctx = ObsidianContext::new(cfg);
+        let err = ctx.check_note_paths().unwrap_err();
+        assert!(err.contains("obsidian.new_notes_folder"));
+        assert!(ctx.new_notes_dir().is_err());
+    }
+}
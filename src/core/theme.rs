@@ -4,6 +4,7 @@
 //! It supports system themes, built-in themes, and custom user themes.
 
 use crate::core::config::ThemeMode;
+use crate::ui::compat::{ColorScheme, set_color_scheme};
 use crate::utils::expand_home;
 use gtk4::gdk;
 
@@ -22,49 +23,47 @@ impl ThemeManager {
     }
 
     pub fn apply(&self, mode: ThemeMode, custom_path: Option<&str>, display: &gdk::Display) {
-        let style_manager = libadwaita::StyleManager::default();
-
         let css_owned;
         let css: &str = match mode {
             ThemeMode::System => {
-                log::info!("Using system theme (libadwaita defaults)");
-                style_manager.set_color_scheme(libadwaita::ColorScheme::Default);
+                log::info!("Using system theme (desktop defaults)");
+                set_color_scheme(ColorScheme::Default);
                 return;
             }
             ThemeMode::SystemLight => {
-                style_manager.set_color_scheme(libadwaita::ColorScheme::ForceLight);
+                set_color_scheme(ColorScheme::ForceLight);
                 themes::LIGHT
             }
             ThemeMode::SystemDark => {
-                style_manager.set_color_scheme(libadwaita::ColorScheme::ForceDark);
+                set_color_scheme(ColorScheme::ForceDark);
                 themes::DARK
             }
             ThemeMode::TokioNight => {
-                style_manager.set_color_scheme(libadwaita::ColorScheme::ForceDark);
+                set_color_scheme(ColorScheme::ForceDark);
                 themes::TOKIO_NIGHT
             }
             ThemeMode::CatppuccinMocha => {
-                style_manager.set_color_scheme(libadwaita::ColorScheme::ForceDark);
+                set_color_scheme(ColorScheme::ForceDark);
                 themes::CATPPUCCIN_MOCHA
             }
             ThemeMode::CatppuccinLatte => {
-                style_manager.set_color_scheme(libadwaita::ColorScheme::ForceLight);
+                set_color_scheme(ColorScheme::ForceLight);
                 themes::CATPPUCCIN_LATTE
             }
             ThemeMode::Nord => {
-                style_manager.set_color_scheme(libadwaita::ColorScheme::ForceDark);
+                set_color_scheme(ColorScheme::ForceDark);
                 themes::NORD
             }
             ThemeMode::GruvboxDark => {
-                style_manager.set_color_scheme(libadwaita::ColorScheme::ForceDark);
+                set_color_scheme(ColorScheme::ForceDark);
                 themes::GRUVBOX_DARK
             }
             ThemeMode::GruvboxLight => {
-                style_manager.set_color_scheme(libadwaita::ColorScheme::ForceLight);
+                set_color_scheme(ColorScheme::ForceLight);
                 themes::GRUVBOX_LIGHT
             }
             ThemeMode::Dracula => {
-                style_manager.set_color_scheme(libadwaita::ColorScheme::ForceDark);
+                set_color_scheme(ColorScheme::ForceDark);
                 themes::DRACULA
             }
             ThemeMode::Custom => {
@@ -73,7 +72,7 @@ impl ThemeManager {
             }
         };
 
-        self.provider.load_from_data(css);
+        self.provider.load_from_string(css);
         log::info!("Loaded CSS provider with {} bytes", css.len());
         gtk4::style_context_remove_provider_for_display(display, &self.provider);
         gtk4::style_context_add_provider_for_display(
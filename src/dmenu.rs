@@ -0,0 +1,355 @@
+//! `--dmenu`: pick one of the lines read from standard input
+//!
+//! Like `dmenu`, `grunner --dmenu` shows the lines piped into it, filters
+//! them as the user types and prints the chosen one to standard output.
+//! Inputs can be very large (`find / | grunner --dmenu`), so nothing here
+//! is proportional to the input on the main thread:
+//!
+//! - Standard input is read on a worker thread by [`read_chunks`], which
+//!   hands over the first screenful as soon as it is read, then the rest in
+//!   chunks of [`CHUNK_LINES`].
+//! - The lines are kept in [`Lines`], the chunks as read, shared with the
+//!   filter workers without copying. Each query is scored against all of
+//!   them by [`filter_lines`], with rayon, and only the best `max_results`
+//!   become list rows.
+//! - The rows left out are counted in a last row, see [`more_lines_label`].
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use rayon::prelude::*;
+use std::io::BufRead;
+use std::sync::Arc;
+
+/// Lines handed from the reader to the window at a time
+pub const CHUNK_LINES: usize = 4096;
+
+/// Read the lines of `reader` and pass them to `send`: the first `first`
+/// lines, then chunks of at most `chunk` lines
+///
+/// Reading stops at the end of the input, on a read error, or when `send`
+/// returns false because the lines are no longer wanted. Invalid UTF-8 is
+/// replaced; line ends, including `\r\n`, are dropped. Returns the number
+/// of lines sent.
+pub fn read_chunks(
+    mut reader: impl BufRead,
+    first: usize,
+    chunk: usize,
+    mut send: impl FnMut(Vec<String>) -> bool,
+) -> usize {
+    let mut size = first.max(1);
+    let mut lines = Vec::with_capacity(size);
+    let mut buf = Vec::new();
+    let mut sent = 0;
+    loop {
+        buf.clear();
+        match reader.read_until(b'\n', &mut buf) {
+            Ok(0) => break,
+            Ok(_) => {
+                let line = buf.strip_suffix(b"\n").unwrap_or(&buf);
+                let line = line.strip_suffix(b"\r").unwrap_or(line);
+                lines.push(String::from_utf8_lossy(line).into_owned());
+            }
+            Err(e) => {
+                log::warn!("Stopped reading standard input: {e}");
+                break;
+            }
+        }
+        if lines.len() >= size {
+            size = chunk.max(1);
+            sent += lines.len();
+            if !send(std::mem::replace(&mut lines, Vec::with_capacity(chunk))) {
+                return sent;
+            }
+        }
+    }
+    if !lines.is_empty() {
+        sent += lines.len();
+        send(lines);
+    }
+    sent
+}
+
+/// The lines read so far, in the chunks they were read in
+///
+/// Chunks are never copied or changed once added: cloning `Lines` for a
+/// worker clones one `Arc` per chunk, and adding a chunk leaves the clones
+/// already handed out as they were.
+#[derive(Debug, Clone, Default)]
+pub struct Lines {
+    chunks: Vec<Arc<[String]>>,
+    /// Index of the first line of each chunk
+    starts: Vec<usize>,
+    len: usize,
+}
+
+impl Lines {
+    /// Add `chunk` after the lines already read
+    pub fn push(&mut self, chunk: Vec<String>) {
+        if chunk.is_empty() {
+            return;
+        }
+        self.starts.push(self.len);
+        self.len += chunk.len();
+        self.chunks.push(chunk.into());
+    }
+
+    /// Number of lines
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Line `index`, counting from the first line read
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&str> {
+        let chunk = self
+            .starts
+            .partition_point(|&start| start <= index)
+            .checked_sub(1)?;
+        self.chunks[chunk]
+            .get(index - self.starts[chunk])
+            .map(String::as_str)
+    }
+}
+
+/// Lines matching a query, best first
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Filtered {
+    /// Indices of the lines to show, at most the limit passed to
+    /// [`filter_lines`]
+    pub shown: Vec<usize>,
+    /// How many lines match in all, shown or not
+    pub matched: usize,
+}
+
+impl Filtered {
+    /// How many matching lines are not shown
+    #[must_use]
+    pub fn hidden(&self) -> usize {
+        self.matched - self.shown.len()
+    }
+}
+
+/// The lines of `lines` matching `query`, best first, keeping `limit`
+///
+/// An empty query matches every line, in input order. Otherwise lines are
+/// ranked by fuzzy score, ties in input order. `cancelled` is checked
+/// before each chunk is scored; once it returns true the remaining chunks
+/// are skipped and `None` is returned.
+#[must_use]
+pub fn filter_lines(
+    lines: &Lines,
+    query: &str,
+    matcher: &SkimMatcherV2,
+    limit: usize,
+    cancelled: &(dyn Fn() -> bool + Sync),
+) -> Option<Filtered> {
+    if query.is_empty() {
+        return Some(Filtered {
+            shown: (0..lines.len().min(limit)).collect(),
+            matched: lines.len(),
+        });
+    }
+    let mut scored: Vec<(i64, usize)> = lines
+        .chunks
+        .par_iter()
+        .zip(lines.starts.par_iter())
+        .flat_map_iter(|(chunk, &start)| {
+            let skip = if cancelled() { chunk.len() } else { 0 };
+            chunk
+                .iter()
+                .enumerate()
+                .skip(skip)
+                .filter_map(move |(i, line)| {
+                    matcher
+                        .fuzzy_match(line, query)
+                        .map(|score| (score, start + i))
+                })
+        })
+        .collect();
+    if cancelled() {
+        return None;
+    }
+    let matched = scored.len();
+    let best_first = |a: &(i64, usize), b: &(i64, usize)| b.0.cmp(&a.0).then(a.1.cmp(&b.1));
+    if scored.len() > limit && limit > 0 {
+        scored.select_nth_unstable_by(limit - 1, best_first);
+    }
+    scored.truncate(limit);
+    scored.sort_unstable_by(best_first);
+    Some(Filtered {
+        shown: scored.into_iter().map(|(_, i)| i).collect(),
+        matched,
+    })
+}
+
+/// Text of the last row while `hidden` matching lines are not shown,
+/// e.g. "… 99,950 more lines"
+#[must_use]
+pub fn more_lines_label(hidden: usize) -> String {
+    let digits = hidden.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    let noun = if hidden == 1 { "line" } else { "lines" };
+    format!("… {grouped} more {noun}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::FuzzyCase;
+    use crate::providers::matcher::build_matcher;
+
+    fn lines(text: &[&str]) -> Vec<String> {
+        text.iter().map(ToString::to_string).collect()
+    }
+
+    /// `text` read in chunks of two lines
+    fn chunked(text: &[&str]) -> Lines {
+        let mut all = Lines::default();
+        for chunk in text.chunks(2) {
+            all.push(lines(chunk));
+        }
+        all
+    }
+
+    fn filter(all: &Lines, query: &str, limit: usize) -> Filtered {
+        let matcher = build_matcher(FuzzyCase::Smart, 0);
+        filter_lines(all, query, &matcher, limit, &|| false).unwrap()
+    }
+
+    #[test]
+    fn test_read_chunks() {
+        let input = b"one\ntwo\r\nthr\xffee\n\nfive";
+        let mut chunks = Vec::new();
+        let sent = read_chunks(&input[..], 1, 2, |chunk| {
+            chunks.push(chunk);
+            true
+        });
+        assert_eq!(sent, 5);
+        assert_eq!(
+            chunks,
+            vec![
+                lines(&["one"]),
+                lines(&["two", "thr\u{fffd}ee"]),
+                lines(&["", "five"])
+            ]
+        );
+
+        // Stops once the lines are no longer wanted
+        let mut calls = 0;
+        let sent = read_chunks(&input[..], 2, 2, |_| {
+            calls += 1;
+            false
+        });
+        assert_eq!((sent, calls), (2, 1));
+    }
+
+    #[test]
+    fn test_lines_get_across_chunks() {
+        let mut all = chunked(&["a", "b", "c"]);
+        all.push(Vec::new());
+        all.push(lines(&["d"]));
+        assert_eq!(all.len(), 4);
+        let got: Vec<Option<&str>> = (0..5).map(|i| all.get(i)).collect();
+        assert_eq!(got, vec![Some("a"), Some("b"), Some("c"), Some("d"), None]);
+        assert_eq!(Lines::default().get(0), None);
+    }
+
+    #[test]
+    fn test_lines_clone_is_a_snapshot() {
+        let mut all = chunked(&["a", "b"]);
+        let snapshot = all.clone();
+        all.push(lines(&["c"]));
+        assert_eq!((snapshot.len(), all.len()), (2, 3));
+        assert!(Arc::ptr_eq(&snapshot.chunks[0], &all.chunks[0]));
+    }
+
+    #[test]
+    fn test_filter_lines() {
+        let all = chunked(&["firefox", "files", "thunderbird", "fire"]);
+
+        let empty = filter(&all, "", 3);
+        assert_eq!(empty.shown, vec![0, 1, 2]);
+        assert_eq!((empty.matched, empty.hidden()), (4, 1));
+
+        let fire = filter(&all, "fire", 10);
+        assert_eq!(fire.matched, 2);
+        assert!(fire.shown.contains(&0) && fire.shown.contains(&3));
+
+        let one = filter(&all, "fi", 1);
+        assert_eq!(one.shown.len(), 1);
+        assert_eq!(one.matched, 3);
+        assert!(filter(&all, "zzz", 10).shown.is_empty());
+    }
+
+    #[test]
+    fn test_filter_lines_ties_keep_input_order() {
+        let all = chunked(&["a1", "a2", "a3", "a4"]);
+        assert_eq!(filter(&all, "a", 3).shown, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_filter_lines_cancelled() {
+        let matcher = build_matcher(FuzzyCase::Smart, 0);
+        let all = chunked(&["a1", "a2", "a3"]);
+        assert_eq!(filter_lines(&all, "a", &matcher, 3, &|| true), None);
+    }
+
+    #[test]
+    fn test_more_lines_label() {
+        assert_eq!(more_lines_label(1), "… 1 more line");
+        assert_eq!(more_lines_label(950), "… 950 more lines");
+        assert_eq!(more_lines_label(99_950), "… 99,950 more lines");
+        assert_eq!(more_lines_label(1_234_567), "… 1,234,567 more lines");
+    }
+
+    #[test]
+    fn test_filter_100k_lines() {
+        let mut all = Lines::default();
+        let mut read = Vec::new();
+        for i in 0..100_000 {
+            read.push(format!("/usr/share/doc/package-{i}/changelog.txt"));
+            if read.len() == CHUNK_LINES {
+                all.push(std::mem::take(&mut read));
+            }
+        }
+        all.push(read);
+
+        let filtered = filter(&all, "package-99999/", 50);
+        assert_eq!(filtered.shown, vec![99_999]);
+        let filtered = filter(&all, "package-4242", 50);
+        assert_eq!(filtered.shown[0], 4242);
+        assert!(filtered.matched > 1);
+        assert_eq!(filter(&all, "", 50).hidden(), 99_950);
+
+        // A keystroke scores every line; the best of a few runs must stay
+        // under 100 ms, with room for unoptimized builds of this crate
+        let budget = if cfg!(debug_assertions) {
+            std::time::Duration::from_millis(400)
+        } else {
+            std::time::Duration::from_millis(100)
+        };
+        let elapsed = (0..3)
+            .map(|_| {
+                let start = std::time::Instant::now();
+                let filtered = filter(&all, "pack42chlog", 50);
+                let elapsed = start.elapsed();
+                assert_eq!(filtered.shown.len(), 50);
+                elapsed
+            })
+            .min()
+            .unwrap();
+        assert!(elapsed < budget, "scoring 100k lines took {elapsed:?}");
+    }
+}
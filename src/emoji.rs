@@ -0,0 +1,204 @@
+//! Emoji picker for the `:e` mode
+//!
+//! The emoji table comes from the `emojis` crate, a static array generated
+//! from the Unicode emoji list with CLDR names and GitHub shortcodes
+//! (`:joy:`, `:+1:`), which serve as keywords. Skin tone variants are left
+//! out.
+//!
+//! Picked emoji are remembered in `EmojiUsage`, a small JSON file in the
+//! user's cache directory, so the most recent ones lead the list of an
+//! empty `:e`. Like the usage history, recording is controlled by
+//! `[privacy] usage_stats`; when that is off nothing is recorded and the
+//! saved list is cleared.
+
+use crate::providers::ranking::abbreviation_score;
+use crate::utils::json_cache;
+use emojis::Emoji;
+use fuzzy_matcher::FuzzyMatcher;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Number of recently used emoji kept
+pub const MAX_RECENT_EMOJI: usize = 24;
+
+/// Recently picked emoji, most recent first
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmojiUsage {
+    #[serde(default)]
+    pub recent: Vec<String>,
+}
+
+impl EmojiUsage {
+    /// Load the usage from `path`
+    ///
+    /// A missing or unreadable file yields an empty list.
+    #[must_use]
+    pub fn load_from(path: &Path) -> Self {
+        json_cache::load(path, "emoji usage file")
+    }
+
+    /// Write the usage to `path`, clearing it first unless `usage_stats`
+    ///
+    /// # Errors
+    /// Returns an error if the directory cannot be created or the file
+    /// cannot be written.
+    pub fn save_to(&mut self, path: &Path, usage_stats: bool) -> std::io::Result<()> {
+        if !usage_stats {
+            self.recent.clear();
+        }
+        json_cache::store(path, self)
+    }
+
+    /// Move `glyph` to the front of the recent emoji
+    pub fn record(&mut self, glyph: &str) {
+        self.recent.retain(|recent| recent != glyph);
+        self.recent.insert(0, glyph.to_string());
+        self.recent.truncate(MAX_RECENT_EMOJI);
+    }
+}
+
+/// Second line of an emoji row: its shortcodes, e.g. `:+1: :thumbsup:`
+#[must_use]
+pub fn shortcodes(emoji: &Emoji) -> String {
+    emoji
+        .shortcodes()
+        .map(|code| format!(":{code}:"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The emoji matching `query`, best first
+///
+/// Every word has to match the name or a shortcode. An empty query lists
+/// the `recent` emoji first, then the others in Unicode order.
+#[must_use]
+pub fn match_emoji(
+    matcher: &impl FuzzyMatcher,
+    query: &str,
+    recent: &[String],
+    max: usize,
+) -> Vec<&'static Emoji> {
+    let words: Vec<&str> = query.split_whitespace().collect();
+    if words.is_empty() {
+        let recent_emoji = recent.iter().filter_map(|glyph| emojis::get(glyph));
+        let others = emojis::iter().filter(|e| !recent.iter().any(|r| r == e.as_str()));
+        return recent_emoji.chain(others).take(max).collect();
+    }
+    let mut scored: Vec<(i64, &'static Emoji)> = emojis::iter()
+        .filter_map(|emoji| {
+            words
+                .iter()
+                .map(|word| {
+                    let name = abbreviation_score(matcher, emoji.name(), word);
+                    let code = emoji
+                        .shortcodes()
+                        .filter_map(|code| abbreviation_score(matcher, code, word))
+                        .max();
+                    name.max(code)
+                })
+                .sum::<Option<i64>>()
+                .map(|score| (score, emoji))
+        })
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().take(max).map(|(_, e)| e).collect()
+}
+
+/// Path of the usage file: `$HOME/.cache/grunner/emoji.json`
+#[must_use]
+pub fn emoji_usage_path() -> PathBuf {
+    json_cache::path("emoji.json")
+}
+
+/// Load the usage from [`emoji_usage_path`]
+#[must_use]
+pub fn load_emoji_usage() -> EmojiUsage {
+    EmojiUsage::load_from(&emoji_usage_path())
+}
+
+/// Record that `glyph` was picked and save the usage
+///
+/// With `usage_stats` disabled nothing is recorded, and emoji left over
+/// from earlier runs are forgotten.
+pub fn record_emoji_use(glyph: &str, usage_stats: bool) {
+    let path = emoji_usage_path();
+    let mut usage = EmojiUsage::load_from(&path);
+    if usage_stats {
+        usage.record(glyph);
+    } else if usage.recent.is_empty() {
+        return;
+    }
+    debug!("Saving emoji usage to {}", path.display());
+    if let Err(e) = usage.save_to(&path, usage_stats) {
+        warn!("Failed to save emoji usage to {}: {e}", path.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuzzy_matcher::skim::SkimMatcherV2;
+
+    fn glyphs(emoji: &[&Emoji]) -> Vec<String> {
+        emoji.iter().map(|e| e.as_str().to_string()).collect()
+    }
+
+    #[test]
+    fn test_record_moves_to_front_and_caps() {
+        let mut usage = EmojiUsage::default();
+        usage.record("🎉");
+        usage.record("👍");
+        usage.record("🎉");
+        assert_eq!(usage.recent, ["🎉", "👍"]);
+
+        for emoji in emojis::iter().take(MAX_RECENT_EMOJI + 5) {
+            usage.record(emoji.as_str());
+        }
+        assert_eq!(usage.recent.len(), MAX_RECENT_EMOJI);
+    }
+
+    #[test]
+    fn test_save_applies_privacy() {
+        let path = std::env::temp_dir()
+            .join(format!("grunner_test_emoji_{}", std::process::id()))
+            .join("emoji.json");
+        let mut usage = EmojiUsage::default();
+        usage.record("🚀");
+        usage.save_to(&path, true).unwrap();
+        assert_eq!(EmojiUsage::load_from(&path).recent, ["🚀"]);
+
+        usage.save_to(&path, false).unwrap();
+        assert!(EmojiUsage::load_from(&path).recent.is_empty());
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_match_emoji_by_name_and_shortcode() {
+        let matcher = SkimMatcherV2::default();
+        let found = match_emoji(&matcher, "rocket", &[], 5);
+        assert_eq!(found.first().map(|e| e.as_str()), Some("🚀"));
+        // GitHub shortcodes work as keywords
+        let found = match_emoji(&matcher, "thumbsup", &[], 5);
+        assert_eq!(found.first().map(|e| e.as_str()), Some("👍"));
+        // Every word has to match
+        let found = match_emoji(&matcher, "red heart", &[], 5);
+        assert_eq!(found.first().map(|e| e.as_str()), Some("❤️"));
+        assert!(match_emoji(&matcher, "rocket zzzz", &[], 5).is_empty());
+        assert_eq!(match_emoji(&matcher, "face", &[], 3).len(), 3);
+    }
+
+    #[test]
+    fn test_empty_query_lists_recent_first() {
+        let matcher = SkimMatcherV2::default();
+        let recent = vec!["🚀".to_string(), "🎉".to_string()];
+        let found = match_emoji(&matcher, "", &recent, 4);
+        let found = glyphs(&found);
+        assert_eq!(&found[..2], ["🚀", "🎉"]);
+        assert_eq!(found[2], "😀");
+        // Recent emoji are not listed twice
+        let all = match_emoji(&matcher, " ", &recent, usize::MAX);
+        assert_eq!(all.iter().filter(|e| e.as_str() == "🚀").count(), 1);
+        assert_eq!(shortcodes(emojis::get("👍").unwrap()), ":+1: :thumbsup:");
+    }
+}
@@ -0,0 +1,219 @@
+//! First-seen times of desktop applications
+//!
+//! `FirstSeen` maps each desktop id to the Unix time at which a scan first
+//! found it. It is kept as a small JSON file in the user's cache directory,
+//! separate from the application cache, so rebuilding or bumping the cache
+//! does not make every application look newly installed.
+//!
+//! The empty query shows the applications first seen within the last
+//! [`RECENT_INSTALL_SECS`] as "Recently installed", newest first.
+
+use crate::launcher::DesktopApp;
+use crate::utils::json_cache;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// How long an application counts as recently installed
+pub const RECENT_INSTALL_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Most applications shown in the "Recently installed" group
+pub const MAX_RECENT_APPS: usize = 5;
+
+/// First-seen time per desktop id
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FirstSeen {
+    /// Unix time in seconds, or 0 for apps found by the first tracked scan
+    #[serde(default)]
+    pub apps: BTreeMap<String, u64>,
+}
+
+impl FirstSeen {
+    /// Load the first-seen times from `path`
+    ///
+    /// A missing or unreadable file yields an empty map.
+    #[must_use]
+    pub fn load_from(path: &Path) -> Self {
+        json_cache::load(path, "first-seen file")
+    }
+
+    /// Write the first-seen times to `path`
+    ///
+    /// # Errors
+    /// Returns an error if the directory cannot be created or the file
+    /// cannot be written.
+    pub fn save_to(&self, path: &Path) -> std::io::Result<()> {
+        json_cache::store(path, self)
+    }
+
+    /// Compare a scan with the previous one
+    ///
+    /// Ids not seen before are recorded at Unix time `now` and ids missing
+    /// from the scan are pruned. The first scan (an empty map) records its
+    /// ids at 0, so installing Grunner does not mark every application as
+    /// new. Returns whether anything changed.
+    pub fn update<'a>(&mut self, ids: impl IntoIterator<Item = &'a str>, now: u64) -> bool {
+        let first_scan = self.apps.is_empty();
+        let stamp = if first_scan { 0 } else { now };
+        let scanned: HashSet<&str> = ids.into_iter().collect();
+
+        let before = self.apps.len();
+        self.apps.retain(|id, _| scanned.contains(id.as_str()));
+        let mut changed = self.apps.len() != before;
+
+        for id in scanned {
+            if !self.apps.contains_key(id) {
+                self.apps.insert(id.to_string(), stamp);
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Applications first seen within `window` seconds of `now`, newest first
+    ///
+    /// At most [`MAX_RECENT_APPS`] are returned; apps found at the same time
+    /// keep the order of `apps`.
+    #[must_use]
+    pub fn recently_installed<'a>(
+        &self,
+        apps: &'a [DesktopApp],
+        now: u64,
+        window: u64,
+    ) -> Vec<&'a DesktopApp> {
+        let cutoff = now.saturating_sub(window);
+        let mut recent: Vec<(u64, &DesktopApp)> = apps
+            .iter()
+            .filter_map(|app| {
+                self.apps
+                    .get(&app.desktop_id)
+                    .filter(|&&seen| seen > 0 && seen >= cutoff)
+                    .map(|&seen| (seen, app))
+            })
+            .collect();
+        recent.sort_by_key(|(seen, _)| std::cmp::Reverse(*seen));
+        recent
+            .into_iter()
+            .take(MAX_RECENT_APPS)
+            .map(|(_, app)| app)
+            .collect()
+    }
+}
+
+/// Path of the first-seen file: `$HOME/.cache/grunner/first-seen.json`
+#[must_use]
+pub fn first_seen_path() -> PathBuf {
+    json_cache::path("first-seen.json")
+}
+
+/// Update the saved first-seen times with a scan and return them
+///
+/// The file is only rewritten when the scan added or removed applications.
+#[must_use]
+pub fn track_first_seen(apps: &[DesktopApp], now: u64) -> FirstSeen {
+    let path = first_seen_path();
+    let mut first_seen = FirstSeen::load_from(&path);
+    if first_seen.update(apps.iter().map(|app| app.desktop_id.as_str()), now) {
+        debug!("Saving first-seen times to {}", path.display());
+        if let Err(e) = first_seen.save_to(&path) {
+            warn!("Failed to save first-seen times {}: {e}", path.display());
+        }
+    }
+    first_seen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 24 * 60 * 60;
+
+    fn app(id: &str) -> DesktopApp {
+        DesktopApp {
+            desktop_id: id.to_string(),
+            name: id.to_string(),
+            name_lower: id.to_lowercase(),
+            exec: String::new(),
+            description: String::new(),
+            generic_name: String::new(),
+            keywords: Vec::new(),
+            icon: String::new(),
+            terminal: false,
+            actions: Vec::new(),
+            startup_wm_class: String::new(),
+            path: PathBuf::from(format!("/usr/share/applications/{id}.desktop")),
+        }
+    }
+
+    fn ids(apps: &[&DesktopApp]) -> Vec<String> {
+        apps.iter().map(|a| a.desktop_id.clone()).collect()
+    }
+
+    #[test]
+    fn test_first_scan_is_not_recent() {
+        let mut first_seen = FirstSeen::default();
+        assert!(first_seen.update(["firefox", "gimp"], 100 * DAY));
+        assert_eq!(first_seen.apps["firefox"], 0);
+
+        let apps = [app("firefox"), app("gimp")];
+        assert!(
+            first_seen
+                .recently_installed(&apps, 100 * DAY, RECENT_INSTALL_SECS)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_scan_diff_records_new_and_prunes_removed() {
+        let mut first_seen = FirstSeen::default();
+        first_seen.update(["firefox", "gimp"], 10 * DAY);
+
+        // Same scan again: nothing changes
+        assert!(!first_seen.update(["gimp", "firefox"], 11 * DAY));
+
+        // Inkscape installed, gimp removed
+        assert!(first_seen.update(["firefox", "inkscape"], 12 * DAY));
+        assert_eq!(first_seen.apps.get("inkscape"), Some(&(12 * DAY)));
+        assert!(!first_seen.apps.contains_key("gimp"));
+        assert_eq!(first_seen.apps["firefox"], 0);
+
+        // A reinstalled app counts as new again
+        assert!(first_seen.update(["firefox", "inkscape", "gimp"], 13 * DAY));
+        assert_eq!(first_seen.apps["gimp"], 13 * DAY);
+    }
+
+    #[test]
+    fn test_recently_installed_window_and_order() {
+        let mut first_seen = FirstSeen::default();
+        first_seen.update(["firefox"], 0);
+        first_seen.update(["firefox", "old"], DAY);
+        first_seen.update(["firefox", "old", "blender"], 20 * DAY);
+        first_seen.update(["firefox", "old", "blender", "krita"], 22 * DAY);
+
+        let apps = [app("blender"), app("firefox"), app("krita"), app("old")];
+        let recent = first_seen.recently_installed(&apps, 25 * DAY, RECENT_INSTALL_SECS);
+        assert_eq!(ids(&recent), vec!["krita", "blender"]);
+
+        // Past the window nothing is recent any more
+        let recent = first_seen.recently_installed(&apps, 40 * DAY, RECENT_INSTALL_SECS);
+        assert!(recent.is_empty());
+    }
+
+    #[test]
+    fn test_recently_installed_is_capped() {
+        let mut first_seen = FirstSeen::default();
+        first_seen.update(["base"], 0);
+        let names: Vec<String> = (0..=MAX_RECENT_APPS).map(|i| format!("app{i}")).collect();
+        for (i, stamp) in (0..names.len()).zip(DAY..) {
+            let mut scan: Vec<&str> = names[..=i].iter().map(String::as_str).collect();
+            scan.push("base");
+            first_seen.update(scan, stamp);
+        }
+
+        let apps: Vec<DesktopApp> = names.iter().map(|n| app(n)).collect();
+        let recent = first_seen.recently_installed(&apps, 2 * DAY, RECENT_INSTALL_SECS);
+        assert_eq!(recent.len(), MAX_RECENT_APPS);
+        assert_eq!(recent[0].desktop_id, format!("app{MAX_RECENT_APPS}"));
+    }
+}
@@ -0,0 +1,418 @@
+//! Local usage history for Grunner
+//!
+//! `LaunchHistory` is a small JSON file in the user's cache directory. It is
+//! only ever read and written locally and never transmitted anywhere.
+//!
+//! It records how often each colon mode (`:ob`, `:f`, …) led to an
+//! activation, which the `:stats` view shows as "Mode usage", and a decaying
+//! activation score ("frecency") per result that ranks frequently and
+//! recently used results higher. Recording is controlled by
+//! `[privacy] usage_stats`; when that is off nothing is recorded, and existing
+//! data is dropped on the next save.
+//!
+//! Scores are keyed by kind of result, so that each mode only looks up its
+//! own: `app:<desktop-id>` for applications (see [`app_key`]), `file:<path>`
+//! for `:f` files, `obsidian:<path in vault>` for `:ob` notes and
+//! `provider:<bus name>/<result id>` for search provider results.
+//!
+//! Every field uses a serde default so that files written by older versions
+//! (with fewer fields) keep loading. Launch scores written before results
+//! were keyed, per `.desktop` file path, are moved to their `app:` key on
+//! load.
+
+use crate::utils::json_cache;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Time after which a launch counts half as much towards frecency
+pub const FRECENCY_HALF_LIFE_SECS: u64 = 14 * 24 * 60 * 60;
+
+/// Records unused for this long are dropped when recording
+pub const PRUNE_AFTER_SECS: u64 = 90 * 24 * 60 * 60;
+
+/// Decaying activation score of one result
+///
+/// Every activation adds 1 to the score, and the score halves every
+/// [`FRECENCY_HALF_LIFE_SECS`], so an app used daily outranks one that was
+/// used a lot long ago.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct LaunchRecord {
+    /// Score at the time of the last launch
+    pub score: f64,
+    /// Unix time of the last launch, in seconds
+    pub last: u64,
+}
+
+/// Key of the application with the given desktop ID, e.g.
+/// `app:org.gnome.Nautilus`
+#[must_use]
+pub fn app_key(desktop_id: &str) -> String {
+    format!("app:{desktop_id}")
+}
+
+/// Key of a file listed by `:f`
+#[must_use]
+pub fn file_key(path: &str) -> String {
+    format!("file:{path}")
+}
+
+/// Key of a note listed by `:ob`, by its path within `vault`
+///
+/// Keyed relative to the vault so that moving the vault keeps its scores.
+/// `None` for a path outside the vault.
+#[must_use]
+pub fn obsidian_key(vault: &Path, path: &str) -> Option<String> {
+    let relative = Path::new(path).strip_prefix(vault).ok()?;
+    let relative = relative.to_str().filter(|r| !r.is_empty())?;
+    Some(format!("obsidian:{relative}"))
+}
+
+/// Key of the result `id` of the search provider at `bus_name`
+#[must_use]
+pub fn provider_key(bus_name: &str, id: &str) -> String {
+    format!("provider:{bus_name}/{id}")
+}
+
+/// Key of the application a launch score was recorded for by path, before
+/// scores were keyed: the desktop ID is the `.desktop` file's stem
+fn legacy_app_key(desktop_path: &str) -> Option<String> {
+    let stem = Path::new(desktop_path).file_stem()?.to_str()?;
+    Some(app_key(stem))
+}
+
+impl LaunchRecord {
+    /// The score decayed to Unix time `now`
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn frecency(&self, now: u64) -> f64 {
+        let elapsed = now.saturating_sub(self.last) as f64;
+        self.score * 0.5_f64.powf(elapsed / FRECENCY_HALF_LIFE_SECS as f64)
+    }
+
+    /// Both records as one, as of the later activation
+    #[must_use]
+    pub fn merge(self, other: Self) -> Self {
+        let last = self.last.max(other.last);
+        Self {
+            score: self.frecency(last) + other.frecency(last),
+            last,
+        }
+    }
+}
+
+/// Usage counters persisted between runs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LaunchHistory {
+    /// Activations per colon command name (`"ob"`, `"fg"`, …)
+    #[serde(default)]
+    pub modes: BTreeMap<String, u64>,
+    /// Activation scores per key, see [`app_key`] and the other key
+    /// functions
+    #[serde(default)]
+    pub activations: BTreeMap<String, LaunchRecord>,
+    /// Launch scores per `.desktop` file path, as written by older
+    /// versions; moved to `activations` on load
+    #[serde(default, skip_serializing)]
+    launches: BTreeMap<String, LaunchRecord>,
+}
+
+impl LaunchHistory {
+    /// Load the history from `path`
+    ///
+    /// A missing or unreadable file yields an empty history.
+    #[must_use]
+    pub fn load_from(path: &Path) -> Self {
+        let mut history: Self = json_cache::load(path, "history file");
+        history.migrate_launches();
+        history
+    }
+
+    /// Move launch scores recorded per `.desktop` path to their `app:` key
+    ///
+    /// A user's copy of a system `.desktop` file has the same desktop ID, so
+    /// the scores of both are merged.
+    fn migrate_launches(&mut self) {
+        for (path, record) in std::mem::take(&mut self.launches) {
+            let Some(key) = legacy_app_key(&path) else {
+                continue;
+            };
+            let merged = match self.activations.remove(&key) {
+                Some(existing) => existing.merge(record),
+                None => record,
+            };
+            self.activations.insert(key, merged);
+        }
+    }
+
+    /// Write the history to `path`, applying the privacy setting first
+    ///
+    /// With `usage_stats` disabled the mode counters and launch scores are
+    /// cleared before writing, so no usage data survives on disk.
+    ///
+    /// # Errors
+    /// Returns an error if the directory cannot be created or the file
+    /// cannot be written.
+    pub fn save_to(&mut self, path: &Path, usage_stats: bool) -> std::io::Result<()> {
+        self.apply_privacy(usage_stats);
+        json_cache::store(path, self)
+    }
+
+    /// Count one activation in the colon mode `mode`
+    pub fn record_mode(&mut self, mode: &str) {
+        *self.modes.entry(mode.to_string()).or_insert(0) += 1;
+    }
+
+    /// Count one activation of the result `key` at Unix time `now`
+    ///
+    /// Records unused for [`PRUNE_AFTER_SECS`] are dropped, so the file does
+    /// not grow with every result ever activated.
+    pub fn record(&mut self, key: &str, now: u64) {
+        self.activations
+            .retain(|_, record| now.saturating_sub(record.last) < PRUNE_AFTER_SECS);
+        let record = self.activations.entry(key.to_string()).or_default();
+        record.score = record.frecency(now) + 1.0;
+        record.last = now;
+    }
+
+    /// Frecency of the result `key` at Unix time `now`
+    #[must_use]
+    pub fn frecency(&self, key: &str, now: u64) -> f64 {
+        self.activations
+            .get(key)
+            .map_or(0.0, |record| record.frecency(now))
+    }
+
+    /// Mode counters, most used first (ties by name)
+    #[must_use]
+    pub fn mode_usage(&self) -> Vec<(&str, u64)> {
+        let mut usage: Vec<(&str, u64)> =
+            self.modes.iter().map(|(m, &n)| (m.as_str(), n)).collect();
+        usage.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        usage
+    }
+
+    /// Drop all usage data unless `usage_stats` is enabled
+    pub fn apply_privacy(&mut self, usage_stats: bool) {
+        if !usage_stats {
+            self.modes.clear();
+            self.activations.clear();
+        }
+    }
+
+    /// Whether nothing at all is recorded
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.modes.is_empty() && self.activations.is_empty()
+    }
+}
+
+/// Path of the history file: `$HOME/.cache/grunner/history.json`
+#[must_use]
+pub fn history_path() -> PathBuf {
+    json_cache::path("history.json")
+}
+
+/// Current Unix time in seconds
+#[must_use]
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Load the history from [`history_path`]
+#[must_use]
+pub fn load_history() -> LaunchHistory {
+    LaunchHistory::load_from(&history_path())
+}
+
+/// Apply `record` to the saved history and write it back
+///
+/// With `usage_stats` disabled nothing is recorded, and data left over from
+/// earlier runs is deleted. Returns the history as it is now on disk.
+fn update_history(usage_stats: bool, record: impl FnOnce(&mut LaunchHistory)) -> LaunchHistory {
+    let path = history_path();
+    let mut history = LaunchHistory::load_from(&path);
+    if usage_stats {
+        record(&mut history);
+    } else if history.is_empty() {
+        return history;
+    }
+    debug!("Saving history to {}", path.display());
+    if let Err(e) = history.save_to(&path, usage_stats) {
+        warn!("Failed to save history to {}: {e}", path.display());
+    }
+    history
+}
+
+/// Record an activation in colon mode `mode` and save the history
+///
+/// With `usage_stats` disabled nothing is counted, and counters left over
+/// from earlier runs are deleted.
+pub fn record_mode_activation(mode: &str, usage_stats: bool) {
+    update_history(usage_stats, |history| history.record_mode(mode));
+}
+
+/// Record an activation of the result `key` and save the history
+///
+/// Returns the updated history so the caller can rank with it right away.
+pub fn record_activation(key: &str, usage_stats: bool) -> LaunchHistory {
+    update_history(usage_stats, |history| history.record(key, now_secs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!(
+                "grunner_test_history_{name}_{}",
+                std::process::id()
+            ))
+            .join("history.json")
+    }
+
+    #[test]
+    fn test_mode_usage_aggregates_and_sorts() {
+        let mut history = LaunchHistory::default();
+        for mode in ["f", "ob", "f", "sh", "f", "ob"] {
+            history.record_mode(mode);
+        }
+        assert_eq!(history.mode_usage(), vec![("f", 3), ("ob", 2), ("sh", 1)]);
+
+        history.record_mode("sh");
+        // Ties are ordered by name
+        assert_eq!(history.mode_usage(), vec![("f", 3), ("ob", 2), ("sh", 2)]);
+    }
+
+    #[test]
+    fn test_disabled_usage_stats_deletes_counters_on_save() {
+        let path = temp_path("privacy");
+        let mut history = LaunchHistory::default();
+        history.record_mode("f");
+        history.save_to(&path, true).unwrap();
+
+        let mut history = LaunchHistory::load_from(&path);
+        history.save_to(&path, false).unwrap();
+        assert!(history.modes.is_empty());
+        assert!(LaunchHistory::load_from(&path).modes.is_empty());
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_frecency_decays_over_time() {
+        const DAY: u64 = 24 * 60 * 60;
+        let now = 1000 * DAY;
+        let mut history = LaunchHistory::default();
+        let (old, daily) = ("app:old", "app:daily");
+
+        // 200 launches 80 days ago
+        for _ in 0..200 {
+            history.record(old, now - 80 * DAY);
+        }
+        // One launch a day for the last two weeks
+        for day in (0..14).rev() {
+            history.record(daily, now - day * DAY);
+        }
+        assert!(history.frecency(daily, now) > history.frecency(old, now));
+        assert!(history.frecency(daily, now) > 1.0);
+
+        // A launch loses half its weight after one half-life
+        let mut single = LaunchHistory::default();
+        single.record(daily, now);
+        let later = single.frecency(daily, now + FRECENCY_HALF_LIFE_SECS);
+        assert!((later - 0.5).abs() < 1e-9);
+        assert!(single.frecency("app:unknown", now).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_record_drops_unused_records() {
+        const DAY: u64 = 24 * 60 * 60;
+        let mut history = LaunchHistory::default();
+        history.record("app:a", 0);
+        history.record("file:/b.txt", 10 * DAY);
+        history.record("file:/c.txt", 91 * DAY);
+        // Unused for 91 days: dropped; for 81 days: kept
+        assert_eq!(
+            history.activations.keys().collect::<Vec<_>>(),
+            ["file:/b.txt", "file:/c.txt"]
+        );
+    }
+
+    #[test]
+    fn test_keys() {
+        assert_eq!(app_key("org.gnome.Nautilus"), "app:org.gnome.Nautilus");
+        assert_eq!(file_key("/home/u/notes.txt"), "file:/home/u/notes.txt");
+        assert_eq!(
+            provider_key("org.gnome.Contacts.SearchProvider", "contact-3"),
+            "provider:org.gnome.Contacts.SearchProvider/contact-3"
+        );
+
+        let vault = Path::new("/home/u/vault");
+        assert_eq!(
+            obsidian_key(vault, "/home/u/vault/daily/2024-05-01.md").as_deref(),
+            Some("obsidian:daily/2024-05-01.md")
+        );
+        // Another vault sharing the name prefix is not inside this one
+        assert_eq!(obsidian_key(vault, "/home/u/vault2/a.md"), None);
+        assert_eq!(obsidian_key(vault, "/home/u/vault"), None);
+    }
+
+    #[test]
+    fn test_load_migrates_launches_by_path() {
+        let path = temp_path("migrate");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(
+            &path,
+            r#"{"modes":{"f":1},"launches":{
+                "/usr/share/applications/firefox.desktop":{"score":2.0,"last":100},
+                "/home/u/.local/share/applications/firefox.desktop":{"score":1.0,"last":100},
+                "/usr/share/applications/org.gnome.Nautilus.desktop":{"score":3.0,"last":50}
+            }}"#,
+        )
+        .unwrap();
+        let mut history = LaunchHistory::load_from(&path);
+        assert!((history.frecency("app:firefox", 100) - 3.0).abs() < 1e-9);
+        assert!((history.frecency("app:org.gnome.Nautilus", 50) - 3.0).abs() < 1e-9);
+        assert_eq!(history.mode_usage(), vec![("f", 1)]);
+
+        // Saved under the new keys only
+        history.save_to(&path, true).unwrap();
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(!saved.contains("launches"));
+        assert_eq!(LaunchHistory::load_from(&path), history);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_disabled_usage_stats_deletes_launches() {
+        let path = temp_path("launch_privacy");
+        let mut history = LaunchHistory::default();
+        history.record("app:a", 10);
+        history.save_to(&path, true).unwrap();
+        assert_eq!(LaunchHistory::load_from(&path).activations.len(), 1);
+
+        history.save_to(&path, false).unwrap();
+        assert!(LaunchHistory::load_from(&path).is_empty());
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_load_old_file() {
+        let path = temp_path("old_format");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        // A file without the modes map still loads
+        std::fs::write(&path, "{}").unwrap();
+        assert!(LaunchHistory::load_from(&path).modes.is_empty());
+        // So does one written before launches were recorded
+        std::fs::write(&path, r#"{"modes":{"f":2}}"#).unwrap();
+        let history = LaunchHistory::load_from(&path);
+        assert_eq!(history.mode_usage(), vec![("f", 2)]);
+        assert!(history.activations.is_empty());
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -4,18 +4,46 @@
 //! application. It contains the logic for determining what action to perform
 //! based on the item type and current application mode.
 
+use crate::actions::launch_failure::open_desktop_file;
+use crate::actions::workspace::focus_window;
 use crate::actions::{
-    launch_app, open_file_or_line, open_obsidian_file_line, open_obsidian_file_path,
-    perform_obsidian_action,
+    LaunchSource, check_action_path, create_vault, launch_app, open_file, open_obsidian_file_line,
+    open_obsidian_file_path, open_terminal_in, open_uri, perform_obsidian_action, run_detached,
+    run_in_terminal, show_error_notification,
 };
 use crate::app_mode::AppMode;
-use crate::core::config::ObsidianConfig;
-use crate::model::items::{AppItem, CommandItem, ObsidianActionItem, SearchResultItem};
+use crate::autostart;
+use crate::clipboard_history::pause_capture;
+use crate::command_handler::{AppCommandHandler, colon_mode};
+use crate::command_safety::CommandGuard;
+use crate::core::config::ProjectFallback;
+use crate::core::global_state::get_home_dir;
+use crate::core::obsidian::ObsidianContext;
+use crate::history;
+use crate::model::command_row::{CommandKind, CommandPayload};
+use crate::model::items::{
+    AppActionItem, AppItem, BookmarkItem, ClipboardItem, ColonCommandItem, CommandItem, EmojiItem,
+    GrunnerAction, GrunnerActionItem, ManPageItem, ObsidianAction, ObsidianActionItem,
+    PassEntryItem, ProcessItem, ProjectItem, SearchResultItem, SnippetItem, SshHostItem,
+    SuggestionItem, TerminalCommandItem, UnitItem, UrlItem, VaultSetupItem, WindowItem,
+};
 use crate::model::list_model::AppListModel;
+use crate::processes::{self, KillSignal};
 use crate::providers::dbus;
-use crate::utils::is_calculator_result;
-use gtk4::prelude::{Cast, DisplayExt};
+use crate::providers::pass::second_line;
+use crate::providers::snippets::expand_placeholders;
+use crate::providers::subprocess::find_program;
+use crate::units::{self, UnitAction};
+use crate::utils::clipboard::copy_text;
+use crate::window_switcher;
+use gtk4::gio;
+use gtk4::prelude::{Cast, FileExt};
 use log::{debug, info, warn};
+use std::ffi::OsStr;
+use std::time::Duration;
+
+/// How long the clipboard history stays paused after `pass show -c` exits
+const PASS_COPY_GRACE: Duration = Duration::from_secs(1);
 
 // ─── Activation Context ────────────────────────────────────────────────────────
 
@@ -36,8 +64,42 @@ impl<'a> ActivationContext<'a> {
     }
 
     #[must_use]
-    pub fn obsidian_config(&self) -> Option<&'a ObsidianConfig> {
-        self.model.config.obsidian_cfg.as_ref()
+    pub fn obsidian(&self) -> Option<std::rc::Rc<ObsidianContext>> {
+        self.model.obsidian()
+    }
+}
+
+// ─── After Activation ──────────────────────────────────────────────────────────
+
+/// What the window does once an item is activated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AfterActivation {
+    /// Hide the window, as for a launched app
+    Close,
+    /// Keep the window open
+    StayOpen,
+    /// Keep the window open and say that a text was copied
+    Copied,
+}
+
+impl AfterActivation {
+    /// After copying a text: close, unless `[launch] close_on_copy` is off
+    #[must_use]
+    pub fn after_copy(close_on_copy: bool) -> Self {
+        if close_on_copy {
+            Self::Close
+        } else {
+            Self::Copied
+        }
+    }
+
+    /// The same activation with Shift+Enter, which never closes the window
+    #[must_use]
+    pub fn staying_open(self) -> Self {
+        match self {
+            Self::Close => Self::StayOpen,
+            other => other,
+        }
     }
 }
 
@@ -46,9 +108,27 @@ impl<'a> ActivationContext<'a> {
 /// Enum representing all item types in Grunner
 pub enum GrunnerItem<'a> {
     App(&'a AppItem),
+    AppAction(&'a AppActionItem),
+    Bookmark(&'a BookmarkItem),
+    Clipboard(&'a ClipboardItem),
+    ColonCommand(&'a ColonCommandItem),
     Command(&'a CommandItem),
+    Emoji(&'a EmojiItem),
+    GrunnerAction(&'a GrunnerActionItem),
+    ManPage(&'a ManPageItem),
     ObsidianAction(&'a ObsidianActionItem),
+    Process(&'a ProcessItem),
+    Project(&'a ProjectItem),
     SearchResult(&'a SearchResultItem),
+    Snippet(&'a SnippetItem),
+    PassEntry(&'a PassEntryItem),
+    SshHost(&'a SshHostItem),
+    Suggestion(&'a SuggestionItem),
+    TerminalCommand(&'a TerminalCommandItem),
+    Unit(&'a UnitItem),
+    Url(&'a UrlItem),
+    VaultSetup(&'a VaultSetupItem),
+    Window(&'a WindowItem),
 }
 
 impl<'a> GrunnerItem<'a> {
@@ -56,13 +136,48 @@ impl<'a> GrunnerItem<'a> {
     pub fn from_object(obj: &'a glib::Object) -> Option<Self> {
         if let Some(item) = obj.downcast_ref::<AppItem>() {
             Some(GrunnerItem::App(item))
+        } else if let Some(item) = obj.downcast_ref::<AppActionItem>() {
+            Some(GrunnerItem::AppAction(item))
+        } else if let Some(item) = obj.downcast_ref::<BookmarkItem>() {
+            Some(GrunnerItem::Bookmark(item))
+        } else if let Some(item) = obj.downcast_ref::<ClipboardItem>() {
+            Some(GrunnerItem::Clipboard(item))
+        } else if let Some(item) = obj.downcast_ref::<ColonCommandItem>() {
+            Some(GrunnerItem::ColonCommand(item))
         } else if let Some(item) = obj.downcast_ref::<CommandItem>() {
             Some(GrunnerItem::Command(item))
+        } else if let Some(item) = obj.downcast_ref::<EmojiItem>() {
+            Some(GrunnerItem::Emoji(item))
+        } else if let Some(item) = obj.downcast_ref::<GrunnerActionItem>() {
+            Some(GrunnerItem::GrunnerAction(item))
+        } else if let Some(item) = obj.downcast_ref::<ManPageItem>() {
+            Some(GrunnerItem::ManPage(item))
         } else if let Some(item) = obj.downcast_ref::<ObsidianActionItem>() {
             Some(GrunnerItem::ObsidianAction(item))
+        } else if let Some(item) = obj.downcast_ref::<ProcessItem>() {
+            Some(GrunnerItem::Process(item))
+        } else if let Some(item) = obj.downcast_ref::<ProjectItem>() {
+            Some(GrunnerItem::Project(item))
+        } else if let Some(item) = obj.downcast_ref::<SearchResultItem>() {
+            Some(GrunnerItem::SearchResult(item))
+        } else if let Some(item) = obj.downcast_ref::<SnippetItem>() {
+            Some(GrunnerItem::Snippet(item))
+        } else if let Some(item) = obj.downcast_ref::<PassEntryItem>() {
+            Some(GrunnerItem::PassEntry(item))
+        } else if let Some(item) = obj.downcast_ref::<SshHostItem>() {
+            Some(GrunnerItem::SshHost(item))
+        } else if let Some(item) = obj.downcast_ref::<SuggestionItem>() {
+            Some(GrunnerItem::Suggestion(item))
+        } else if let Some(item) = obj.downcast_ref::<TerminalCommandItem>() {
+            Some(GrunnerItem::TerminalCommand(item))
+        } else if let Some(item) = obj.downcast_ref::<UnitItem>() {
+            Some(GrunnerItem::Unit(item))
+        } else if let Some(item) = obj.downcast_ref::<UrlItem>() {
+            Some(GrunnerItem::Url(item))
+        } else if let Some(item) = obj.downcast_ref::<VaultSetupItem>() {
+            Some(GrunnerItem::VaultSetup(item))
         } else {
-            obj.downcast_ref::<SearchResultItem>()
-                .map(GrunnerItem::SearchResult)
+            obj.downcast_ref::<WindowItem>().map(GrunnerItem::Window)
         }
     }
 }
@@ -76,74 +191,369 @@ fn activate_app(item: &AppItem) {
         item.terminal()
     );
     let did = item.desktop_id();
-    let desktop_id = if did.is_empty() {
-        None
-    } else {
-        Some(did.as_str())
+    let source = LaunchSource {
+        name: item.name(),
+        desktop_id: (!did.is_empty()).then_some(did),
+        desktop_file: Some(item.path()),
     };
-    launch_app(&item.exec(), item.terminal(), None, desktop_id);
+    launch_app(&item.exec(), item.terminal(), None, &source);
 }
 
-fn activate_command(item: &CommandItem, ctx: &ActivationContext) {
-    let line = item.line();
-    debug!(
-        "Activating command line item: {line} in mode {:?}",
-        ctx.mode
+/// Focus the open window of a running application
+///
+/// Falls back to launching the application if the window is gone.
+fn focus_running_app(item: &AppItem, window: u32) {
+    info!(
+        "Focusing window {window} of running application {}",
+        item.name()
+    );
+    let item = item.clone();
+    glib::spawn_future_local(async move {
+        if !focus_window(window).await {
+            activate_app(&item);
+        }
+    });
+}
+
+/// Run a desktop action with the `Terminal=` setting of its application
+///
+/// The action's own command is run, so no desktop id is passed: launching
+/// through the desktop entry would start the application instead. The
+/// `.desktop` file is still offered if the action fails to start.
+fn activate_app_action(item: &AppActionItem) {
+    info!(
+        "Launching action {:?} of {}: {}",
+        item.name(),
+        item.app_name(),
+        item.exec()
     );
+    let source = LaunchSource {
+        name: format!("{}: {}", item.app_name(), item.name()),
+        desktop_id: None,
+        desktop_file: Some(item.path()),
+    };
+    launch_app(&item.exec(), item.terminal(), None, &source);
+}
 
-    if is_calculator_result(&line) {
-        if let Some((_expr, result)) = line.split_once('=') {
-            let result_text = result.trim().to_string();
-            info!("Copying calculator result to clipboard: {result_text}");
-            if let Some(display) = gtk4::gdk::Display::default() {
-                let clipboard = display.clipboard();
-                clipboard.set_text(&result_text);
+fn activate_bookmark(item: &BookmarkItem) {
+    let url = item.url();
+    info!("Opening bookmark: {url}");
+    if let Err(e) = open_uri(&url) {
+        show_error_notification(&format!("Failed to open {url}: {e}"));
+    }
+}
+
+/// Open a `:proj` folder in the IDE that last opened it
+///
+/// Without an IDE, or if its command is not installed, the folder goes to
+/// the file manager or a terminal, as set by `[projects] open_with`.
+fn activate_project(item: &ProjectItem, model: &AppListModel) {
+    let path = item.path();
+    let dir = path.to_string_lossy();
+    if let Some(opener) = item.opener().filter(|cmd| find_program(cmd).is_some()) {
+        info!("Opening project {dir} with {opener}");
+        let quoted = glib::shell_quote(path.as_os_str());
+        run_detached(&format!("{opener} {}", quoted.to_string_lossy()));
+        return;
+    }
+    match model.config.project_open_with.get() {
+        ProjectFallback::Terminal => open_terminal_in(&dir),
+        ProjectFallback::FileManager => {
+            info!("Opening project {dir} in the file manager");
+            let uri = gtk4::gio::File::for_path(&path).uri();
+            if let Err(e) = open_uri(&uri) {
+                show_error_notification(&format!("Failed to open {dir}: {e}"));
             }
         }
+    }
+}
+
+/// Connect to a `:ssh` host in the terminal emulator
+fn activate_ssh_host(item: &SshHostItem) {
+    let args: Vec<String> = item
+        .ssh_args()
+        .iter()
+        .map(|arg| glib::shell_quote(arg).to_string_lossy().into_owned())
+        .collect();
+    info!("Connecting to SSH host {}", item.alias());
+    run_in_terminal(&format!("ssh {}", args.join(" ")));
+}
+
+/// Open the page of a `:man` row with `man` in the terminal emulator
+///
+/// The launcher's own viewer is used for Enter; this is the Ctrl+Enter
+/// alternative and the fallback when the page cannot be rendered.
+pub fn open_man_page_in_terminal(item: &ManPageItem) {
+    let args: Vec<String> = item
+        .page()
+        .man_args()
+        .iter()
+        .map(|arg| glib::shell_quote(arg).to_string_lossy().into_owned())
+        .collect();
+    info!("Opening manual page {} in the terminal", item.title());
+    run_in_terminal(&format!("man {}", args.join(" ")));
+}
+
+/// Open the folder of the file at `path` in the file manager
+fn open_containing_folder(path: &std::path::Path) {
+    let Some(dir) = path.parent() else {
         return;
+    };
+    info!("Opening the folder of {}", path.display());
+    let uri = gtk4::gio::File::for_path(dir).uri();
+    if let Err(e) = open_uri(&uri) {
+        show_error_notification(&format!("Failed to open {}: {e}", dir.display()));
     }
+}
 
-    match ctx.mode {
-        AppMode::ObsidianGrep => {
-            if let Some(cfg) = ctx.obsidian_config() {
-                open_obsidian_grep_line(&line, cfg);
-            } else {
-                warn!("Obsidian configuration missing for grep line activation");
-            }
+/// Start, stop or restart the systemd unit of a `:unit` row
+///
+/// The job is queued in the background; systemd may ask for authorization
+/// first. A refused job is reported as a notification, since the window
+/// is already closed by then.
+fn control_unit(item: &UnitItem, action: UnitAction) {
+    let unit = item.unit();
+    info!("Asking systemd to {} {}", action.verb(), unit.name);
+    glib::spawn_future_local(async move {
+        if let Err(e) = units::run_action(&unit, action).await {
+            warn!("Could not {} {}: {e}", action.verb(), unit.name);
+            show_error_notification(&format!("Could not {} {}: {e}", action.verb(), unit.name));
         }
-        AppMode::Obsidian => {
-            if let Some(cfg) = ctx.obsidian_config() {
-                open_obsidian_file_path(&line, cfg);
-            } else {
-                warn!("Obsidian configuration missing for file activation");
+    });
+}
+
+fn activate_url(item: &UrlItem) {
+    let url = item.url();
+    info!("Opening URL: {url}");
+    if let Err(e) = open_uri(&url) {
+        show_error_notification(&format!("Failed to open {url}: {e}"));
+    }
+}
+
+/// Copy a `:snip` snippet, with `{date}` and `{time}` filled in
+fn activate_snippet(item: &SnippetItem) {
+    info!("Copying snippet {}", item.name());
+    let now = chrono::Local::now().naive_local();
+    copy_text(&expand_placeholders(&item.text(), &now));
+}
+
+/// Copy the password of a `:pw` entry with `pass show -c`
+///
+/// `pass` decrypts it straight into the clipboard and clears it again
+/// after 45 seconds, so the secret never goes through Grunner. The
+/// clipboard history is paused meanwhile, and for a moment after `pass`
+/// exits since the change is reported asynchronously, so it is not kept.
+fn activate_pass_entry(item: &PassEntryItem) {
+    let name = item.name();
+    info!("Copying the password of {name}");
+    glib::spawn_future_local(async move {
+        let pause = pause_capture();
+        let argv = [
+            OsStr::new("pass"),
+            OsStr::new("show"),
+            OsStr::new("-c"),
+            OsStr::new("--"),
+            OsStr::new(&name),
+        ];
+        let copied = match gio::Subprocess::newv(&argv, gio::SubprocessFlags::STDOUT_SILENCE) {
+            Ok(process) => process.wait_check_future().await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = copied {
+            warn!("pass show -c {name} failed: {e}");
+            show_error_notification(&format!("Could not copy the password of {name}"));
+        }
+        glib::timeout_future(PASS_COPY_GRACE).await;
+        drop(pause);
+    });
+}
+
+/// Copy the second line of a `:pw` entry, usually the user name, as
+/// Shift+Enter does
+///
+/// Returns `false` if `obj` is not a password store row.
+pub fn copy_pass_login(obj: &glib::Object, model: &AppListModel) -> bool {
+    let Some(item) = obj.downcast_ref::<PassEntryItem>() else {
+        return false;
+    };
+    let name = item.name();
+    info!("Copying the second line of {name}");
+    glib::spawn_future_local(async move {
+        let argv = [
+            OsStr::new("pass"),
+            OsStr::new("show"),
+            OsStr::new("--"),
+            OsStr::new(&name),
+        ];
+        let output = match gio::Subprocess::newv(&argv, gio::SubprocessFlags::STDOUT_PIPE) {
+            Ok(process) => process
+                .communicate_utf8_future(None)
+                .await
+                .map(|(stdout, _)| process.is_successful().then_some(stdout).flatten()),
+            Err(e) => Err(e),
+        };
+        match output {
+            Ok(Some(stdout)) => match second_line(&stdout) {
+                Some(line) => copy_text(line),
+                None => show_error_notification(&format!("{name} has no second line")),
+            },
+            Ok(None) => show_error_notification(&format!("Could not read {name}")),
+            Err(e) => {
+                warn!("pass show {name} failed: {e}");
+                show_error_notification(&format!("Could not read {name}"));
             }
         }
-        AppMode::CustomScript => {
-            let command_to_run = if let Some((_name, cmd)) = line.split_once(" | ") {
-                cmd.trim()
-            } else if let Some(stripped) = line.strip_prefix("Run: ") {
-                stripped.trim()
-            } else {
-                line.trim()
-            };
+    });
+    record_mode_usage(model);
+    true
+}
 
-            if !command_to_run.is_empty() {
-                info!("Executing custom script command: {command_to_run}");
-                let working_dir = item.working_dir();
-                let keep_open = item.keep_open();
+fn activate_clipboard(item: &ClipboardItem, model: &AppListModel) -> AfterActivation {
+    info!("Copying clipboard history entry back to the clipboard");
+    copy_text(&item.text());
+    AfterActivation::after_copy(model.config.close_on_copy.get())
+}
 
-                let final_command = if keep_open {
-                    format!("{command_to_run}; exec $SHELL")
-                } else {
-                    command_to_run.to_string()
-                };
+/// Focus an open window of the `:w` mode
+///
+/// Backends block on D-Bus or Wayland, so the window is focused from a
+/// worker thread while the launcher closes.
+fn activate_window(item: &WindowItem) {
+    let Some(backend) = window_switcher::backend() else {
+        warn!("No window backend to focus {:?}", item.title());
+        return;
+    };
+    let window = item.window();
+    info!(
+        "Focusing window {:?} through {}",
+        window.title,
+        backend.name()
+    );
+    std::thread::spawn(move || {
+        if !backend.focus(&window) {
+            warn!("Could not focus window {:?}", window.title);
+        }
+    });
+}
 
-                launch_app(&final_command, true, working_dir, None);
-            }
+/// Copy an emoji of the `:e` mode and remember it as recently used
+fn activate_emoji(item: &EmojiItem, model: &AppListModel) {
+    let glyph = item.glyph();
+    info!("Copying emoji {glyph} ({})", item.name());
+    copy_text(&glyph);
+    crate::emoji::record_emoji_use(&glyph, model.config.usage_stats.get());
+}
+
+/// Send `signal` to the process of a `:kill` row
+///
+/// The process is checked first: if it has exited, or its PID now belongs
+/// to another process, nothing is signalled and an error row replaces the
+/// results. Returns whether the signal was sent.
+pub fn signal_process(item: &ProcessItem, signal: KillSignal, model: &AppListModel) -> bool {
+    let process = item.process();
+    match processes::kill(&process, signal) {
+        Ok(()) => {
+            info!(
+                "Sent {} to {} ({})",
+                signal.name(),
+                process.name,
+                process.pid
+            );
+            record_mode_usage(model);
+            true
+        }
+        Err(e) => {
+            warn!(
+                "Could not send {} to {} ({}): {e}",
+                signal.name(),
+                process.name,
+                process.pid
+            );
+            AppCommandHandler::new(model.clone()).show_error(format!(
+                "Could not stop {} ({}): {e}",
+                process.name, process.pid
+            ));
+            false
+        }
+    }
+}
+
+fn activate_command(item: &CommandItem, ctx: &ActivationContext) -> AfterActivation {
+    let row = item.row();
+    debug!("Activating {:?} command row: {}", row.kind, row.name);
+
+    if !item.guard().may_run() {
+        warn!(
+            "Not running destructive command without confirmation: {}",
+            row.description
+        );
+        return AfterActivation::Close;
+    }
+
+    match (row.kind, &row.payload) {
+        (CommandKind::Stats, _) => debug!("Statistics rows have no action"),
+        (CommandKind::Calculator, CommandPayload::Text(value)) => {
+            info!("Copying calculator result to clipboard: {value}");
+            copy_text(value);
+            return AfterActivation::after_copy(ctx.model.config.close_on_copy.get());
+        }
+        (CommandKind::Message, CommandPayload::Text(text)) => {
+            info!("Copying message to clipboard: {text}");
+            copy_text(text);
+        }
+        (CommandKind::Shell, CommandPayload::Text(command)) => run_shell_command(item, command),
+        (CommandKind::Note, payload) => open_note(payload, ctx),
+        (CommandKind::File, CommandPayload::Path(path)) => {
+            open_file(path, None);
+            record_activation(&history::file_key(&path.to_string_lossy()), ctx.model);
         }
-        _ => {
-            open_file_or_line(&line);
+        (CommandKind::File, CommandPayload::PathLine { path, line, column }) => {
+            open_file(path, Some((*line, *column)));
         }
+        (kind, payload) => warn!("No action for a {kind:?} row of {payload:?}"),
+    }
+    AfterActivation::Close
+}
+
+/// Run the command of a `:sh` row in the terminal, in its working directory
+fn run_shell_command(item: &CommandItem, command: &str) {
+    if command.is_empty() {
+        return;
+    }
+    info!("Executing custom script command: {command}");
+    let final_command = if item.keep_open() {
+        format!("{command}; exec $SHELL")
+    } else {
+        command.to_string()
+    };
+    launch_app(
+        &final_command,
+        true,
+        item.working_dir(),
+        &LaunchSource::default(),
+    );
+}
+
+/// Open the note of a `:ob` row, or a `:obg` match at its line, in Obsidian
+fn open_note(payload: &CommandPayload, ctx: &ActivationContext) {
+    let Some(obsidian) = ctx.obsidian() else {
+        warn!("Obsidian configuration missing for note activation");
+        return;
+    };
+    match payload {
+        CommandPayload::PathLine { path, line, .. } => {
+            let path = path.to_string_lossy();
+            info!("Opening Obsidian file at line: {path}:{line}");
+            open_obsidian_file_line(&path, &line.to_string(), &obsidian);
+        }
+        CommandPayload::Path(path) => {
+            let path = path.to_string_lossy();
+            open_obsidian_file_path(&path, &obsidian);
+            if let Some(key) = history::obsidian_key(obsidian.vault(), &path) {
+                record_activation(&key, ctx.model);
+            }
+        }
+        CommandPayload::Text(text) => warn!("Note row without a path: {text}"),
     }
 }
 
@@ -153,8 +563,8 @@ fn activate_obsidian_action(item: &ObsidianActionItem, ctx: &ActivationContext)
         item.action(),
         item.arg()
     );
-    if let Some(cfg) = ctx.obsidian_config() {
-        perform_obsidian_action(item.action(), item.arg().as_deref(), cfg);
+    if let Some(obsidian) = ctx.obsidian() {
+        perform_obsidian_action(item.action(), item.arg().as_deref(), &obsidian);
     } else {
         warn!("Obsidian configuration missing for action activation");
     }
@@ -163,45 +573,29 @@ fn activate_obsidian_action(item: &ObsidianActionItem, ctx: &ActivationContext)
 fn activate_search_result(item: &SearchResultItem, ctx: &ActivationContext) {
     let (bus, path, id, terms) = (item.bus_name(), item.object_path(), item.id(), item.terms());
 
-    if let Some(text) = item.clipboard_text()
-        && let Some(display) = gtk4::gdk::Display::default()
-    {
-        let clipboard = display.clipboard();
-        clipboard.set_text(&text);
+    // Results with a clipboard text are copied as well as activated, as in
+    // GNOME Shell
+    if let Some(text) = item.clipboard_text() {
+        copy_text(&text);
         info!("Copied '{text}' to clipboard from search result activation");
     }
 
     info!("Activating search result: {id} from provider {bus}");
+    record_activation(&history::provider_key(&bus, &id), ctx.model);
     let timestamp = ctx.timestamp;
     std::thread::spawn(move || {
         dbus::activate_result(&bus, &path, &id, &terms, timestamp);
     });
 }
 
-// ─── Legacy API (for backwards compatibility) ─────────────────────────────────
-
-/// Parse and open Obsidian grep result lines
-///
-/// This function handles grep output lines in the format `<file:line:context>`
-/// and opens them in Obsidian at the appropriate line number.
-///
-/// # Arguments
-/// * `line` - The grep result line to parse
-/// * `cfg` - Obsidian configuration for vault path and settings
-pub fn open_obsidian_grep_line(line: &str, cfg: &ObsidianConfig) {
-    debug!("Processing Obsidian grep line: {line}");
-    if let Some((file_path, rest)) = line.split_once(':') {
-        if let Some((line_num, _)) = rest.split_once(':') {
-            info!("Opening Obsidian file at line: {file_path}:{line_num}");
-            open_obsidian_file_line(file_path, line_num, cfg);
-        } else {
-            info!("Opening Obsidian file: {file_path}");
-            open_obsidian_file_path(file_path, cfg);
-        }
-    } else {
-        info!("Opening Obsidian file (non-grep format): {line}");
-        open_obsidian_file_path(line, cfg);
-    }
+/// Open the application of a search provider result with its search
+/// applied, instead of activating the single result
+fn launch_provider_search(item: &SearchResultItem, timestamp: u32) {
+    let (bus, path, terms) = (item.bus_name(), item.object_path(), item.terms());
+    info!("Launching search {terms:?} in provider {bus}");
+    std::thread::spawn(move || {
+        dbus::launch_search(&bus, &path, &terms, timestamp);
+    });
 }
 
 /// Activate an item based on its type and the current application mode
@@ -209,15 +603,368 @@ pub fn open_obsidian_grep_line(line: &str, cfg: &ObsidianConfig) {
 /// This is the main entry point for item activation in Grunner. It determines
 /// what action to perform based on the type of item (application, command,
 /// Obsidian action, or search result) and the current application mode.
-pub fn activate_item(obj: &glib::Object, model: &AppListModel, mode: AppMode, timestamp: u32) {
+/// Returns whether the window closes afterwards, which is up to the caller.
+pub fn activate_item(
+    obj: &glib::Object,
+    model: &AppListModel,
+    mode: AppMode,
+    timestamp: u32,
+) -> AfterActivation {
     debug!("Activating item in mode {mode:?}");
     let ctx = ActivationContext::new(model, mode, timestamp);
 
-    match GrunnerItem::from_object(obj) {
-        Some(GrunnerItem::App(item)) => activate_app(item),
-        Some(GrunnerItem::Command(item)) => activate_command(item, &ctx),
-        Some(GrunnerItem::ObsidianAction(item)) => activate_obsidian_action(item, &ctx),
-        Some(GrunnerItem::SearchResult(item)) => activate_search_result(item, &ctx),
-        None => warn!("Unknown item type, cannot activate"),
+    let Some(item) = GrunnerItem::from_object(obj) else {
+        warn!("Unknown item type, cannot activate");
+        return AfterActivation::Close;
+    };
+
+    let mut after = AfterActivation::Close;
+    match item {
+        GrunnerItem::App(item) => {
+            match item.running_window() {
+                Some(window) if model.config.prefer_focus_running.get() => {
+                    focus_running_app(item, window);
+                }
+                _ => activate_app(item),
+            }
+            record_app_launch(&item.desktop_id(), model);
+        }
+        // An action counts as a launch of its application
+        GrunnerItem::AppAction(item) => {
+            activate_app_action(item);
+            record_app_launch(&item.desktop_id(), model);
+        }
+        GrunnerItem::Bookmark(item) => activate_bookmark(item),
+        GrunnerItem::Clipboard(item) => after = activate_clipboard(item, model),
+        // Palette rows type their command into the entry, which the window
+        // handles as well
+        GrunnerItem::ColonCommand(item) => {
+            debug!("Ignoring activation of palette row :{}", item.name());
+            return AfterActivation::StayOpen;
+        }
+        GrunnerItem::Command(item) => after = activate_command(item, &ctx),
+        GrunnerItem::Emoji(item) => activate_emoji(item, model),
+        // Settings rows are listed again with their new state, so the window
+        // handles them and stays open
+        GrunnerItem::GrunnerAction(item) => {
+            debug!("Ignoring activation of {:?}", item.action());
+            return AfterActivation::StayOpen;
+        }
+        GrunnerItem::ManPage(item) => open_man_page_in_terminal(item),
+        GrunnerItem::ObsidianAction(item) => activate_obsidian_action(item, &ctx),
+        // Counts towards the mode usage itself, once the signal is sent. The
+        // window stays open on the row shown if the process is gone.
+        GrunnerItem::Process(item) => {
+            return if signal_process(item, KillSignal::Term, model) {
+                AfterActivation::Close
+            } else {
+                AfterActivation::StayOpen
+            };
+        }
+        GrunnerItem::Project(item) => activate_project(item, model),
+        GrunnerItem::SearchResult(item) => activate_search_result(item, &ctx),
+        GrunnerItem::Snippet(item) => activate_snippet(item),
+        GrunnerItem::PassEntry(item) => activate_pass_entry(item),
+        GrunnerItem::SshHost(item) => activate_ssh_host(item),
+        // Suggestions only rewrite the search entry, which the window handles
+        GrunnerItem::Suggestion(item) => {
+            debug!("Ignoring activation of suggestion {:?}", item.label());
+            return AfterActivation::StayOpen;
+        }
+        // A bare `!` has nothing to run yet; the row asks for a command
+        GrunnerItem::TerminalCommand(item) if item.command().trim().is_empty() => {
+            debug!("Ignoring activation of an empty terminal command");
+            return AfterActivation::StayOpen;
+        }
+        GrunnerItem::TerminalCommand(item) if item.terminal() => run_in_terminal(&item.command()),
+        GrunnerItem::TerminalCommand(item) => run_detached(&item.command()),
+        GrunnerItem::Unit(item) => control_unit(item, UnitAction::toggle(&item.unit())),
+        GrunnerItem::Url(item) => activate_url(item),
+        // Vault creation keeps the window open, so the window handles it too
+        GrunnerItem::VaultSetup(item) => {
+            debug!("Ignoring activation of vault setup for {:?}", item.vault());
+            return AfterActivation::StayOpen;
+        }
+        GrunnerItem::Window(item) => activate_window(item),
+    }
+
+    record_mode_usage(model);
+    after
+}
+
+// ─── Alternative Actions ───────────────────────────────────────────────────────
+
+/// A secondary action of a row, run by Ctrl+Enter or picked from its menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AltAction {
+    /// Run the command of an app in the terminal emulator
+    RunInTerminal,
+    /// Open the `.desktop` file of an app in the text editor
+    OpenDesktopFile,
+    /// Open the folder of a file row in the file manager
+    OpenFolder,
+    /// Copy the path of a file row
+    CopyPath,
+    /// Open the app of a provider result with the search applied
+    LaunchApp,
+    /// Copy the address of a bookmark
+    CopyUrl,
+    /// Restart the unit of a `:unit` row
+    RestartUnit,
+    /// Show the page of a `:man` row with `man` in the terminal
+    ManInTerminal,
+}
+
+impl AltAction {
+    /// Label of the action in the row menu
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::RunInTerminal => "Run in terminal",
+            Self::OpenDesktopFile => "Open .desktop file",
+            Self::OpenFolder => "Open containing folder",
+            Self::CopyPath => "Copy path",
+            Self::LaunchApp => "Launch app",
+            Self::CopyUrl => "Copy URL",
+            Self::RestartUnit => "Restart",
+            Self::ManInTerminal => "Open in terminal",
+        }
+    }
+}
+
+/// The alternative actions of each item type, Ctrl+Enter's first
+///
+/// Command rows have them only when they are files, as in `:f`, `:fg` and
+/// `:r`.
+fn alternatives(item: &GrunnerItem) -> &'static [AltAction] {
+    match item {
+        GrunnerItem::App(_) => &[AltAction::RunInTerminal, AltAction::OpenDesktopFile],
+        GrunnerItem::Bookmark(_) => &[AltAction::CopyUrl],
+        GrunnerItem::Command(item) if item.kind() == CommandKind::File => {
+            &[AltAction::OpenFolder, AltAction::CopyPath]
+        }
+        GrunnerItem::ManPage(_) => &[AltAction::ManInTerminal],
+        GrunnerItem::SearchResult(_) => &[AltAction::LaunchApp],
+        GrunnerItem::Unit(_) => &[AltAction::RestartUnit],
+        _ => &[],
+    }
+}
+
+/// The alternative actions of the row `obj`, Ctrl+Enter's first
+#[must_use]
+pub fn alternative_actions(obj: &glib::Object) -> &'static [AltAction] {
+    GrunnerItem::from_object(obj).map_or(&[], |item| alternatives(&item))
+}
+
+/// The file of a file row, also of a `:fg` match
+fn file_row_path(item: &CommandItem) -> std::path::PathBuf {
+    item.payload()
+        .path()
+        .map(std::path::Path::to_path_buf)
+        .unwrap_or_default()
+}
+
+/// Run the alternative `action` of the row `obj`
+///
+/// Returns `None` if the row does not offer `action`, else whether the
+/// window closes afterwards, as for [`activate_item`].
+pub fn run_alternative(
+    obj: &glib::Object,
+    action: AltAction,
+    model: &AppListModel,
+    timestamp: u32,
+) -> Option<AfterActivation> {
+    let item = GrunnerItem::from_object(obj)?;
+    if !alternatives(&item).contains(&action) {
+        return None;
+    }
+    let close_on_copy = model.config.close_on_copy.get();
+    let mut after = AfterActivation::Close;
+    match (action, item) {
+        (AltAction::RunInTerminal, GrunnerItem::App(item)) => {
+            info!("Running {} in the terminal", item.exec());
+            let source = LaunchSource {
+                name: item.name(),
+                desktop_id: None,
+                desktop_file: Some(item.path()),
+            };
+            launch_app(&item.exec(), true, None, &source);
+            record_app_launch(&item.desktop_id(), model);
+        }
+        (AltAction::OpenDesktopFile, GrunnerItem::App(item)) => open_desktop_file(&item.path()),
+        (AltAction::OpenFolder, GrunnerItem::Command(item)) => {
+            open_containing_folder(&file_row_path(item));
+        }
+        (AltAction::CopyPath, GrunnerItem::Command(item)) => {
+            copy_text(&file_row_path(item).to_string_lossy());
+            after = AfterActivation::after_copy(close_on_copy);
+        }
+        (AltAction::LaunchApp, GrunnerItem::SearchResult(item)) => {
+            launch_provider_search(item, timestamp);
+        }
+        (AltAction::CopyUrl, GrunnerItem::Bookmark(item)) => {
+            copy_text(&item.url());
+            after = AfterActivation::after_copy(close_on_copy);
+        }
+        (AltAction::RestartUnit, GrunnerItem::Unit(item)) => {
+            control_unit(item, UnitAction::Restart);
+        }
+        (AltAction::ManInTerminal, GrunnerItem::ManPage(item)) => open_man_page_in_terminal(item),
+        _ => return None,
+    }
+    record_mode_usage(model);
+    Some(after)
+}
+
+/// Run the first alternative action of the row `obj`, as Ctrl+Enter does
+///
+/// Returns `None` if the row has none.
+pub fn run_first_alternative(
+    obj: &glib::Object,
+    model: &AppListModel,
+    timestamp: u32,
+) -> Option<AfterActivation> {
+    let action = *alternative_actions(obj).first()?;
+    run_alternative(obj, action, model, timestamp)
+}
+
+/// Create the missing Obsidian vault, then run the pending command again
+///
+/// Errors replace the results with a single row describing the failure.
+pub fn create_vault_and_retry(item: &VaultSetupItem, model: &AppListModel) {
+    let Some(obsidian) = model.obsidian() else {
+        warn!("Obsidian configuration missing for vault creation");
+        return;
+    };
+    match create_vault(&obsidian, std::path::Path::new(get_home_dir())) {
+        Ok(vault) => {
+            info!("Created Obsidian vault at {}", vault.display());
+            model.populate(&item.retry_query());
+        }
+        Err(e) => {
+            warn!("Failed to create Obsidian vault {}: {e}", item.vault());
+            AppCommandHandler::new(model.clone()).show_error(e);
+        }
+    }
+}
+
+/// Show why an Obsidian action cannot run instead of running it
+///
+/// Returns `true` if the note path `action` writes to escapes the vault, in
+/// which case the results are replaced with a row naming the setting and
+/// nothing is written.
+pub fn refuse_invalid_obsidian_action(action: ObsidianAction, model: &AppListModel) -> bool {
+    let Some(Err(e)) = model
+        .obsidian()
+        .map(|obsidian| check_action_path(action, &obsidian))
+    else {
+        return false;
+    };
+    warn!("Refusing Obsidian action {action:?}: {e}");
+    AppCommandHandler::new(model.clone()).show_error(e);
+    true
+}
+
+/// Turn the setting of a `:grunner` row on or off, then list the rows again
+///
+/// Errors, such as an autostart entry edited by hand, replace the results
+/// with a single row describing the failure.
+pub fn toggle_grunner_action(item: &GrunnerActionItem, model: &AppListModel) {
+    let result = match item.action() {
+        GrunnerAction::StartAtLogin => {
+            let path = autostart::autostart_path();
+            let changed = if item.on() {
+                autostart::remove(&path, false)
+            } else {
+                autostart::install(&path, false)
+            };
+            changed.map(|outcome| outcome.message(&path))
+        }
+    };
+    match result {
+        Ok(message) => {
+            info!("{message}");
+            model.populate(&model.state.current_query());
+        }
+        Err(e) => {
+            warn!("Could not change {:?}: {e}", item.action());
+            AppCommandHandler::new(model.clone()).show_error(e.to_string());
+        }
+    }
+}
+
+/// Handle Enter on a `:sh` command that must not run right away
+///
+/// A blocked command stays listed and does nothing. A dangerous command is
+/// replaced by its confirmation row, which runs it on the next Enter.
+/// Returns whether the activation was handled here.
+pub fn guard_command(item: &CommandItem, model: &AppListModel) -> bool {
+    match item.guard() {
+        CommandGuard::Run | CommandGuard::Confirmed => false,
+        CommandGuard::Blocked(operation) => {
+            warn!(
+                "Command uses {operation} and is not marked dangerous: {}",
+                item.row().description
+            );
+            true
+        }
+        CommandGuard::Confirm(_) => {
+            AppCommandHandler::new(model.clone()).show_confirmation(item);
+            true
+        }
+    }
+}
+
+/// Count an activation of the result `key` towards its frecency ranking
+fn record_activation(key: &str, model: &AppListModel) {
+    let history = history::record_activation(key, model.config.usage_stats.get());
+    *model.config.history.borrow_mut() = history;
+}
+
+/// Count a launch of the app `desktop_id` towards its frecency ranking
+fn record_app_launch(desktop_id: &str, model: &AppListModel) {
+    if !desktop_id.is_empty() {
+        record_activation(&history::app_key(desktop_id), model);
+    }
+}
+
+/// Count an activation towards the current colon mode, if any, and record
+/// the query for Alt+Up and Alt+Down
+pub(crate) fn record_mode_usage(model: &AppListModel) {
+    let query = model.state.current_query();
+    model.config.record_query(&query);
+    if model.config.disable_modes.get() {
+        return;
+    }
+    if let Some(mode) = colon_mode(&query) {
+        history::record_mode_activation(mode, model.config.usage_stats.get());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_after_copy_follows_close_on_copy() {
+        assert_eq!(AfterActivation::after_copy(true), AfterActivation::Close);
+        assert_eq!(AfterActivation::after_copy(false), AfterActivation::Copied);
+    }
+
+    #[test]
+    fn test_shift_enter_never_closes() {
+        assert_eq!(
+            AfterActivation::Close.staying_open(),
+            AfterActivation::StayOpen
+        );
+        assert_eq!(
+            AfterActivation::StayOpen.staying_open(),
+            AfterActivation::StayOpen
+        );
+        // A copy still says so
+        assert_eq!(
+            AfterActivation::Copied.staying_open(),
+            AfterActivation::Copied
+        );
     }
 }
@@ -6,19 +6,22 @@
 //!
 //! Key features:
 //! - Parallel scanning of application directories using Rayon
-//! - Binary caching of parsed applications for fast subsequent loads
-//! - Proper handling of desktop entry specifications
+//! - Binary caching of parsed applications for fast subsequent loads, served
+//!   immediately and revalidated in the background against a content hash
+//! - Proper handling of desktop entry specifications, including localized
+//!   `Name`, `GenericName` and `Comment` keys
 //! - Filtering of non-application and hidden entries
 
 use crate::core::global_state::get_home_dir;
+use crate::utils::Fnv1a;
+use crate::versioned_cache;
 use jwalk::WalkDir;
 use log::{debug, error, info, trace};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::time::SystemTime;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Represents a parsed desktop application entry
 ///
@@ -37,16 +40,134 @@ pub struct DesktopApp {
     pub exec: String,
     /// Description or comment about the application (from the `Comment=` field)
     pub description: String,
+    /// Generic name of the application, e.g. "Web Browser" (from the
+    /// `GenericName=` field)
+    pub generic_name: String,
+    /// Search keywords for the application (from the `Keywords=` field)
+    pub keywords: Vec<String>,
     /// Icon name or path for the application (from the `Icon=` field)
     pub icon: String,
     /// Whether the application should be launched in a terminal (from `Terminal=` field)
     pub terminal: bool,
+    /// Quick actions such as "New Private Window", in `Actions=` order
+    pub actions: Vec<DesktopAction>,
+    /// Window class of the application's windows, empty if not declared
+    /// (from the `StartupWMClass=` field)
+    pub startup_wm_class: String,
+    /// Path of the `.desktop` file this entry was parsed from
+    ///
+    /// Launch history is keyed by this path.
+    pub path: PathBuf,
 }
 
+/// Whether `id`, a desktop ID as written in the config, names `app`
+///
+/// The ID may be given with or without its `.desktop` suffix.
+#[must_use]
+pub fn matches_desktop_id(app: &DesktopApp, id: &str) -> bool {
+    id.strip_suffix(".desktop").unwrap_or(id) == app.desktop_id
+}
+
+/// The apps named by `ids`, in the order of `ids`
+///
+/// IDs of apps that are not installed are skipped, and so are repeats.
+#[must_use]
+pub fn apps_by_ids<'a>(ids: &[String], apps: &'a [DesktopApp]) -> Vec<&'a DesktopApp> {
+    let mut found: Vec<&DesktopApp> = Vec::new();
+    for id in ids {
+        if let Some(app) = apps.iter().find(|app| matches_desktop_id(app, id))
+            && !found.iter().any(|f| f.desktop_id == app.desktop_id)
+        {
+            found.push(app);
+        }
+    }
+    found
+}
+
+/// Whether `text` matches the glob `pattern`, where `*` stands for any run
+/// of characters and `?` for one character
+#[must_use]
+pub fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position after the last `*` and the text position it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p + 1, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` swallow one more character
+                Some((star_p, star_t)) => {
+                    backtrack = Some((star_p, star_t + 1));
+                    p = star_p;
+                    t = star_t + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Whether `app` is named by one of the `[search] app_blacklist` entries
+///
+/// Entries are desktop IDs or globs over them, with or without the
+/// `.desktop` suffix.
+#[must_use]
+pub fn is_app_blacklisted(app: &DesktopApp, blacklist: &[String]) -> bool {
+    blacklist.iter().any(|entry| {
+        let pattern = entry.strip_suffix(".desktop").unwrap_or(entry);
+        glob_matches(pattern, &app.desktop_id)
+    })
+}
+
+/// `apps` without the ones named by `blacklist`
+#[must_use]
+pub fn without_blacklisted(apps: &[DesktopApp], blacklist: &[String]) -> Vec<DesktopApp> {
+    apps.iter()
+        .filter(|app| !is_app_blacklisted(app, blacklist))
+        .cloned()
+        .collect()
+}
+
+/// A quick action of an application (a `[Desktop Action <id>]` group)
+///
+/// Actions run their own `Exec=` line with the `Terminal=` setting of the
+/// application that defines them.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DesktopAction {
+    /// Action identifier, as listed in `Actions=`
+    pub id: String,
+    /// Display name (from the action's `Name=` field, localized)
+    pub name: String,
+    /// Command to execute (from the action's `Exec=` field)
+    pub exec: String,
+    /// Icon name or path, empty to use the application's icon
+    pub icon: String,
+}
+
+/// Format version of the cache, see [`versioned_cache`]
+///
+/// Bump it whenever the serialized layout of [`AppCache`] (including
+/// [`DesktopApp`]) changes. It is part of the file name and of the cache
+/// header, so caches written by other versions are rebuilt rather than
+/// misread.
+const CACHE_VERSION: u32 = 10;
+
 /// Get the path to the application cache file
 ///
 /// The cache is stored in the user's cache directory at:
-/// `$HOME/.cache/grunner/apps.bin`
+/// `$HOME/.cache/grunner/apps.bin`. The name stays the same across
+/// format versions: the header tells them apart, and a cache of another
+/// version is overwritten by the next scan.
 ///
 /// # Returns
 /// `PathBuf` pointing to the cache file location
@@ -58,155 +179,277 @@ fn cache_path() -> PathBuf {
         .join("apps.bin")
 }
 
-/// Get the maximum modification time among a list of directories
-///
-/// This is used to determine if the cache is stale by comparing the
-/// cache file's modification time with the most recently modified
-/// application directory.
-///
-/// # Arguments
-/// * `dirs` - Slice of directory paths to check
-///
-/// # Returns
-/// `Some(SystemTime)` with the latest modification time if all directories
-/// exist and have readable metadata, `None` otherwise.
-fn dirs_max_mtime(dirs: &[PathBuf]) -> Option<SystemTime> {
-    dirs.iter()
-        .filter_map(|d| fs::metadata(d).ok()?.modified().ok())
-        .max()
+/// Application cache as stored on disk
+#[derive(Serialize, Deserialize)]
+struct AppCache {
+    /// [`snapshot_hash`] of the `.desktop` files the apps were parsed from
+    snapshot: u64,
+    /// [`DesktopLocale::tag`] of the locale the names were picked for
+    locale: String,
+    /// Application directories that were scanned, in config order
+    dirs: Vec<PathBuf>,
+    apps: Vec<DesktopApp>,
 }
 
-/// Attempt to load applications from cache if it's still valid
-///
-/// The cache is considered valid if:
-/// 1. The cache file exists and is readable
-/// 2. The cache file is newer than all application directories
-///
-/// # Arguments
-/// * `dirs` - Application directories that would be scanned if cache is invalid
+/// Borrowing twin of [`AppCache`] for writing without cloning the apps
+#[derive(Serialize)]
+struct AppCacheRef<'a> {
+    snapshot: u64,
+    locale: &'a str,
+    dirs: &'a [PathBuf],
+    apps: &'a [DesktopApp],
+}
+
+/// Locale used to pick localized keys such as `Name[it]=`
 ///
-/// # Returns
-/// `Some(Vec<DesktopApp>)` if cache is valid and loaded successfully,
-/// `None` if cache is stale, missing, or corrupt.
-fn try_load_cache(dirs: &[PathBuf]) -> Option<Vec<DesktopApp>> {
-    let cache = cache_path();
-    debug!("Checking application cache at {}", cache.display());
-
-    // Get cache file modification time
-    let cache_mtime = match fs::metadata(&cache) {
-        Ok(metadata) => match metadata.modified() {
-            Ok(mtime) => mtime,
-            Err(e) => {
-                debug!("Failed to get cache file modification time: {e}");
-                return None;
-            }
-        },
-        Err(e) => {
-            debug!("Cache file not found or inaccessible: {e}");
-            return None;
-        }
-    };
+/// Holds the parts of a POSIX locale name (`lang_COUNTRY.ENCODING@MODIFIER`)
+/// that the Desktop Entry spec matches keys against; the encoding is ignored.
+/// The default value is the `C` locale, which only uses unlocalized keys.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DesktopLocale {
+    lang: String,
+    country: Option<String>,
+    modifier: Option<String>,
+}
 
-    // Get latest directory modification time
-    let Some(dirs_mtime) = dirs_max_mtime(dirs) else {
-        debug!("Failed to get directory modification times");
-        return None;
-    };
+/// Match rank of an unlocalized key, worse than any matching localized one
+const UNLOCALIZED_RANK: u8 = 4;
+
+impl DesktopLocale {
+    /// Parse a locale name such as `it_IT.UTF-8` or `sr_RS@latin`
+    ///
+    /// `C`, `POSIX` and empty names give the default (unlocalized) locale.
+    #[must_use]
+    pub fn parse(name: &str) -> Self {
+        let (rest, modifier) = name
+            .split_once('@')
+            .map_or((name, None), |(rest, m)| (rest, Some(m)));
+        let rest = rest.split_once('.').map_or(rest, |(rest, _)| rest);
+        let (lang, country) = rest
+            .split_once('_')
+            .map_or((rest, None), |(lang, c)| (lang, Some(c)));
+        if lang.is_empty() || lang == "C" || lang == "POSIX" {
+            return Self::default();
+        }
+        Self {
+            lang: lang.to_string(),
+            country: country.filter(|c| !c.is_empty()).map(str::to_string),
+            modifier: modifier.filter(|m| !m.is_empty()).map(str::to_string),
+        }
+    }
 
-    // Cache is stale if directories were modified after cache was created
-    if dirs_mtime > cache_mtime {
-        info!("Cache is stale (dirs modified after cache creation)");
-        return None;
+    /// Locale of the user's messages: `LC_ALL`, `LC_MESSAGES`, then `LANG`
+    #[must_use]
+    pub fn from_env() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))
+            .map_or_else(Self::default, |name| Self::parse(&name))
     }
 
-    // Read cache file
-    let bytes = match fs::read(&cache) {
-        Ok(bytes) => bytes,
-        Err(e) => {
-            error!("Failed to read cache file: {e}");
-            return None;
+    /// Canonical name stored in the cache, e.g. `it_IT` (empty for `C`)
+    #[must_use]
+    pub fn tag(&self) -> String {
+        let mut tag = self.lang.clone();
+        if let Some(country) = &self.country {
+            tag.push('_');
+            tag.push_str(country);
         }
-    };
+        if let Some(modifier) = &self.modifier {
+            tag.push('@');
+            tag.push_str(modifier);
+        }
+        tag
+    }
 
-    // Deserialize cache and populate computed fields
-    match bincode::deserialize::<Vec<DesktopApp>>(&bytes) {
-        Ok(mut apps) => {
-            // Populate name_lower (skipped during deserialization)
-            for app in &mut apps {
-                app.name_lower = app.name.to_lowercase();
-            }
-            info!("Loaded {} applications from cache", apps.len());
-            Some(apps)
+    /// How well the locale of a `Key[locale]` entry matches, lower is better
+    ///
+    /// Follows the spec's lookup order: `lang_COUNTRY@MODIFIER`,
+    /// `lang_COUNTRY`, `lang@MODIFIER`, then `lang`. Returns `None` for
+    /// entries of other locales.
+    fn match_rank(&self, key_locale: &str) -> Option<u8> {
+        if self.lang.is_empty() {
+            return None;
         }
-        Err(e) => {
-            error!("Failed to deserialize cache: {e}");
-            None
+        let (rest, modifier) = key_locale
+            .split_once('@')
+            .map_or((key_locale, None), |(rest, m)| (rest, Some(m)));
+        let (lang, country) = rest
+            .split_once('_')
+            .map_or((rest, None), |(lang, c)| (lang, Some(c)));
+        if lang != self.lang
+            || country.is_some_and(|c| self.country.as_deref() != Some(c))
+            || modifier.is_some_and(|m| self.modifier.as_deref() != Some(m))
+        {
+            return None;
         }
+        Some(match (country.is_some(), modifier.is_some()) {
+            (true, true) => 0,
+            (true, false) => 1,
+            (false, true) => 2,
+            (false, false) => 3,
+        })
     }
 }
 
-/// Save parsed applications to cache for faster future loads
+/// Split a key such as `Name[it]` into its field and match rank for `locale`
 ///
-/// # Arguments
-/// * `apps` - Vector of desktop applications to cache
-///
-/// The cache is written as a binary serialized format using bincode
-/// for fast reading/writing and compact storage.
-fn save_cache(apps: &[DesktopApp]) {
-    let path = cache_path();
-    debug!(
-        "Saving {} applications to cache at {}",
-        apps.len(),
-        path.display()
-    );
+/// Unlocalized keys get [`UNLOCALIZED_RANK`]; keys localized for another
+/// locale get `None`.
+fn localized_key<'a>(key: &'a str, locale: &DesktopLocale) -> (&'a str, Option<u8>) {
+    match key.strip_suffix(']').and_then(|k| k.split_once('[')) {
+        Some((field, key_locale)) => (field, locale.match_rank(key_locale)),
+        None => (key, Some(UNLOCALIZED_RANK)),
+    }
+}
 
-    // Ensure cache directory exists
-    if let Some(dir) = path.parent() {
-        if let Err(e) = fs::create_dir_all(dir) {
-            error!("Failed to create cache directory {}: {e}", dir.display());
-            return;
+/// A `[Desktop Action <id>]` group while it is being parsed
+struct ActionGroup {
+    id: String,
+    name: Localized,
+    exec: Option<String>,
+    icon: String,
+}
+
+impl ActionGroup {
+    fn new(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            name: Localized::default(),
+            exec: None,
+            icon: String::new(),
         }
-        debug!("Created cache directory: {}", dir.display());
     }
 
-    // Serialize and write cache
-    match bincode::serialize(apps) {
-        Ok(bytes) => {
-            let len = bytes.len();
-            debug!("Serialized {len} bytes of cache data");
-            if let Err(e) = fs::write(&path, &bytes) {
-                error!("Failed to write cache to {}: {e}", path.display());
-            } else {
-                let len = apps.len();
-                info!("Saved {len} applications to cache");
-            }
+    fn parse_line(&mut self, line: &str, locale: &DesktopLocale) {
+        let Some((key, val)) = line.split_once('=') else {
+            return;
+        };
+        match localized_key(key.trim_end(), locale) {
+            ("Name", Some(rank)) => self.name.offer(rank, val),
+            ("Exec", Some(UNLOCALIZED_RANK)) => self.exec = Some(val.trim().to_string()),
+            ("Icon", Some(UNLOCALIZED_RANK)) => self.icon = val.trim().to_string(),
+            _ => {}
         }
-        Err(e) => {
-            error!("Failed to serialize cache: {e}");
+    }
+
+    /// The finished action, if it has both a name and a command
+    fn into_action(self) -> Option<DesktopAction> {
+        Some(DesktopAction {
+            id: self.id,
+            name: self.name.value?,
+            exec: self.exec.filter(|e| !e.is_empty())?,
+            icon: self.icon,
+        })
+    }
+}
+
+/// Group of a `.desktop` file that the parser is in
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Group {
+    /// `[Desktop Entry]`
+    Entry,
+    /// `[Desktop Action <id>]`, by index into the parsed action groups
+    Action(usize),
+    /// Anything else, or before the first group
+    Other,
+}
+
+/// Value of a localized key, keeping the entry that best matches the locale
+#[derive(Default)]
+struct Localized {
+    value: Option<String>,
+    rank: u8,
+}
+
+impl Localized {
+    /// Keep `value` if it matches better than the current one
+    ///
+    /// Among entries of equal rank the first one wins.
+    fn offer(&mut self, rank: u8, value: &str) {
+        if self.value.is_none() || rank < self.rank {
+            self.value = Some(value.trim().to_string());
+            self.rank = rank;
         }
     }
 }
 
-/// Scan application directories for `.desktop` files and parse them
+/// A `.desktop` file with its modification time and size, as seen by a scan
 ///
-/// This function performs the actual filesystem scanning and parsing:
-/// 1. Walks each directory recursively to find all `.desktop` files
-/// 2. Uses parallel processing (Rayon) for faster scanning
-/// 3. Removes duplicate paths (same file accessed via symlinks or multiple dirs)
-/// 4. Parses each `.desktop` file in parallel
-/// 5. Sorts applications alphabetically by name (case-insensitive)
+/// The size catches edits that land within the mtime resolution of the
+/// filesystem.
+type SnapshotEntry = (PathBuf, Option<SystemTime>, u64);
+
+/// Applications returned by [`load_apps`]
+pub struct LoadedApps {
+    pub apps: Vec<DesktopApp>,
+    /// Snapshot hash stored with the cached list, or `None` after a fresh
+    /// scan; pass it to [`refresh_apps`] to revalidate the cached list
+    pub cached_snapshot: Option<u64>,
+}
+
+/// Content hash of a directory snapshot ([`Fnv1a`] over paths, mtimes and
+/// sizes)
 ///
-/// # Arguments
-/// * `dirs` - Directories to scan for `.desktop` files
+/// The hash is stable, so it can be stored in the cache file. Entries are
+/// hashed in order; [`desktop_snapshot`] sorts them.
+fn snapshot_hash(snapshot: &[SnapshotEntry]) -> u64 {
+    let mut hash = Fnv1a::new();
+    for (path, mtime, size) in snapshot {
+        hash.write(path.as_os_str().as_encoded_bytes());
+        // Separator so that ("ab", t) and ("a", "b"…) cannot collide trivially
+        hash.write(&[0]);
+        let (secs, nanos) = mtime
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map_or((0, 0), |d| (d.as_secs(), d.subsec_nanos()));
+        hash.write(&secs.to_le_bytes());
+        hash.write(&nanos.to_le_bytes());
+        hash.write(&size.to_le_bytes());
+    }
+    hash.finish()
+}
+
+/// Whether a cache written for `cached` still matches `snapshot`
 ///
-/// # Returns
-/// Vector of parsed `DesktopApp` instances
-fn scan_apps(dirs: &[PathBuf]) -> Vec<DesktopApp> {
-    info!("Scanning {} directories for .desktop files", dirs.len());
+/// Compares the set of `.desktop` paths with their mtimes and sizes rather
+/// than directory mtimes, so edits to existing files are noticed too.
+fn is_snapshot_current(cached: u64, snapshot: &[SnapshotEntry]) -> bool {
+    snapshot_hash(snapshot) == cached
+}
 
-    // Collect all .desktop file paths (Rayon handles small workloads efficiently)
-    let paths: Vec<PathBuf> = dirs
-        .par_iter()
+/// Whether `cache` was written for the application directories `dirs` and
+/// the current `locale`
+///
+/// A cache from another `app_dirs` list is treated as missing even when its
+/// snapshot would match, since the set of scanned files differs.
+fn is_cache_for(cache: &AppCache, dirs: &[PathBuf], locale: &DesktopLocale) -> bool {
+    if cache.locale != locale.tag() {
+        info!(
+            "Application cache is for locale {:?}, not {:?}",
+            cache.locale,
+            locale.tag()
+        );
+        return false;
+    }
+    if cache.dirs != dirs {
+        info!("Application cache is for other application directories");
+        return false;
+    }
+    true
+}
+
+/// List every `.desktop` file under `dirs` with its modification time and
+/// size
+///
+/// Skips directories that do not exist and returns the paths deduplicated
+/// and sorted so that the snapshot does not depend on walk order.
+///
+/// Directories are walked one after another: jwalk already walks each one on
+/// the rayon pool, and nesting it inside a parallel iterator can exhaust the
+/// pool so the walk times out and yields nothing.
+fn desktop_snapshot(dirs: &[PathBuf]) -> Vec<SnapshotEntry> {
+    let mut paths: Vec<PathBuf> = dirs
+        .iter()
         .filter(|d| {
             let exists = d.exists();
             if !exists {
@@ -226,72 +469,226 @@ fn scan_apps(dirs: &[PathBuf]) -> Vec<DesktopApp> {
         .collect();
 
     debug!("Found {} .desktop files before deduplication", paths.len());
+    paths.sort_unstable();
+    paths.dedup();
+
+    paths
+        .into_par_iter()
+        .map(|p| {
+            let meta = fs::metadata(&p).ok();
+            let mtime = meta.as_ref().and_then(|m| m.modified().ok());
+            let size = meta.map_or(0, |m| m.len());
+            (p, mtime, size)
+        })
+        .collect()
+}
 
-    // Remove duplicate paths using a hash set for deduplication
-    let mut seen = HashSet::new();
-    let unique_paths: Vec<PathBuf> = paths
-        .into_iter()
-        .filter(|p| seen.insert(p.clone()))
-        .collect();
+/// Read the cache file, whatever its age
+///
+/// # Returns
+/// `Some(AppCache)` if the file exists and was written for
+/// [`CACHE_VERSION`], `None` otherwise.
+fn read_cache() -> Option<AppCache> {
+    let cache = cache_path();
+    debug!("Reading application cache at {}", cache.display());
+    versioned_cache::load(&cache, CACHE_VERSION).map(loaded_cache)
+}
+
+/// Populate the fields of a cache read from disk that are not stored
+fn loaded_cache(mut cache: AppCache) -> AppCache {
+    // Populate name_lower (skipped during deserialization)
+    for app in &mut cache.apps {
+        app.name_lower = app.name.to_lowercase();
+    }
+    info!("Loaded {} applications from cache", cache.apps.len());
+    cache
+}
 
+/// Save parsed applications to cache for faster future loads
+///
+/// # Arguments
+/// * `snapshot` - Hash of the `.desktop` files the apps were parsed from
+/// * `locale` - Tag of the locale the apps were parsed for
+/// * `apps` - Vector of desktop applications to cache
+///
+/// The cache is written as bincode behind a [`versioned_cache`] header,
+/// for fast reading/writing and compact storage.
+fn save_cache(snapshot: u64, locale: &str, dirs: &[PathBuf], apps: &[DesktopApp]) {
+    let path = cache_path();
     debug!(
-        "{} unique .desktop files after deduplication",
-        unique_paths.len()
+        "Saving {} applications to cache at {}",
+        apps.len(),
+        path.display()
     );
 
-    // Parse desktop files
-    let mut apps: Vec<DesktopApp> = unique_paths
+    let cache = AppCacheRef {
+        snapshot,
+        locale,
+        dirs,
+        apps,
+    };
+    match versioned_cache::store(&path, CACHE_VERSION, &cache) {
+        Ok(()) => info!("Saved {} applications to cache", apps.len()),
+        Err(e) => error!("Failed to write cache to {}: {e}", path.display()),
+    }
+}
+
+/// Parse the `.desktop` files of a snapshot for `locale`
+///
+/// Parses in parallel (Rayon) and sorts applications alphabetically by name
+/// (case-insensitive).
+fn parse_apps(snapshot: &[SnapshotEntry], locale: &DesktopLocale) -> Vec<DesktopApp> {
+    let mut apps: Vec<DesktopApp> = snapshot
         .par_iter()
-        .filter_map(|p| parse_desktop_file(p))
+        .filter_map(|(p, _, _)| parse_desktop_file(p, locale))
         .collect();
 
     debug!("Successfully parsed {} applications", apps.len());
 
     // Sort applications alphabetically for consistent UI presentation
     apps.sort_unstable_by(|a, b| a.name_lower.cmp(&b.name_lower));
+    apps
+}
 
+/// Scan application directories, parse them and rewrite the cache
+fn scan_and_cache(dirs: &[PathBuf], locale: &DesktopLocale) -> Vec<DesktopApp> {
+    info!("Scanning {} directories for .desktop files", dirs.len());
+    let snapshot = desktop_snapshot(dirs);
+    let apps = parse_apps(&snapshot, locale);
     info!(
         "Scanned {} applications from {} directories",
         apps.len(),
         dirs.len()
     );
+    save_cache(snapshot_hash(&snapshot), &locale.tag(), dirs, &apps);
     apps
 }
 
 /// Main entry point for loading desktop applications
 ///
-/// This function implements the caching strategy:
-/// 1. Try to load from cache if it exists and is valid
-/// 2. If cache is invalid or missing, scan and parse directories
-/// 3. Save fresh scan results to cache for next time
+/// This function implements a stale-while-revalidate strategy:
+/// 1. If the cache file deserializes, return its list immediately, even if
+///    it may be stale; the caller revalidates it with [`refresh_apps`]
+/// 2. Otherwise scan and parse the directories synchronously and save the
+///    result to the cache
+///
+/// A cache written for another locale (see [`DesktopLocale::from_env`]) is
+/// treated as missing, since its names are in the wrong language.
 ///
 /// # Arguments
 /// * `dirs` - Directories to scan for `.desktop` files
 ///
 /// # Returns
-/// Vector of `DesktopApp` instances ready for display and launching
+/// The applications, plus the cached snapshot hash if they came from cache
 #[must_use]
-pub fn load_apps(dirs: &[PathBuf]) -> Vec<DesktopApp> {
-    // First attempt to load from cache
-    if let Some(cached) = try_load_cache(dirs) {
-        info!("Cache hit: loaded {} applications from cache", cached.len());
-        return cached;
+pub fn load_apps(dirs: &[PathBuf]) -> LoadedApps {
+    let locale = DesktopLocale::from_env();
+    let cache = read_cache().filter(|cache| is_cache_for(cache, dirs, &locale));
+    if let Some(cache) = cache {
+        info!(
+            "Cache hit: loaded {} applications from cache",
+            cache.apps.len()
+        );
+        return LoadedApps {
+            apps: cache.apps,
+            cached_snapshot: Some(cache.snapshot),
+        };
+    }
+
+    info!("Cache missing or unreadable, scanning application directories");
+    LoadedApps {
+        apps: scan_and_cache(dirs, &locale),
+        cached_snapshot: None,
+    }
+}
+
+/// Revalidate a cached application list against the `.desktop` files
+///
+/// Takes a fresh snapshot of `dirs`; if it still hashes to `cached_snapshot`
+/// nothing is parsed and the cache file is left untouched. Otherwise the
+/// applications are re-parsed and the cache rewritten.
+///
+/// # Returns
+/// `Some(apps)` if the applications changed, `None` if the cache is current
+#[must_use]
+pub fn refresh_apps(dirs: &[PathBuf], cached_snapshot: u64) -> Option<Vec<DesktopApp>> {
+    let snapshot = desktop_snapshot(dirs);
+    if is_snapshot_current(cached_snapshot, &snapshot) {
+        debug!("Application cache is current ({} files)", snapshot.len());
+        return None;
     }
 
-    info!("Cache miss or invalid, scanning application directories");
-    // Cache miss or invalid - perform fresh scan
-    let apps = scan_apps(dirs);
     info!(
-        "Scanned {} applications from {} directories",
-        apps.len(),
-        dirs.len()
+        "Application cache is stale, re-parsing {} files",
+        snapshot.len()
     );
+    let locale = DesktopLocale::from_env();
+    let apps = parse_apps(&snapshot, &locale);
+    save_cache(snapshot_hash(&snapshot), &locale.tag(), dirs, &apps);
+    Some(apps)
+}
 
-    // Save to cache for future use
-    save_cache(&apps);
+/// Revalidate the saved application cache against the `.desktop` files
+///
+/// Used when a resident window is shown again. Like [`refresh_apps`] with
+/// the snapshot stored in the cache; without a usable cache the directories
+/// are scanned.
+///
+/// # Returns
+/// `Some(apps)` if the applications changed since the cache was written
+#[must_use]
+pub fn revalidate_apps(dirs: &[PathBuf]) -> Option<Vec<DesktopApp>> {
+    let locale = DesktopLocale::from_env();
+    match read_cache().filter(|cache| is_cache_for(cache, dirs, &locale)) {
+        Some(cache) => refresh_apps(dirs, cache.snapshot),
+        None => Some(scan_and_cache(dirs, &locale)),
+    }
+}
+
+/// Re-parse the applications under the `changed` directories and merge them
+/// into `current`
+///
+/// Used when a directory monitor reports `.desktop` files created, removed
+/// or edited. Only `changed` is walked and parsed; the other applications
+/// are kept as they are. The cache is rewritten for the whole of `dirs`.
+///
+/// # Returns
+/// The merged list, sorted by name
+#[must_use]
+pub fn rescan_dirs(
+    dirs: &[PathBuf],
+    changed: &[PathBuf],
+    current: Vec<DesktopApp>,
+) -> Vec<DesktopApp> {
+    info!(
+        "Re-scanning {} changed application directories",
+        changed.len()
+    );
+    let locale = DesktopLocale::from_env();
+    let rescanned = parse_apps(&desktop_snapshot(changed), &locale);
+    let apps = merge_rescanned(current, changed, rescanned);
+    save_cache(
+        snapshot_hash(&desktop_snapshot(dirs)),
+        &locale.tag(),
+        dirs,
+        &apps,
+    );
     apps
 }
 
+/// Replace the applications of `current` found under the `changed`
+/// directories by `rescanned`, keeping the list sorted by name
+fn merge_rescanned(
+    mut current: Vec<DesktopApp>,
+    changed: &[PathBuf],
+    rescanned: Vec<DesktopApp>,
+) -> Vec<DesktopApp> {
+    current.retain(|app| !changed.iter().any(|dir| app.path.starts_with(dir)));
+    current.extend(rescanned);
+    current.sort_unstable_by(|a, b| a.name_lower.cmp(&b.name_lower));
+    current
+}
+
 /// Parse a single `.desktop` file into a `DesktopApp` struct
 ///
 /// This function implements a subset of the Desktop Entry Specification:
@@ -301,13 +698,19 @@ pub fn load_apps(dirs: &[PathBuf]) -> Vec<DesktopApp> {
 /// - Non-application entries (Type != "Application")
 /// - Hidden entries (Hidden=true or NoDisplay=true)
 ///
+/// `Name`, `GenericName` and `Comment` use the entry localized for `locale`
+/// if there is one. `[Desktop Action <id>]` groups become
+/// [`DesktopApp::actions`], in the order of the `Actions=` key; groups that
+/// are not listed there are ignored.
+///
 /// # Arguments
 /// * `path` - Path to the `.desktop` file to parse
+/// * `locale` - Locale to pick localized keys for
 ///
 /// # Returns
 /// `Some(DesktopApp)` if the file is a valid, displayable application,
 /// `None` if it's not an application or should be hidden.
-pub(crate) fn parse_desktop_file(path: &Path) -> Option<DesktopApp> {
+pub(crate) fn parse_desktop_file(path: &Path, locale: &DesktopLocale) -> Option<DesktopApp> {
     // Read file content
     trace!("Parsing desktop file: {}", path.display());
     let content = fs::read_to_string(path).ok()?;
@@ -320,49 +723,81 @@ pub(crate) fn parse_desktop_file(path: &Path) -> Option<DesktopApp> {
         .replace('/', "-");
 
     // Initialize parser state
-    let mut name: Option<String> = None;
+    let mut name = Localized::default();
+    let mut generic_name = Localized::default();
+    let mut comment = Localized::default();
     let mut exec: Option<String> = None;
-    let mut description = String::new();
+    let mut keywords: Vec<String> = Vec::new();
     let mut icon = String::new();
     let mut app_type = String::new();
     let mut no_display = false;
     let mut hidden = false;
     let mut terminal = false;
-    let mut in_desktop_entry = false;
+    let mut startup_wm_class = String::new();
+    let mut action_ids: Vec<String> = Vec::new();
+    let mut action_groups: Vec<ActionGroup> = Vec::new();
+    let mut seen_entry = false;
+    let mut group = Group::Other;
 
     // Parse file line by line
     for line in content.lines() {
         let line = line.trim();
 
-        // Section detection
-        if line == "[Desktop Entry]" {
-            in_desktop_entry = true;
+        // Group detection; only the first [Desktop Entry] group counts
+        if line.starts_with('[') {
+            group = if line == "[Desktop Entry]" && !seen_entry {
+                seen_entry = true;
+                Group::Entry
+            } else if let Some(id) = line
+                .strip_prefix("[Desktop Action ")
+                .and_then(|l| l.strip_suffix(']'))
+            {
+                action_groups.push(ActionGroup::new(id));
+                Group::Action(action_groups.len() - 1)
+            } else {
+                Group::Other
+            };
             continue;
         }
-        // Exit Desktop Entry section if we encounter another section
-        if line.starts_with('[') && line != "[Desktop Entry]" {
-            if in_desktop_entry {
-                break;
+        match group {
+            Group::Entry => {}
+            Group::Action(index) => {
+                action_groups[index].parse_line(line, locale);
+                continue;
             }
-            continue;
+            Group::Other => continue,
         }
-        // Skip lines outside Desktop Entry section
-        if !in_desktop_entry {
-            continue;
+
+        // Localized keys: keep the entry that best matches the locale
+        if let Some((key, val)) = line.split_once('=') {
+            let (field, rank) = localized_key(key.trim_end(), locale);
+            let target = match field {
+                "Name" => Some(&mut name),
+                "GenericName" => Some(&mut generic_name),
+                "Comment" => Some(&mut comment),
+                _ => None,
+            };
+            if let Some(target) = target {
+                if let Some(rank) = rank {
+                    target.offer(rank, val);
+                }
+                continue;
+            }
         }
 
         // Parse key-value pairs
         if let Some(val) = line.strip_prefix("Type=") {
             app_type = val.trim().to_string();
-        } else if let Some(val) = line.strip_prefix("Name=") {
-            if name.is_none() {
-                name = Some(val.trim().to_string());
-            }
         } else if let Some(val) = line.strip_prefix("Exec=") {
             exec = Some(val.trim().to_string());
-        } else if let Some(val) = line.strip_prefix("Comment=") {
-            if description.is_empty() {
-                description = val.trim().to_string();
+        } else if let Some(val) = line.strip_prefix("Keywords=") {
+            if keywords.is_empty() {
+                keywords = val
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|k| !k.is_empty())
+                    .map(str::to_string)
+                    .collect();
             }
         } else if let Some(val) = line.strip_prefix("Icon=") {
             if icon.is_empty() {
@@ -374,6 +809,15 @@ pub(crate) fn parse_desktop_file(path: &Path) -> Option<DesktopApp> {
             hidden = val.trim().eq_ignore_ascii_case("true");
         } else if let Some(val) = line.strip_prefix("Terminal=") {
             terminal = val.trim().eq_ignore_ascii_case("true");
+        } else if let Some(val) = line.strip_prefix("StartupWMClass=") {
+            startup_wm_class = val.trim().to_string();
+        } else if let Some(val) = line.strip_prefix("Actions=") {
+            action_ids = val
+                .split(';')
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(str::to_string)
+                .collect();
         }
     }
 
@@ -395,7 +839,7 @@ pub(crate) fn parse_desktop_file(path: &Path) -> Option<DesktopApp> {
     }
 
     // Return parsed application (requires at least name and exec)
-    let Some(name) = name else {
+    let Some(name) = name.value else {
         debug!("Missing Name field in desktop file {}", path.display());
         return None;
     };
@@ -404,6 +848,16 @@ pub(crate) fn parse_desktop_file(path: &Path) -> Option<DesktopApp> {
         return None;
     };
 
+    let description = comment.value.unwrap_or_default();
+    let actions = action_ids
+        .iter()
+        .filter_map(|id| {
+            let index = action_groups.iter().position(|g| g.id == *id)?;
+            action_groups.swap_remove(index).into_action()
+        })
+        .collect();
+    let generic_name = generic_name.value.unwrap_or_default();
+
     trace!(
         "Successfully parsed desktop application: {name} from {}",
         path.display()
@@ -414,8 +868,13 @@ pub(crate) fn parse_desktop_file(path: &Path) -> Option<DesktopApp> {
         name,
         exec,
         description,
+        generic_name,
+        keywords,
         icon,
         terminal,
+        actions,
+        startup_wm_class,
+        path: path.to_path_buf(),
     })
 }
 
@@ -521,6 +980,92 @@ mod tests {
         assert_eq!(clean_exec("python3 -m myapp %u"), "python3 -m myapp");
     }
 
+    // ── desktop ID lookup tests ───────────────────────────────────────
+
+    fn app_with_id(id: &str) -> DesktopApp {
+        DesktopApp {
+            desktop_id: id.to_string(),
+            name: id.to_string(),
+            name_lower: id.to_lowercase(),
+            exec: id.to_string(),
+            description: String::new(),
+            generic_name: String::new(),
+            keywords: Vec::new(),
+            icon: String::new(),
+            terminal: false,
+            actions: Vec::new(),
+            startup_wm_class: String::new(),
+            path: PathBuf::from(format!("/usr/share/applications/{id}.desktop")),
+        }
+    }
+
+    #[test]
+    fn test_matches_desktop_id() {
+        let app = app_with_id("org.gnome.Nautilus");
+        assert!(matches_desktop_id(&app, "org.gnome.Nautilus"));
+        assert!(matches_desktop_id(&app, "org.gnome.Nautilus.desktop"));
+        assert!(!matches_desktop_id(&app, "org.gnome"));
+        assert!(!matches_desktop_id(&app, "nautilus"));
+    }
+
+    #[test]
+    fn test_apps_by_ids_follows_id_order() {
+        let apps: Vec<DesktopApp> = ["a-app", "firefox", "org.gnome.Nautilus"]
+            .into_iter()
+            .map(app_with_id)
+            .collect();
+        let ids: Vec<String> = [
+            "org.gnome.Nautilus.desktop",
+            "missing.desktop",
+            "firefox",
+            "firefox.desktop",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+        let found: Vec<&str> = apps_by_ids(&ids, &apps)
+            .into_iter()
+            .map(|app| app.desktop_id.as_str())
+            .collect();
+        assert_eq!(found, vec!["org.gnome.Nautilus", "firefox"]);
+        assert!(apps_by_ids(&[], &apps).is_empty());
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("wine-*", "wine-Programs-Uninstall"));
+        assert!(glob_matches("wine-*", "wine-"));
+        assert!(!glob_matches("wine-*", "winetricks"));
+        assert!(glob_matches("*-uninstall", "wine-foo-uninstall"));
+        assert!(glob_matches("org.*.Nautil?s", "org.gnome.Nautilus"));
+        assert!(glob_matches("a*b*c", "axxbyyc"));
+        assert!(!glob_matches("a*b*c", "axxbyy"));
+        assert!(glob_matches("*", ""));
+        assert!(!glob_matches("?", ""));
+        assert!(glob_matches("avahi-discover", "avahi-discover"));
+        assert!(!glob_matches("avahi", "avahi-discover"));
+    }
+
+    #[test]
+    fn test_without_blacklisted() {
+        let apps: Vec<DesktopApp> = [
+            "avahi-discover",
+            "firefox",
+            "wine-Programs-Foo-Uninstall",
+            "wine-Programs-Bar",
+        ]
+        .into_iter()
+        .map(app_with_id)
+        .collect();
+        let blacklist = vec!["avahi-discover.desktop".to_string(), "wine-*".to_string()];
+        let kept: Vec<String> = without_blacklisted(&apps, &blacklist)
+            .into_iter()
+            .map(|app| app.desktop_id)
+            .collect();
+        assert_eq!(kept, vec!["firefox"]);
+        assert_eq!(without_blacklisted(&apps, &[]).len(), apps.len());
+    }
+
     // ── parse_desktop_file tests ──────────────────────────────────────
 
     fn write_temp_desktop(dir: &Path, name: &str, content: &str) -> PathBuf {
@@ -539,13 +1084,30 @@ mod tests {
             "[Desktop Entry]\nType=Application\nName=Test App\nExec=test-app %f\nIcon=test-icon\nComment=A test application\n",
         );
 
-        let app = parse_desktop_file(&path).unwrap();
+        let app = parse_desktop_file(&path, &DesktopLocale::default()).unwrap();
         assert_eq!(app.name, "Test App");
         assert_eq!(app.exec, "test-app %f");
         assert_eq!(app.icon, "test-icon");
         assert_eq!(app.description, "A test application");
         assert!(!app.terminal);
         assert_eq!(app.desktop_id, "test-app");
+        assert_eq!(app.startup_wm_class, "");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_parse_desktop_file_keywords() {
+        let dir = std::env::temp_dir().join("grunner_test_desktop_keywords");
+        let _ = fs::create_dir_all(&dir);
+        let path = write_temp_desktop(
+            &dir,
+            "disks.desktop",
+            "[Desktop Entry]\nType=Application\nName=Disks\nExec=gnome-disks\nKeywords[de]=Festplatte;\nKeywords=disk; drive;;volume;\n",
+        );
+
+        let app = parse_desktop_file(&path, &DesktopLocale::default()).unwrap();
+        assert_eq!(app.keywords, vec!["disk", "drive", "volume"]);
 
         let _ = fs::remove_dir_all(&dir);
     }
@@ -560,7 +1122,7 @@ mod tests {
             "[Desktop Entry]\nType=Link\nName=Link\nURL=http://example.com\n",
         );
 
-        assert!(parse_desktop_file(&path).is_none());
+        assert!(parse_desktop_file(&path, &DesktopLocale::default()).is_none());
         let _ = fs::remove_dir_all(&dir);
     }
 
@@ -574,7 +1136,7 @@ mod tests {
             "[Desktop Entry]\nType=Application\nName=Hidden\nExec=hidden\nNoDisplay=true\n",
         );
 
-        assert!(parse_desktop_file(&path).is_none());
+        assert!(parse_desktop_file(&path, &DesktopLocale::default()).is_none());
         let _ = fs::remove_dir_all(&dir);
     }
 
@@ -588,7 +1150,7 @@ mod tests {
             "[Desktop Entry]\nType=Application\nName=Hidden2\nExec=hidden2\nHidden=true\n",
         );
 
-        assert!(parse_desktop_file(&path).is_none());
+        assert!(parse_desktop_file(&path, &DesktopLocale::default()).is_none());
         let _ = fs::remove_dir_all(&dir);
     }
 
@@ -602,7 +1164,7 @@ mod tests {
             "[Desktop Entry]\nType=Application\nExec=noname\n",
         );
 
-        assert!(parse_desktop_file(&path).is_none());
+        assert!(parse_desktop_file(&path, &DesktopLocale::default()).is_none());
         let _ = fs::remove_dir_all(&dir);
     }
 
@@ -616,7 +1178,7 @@ mod tests {
             "[Desktop Entry]\nType=Application\nName=NoExec\n",
         );
 
-        assert!(parse_desktop_file(&path).is_none());
+        assert!(parse_desktop_file(&path, &DesktopLocale::default()).is_none());
         let _ = fs::remove_dir_all(&dir);
     }
 
@@ -630,11 +1192,26 @@ mod tests {
             "[Desktop Entry]\nType=Application\nName=Terminal App\nExec=term-app\nTerminal=true\n",
         );
 
-        let app = parse_desktop_file(&path).unwrap();
+        let app = parse_desktop_file(&path, &DesktopLocale::default()).unwrap();
         assert!(app.terminal);
         let _ = fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn test_parse_desktop_file_startup_wm_class() {
+        let dir = std::env::temp_dir().join("grunner_test_desktop_wm_class");
+        let _ = fs::create_dir_all(&dir);
+        let path = write_temp_desktop(
+            &dir,
+            "code.desktop",
+            "[Desktop Entry]\nType=Application\nName=Code\nExec=/usr/share/code/code %F\nStartupWMClass=Code \n\n[Desktop Action new-empty-window]\nName=New Window\nExec=code --new-window\nStartupWMClass=Other\n",
+        );
+
+        let app = parse_desktop_file(&path, &DesktopLocale::default()).unwrap();
+        assert_eq!(app.startup_wm_class, "Code");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_parse_desktop_file_multiple_sections() {
         let dir = std::env::temp_dir().join("grunner_test_desktop_multi");
@@ -645,7 +1222,7 @@ mod tests {
             "[Desktop Entry]\nType=Application\nName=Multi\nExec=multi\n\n[Another Section]\nFoo=bar\n",
         );
 
-        let app = parse_desktop_file(&path).unwrap();
+        let app = parse_desktop_file(&path, &DesktopLocale::default()).unwrap();
         assert_eq!(app.name, "Multi");
         let _ = fs::remove_dir_all(&dir);
     }
@@ -653,7 +1230,7 @@ mod tests {
     #[test]
     fn test_parse_desktop_file_nonexistent() {
         let path = Path::new("/nonexistent/path/app.desktop");
-        assert!(parse_desktop_file(path).is_none());
+        assert!(parse_desktop_file(path, &DesktopLocale::default()).is_none());
     }
 
     #[test]
@@ -666,8 +1243,308 @@ mod tests {
             "[Desktop Entry]\nType=Application\nName=Example\nExec=example\n",
         );
 
-        let app = parse_desktop_file(&path).unwrap();
+        let app = parse_desktop_file(&path, &DesktopLocale::default()).unwrap();
         assert_eq!(app.desktop_id, "org.example.App");
         let _ = fs::remove_dir_all(&dir);
     }
+
+    // ── locale tests ──────────────────────────────────────────────────
+
+    #[test]
+    fn test_desktop_locale_parse() {
+        let locale = DesktopLocale::parse("it_IT.UTF-8");
+        assert_eq!(locale.tag(), "it_IT");
+        assert_eq!(
+            DesktopLocale::parse("sr_RS.UTF-8@latin").tag(),
+            "sr_RS@latin"
+        );
+        assert_eq!(DesktopLocale::parse("de").tag(), "de");
+        assert_eq!(DesktopLocale::parse("C.UTF-8"), DesktopLocale::default());
+        assert_eq!(DesktopLocale::parse("POSIX"), DesktopLocale::default());
+        assert_eq!(DesktopLocale::parse(""), DesktopLocale::default());
+    }
+
+    #[test]
+    fn test_desktop_locale_match_rank() {
+        let locale = DesktopLocale::parse("sr_RS@latin");
+        assert_eq!(locale.match_rank("sr_RS@latin"), Some(0));
+        assert_eq!(locale.match_rank("sr_RS"), Some(1));
+        assert_eq!(locale.match_rank("sr@latin"), Some(2));
+        assert_eq!(locale.match_rank("sr"), Some(3));
+        assert_eq!(locale.match_rank("sr_ME"), None);
+        assert_eq!(locale.match_rank("sr@ijekavian"), None);
+        assert_eq!(locale.match_rank("de"), None);
+
+        // Without a country, lang_COUNTRY keys do not apply
+        assert_eq!(DesktopLocale::parse("it").match_rank("it_IT"), None);
+        assert_eq!(DesktopLocale::default().match_rank("it"), None);
+    }
+
+    #[test]
+    fn test_parse_desktop_file_localized() {
+        let dir = std::env::temp_dir().join("grunner_test_desktop_localized");
+        let _ = fs::create_dir_all(&dir);
+        let path = write_temp_desktop(
+            &dir,
+            "files.desktop",
+            "[Desktop Entry]\nType=Application\nExec=nautilus\nName[it_CH]=File (CH)\nName=Files\nName[it]=File\nName[de]=Dateien\nComment=Access files\nComment[it]=Accedi ai file\n",
+        );
+
+        let app = parse_desktop_file(&path, &DesktopLocale::parse("it_IT.UTF-8")).unwrap();
+        assert_eq!(app.name, "File");
+        assert_eq!(app.name_lower, "file");
+        assert_eq!(app.description, "Accedi ai file");
+
+        let app = parse_desktop_file(&path, &DesktopLocale::parse("it_CH")).unwrap();
+        assert_eq!(app.name, "File (CH)");
+
+        let app = parse_desktop_file(&path, &DesktopLocale::parse("fr_FR")).unwrap();
+        assert_eq!(app.name, "Files");
+        assert_eq!(app.description, "Access files");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_parse_desktop_file_generic_name() {
+        let dir = std::env::temp_dir().join("grunner_test_desktop_generic");
+        let _ = fs::create_dir_all(&dir);
+        let path = write_temp_desktop(
+            &dir,
+            "browser.desktop",
+            "[Desktop Entry]\nType=Application\nName=Firefox\nExec=firefox\nGenericName=Web Browser\nGenericName[it]=Browser web\n",
+        );
+
+        let app = parse_desktop_file(&path, &DesktopLocale::default()).unwrap();
+        assert_eq!(app.generic_name, "Web Browser");
+        assert_eq!(app.description, "");
+        let app = parse_desktop_file(&path, &DesktopLocale::parse("it_IT")).unwrap();
+        assert_eq!(app.generic_name, "Browser web");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    // ── desktop action tests ──────────────────────────────────────────
+
+    #[test]
+    fn test_parse_desktop_file_actions() {
+        let dir = std::env::temp_dir().join("grunner_test_desktop_actions");
+        let _ = fs::create_dir_all(&dir);
+        let path = write_temp_desktop(
+            &dir,
+            "firefox.desktop",
+            "[Desktop Entry]\nType=Application\nName=Firefox\nExec=firefox %u\nActions=private;new-window;\n\n\
+             [Desktop Action new-window]\nName=New Window\nName[it]=Nuova finestra\nExec=firefox --new-window %u\n\n\
+             [Desktop Action private]\nName=New Private Window\nExec=firefox --private-window %u\nIcon=firefox-private\n",
+        );
+
+        let app = parse_desktop_file(&path, &DesktopLocale::default()).unwrap();
+        assert_eq!(app.exec, "firefox %u");
+        assert_eq!(
+            app.actions,
+            vec![
+                DesktopAction {
+                    id: "private".to_string(),
+                    name: "New Private Window".to_string(),
+                    exec: "firefox --private-window %u".to_string(),
+                    icon: "firefox-private".to_string(),
+                },
+                DesktopAction {
+                    id: "new-window".to_string(),
+                    name: "New Window".to_string(),
+                    exec: "firefox --new-window %u".to_string(),
+                    icon: String::new(),
+                },
+            ]
+        );
+
+        let app = parse_desktop_file(&path, &DesktopLocale::parse("it_IT")).unwrap();
+        assert_eq!(app.name, "Firefox");
+        assert_eq!(app.actions[1].name, "Nuova finestra");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_parse_desktop_file_skips_unlisted_and_incomplete_actions() {
+        let dir = std::env::temp_dir().join("grunner_test_desktop_bad_actions");
+        let _ = fs::create_dir_all(&dir);
+        let path = write_temp_desktop(
+            &dir,
+            "app.desktop",
+            "[Desktop Entry]\nType=Application\nName=App\nExec=app\nActions=no-exec;missing;ok;\n\n\
+             [Desktop Action no-exec]\nName=No Exec\n\n\
+             [Desktop Action ok]\nName=Ok\nExec=app --ok\n\n\
+             [Desktop Action unlisted]\nName=Unlisted\nExec=app --unlisted\n",
+        );
+
+        let app = parse_desktop_file(&path, &DesktopLocale::default()).unwrap();
+        let ids: Vec<&str> = app.actions.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, vec!["ok"]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    // ── cache format tests ────────────────────────────────────────────
+
+    #[test]
+    fn test_decode_cache_checks_version() {
+        let dir = std::env::temp_dir().join("grunner_test_cache_version");
+        let _ = fs::create_dir_all(&dir);
+        let path = write_temp_desktop(
+            &dir,
+            "firefox.desktop",
+            "[Desktop Entry]\nType=Application\nName=Firefox\nGenericName=Web Browser\nExec=firefox\nKeywords=Internet;WWW;Browser;\n",
+        );
+        let apps = vec![parse_desktop_file(&path, &DesktopLocale::default()).unwrap()];
+        let encode = |version| {
+            versioned_cache::encode(
+                version,
+                &AppCacheRef {
+                    snapshot: 7,
+                    locale: "it_IT",
+                    dirs: &[PathBuf::from("/usr/share/applications")],
+                    apps: &apps,
+                },
+            )
+            .unwrap()
+        };
+        let decode_cache = |bytes: &[u8]| {
+            versioned_cache::decode::<AppCache>(bytes, CACHE_VERSION)
+                .ok()
+                .map(loaded_cache)
+        };
+
+        let cache = decode_cache(&encode(CACHE_VERSION)).unwrap();
+        assert_eq!(cache.snapshot, 7);
+        assert_eq!(cache.locale, "it_IT");
+        assert_eq!(cache.dirs, vec![PathBuf::from("/usr/share/applications")]);
+        assert_eq!(cache.apps[0].generic_name, "Web Browser");
+        assert_eq!(cache.apps[0].keywords, vec!["Internet", "WWW", "Browser"]);
+        assert_eq!(cache.apps[0].name_lower, "firefox");
+
+        assert!(decode_cache(&encode(CACHE_VERSION - 1)).is_none());
+        assert!(decode_cache(&[1, 2]).is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    // ── cache snapshot tests ──────────────────────────────────────────
+
+    fn at(secs: u64) -> Option<SystemTime> {
+        Some(UNIX_EPOCH + std::time::Duration::from_secs(secs))
+    }
+
+    fn snapshot(entries: &[(&str, Option<SystemTime>)]) -> Vec<SnapshotEntry> {
+        entries
+            .iter()
+            .map(|(p, t)| (PathBuf::from(p), *t, 100))
+            .collect()
+    }
+
+    #[test]
+    fn test_snapshot_hash_is_deterministic() {
+        let a = snapshot(&[("/apps/a.desktop", at(10)), ("/apps/b.desktop", at(20))]);
+        assert_eq!(snapshot_hash(&a), snapshot_hash(&a.clone()));
+        assert!(is_snapshot_current(snapshot_hash(&a), &a));
+    }
+
+    #[test]
+    fn test_snapshot_hash_detects_changes() {
+        let base = snapshot(&[("/apps/a.desktop", at(10)), ("/apps/b.desktop", at(20))]);
+        let cached = snapshot_hash(&base);
+
+        let edited = snapshot(&[("/apps/a.desktop", at(10)), ("/apps/b.desktop", at(21))]);
+        let added = snapshot(&[
+            ("/apps/a.desktop", at(10)),
+            ("/apps/b.desktop", at(20)),
+            ("/apps/c.desktop", at(5)),
+        ]);
+        let removed = snapshot(&[("/apps/a.desktop", at(10))]);
+        let renamed = snapshot(&[("/apps/a.desktop", at(10)), ("/apps/d.desktop", at(20))]);
+        let unreadable = snapshot(&[("/apps/a.desktop", at(10)), ("/apps/b.desktop", None)]);
+
+        // Same mtime, different length: an edit within the mtime resolution
+        let mut resized = base.clone();
+        resized[1].2 = 101;
+
+        for changed in [&edited, &added, &removed, &renamed, &unreadable, &resized] {
+            assert!(!is_snapshot_current(cached, changed));
+        }
+        assert!(!is_snapshot_current(cached, &[]));
+    }
+
+    #[test]
+    fn test_snapshot_hash_path_boundaries() {
+        // Moving bytes between adjacent paths must change the hash
+        let a = snapshot(&[("/x/ab", at(1)), ("/x/c", at(1))]);
+        let b = snapshot(&[("/x/a", at(1)), ("/x/bc", at(1))]);
+        assert_ne!(snapshot_hash(&a), snapshot_hash(&b));
+    }
+
+    #[test]
+    fn test_desktop_snapshot_tracks_directory() {
+        let dir = std::env::temp_dir().join("grunner_test_desktop_snapshot");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        let app = write_temp_desktop(&dir, "b.desktop", "[Desktop Entry]\n");
+        write_temp_desktop(&dir.join("sub"), "a.desktop", "[Desktop Entry]\n");
+        write_temp_desktop(&dir, "notes.txt", "ignored");
+
+        // Listing the same directory twice does not duplicate entries
+        let dirs = [dir.clone(), dir.clone()];
+        let first = desktop_snapshot(&dirs);
+        let paths: Vec<&Path> = first.iter().map(|(p, _, _)| p.as_path()).collect();
+        assert_eq!(
+            paths,
+            vec![app.as_path(), dir.join("sub/a.desktop").as_path()]
+        );
+        let cached = snapshot_hash(&first);
+        assert!(is_snapshot_current(cached, &desktop_snapshot(&dirs)));
+
+        let file = fs::File::options().write(true).open(&app).unwrap();
+        file.set_modified(UNIX_EPOCH + std::time::Duration::from_secs(1))
+            .unwrap();
+        assert!(!is_snapshot_current(cached, &desktop_snapshot(&dirs)));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_merge_rescanned_replaces_changed_dirs() {
+        let mut zed = app_with_id("zed");
+        zed.path = PathBuf::from("/home/u/.local/share/applications/zed.desktop");
+        let current = vec![app_with_id("firefox"), app_with_id("gimp"), zed];
+        // Gimp was removed and Blender installed system-wide
+        let rescanned = vec![app_with_id("firefox"), app_with_id("blender")];
+        let merged = merge_rescanned(
+            current,
+            &[PathBuf::from("/usr/share/applications")],
+            rescanned,
+        );
+        let ids: Vec<&str> = merged.iter().map(|a| a.desktop_id.as_str()).collect();
+        assert_eq!(ids, vec!["blender", "firefox", "zed"]);
+    }
+
+    #[test]
+    fn test_is_cache_for_checks_locale_and_dirs() {
+        let dirs = vec![PathBuf::from("/a"), PathBuf::from("/b")];
+        let cache = AppCache {
+            snapshot: 0,
+            locale: DesktopLocale::default().tag(),
+            dirs: dirs.clone(),
+            apps: Vec::new(),
+        };
+        assert!(is_cache_for(&cache, &dirs, &DesktopLocale::default()));
+
+        // Reordered, removed or added directories all need a rescan
+        let reordered = vec![PathBuf::from("/b"), PathBuf::from("/a")];
+        assert!(!is_cache_for(&cache, &reordered, &DesktopLocale::default()));
+        assert!(!is_cache_for(&cache, &dirs[..1], &DesktopLocale::default()));
+
+        let other_locale = AppCache {
+            locale: "de_DE".to_string(),
+            ..cache
+        };
+        assert!(!is_cache_for(
+            &other_locale,
+            &dirs,
+            &DesktopLocale::default()
+        ));
+    }
 }
@@ -3,37 +3,85 @@
 //! This crate provides the core functionality for the Grunner application launcher.
 //! It includes configuration management, search providers, and other utilities.
 
+#![forbid(unsafe_code)]
+
 pub mod actions;
 pub mod app_mode;
+pub mod app_origin;
+pub mod autostart;
 pub mod calculator;
+pub mod cli;
+pub mod clipboard_history;
 pub mod command_handler;
+pub mod command_safety;
 pub mod core {
     pub mod callbacks;
     pub mod config;
+    pub mod config_include;
     pub mod global_state;
+    pub mod obsidian;
     pub mod theme;
 }
+pub mod dmenu;
+pub mod emoji;
+pub mod first_seen;
+pub mod history;
 pub mod item_activation;
 pub mod launcher;
 pub mod logging;
+pub mod metrics;
 pub mod model {
+    pub mod auto_launch;
+    pub mod command_row;
     pub mod debounce;
     pub mod items;
     pub mod list_model;
+    pub mod main_thread;
     pub mod model_config;
+    pub mod provider_rows;
     pub mod search_state;
+    pub mod worker;
 }
+pub mod processes;
 pub mod providers;
+pub mod query_history;
+pub mod scratchpad;
 pub mod settings_window;
 pub mod ui {
+    pub mod animation;
+    pub mod auto_launch;
+    pub mod compat;
     pub mod context_menu;
+    pub mod debug_overlay;
+    pub mod dialogs;
+    pub mod dir_watch;
+    pub mod direction;
+    pub mod dmenu_window;
+    pub mod icons;
+    pub mod layer_shell;
     pub mod list_factory;
+    pub mod man_viewer;
+    pub mod mnemonics;
+    pub mod navigation;
     pub mod obsidian_bar;
     pub mod pinned_strip;
     pub mod power_bar;
+    pub mod provider_blacklist;
+    pub mod provider_hint;
+    pub mod quick_select;
+    pub mod result_position;
     pub mod result_row;
+    pub mod row_kind;
+    pub mod severity;
+    pub mod status_footer;
+    pub mod surface;
+    pub mod tab_complete;
     pub mod window;
     pub mod window_context;
+    pub mod window_height;
     pub mod workspace_bar;
 }
+pub mod units;
 pub mod utils;
+pub mod versioned_cache;
+pub mod window_switcher;
@@ -1,12 +1,17 @@
 use glib::ExitCode;
-use grunner::{core, logging, providers, ui};
+use grunner::cli::{self, Command};
+use grunner::ui::compat::Application;
+use grunner::{actions, autostart, core, logging, providers, scratchpad, ui};
+use gtk4::gio;
 use gtk4::prelude::*;
-use lexopt::prelude::*;
-use libadwaita::Application;
+use std::cell::RefCell;
 
 /// Application ID for D-Bus and GNOME Shell integration
 const APP_ID: &str = "org.nihmar.grunner";
 
+/// Application ID of `--dmenu` pickers
+const DMENU_APP_ID: &str = "org.nihmar.grunner.Dmenu";
+
 /// Main entry point for the Grunner application
 ///
 /// Grunner is a GTK4 application launcher with Obsidian integration and power controls.
@@ -27,32 +32,32 @@ fn main() -> glib::ExitCode {
 }
 
 fn run() -> Result<ExitCode, lexopt::Error> {
-    let mut parser = lexopt::Parser::from_env();
-    let mut disable_modes = false;
-
-    while let Some(arg) = parser.next()? {
-        match arg {
-            Short('h') | Long("help") => {
-                print_help();
-                return Ok(ExitCode::SUCCESS);
-            }
-            Short('v' | 'V') | Long("version") => {
-                println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
-                return Ok(ExitCode::SUCCESS);
-            }
-            Short('s') | Long("simple") => {
-                disable_modes = true;
-            }
-            Long("list-providers") => {
-                print_providers();
-                return Ok(ExitCode::SUCCESS);
-            }
-            _ => return Err(arg.unexpected()),
+    let mut options = match cli::parse(std::env::args_os().skip(1))? {
+        Command::Run(options) => options,
+        Command::Help => {
+            print_help();
+            return Ok(ExitCode::SUCCESS);
         }
-    }
+        Command::Version => {
+            println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+            return Ok(ExitCode::SUCCESS);
+        }
+        Command::ListProviders => {
+            print_providers();
+            return Ok(ExitCode::SUCCESS);
+        }
+        Command::InitConfig => return Ok(init_config()),
+        Command::Dmenu => return Ok(dmenu()),
+        Command::InstallAutostart { force } => {
+            return Ok(change_autostart(|path| autostart::install(path, force)));
+        }
+        Command::RemoveAutostart { force } => {
+            return Ok(change_autostart(|path| autostart::remove(path, force)));
+        }
+    };
 
-    // GRUNNER_SIMPLE=1 also enables simple mode (recommended over -s since GTK may intercept it)
-    disable_modes |= std::env::var("GRUNNER_SIMPLE").is_ok();
+    // GRUNNER_SIMPLE=1 also enables simple mode
+    options.disable_modes |= std::env::var("GRUNNER_SIMPLE").is_ok();
 
     // Initialize logging system
     if let Err(e) = logging::init() {
@@ -63,9 +68,31 @@ fn run() -> Result<ExitCode, lexopt::Error> {
     log::info!("Grunner {} starting up", env!("CARGO_PKG_VERSION"));
 
     let mut cfg = core::config::load();
-    cfg.disable_modes = disable_modes;
+    cfg.disable_modes = options.disable_modes;
 
-    let app = Application::builder().application_id(APP_ID).build();
+    // A scratchpad registers under its own ID, so window manager rules can
+    // match it and `grunner --scratchpad` toggles that instance. The program
+    // name sets the X11 WM_CLASS and must be set before the app registers.
+    let app_id = if options.scratchpad {
+        glib::set_prgname(Some(scratchpad::SCRATCHPAD_PRGNAME));
+        scratchpad::print_rule_once();
+        scratchpad::SCRATCHPAD_APP_ID
+    } else {
+        APP_ID
+    };
+    let app = Application::builder().application_id(app_id).build();
+
+    // Resident instances keep running while the window is hidden. Startup
+    // only runs in the primary instance; later invocations just activate it.
+    let resident = options.resident();
+    let hold = RefCell::new(None);
+    app.connect_startup(move |app| {
+        actions::launch_failure::register_open_desktop_file_action(app);
+        if resident {
+            log::info!("Staying resident until the session ends");
+            hold.replace(Some(app.hold()));
+        }
+    });
 
     app.connect_activate(move |app| {
         log::debug!("Application activated");
@@ -92,7 +119,7 @@ fn run() -> Result<ExitCode, lexopt::Error> {
 
             if win.is_visible() {
                 log::debug!("Hiding window");
-                win.hide();
+                win.set_visible(false);
             } else {
                 log::debug!("Presenting window");
                 win.present();
@@ -101,10 +128,12 @@ fn run() -> Result<ExitCode, lexopt::Error> {
         }
         log::debug!("No launcher window found, building new UI");
 
-        ui::window::build_ui(app, &cfg);
+        ui::window::build_ui(app, &cfg, options.present());
     });
 
-    Ok(app.run())
+    // Our options are parsed above; GTK only gets the program name
+    let program: Vec<String> = std::env::args().take(1).collect();
+    Ok(app.run_with_args(&program))
 }
 
 fn print_help() {
@@ -116,10 +145,91 @@ fn print_help() {
     println!("  -h, --help            Show this help message");
     println!("  -v, --version         Show version information");
     println!("  -s, --simple          Simple mode: only app search, hide power bar");
+    println!("      --daemon          Stay running in the background with the window hidden");
+    println!("      --toggle          Show or hide the window, starting a resident instance");
+    println!("                        if none is running");
+    println!("      --scratchpad      Like --toggle, but start hidden under the app ID");
+    println!(
+        "                        {} for window manager rules",
+        scratchpad::SCRATCHPAD_APP_ID
+    );
     println!("      --list-providers  List available GNOME Shell search providers");
+    println!("      --init-config     Write the default configuration file if there is none");
+    println!("      --dmenu           Pick one of the lines of standard input and print it");
+    println!("      --install-autostart");
+    println!("                        Run `grunner --daemon` at login");
+    println!("      --remove-autostart");
+    println!("                        Stop running grunner at login");
+    println!("      --force           With the above, also replace or remove an autostart");
+    println!("                        entry that was edited by hand");
     println!();
     println!("Environment variables:");
-    println!("  GRUNNER_SIMPLE=1      Enable simple mode (recommended, more reliable than -s)");
+    println!("  GRUNNER_SIMPLE=1      Enable simple mode");
+    println!("  GRUNNER_CONFIG=PATH   Read the configuration from PATH and never write it");
+}
+
+/// Run `--dmenu`: print the picked line, or fail if none was picked
+fn dmenu() -> ExitCode {
+    if let Err(e) = logging::init() {
+        eprintln!("Failed to initialize logging: {e}");
+    }
+    let cfg = core::config::load();
+    // Pickers run next to the launcher and each other
+    let app = Application::builder()
+        .application_id(DMENU_APP_ID)
+        .flags(gio::ApplicationFlags::NON_UNIQUE)
+        .build();
+    let choice = ui::dmenu_window::DmenuChoice::default();
+    let picked = choice.clone();
+    app.connect_activate(move |app| ui::dmenu_window::build_dmenu_window(app, &cfg, &picked));
+
+    let program: Vec<String> = std::env::args().take(1).collect();
+    let code = app.run_with_args(&program);
+    match choice.take() {
+        Some(line) if code == ExitCode::SUCCESS => {
+            println!("{line}");
+            ExitCode::SUCCESS
+        }
+        _ => ExitCode::FAILURE,
+    }
+}
+
+fn init_config() -> ExitCode {
+    match core::config::init_config() {
+        Ok(Some(path)) => {
+            println!("Created {}", path.display());
+            ExitCode::SUCCESS
+        }
+        Ok(None) => {
+            println!(
+                "Not created: {} already exists, is set by {} or is not writable",
+                core::config::config_path().display(),
+                core::config::CONFIG_ENV
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("grunner: failed to create configuration: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Install or remove the autostart entry with `change` and print the result
+fn change_autostart(
+    change: impl FnOnce(&std::path::Path) -> Result<autostart::Outcome, autostart::AutostartError>,
+) -> ExitCode {
+    let path = autostart::autostart_path();
+    match change(&path) {
+        Ok(outcome) => {
+            println!("{}", outcome.message(&path));
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("grunner: {e}");
+            ExitCode::FAILURE
+        }
+    }
 }
 
 fn print_providers() {
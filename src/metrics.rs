@@ -0,0 +1,421 @@
+//! Timings shown by the F12 debug overlay
+//!
+//! Search code paths measure themselves with [`start`] and hand the result
+//! to [`record_elapsed`]; the overlay reads a [`get`] snapshot every 500 ms
+//! while it is visible. While the overlay is hidden, [`start`] returns
+//! `None` after a single atomic load, so nothing is timed, locked or stored.
+//!
+//! App searches are also timed all the time for the [`LatencyController`],
+//! which trims search work on systems too slow to keep up with typing.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Whether measurements are recorded (the overlay is visible)
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// The latest measurements
+static METRICS: Mutex<Metrics> = Mutex::new(Metrics::new());
+
+/// The latest measurement of each timed code path
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Metrics {
+    /// Last app search, from the query to the filled store
+    pub populate: Option<Duration>,
+    /// Last colon command subprocess, from spawn to exit
+    pub subprocess: Option<Duration>,
+    /// Last round trip of each search provider, by desktop id
+    pub providers: BTreeMap<String, Duration>,
+    /// Last replacement of the result store, including the list view update
+    pub splice: Option<Duration>,
+}
+
+impl Metrics {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            populate: None,
+            subprocess: None,
+            providers: BTreeMap::new(),
+            splice: None,
+        }
+    }
+
+    /// Multi-line text shown by the overlay
+    ///
+    /// `app_count`, `store_size` and `cuts` are current values read from
+    /// the model, not measurements.
+    #[must_use]
+    pub fn report(&self, app_count: usize, store_size: u32, cuts: SearchCuts) -> String {
+        let mut lines = vec![
+            format!("populate    {}", format_duration(self.populate)),
+            format!("subprocess  {}", format_duration(self.subprocess)),
+            format!("splice      {}", format_duration(self.splice)),
+            format!("apps        {app_count}"),
+            format!("store       {store_size}"),
+            format!("degraded    {}", cuts.describe()),
+        ];
+        if self.providers.is_empty() {
+            lines.push("providers   —".to_string());
+        } else {
+            lines.push("providers".to_string());
+            lines.extend(
+                self.providers
+                    .iter()
+                    .map(|(id, elapsed)| format!("  {id}  {}", format_duration(Some(*elapsed)))),
+            );
+        }
+        lines.join("\n")
+    }
+}
+
+/// `12.3 ms`, or `—` for a path that has not run since recording started
+fn format_duration(duration: Option<Duration>) -> String {
+    duration.map_or_else(
+        || "—".to_string(),
+        |d| format!("{:.1} ms", d.as_secs_f64() * 1000.0),
+    )
+}
+
+/// How much search work is cut to stay within the latency budget
+///
+/// Each level keeps the cuts of the one before it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Degradation {
+    /// Everything is searched
+    #[default]
+    Full,
+    /// App descriptions are not matched
+    NoDescriptions,
+    /// Fewer app results are listed, see [`reduced_max_results`]
+    FewerResults,
+    /// Search providers are not queried
+    NoProviders,
+}
+
+impl Degradation {
+    /// The next level down, or `self` at the lowest
+    #[must_use]
+    pub fn lower(self) -> Self {
+        match self {
+            Self::Full => Self::NoDescriptions,
+            Self::NoDescriptions => Self::FewerResults,
+            Self::FewerResults | Self::NoProviders => Self::NoProviders,
+        }
+    }
+
+    /// The next level up, or `self` at [`Degradation::Full`]
+    #[must_use]
+    pub fn raise(self) -> Self {
+        match self {
+            Self::Full | Self::NoDescriptions => Self::Full,
+            Self::FewerResults => Self::NoDescriptions,
+            Self::NoProviders => Self::FewerResults,
+        }
+    }
+
+    /// The work cut at this level
+    #[must_use]
+    pub fn cuts(self) -> SearchCuts {
+        SearchCuts {
+            descriptions: self >= Self::NoDescriptions,
+            results: self >= Self::FewerResults,
+            providers: self >= Self::NoProviders,
+        }
+    }
+}
+
+/// Search work left out, each flag `true` when cut
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchCuts {
+    /// App descriptions are not matched
+    pub descriptions: bool,
+    /// App results are capped at [`reduced_max_results`]
+    pub results: bool,
+    /// Search providers are not queried, and their rows not kept
+    pub providers: bool,
+}
+
+impl SearchCuts {
+    /// `descriptions, results`, or `—` when nothing is cut
+    #[must_use]
+    pub fn describe(self) -> String {
+        let cut: Vec<&str> = [
+            (self.descriptions, "descriptions"),
+            (self.results, "results"),
+            (self.providers, "providers"),
+        ]
+        .into_iter()
+        .filter_map(|(cut, name)| cut.then_some(name))
+        .collect();
+        if cut.is_empty() {
+            "—".to_string()
+        } else {
+            cut.join(", ")
+        }
+    }
+}
+
+/// Fewest app results listed when results are cut
+const MIN_REDUCED_RESULTS: usize = 5;
+
+/// Cap of app results when results are cut: half of `max_results`, but
+/// not below [`MIN_REDUCED_RESULTS`] unless `max_results` is
+#[must_use]
+pub fn reduced_max_results(max_results: usize) -> usize {
+    (max_results / 2).max(MIN_REDUCED_RESULTS).min(max_results)
+}
+
+/// Searches in a row over the budget before a level is dropped
+const SLOW_STREAK: u32 = 3;
+/// Searches in a row within half the budget before a level is restored
+const FAST_STREAK: u32 = 10;
+
+/// Lowers the [`Degradation`] level while app searches run over a budget,
+/// and raises it again once they are well within it
+///
+/// A level changes only after a streak of slow or fast searches, so a
+/// single hiccup does nothing. Searches between half the budget and the
+/// budget break both streaks, which keeps the level from flapping right at
+/// the limit.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LatencyController {
+    /// Time a search may take, `Duration::ZERO` to never degrade
+    budget: Duration,
+    level: Degradation,
+    slow: u32,
+    fast: u32,
+}
+
+impl LatencyController {
+    #[must_use]
+    pub fn new(budget: Duration) -> Self {
+        Self {
+            budget,
+            ..Self::default()
+        }
+    }
+
+    /// The current level
+    #[must_use]
+    pub fn level(&self) -> Degradation {
+        self.level
+    }
+
+    /// Change the budget, which starts over from [`Degradation::Full`]
+    pub fn set_budget(&mut self, budget: Duration) {
+        if budget != self.budget {
+            *self = Self::new(budget);
+        }
+    }
+
+    /// Count a search that took `elapsed`
+    ///
+    /// Returns the new level if it changed.
+    pub fn record(&mut self, elapsed: Duration) -> Option<Degradation> {
+        if self.budget.is_zero() {
+            return None;
+        }
+        if elapsed > self.budget {
+            self.slow += 1;
+            self.fast = 0;
+        } else if elapsed <= self.budget / 2 {
+            self.fast += 1;
+            self.slow = 0;
+        } else {
+            self.slow = 0;
+            self.fast = 0;
+        }
+
+        let next = if self.slow >= SLOW_STREAK {
+            self.level.lower()
+        } else if self.fast >= FAST_STREAK {
+            self.level.raise()
+        } else {
+            return None;
+        };
+        self.slow = 0;
+        self.fast = 0;
+        (next != self.level).then(|| {
+            self.level = next;
+            next
+        })
+    }
+}
+
+/// Start or stop recording
+///
+/// Stopping also forgets the measurements, so the overlay never shows
+/// numbers from before it was last opened.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+    if !enabled {
+        *lock() = Metrics::new();
+    }
+}
+
+/// Start timing a code path, or `None` when not recording
+#[must_use]
+pub fn start() -> Option<Instant> {
+    ENABLED.load(Ordering::Relaxed).then(Instant::now)
+}
+
+/// Store the time elapsed since `started` with `store`
+///
+/// Does nothing if `started` is `None`.
+pub fn record_elapsed(started: Option<Instant>, store: impl FnOnce(&mut Metrics, Duration)) {
+    if let Some(started) = started {
+        store(&mut lock(), started.elapsed());
+    }
+}
+
+/// A copy of the latest measurements
+#[must_use]
+pub fn get() -> Metrics {
+    lock().clone()
+}
+
+fn lock() -> std::sync::MutexGuard<'static, Metrics> {
+    METRICS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_before_any_measurement() {
+        let report = Metrics::new().report(0, 0, SearchCuts::default());
+        assert_eq!(
+            report,
+            "populate    —\n\
+             subprocess  —\n\
+             splice      —\n\
+             apps        0\n\
+             store       0\n\
+             degraded    —\n\
+             providers   —"
+        );
+    }
+
+    #[test]
+    fn test_report_formats_measurements() {
+        let mut metrics = Metrics::new();
+        metrics.populate = Some(Duration::from_micros(4_260));
+        metrics.splice = Some(Duration::from_micros(980));
+        metrics
+            .providers
+            .insert("org.gnome.Nautilus".to_string(), Duration::from_millis(120));
+        metrics.providers.insert(
+            "org.gnome.Calculator".to_string(),
+            Duration::from_micros(15_060),
+        );
+
+        let report = metrics.report(312, 41, Degradation::FewerResults.cuts());
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines[0], "populate    4.3 ms");
+        assert_eq!(lines[1], "subprocess  —");
+        assert_eq!(lines[2], "splice      1.0 ms");
+        assert_eq!(lines[3], "apps        312");
+        assert_eq!(lines[4], "store       41");
+        assert_eq!(lines[5], "degraded    descriptions, results");
+        // Providers are listed by id
+        assert_eq!(
+            &lines[6..],
+            [
+                "providers",
+                "  org.gnome.Calculator  15.1 ms",
+                "  org.gnome.Nautilus  120.0 ms"
+            ]
+        );
+    }
+
+    /// Feed `ms` timings to `controller`, returning the level changes
+    fn feed(controller: &mut LatencyController, ms: &[u64]) -> Vec<Degradation> {
+        ms.iter()
+            .filter_map(|ms| controller.record(Duration::from_millis(*ms)))
+            .collect()
+    }
+
+    #[test]
+    fn test_controller_degrades_after_slow_streak() {
+        let mut controller = LatencyController::new(Duration::from_millis(50));
+        // Two slow searches are a hiccup, the third drops a level
+        assert!(feed(&mut controller, &[80, 90]).is_empty());
+        assert_eq!(feed(&mut controller, &[70]), [Degradation::NoDescriptions]);
+        // Each further streak drops one more, down to the lowest level
+        assert_eq!(
+            feed(&mut controller, &[60, 60, 60, 60, 60, 60, 60, 60, 60]),
+            [Degradation::FewerResults, Degradation::NoProviders]
+        );
+        assert_eq!(controller.level(), Degradation::NoProviders);
+    }
+
+    #[test]
+    fn test_controller_streaks_are_reset() {
+        let mut controller = LatencyController::new(Duration::from_millis(50));
+        // A search within the budget breaks the slow streak
+        assert!(feed(&mut controller, &[80, 80, 30, 80, 80]).is_empty());
+        assert_eq!(controller.level(), Degradation::Full);
+    }
+
+    #[test]
+    fn test_controller_recovers_after_fast_streak() {
+        let mut controller = LatencyController::new(Duration::from_millis(50));
+        feed(&mut controller, &[80; 6]);
+        assert_eq!(controller.level(), Degradation::FewerResults);
+
+        // Within the budget but over half of it: no recovery
+        assert!(feed(&mut controller, &[40; 20]).is_empty());
+        // Nine fast searches are not enough, the tenth raises a level
+        assert!(feed(&mut controller, &[10; 9]).is_empty());
+        assert_eq!(feed(&mut controller, &[10]), [Degradation::NoDescriptions]);
+        assert_eq!(feed(&mut controller, &[5; 10]), [Degradation::Full]);
+        // Full behavior stays full
+        assert!(feed(&mut controller, &[5; 30]).is_empty());
+    }
+
+    #[test]
+    fn test_controller_without_budget_never_degrades() {
+        let mut controller = LatencyController::new(Duration::ZERO);
+        assert!(feed(&mut controller, &[500; 30]).is_empty());
+        assert_eq!(controller.level(), Degradation::Full);
+
+        // Setting a budget starts over, and so does turning it off
+        controller.set_budget(Duration::from_millis(50));
+        feed(&mut controller, &[500; 3]);
+        assert_eq!(controller.level(), Degradation::NoDescriptions);
+        controller.set_budget(Duration::ZERO);
+        assert_eq!(controller.level(), Degradation::Full);
+    }
+
+    #[test]
+    fn test_cuts_by_level() {
+        assert_eq!(Degradation::Full.cuts(), SearchCuts::default());
+        assert_eq!(Degradation::Full.cuts().describe(), "—");
+        assert_eq!(
+            Degradation::NoProviders.cuts().describe(),
+            "descriptions, results, providers"
+        );
+        assert_eq!(Degradation::Full.raise(), Degradation::Full);
+        assert_eq!(Degradation::NoProviders.lower(), Degradation::NoProviders);
+    }
+
+    #[test]
+    fn test_reduced_max_results() {
+        assert_eq!(reduced_max_results(64), 32);
+        assert_eq!(reduced_max_results(8), 5);
+        assert_eq!(reduced_max_results(3), 3);
+    }
+
+    #[test]
+    fn test_start_is_none_while_disabled() {
+        assert!(start().is_none());
+        record_elapsed(None, |m, d| m.populate = Some(d));
+        assert_eq!(get().populate, None);
+    }
+}
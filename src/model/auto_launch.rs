@@ -0,0 +1,204 @@
+//! Auto-launch decision logic
+//!
+//! When `search.auto_launch_threshold` is non-zero, grunner launches the top
+//! application on its own once the user stops typing, provided the result is
+//! unambiguous: the only result, an exact name match, or a score that
+//! reaches the threshold and clearly beats the runner-up. This module holds the pure decision so it can be tested
+//! without GTK; the countdown and activation live in `ui::auto_launch`.
+
+/// What kind of row a result is, as far as auto-launch is concerned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CandidateKind {
+    /// A desktop application (the only kind that may be auto-launched)
+    App,
+    /// A result from a GNOME Shell search provider
+    ProviderResult,
+    /// Power actions, calculator results, commands, errors and other
+    /// internal rows
+    Internal,
+}
+
+/// A single row of the current result list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Candidate {
+    /// Kind of row
+    pub kind: CandidateKind,
+    /// Match score from 0 to 100 (0 for rows that are not scored)
+    pub score: u8,
+    /// Whether the query is the row's whole name, ignoring case
+    pub exact_name: bool,
+}
+
+impl Candidate {
+    #[must_use]
+    pub fn new(kind: CandidateKind, score: u8) -> Self {
+        Self {
+            kind,
+            score,
+            exact_name: false,
+        }
+    }
+
+    /// Mark the row as named exactly like the query
+    #[must_use]
+    pub fn with_exact_name(mut self, exact_name: bool) -> Self {
+        self.exact_name = exact_name;
+        self
+    }
+}
+
+/// How far the top score must be ahead of the runner-up's
+///
+/// Every name prefix match scores 100, so with several of them the top row
+/// is only first by ranking order, not by being a better match.
+pub const MIN_SCORE_LEAD: u8 = 20;
+
+/// Check whether the result list is unambiguous enough to auto-launch
+///
+/// The top row must be an application, and it must be the only result
+/// left, be named exactly like the query, or have a score that reaches
+/// `threshold` and is at least [`MIN_SCORE_LEAD`] ahead of the second row.
+/// A threshold of 0 disables auto-launch entirely.
+///
+/// # Arguments
+/// * `candidates` - Rows of the result list, in display order
+/// * `threshold` - Configured `auto_launch_threshold` (0-100)
+///
+/// # Returns
+/// `true` if the top row qualifies for auto-launch.
+#[must_use]
+pub fn is_auto_launch_target(candidates: &[Candidate], threshold: u8) -> bool {
+    if threshold == 0 {
+        return false;
+    }
+
+    let Some(top) = candidates.first() else {
+        return false;
+    };
+
+    if top.kind != CandidateKind::App {
+        return false;
+    }
+
+    let Some(runner_up) = candidates.get(1) else {
+        return true;
+    };
+    top.exact_name
+        || (top.score >= threshold && top.score.saturating_sub(runner_up.score) >= MIN_SCORE_LEAD)
+}
+
+/// Decide whether the top result should be launched now
+///
+/// Combines [`is_auto_launch_target`] with the idle requirement: the user
+/// must not have typed anything for at least `required_idle_ms`.
+///
+/// # Arguments
+/// * `candidates` - Rows of the result list, in display order
+/// * `threshold` - Configured `auto_launch_threshold` (0-100)
+/// * `idle_ms` - Milliseconds since the last key press
+/// * `required_idle_ms` - Debounce period the user must stay idle for
+///
+/// # Returns
+/// `true` if the top row should be activated.
+#[must_use]
+pub fn should_auto_launch(
+    candidates: &[Candidate],
+    threshold: u8,
+    idle_ms: u64,
+    required_idle_ms: u64,
+) -> bool {
+    idle_ms >= required_idle_ms && is_auto_launch_target(candidates, threshold)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(score: u8) -> Candidate {
+        Candidate::new(CandidateKind::App, score)
+    }
+
+    #[test]
+    fn test_disabled_when_threshold_zero() {
+        assert!(!is_auto_launch_target(&[app(100)], 0));
+        assert!(!should_auto_launch(&[app(100)], 0, 1000, 300));
+    }
+
+    #[test]
+    fn test_no_results() {
+        assert!(!is_auto_launch_target(&[], 95));
+        assert!(!should_auto_launch(&[], 95, 1000, 300));
+    }
+
+    #[test]
+    fn test_single_app_result_launches_regardless_of_score() {
+        assert!(is_auto_launch_target(&[app(0)], 95));
+        assert!(is_auto_launch_target(&[app(40)], 100));
+    }
+
+    #[test]
+    fn test_top_score_reaching_threshold() {
+        assert!(!is_auto_launch_target(&[app(100), app(100)], 95));
+        assert!(is_auto_launch_target(&[app(95), app(50)], 95));
+        assert!(!is_auto_launch_target(&[app(94), app(50)], 95));
+        assert!(!is_auto_launch_target(&[app(50), app(50)], 95));
+    }
+
+    #[test]
+    fn test_top_score_must_beat_runner_up() {
+        assert!(is_auto_launch_target(&[app(100), app(80)], 95));
+        assert!(!is_auto_launch_target(&[app(100), app(81)], 95));
+    }
+
+    #[test]
+    fn test_one_letter_prefix_with_several_matches() {
+        // "f" is a name prefix of Firefox, Files and Fonts alike
+        let prefix_matches = [app(100), app(100), app(100), app(50)];
+        assert!(!is_auto_launch_target(&prefix_matches, 95));
+        assert!(!should_auto_launch(&prefix_matches, 95, 5000, 300));
+        assert!(!is_auto_launch_target(&prefix_matches, 1));
+    }
+
+    #[test]
+    fn test_exact_name_match_launches() {
+        let exact = app(100).with_exact_name(true);
+        assert!(is_auto_launch_target(&[exact, app(100)], 95));
+        assert!(!is_auto_launch_target(&[app(100), exact], 95));
+        assert!(!is_auto_launch_target(&[exact, app(100)], 0));
+    }
+
+    #[test]
+    fn test_never_launches_internal_rows() {
+        let internal = Candidate::new(CandidateKind::Internal, 100);
+        assert!(!is_auto_launch_target(&[internal], 1));
+        assert!(!is_auto_launch_target(&[internal, app(100)], 1));
+    }
+
+    #[test]
+    fn test_never_launches_provider_results() {
+        let provider = Candidate::new(CandidateKind::ProviderResult, 100);
+        assert!(!is_auto_launch_target(&[provider], 1));
+        assert!(!is_auto_launch_target(&[provider, app(100)], 1));
+    }
+
+    #[test]
+    fn test_trailing_non_app_rows_count_as_competition() {
+        // An app followed by a calculator row is not the only result
+        let calc = Candidate::new(CandidateKind::Internal, 0);
+        assert!(!is_auto_launch_target(&[app(50), calc], 95));
+        assert!(is_auto_launch_target(&[app(100), calc], 95));
+    }
+
+    #[test]
+    fn test_requires_idle_period() {
+        assert!(!should_auto_launch(&[app(100)], 95, 0, 300));
+        assert!(!should_auto_launch(&[app(100)], 95, 299, 300));
+        assert!(should_auto_launch(&[app(100)], 95, 300, 300));
+        assert!(should_auto_launch(&[app(100)], 95, 5000, 300));
+    }
+
+    #[test]
+    fn test_zero_idle_requirement() {
+        assert!(should_auto_launch(&[app(100)], 95, 0, 0));
+    }
+}
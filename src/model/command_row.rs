@@ -0,0 +1,385 @@
+//! What a command row shows and what activating it does
+//!
+//! Command rows hold calculator results, `:sh` commands, `:stats` lines,
+//! messages, and the files and grep matches of `:f`, `:fg`, `:ob`, `:obg`
+//! and `:r`. A [`CommandRow`] is built once, where the row is produced and
+//! its context is known: the display text goes to [`CommandRow::name`] and
+//! [`CommandRow::description`], and what activation opens, runs or copies to
+//! a [`CommandPayload`]. Binding and activation read these fields and never
+//! parse the text again, so a file named `12:30 standup.md` or a pasted
+//! `C:\Users\me` command cannot be taken for something else.
+
+use crate::actions::file::parse_grep_line;
+use crate::calculator::decimal_form;
+use crate::utils::contract_home;
+use std::ffi::OsStr;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+/// What a command row is, which decides its icon and how it activates
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CommandKind {
+    /// A calculator result, copied on activation
+    Calculator,
+    /// A `:sh` command, run on activation
+    Shell,
+    /// A file or a grep match in a file, opened on activation
+    File,
+    /// A note or a grep match in a note, opened in Obsidian on activation
+    Note,
+    /// A `:stats` line, with no action
+    Stats,
+    /// A message such as an error, copied on activation
+    #[default]
+    Message,
+}
+
+/// What activating a command row opens, runs or copies
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandPayload {
+    /// A file, by its exact bytes
+    Path(PathBuf),
+    /// A position in a file, from grep output
+    PathLine {
+        path: PathBuf,
+        /// 1-based line number
+        line: u32,
+        /// 1-based byte column of the match, if the tool reported one
+        column: Option<u32>,
+    },
+    /// A command to run, or text to copy
+    Text(String),
+}
+
+impl Default for CommandPayload {
+    fn default() -> Self {
+        Self::Text(String::new())
+    }
+}
+
+impl CommandPayload {
+    /// The file of a `Path` or `PathLine` payload
+    #[must_use]
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Self::Path(path) | Self::PathLine { path, .. } => Some(path),
+            Self::Text(_) => None,
+        }
+    }
+
+    /// The text of a `Text` payload
+    #[must_use]
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            Self::Text(text) => Some(text),
+            Self::Path(_) | Self::PathLine { .. } => None,
+        }
+    }
+}
+
+/// Display text and activation payload of a command row
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandRow {
+    pub kind: CommandKind,
+    /// First line of the row
+    pub name: String,
+    /// Second line of the row; for grep matches `line:text`
+    pub description: String,
+    /// Path shown by single-line file rows: `~`-shortened, or relative to
+    /// the vault for notes
+    pub location: String,
+    pub payload: CommandPayload,
+}
+
+impl CommandRow {
+    /// A message, copied on activation
+    #[must_use]
+    pub fn message(text: impl Into<String>) -> Self {
+        let text = text.into();
+        Self {
+            kind: CommandKind::Message,
+            name: text.clone(),
+            payload: CommandPayload::Text(text),
+            ..Self::default()
+        }
+    }
+
+    /// The calculator result of `expression`
+    ///
+    /// Activation copies the decimal form of results shown in several bases.
+    #[must_use]
+    pub fn calculator(expression: &str, result: &str) -> Self {
+        let result = result.trim();
+        Self {
+            kind: CommandKind::Calculator,
+            name: result.to_string(),
+            description: format!("Calc: {expression} = {result}"),
+            payload: CommandPayload::Text(decimal_form(result).to_string()),
+            ..Self::default()
+        }
+    }
+
+    /// A configured `:sh` command
+    #[must_use]
+    pub fn shell(name: &str, command: &str) -> Self {
+        Self {
+            kind: CommandKind::Shell,
+            name: name.trim().to_string(),
+            description: command.trim().to_string(),
+            payload: CommandPayload::Text(command.trim().to_string()),
+            ..Self::default()
+        }
+    }
+
+    /// The `:sh` row running the typed `command`
+    #[must_use]
+    pub fn run(command: &str) -> Self {
+        Self {
+            kind: CommandKind::Shell,
+            name: "Run command".to_string(),
+            description: command.to_string(),
+            payload: CommandPayload::Text(command.trim().to_string()),
+            ..Self::default()
+        }
+    }
+
+    /// A `:stats` line
+    #[must_use]
+    pub fn stats(title: &str, description: &str) -> Self {
+        Self {
+            kind: CommandKind::Stats,
+            name: title.to_string(),
+            description: description.to_string(),
+            payload: CommandPayload::Text(title.to_string()),
+            ..Self::default()
+        }
+    }
+
+    /// A file: its name, then its folder
+    #[must_use]
+    pub fn file(path: PathBuf) -> Self {
+        let description = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .map(contract_home)
+            .unwrap_or_default();
+        Self {
+            kind: CommandKind::File,
+            name: file_name(&path),
+            description,
+            location: contract_home(&path),
+            payload: CommandPayload::Path(path),
+        }
+    }
+
+    /// A note of `vault`: its name, then its folder within the vault
+    ///
+    /// Notes at the top of the vault show the vault's own path instead.
+    #[must_use]
+    pub fn note(path: PathBuf, vault: &Path) -> Self {
+        let relative = path.strip_prefix(vault).unwrap_or(&path);
+        let description = relative
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .or_else(|| path.parent())
+            .map(|parent| parent.display().to_string())
+            .unwrap_or_default();
+        Self {
+            kind: CommandKind::Note,
+            name: file_name(&path),
+            description,
+            location: relative.display().to_string(),
+            payload: CommandPayload::Path(path),
+        }
+    }
+
+    /// A line of a file listing, a note of `vault` if there is one
+    ///
+    /// `raw` is the line as the command printed it, so file names that are
+    /// not valid UTF-8 are kept byte for byte. Lines that are not absolute
+    /// paths become messages.
+    #[must_use]
+    pub fn from_listing(raw: &[u8], vault: Option<&Path>) -> Self {
+        if !raw.starts_with(b"/") {
+            return Self::message(String::from_utf8_lossy(raw));
+        }
+        let path = PathBuf::from(OsStr::from_bytes(raw));
+        match vault {
+            Some(vault) => Self::note(path, vault),
+            None => Self::file(path),
+        }
+    }
+
+    /// A line of grep output, a match in a note of `vault` if there is one
+    ///
+    /// `with_column` tells whether the command was run with `--column`.
+    /// Lines that are not matches in an absolute path become messages.
+    #[must_use]
+    pub fn from_grep(raw: &[u8], vault: Option<&Path>, with_column: bool) -> Self {
+        let Some(grep) = parse_grep_line(raw, with_column) else {
+            return Self::message(String::from_utf8_lossy(raw));
+        };
+        let (kind, location) = match vault {
+            Some(vault) => (
+                CommandKind::Note,
+                grep.file
+                    .strip_prefix(vault)
+                    .unwrap_or(grep.file)
+                    .display()
+                    .to_string(),
+            ),
+            None => (CommandKind::File, contract_home(grep.file)),
+        };
+        Self {
+            kind,
+            name: file_name(grep.file),
+            description: format!("{}:{}", grep.line, grep.text),
+            location,
+            payload: CommandPayload::PathLine {
+                path: grep.file.to_path_buf(),
+                line: grep.line,
+                column: grep.column,
+            },
+        }
+    }
+
+    /// The matching text of a grep match, without the line number
+    #[must_use]
+    pub fn matched_text(&self) -> &str {
+        match &self.payload {
+            CommandPayload::PathLine { line, .. } => self
+                .description
+                .strip_prefix(&format!("{line}:"))
+                .unwrap_or(&self.description),
+            _ => "",
+        }
+    }
+}
+
+/// The file name of `path` for display, or the whole path if it has none
+fn file_name(path: &Path) -> String {
+    path.file_name().map_or_else(
+        || path.display().to_string(),
+        |name| name.to_string_lossy().into_owned(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calculator_copies_the_decimal_form() {
+        let row = CommandRow::calculator("128 + 128", "256  •  0x100  •  0b100000000");
+        assert_eq!(row.kind, CommandKind::Calculator);
+        assert_eq!(row.name, "256  •  0x100  •  0b100000000");
+        assert_eq!(
+            row.description,
+            "Calc: 128 + 128 = 256  •  0x100  •  0b100000000"
+        );
+        assert_eq!(row.payload.text(), Some("256"));
+    }
+
+    #[test]
+    fn test_shell_rows_run_the_command_only() {
+        let row = CommandRow::shell("Build | release", " cargo build --release ");
+        assert_eq!(row.name, "Build | release");
+        assert_eq!(row.payload.text(), Some("cargo build --release"));
+
+        let row = CommandRow::run("echo a = 1");
+        assert_eq!(row.kind, CommandKind::Shell);
+        assert_eq!(row.payload.text(), Some("echo a = 1"));
+    }
+
+    #[test]
+    fn test_file_name_with_colons() {
+        let row = CommandRow::from_listing(b"/tmp/notes/12:30 standup.md:4:x", None);
+        assert_eq!(row.kind, CommandKind::File);
+        assert_eq!(row.name, "12:30 standup.md:4:x");
+        assert_eq!(row.description, "/tmp/notes");
+        assert_eq!(
+            row.payload,
+            CommandPayload::Path("/tmp/notes/12:30 standup.md:4:x".into())
+        );
+    }
+
+    #[test]
+    fn test_grep_match_in_file_name_with_colons() {
+        // rg --null and grep -Z end the file name with a NUL byte
+        let row = CommandRow::from_grep(b"/tmp/a:b.txt\x003:2:x: y", None, true);
+        assert_eq!(
+            row.payload,
+            CommandPayload::PathLine {
+                path: "/tmp/a:b.txt".into(),
+                line: 3,
+                column: Some(2),
+            }
+        );
+        assert_eq!(row.name, "a:b.txt");
+        assert_eq!(row.location, "/tmp/a:b.txt");
+        assert_eq!(row.description, "3:x: y");
+        assert_eq!(row.matched_text(), "x: y");
+    }
+
+    #[test]
+    fn test_windows_paths_stay_text() {
+        let pasted = r"C:\Users\me\notes.txt:3:hello";
+        let row = CommandRow::from_grep(pasted.as_bytes(), None, false);
+        assert_eq!(row, CommandRow::message(pasted));
+        assert_eq!(row.payload.path(), None);
+
+        let row = CommandRow::from_listing(br"C:\Users\me\notes.txt", None);
+        assert_eq!(row.kind, CommandKind::Message);
+
+        let row = CommandRow::run(r"C:\Tools\build.bat --fast");
+        assert_eq!(row.kind, CommandKind::Shell);
+        assert_eq!(row.payload.text(), Some(r"C:\Tools\build.bat --fast"));
+    }
+
+    #[test]
+    fn test_non_utf8_file_names_are_kept() {
+        let row = CommandRow::from_listing(b"/tmp/not\xffes.md", None);
+        assert_eq!(
+            row.payload.path().map(|p| p.as_os_str().as_bytes()),
+            Some(&b"/tmp/not\xffes.md"[..])
+        );
+        assert_eq!(row.name, "not\u{fffd}es.md");
+
+        let row = CommandRow::from_grep(b"/tmp/not\xffes.md\x002:6:line two", None, true);
+        assert_eq!(
+            row.payload.path().map(|p| p.as_os_str().as_bytes()),
+            Some(&b"/tmp/not\xffes.md"[..])
+        );
+    }
+
+    #[test]
+    fn test_notes_are_relative_to_the_vault() {
+        let vault = Path::new("/home/u/vault");
+        let row = CommandRow::from_listing(b"/home/u/vault/Projects/Plan.md", Some(vault));
+        assert_eq!(row.kind, CommandKind::Note);
+        assert_eq!(row.name, "Plan.md");
+        assert_eq!(row.description, "Projects");
+        assert_eq!(row.location, "Projects/Plan.md");
+
+        // Notes at the top of the vault show the vault
+        let row = CommandRow::note("/home/u/vault/Inbox.md".into(), vault);
+        assert_eq!(row.description, "/home/u/vault");
+        assert_eq!(row.location, "Inbox.md");
+
+        let row = CommandRow::from_grep(b"/home/u/vault/Ideas.md\x007:todo", Some(vault), false);
+        assert_eq!(row.kind, CommandKind::Note);
+        assert_eq!(row.location, "Ideas.md");
+        assert_eq!(row.matched_text(), "todo");
+    }
+
+    #[test]
+    fn test_messages_have_no_match_text() {
+        let row = CommandRow::message("rg not found — install rg to search");
+        assert_eq!(row.kind, CommandKind::Message);
+        assert_eq!(row.matched_text(), "");
+        assert_eq!(
+            row.payload.text(),
+            Some("rg not found — install rg to search")
+        );
+    }
+}
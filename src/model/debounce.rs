@@ -2,28 +2,26 @@ use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 use std::time::Duration;
 
+/// Delay before a plain (non-colon) query is searched
+///
+/// Applied by the search entry itself through its `search-changed` signal.
 pub(crate) const DEFAULT_SEARCH_DEBOUNCE_MS: u32 = 100;
 
-/// Manages debounce timers for command execution and search operations.
+/// Manages debounce timers for command execution.
 ///
-/// Provides separate scheduling for:
-/// - Commands (colon commands) using `schedule_command`
-/// - Search providers using `schedule_search`
+/// Colon commands and search providers are scheduled with
+/// `schedule_command`; plain queries are debounced by the search entry.
 pub struct DebounceScheduler {
     command_debounce: Rc<RefCell<Option<glib::SourceId>>>,
     command_debounce_ms: Cell<u32>,
-    search_debounce: Rc<RefCell<Option<glib::SourceId>>>,
-    search_debounce_ms: u32,
 }
 
 impl DebounceScheduler {
     #[must_use]
-    pub fn new(command_ms: u32, search_ms: u32) -> Self {
+    pub fn new(command_ms: u32) -> Self {
         Self {
             command_debounce: Rc::new(RefCell::new(None)),
             command_debounce_ms: Cell::new(command_ms),
-            search_debounce: Rc::new(RefCell::new(None)),
-            search_debounce_ms: search_ms,
         }
     }
 
@@ -42,12 +40,6 @@ impl DebounceScheduler {
         }
     }
 
-    pub fn cancel_search(&self) {
-        if let Some(id) = self.search_debounce.borrow_mut().take() {
-            id.remove();
-        }
-    }
-
     pub fn schedule_command<F>(&self, f: F)
     where
         F: FnOnce() + 'static,
@@ -56,14 +48,6 @@ impl DebounceScheduler {
         Self::schedule_with_delay(&self.command_debounce, self.command_debounce_ms.get(), f);
     }
 
-    pub fn schedule_search<F>(&self, f: F)
-    where
-        F: FnOnce() + 'static,
-    {
-        self.cancel_search();
-        Self::schedule_with_delay(&self.search_debounce, self.search_debounce_ms, f);
-    }
-
     pub fn schedule_command_with_delay<F>(&self, delay_ms: u32, f: F)
     where
         F: FnOnce() + 'static,
@@ -98,14 +82,12 @@ impl Clone for DebounceScheduler {
         Self {
             command_debounce: Rc::clone(&self.command_debounce),
             command_debounce_ms: Cell::new(self.command_debounce_ms.get()),
-            search_debounce: Rc::clone(&self.search_debounce),
-            search_debounce_ms: self.search_debounce_ms,
         }
     }
 }
 
 impl Default for DebounceScheduler {
     fn default() -> Self {
-        Self::new(300, DEFAULT_SEARCH_DEBOUNCE_MS)
+        Self::new(300)
     }
 }
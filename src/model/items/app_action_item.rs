@@ -0,0 +1,107 @@
+//! GTK Object wrapper for desktop actions
+//!
+//! This module provides `AppActionItem`, a row shown under an expanded
+//! `AppItem` for one of its `[Desktop Action ...]` groups (e.g. "New Private
+//! Window"). Activating it runs the action's own command.
+
+use glib::Object;
+use glib::subclass::prelude::*;
+use std::cell::RefCell;
+
+use super::AppItem;
+use crate::launcher::DesktopAction;
+
+/// Internal implementation module for GTK object subclassing
+mod imp {
+    use super::RefCell;
+    #[allow(unused_imports)]
+    use glib::subclass::prelude::{
+        ObjectImpl, ObjectInterfaceType, ObjectSubclass, ObjectSubclassType,
+    };
+
+    #[derive(Default)]
+    pub struct AppActionItem {
+        /// Action name, shown as the row name
+        pub name: RefCell<String>,
+        /// Command of the action
+        pub exec: RefCell<String>,
+        /// Action icon, or the application's icon if it has none
+        pub icon: RefCell<String>,
+        /// `Terminal=` setting of the application
+        pub terminal: RefCell<bool>,
+        /// Name of the application, shown as the description
+        pub app_name: RefCell<String>,
+        /// Desktop entry ID of the application
+        pub desktop_id: RefCell<String>,
+        /// Path of the application's `.desktop` file
+        pub path: RefCell<std::path::PathBuf>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for AppActionItem {
+        const NAME: &'static str = "GrunnerAppActionItem";
+        type Type = super::AppActionItem;
+    }
+
+    impl ObjectImpl for AppActionItem {}
+}
+
+glib::wrapper! {
+    pub struct AppActionItem(ObjectSubclass<imp::AppActionItem>);
+}
+
+impl AppActionItem {
+    /// Create a new `AppActionItem` for an action of `app`
+    #[must_use]
+    pub fn new(app: &AppItem, action: &DesktopAction) -> Self {
+        let obj: Self = Object::new();
+        let icon = if action.icon.is_empty() {
+            app.icon()
+        } else {
+            action.icon.clone()
+        };
+        *obj.imp().name.borrow_mut() = action.name.clone();
+        *obj.imp().exec.borrow_mut() = action.exec.clone();
+        *obj.imp().icon.borrow_mut() = icon;
+        *obj.imp().terminal.borrow_mut() = app.terminal();
+        *obj.imp().app_name.borrow_mut() = app.name();
+        *obj.imp().desktop_id.borrow_mut() = app.desktop_id();
+        *obj.imp().path.borrow_mut() = app.path();
+        obj
+    }
+
+    #[must_use]
+    pub fn name(&self) -> String {
+        self.imp().name.borrow().clone()
+    }
+
+    #[must_use]
+    pub fn exec(&self) -> String {
+        self.imp().exec.borrow().clone()
+    }
+
+    #[must_use]
+    pub fn icon(&self) -> String {
+        self.imp().icon.borrow().clone()
+    }
+
+    #[must_use]
+    pub fn terminal(&self) -> bool {
+        *self.imp().terminal.borrow()
+    }
+
+    #[must_use]
+    pub fn app_name(&self) -> String {
+        self.imp().app_name.borrow().clone()
+    }
+
+    #[must_use]
+    pub fn desktop_id(&self) -> String {
+        self.imp().desktop_id.borrow().clone()
+    }
+
+    #[must_use]
+    pub fn path(&self) -> std::path::PathBuf {
+        self.imp().path.borrow().clone()
+    }
+}
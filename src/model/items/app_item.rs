@@ -9,7 +9,7 @@ use glib::Object;
 use glib::subclass::prelude::*;
 use std::cell::RefCell;
 
-use crate::launcher::DesktopApp;
+use crate::launcher::{DesktopAction, DesktopApp};
 
 /// Internal implementation module for GTK object subclassing
 ///
@@ -40,6 +40,20 @@ mod imp {
         pub terminal: bool,
         /// Desktop entry ID for GIO launch
         pub desktop_id: String,
+        /// Path of the `.desktop` file the application was loaded from
+        pub path: std::path::PathBuf,
+        /// Quick actions from the `[Desktop Action ...]` groups
+        pub actions: Vec<super::DesktopAction>,
+        /// `StartupWMClass=` of the application, empty if not declared
+        pub startup_wm_class: String,
+        /// Id of an open window of the application, if it is running
+        pub running_window: Option<u32>,
+        /// Match score (0-100) assigned by the search that produced this item
+        pub score: u8,
+        /// Character positions of the name matched by the query
+        pub name_matches: Vec<usize>,
+        /// Character positions of the description matched by the query
+        pub description_matches: Vec<usize>,
     }
 
     /// Main GTK object implementation struct
@@ -101,11 +115,23 @@ impl AppItem {
         // Initialize the internal data with values from the DesktopApp
         *obj.imp().data.borrow_mut() = imp::AppItemInner {
             name: app.name.clone(),
-            description: app.description.clone(),
+            // Apps without a comment show their generic name instead
+            description: if app.description.is_empty() {
+                app.generic_name.clone()
+            } else {
+                app.description.clone()
+            },
             icon: app.icon.clone(),
             exec: app.exec.clone(),
             terminal: app.terminal,
             desktop_id: app.desktop_id.clone(),
+            path: app.path.clone(),
+            actions: app.actions.clone(),
+            startup_wm_class: app.startup_wm_class.clone(),
+            running_window: None,
+            score: 0,
+            name_matches: Vec::new(),
+            description_matches: Vec::new(),
         };
 
         obj
@@ -146,4 +172,60 @@ impl AppItem {
     pub fn desktop_id(&self) -> String {
         self.imp().data.borrow().desktop_id.clone()
     }
+
+    /// Get the path of the `.desktop` file the application was loaded from
+    #[must_use]
+    pub fn path(&self) -> std::path::PathBuf {
+        self.imp().data.borrow().path.clone()
+    }
+
+    /// Get the application's quick actions, in `Actions=` order
+    #[must_use]
+    pub fn actions(&self) -> Vec<DesktopAction> {
+        self.imp().data.borrow().actions.clone()
+    }
+
+    /// Get the window class declared by the application's `StartupWMClass=`
+    #[must_use]
+    pub fn startup_wm_class(&self) -> String {
+        self.imp().data.borrow().startup_wm_class.clone()
+    }
+
+    /// Get the id of an open window of the application, if it is running
+    #[must_use]
+    pub fn running_window(&self) -> Option<u32> {
+        self.imp().data.borrow().running_window
+    }
+
+    /// Set the id of an open window of the application
+    pub fn set_running_window(&self, window: Option<u32>) {
+        self.imp().data.borrow_mut().running_window = window;
+    }
+
+    /// Get the match score (0-100) of this item for the current query
+    #[must_use]
+    pub fn score(&self) -> u8 {
+        self.imp().data.borrow().score
+    }
+
+    /// Set the match score (0-100) of this item for the current query
+    pub fn set_score(&self, score: u8) {
+        self.imp().data.borrow_mut().score = score;
+    }
+
+    /// Get the character positions of the name and description matched by
+    /// the current query
+    #[must_use]
+    pub fn match_positions(&self) -> (Vec<usize>, Vec<usize>) {
+        let data = self.imp().data.borrow();
+        (data.name_matches.clone(), data.description_matches.clone())
+    }
+
+    /// Set the character positions of the name and description matched by
+    /// the current query
+    pub fn set_match_positions(&self, name: Vec<usize>, description: Vec<usize>) {
+        let mut data = self.imp().data.borrow_mut();
+        data.name_matches = name;
+        data.description_matches = description;
+    }
 }
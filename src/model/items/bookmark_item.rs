@@ -0,0 +1,59 @@
+//! GTK Object wrapper for browser bookmarks
+//!
+//! This module provides `BookmarkItem`, the row type of the `:b` mode.
+//! Activating it opens the URL in the default browser.
+
+use crate::providers::bookmarks::Bookmark;
+use glib::Object;
+use glib::subclass::prelude::*;
+use std::cell::RefCell;
+
+/// Internal implementation module for GTK object subclassing
+mod imp {
+    use super::RefCell;
+    #[allow(unused_imports)]
+    use glib::subclass::prelude::{
+        ObjectImpl, ObjectInterfaceType, ObjectSubclass, ObjectSubclassType,
+    };
+
+    #[derive(Default)]
+    pub struct BookmarkItem {
+        /// Title shown in the row (the URL for untitled bookmarks)
+        pub title: RefCell<String>,
+        /// URL opened on activation
+        pub url: RefCell<String>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for BookmarkItem {
+        const NAME: &'static str = "GrunnerBookmarkItem";
+        type Type = super::BookmarkItem;
+    }
+
+    impl ObjectImpl for BookmarkItem {}
+}
+
+glib::wrapper! {
+    pub struct BookmarkItem(ObjectSubclass<imp::BookmarkItem>);
+}
+
+impl BookmarkItem {
+    /// Create a new `BookmarkItem` from a loaded bookmark
+    #[must_use]
+    pub fn new(bookmark: &Bookmark) -> Self {
+        let obj: Self = Object::new();
+        *obj.imp().title.borrow_mut() = bookmark.display_title().to_string();
+        *obj.imp().url.borrow_mut() = bookmark.url.clone();
+        obj
+    }
+
+    #[must_use]
+    pub fn title(&self) -> String {
+        self.imp().title.borrow().clone()
+    }
+
+    #[must_use]
+    pub fn url(&self) -> String {
+        self.imp().url.borrow().clone()
+    }
+}
@@ -0,0 +1,68 @@
+//! GTK Object wrapper for clipboard history entries
+//!
+//! This module provides `ClipboardItem`, the row type of the `:c` mode.
+//! Activating it copies the text back to the clipboard.
+
+use crate::clipboard_history::ClipboardEntry;
+use glib::Object;
+use glib::subclass::prelude::*;
+use std::cell::RefCell;
+
+/// Internal implementation module for GTK object subclassing
+mod imp {
+    use super::RefCell;
+    #[allow(unused_imports)]
+    use glib::subclass::prelude::{
+        ObjectImpl, ObjectInterfaceType, ObjectSubclass, ObjectSubclassType,
+    };
+
+    #[derive(Default)]
+    pub struct ClipboardItem {
+        /// The full copied text
+        pub text: RefCell<String>,
+        /// First line, shown as the row name
+        pub title: RefCell<String>,
+        /// Preview of the following lines, shown as the description
+        pub preview: RefCell<String>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ClipboardItem {
+        const NAME: &'static str = "GrunnerClipboardItem";
+        type Type = super::ClipboardItem;
+    }
+
+    impl ObjectImpl for ClipboardItem {}
+}
+
+glib::wrapper! {
+    pub struct ClipboardItem(ObjectSubclass<imp::ClipboardItem>);
+}
+
+impl ClipboardItem {
+    /// Create a new `ClipboardItem` from a history entry
+    #[must_use]
+    pub fn new(entry: &ClipboardEntry) -> Self {
+        let obj: Self = Object::new();
+        let (title, preview) = entry.title_and_preview();
+        *obj.imp().text.borrow_mut() = entry.text.clone();
+        *obj.imp().title.borrow_mut() = title;
+        *obj.imp().preview.borrow_mut() = preview;
+        obj
+    }
+
+    #[must_use]
+    pub fn text(&self) -> String {
+        self.imp().text.borrow().clone()
+    }
+
+    #[must_use]
+    pub fn title(&self) -> String {
+        self.imp().title.borrow().clone()
+    }
+
+    #[must_use]
+    pub fn preview(&self) -> String {
+        self.imp().preview.borrow().clone()
+    }
+}
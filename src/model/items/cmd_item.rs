@@ -1,15 +1,17 @@
-//! GTK Object wrapper for command line entries
+//! GTK Object wrapper for command rows
 //!
-//! This module provides `CommandItem`, a GTK object that wraps command line strings
-//! for use in GTK list models and UI components. It implements the necessary
-//! GTK object subclassing to make command data accessible to GTK's property
-//! system and list views.
+//! This module provides `CommandItem`, a GTK object that wraps a
+//! [`CommandRow`] for use in GTK list models and UI components. It implements
+//! the necessary GTK object subclassing to make command data accessible to
+//! GTK's property system and list views.
 //!
 //! Command items are used to represent:
 //! - Shell commands entered by the user
 //! - File paths with line numbers
 //! - Search results that can be executed or opened
 
+use crate::command_safety::CommandGuard;
+use crate::model::command_row::{CommandKind, CommandPayload, CommandRow};
 use glib::Object;
 use glib::subclass::prelude::*;
 use std::cell::RefCell;
@@ -19,7 +21,7 @@ use std::cell::RefCell;
 /// GTK requires object implementations to be separated into an `imp` module
 /// for encapsulation and proper object lifecycle management.
 mod imp {
-    use super::RefCell;
+    use super::{CommandGuard, CommandRow, RefCell};
     #[allow(unused_imports)]
     use glib::subclass::prelude::{
         ObjectImpl, ObjectInterfaceType, ObjectSubclass, ObjectSubclassType,
@@ -28,19 +30,19 @@ mod imp {
     /// Main GTK object implementation struct for command items
     ///
     /// This struct implements the GTK object subclass. The `RefCell`
-    /// allows mutable access to the command line text while maintaining
-    /// Rust's borrowing rules within GTK's ownership model.
+    /// allows mutable access to the row while maintaining Rust's borrowing
+    /// rules within GTK's ownership model.
     #[derive(Default)]
     pub struct CommandItem {
-        /// The command line text wrapped in `RefCell` for interior mutability
-        ///
-        /// This stores the actual command string that will be displayed
-        /// in the UI and potentially executed when selected.
-        pub line: RefCell<String>,
+        /// What the row shows and what activating it does
+        pub row: RefCell<CommandRow>,
         /// Working directory for command execution (None = home directory)
         pub working_dir: RefCell<Option<String>>,
         /// Whether to keep the terminal open after executing
         pub keep_open: RefCell<bool>,
+        /// Whether activating a `:sh` command runs it, see
+        /// [`crate::command_safety`]
+        pub guard: RefCell<CommandGuard>,
     }
 
     /// GTK object subclass implementation
@@ -63,7 +65,7 @@ mod imp {
     impl ObjectImpl for CommandItem {}
 }
 
-// Public GTK object wrapper for command line entries
+// Public GTK object wrapper for command rows
 //
 // This is the public-facing type that UI code interacts with.
 // It wraps the internal GTK object implementation and provides
@@ -76,62 +78,79 @@ glib::wrapper! {
 }
 
 impl CommandItem {
-    /// Create a new `CommandItem` from a command line string
+    /// Create a new `CommandItem` from a row
     ///
     /// # Arguments
-    /// * `line` - The command line text to store
+    /// * `row` - What the row shows and what activating it does
     ///
     /// # Returns
-    /// A new `CommandItem` GTK object populated with the command text.
+    /// A new `CommandItem` GTK object holding the row.
     ///
     /// # Examples
     /// ```rust
+    /// use grunner::model::command_row::CommandRow;
     /// use grunner::model::items::CommandItem;
-    /// let cmd = CommandItem::new("ls -la".to_string());
+    /// let cmd = CommandItem::new(CommandRow::run("ls -la"));
     /// ```
     #[must_use]
-    pub fn new(line: String) -> Self {
-        Self::new_with_options(line, None, true)
+    pub fn new(row: CommandRow) -> Self {
+        Self::new_with_options(row, None, true)
     }
 
     /// Create a new `CommandItem` with full options
     ///
     /// # Arguments
-    /// * `line` - The command line text to store
+    /// * `row` - What the row shows and what activating it does
     /// * `working_dir` - Optional working directory (None = home directory)
     /// * `keep_open` - Whether to keep the terminal open after execution
     ///
     /// # Returns
-    /// A new `CommandItem` GTK object populated with the command text.
+    /// A new `CommandItem` GTK object holding the row.
     ///
     /// # Examples
     /// ```rust
+    /// use grunner::model::command_row::CommandRow;
     /// use grunner::model::items::CommandItem;
-    /// let cmd = CommandItem::new_with_options("ls -la".to_string(), None, true);
-    /// let cmd = CommandItem::new_with_options("/path/to/file.rs:42".to_string(), Some("/home/user".to_string()), false);
+    /// let cmd = CommandItem::new_with_options(CommandRow::run("ls -la"), None, true);
+    /// let cmd = CommandItem::new_with_options(
+    ///     CommandRow::shell("Build", "cargo build"),
+    ///     Some("/home/user/src".to_string()),
+    ///     false,
+    /// );
     /// ```
     #[must_use]
-    pub fn new_with_options(line: String, working_dir: Option<String>, keep_open: bool) -> Self {
+    pub fn new_with_options(row: CommandRow, working_dir: Option<String>, keep_open: bool) -> Self {
         // Create a new GTK object instance
         let obj: Self = Object::new();
-        // Initialize the internal data with the command line text
-        *obj.imp().line.borrow_mut() = line;
+        // Initialize the internal data with the row
+        *obj.imp().row.borrow_mut() = row;
         *obj.imp().working_dir.borrow_mut() = working_dir;
         *obj.imp().keep_open.borrow_mut() = keep_open;
         obj
     }
 
-    /// Get the command line text stored in this item
-    ///
-    /// # Returns
-    /// A clone of the command line string.
-    ///
-    /// # Usage
-    /// This is typically called by UI code to display the command
-    /// or by action handlers to execute the command.
+    /// Get the row stored in this item
+    #[must_use]
+    pub fn row(&self) -> CommandRow {
+        self.imp().row.borrow().clone()
+    }
+
+    /// Get what the row is
+    #[must_use]
+    pub fn kind(&self) -> CommandKind {
+        self.imp().row.borrow().kind
+    }
+
+    /// Get the first line of the row
     #[must_use]
-    pub fn line(&self) -> String {
-        self.imp().line.borrow().clone()
+    pub fn name(&self) -> String {
+        self.imp().row.borrow().name.clone()
+    }
+
+    /// Get what activating the row opens, runs or copies
+    #[must_use]
+    pub fn payload(&self) -> CommandPayload {
+        self.imp().row.borrow().payload.clone()
     }
 
     /// Get the working directory for this command
@@ -151,4 +170,15 @@ impl CommandItem {
     pub fn keep_open(&self) -> bool {
         *self.imp().keep_open.borrow()
     }
+
+    /// Get whether activating this command runs it
+    #[must_use]
+    pub fn guard(&self) -> CommandGuard {
+        *self.imp().guard.borrow()
+    }
+
+    /// Set whether activating this command runs it
+    pub fn set_guard(&self, guard: CommandGuard) {
+        *self.imp().guard.borrow_mut() = guard;
+    }
 }
@@ -0,0 +1,89 @@
+//! GTK Object wrapper for rows of the colon-command palette
+//!
+//! This module provides `ColonCommandItem`, listed while the query is a bare
+//! `:` or the start of a command name. Activating the row types the command
+//! into the search entry, so the window stays open in that mode.
+
+use crate::app_mode::AppMode;
+use glib::Object;
+use glib::subclass::prelude::*;
+use std::cell::{Cell, RefCell};
+
+/// Internal implementation module for GTK object subclassing
+mod imp {
+    use super::{AppMode, Cell, RefCell};
+    #[allow(unused_imports)]
+    use glib::subclass::prelude::{
+        ObjectImpl, ObjectInterfaceType, ObjectSubclass, ObjectSubclassType,
+    };
+
+    #[derive(Default)]
+    pub struct ColonCommandItem {
+        /// Command name, without the colon
+        pub name: RefCell<String>,
+        /// What the command does
+        pub description: RefCell<String>,
+        /// Icon used when the mode has none of its own
+        pub icon: RefCell<String>,
+        /// Mode the command puts the UI in
+        pub mode: Cell<AppMode>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ColonCommandItem {
+        const NAME: &'static str = "GrunnerColonCommandItem";
+        type Type = super::ColonCommandItem;
+    }
+
+    impl ObjectImpl for ColonCommandItem {}
+}
+
+glib::wrapper! {
+    pub struct ColonCommandItem(ObjectSubclass<imp::ColonCommandItem>);
+}
+
+impl ColonCommandItem {
+    /// Create a new `ColonCommandItem`
+    ///
+    /// # Arguments
+    /// * `name` - Command name, without the colon
+    /// * `description` - Secondary line saying what the command does
+    /// * `icon` - Icon shown when `mode` has none, e.g. for web searches
+    /// * `mode` - Mode the command puts the UI in
+    #[must_use]
+    pub fn new(name: &str, description: String, icon: String, mode: AppMode) -> Self {
+        let obj: Self = Object::new();
+        let imp = obj.imp();
+        *imp.name.borrow_mut() = name.to_string();
+        *imp.description.borrow_mut() = description;
+        *imp.icon.borrow_mut() = icon;
+        imp.mode.set(mode);
+        obj
+    }
+
+    #[must_use]
+    pub fn name(&self) -> String {
+        self.imp().name.borrow().clone()
+    }
+
+    #[must_use]
+    pub fn description(&self) -> String {
+        self.imp().description.borrow().clone()
+    }
+
+    #[must_use]
+    pub fn icon(&self) -> String {
+        self.imp().icon.borrow().clone()
+    }
+
+    #[must_use]
+    pub fn mode(&self) -> AppMode {
+        self.imp().mode.get()
+    }
+
+    /// Search text the row puts in the entry: the command and a space
+    #[must_use]
+    pub fn entry_text(&self) -> String {
+        format!(":{} ", self.imp().name.borrow())
+    }
+}
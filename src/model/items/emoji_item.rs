@@ -0,0 +1,68 @@
+//! GTK Object wrapper for emoji
+//!
+//! This module provides `EmojiItem`, the row type of the `:e` mode.
+//! Activating it copies the emoji to the clipboard.
+
+use crate::emoji::shortcodes;
+use emojis::Emoji;
+use glib::Object;
+use glib::subclass::prelude::*;
+use std::cell::RefCell;
+
+/// Internal implementation module for GTK object subclassing
+mod imp {
+    use super::RefCell;
+    #[allow(unused_imports)]
+    use glib::subclass::prelude::{
+        ObjectImpl, ObjectInterfaceType, ObjectSubclass, ObjectSubclassType,
+    };
+
+    #[derive(Default)]
+    pub struct EmojiItem {
+        /// The emoji itself, shown in place of the row icon
+        pub glyph: RefCell<String>,
+        /// CLDR name, shown as the row name
+        pub name: RefCell<String>,
+        /// Shortcodes, shown as the description
+        pub shortcodes: RefCell<String>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for EmojiItem {
+        const NAME: &'static str = "GrunnerEmojiItem";
+        type Type = super::EmojiItem;
+    }
+
+    impl ObjectImpl for EmojiItem {}
+}
+
+glib::wrapper! {
+    pub struct EmojiItem(ObjectSubclass<imp::EmojiItem>);
+}
+
+impl EmojiItem {
+    /// Create a new `EmojiItem` from an emoji of the table
+    #[must_use]
+    pub fn new(emoji: &Emoji) -> Self {
+        let obj: Self = Object::new();
+        *obj.imp().glyph.borrow_mut() = emoji.as_str().to_string();
+        *obj.imp().name.borrow_mut() = emoji.name().to_string();
+        *obj.imp().shortcodes.borrow_mut() = shortcodes(emoji);
+        obj
+    }
+
+    #[must_use]
+    pub fn glyph(&self) -> String {
+        self.imp().glyph.borrow().clone()
+    }
+
+    #[must_use]
+    pub fn name(&self) -> String {
+        self.imp().name.borrow().clone()
+    }
+
+    #[must_use]
+    pub fn shortcodes(&self) -> String {
+        self.imp().shortcodes.borrow().clone()
+    }
+}
@@ -0,0 +1,128 @@
+//! GTK Object wrapper for rows of the `:grunner` palette
+//!
+//! This module provides `GrunnerActionItem`, a setting of grunner itself that
+//! Enter turns on or off, such as starting grunner at login. The row keeps
+//! the state it had when listed; activating it lists the rows again.
+
+use glib::Object;
+use glib::subclass::prelude::*;
+use std::cell::Cell;
+
+/// Something `:grunner` turns on or off
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GrunnerAction {
+    /// Run `grunner --daemon` at login, see [`crate::autostart`]
+    #[default]
+    StartAtLogin,
+}
+
+impl GrunnerAction {
+    /// Every action, in palette order
+    pub const ALL: [Self; 1] = [Self::StartAtLogin];
+
+    #[must_use]
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::StartAtLogin => "Start at login",
+        }
+    }
+
+    /// Secondary line of the row while the action is `on` or off
+    #[must_use]
+    pub fn description(self, on: bool) -> &'static str {
+        match (self, on) {
+            (Self::StartAtLogin, true) => {
+                "On · grunner --daemon starts with your session; Enter turns it off"
+            }
+            (Self::StartAtLogin, false) => "Off · Enter runs grunner --daemon at login",
+        }
+    }
+
+    #[must_use]
+    pub fn icon(self) -> &'static str {
+        match self {
+            Self::StartAtLogin => "system-run",
+        }
+    }
+
+    /// The actions whose title contains every word of `query`, ignoring case
+    #[must_use]
+    pub fn matching(query: &str) -> Vec<Self> {
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        Self::ALL
+            .into_iter()
+            .filter(|action| {
+                let title = action.title().to_lowercase();
+                words.iter().all(|word| title.contains(word.as_str()))
+            })
+            .collect()
+    }
+}
+
+/// Internal implementation module for GTK object subclassing
+mod imp {
+    use super::{Cell, GrunnerAction};
+    #[allow(unused_imports)]
+    use glib::subclass::prelude::{
+        ObjectImpl, ObjectInterfaceType, ObjectSubclass, ObjectSubclassType,
+    };
+
+    #[derive(Default)]
+    pub struct GrunnerActionItem {
+        pub action: Cell<GrunnerAction>,
+        /// Whether the action was on when the row was listed
+        pub on: Cell<bool>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for GrunnerActionItem {
+        const NAME: &'static str = "GrunnerActionItem";
+        type Type = super::GrunnerActionItem;
+    }
+
+    impl ObjectImpl for GrunnerActionItem {}
+}
+
+glib::wrapper! {
+    pub struct GrunnerActionItem(ObjectSubclass<imp::GrunnerActionItem>);
+}
+
+impl GrunnerActionItem {
+    /// Create a new `GrunnerActionItem`
+    ///
+    /// # Arguments
+    /// * `action` - What the row turns on or off
+    /// * `on` - Whether it is on now
+    #[must_use]
+    pub fn new(action: GrunnerAction, on: bool) -> Self {
+        let obj: Self = Object::new();
+        obj.imp().action.set(action);
+        obj.imp().on.set(on);
+        obj
+    }
+
+    #[must_use]
+    pub fn action(&self) -> GrunnerAction {
+        self.imp().action.get()
+    }
+
+    #[must_use]
+    pub fn on(&self) -> bool {
+        self.imp().on.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matching_actions() {
+        assert_eq!(GrunnerAction::matching(""), GrunnerAction::ALL);
+        assert_eq!(
+            GrunnerAction::matching("LOGIN start"),
+            [GrunnerAction::StartAtLogin]
+        );
+        assert!(GrunnerAction::matching("theme").is_empty());
+    }
+}
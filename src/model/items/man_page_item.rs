@@ -0,0 +1,63 @@
+//! GTK Object wrapper for manual pages
+//!
+//! This module provides `ManPageItem`, the row type of the `:man` mode.
+//! Activating it shows the page in the launcher's viewer.
+
+use crate::providers::manpages::ManPage;
+use glib::Object;
+use glib::subclass::prelude::*;
+use std::cell::RefCell;
+
+/// Internal implementation module for GTK object subclassing
+mod imp {
+    use super::{ManPage, RefCell};
+    #[allow(unused_imports)]
+    use glib::subclass::prelude::{
+        ObjectImpl, ObjectInterfaceType, ObjectSubclass, ObjectSubclassType,
+    };
+
+    #[derive(Default)]
+    pub struct ManPageItem {
+        /// The page as listed by `apropos`
+        pub page: RefCell<ManPage>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ManPageItem {
+        const NAME: &'static str = "GrunnerManPageItem";
+        type Type = super::ManPageItem;
+    }
+
+    impl ObjectImpl for ManPageItem {}
+}
+
+glib::wrapper! {
+    pub struct ManPageItem(ObjectSubclass<imp::ManPageItem>);
+}
+
+impl ManPageItem {
+    /// Create a new `ManPageItem` from a listed page
+    #[must_use]
+    pub fn new(page: &ManPage) -> Self {
+        let obj: Self = Object::new();
+        *obj.imp().page.borrow_mut() = page.clone();
+        obj
+    }
+
+    /// The page this row opens
+    #[must_use]
+    pub fn page(&self) -> ManPage {
+        self.imp().page.borrow().clone()
+    }
+
+    /// Name with its section, as `ls(1)`
+    #[must_use]
+    pub fn title(&self) -> String {
+        self.imp().page.borrow().title()
+    }
+
+    #[must_use]
+    pub fn description(&self) -> String {
+        self.imp().page.borrow().description.clone()
+    }
+}
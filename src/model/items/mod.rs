@@ -6,12 +6,50 @@
 //! `use crate::model::items::AppItem` (or whatever they imported before)
 //! without any changes.
 
+mod app_action_item;
 mod app_item;
+mod bookmark_item;
+mod clipboard_item;
 mod cmd_item;
+mod colon_command_item;
+mod emoji_item;
+mod grunner_action_item;
+mod man_page_item;
 mod obsidian_item;
+mod pass_entry_item;
+mod process_item;
+mod project_item;
 mod search_result_item;
+mod section_header_item;
+mod snippet_item;
+mod ssh_host_item;
+mod suggestion_item;
+mod terminal_command_item;
+mod unit_item;
+mod url_item;
+mod vault_setup_item;
+mod window_item;
 
+pub use app_action_item::AppActionItem;
 pub use app_item::AppItem;
+pub use bookmark_item::BookmarkItem;
+pub use clipboard_item::ClipboardItem;
 pub use cmd_item::CommandItem;
+pub use colon_command_item::ColonCommandItem;
+pub use emoji_item::EmojiItem;
+pub use grunner_action_item::{GrunnerAction, GrunnerActionItem};
+pub use man_page_item::ManPageItem;
 pub use obsidian_item::{ObsidianAction, ObsidianActionItem};
+pub use pass_entry_item::PassEntryItem;
+pub use process_item::ProcessItem;
+pub use project_item::ProjectItem;
 pub use search_result_item::SearchResultItem;
+pub use section_header_item::SectionHeaderItem;
+pub use snippet_item::SnippetItem;
+pub use ssh_host_item::SshHostItem;
+pub use suggestion_item::SuggestionItem;
+pub use terminal_command_item::TerminalCommandItem;
+pub use unit_item::UnitItem;
+pub use url_item::UrlItem;
+pub use vault_setup_item::VaultSetupItem;
+pub use window_item::WindowItem;
@@ -0,0 +1,64 @@
+//! GTK Object wrapper for password store entries
+//!
+//! This module provides `PassEntryItem`, the row type of the `:pw` mode.
+//! It only holds the name of the entry; `pass` copies the secret itself.
+
+use crate::providers::pass::PassEntry;
+use glib::Object;
+use glib::subclass::prelude::*;
+use std::cell::RefCell;
+
+/// Internal implementation module for GTK object subclassing
+mod imp {
+    use super::{PassEntry, RefCell};
+    #[allow(unused_imports)]
+    use glib::subclass::prelude::{
+        ObjectImpl, ObjectInterfaceType, ObjectSubclass, ObjectSubclassType,
+    };
+
+    #[derive(Default)]
+    pub struct PassEntryItem {
+        /// The entry of the store
+        pub entry: RefCell<PassEntry>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for PassEntryItem {
+        const NAME: &'static str = "GrunnerPassEntryItem";
+        type Type = super::PassEntryItem;
+    }
+
+    impl ObjectImpl for PassEntryItem {}
+}
+
+glib::wrapper! {
+    pub struct PassEntryItem(ObjectSubclass<imp::PassEntryItem>);
+}
+
+impl PassEntryItem {
+    /// Create a new `PassEntryItem` from an entry of the store
+    #[must_use]
+    pub fn new(entry: &PassEntry) -> Self {
+        let obj: Self = Object::new();
+        *obj.imp().entry.borrow_mut() = entry.clone();
+        obj
+    }
+
+    /// Path in the store, as `pass show` takes it
+    #[must_use]
+    pub fn name(&self) -> String {
+        self.imp().entry.borrow().name.clone()
+    }
+
+    /// Last part of the name
+    #[must_use]
+    pub fn title(&self) -> String {
+        self.imp().entry.borrow().title().to_string()
+    }
+
+    /// Folder of the entry in the store, empty at its root
+    #[must_use]
+    pub fn folder(&self) -> String {
+        self.imp().entry.borrow().folder().to_string()
+    }
+}
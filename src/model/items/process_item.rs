@@ -0,0 +1,69 @@
+//! GTK Object wrapper for processes
+//!
+//! This module provides `ProcessItem`, the row type of the `:kill` mode.
+//! Activating it sends the process a signal.
+
+use crate::processes::Process;
+use crate::ui::severity::Severity;
+use glib::Object;
+use glib::subclass::prelude::*;
+use std::cell::RefCell;
+
+/// Internal implementation module for GTK object subclassing
+mod imp {
+    use super::{Process, RefCell};
+    #[allow(unused_imports)]
+    use glib::subclass::prelude::{
+        ObjectImpl, ObjectInterfaceType, ObjectSubclass, ObjectSubclassType,
+    };
+
+    #[derive(Default)]
+    pub struct ProcessItem {
+        /// The process as listed from /proc
+        pub process: RefCell<Process>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ProcessItem {
+        const NAME: &'static str = "GrunnerProcessItem";
+        type Type = super::ProcessItem;
+    }
+
+    impl ObjectImpl for ProcessItem {}
+}
+
+glib::wrapper! {
+    pub struct ProcessItem(ObjectSubclass<imp::ProcessItem>);
+}
+
+impl ProcessItem {
+    /// Create a new `ProcessItem` from a listed process
+    #[must_use]
+    pub fn new(process: &Process) -> Self {
+        let obj: Self = Object::new();
+        *obj.imp().process.borrow_mut() = process.clone();
+        obj
+    }
+
+    /// The process this row signals
+    #[must_use]
+    pub fn process(&self) -> Process {
+        self.imp().process.borrow().clone()
+    }
+
+    #[must_use]
+    pub fn name(&self) -> String {
+        self.imp().process.borrow().name.clone()
+    }
+
+    #[must_use]
+    pub fn description(&self) -> String {
+        self.imp().process.borrow().description()
+    }
+
+    /// Busy processes are tinted by their CPU use
+    #[must_use]
+    pub fn severity(&self) -> Severity {
+        Severity::of_cpu_percent(self.imp().process.borrow().cpu_percent)
+    }
+}
@@ -0,0 +1,79 @@
+//! GTK Object wrapper for recent projects
+//!
+//! This module provides `ProjectItem`, the row type of the `:proj` mode.
+//! Activating it opens the folder in the IDE that last opened it.
+
+use crate::core::global_state::get_home_dir;
+use crate::providers::projects::Project;
+use glib::Object;
+use glib::subclass::prelude::*;
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+
+/// Internal implementation module for GTK object subclassing
+mod imp {
+    use super::{PathBuf, RefCell};
+    #[allow(unused_imports)]
+    use glib::subclass::prelude::{
+        ObjectImpl, ObjectInterfaceType, ObjectSubclass, ObjectSubclassType,
+    };
+
+    #[derive(Default)]
+    pub struct ProjectItem {
+        /// Project folder
+        pub path: RefCell<PathBuf>,
+        /// Directory name shown in the row
+        pub name: RefCell<String>,
+        /// Path and source shown below the name
+        pub description: RefCell<String>,
+        /// Command of the IDE that opened the project
+        pub opener: RefCell<Option<String>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ProjectItem {
+        const NAME: &'static str = "GrunnerProjectItem";
+        type Type = super::ProjectItem;
+    }
+
+    impl ObjectImpl for ProjectItem {}
+}
+
+glib::wrapper! {
+    pub struct ProjectItem(ObjectSubclass<imp::ProjectItem>);
+}
+
+impl ProjectItem {
+    /// Create a new `ProjectItem` from a loaded project
+    #[must_use]
+    pub fn new(project: &Project) -> Self {
+        let obj: Self = Object::new();
+        let imp = obj.imp();
+        *imp.path.borrow_mut() = project.path.clone();
+        *imp.name.borrow_mut() = project.name();
+        *imp.description.borrow_mut() = project.description(Path::new(get_home_dir()));
+        *imp.opener.borrow_mut() = project.opener.clone();
+        obj
+    }
+
+    #[must_use]
+    pub fn path(&self) -> PathBuf {
+        self.imp().path.borrow().clone()
+    }
+
+    #[must_use]
+    pub fn name(&self) -> String {
+        self.imp().name.borrow().clone()
+    }
+
+    #[must_use]
+    pub fn description(&self) -> String {
+        self.imp().description.borrow().clone()
+    }
+
+    /// Command that opens the project in its IDE, if any
+    #[must_use]
+    pub fn opener(&self) -> Option<String> {
+        self.imp().opener.borrow().clone()
+    }
+}
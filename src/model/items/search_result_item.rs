@@ -20,7 +20,7 @@ mod imp {
     use glib::subclass::prelude::{
         ObjectImpl, ObjectInterfaceType, ObjectSubclass, ObjectSubclassType,
     };
-    use std::cell::RefCell;
+    use std::cell::{Cell, RefCell};
 
     /// Internal data structure holding search result metadata
     ///
@@ -60,6 +60,10 @@ mod imp {
         ///
         /// Required for activating the result when the user selects it.
         pub object_path: RefCell<String>,
+        /// Desktop ID of the provider application (as in its .ini file)
+        ///
+        /// Used to blacklist the provider from the results list.
+        pub desktop_id: RefCell<String>,
         /// Original search terms that produced this result
         ///
         /// Passed back to the provider when activating the result for context.
@@ -69,6 +73,11 @@ mod imp {
         /// This is provided by some providers (e.g., GNOME Calculator) and should only
         /// be used at activation time, not during passive search result rendering.
         pub clipboard_text: RefCell<Option<String>>,
+        /// Whether this result belongs to a previous query
+        ///
+        /// Stale results stay visible, dimmed, until fresh provider results
+        /// replace them.
+        pub stale: Cell<bool>,
     }
 
     /// GTK object subclass implementation
@@ -117,6 +126,7 @@ impl SearchResultItem {
     /// * `app_icon_name` - Provider application icon name
     /// * `bus_name` - D-Bus bus name of the search provider
     /// * `object_path` - D-Bus object path of the search provider
+    /// * `desktop_id` - Desktop ID of the provider application
     /// * `terms` - Original search terms that produced this result
     ///
     /// # Returns
@@ -131,6 +141,7 @@ impl SearchResultItem {
         app_icon_name: impl Into<String>,
         bus_name: impl Into<String>,
         object_path: impl Into<String>,
+        desktop_id: impl Into<String>,
         terms: Vec<String>,
         clipboard_text: Option<String>,
     ) -> Self {
@@ -146,6 +157,7 @@ impl SearchResultItem {
         *imp.app_icon_name.borrow_mut() = app_icon_name.into();
         *imp.bus_name.borrow_mut() = bus_name.into();
         *imp.object_path.borrow_mut() = object_path.into();
+        *imp.desktop_id.borrow_mut() = desktop_id.into();
         *imp.terms.borrow_mut() = terms;
         *imp.clipboard_text.borrow_mut() = clipboard_text;
 
@@ -210,6 +222,14 @@ impl SearchResultItem {
         self.imp().object_path.borrow().clone()
     }
 
+    /// Get the desktop ID of the provider application
+    ///
+    /// This is the ID listed in `search.provider_blacklist` to hide the provider.
+    #[must_use]
+    pub fn desktop_id(&self) -> String {
+        self.imp().desktop_id.borrow().clone()
+    }
+
     /// Get the original search terms that produced this result
     ///
     /// These terms are passed back to the provider when activating the result.
@@ -225,4 +245,15 @@ impl SearchResultItem {
     pub fn clipboard_text(&self) -> Option<String> {
         self.imp().clipboard_text.borrow().clone()
     }
+
+    /// Whether this result belongs to a previous query
+    #[must_use]
+    pub fn is_stale(&self) -> bool {
+        self.imp().stale.get()
+    }
+
+    /// Mark this result as belonging to a previous query
+    pub fn set_stale(&self, stale: bool) {
+        self.imp().stale.set(stale);
+    }
 }
@@ -0,0 +1,51 @@
+//! GTK Object wrapper for section headers
+//!
+//! This module provides `SectionHeaderItem`, a title row that groups the
+//! results below it (e.g. "Recently installed"). Header rows cannot be
+//! selected or activated.
+
+use glib::Object;
+use glib::subclass::prelude::*;
+use std::cell::RefCell;
+
+/// Internal implementation module for GTK object subclassing
+mod imp {
+    use super::RefCell;
+    #[allow(unused_imports)]
+    use glib::subclass::prelude::{
+        ObjectImpl, ObjectInterfaceType, ObjectSubclass, ObjectSubclassType,
+    };
+
+    #[derive(Default)]
+    pub struct SectionHeaderItem {
+        /// Section title shown in the row
+        pub title: RefCell<String>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SectionHeaderItem {
+        const NAME: &'static str = "GrunnerSectionHeaderItem";
+        type Type = super::SectionHeaderItem;
+    }
+
+    impl ObjectImpl for SectionHeaderItem {}
+}
+
+glib::wrapper! {
+    pub struct SectionHeaderItem(ObjectSubclass<imp::SectionHeaderItem>);
+}
+
+impl SectionHeaderItem {
+    /// Create a new `SectionHeaderItem` with the given title
+    #[must_use]
+    pub fn new(title: &str) -> Self {
+        let obj: Self = Object::new();
+        *obj.imp().title.borrow_mut() = title.to_string();
+        obj
+    }
+
+    #[must_use]
+    pub fn title(&self) -> String {
+        self.imp().title.borrow().clone()
+    }
+}
@@ -0,0 +1,66 @@
+//! GTK Object wrapper for text snippets
+//!
+//! This module provides `SnippetItem`, the row type of the `:snip` mode.
+//! Activating it copies the text, placeholders filled in, to the clipboard.
+
+use crate::providers::snippets::Snippet;
+use glib::Object;
+use glib::subclass::prelude::*;
+use std::cell::RefCell;
+
+/// Internal implementation module for GTK object subclassing
+mod imp {
+    use super::{RefCell, Snippet};
+    #[allow(unused_imports)]
+    use glib::subclass::prelude::{
+        ObjectImpl, ObjectInterfaceType, ObjectSubclass, ObjectSubclassType,
+    };
+
+    #[derive(Default)]
+    pub struct SnippetItem {
+        /// The snippet as loaded
+        pub snippet: RefCell<Snippet>,
+        /// One-line preview shown below the name
+        pub preview: RefCell<String>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SnippetItem {
+        const NAME: &'static str = "GrunnerSnippetItem";
+        type Type = super::SnippetItem;
+    }
+
+    impl ObjectImpl for SnippetItem {}
+}
+
+glib::wrapper! {
+    pub struct SnippetItem(ObjectSubclass<imp::SnippetItem>);
+}
+
+impl SnippetItem {
+    /// Create a new `SnippetItem` from a loaded snippet
+    #[must_use]
+    pub fn new(snippet: &Snippet) -> Self {
+        let obj: Self = Object::new();
+        let imp = obj.imp();
+        *imp.snippet.borrow_mut() = snippet.clone();
+        *imp.preview.borrow_mut() = snippet.preview();
+        obj
+    }
+
+    #[must_use]
+    pub fn name(&self) -> String {
+        self.imp().snippet.borrow().name.clone()
+    }
+
+    /// Text with its placeholders, as written
+    #[must_use]
+    pub fn text(&self) -> String {
+        self.imp().snippet.borrow().text.clone()
+    }
+
+    #[must_use]
+    pub fn preview(&self) -> String {
+        self.imp().preview.borrow().clone()
+    }
+}
@@ -0,0 +1,63 @@
+//! GTK Object wrapper for SSH hosts
+//!
+//! This module provides `SshHostItem`, the row type of the `:ssh` mode.
+//! Activating it runs `ssh` to the host in the terminal emulator.
+
+use crate::providers::ssh::SshHost;
+use glib::Object;
+use glib::subclass::prelude::*;
+use std::cell::RefCell;
+
+/// Internal implementation module for GTK object subclassing
+mod imp {
+    use super::{RefCell, SshHost};
+    #[allow(unused_imports)]
+    use glib::subclass::prelude::{
+        ObjectImpl, ObjectInterfaceType, ObjectSubclass, ObjectSubclassType,
+    };
+
+    #[derive(Default)]
+    pub struct SshHostItem {
+        /// The host as read from the SSH config or `known_hosts`
+        pub host: RefCell<SshHost>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SshHostItem {
+        const NAME: &'static str = "GrunnerSshHostItem";
+        type Type = super::SshHostItem;
+    }
+
+    impl ObjectImpl for SshHostItem {}
+}
+
+glib::wrapper! {
+    pub struct SshHostItem(ObjectSubclass<imp::SshHostItem>);
+}
+
+impl SshHostItem {
+    /// Create a new `SshHostItem` from a loaded host
+    #[must_use]
+    pub fn new(host: &SshHost) -> Self {
+        let obj: Self = Object::new();
+        *obj.imp().host.borrow_mut() = host.clone();
+        obj
+    }
+
+    /// Alias or host name shown in the row
+    #[must_use]
+    pub fn alias(&self) -> String {
+        self.imp().host.borrow().alias.clone()
+    }
+
+    #[must_use]
+    pub fn description(&self) -> String {
+        self.imp().host.borrow().description()
+    }
+
+    /// Arguments of `ssh` that connect to the host
+    #[must_use]
+    pub fn ssh_args(&self) -> Vec<String> {
+        self.imp().host.borrow().ssh_args()
+    }
+}
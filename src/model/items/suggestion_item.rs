@@ -0,0 +1,72 @@
+//! GTK Object wrapper for "Did you mean …?" rows
+//!
+//! This module provides `SuggestionItem`, shown when a query produces no
+//! results but is close to an application or command name. Activating the row
+//! replaces the search text with the suggestion instead of launching anything.
+
+use glib::Object;
+use glib::subclass::prelude::*;
+use std::cell::RefCell;
+
+/// Internal implementation module for GTK object subclassing
+mod imp {
+    use super::RefCell;
+    #[allow(unused_imports)]
+    use glib::subclass::prelude::{
+        ObjectImpl, ObjectInterfaceType, ObjectSubclass, ObjectSubclassType,
+    };
+
+    #[derive(Default)]
+    pub struct SuggestionItem {
+        /// Text that replaces the search entry when the row is activated
+        pub replacement: RefCell<String>,
+        /// The corrected name shown in the row title
+        pub label: RefCell<String>,
+        /// Why the suggestion is shown (e.g. "No results for …")
+        pub description: RefCell<String>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SuggestionItem {
+        const NAME: &'static str = "GrunnerSuggestionItem";
+        type Type = super::SuggestionItem;
+    }
+
+    impl ObjectImpl for SuggestionItem {}
+}
+
+glib::wrapper! {
+    pub struct SuggestionItem(ObjectSubclass<imp::SuggestionItem>);
+}
+
+impl SuggestionItem {
+    /// Create a new `SuggestionItem`
+    ///
+    /// # Arguments
+    /// * `replacement` - Full search text to use when the row is activated
+    /// * `label` - The corrected name, shown as "Did you mean {label}?"
+    /// * `description` - Secondary line explaining the suggestion
+    #[must_use]
+    pub fn new(replacement: String, label: String, description: String) -> Self {
+        let obj: Self = Object::new();
+        *obj.imp().replacement.borrow_mut() = replacement;
+        *obj.imp().label.borrow_mut() = label;
+        *obj.imp().description.borrow_mut() = description;
+        obj
+    }
+
+    #[must_use]
+    pub fn replacement(&self) -> String {
+        self.imp().replacement.borrow().clone()
+    }
+
+    #[must_use]
+    pub fn label(&self) -> String {
+        self.imp().label.borrow().clone()
+    }
+
+    #[must_use]
+    pub fn description(&self) -> String {
+        self.imp().description.borrow().clone()
+    }
+}
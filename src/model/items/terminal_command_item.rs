@@ -0,0 +1,88 @@
+//! GTK Object wrapper for `!command` queries
+//!
+//! This module provides `TerminalCommandItem`, the single row shown for a
+//! query starting with `!`. Activating it runs the text after `!` in the
+//! terminal emulator, unchanged.
+//!
+//! When a plain query matches nothing, two fallback rows offer to run the
+//! query as typed, once without and once inside a terminal.
+
+use glib::Object;
+use glib::subclass::prelude::*;
+use std::cell::RefCell;
+
+/// Internal implementation module for GTK object subclassing
+mod imp {
+    use super::RefCell;
+    #[allow(unused_imports)]
+    use glib::subclass::prelude::{
+        ObjectImpl, ObjectInterfaceType, ObjectSubclass, ObjectSubclassType,
+    };
+
+    #[derive(Default)]
+    pub struct TerminalCommandItem {
+        /// Shell command line, empty while only `!` is typed
+        pub command: RefCell<String>,
+        /// Whether the command runs inside the terminal emulator
+        pub terminal: RefCell<bool>,
+        /// Row title, or empty to show the command itself
+        pub title: RefCell<String>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for TerminalCommandItem {
+        const NAME: &'static str = "GrunnerTerminalCommandItem";
+        type Type = super::TerminalCommandItem;
+    }
+
+    impl ObjectImpl for TerminalCommandItem {}
+}
+
+glib::wrapper! {
+    pub struct TerminalCommandItem(ObjectSubclass<imp::TerminalCommandItem>);
+}
+
+impl TerminalCommandItem {
+    /// Create a new `TerminalCommandItem` for a command line
+    #[must_use]
+    pub fn new(command: &str) -> Self {
+        let obj: Self = Object::new();
+        *obj.imp().command.borrow_mut() = command.to_string();
+        *obj.imp().terminal.borrow_mut() = true;
+        obj
+    }
+
+    /// Create a fallback row running a query that matched nothing
+    #[must_use]
+    pub fn fallback(query: &str, terminal: bool) -> Self {
+        let obj = Self::new(query);
+        *obj.imp().terminal.borrow_mut() = terminal;
+        *obj.imp().title.borrow_mut() = if terminal {
+            format!("Run '{query}' in terminal")
+        } else {
+            format!("Run '{query}'")
+        };
+        obj
+    }
+
+    #[must_use]
+    pub fn command(&self) -> String {
+        self.imp().command.borrow().clone()
+    }
+
+    #[must_use]
+    pub fn terminal(&self) -> bool {
+        *self.imp().terminal.borrow()
+    }
+
+    /// Row title: the fallback label, or the command itself
+    #[must_use]
+    pub fn title(&self) -> String {
+        let title = self.imp().title.borrow();
+        if title.is_empty() {
+            self.command()
+        } else {
+            title.clone()
+        }
+    }
+}
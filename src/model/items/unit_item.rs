@@ -0,0 +1,69 @@
+//! GTK Object wrapper for systemd units
+//!
+//! This module provides `UnitItem`, the row type of the `:unit` mode.
+//! Activating it starts, stops or restarts the unit.
+
+use crate::ui::severity::Severity;
+use crate::units::Unit;
+use glib::Object;
+use glib::subclass::prelude::*;
+use std::cell::RefCell;
+
+/// Internal implementation module for GTK object subclassing
+mod imp {
+    use super::{RefCell, Unit};
+    #[allow(unused_imports)]
+    use glib::subclass::prelude::{
+        ObjectImpl, ObjectInterfaceType, ObjectSubclass, ObjectSubclassType,
+    };
+
+    #[derive(Default)]
+    pub struct UnitItem {
+        /// The unit as listed by its systemd manager
+        pub unit: RefCell<Unit>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for UnitItem {
+        const NAME: &'static str = "GrunnerUnitItem";
+        type Type = super::UnitItem;
+    }
+
+    impl ObjectImpl for UnitItem {}
+}
+
+glib::wrapper! {
+    pub struct UnitItem(ObjectSubclass<imp::UnitItem>);
+}
+
+impl UnitItem {
+    /// Create a new `UnitItem` from a listed unit
+    #[must_use]
+    pub fn new(unit: &Unit) -> Self {
+        let obj: Self = Object::new();
+        *obj.imp().unit.borrow_mut() = unit.clone();
+        obj
+    }
+
+    /// The unit this row controls
+    #[must_use]
+    pub fn unit(&self) -> Unit {
+        self.imp().unit.borrow().clone()
+    }
+
+    #[must_use]
+    pub fn name(&self) -> String {
+        self.imp().unit.borrow().name.clone()
+    }
+
+    #[must_use]
+    pub fn description(&self) -> String {
+        self.imp().unit.borrow().summary()
+    }
+
+    /// Failed, changing and active units are tinted by their state
+    #[must_use]
+    pub fn severity(&self) -> Severity {
+        Severity::of_unit_state(&self.imp().unit.borrow().active_state)
+    }
+}
@@ -0,0 +1,74 @@
+//! GTK Object wrapper for a URL typed in the search bar
+//!
+//! This module provides `UrlItem`, the "Open in browser" row shown above the
+//! app results when the query looks like a URL, and the row of a configured
+//! web search (`:ddg rust gtk`).
+
+use glib::Object;
+use glib::subclass::prelude::*;
+use std::cell::RefCell;
+
+/// Internal implementation module for GTK object subclassing
+mod imp {
+    use super::RefCell;
+    #[allow(unused_imports)]
+    use glib::subclass::prelude::{
+        ObjectImpl, ObjectInterfaceType, ObjectSubclass, ObjectSubclassType,
+    };
+
+    #[derive(Default)]
+    pub struct UrlItem {
+        /// URL opened on activation, with its scheme
+        pub url: RefCell<String>,
+        /// Title shown in the row (empty for "Open in browser")
+        pub title: RefCell<String>,
+        /// Themed icon name or image path (empty for "web-browser")
+        pub icon: RefCell<String>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for UrlItem {
+        const NAME: &'static str = "GrunnerUrlItem";
+        type Type = super::UrlItem;
+    }
+
+    impl ObjectImpl for UrlItem {}
+}
+
+glib::wrapper! {
+    pub struct UrlItem(ObjectSubclass<imp::UrlItem>);
+}
+
+impl UrlItem {
+    /// Create a new `UrlItem` opening `url`
+    #[must_use]
+    pub fn new(url: &str) -> Self {
+        let obj: Self = Object::new();
+        *obj.imp().url.borrow_mut() = url.to_string();
+        obj
+    }
+
+    /// Create the row of a web search opening `url`
+    #[must_use]
+    pub fn web_search(url: &str, title: String, icon: String) -> Self {
+        let obj = Self::new(url);
+        *obj.imp().title.borrow_mut() = title;
+        *obj.imp().icon.borrow_mut() = icon;
+        obj
+    }
+
+    #[must_use]
+    pub fn title(&self) -> String {
+        self.imp().title.borrow().clone()
+    }
+
+    #[must_use]
+    pub fn icon(&self) -> String {
+        self.imp().icon.borrow().clone()
+    }
+
+    #[must_use]
+    pub fn url(&self) -> String {
+        self.imp().url.borrow().clone()
+    }
+}
@@ -0,0 +1,63 @@
+//! GTK Object wrapper for the "create the missing vault" row
+//!
+//! This module provides `VaultSetupItem`, shown by `:ob` and `:obg` when the
+//! configured Obsidian vault does not exist yet. Activating the row creates
+//! the vault and then runs the pending command again.
+
+use glib::Object;
+use glib::subclass::prelude::*;
+use std::cell::RefCell;
+
+/// Internal implementation module for GTK object subclassing
+mod imp {
+    use super::RefCell;
+    #[allow(unused_imports)]
+    use glib::subclass::prelude::{
+        ObjectImpl, ObjectInterfaceType, ObjectSubclass, ObjectSubclassType,
+    };
+
+    #[derive(Default)]
+    pub struct VaultSetupItem {
+        /// The missing vault path as shown to the user (e.g. `~/Notes`)
+        pub vault: RefCell<String>,
+        /// The colon command to run again once the vault exists
+        pub retry_query: RefCell<String>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for VaultSetupItem {
+        const NAME: &'static str = "GrunnerVaultSetupItem";
+        type Type = super::VaultSetupItem;
+    }
+
+    impl ObjectImpl for VaultSetupItem {}
+}
+
+glib::wrapper! {
+    pub struct VaultSetupItem(ObjectSubclass<imp::VaultSetupItem>);
+}
+
+impl VaultSetupItem {
+    /// Create a new `VaultSetupItem`
+    ///
+    /// # Arguments
+    /// * `vault` - Display form of the missing vault path
+    /// * `retry_query` - Search text to run again after the vault is created
+    #[must_use]
+    pub fn new(vault: String, retry_query: String) -> Self {
+        let obj: Self = Object::new();
+        *obj.imp().vault.borrow_mut() = vault;
+        *obj.imp().retry_query.borrow_mut() = retry_query;
+        obj
+    }
+
+    #[must_use]
+    pub fn vault(&self) -> String {
+        self.imp().vault.borrow().clone()
+    }
+
+    #[must_use]
+    pub fn retry_query(&self) -> String {
+        self.imp().retry_query.borrow().clone()
+    }
+}
@@ -0,0 +1,67 @@
+//! GTK Object wrapper for open windows
+//!
+//! This module provides `WindowItem`, the row type of the `:w` mode.
+//! Activating it focuses the window.
+
+use crate::window_switcher::OpenWindow;
+use glib::Object;
+use glib::subclass::prelude::*;
+use std::cell::RefCell;
+
+/// Internal implementation module for GTK object subclassing
+mod imp {
+    use super::{OpenWindow, RefCell};
+    #[allow(unused_imports)]
+    use glib::subclass::prelude::{
+        ObjectImpl, ObjectInterfaceType, ObjectSubclass, ObjectSubclassType,
+    };
+
+    #[derive(Default)]
+    pub struct WindowItem {
+        /// The window as listed by the window backend
+        pub window: RefCell<OpenWindow>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for WindowItem {
+        const NAME: &'static str = "GrunnerWindowItem";
+        type Type = super::WindowItem;
+    }
+
+    impl ObjectImpl for WindowItem {}
+}
+
+glib::wrapper! {
+    pub struct WindowItem(ObjectSubclass<imp::WindowItem>);
+}
+
+impl WindowItem {
+    /// Create a new `WindowItem` from a listed window
+    #[must_use]
+    pub fn new(window: &OpenWindow) -> Self {
+        let obj: Self = Object::new();
+        *obj.imp().window.borrow_mut() = window.clone();
+        obj
+    }
+
+    /// The window this row focuses
+    #[must_use]
+    pub fn window(&self) -> OpenWindow {
+        self.imp().window.borrow().clone()
+    }
+
+    #[must_use]
+    pub fn title(&self) -> String {
+        self.imp().window.borrow().title.clone()
+    }
+
+    #[must_use]
+    pub fn description(&self) -> String {
+        self.imp().window.borrow().description()
+    }
+
+    #[must_use]
+    pub fn icon(&self) -> String {
+        self.imp().window.borrow().icon.clone()
+    }
+}
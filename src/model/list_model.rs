@@ -6,161 +6,96 @@
 //! - Search provider integration
 //! - Real-time result updates with background threads
 //!
-//! The `AppListModel` struct coordinates three sub-components:
+//! The `AppListModel` struct coordinates four sub-components:
+//! - [`MainThreadModel`]: the result store and selection, main-thread only
 //! - [`SearchState`]: manages query text and task generation for cancellation
-//! - [`DebounceScheduler`]: handles debounce timers for commands and search
+//! - [`DebounceScheduler`]: handles debounce timers for commands
 //! - `ModelConfig`: holds configuration (`max_results`, obsidian, commands, blacklist)
+//!
+//! With `prefer_focus_running`, app rows are also matched against the open
+//! windows, fetched at most every
+//! [`RUNNING_WINDOWS_TTL`](crate::actions::workspace::RUNNING_WINDOWS_TTL),
+//! to mark running apps.
 
+use crate::actions::workspace::{RunningWindows, fetch_running_windows, find_running_window};
 use crate::app_mode::ActiveMode;
-use crate::core::config::{CommandConfig, ObsidianConfig};
-use crate::launcher::DesktopApp;
-use crate::model::debounce::{DEFAULT_SEARCH_DEBOUNCE_MS, DebounceScheduler};
-use crate::model::items::SearchResultItem;
+use crate::core::config::{CommandConfig, ObsidianConfig, ProviderSettings};
+use crate::core::global_state::get_tokio_runtime;
+use crate::core::obsidian::ObsidianContext;
+use crate::first_seen::{FirstSeen, RECENT_INSTALL_SECS};
+use crate::history::{app_key, now_secs, provider_key};
+use crate::launcher::{DesktopApp, apps_by_ids, without_blacklisted};
+use crate::metrics;
+use crate::model::command_row::CommandRow;
+use crate::model::debounce::DebounceScheduler;
+use crate::model::items::{
+    AppActionItem, AppItem, BookmarkItem, ClipboardItem, CommandItem, EmojiItem, ManPageItem,
+    PassEntryItem, ProcessItem, ProjectItem, SearchResultItem, SectionHeaderItem, SnippetItem,
+    SshHostItem, SuggestionItem, TerminalCommandItem, UnitItem, UrlItem, WindowItem,
+};
+use crate::model::main_thread::MainThreadModel;
 use crate::model::model_config::ModelConfig;
+use crate::model::provider_rows::{ProviderBatches, ProviderRows, RowsAction};
 use crate::model::search_state::SearchState;
+use crate::model::worker::spawn_worker_with_updates;
+use crate::processes::{self, Process};
+use crate::providers::bookmarks::{BookmarkCache, load_all_bookmarks, match_bookmarks};
 use crate::providers::dbus::{self, SearchProvider as DbusSearchProvider};
-use gtk4::SingleSelection;
-use gtk4::gio;
+use crate::providers::manpages::{ManPageCache, load_man_pages, match_man_pages};
+use crate::providers::pass::{load_pass_entries, match_pass_entries, password_store_dir};
+use crate::providers::projects::{ProjectCache, load_all_projects, match_projects};
+use crate::providers::ranking::{boost_by_frecency, frecency_weight, suggest_app_name};
+use crate::providers::recent::{load_recent_documents, match_recent_documents};
+use crate::providers::snippets::{load_snippets, match_snippets};
+use crate::providers::ssh::{load_ssh_hosts, match_ssh_hosts};
+use crate::units::{self, Unit};
+use crate::window_switcher::{OpenWindow, WindowBackend, match_windows};
 use gtk4::prelude::*;
 use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::rc::Rc;
-use std::time::Duration;
+use std::time::Instant;
 
 const PROVIDER_SEARCH_DEBOUNCE_MS: u32 = 120;
-const PROVIDER_CLEAR_TIMEOUT_MS: u64 = 25;
-
-// ── Pollers ───────────────────────────────────────────────────────────────────
-
-/// Drives the idle-poll loop for a streaming search-provider query.
-///
-/// This struct handles the more complex streaming results from
-/// GNOME Shell search providers, which can return multiple batches
-/// of results over time.
-struct ProviderSearchPoller {
-    /// Channel receiver for search result batches
-    rx: std::sync::mpsc::Receiver<Vec<dbus::SearchResult>>,
-    /// Reference to the main list model for UI updates
-    model: AppListModel,
-    /// Generation ID to prevent stale updates after new searches
-    generation: u64,
-    /// Search terms for highlighting in results
-    terms: Vec<String>,
-    /// Timeout source ID for clearing old results
-    clear_timeout: Rc<RefCell<Option<glib::SourceId>>>,
-    /// Whether the first batch of results has been processed
-    first_batch: Rc<Cell<bool>>,
-    /// Whether to clear the store before showing results
-    clear_store: bool,
-}
-
-impl ProviderSearchPoller {
-    /// Poll for search provider results and update UI as batches arrive
-    ///
-    /// This method processes available result batches in a loop to avoid
-    /// unnecessary idle callbacks when providers return data quickly.
-    /// It handles the first batch specially by clearing previous results
-    /// and manages a timeout to show a loading indicator.
-    fn poll(self) {
-        // Early exit if search generation has changed
-        if self.model.state.task_gen() != self.generation {
-            return;
-        }
-
-        // Consume all currently-available batches before yielding back to the
-        // main loop, so a fast provider doesn't stall behind repeated idles.
-        let this = self;
-        loop {
-            match this.rx.try_recv() {
-                Ok(results) => {
-                    // Double-check generation after receiving results
-                    if this.model.state.task_gen() != this.generation {
-                        return;
-                    }
-
-                    // Cancel the clear timeout since we now have results
-                    if let Some(id) = this.clear_timeout.borrow_mut().take() {
-                        id.remove();
-                    }
-
-                    // Convert search results to GTK list items
-                    let items: Vec<glib::Object> = results
-                        .into_iter()
-                        .map(|r| {
-                            let (icon_themed, icon_file) = match r.icon {
-                                Some(dbus::IconData::Themed(n)) => (n, String::new()),
-                                Some(dbus::IconData::File(p)) => (String::new(), p),
-                                None => (String::new(), String::new()),
-                            };
-                            SearchResultItem::new(
-                                r.id,
-                                r.name,
-                                r.description,
-                                icon_themed,
-                                icon_file,
-                                r.app_icon,
-                                r.bus_name,
-                                r.object_path,
-                                this.terms.clone(),
-                                r.clipboard_text,
-                            )
-                            .upcast::<glib::Object>()
-                        })
-                        .collect();
-
-                    // Clear store only on first batch and if clear_store is true
-                    if !this.first_batch.get() && this.clear_store {
-                        this.model.store.remove_all();
-                        this.first_batch.set(true);
-                    }
-
-                    // Append new items to the store
-                    this.model
-                        .store
-                        .splice(this.model.store.n_items(), 0, &items);
-
-                    // Auto-select first item if nothing is selected
-                    if this.model.store.n_items() > 0
-                        && this.model.selection.selected() == gtk4::INVALID_LIST_POSITION
-                    {
-                        this.model.selection.set_selected(0);
-                    }
-                }
-                Err(std::sync::mpsc::TryRecvError::Empty) => {
-                    // No more data ready - schedule next poll on idle
-                    glib::idle_add_local_once(move || this.poll());
-                    return;
-                }
-                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
-                    // Provider finished sending all results
-                    return;
-                }
-            }
-        }
-    }
-}
 
 /// Main data model for Grunner's search interface
 ///
-/// Coordinates three sub-components:
+/// Coordinates four sub-components:
+/// - [`MainThreadModel`]: the GTK list store and selection
 /// - [`SearchState`]: query text and task generation for cancellation
-/// - [`DebounceScheduler`]: debounce timers for commands and search
+/// - [`DebounceScheduler`]: debounce timers for commands
 /// - `ModelConfig`: configuration (`max_results`, obsidian, commands, blacklist)
-///
-/// The struct itself provides GTK list/selection models and delegates
-/// to the sub-components.
 #[derive(Clone)]
 pub struct AppListModel {
-    pub(crate) store: gio::ListStore,
-    pub(crate) selection: SingleSelection,
+    pub(crate) results: MainThreadModel,
 
     pub(crate) state: SearchState,
     pub(crate) debounce: DebounceScheduler,
     pub(crate) config: ModelConfig,
 
     /// Cached GNOME Shell search providers
-    search_providers: Rc<std::cell::OnceCell<Vec<DbusSearchProvider>>>,
-    /// All available desktop applications (used by providers)
+    search_providers: Rc<RefCell<Option<Vec<DbusSearchProvider>>>>,
+    /// Result IDs of each provider's last applied search, by bus name, for
+    /// refining them when the query is extended
+    provider_result_sets: Rc<RefCell<HashMap<String, dbus::ResultSet>>>,
+    /// Desktop applications shown in results (used by providers): the
+    /// loaded ones minus `[search] app_blacklist`
     all_apps: Rc<RefCell<Vec<DesktopApp>>>,
+    /// Every application of the last scan, kept so a changed blacklist can
+    /// be applied without scanning again
+    loaded_apps: Rc<RefCell<Vec<DesktopApp>>>,
+    /// Browser bookmarks for `:b`, loaded on first use
+    bookmarks: Rc<RefCell<BookmarkCache>>,
+    /// Recent projects for `:proj`, loaded on first use
+    projects: Rc<RefCell<ProjectCache>>,
+    /// Manual pages for `:man`, loaded on first use
+    man_pages: Rc<RefCell<ManPageCache>>,
+    /// When each application was first found, for "Recently installed"
+    first_seen: Rc<RefCell<FirstSeen>>,
+    /// Windows open at the last check, for marking running apps
+    running_windows: Rc<RefCell<Option<RunningWindows>>>,
+    /// Whether the window list is being fetched
+    running_fetch_pending: Rc<Cell<bool>>,
 }
 
 /// Trait for command handlers that need to interact with the list model.
@@ -179,7 +114,7 @@ pub trait CommandSink: Clone + 'static {
     fn schedule<F: FnOnce() + 'static>(&self, f: F);
     fn bump_and_schedule<F: FnOnce() + 'static>(&self, f: F);
     fn get_commands(&self, query: &str) -> Vec<CommandConfig>;
-    fn obsidian_config(&self) -> Option<ObsidianConfig>;
+    fn obsidian(&self) -> Option<Rc<ObsidianContext>>;
 }
 
 impl CommandSink for AppListModel {
@@ -242,8 +177,8 @@ impl CommandSink for AppListModel {
             .collect()
     }
 
-    fn obsidian_config(&self) -> Option<ObsidianConfig> {
-        self.config.obsidian_cfg.clone()
+    fn obsidian(&self) -> Option<Rc<ObsidianContext>> {
+        self.config.obsidian.borrow().clone()
     }
 }
 
@@ -262,27 +197,27 @@ impl AppListModel {
 
     /// Append an item to the list store
     pub(crate) fn append_store_item(&self, obj: &impl IsA<glib::Object>) {
-        self.store.append(obj);
+        self.results.append(obj);
     }
 
     /// Remove all items from the list store
     pub(crate) fn remove_all_store_items(&self) {
-        self.store.remove_all();
+        self.results.remove_all();
     }
 
     /// Return the number of items in the list store
     pub(crate) fn store_item_count(&self) -> u32 {
-        self.store.n_items()
+        self.results.n_items()
     }
 
     /// Set the selected position in the selection model
     pub(crate) fn set_selected_position(&self, pos: u32) {
-        self.selection.set_selected(pos);
+        self.results.set_selected(pos);
     }
 
-    /// Return a reference to the Obsidian configuration, if present
-    pub(crate) fn obsidian_config(&self) -> Option<&ObsidianConfig> {
-        self.config.obsidian_cfg.as_ref()
+    /// Return the resolved Obsidian settings, if configured
+    pub(crate) fn obsidian(&self) -> Option<Rc<ObsidianContext>> {
+        self.config.obsidian.borrow().clone()
     }
 
     /// Create a new `AppListModel` with the given configuration
@@ -294,6 +229,7 @@ impl AppListModel {
     /// * `search_provider_blacklist` - List of provider IDs to exclude
     /// * `commands` - List of custom script commands
     /// * `disable_modes` - Whether to disable all special modes (colon commands)
+    /// * `auto_launch_threshold` - Score at which the top app auto-launches (0 = off)
     #[must_use]
     pub fn new(
         max_results: usize,
@@ -302,67 +238,140 @@ impl AppListModel {
         search_provider_blacklist: Vec<String>,
         commands: Vec<crate::core::config::CommandConfig>,
         disable_modes: bool,
+        auto_launch_threshold: u8,
     ) -> Self {
-        let store = gio::ListStore::new::<glib::Object>();
-        let selection = SingleSelection::new(Some(store.clone()));
-        selection.set_autoselect(true);
-        selection.set_can_unselect(false);
+        let results = MainThreadModel::new();
 
         let all_apps = Rc::new(RefCell::new(Vec::new()));
 
         let state = SearchState::new();
-        let debounce = DebounceScheduler::new(command_debounce_ms, DEFAULT_SEARCH_DEBOUNCE_MS);
+        let debounce = DebounceScheduler::new(command_debounce_ms);
         let config = ModelConfig::new(
             max_results,
             obsidian_cfg,
             search_provider_blacklist,
             commands,
             disable_modes,
+            auto_launch_threshold,
             all_apps.clone(),
         );
 
         Self {
-            store,
-            selection,
+            results,
             state,
             debounce,
             config,
-            search_providers: Rc::new(std::cell::OnceCell::new()),
+            search_providers: Rc::new(RefCell::new(None)),
+            provider_result_sets: Rc::new(RefCell::new(HashMap::new())),
             all_apps,
+            loaded_apps: Rc::new(RefCell::new(Vec::new())),
+            bookmarks: Rc::new(RefCell::new(BookmarkCache::default())),
+            projects: Rc::new(RefCell::new(ProjectCache::default())),
+            man_pages: Rc::new(RefCell::new(ManPageCache::default())),
+            first_seen: Rc::new(RefCell::new(FirstSeen::default())),
+            running_windows: Rc::new(RefCell::new(None)),
+            running_fetch_pending: Rc::new(Cell::new(false)),
         }
     }
 
     /// Update the list of available desktop applications
     ///
     /// This is typically called once at startup after scanning .desktop files.
-    /// It triggers a repopulation of the list with the current query.
-    pub fn set_apps(&self, apps: Vec<DesktopApp>) {
-        *self.all_apps.borrow_mut() = apps;
+    /// `first_seen` holds the first-seen times tracked for the scan. It
+    /// triggers a repopulation of the list with the current query.
+    ///
+    /// Apps named by `[search] app_blacklist` are kept out of the results.
+    /// The filter runs here rather than in the scan, so the app cache does
+    /// not depend on the configuration.
+    pub fn set_apps(&self, apps: Vec<DesktopApp>, first_seen: FirstSeen) {
+        *self.loaded_apps.borrow_mut() = apps;
+        self.filter_apps();
+        *self.first_seen.borrow_mut() = first_seen;
         let query = self.state.current_query();
         self.populate(&query);
     }
 
-    /// Apply configuration changes (hot-reload after saving settings)
+    /// Hide an application from the results
     ///
-    /// This updates all configurable settings without restarting the app.
-    pub fn apply_config(&self, config: &crate::core::config::Config) {
-        let old_max_results = self.config.max_results.get();
+    /// Adds `desktop_id` to the in-memory app blacklist and runs the current
+    /// query again. Persisting the change to the config file is up to the
+    /// caller.
+    pub fn hide_app(&self, desktop_id: &str) {
+        {
+            let mut blacklist = self.config.app_blacklist.borrow_mut();
+            if !blacklist.iter().any(|b| b == desktop_id) {
+                blacklist.push(desktop_id.to_string());
+            }
+        }
+        self.filter_apps();
+        let query = self.state.current_query();
+        self.populate(&query);
+    }
 
+    /// Recompute the shown apps from the loaded ones and the app blacklist
+    fn filter_apps(&self) {
+        *self.all_apps.borrow_mut() = without_blacklisted(
+            &self.loaded_apps.borrow(),
+            &self.config.app_blacklist.borrow(),
+        );
+    }
+
+    /// Apply configuration changes (hot-reload after saving settings or
+    /// editing the config file)
+    ///
+    /// This updates all configurable settings without restarting the app,
+    /// then runs the current query again so the change shows at once.
+    pub fn apply_config(&self, config: &crate::core::config::Config) {
         self.config.apply_config(config);
 
         // Update command debounce
         self.debounce
             .set_command_debounce_ms(config.command_debounce_ms);
 
-        // Repopulate if max_results changed or in CustomScript mode
-        if old_max_results != config.max_results {
-            let query = self.state.current_query();
-            self.populate(&query);
-        } else if self.state.active_mode() == ActiveMode::CustomScript {
-            use crate::command_handler::CommandHandler;
-            let query = self.state.current_query();
-            let handler = CommandHandler::new(self.clone());
-            handler.handle_sh(&query);
+        // Rediscover providers in case the blacklist changed
+        self.search_providers.borrow_mut().take();
+        self.provider_result_sets.borrow_mut().clear();
+        self.filter_apps();
+
+        // The roots may have changed; projects are read again on next use
+        *self.projects.borrow_mut() = ProjectCache::Unloaded;
+
+        let query = self.state.current_query();
+        self.populate(&query);
+    }
+
+    /// Stop showing results from a search provider
+    ///
+    /// Adds `desktop_id` to the in-memory blacklist, drops the provider's
+    /// results from the current list, and forgets the cached provider list
+    /// so the next search re-discovers providers without it. Persisting the
+    /// change to the config file is up to the caller.
+    pub fn blacklist_provider(&self, desktop_id: &str) {
+        {
+            let mut blacklist = self.config.blacklist.borrow_mut();
+            if !blacklist.iter().any(|b| b == desktop_id) {
+                blacklist.push(desktop_id.to_string());
+            }
+        }
+        self.search_providers.borrow_mut().take();
+
+        let selected = self.results.selected();
+        let mut pos = self.results.n_items();
+        while pos > 0 {
+            pos -= 1;
+            if self
+                .results
+                .item(pos)
+                .and_downcast::<SearchResultItem>()
+                .is_some_and(|item| item.desktop_id() == desktop_id)
+            {
+                self.results.remove(pos);
+            }
+        }
+
+        let n = self.results.n_items();
+        if n > 0 {
+            self.results.set_selected(selected.min(n - 1));
         }
     }
 
@@ -373,8 +382,14 @@ impl AppListModel {
         self.debounce.cancel_command();
     }
 
-    fn cancel_search_debounce(&self) {
-        self.debounce.cancel_search();
+    /// Drop pending and in-flight searches when the window is hidden
+    ///
+    /// A resident window keeps its model, so without this a debounced
+    /// command or a late worker batch from before the hide would land in the
+    /// list of the next show.
+    pub fn cancel_pending(&self) {
+        self.cancel_debounce();
+        self.bump_task_gen();
     }
 
     /// Schedule a command to run with the configured default debounce delay
@@ -385,13 +400,6 @@ impl AppListModel {
         self.debounce.schedule_command(f);
     }
 
-    fn schedule_search<F>(&self, f: F)
-    where
-        F: FnOnce() + 'static,
-    {
-        self.debounce.schedule_search(f);
-    }
-
     /// Schedule a search provider query to run with a specific delay
     fn schedule_provider_search_with_delay<F>(&self, delay_ms: u32, f: F)
     where
@@ -404,40 +412,78 @@ impl AppListModel {
         self.state.bump_task_gen()
     }
 
-    pub fn schedule_populate(&self, query: &str) {
-        self.cancel_debounce();
-        self.cancel_search_debounce();
+    /// Whether `query` waits for the search entry's `search-changed` delay
+    ///
+    /// Plain queries are debounced by the entry; empty queries and colon
+    /// commands are populated on every change (commands debounce internally).
+    #[must_use]
+    pub fn is_debounced_query(query: &str) -> bool {
+        !query.is_empty() && !query.starts_with(':')
+    }
 
-        // Empty query: immediate clear
-        if query.is_empty() {
-            let model = self.clone();
-            glib::idle_add_local_once(move || model.populate(""));
-            return;
+    /// Command line of a `!command` query, run verbatim in a terminal
+    ///
+    /// Whitespace before and right after the `!` is ignored; `!` alone gives
+    /// an empty command.
+    #[must_use]
+    pub fn terminal_command(query: &str) -> Option<&str> {
+        query.trim_start().strip_prefix('!').map(str::trim_start)
+    }
+
+    /// URL to open for a query that looks like one, or `None`
+    ///
+    /// A query with a scheme (`https://crates.io`) is kept as typed. A bare
+    /// domain with an alphabetic top-level domain (`github.com/Nihmar`) gets
+    /// `https://` prepended. Words without a dot, like `git`, never match.
+    #[must_use]
+    pub fn url_from_query(query: &str) -> Option<String> {
+        let query = query.trim();
+        if query.is_empty() || query.contains(char::is_whitespace) {
+            return None;
         }
 
+        if let Some((scheme, rest)) = query.split_once("://") {
+            let valid_scheme = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+            return (valid_scheme && !rest.is_empty()).then(|| query.to_string());
+        }
+
+        let host_end = query.find(['/', ':', '?', '#']).unwrap_or(query.len());
+        let host = &query[..host_end];
+        let labels: Vec<&str> = host.split('.').collect();
+        let valid_labels = labels.iter().all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && label.chars().all(|c| c.is_alphanumeric() || c == '-')
+        });
+        let tld = labels.last().copied().unwrap_or_default();
+        let valid_tld = tld.chars().count() >= 2 && tld.chars().all(char::is_alphabetic);
+        (labels.len() >= 2 && valid_labels && valid_tld).then(|| format!("https://{query}"))
+    }
+
+    /// Populate the store on the next idle cycle for non-debounced queries
+    pub fn schedule_populate(&self, query: &str) {
+        self.cancel_debounce();
+
         let query = query.to_string();
         let model = self.clone();
-
-        if query.starts_with(':') {
-            // Colon commands: immediate (they have internal debounce)
-            glib::idle_add_local_once(move || model.populate(&query));
-        } else {
-            // Default search: debounce via schedule_search (uses search_debounce_ms)
-            self.schedule_search(move || model.populate(&query));
-        }
+        glib::idle_add_local_once(move || model.populate(&query));
     }
 
     /// Main entry point for updating search results based on query
     ///
     /// This method routes the query to the appropriate handler:
     /// - Colon commands (starting with `:`) go to command handlers
-    /// - Empty queries show all applications
+    /// - `!command` shows a single row that runs the command in a terminal
+    /// - Queries matching nothing offer to run the query as a shell command
+    /// - Empty queries show recently installed applications, then all others
     /// - Non-empty queries trigger fuzzy application search
     pub fn populate(&self, query: &str) {
         self.state.set_query(query);
         self.state.set_active_mode(ActiveMode::None);
         self.cancel_debounce();
-        self.cancel_search_debounce();
 
         // Handle colon-prefixed commands (skip if modes are disabled)
         if !self.config.disable_modes.get() && query.starts_with(':') {
@@ -445,49 +491,270 @@ impl AppListModel {
             return;
         }
 
+        // `!command` bypasses every search
+        if self.config.terminal_prefix.get()
+            && let Some(command) = Self::terminal_command(query)
+        {
+            self.bump_task_gen();
+            self.results
+                .replace_all(&[TerminalCommandItem::new(command).upcast()]);
+            self.results.set_selected(0);
+            return;
+        }
+
         // Regular application search — splice replaces existing content
         // atomically (single items-changed signal) instead of N append() calls.
+        let started = metrics::start();
+        let began = Instant::now();
         self.bump_task_gen();
+        let skip_providers = self.config.search_cuts.get().providers;
 
-        let all_results: Vec<glib::Object> = self
+        let mut all_results: Vec<glib::Object> = self
             .config
             .providers
             .iter()
             .flat_map(|p| p.search(query))
             .collect();
+        let url = Self::url_from_query(query);
+
+        // The empty query lists pinned apps, then recently installed ones,
+        // first, each under a header
+        let mut first_fresh = 0;
+        if query.is_empty() {
+            let mut leading = self.pinned_rows();
+            leading.extend(self.recently_installed_rows(&leading));
+            if !leading.is_empty() {
+                let leading_ids = app_ids(&leading);
+                all_results.retain(|obj| {
+                    obj.downcast_ref::<AppItem>()
+                        .is_none_or(|app| !leading_ids.contains(&app.desktop_id()))
+                });
+                all_results.splice(0..0, leading);
+                first_fresh = 1;
+            }
+        }
+
+        // A query matching nothing gets a "did you mean" row, and can still
+        // be run as typed, without or inside a terminal
+        if all_results.is_empty() && url.is_none() && !query.trim().is_empty() {
+            if let Some(item) = self.app_name_suggestion(query) {
+                all_results.push(item.upcast());
+            }
+            all_results.push(TerminalCommandItem::fallback(query, false).upcast());
+            all_results.push(TerminalCommandItem::fallback(query, true).upcast());
+        }
+
+        // A URL-looking query can be opened in the browser, above the apps
+        if let Some(url) = url {
+            all_results.insert(0, UrlItem::new(&url).upcast());
+        }
+        let fresh_count = all_results.len();
+
+        // Provider rows of the previous query stay, dimmed, until the new
+        // provider search replaces or clears them
+        if !query.is_empty() && !skip_providers {
+            all_results.extend(self.stale_provider_rows());
+        }
 
-        self.store.splice(0, self.store.n_items(), &all_results);
+        self.mark_running_apps(&all_results);
+        self.results.replace_all(&all_results);
+        self.refresh_running_windows();
 
         // Schedule search provider query to mimic GNOME Search behavior
-        if !query.is_empty() {
-            self.schedule_provider_search(query.to_string(), false);
+        if !query.is_empty() && !skip_providers {
+            self.schedule_provider_search(query.to_string());
+        }
+
+        // Auto-select the first fresh result; stale rows are not selected
+        if fresh_count > 0 {
+            self.results.set_selected(first_fresh);
+        } else {
+            self.results.set_selected(gtk4::INVALID_LIST_POSITION);
+        }
+        metrics::record_elapsed(started, |m, elapsed| m.populate = Some(elapsed));
+        self.config.record_search_latency(began.elapsed());
+    }
+
+    /// "Pinned" header and the pinned apps in `[search] pinned_apps` order,
+    /// or nothing if no pinned app is installed
+    fn pinned_rows(&self) -> Vec<glib::Object> {
+        let apps = self.all_apps.borrow();
+        let pinned = apps_by_ids(&self.config.pinned_apps.borrow(), &apps);
+        if pinned.is_empty() {
+            return Vec::new();
+        }
+        std::iter::once(SectionHeaderItem::new("Pinned").upcast())
+            .chain(pinned.into_iter().map(|app| AppItem::new(app).upcast()))
+            .collect()
+    }
+
+    /// "Recently installed" header and app rows, or nothing if there are none
+    ///
+    /// Apps already among the `above` rows are left out.
+    fn recently_installed_rows(&self, above: &[glib::Object]) -> Vec<glib::Object> {
+        let apps = self.all_apps.borrow();
+        let above = app_ids(above);
+        let mut recent =
+            self.first_seen
+                .borrow()
+                .recently_installed(&apps, now_secs(), RECENT_INSTALL_SECS);
+        recent.retain(|app| !above.contains(&app.desktop_id));
+        if recent.is_empty() {
+            return Vec::new();
+        }
+        std::iter::once(SectionHeaderItem::new("Recently installed").upcast())
+            .chain(recent.into_iter().map(|app| AppItem::new(app).upcast()))
+            .collect()
+    }
+
+    /// Mark the app rows among `rows` whose application has a window open
+    ///
+    /// Uses the last fetched window list, however old, so rows never wait
+    /// for D-Bus; [`refresh_running_windows`](Self::refresh_running_windows)
+    /// corrects them. Returns whether any row changed.
+    fn mark_running_apps(&self, rows: &[glib::Object]) -> bool {
+        if !self.config.prefer_focus_running.get() {
+            return false;
+        }
+        let cache = self.running_windows.borrow();
+        let windows = cache.as_ref().map_or(&[][..], |c| c.windows.as_slice());
+        let mut changed = false;
+        for app in rows.iter().filter_map(|obj| obj.downcast_ref::<AppItem>()) {
+            let window = find_running_window(
+                windows,
+                &app.startup_wm_class(),
+                &app.desktop_id(),
+                &app.exec(),
+            )
+            .map(|w| w.id);
+            if app.running_window() != window {
+                app.set_running_window(window);
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Fetch the window list again once it is older than the TTL
+    ///
+    /// When the list arrives, the app rows shown are marked again and the
+    /// changed ones rebound. A failed fetch (e.g. no window-calls extension)
+    /// counts as no open windows until the TTL runs out again.
+    fn refresh_running_windows(&self) {
+        if !self.config.prefer_focus_running.get() || self.running_fetch_pending.get() {
+            return;
+        }
+        if self
+            .running_windows
+            .borrow()
+            .as_ref()
+            .is_some_and(|c| c.is_fresh(Instant::now()))
+        {
+            return;
+        }
+
+        self.running_fetch_pending.set(true);
+        let model = self.clone();
+        glib::spawn_future_local(async move {
+            let fetched = fetch_running_windows()
+                .await
+                .unwrap_or_else(|| RunningWindows {
+                    fetched: Instant::now(),
+                    windows: Vec::new(),
+                });
+            model.running_fetch_pending.set(false);
+            *model.running_windows.borrow_mut() = Some(fetched);
+
+            let selected = model.results.selected();
+            for pos in 0..model.results.n_items() {
+                if let Some(obj) = model.results.item(pos)
+                    && model.mark_running_apps(std::slice::from_ref(&obj))
+                {
+                    model.results.rebind(pos);
+                }
+            }
+            if selected != model.results.selected() {
+                model.results.set_selected(selected);
+            }
+        });
+    }
+
+    /// The provider results currently shown, marked stale
+    fn stale_provider_rows(&self) -> Vec<glib::Object> {
+        let mut rows = Vec::new();
+        for obj in (0..self.results.n_items()).filter_map(|pos| self.results.item(pos)) {
+            if let Some(item) = obj.downcast_ref::<SearchResultItem>() {
+                item.set_stale(true);
+                rows.push(obj);
+            }
         }
+        rows
+    }
 
-        // Auto-select first item if we have results
-        if self.store.n_items() > 0 {
-            self.selection.set_selected(0);
+    /// `results` with the frequently activated ones of each provider moved
+    /// up, see [`boost_by_frecency`]
+    ///
+    /// Results never move past those of another provider.
+    fn boost_provider_results(&self, results: Vec<dbus::SearchResult>) -> Vec<dbus::SearchResult> {
+        let history = self.config.history.borrow();
+        let now = now_secs();
+        let mut groups: Vec<Vec<dbus::SearchResult>> = Vec::new();
+        for result in results {
+            match groups.last_mut() {
+                Some(group) if group[0].bus_name == result.bus_name => group.push(result),
+                _ => groups.push(vec![result]),
+            }
+        }
+        for group in &mut groups {
+            boost_by_frecency(group, |r| {
+                history.frecency(&provider_key(&r.bus_name, &r.id), now)
+            });
         }
+        groups.into_iter().flatten().collect()
+    }
+
+    /// "Did you mean …?" row for a query that matched no application
+    fn app_name_suggestion(&self, query: &str) -> Option<SuggestionItem> {
+        let query = query.trim();
+        if query.is_empty() {
+            return None;
+        }
+        // Among equally close names, prefer the more frequently launched app
+        let history = self.config.history.borrow();
+        let now = now_secs();
+        let name = suggest_app_name(query, &self.all_apps.borrow(), |app| {
+            frecency_weight(history.frecency(&app_key(&app.desktop_id), now))
+        })?;
+        Some(SuggestionItem::new(
+            name.clone(),
+            name,
+            format!("No results for \"{query}\""),
+        ))
     }
 
     /// Schedule a search provider query to run in parallel with application search
-    fn schedule_provider_search(&self, query: String, clear_store: bool) {
+    fn schedule_provider_search(&self, query: String) {
         // Discover providers (cached after first use)
-        let providers = self
+        let mut providers_clone: Vec<DbusSearchProvider> = self
             .search_providers
-            .get_or_init(|| dbus::discover_providers(&self.config.blacklist.borrow()));
+            .borrow_mut()
+            .get_or_insert_with(|| dbus::discover_providers(&self.config.blacklist.borrow()))
+            .clone();
 
-        if providers.is_empty() {
+        if providers_clone.is_empty() {
             return;
         }
 
         self.state.set_active_mode(ActiveMode::None);
         self.bump_task_gen();
-        let providers_clone: Vec<DbusSearchProvider> = providers.clone();
+        let settings = self.config.provider_settings.borrow().clone();
+        // Stable, so unlisted providers keep their discovery order
+        providers_clone.sort_by_key(|provider| settings.priority(&provider.desktop_id));
         let max = self.config.max_results.get();
         let model_clone = self.clone();
         // Use shorter debounce for search providers for more responsive feel
         self.schedule_provider_search_with_delay(PROVIDER_SEARCH_DEBOUNCE_MS, move || {
-            model_clone.run_provider_search(providers_clone, query, max, clear_store);
+            model_clone.run_provider_search(providers_clone, query, max, settings);
         });
     }
 
@@ -499,62 +766,671 @@ impl AppListModel {
     }
 
     /// Run a search query through GNOME Shell search providers
+    ///
+    /// Providers stream result batches from a worker thread; each batch is
+    /// appended once every provider before it in `providers` has answered,
+    /// until a newer search bumps the generation. Each provider shows at most
+    /// its `[search.providers] max_results`, or `max`.
+    /// Stale rows from the previous query are replaced by the first batch, or
+    /// removed once every provider has answered with nothing.
+    ///
+    /// Each answer of the current generation replaces the provider's kept
+    /// result set, so a query that extends this one is refined from it.
     fn run_provider_search(
         &self,
         providers: Vec<DbusSearchProvider>,
         query: String,
         max: usize,
-        clear_store: bool,
+        settings: ProviderSettings,
     ) {
         let generation = self.state.task_gen();
-        let model_clone = self.clone();
         let terms: Vec<String> = query.split_whitespace().map(String::from).collect();
+        let previous = self.provider_result_sets.borrow().clone();
 
-        // Set up a short timeout to clear old results and show "searching" state
-        let clear_timeout = Rc::new(RefCell::new(None::<glib::SourceId>));
-        if clear_store {
-            let clear_model = self.clone();
-            let clear_gen = generation;
-            let clear_timeout_clone = clear_timeout.clone();
-            let timeout_id = glib::timeout_add_local(
-                Duration::from_millis(PROVIDER_CLEAR_TIMEOUT_MS),
-                move || {
-                    if clear_model.state.task_gen() == clear_gen {
-                        clear_model.store.remove_all();
-                        clear_model
-                            .selection
-                            .set_selected(gtk4::INVALID_LIST_POSITION);
+        let state = self.state.clone();
+        let model = self.clone();
+        let mut rows = ProviderRows::default();
+        let mut batches =
+            ProviderBatches::new(providers.iter().map(|provider| provider.bus_name.clone()));
+        spawn_worker_with_updates(
+            move |tx| {
+                let max_results =
+                    |provider: &DbusSearchProvider| settings.max_results(&provider.desktop_id, max);
+                dbus::run_search_streaming(&providers, &query, &previous, max_results, tx.clone());
+                let _ = tx.send(ProviderUpdate::Finished);
+            },
+            move || state.task_gen() == generation,
+            move |update: ProviderUpdate| {
+                let (action, results) = match update {
+                    ProviderUpdate::Answer(answer) => {
+                        let results = batches.on_answer(&answer.bus_name, answer.results);
+                        model
+                            .provider_result_sets
+                            .borrow_mut()
+                            .insert(answer.bus_name, answer.result_set);
+                        if results.is_empty() {
+                            return;
+                        }
+                        (rows.on_batch(), results)
                     }
-                    *clear_timeout_clone.borrow_mut() = None;
-                    glib::ControlFlow::Break
-                },
-            );
-            *clear_timeout.borrow_mut() = Some(timeout_id);
-        }
+                    ProviderUpdate::Finished => {
+                        let results = batches.on_finished();
+                        if results.is_empty() {
+                            (rows.on_finished(), results)
+                        } else {
+                            (rows.on_batch(), results)
+                        }
+                    }
+                };
+                if matches!(action, RowsAction::ReplaceStale | RowsAction::ClearStale) {
+                    model.remove_stale_rows();
+                }
+                if !results.is_empty() {
+                    let items: Vec<glib::Object> = model
+                        .boost_provider_results(results)
+                        .into_iter()
+                        .map(|r| search_result_item(r, &terms))
+                        .collect();
+                    model.results.extend(&items);
+                }
+                model.results.select_first_if_unselected();
+            },
+        );
+    }
+
+    /// Show the bookmarks matching `query` (`:b` mode)
+    ///
+    /// The first call starts loading the bookmarks in the background and
+    /// shows nothing; once loaded, the current query is run again if it is
+    /// still a `:b` query.
+    pub(crate) fn show_bookmarks(&self, query: &str) {
+        self.bump_task_gen();
+        let cache = self.bookmarks.borrow();
+        let BookmarkCache::Loaded(bookmarks) = &*cache else {
+            let unloaded = matches!(*cache, BookmarkCache::Unloaded);
+            drop(cache);
+            self.results.remove_all();
+            if unloaded {
+                self.load_bookmarks();
+            }
+            return;
+        };
+        let matcher = self.config.matcher.borrow();
+        let items: Vec<glib::Object> =
+            match_bookmarks(&*matcher, query, bookmarks, self.config.max_results.get())
+                .into_iter()
+                .map(|b| BookmarkItem::new(b).upcast())
+                .collect();
+        drop(matcher);
+        drop(cache);
+
+        self.results.replace_all(&items);
+        self.results.set_selected(if items.is_empty() {
+            gtk4::INVALID_LIST_POSITION
+        } else {
+            0
+        });
+    }
+
+    /// Show the projects matching `query` (`:proj` mode)
+    ///
+    /// Loaded like bookmarks: the first call starts reading the IDE histories
+    /// and `[projects] roots` in the background and shows nothing.
+    pub(crate) fn show_projects(&self, query: &str) {
+        self.bump_task_gen();
+        let cache = self.projects.borrow();
+        let ProjectCache::Loaded(projects) = &*cache else {
+            let unloaded = matches!(*cache, ProjectCache::Unloaded);
+            drop(cache);
+            self.results.remove_all();
+            if unloaded {
+                self.load_projects();
+            }
+            return;
+        };
+        let matcher = self.config.matcher.borrow();
+        let items: Vec<glib::Object> =
+            match_projects(&*matcher, query, projects, self.config.max_results.get())
+                .into_iter()
+                .map(|p| ProjectItem::new(p).upcast())
+                .collect();
+        drop(matcher);
+        drop(cache);
+
+        self.results.replace_all(&items);
+        self.results.set_selected(if items.is_empty() {
+            gtk4::INVALID_LIST_POSITION
+        } else {
+            0
+        });
+    }
+
+    /// Show the manual pages matching `query` (`:man` mode)
+    ///
+    /// Loaded like projects: the first call starts listing the pages in the
+    /// background and shows nothing.
+    pub(crate) fn show_man_pages(&self, query: &str) {
+        self.bump_task_gen();
+        let cache = self.man_pages.borrow();
+        let ManPageCache::Loaded(pages) = &*cache else {
+            let unloaded = matches!(*cache, ManPageCache::Unloaded);
+            drop(cache);
+            self.results.remove_all();
+            if unloaded {
+                self.load_man_pages();
+            }
+            return;
+        };
+        let matcher = self.config.matcher.borrow();
+        let items: Vec<glib::Object> =
+            match_man_pages(&*matcher, query, pages, self.config.max_results.get())
+                .into_iter()
+                .map(|p| ManPageItem::new(p).upcast())
+                .collect();
+        drop(matcher);
+        drop(cache);
+
+        self.results.replace_all(&items);
+        self.results.set_selected(if items.is_empty() {
+            gtk4::INVALID_LIST_POSITION
+        } else {
+            0
+        });
+    }
+
+    /// Show the SSH hosts matching `query` (`:ssh` mode)
+    ///
+    /// The SSH config and `known_hosts` are read for every query, so edits
+    /// show up right away.
+    pub(crate) fn show_ssh_hosts(&self, query: &str) {
+        self.bump_task_gen();
+        let hosts = load_ssh_hosts(self.config.ssh_known_hosts.get());
+        let matcher = self.config.matcher.borrow();
+        let items: Vec<glib::Object> =
+            match_ssh_hosts(&*matcher, query, &hosts, self.config.max_results.get())
+                .into_iter()
+                .map(|h| SshHostItem::new(h).upcast())
+                .collect();
+        drop(matcher);
+
+        self.results.replace_all(&items);
+        self.results.set_selected(if items.is_empty() {
+            gtk4::INVALID_LIST_POSITION
+        } else {
+            0
+        });
+    }
+
+    /// Show the recently used documents matching `query` (`:r` mode)
+    ///
+    /// The rows are file paths, like those of `:f`, so they get the same
+    /// icons, context menu and activation.
+    pub(crate) fn show_recent_documents(&self, query: &str) {
+        self.bump_task_gen();
+        let documents = load_recent_documents();
+        let matcher = self.config.matcher.borrow();
+        let items: Vec<glib::Object> =
+            match_recent_documents(&*matcher, query, &documents, self.config.max_results.get())
+                .into_iter()
+                .map(|doc| CommandItem::new(CommandRow::file(doc.path.clone())).upcast())
+                .collect();
+        drop(matcher);
+
+        self.results.replace_all(&items);
+        self.results.set_selected(if items.is_empty() {
+            gtk4::INVALID_LIST_POSITION
+        } else {
+            0
+        });
+    }
+
+    /// Show the snippets whose name matches `query` (`:snip` mode)
+    ///
+    /// Snippet files are read again for every query, like the SSH config.
+    pub(crate) fn show_snippets(&self, query: &str) {
+        self.bump_task_gen();
+        let snippets = load_snippets(&self.config.snippets.borrow());
+        let matcher = self.config.matcher.borrow();
+        let items: Vec<glib::Object> =
+            match_snippets(&*matcher, query, &snippets, self.config.max_results.get())
+                .into_iter()
+                .map(|s| SnippetItem::new(s).upcast())
+                .collect();
+        drop(matcher);
+
+        self.results.replace_all(&items);
+        self.results.set_selected(if items.is_empty() {
+            gtk4::INVALID_LIST_POSITION
+        } else {
+            0
+        });
+    }
+
+    /// Show the password store entries matching `query` (`:pw` mode)
+    ///
+    /// The store is walked again for every query, like the snippets folder.
+    pub(crate) fn show_pass_entries(&self, query: &str) {
+        self.bump_task_gen();
+        let entries = load_pass_entries(&password_store_dir());
+        let matcher = self.config.matcher.borrow();
+        let items: Vec<glib::Object> =
+            match_pass_entries(&*matcher, query, &entries, self.config.max_results.get())
+                .into_iter()
+                .map(|e| PassEntryItem::new(e).upcast())
+                .collect();
+        drop(matcher);
+
+        self.results.replace_all(&items);
+        self.results.set_selected(if items.is_empty() {
+            gtk4::INVALID_LIST_POSITION
+        } else {
+            0
+        });
+    }
+
+    /// Show the clipboard history entries matching `query` (`:c` mode)
+    pub(crate) fn show_clipboard(&self, query: &str) {
+        self.bump_task_gen();
+        let items: Vec<glib::Object> = self
+            .config
+            .clipboard
+            .borrow()
+            .matches(
+                &*self.config.matcher.borrow(),
+                query,
+                self.config.max_results.get(),
+            )
+            .into_iter()
+            .map(|entry| ClipboardItem::new(entry).upcast())
+            .collect();
 
-        // Channel for streaming results from background thread
-        let (tx, rx) = std::sync::mpsc::channel::<Vec<dbus::SearchResult>>();
-        std::thread::spawn(move || {
-            dbus::run_search_streaming(&providers, &query, max, tx);
+        self.results.replace_all(&items);
+        self.results.set_selected(if items.is_empty() {
+            gtk4::INVALID_LIST_POSITION
+        } else {
+            0
         });
+    }
 
-        let poller = ProviderSearchPoller {
-            rx,
-            model: model_clone,
-            generation,
-            terms,
-            clear_timeout,
-            first_batch: Rc::new(Cell::new(false)),
-            clear_store,
+    /// Show the emoji matching `query` (`:e` mode)
+    ///
+    /// An empty query lists the recently picked emoji first, read from the
+    /// usage file unless `usage_stats` is off.
+    pub(crate) fn show_emoji(&self, query: &str) {
+        self.bump_task_gen();
+        let recent = if query.trim().is_empty() && self.config.usage_stats.get() {
+            crate::emoji::load_emoji_usage().recent
+        } else {
+            Vec::new()
         };
-        glib::idle_add_local_once(move || poller.poll());
+        let items: Vec<glib::Object> = crate::emoji::match_emoji(
+            &*self.config.matcher.borrow(),
+            query,
+            &recent,
+            self.config.max_results.get(),
+        )
+        .into_iter()
+        .map(|emoji| EmojiItem::new(emoji).upcast())
+        .collect();
+
+        self.results.replace_all(&items);
+        self.results.set_selected(if items.is_empty() {
+            gtk4::INVALID_LIST_POSITION
+        } else {
+            0
+        });
     }
+
+    /// Show the open windows matching `query` (`:w` mode)
+    ///
+    /// The windows are listed again for every query, on a worker thread, so
+    /// the list is never out of date; the previous rows stay until it
+    /// arrives.
+    pub(crate) fn show_windows(&self, backend: &'static dyn WindowBackend, query: &str) {
+        let generation = self.bump_task_gen();
+        let state = self.state.clone();
+        let model = self.clone();
+        let query = query.to_string();
+        spawn_worker_with_updates(
+            move |tx| {
+                let _ = tx.send(backend.list_windows());
+            },
+            move || state.task_gen() == generation,
+            move |windows: Option<Vec<OpenWindow>>| {
+                let Some(windows) = windows else {
+                    crate::command_handler::CommandHandler::new(model.clone())
+                        .show_error(format!("Could not list the windows of {}", backend.name()));
+                    return;
+                };
+                let items: Vec<glib::Object> = match_windows(
+                    &*model.config.matcher.borrow(),
+                    &query,
+                    &windows,
+                    model.config.max_results.get(),
+                )
+                .into_iter()
+                .map(|window| WindowItem::new(window).upcast())
+                .collect();
+
+                model.results.replace_all(&items);
+                model.results.set_selected(if items.is_empty() {
+                    gtk4::INVALID_LIST_POSITION
+                } else {
+                    0
+                });
+            },
+        );
+    }
+
+    /// Show the processes matching `query` (`:kill` mode)
+    ///
+    /// `/proc` is scanned again for every query, on a worker thread, so PIDs
+    /// and memory are current; the previous rows stay until the scan is done.
+    pub(crate) fn show_processes(&self, query: &str) {
+        let generation = self.bump_task_gen();
+        let state = self.state.clone();
+        let model = self.clone();
+        let query = query.to_string();
+        spawn_worker_with_updates(
+            move |tx| {
+                let _ = tx.send(processes::list_processes());
+            },
+            move || state.task_gen() == generation,
+            move |processes: Vec<Process>| {
+                let items: Vec<glib::Object> = processes::match_processes(
+                    &*model.config.matcher.borrow(),
+                    &query,
+                    &processes,
+                    model.config.max_results.get(),
+                )
+                .into_iter()
+                .map(|process| ProcessItem::new(process).upcast())
+                .collect();
+
+                model.results.replace_all(&items);
+                model.results.set_selected(if items.is_empty() {
+                    gtk4::INVALID_LIST_POSITION
+                } else {
+                    0
+                });
+            },
+        );
+    }
+
+    /// Show the systemd units matching `query` (`:unit` mode)
+    ///
+    /// The units are listed again over D-Bus for every query, on a worker
+    /// thread, so their states are current; the previous rows stay until
+    /// the managers answer.
+    pub(crate) fn show_units(&self, query: &str) {
+        let generation = self.bump_task_gen();
+        let state = self.state.clone();
+        let model = self.clone();
+        let query = query.to_string();
+        let user = self.config.units_user.get();
+        spawn_worker_with_updates(
+            move |tx| {
+                let _ = tx.send(get_tokio_runtime().block_on(units::list_units(user)));
+            },
+            move || state.task_gen() == generation,
+            move |units: Vec<Unit>| {
+                let items: Vec<glib::Object> = units::match_units(
+                    &*model.config.matcher.borrow(),
+                    &query,
+                    &units,
+                    model.config.max_results.get(),
+                )
+                .into_iter()
+                .map(|unit| UnitItem::new(unit).upcast())
+                .collect();
+
+                model.results.replace_all(&items);
+                model.results.set_selected(if items.is_empty() {
+                    gtk4::INVALID_LIST_POSITION
+                } else {
+                    0
+                });
+            },
+        );
+    }
+
+    /// Add a copied text to the clipboard history and save it
+    ///
+    /// Does nothing while clipboard capture is disabled. An open `:c` list
+    /// is refreshed.
+    pub fn record_clipboard(&self, text: &str) {
+        if !self.config.clipboard_history.get() {
+            return;
+        }
+        let changed = self.config.clipboard.borrow_mut().push(text, now_secs());
+        if !changed {
+            return;
+        }
+        self.config.clipboard.borrow().save();
+        if self.active_mode() == ActiveMode::Clipboard {
+            self.populate(&self.state.current_query());
+        }
+    }
+
+    /// Number of desktop action rows shown below the row at `pos`
+    #[must_use]
+    pub fn expanded_actions(&self, pos: u32) -> u32 {
+        (pos + 1..self.results.n_items())
+            .take_while(|&i| {
+                self.results
+                    .item(i)
+                    .is_some_and(|obj| obj.is::<AppActionItem>())
+            })
+            .fold(0, |n, _| n + 1)
+    }
+
+    /// Show or hide the desktop actions of the application at `pos`
+    ///
+    /// The actions are inserted as rows right below the application.
+    /// Returns `false` if the row is not an application with actions or is
+    /// already in the requested state.
+    pub fn set_actions_expanded(&self, pos: u32, expand: bool) -> bool {
+        let Some(app) = self
+            .results
+            .item(pos)
+            .and_then(|obj| obj.downcast::<AppItem>().ok())
+        else {
+            return false;
+        };
+        let actions = app.actions();
+        let expanded = self.expanded_actions(pos);
+        if actions.is_empty() || expand == (expanded > 0) {
+            return false;
+        }
+        if expand {
+            let rows: Vec<glib::Object> = actions
+                .iter()
+                .map(|action| AppActionItem::new(&app, action).upcast())
+                .collect();
+            self.results.splice(pos + 1, 0, &rows);
+        } else {
+            self.results.splice(pos + 1, expanded, &[]);
+        }
+        true
+    }
+
+    /// Read the browser bookmarks on a worker thread and cache them
+    fn load_bookmarks(&self) {
+        *self.bookmarks.borrow_mut() = BookmarkCache::Loading;
+        let model = self.clone();
+        spawn_worker_with_updates(
+            move |tx| {
+                let _ = tx.send(load_all_bookmarks());
+            },
+            // The cache is kept even if the user left `:b` in the meantime
+            || true,
+            move |bookmarks| {
+                *model.bookmarks.borrow_mut() = BookmarkCache::Loaded(bookmarks);
+                if model.active_mode() == ActiveMode::Bookmarks {
+                    model.populate(&model.state.current_query());
+                }
+            },
+        );
+    }
+
+    /// Read the recent projects on a worker thread and cache them
+    fn load_projects(&self) {
+        *self.projects.borrow_mut() = ProjectCache::Loading;
+        let roots = self.config.project_roots.borrow().clone();
+        let model = self.clone();
+        spawn_worker_with_updates(
+            move |tx| {
+                let _ = tx.send(load_all_projects(&roots));
+            },
+            // The cache is kept even if the user left `:proj` in the meantime
+            || true,
+            move |projects| {
+                *model.projects.borrow_mut() = ProjectCache::Loaded(projects);
+                if model.active_mode() == ActiveMode::Projects {
+                    model.populate(&model.state.current_query());
+                }
+            },
+        );
+    }
+
+    /// List the manual pages on a worker thread and cache them
+    fn load_man_pages(&self) {
+        *self.man_pages.borrow_mut() = ManPageCache::Loading;
+        let model = self.clone();
+        spawn_worker_with_updates(
+            move |tx| {
+                let _ = tx.send(load_man_pages());
+            },
+            // The cache is kept even if the user left `:man` in the meantime
+            || true,
+            move |pages| {
+                *model.man_pages.borrow_mut() = ManPageCache::Loaded(pages);
+                if model.active_mode() == ActiveMode::Manual {
+                    model.populate(&model.state.current_query());
+                }
+            },
+        );
+    }
+
+    /// Remove provider rows left over from a previous query
+    fn remove_stale_rows(&self) {
+        self.results.retain(|obj| {
+            !obj.downcast_ref::<SearchResultItem>()
+                .is_some_and(SearchResultItem::is_stale)
+        });
+    }
+}
+
+/// Message from the provider search worker
+enum ProviderUpdate {
+    /// Answer of one provider, possibly without results
+    Answer(dbus::ProviderAnswer),
+    /// Every provider has answered
+    Finished,
+}
+
+impl From<dbus::ProviderAnswer> for ProviderUpdate {
+    fn from(answer: dbus::ProviderAnswer) -> Self {
+        Self::Answer(answer)
+    }
+}
+
+/// Desktop IDs of the app rows among `rows`
+fn app_ids(rows: &[glib::Object]) -> Vec<String> {
+    rows.iter()
+        .filter_map(|obj| obj.downcast_ref::<AppItem>().map(AppItem::desktop_id))
+        .collect()
+}
+
+/// Convert a provider result into a list item
+fn search_result_item(r: dbus::SearchResult, terms: &[String]) -> glib::Object {
+    let (icon_themed, icon_file) = match r.icon {
+        Some(dbus::IconData::Themed(n)) => (n, String::new()),
+        Some(dbus::IconData::File(p)) => (String::new(), p),
+        None => (String::new(), String::new()),
+    };
+    SearchResultItem::new(
+        r.id,
+        r.name,
+        r.description,
+        icon_themed,
+        icon_file,
+        r.app_icon,
+        r.bus_name,
+        r.object_path,
+        r.desktop_id,
+        terms.to_vec(),
+        r.clipboard_text,
+    )
+    .upcast::<glib::Object>()
 }
 
 #[cfg(test)]
 mod tests {
+    use super::AppListModel;
     use crate::utils::is_calculator_result;
 
+    #[test]
+    fn test_is_debounced_query() {
+        assert!(AppListModel::is_debounced_query("fire"));
+        assert!(AppListModel::is_debounced_query("a:b"));
+        assert!(!AppListModel::is_debounced_query(""));
+        assert!(!AppListModel::is_debounced_query(":ob"));
+        assert!(!AppListModel::is_debounced_query(":"));
+    }
+
+    #[test]
+    fn test_terminal_command() {
+        assert_eq!(AppListModel::terminal_command("!htop"), Some("htop"));
+        assert_eq!(
+            AppListModel::terminal_command("! journalctl -f"),
+            Some("journalctl -f")
+        );
+        assert_eq!(
+            AppListModel::terminal_command("  !ls | grep %f "),
+            Some("ls | grep %f ")
+        );
+        assert_eq!(AppListModel::terminal_command("!"), Some(""));
+        assert_eq!(AppListModel::terminal_command("  ! "), Some(""));
+        assert_eq!(AppListModel::terminal_command("htop!"), None);
+        assert_eq!(AppListModel::terminal_command(""), None);
+    }
+
+    #[test]
+    fn test_url_from_query() {
+        let url = AppListModel::url_from_query;
+        assert_eq!(url("https://crates.io"), Some("https://crates.io".into()));
+        assert_eq!(
+            url("  ftp://example.org/pub "),
+            Some("ftp://example.org/pub".into())
+        );
+        assert_eq!(
+            url("github.com/Nihmar/grunner"),
+            Some("https://github.com/Nihmar/grunner".into())
+        );
+        assert_eq!(url("docs.rs?q=gtk4"), Some("https://docs.rs?q=gtk4".into()));
+        assert_eq!(
+            url("example.org:8080"),
+            Some("https://example.org:8080".into())
+        );
+    }
+
+    #[test]
+    fn test_url_from_query_rejects_plain_text() {
+        let url = AppListModel::url_from_query;
+        assert_eq!(url("git"), None);
+        assert_eq!(url(""), None);
+        assert_eq!(url("firefox web"), None);
+        assert_eq!(url("1.5*3"), None);
+        assert_eq!(url("3.14"), None);
+        assert_eq!(url("example."), None);
+        assert_eq!(url(".com"), None);
+        assert_eq!(url("a..com"), None);
+        assert_eq!(url("user@example.com"), None);
+        assert_eq!(url("https://"), None);
+        assert_eq!(url("1http://x"), None);
+    }
+
     #[test]
     fn test_is_calculator_result() {
         assert!(is_calculator_result("2 + 2 = 4"));
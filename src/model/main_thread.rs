@@ -0,0 +1,155 @@
+use crate::metrics;
+use gtk4::prelude::*;
+use gtk4::{SingleSelection, gio};
+use std::rc::Rc;
+
+/// The result list's store and selection, mutable only on the GTK main thread
+///
+/// GTK widgets bound to the store react synchronously to every change, so
+/// mutating it from a worker thread corrupts the UI. Every mutation below
+/// asserts that the caller owns the default main context; background work
+/// must hand its results back through
+/// [`spawn_worker_with_updates`](crate::model::worker::spawn_worker_with_updates).
+#[derive(Clone)]
+pub struct MainThreadModel {
+    store: gio::ListStore,
+    selection: SingleSelection,
+}
+
+impl MainThreadModel {
+    #[must_use]
+    pub fn new() -> Self {
+        let store = gio::ListStore::new::<glib::Object>();
+        let selection = SingleSelection::new(Some(store.clone()));
+        selection.set_autoselect(true);
+        selection.set_can_unselect(false);
+        Self { store, selection }
+    }
+
+    /// Panic unless called from the thread that runs the GTK main loop
+    fn assert_main_thread() {
+        assert!(
+            glib::MainContext::default().is_owner(),
+            "result list mutated outside the GTK main thread"
+        );
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    /// Selection model to hand to the list view
+    #[must_use]
+    pub fn selection_model(&self) -> &SingleSelection {
+        &self.selection
+    }
+
+    #[must_use]
+    pub fn n_items(&self) -> u32 {
+        self.store.n_items()
+    }
+
+    #[must_use]
+    pub fn item(&self, pos: u32) -> Option<glib::Object> {
+        self.store.item(pos)
+    }
+
+    /// Position of the selected item, or `gtk4::INVALID_LIST_POSITION`
+    #[must_use]
+    pub fn selected(&self) -> u32 {
+        self.selection.selected()
+    }
+
+    /// The selected item, if any
+    #[must_use]
+    pub fn selected_item(&self) -> Option<glib::Object> {
+        self.store.item(self.selection.selected())
+    }
+
+    pub fn connect_items_changed<F>(&self, f: F) -> glib::SignalHandlerId
+    where
+        F: Fn(&gio::ListStore, u32, u32, u32) + 'static,
+    {
+        self.store.connect_items_changed(f)
+    }
+
+    /// Call `f` whenever the results or the selected position change,
+    /// including each batch a streaming search adds
+    pub fn connect_results_changed<F>(&self, f: F)
+    where
+        F: Fn() + 'static,
+    {
+        let f = Rc::new(f);
+        let on_items = f.clone();
+        self.store
+            .connect_items_changed(move |_, _, _, _| on_items());
+        self.selection.connect_selected_notify(move |_| f());
+    }
+
+    // ── Mutations ─────────────────────────────────────────────────────────────
+
+    pub fn append(&self, obj: &impl IsA<glib::Object>) {
+        Self::assert_main_thread();
+        self.store.append(obj);
+    }
+
+    /// Append several items with a single `items-changed` signal
+    pub fn extend(&self, items: &[glib::Object]) {
+        Self::assert_main_thread();
+        self.store.splice(self.store.n_items(), 0, items);
+    }
+
+    /// Replace the whole list with a single `items-changed` signal
+    pub fn replace_all(&self, items: &[glib::Object]) {
+        self.splice(0, self.store.n_items(), items);
+    }
+
+    /// Remove `n_removals` items at `pos` and insert `items` in their place
+    ///
+    /// The list view updates synchronously, so the time taken is recorded
+    /// as the splice timing of the debug overlay.
+    pub fn splice(&self, pos: u32, n_removals: u32, items: &[glib::Object]) {
+        Self::assert_main_thread();
+        let started = metrics::start();
+        self.store.splice(pos, n_removals, items);
+        metrics::record_elapsed(started, |m, elapsed| m.splice = Some(elapsed));
+    }
+
+    /// Rebind the row at `pos` after its item changed in place
+    pub fn rebind(&self, pos: u32) {
+        Self::assert_main_thread();
+        self.store.items_changed(pos, 1, 1);
+    }
+
+    pub fn remove(&self, pos: u32) {
+        Self::assert_main_thread();
+        self.store.remove(pos);
+    }
+
+    /// Remove every item for which `keep` returns `false`
+    pub fn retain(&self, keep: impl FnMut(&glib::Object) -> bool) {
+        Self::assert_main_thread();
+        self.store.retain(keep);
+    }
+
+    pub fn remove_all(&self) {
+        Self::assert_main_thread();
+        self.store.remove_all();
+    }
+
+    pub fn set_selected(&self, pos: u32) {
+        Self::assert_main_thread();
+        self.selection.set_selected(pos);
+    }
+
+    /// Select the first item if the list is non-empty and nothing is selected
+    pub fn select_first_if_unselected(&self) {
+        if self.n_items() > 0 && self.selected() == gtk4::INVALID_LIST_POSITION {
+            self.set_selected(0);
+        }
+    }
+}
+
+impl Default for MainThreadModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
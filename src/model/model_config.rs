@@ -1,8 +1,25 @@
-use crate::core::config::{CommandConfig, ObsidianConfig};
+use crate::app_origin::AppOrigins;
+use crate::clipboard_history::{
+    ClipboardHistory, delete_clipboard_history, load_clipboard_history,
+};
+use crate::core::config::{
+    AngleUnit, CommandConfig, DEFAULT_LATENCY_BUDGET_MS, FileResultsLayout, ObsidianConfig, Pin,
+    ProjectFallback, ProviderSettings, SnippetConfig, WebSearchConfig,
+};
+use crate::core::obsidian::ObsidianContext;
+use crate::history::{LaunchHistory, load_history};
 use crate::launcher::DesktopApp;
+use crate::metrics::{LatencyController, SearchCuts, reduced_max_results};
+use crate::providers::matcher::{SharedMatcher, build_matcher, shared_matcher};
 use crate::providers::{AppProvider, CalculatorProvider, SearchProvider};
+use crate::query_history::{
+    DEFAULT_QUERY_HISTORY, QueryHistory, delete_query_history, load_query_history,
+};
+use log::info;
 use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
 use std::rc::Rc;
+use std::time::Duration;
 
 /// Holds configuration settings for the search model.
 ///
@@ -10,10 +27,75 @@ use std::rc::Rc;
 #[derive(Clone)]
 pub struct ModelConfig {
     pub max_results: Cell<usize>,
-    pub obsidian_cfg: Option<ObsidianConfig>,
+    /// Resolved Obsidian settings, rebuilt when the configuration is
+    /// reloaded; shared with the list factory
+    pub obsidian: Rc<RefCell<Option<Rc<ObsidianContext>>>>,
     pub commands: Rc<RefCell<Vec<CommandConfig>>>,
     pub blacklist: Rc<RefCell<Vec<String>>>,
+    /// `[search.providers]`: order and result caps of search providers
+    pub provider_settings: Rc<RefCell<ProviderSettings>>,
+    /// `[search] app_blacklist`: desktop IDs or globs of hidden apps
+    pub app_blacklist: Rc<RefCell<Vec<String>>>,
+    /// Web searches by keyword, for `:<keyword> <terms>`
+    pub web_searches: Rc<RefCell<BTreeMap<String, WebSearchConfig>>>,
+    /// Snippets by name, for `:snip`
+    pub snippets: Rc<RefCell<BTreeMap<String, SnippetConfig>>>,
     pub disable_modes: Cell<bool>,
+    pub auto_launch_threshold: Cell<u8>,
+    pub usage_stats: Cell<bool>,
+    /// Usage history loaded at startup, used to rank apps by frecency
+    pub history: Rc<RefCell<LaunchHistory>>,
+    /// Texts copied while the window is open, for `:c`
+    pub clipboard: Rc<RefCell<ClipboardHistory>>,
+    /// Whether copied texts are captured
+    pub clipboard_history: Rc<Cell<bool>>,
+    /// Queries results were activated from, for Alt+Up and Alt+Down
+    pub queries: Rc<RefCell<QueryHistory>>,
+    /// Number of queries kept, 0 to keep none
+    pub query_history: Cell<usize>,
+    /// Layout of file and grep rows, shared with the list factory
+    pub file_results_layout: Rc<Cell<FileResultsLayout>>,
+    /// Whether rows end in a badge naming their source, shared with the
+    /// list factory
+    pub show_kind_badges: Rc<Cell<bool>>,
+    /// Whether app descriptions end in where the app came from, shared with
+    /// the list factory
+    pub show_app_origin: Rc<Cell<bool>>,
+    /// Origins of the desktop files looked up so far
+    pub app_origins: AppOrigins,
+    /// Whether `!command` runs the command in a terminal
+    pub terminal_prefix: Cell<bool>,
+    /// Whether running apps are marked and focused instead of launched
+    pub prefer_focus_running: Cell<bool>,
+    /// Whether copying a calculator result or clipboard entry closes the
+    /// window
+    pub close_on_copy: Cell<bool>,
+    /// Whether Escape clears a non-empty query before closing the window
+    pub escape_clears_first: Cell<bool>,
+    /// Desktop IDs of the pinned apps, listed first for the empty query;
+    /// shared with the pinned strip
+    pub pinned_apps: Rc<RefCell<Vec<String>>>,
+    /// Angle unit of the calculator, shared with its provider
+    pub angle_unit: Rc<Cell<AngleUnit>>,
+    /// `[projects] roots`: folders searched for Git repositories by `:proj`
+    pub project_roots: Rc<RefCell<Vec<String>>>,
+    /// How `:proj` opens a project no IDE has opened
+    pub project_open_with: Cell<ProjectFallback>,
+    /// Whether `:ssh` also lists the hosts of `known_hosts`
+    pub ssh_known_hosts: Cell<bool>,
+    /// Whether `:unit` also lists the units of the user manager
+    pub units_user: Cell<bool>,
+    /// Cuts search work while app searches run over `[performance]
+    /// latency_budget_ms`
+    pub latency: Rc<RefCell<LatencyController>>,
+    /// `[performance]` pins of the cuts of [`Self::latency`]
+    pub match_descriptions: Cell<Pin>,
+    pub full_results: Cell<Pin>,
+    pub search_providers: Cell<Pin>,
+    /// Search work currently cut, from the latency level and the pins
+    pub search_cuts: Cell<SearchCuts>,
+    /// Fuzzy matcher shared by app search, `:b` and `:c`
+    pub matcher: SharedMatcher,
     pub providers: Rc<Vec<Box<dyn SearchProvider>>>,
 }
 
@@ -24,32 +106,191 @@ impl ModelConfig {
         blacklist: Vec<String>,
         commands: Vec<CommandConfig>,
         disable_modes: bool,
+        auto_launch_threshold: u8,
         all_apps: Rc<RefCell<Vec<DesktopApp>>>,
     ) -> Self {
+        let history = Rc::new(RefCell::new(load_history()));
+        let matcher = shared_matcher();
+        let angle_unit = Rc::new(Cell::new(AngleUnit::default()));
         let providers = Rc::new(vec![
-            Box::new(AppProvider::new(all_apps, max_results)) as Box<dyn SearchProvider>,
-            Box::new(CalculatorProvider::new()) as Box<dyn SearchProvider>,
+            Box::new(AppProvider::new(
+                all_apps,
+                history.clone(),
+                matcher.clone(),
+                max_results,
+            )) as Box<dyn SearchProvider>,
+            Box::new(CalculatorProvider::new(angle_unit.clone())) as Box<dyn SearchProvider>,
         ]);
 
         Self {
             max_results: Cell::new(max_results),
-            obsidian_cfg,
+            obsidian: Rc::new(RefCell::new(obsidian_cfg.map(obsidian_context))),
             commands: Rc::new(RefCell::new(commands)),
             blacklist: Rc::new(RefCell::new(blacklist)),
+            provider_settings: Rc::new(RefCell::new(ProviderSettings::default())),
+            app_blacklist: Rc::new(RefCell::new(Vec::new())),
+            web_searches: Rc::new(RefCell::new(BTreeMap::new())),
+            snippets: Rc::new(RefCell::new(BTreeMap::new())),
             disable_modes: Cell::new(disable_modes),
+            auto_launch_threshold: Cell::new(auto_launch_threshold),
+            usage_stats: Cell::new(true),
+            history,
+            clipboard: Rc::new(RefCell::new(load_clipboard_history())),
+            clipboard_history: Rc::new(Cell::new(true)),
+            queries: Rc::new(RefCell::new(load_query_history())),
+            query_history: Cell::new(DEFAULT_QUERY_HISTORY),
+            file_results_layout: Rc::new(Cell::new(FileResultsLayout::default())),
+            show_kind_badges: Rc::new(Cell::new(false)),
+            show_app_origin: Rc::new(Cell::new(false)),
+            app_origins: AppOrigins::default(),
+            terminal_prefix: Cell::new(false),
+            prefer_focus_running: Cell::new(false),
+            close_on_copy: Cell::new(true),
+            escape_clears_first: Cell::new(true),
+            pinned_apps: Rc::new(RefCell::new(Vec::new())),
+            angle_unit,
+            project_roots: Rc::new(RefCell::new(Vec::new())),
+            project_open_with: Cell::new(ProjectFallback::default()),
+            ssh_known_hosts: Cell::new(false),
+            units_user: Cell::new(false),
+            latency: Rc::new(RefCell::new(LatencyController::new(Duration::from_millis(
+                u64::from(DEFAULT_LATENCY_BUDGET_MS),
+            )))),
+            match_descriptions: Cell::new(Pin::default()),
+            full_results: Cell::new(Pin::default()),
+            search_providers: Cell::new(Pin::default()),
+            search_cuts: Cell::new(SearchCuts::default()),
+            matcher,
             providers,
         }
     }
 
+    /// Enable or disable usage recording
+    ///
+    /// Disabling it also stops ranking by the history already in memory.
+    pub fn set_usage_stats(&self, enabled: bool) {
+        self.usage_stats.set(enabled);
+        self.history.borrow_mut().apply_privacy(enabled);
+    }
+
+    /// Enable or disable clipboard capture
+    ///
+    /// Disabling it forgets the captured texts and deletes the saved file.
+    pub fn set_clipboard_history(&self, enabled: bool) {
+        self.clipboard_history.set(enabled);
+        if !enabled {
+            self.clipboard.borrow_mut().entries.clear();
+            delete_clipboard_history();
+        }
+    }
+
+    /// Set the number of queries kept
+    ///
+    /// Older queries are forgotten; 0 forgets them all and deletes the
+    /// saved file.
+    pub fn set_query_history(&self, length: usize) {
+        self.query_history.set(length);
+        let mut queries = self.queries.borrow_mut();
+        if length == 0 {
+            queries.queries.clear();
+            delete_query_history();
+        } else if queries.queries.len() > length {
+            queries.queries.truncate(length);
+            queries.save();
+        }
+    }
+
+    /// Record `query` as activated from, and save the history
+    pub fn record_query(&self, query: &str) {
+        let mut queries = self.queries.borrow_mut();
+        if queries.push(query, self.query_history.get()) {
+            queries.save();
+        }
+    }
+
+    /// Count an app search that took `elapsed`, cutting or restoring search
+    /// work if the latency level changes
+    pub fn record_search_latency(&self, elapsed: Duration) {
+        let changed = self.latency.borrow_mut().record(elapsed);
+        if let Some(level) = changed {
+            info!(
+                "Searches take {:.1} ms, search level is now {level:?}",
+                elapsed.as_secs_f64() * 1000.0
+            );
+            self.apply_search_cuts();
+        }
+    }
+
+    /// Apply the `[performance]` settings
+    ///
+    /// Also sets the result cap of the providers from `max_results`.
+    pub fn apply_performance(&self, config: &crate::core::config::Config) {
+        self.latency
+            .borrow_mut()
+            .set_budget(Duration::from_millis(u64::from(config.latency_budget_ms)));
+        self.match_descriptions.set(config.match_descriptions);
+        self.full_results.set(config.full_results);
+        self.search_providers.set(config.search_providers);
+        self.apply_search_cuts();
+    }
+
+    /// Cut the search work of the latency level, as the pins allow
+    fn apply_search_cuts(&self) {
+        let auto = self.latency.borrow().level().cuts();
+        let cuts = SearchCuts {
+            descriptions: self.match_descriptions.get().cut(auto.descriptions),
+            results: self.full_results.get().cut(auto.results),
+            providers: self.search_providers.get().cut(auto.providers),
+        };
+        self.search_cuts.set(cuts);
+
+        let max = self.max_results.get();
+        let max = if cuts.results {
+            reduced_max_results(max)
+        } else {
+            max
+        };
+        for provider in self.providers.iter() {
+            provider.set_max_results(max);
+            provider.set_match_descriptions(!cuts.descriptions);
+        }
+    }
+
     pub fn apply_config(&self, config: &crate::core::config::Config) {
         self.max_results.set(config.max_results);
         self.disable_modes.set(config.disable_modes);
+        self.auto_launch_threshold.set(config.auto_launch_threshold);
+        self.set_usage_stats(config.usage_stats);
+        self.set_clipboard_history(config.clipboard_history);
+        self.set_query_history(config.query_history);
+        self.file_results_layout.set(config.file_results_layout);
+        self.show_kind_badges.set(config.show_kind_badges);
+        self.show_app_origin.set(config.show_app_origin);
+        self.terminal_prefix.set(config.terminal_prefix);
+        self.prefer_focus_running.set(config.prefer_focus_running);
+        self.close_on_copy.set(config.close_on_copy);
+        self.escape_clears_first.set(config.escape_clears_first);
+        self.angle_unit.set(config.angle_unit);
+        self.project_open_with.set(config.project_open_with);
+        self.ssh_known_hosts.set(config.ssh_known_hosts);
+        self.units_user.set(config.units_user);
+        *self.matcher.borrow_mut() = build_matcher(config.fuzzy_case, config.fuzzy_element_limit);
 
-        for provider in self.providers.iter() {
-            provider.set_max_results(config.max_results);
-        }
+        self.apply_performance(config);
 
+        *self.obsidian.borrow_mut() = config.obsidian.clone().map(obsidian_context);
         (*self.blacklist.borrow_mut()).clone_from(&config.search_provider_blacklist);
+        (*self.provider_settings.borrow_mut()).clone_from(&config.provider_settings);
+        (*self.app_blacklist.borrow_mut()).clone_from(&config.app_blacklist);
         (*self.commands.borrow_mut()).clone_from(&config.commands);
+        (*self.web_searches.borrow_mut()).clone_from(&config.web_searches);
+        (*self.snippets.borrow_mut()).clone_from(&config.snippets);
+        (*self.pinned_apps.borrow_mut()).clone_from(&config.pinned_apps);
+        (*self.project_roots.borrow_mut()).clone_from(&config.project_roots);
     }
 }
+
+/// Build the shared context of the `[obsidian]` settings
+fn obsidian_context(cfg: ObsidianConfig) -> Rc<ObsidianContext> {
+    Rc::new(ObsidianContext::new(cfg))
+}
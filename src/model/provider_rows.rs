@@ -0,0 +1,223 @@
+//! Search provider rows across consecutive queries
+//!
+//! When the query changes, the provider results of the previous query are not
+//! cleared right away, which would flash an empty list before the new results
+//! arrive. They stay in the list marked stale (and drawn dimmed) until the
+//! first batch for the new query replaces them, or until every provider has
+//! answered without results, at which point they are removed.
+//!
+//! [`ProviderRows`] is the state machine behind this; the list model applies
+//! the [`RowsAction`] it returns for each provider event.
+//!
+//! Providers answer in any order, but their rows follow the priority of
+//! `[search.providers] order`. [`ProviderBatches`] holds an answer back until
+//! every provider ranked before it has answered.
+
+/// Lifecycle of the provider rows shown for the current query
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProviderRows {
+    /// Rows of the previous query are shown, marked stale
+    #[default]
+    Stale,
+    /// At least one batch for the current query has been shown
+    Fresh,
+    /// Providers finished without results and the stale rows were removed
+    Cleared,
+}
+
+/// Change to apply to the result list for a provider event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowsAction {
+    /// Remove the stale rows, then append the batch
+    ReplaceStale,
+    /// Append the batch after the rows already shown
+    Append,
+    /// Remove the stale rows
+    ClearStale,
+    /// Leave the list as it is
+    Keep,
+}
+
+impl ProviderRows {
+    /// A non-empty batch of results for the current query arrived
+    pub fn on_batch(&mut self) -> RowsAction {
+        let action = match self {
+            Self::Stale => RowsAction::ReplaceStale,
+            Self::Fresh | Self::Cleared => RowsAction::Append,
+        };
+        *self = Self::Fresh;
+        action
+    }
+
+    /// Every provider has answered
+    pub fn on_finished(&mut self) -> RowsAction {
+        match self {
+            Self::Stale => {
+                *self = Self::Cleared;
+                RowsAction::ClearStale
+            }
+            Self::Fresh | Self::Cleared => RowsAction::Keep,
+        }
+    }
+}
+
+/// Provider answers released in priority order
+#[derive(Debug)]
+pub struct ProviderBatches<T> {
+    /// Bus name of each provider in priority order, with its answer once
+    /// received and not yet released
+    slots: Vec<(String, Option<Vec<T>>)>,
+    /// Number of leading slots already released
+    released: usize,
+}
+
+impl<T> ProviderBatches<T> {
+    /// Wait for the providers with `bus_names`, highest priority first
+    pub fn new(bus_names: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            slots: bus_names.into_iter().map(|name| (name, None)).collect(),
+            released: 0,
+        }
+    }
+
+    /// The provider `bus_name` answered with `results`, possibly none
+    ///
+    /// Returns the results to append now, in priority order: this answer
+    /// and the held back ones after it, once no provider ranked before them
+    /// is still searching. An unknown provider's results are returned as
+    /// they are.
+    pub fn on_answer(&mut self, bus_name: &str, results: Vec<T>) -> Vec<T> {
+        let Some(slot) = self.slots[self.released..]
+            .iter_mut()
+            .find(|(name, answer)| name == bus_name && answer.is_none())
+        else {
+            return results;
+        };
+        slot.1 = Some(results);
+
+        let mut ready = Vec::new();
+        while let Some((_, answer)) = self.slots.get_mut(self.released) {
+            let Some(results) = answer.take() else {
+                break;
+            };
+            ready.extend(results);
+            self.released += 1;
+        }
+        ready
+    }
+
+    /// Every provider has answered or given up
+    ///
+    /// Returns the held back results, in priority order, skipping the
+    /// providers that never answered.
+    pub fn on_finished(&mut self) -> Vec<T> {
+        let rest = self.slots[self.released..]
+            .iter_mut()
+            .filter_map(|(_, answer)| answer.take())
+            .flatten()
+            .collect();
+        self.released = self.slots.len();
+        rest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Copy)]
+    enum Event {
+        Batch,
+        Finished,
+    }
+
+    /// Feed `events` to a new state machine, collecting the actions
+    fn script(events: &[Event]) -> (Vec<RowsAction>, ProviderRows) {
+        let mut rows = ProviderRows::default();
+        let actions = events
+            .iter()
+            .map(|event| match event {
+                Event::Batch => rows.on_batch(),
+                Event::Finished => rows.on_finished(),
+            })
+            .collect();
+        (actions, rows)
+    }
+
+    #[test]
+    fn test_first_batch_replaces_stale_rows() {
+        let (actions, rows) = script(&[Event::Batch, Event::Batch, Event::Finished]);
+        assert_eq!(
+            actions,
+            vec![
+                RowsAction::ReplaceStale,
+                RowsAction::Append,
+                RowsAction::Keep
+            ]
+        );
+        assert_eq!(rows, ProviderRows::Fresh);
+    }
+
+    #[test]
+    fn test_finishing_without_results_clears_stale_rows() {
+        let (actions, rows) = script(&[Event::Finished]);
+        assert_eq!(actions, vec![RowsAction::ClearStale]);
+        assert_eq!(rows, ProviderRows::Cleared);
+    }
+
+    #[test]
+    fn test_no_events_keeps_stale_rows() {
+        // A newer query took over before any provider answered; its own
+        // search decides what happens to the rows
+        let (actions, rows) = script(&[]);
+        assert!(actions.is_empty());
+        assert_eq!(rows, ProviderRows::Stale);
+    }
+
+    fn batches() -> ProviderBatches<&'static str> {
+        ProviderBatches::new(["nautilus", "calculator", "software"].map(String::from))
+    }
+
+    #[test]
+    fn test_answers_in_priority_order_are_released_at_once() {
+        let mut batches = batches();
+        assert_eq!(
+            batches.on_answer("nautilus", vec!["n1", "n2"]),
+            ["n1", "n2"]
+        );
+        assert_eq!(batches.on_answer("calculator", vec!["c1"]), ["c1"]);
+        assert_eq!(batches.on_answer("software", vec!["s1"]), ["s1"]);
+        assert!(batches.on_finished().is_empty());
+    }
+
+    #[test]
+    fn test_low_priority_answer_waits_for_higher_ones() {
+        let mut batches = batches();
+        assert!(batches.on_answer("software", vec!["s1"]).is_empty());
+        assert!(batches.on_answer("calculator", vec!["c1"]).is_empty());
+        // An empty answer still releases the ones after it
+        assert_eq!(batches.on_answer("nautilus", vec![]), ["c1", "s1"]);
+    }
+
+    #[test]
+    fn test_finished_releases_answers_behind_a_silent_provider() {
+        let mut batches = batches();
+        assert!(batches.on_answer("calculator", vec!["c1"]).is_empty());
+        assert!(batches.on_answer("software", vec!["s1"]).is_empty());
+        assert_eq!(batches.on_finished(), ["c1", "s1"]);
+        // A straggler after the end is shown as it comes
+        assert_eq!(batches.on_answer("nautilus", vec!["n1"]), ["n1"]);
+    }
+
+    #[test]
+    fn test_unknown_provider_is_not_held_back() {
+        let mut batches = batches();
+        assert_eq!(batches.on_answer("characters", vec!["x"]), ["x"]);
+    }
+
+    #[test]
+    fn test_late_batch_after_clear_appends() {
+        let (actions, _) = script(&[Event::Finished, Event::Batch]);
+        assert_eq!(actions, vec![RowsAction::ClearStale, RowsAction::Append]);
+    }
+}
@@ -0,0 +1,291 @@
+//! Background work with updates delivered on the GTK main thread
+//!
+//! Workers run on a plain thread and send their results through a channel;
+//! the main thread polls the channel from idle callbacks and hands each update
+//! to a callback, which is the only place allowed to touch the result list.
+//! A staleness check (usually a search generation) stops delivery as soon as
+//! the user has moved on, and dropping the receiver lets the worker notice
+//! through a failed send.
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+
+/// Order of overlapping runs of the same background job
+///
+/// Each run takes a number from [`start`](Self::start). A run stays current
+/// until a later run has delivered an update, so a slow older run can never
+/// overwrite newer results, while a later run that sends nothing (nothing
+/// changed) leaves the older run free to finish.
+#[derive(Debug, Clone, Default)]
+pub struct RunOrder {
+    started: Rc<Cell<u64>>,
+    applied: Rc<Cell<u64>>,
+}
+
+impl RunOrder {
+    /// Number a new run
+    #[must_use]
+    pub fn start(&self) -> u64 {
+        let run = self.started.get() + 1;
+        self.started.set(run);
+        run
+    }
+
+    /// Whether updates of `run` may still be applied
+    #[must_use]
+    pub fn is_current(&self, run: u64) -> bool {
+        run >= self.applied.get()
+    }
+
+    /// Record that an update of `run` was applied
+    pub fn applied(&self, run: u64) {
+        self.applied.set(self.applied.get().max(run));
+    }
+}
+
+/// State of an update stream after draining what is currently available
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PollState {
+    /// The worker is still running; poll again later
+    Pending,
+    /// The worker finished and every update has been delivered
+    Finished,
+    /// The results are no longer wanted
+    Stale,
+}
+
+/// Main-thread side of a worker: receiver plus update callbacks
+struct WorkerUpdates<T, C, U> {
+    rx: Receiver<T>,
+    is_current: C,
+    on_update: U,
+}
+
+impl<T, C, U> WorkerUpdates<T, C, U>
+where
+    C: Fn() -> bool,
+    U: FnMut(T),
+{
+    /// Deliver every update that is ready without blocking
+    ///
+    /// Staleness is checked before each update, so nothing is delivered once
+    /// `is_current` turns false, even if more updates are already queued.
+    fn drain(&mut self) -> PollState {
+        loop {
+            if !(self.is_current)() {
+                return PollState::Stale;
+            }
+            match self.rx.try_recv() {
+                Ok(update) => (self.on_update)(update),
+                Err(TryRecvError::Empty) => return PollState::Pending,
+                Err(TryRecvError::Disconnected) => return PollState::Finished,
+            }
+        }
+    }
+}
+
+impl<T, C, U> WorkerUpdates<T, C, U>
+where
+    T: 'static,
+    C: Fn() -> bool + 'static,
+    U: FnMut(T) + 'static,
+{
+    fn poll(mut self) {
+        if self.drain() == PollState::Pending {
+            glib::idle_add_local_once(move || self.poll());
+        }
+    }
+}
+
+/// Run `work` on a background thread and apply its updates on the main thread
+///
+/// `work` receives a sender and may send any number of updates. Each one is
+/// passed to `on_update` from an idle callback on the GTK main thread, as long
+/// as `is_current` still returns `true`; once it returns `false` polling stops
+/// and the receiver is dropped.
+///
+/// Must be called from the main thread.
+pub fn spawn_worker_with_updates<T, W, C, U>(work: W, is_current: C, on_update: U)
+where
+    T: Send + 'static,
+    W: FnOnce(Sender<T>) + Send + 'static,
+    C: Fn() -> bool + 'static,
+    U: FnMut(T) + 'static,
+{
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || work(tx));
+    let updates = WorkerUpdates {
+        rx,
+        is_current,
+        on_update,
+    };
+    glib::idle_add_local_once(move || updates.poll());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::search_state::SearchState;
+    use std::cell::RefCell;
+
+    fn updates_for_generation(
+        rx: Receiver<u32>,
+        current: &Rc<Cell<u64>>,
+        generation: u64,
+        seen: &Rc<RefCell<Vec<u32>>>,
+    ) -> WorkerUpdates<u32, impl Fn() -> bool, impl FnMut(u32)> {
+        let current = current.clone();
+        let seen = seen.clone();
+        WorkerUpdates {
+            rx,
+            is_current: move || current.get() == generation,
+            on_update: move |v| seen.borrow_mut().push(v),
+        }
+    }
+
+    #[test]
+    fn test_drain_delivers_queued_updates_in_order() {
+        let (tx, rx) = mpsc::channel();
+        let current = Rc::new(Cell::new(1));
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut updates = updates_for_generation(rx, &current, 1, &seen);
+
+        tx.send(1).unwrap();
+        tx.send(2).unwrap();
+        assert_eq!(updates.drain(), PollState::Pending);
+        assert_eq!(*seen.borrow(), vec![1, 2]);
+
+        tx.send(3).unwrap();
+        drop(tx);
+        assert_eq!(updates.drain(), PollState::Finished);
+        assert_eq!(*seen.borrow(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_drain_stops_when_generation_changes() {
+        let (tx, rx) = mpsc::channel();
+        let current = Rc::new(Cell::new(7));
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut updates = updates_for_generation(rx, &current, 7, &seen);
+
+        tx.send(1).unwrap();
+        assert_eq!(updates.drain(), PollState::Pending);
+
+        // A newer search started: queued results must not be applied
+        current.set(8);
+        tx.send(2).unwrap();
+        assert_eq!(updates.drain(), PollState::Stale);
+        assert_eq!(*seen.borrow(), vec![1]);
+    }
+
+    #[test]
+    fn test_drain_stale_before_first_update() {
+        let (tx, rx) = mpsc::channel();
+        let current = Rc::new(Cell::new(3));
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut updates = updates_for_generation(rx, &current, 2, &seen);
+
+        tx.send(1).unwrap();
+        assert_eq!(updates.drain(), PollState::Stale);
+        assert!(seen.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_generation_change_mid_batch() {
+        let (tx, rx) = mpsc::channel();
+        let current = Rc::new(Cell::new(1));
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        // The first update starts a new search, as a user keystroke would
+        let mut updates = WorkerUpdates {
+            rx,
+            is_current: {
+                let current = current.clone();
+                move || current.get() == 1
+            },
+            on_update: {
+                let current = current.clone();
+                let seen = seen.clone();
+                move |v| {
+                    seen.borrow_mut().push(v);
+                    current.set(2);
+                }
+            },
+        };
+
+        tx.send(1).unwrap();
+        tx.send(2).unwrap();
+        assert_eq!(updates.drain(), PollState::Stale);
+        assert_eq!(*seen.borrow(), vec![1]);
+    }
+
+    #[test]
+    fn test_hide_during_search_drops_late_batch() {
+        let state = SearchState::new();
+        let (tx, rx) = mpsc::channel();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let generation = state.bump_task_gen();
+        let mut updates = WorkerUpdates {
+            rx,
+            is_current: {
+                let state = state.clone();
+                move || state.task_gen() == generation
+            },
+            on_update: {
+                let seen = seen.clone();
+                move |v| seen.borrow_mut().push(v)
+            },
+        };
+
+        tx.send(1).unwrap();
+        assert_eq!(updates.drain(), PollState::Pending);
+
+        // The window is hidden while the worker is still sending, then shown
+        // again: the batch sent before the hide must not reach the new list
+        let _ = state.bump_task_gen();
+        tx.send(2).unwrap();
+        assert_eq!(updates.drain(), PollState::Stale);
+        assert_eq!(*seen.borrow(), vec![1]);
+
+        drop(updates);
+        assert!(tx.send(3).is_err());
+    }
+
+    #[test]
+    fn test_run_order_older_scan_cannot_clobber_newer() {
+        let order = RunOrder::default();
+        let before_hide = order.start();
+        let after_show = order.start();
+        assert!(order.is_current(before_hide));
+
+        // The scan started after the show finishes first
+        assert!(order.is_current(after_show));
+        order.applied(after_show);
+
+        // The older scan's late result is dropped
+        assert!(!order.is_current(before_hide));
+        assert!(order.is_current(after_show));
+    }
+
+    #[test]
+    fn test_run_order_silent_newer_run_keeps_older_current() {
+        let order = RunOrder::default();
+        let initial = order.start();
+        // A revalidation that finds no change sends nothing
+        let _unchanged = order.start();
+        assert!(order.is_current(initial));
+        order.applied(initial);
+        assert!(order.is_current(initial));
+    }
+
+    #[test]
+    fn test_dropping_updates_disconnects_worker() {
+        let (tx, rx) = mpsc::channel::<u32>();
+        let current = Rc::new(Cell::new(1));
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let updates = updates_for_generation(rx, &current, 1, &seen);
+
+        drop(updates);
+        assert!(tx.send(1).is_err());
+    }
+}
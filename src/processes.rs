@@ -0,0 +1,456 @@
+//! Running processes for the `:kill` mode
+//!
+//! Processes are read straight from `/proc` on a worker thread rather than
+//! by running `ps`: the name from `comm`, the command line from `cmdline`,
+//! the resident memory from `status`, and the start time and CPU time from
+//! `stat`. Only processes owned by the current user are listed, Grunner
+//! itself excepted.
+//!
+//! CPU use is the average since the process started, as `ps` shows it,
+//! since a single scan has no earlier sample to compare against.
+//!
+//! PIDs are reused, so a process is signalled only after checking that the
+//! PID still belongs to a process with the same owner and start time as the
+//! one that was listed. Otherwise the row reports that it has exited.
+
+use crate::providers::ranking::abbreviation_score;
+use fuzzy_matcher::FuzzyMatcher;
+use rustix::process::{Pid, Signal};
+use std::fmt;
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+
+/// Mount point of the process file system
+pub const PROC_ROOT: &str = "/proc";
+
+/// A process of the current user
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Process {
+    pub pid: u32,
+    /// Short name from `comm`, at most 15 bytes
+    pub name: String,
+    /// Arguments joined by spaces, empty for kernel threads and zombies
+    pub cmdline: String,
+    /// Resident memory in KiB
+    pub rss_kib: u64,
+    /// Start time in clock ticks after boot, which tells a reused PID apart
+    pub start_time: u64,
+    /// Average CPU use since the start, in percent of one core
+    pub cpu_percent: u32,
+}
+
+impl Process {
+    /// Second line of the row: PID, memory, CPU use if any and command line
+    #[must_use]
+    pub fn description(&self) -> String {
+        let mut description = format!("PID {} · {}", self.pid, format_memory(self.rss_kib));
+        if self.cpu_percent > 0 {
+            description.push_str(&format!(" · {}% CPU", self.cpu_percent));
+        }
+        if !self.cmdline.is_empty() {
+            description.push_str(" · ");
+            description.push_str(&self.cmdline);
+        }
+        description
+    }
+}
+
+/// Signals the `:kill` mode sends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KillSignal {
+    /// `SIGTERM`, sent by Enter: asks the process to exit
+    Term,
+    /// `SIGKILL`, sent by Shift+Enter: ends the process at once
+    Kill,
+}
+
+impl KillSignal {
+    /// Signal name for messages
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Term => "SIGTERM",
+            Self::Kill => "SIGKILL",
+        }
+    }
+
+    fn signal(self) -> Signal {
+        match self {
+            Self::Term => Signal::TERM,
+            Self::Kill => Signal::KILL,
+        }
+    }
+}
+
+/// Why a process could not be signalled
+#[derive(Debug)]
+pub enum KillError {
+    /// The process exited, or its PID now belongs to another process
+    Gone,
+    /// The kernel refused the signal
+    Signal(std::io::Error),
+}
+
+impl fmt::Display for KillError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Gone => write!(f, "the process has already exited"),
+            Self::Signal(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+/// Human-readable memory size of `kib` KiB, e.g. `512 KiB` or `1.5 GiB`
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn format_memory(kib: u64) -> String {
+    if kib < 1024 {
+        format!("{kib} KiB")
+    } else if kib < 1024 * 1024 {
+        format!("{:.1} MiB", kib as f64 / 1024.0)
+    } else {
+        format!("{:.1} GiB", kib as f64 / (1024.0 * 1024.0))
+    }
+}
+
+/// Start time from the contents of `/proc/<pid>/stat`
+///
+/// The name in parentheses may contain spaces and parentheses itself, so
+/// fields are counted from the last `)`. The start time is field 22, the
+/// 20th after the name.
+#[must_use]
+pub fn parse_start_time(stat: &str) -> Option<u64> {
+    let (_, fields) = stat.rsplit_once(')')?;
+    fields.split_whitespace().nth(19)?.parse().ok()
+}
+
+/// CPU time in clock ticks, user and system, from the contents of
+/// `/proc/<pid>/stat`
+///
+/// These are fields 14 and 15, the 12th and 13th after the name.
+#[must_use]
+pub fn parse_cpu_ticks(stat: &str) -> Option<u64> {
+    let (_, fields) = stat.rsplit_once(')')?;
+    let mut fields = fields.split_whitespace().skip(11);
+    let user: u64 = fields.next()?.parse().ok()?;
+    let system: u64 = fields.next()?.parse().ok()?;
+    Some(user + system)
+}
+
+/// Seconds since boot from the contents of `/proc/uptime`
+#[must_use]
+pub fn parse_uptime(uptime: &str) -> Option<f64> {
+    uptime.split_whitespace().next()?.parse().ok()
+}
+
+/// Average CPU use in percent of one core of a process that used
+/// `cpu_ticks` since it started `start_time` ticks after boot
+///
+/// A process using several cores can exceed 100%.
+#[must_use]
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+pub fn cpu_percent(cpu_ticks: u64, start_time: u64, uptime_secs: f64, ticks_per_sec: u64) -> u32 {
+    let ticks_per_sec = ticks_per_sec.max(1) as f64;
+    let running = uptime_secs - start_time as f64 / ticks_per_sec;
+    if running <= 0.0 {
+        return 0;
+    }
+    (cpu_ticks as f64 / ticks_per_sec / running * 100.0).round() as u32
+}
+
+/// Resident memory in KiB from the contents of `/proc/<pid>/status`
+///
+/// Kernel threads have no `VmRSS` line and use no memory of their own.
+#[must_use]
+pub fn parse_rss_kib(status: &str) -> u64 {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))
+        .and_then(|value| value.split_whitespace().next()?.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Read the process `pid` from `proc_root`, if it is owned by `uid`
+///
+/// CPU use is left at 0 without `uptime_secs`.
+fn read_process(proc_root: &Path, pid: u32, uid: u32, uptime_secs: Option<f64>) -> Option<Process> {
+    let dir = proc_root.join(pid.to_string());
+    if std::fs::metadata(&dir).ok()?.uid() != uid {
+        return None;
+    }
+    let stat = std::fs::read_to_string(dir.join("stat")).ok()?;
+    let name = std::fs::read_to_string(dir.join("comm")).ok()?;
+    let cmdline = std::fs::read(dir.join("cmdline")).unwrap_or_default();
+    let status = std::fs::read_to_string(dir.join("status")).unwrap_or_default();
+    let start_time = parse_start_time(&stat)?;
+    let cpu_percent = uptime_secs
+        .zip(parse_cpu_ticks(&stat))
+        .map_or(0, |(uptime, ticks)| {
+            cpu_percent(
+                ticks,
+                start_time,
+                uptime,
+                rustix::param::clock_ticks_per_second(),
+            )
+        });
+    Some(Process {
+        pid,
+        name: name.trim_end().to_string(),
+        cmdline: String::from_utf8_lossy(&cmdline)
+            .split('\0')
+            .filter(|arg| !arg.is_empty())
+            .collect::<Vec<_>>()
+            .join(" "),
+        rss_kib: parse_rss_kib(&status),
+        start_time,
+        cpu_percent,
+    })
+}
+
+/// The processes in `proc_root` owned by `uid`, except `own_pid`
+///
+/// Processes that exit while being read are left out.
+#[must_use]
+pub fn scan_processes(proc_root: &Path, uid: u32, own_pid: u32) -> Vec<Process> {
+    let Ok(entries) = std::fs::read_dir(proc_root) else {
+        return Vec::new();
+    };
+    let uptime = std::fs::read_to_string(proc_root.join("uptime"))
+        .ok()
+        .and_then(|text| parse_uptime(&text));
+    let mut processes: Vec<Process> = entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+        .filter(|&pid| pid != own_pid)
+        .filter_map(|pid| read_process(proc_root, pid, uid, uptime))
+        .collect();
+    processes.sort_by_key(|process| process.pid);
+    processes
+}
+
+/// The processes of the current user, read from [`PROC_ROOT`]
+#[must_use]
+pub fn list_processes() -> Vec<Process> {
+    scan_processes(
+        Path::new(PROC_ROOT),
+        rustix::process::getuid().as_raw(),
+        std::process::id(),
+    )
+}
+
+/// Whether `process` still runs under its PID in `proc_root`
+///
+/// The PID must belong to a process of the same owner started at the same
+/// time; a new process that was given the PID does not count.
+#[must_use]
+pub fn is_running(proc_root: &Path, process: &Process, uid: u32) -> bool {
+    read_process(proc_root, process.pid, uid, None)
+        .is_some_and(|current| current.start_time == process.start_time)
+}
+
+/// Send `signal` to `process`, if it still runs
+///
+/// # Errors
+/// Returns [`KillError::Gone`] if the process exited or its PID was reused,
+/// and [`KillError::Signal`] if the signal could not be sent.
+pub fn kill(process: &Process, signal: KillSignal) -> Result<(), KillError> {
+    let uid = rustix::process::getuid().as_raw();
+    if !is_running(Path::new(PROC_ROOT), process, uid) {
+        return Err(KillError::Gone);
+    }
+    let pid = i32::try_from(process.pid)
+        .ok()
+        .and_then(Pid::from_raw)
+        .ok_or(KillError::Gone)?;
+    rustix::process::kill_process(pid, signal.signal()).map_err(|e| match e {
+        rustix::io::Errno::SRCH => KillError::Gone,
+        e => KillError::Signal(e.into()),
+    })
+}
+
+/// The processes matching `query`, best first
+///
+/// Every word has to match the name or the command line. An empty query
+/// lists the processes using the most memory first.
+#[must_use]
+pub fn match_processes<'a>(
+    matcher: &impl FuzzyMatcher,
+    query: &str,
+    processes: &'a [Process],
+    max: usize,
+) -> Vec<&'a Process> {
+    let words: Vec<&str> = query.split_whitespace().collect();
+    if words.is_empty() {
+        let mut all: Vec<&Process> = processes.iter().collect();
+        all.sort_by_key(|process| std::cmp::Reverse(process.rss_kib));
+        all.truncate(max);
+        return all;
+    }
+    let mut scored: Vec<(i64, &Process)> = processes
+        .iter()
+        .filter_map(|process| {
+            words
+                .iter()
+                .map(|word| {
+                    let name = abbreviation_score(matcher, &process.name, word);
+                    // The command line only helps, the name ranks first
+                    let cmdline =
+                        abbreviation_score(matcher, &process.cmdline, word).map(|score| score / 2);
+                    name.max(cmdline)
+                })
+                .sum::<Option<i64>>()
+                .map(|score| (score, process))
+        })
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().take(max).map(|(_, p)| p).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuzzy_matcher::skim::SkimMatcherV2;
+    use std::path::PathBuf;
+
+    fn process(pid: u32, name: &str, cmdline: &str, rss_kib: u64) -> Process {
+        Process {
+            pid,
+            name: name.to_string(),
+            cmdline: cmdline.to_string(),
+            rss_kib,
+            start_time: 100,
+            cpu_percent: 0,
+        }
+    }
+
+    /// `stat` contents of a process that used 150 ticks of user and 50 of
+    /// system time
+    fn stat_line(pid: u32, name: &str, start_time: u64) -> String {
+        let mut fields = vec!["0"; 18];
+        fields[10] = "150";
+        fields[11] = "50";
+        let start = start_time.to_string();
+        fields.push(&start);
+        fields.push("4096");
+        format!("{pid} ({name}) S {}", fields.join(" "))
+    }
+
+    /// Write a fake process directory under `root`
+    fn fake_process(root: &Path, pid: u32, name: &str, start_time: u64) {
+        let dir = root.join(pid.to_string());
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("stat"), stat_line(pid, name, start_time)).unwrap();
+        std::fs::write(dir.join("comm"), format!("{name}\n")).unwrap();
+        std::fs::write(dir.join("cmdline"), format!("/usr/bin/{name}\0--flag\0")).unwrap();
+        std::fs::write(dir.join("status"), "Name:\tx\nVmRSS:\t  2048 kB\n").unwrap();
+    }
+
+    fn temp_root(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("grunner_test_proc_{name}_{}", std::process::id()))
+    }
+
+    #[test]
+    fn test_parse_stat_and_status() {
+        assert_eq!(parse_start_time(&stat_line(42, "cat", 12345)), Some(12345));
+        // Names may contain spaces and parentheses
+        assert_eq!(parse_start_time(&stat_line(42, "a) b (c", 7)), Some(7));
+        assert_eq!(parse_start_time("42 (cat) S 1 2"), None);
+        assert_eq!(parse_cpu_ticks(&stat_line(42, "a) b (c", 7)), Some(200));
+        assert_eq!(parse_cpu_ticks("42 (cat) S 1 2"), None);
+        assert_eq!(parse_uptime("3600.25 7100.50\n"), Some(3600.25));
+        assert_eq!(parse_rss_kib("Name:\tcat\nVmRSS:\t   3512 kB\n"), 3512);
+        assert_eq!(parse_rss_kib("Name:\tkthreadd\n"), 0);
+    }
+
+    #[test]
+    fn test_format_memory_and_description() {
+        assert_eq!(format_memory(512), "512 KiB");
+        assert_eq!(format_memory(1536), "1.5 MiB");
+        assert_eq!(format_memory(3 * 1024 * 1024), "3.0 GiB");
+        assert_eq!(
+            process(7, "vim", "vim notes.md", 4096).description(),
+            "PID 7 · 4.0 MiB · vim notes.md"
+        );
+        assert_eq!(process(7, "kworker", "", 0).description(), "PID 7 · 0 KiB");
+        let busy = Process {
+            cpu_percent: 85,
+            ..process(7, "cc1", "cc1 main.c", 1024)
+        };
+        assert_eq!(busy.description(), "PID 7 · 1.0 MiB · 85% CPU · cc1 main.c");
+    }
+
+    #[test]
+    fn test_cpu_percent() {
+        // 50 s of CPU over the 100 s since the start at 10 s after boot
+        assert_eq!(cpu_percent(5_000, 1_000, 110.0, 100), 50);
+        // Two busy cores
+        assert_eq!(cpu_percent(20_000, 0, 100.0, 100), 200);
+        // Started in the same tick as the uptime was read
+        assert_eq!(cpu_percent(10, 1_000, 10.0, 100), 0);
+        assert_eq!(cpu_percent(0, 0, 100.0, 0), 0);
+    }
+
+    #[test]
+    fn test_scan_processes() {
+        let root = temp_root("scan");
+        fake_process(&root, 300, "firefox", 50);
+        fake_process(&root, 20, "bash", 10);
+        fake_process(&root, 55, "grunner", 20);
+        std::fs::create_dir_all(root.join("self")).unwrap();
+        std::fs::write(root.join("uptime"), "10.00 19.00\n").unwrap();
+        let uid = std::fs::metadata(&root).unwrap().uid();
+
+        let found = scan_processes(&root, uid, 55);
+        let _ = std::fs::remove_dir_all(&root);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].pid, 20);
+        assert_eq!(found[1].name, "firefox");
+        assert_eq!(found[1].cmdline, "/usr/bin/firefox --flag");
+        assert_eq!(found[1].rss_kib, 2048);
+        assert_eq!(found[1].start_time, 50);
+        assert!(found[1].cpu_percent > 0);
+        // Other users' processes are not listed
+        assert!(scan_processes(&root, uid.wrapping_add(1), 0).is_empty());
+    }
+
+    #[test]
+    fn test_is_running_detects_reused_pid() {
+        let root = temp_root("reuse");
+        fake_process(&root, 300, "firefox", 50);
+        let uid = std::fs::metadata(&root).unwrap().uid();
+        let listed = scan_processes(&root, uid, 0).remove(0);
+        assert!(is_running(&root, &listed, uid));
+
+        // The PID now belongs to a process started later
+        fake_process(&root, 300, "cat", 90);
+        assert!(!is_running(&root, &listed, uid));
+        std::fs::remove_dir_all(root.join("300")).unwrap();
+        assert!(!is_running(&root, &listed, uid));
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_match_processes() {
+        let matcher = SkimMatcherV2::default();
+        let processes = vec![
+            process(1, "bash", "/bin/bash", 4_000),
+            process(2, "firefox", "/usr/lib/firefox/firefox -P work", 900_000),
+            process(3, "python3", "python3 /home/me/server.py", 30_000),
+        ];
+
+        let all = match_processes(&matcher, "", &processes, 10);
+        assert_eq!(all.iter().map(|p| p.pid).collect::<Vec<_>>(), [2, 3, 1]);
+        assert_eq!(match_processes(&matcher, " ", &processes, 2).len(), 2);
+
+        let found = match_processes(&matcher, "fire", &processes, 10);
+        assert_eq!(found.first().map(|p| p.pid), Some(2));
+        // The command line matches too
+        let found = match_processes(&matcher, "server", &processes, 10);
+        assert_eq!(found.iter().map(|p| p.pid).collect::<Vec<_>>(), [3]);
+        assert!(match_processes(&matcher, "bash server", &processes, 10).is_empty());
+    }
+}
@@ -0,0 +1,350 @@
+//! Browser bookmarks for the `:b` mode
+//!
+//! Bookmarks are read from every Firefox and Chromium-based profile found in
+//! the home directory:
+//!
+//! - Chromium, Chrome, Brave, Edge and Vivaldi keep them in a JSON file named
+//!   `Bookmarks` in each profile directory.
+//! - Firefox keeps them in `places.sqlite`, which is queried with the
+//!   `sqlite3` command line tool. The database is locked while Firefox runs,
+//!   so a copy is queried instead. Without `sqlite3` Firefox is skipped.
+//!
+//! Loading can take a while (opening `places.sqlite` especially), so the list
+//! model loads bookmarks once on a worker thread and keeps them for the
+//! lifetime of the window.
+
+use crate::core::global_state::get_home_dir;
+use crate::providers::ranking::abbreviation_score;
+use crate::providers::subprocess::query_sqlite_copy;
+use fuzzy_matcher::FuzzyMatcher;
+use log::{debug, warn};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Firefox profile roots, relative to the home directory
+const FIREFOX_ROOTS: &[&str] = &[
+    ".mozilla/firefox",
+    "snap/firefox/common/.mozilla/firefox",
+    ".var/app/org.mozilla.firefox/.mozilla/firefox",
+];
+
+/// Chromium-based browser config directories, relative to the home directory
+const CHROMIUM_ROOTS: &[&str] = &[
+    ".config/chromium",
+    ".config/google-chrome",
+    ".config/BraveSoftware/Brave-Browser",
+    ".config/microsoft-edge",
+    ".config/vivaldi",
+];
+
+/// Bookmarked pages of `places.sqlite`, skipping folders and smart bookmarks
+const FIREFOX_QUERY: &str = "SELECT b.title AS title, p.url AS url \
+     FROM moz_bookmarks b JOIN moz_places p ON p.id = b.fk \
+     WHERE b.type = 1 AND p.url NOT LIKE 'place:%'";
+
+/// A bookmarked page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bookmark {
+    /// Title as saved by the browser, possibly empty
+    pub title: String,
+    pub url: String,
+}
+
+impl Bookmark {
+    /// Title to show, falling back to the URL for untitled bookmarks
+    #[must_use]
+    pub fn display_title(&self) -> &str {
+        if self.title.is_empty() {
+            &self.url
+        } else {
+            &self.title
+        }
+    }
+}
+
+/// Bookmarks loaded for the window
+#[derive(Debug, Default)]
+pub enum BookmarkCache {
+    /// Nothing was requested yet
+    #[default]
+    Unloaded,
+    /// A worker is reading the browser profiles
+    Loading,
+    Loaded(Vec<Bookmark>),
+}
+
+/// Load the bookmarks of every browser profile in the home directory
+///
+/// Firefox bookmarks come first. Bookmarks saved in several browsers are
+/// kept once, and `javascript:` bookmarklets are dropped since they cannot
+/// be opened from here.
+#[must_use]
+pub fn load_all_bookmarks() -> Vec<Bookmark> {
+    let home = Path::new(get_home_dir());
+    let mut all = Vec::new();
+    for places in profile_files(home, FIREFOX_ROOTS, "places.sqlite") {
+        all.extend(read_firefox_bookmarks(&places));
+    }
+    for file in profile_files(home, CHROMIUM_ROOTS, "Bookmarks") {
+        match std::fs::read_to_string(&file) {
+            Ok(json) => all.extend(parse_chromium_bookmarks(&json)),
+            Err(e) => warn!("Failed to read {}: {e}", file.display()),
+        }
+    }
+    let bookmarks = dedup_bookmarks(all);
+    debug!("Loaded {} bookmarks", bookmarks.len());
+    bookmarks
+}
+
+/// Files named `name` in the profile directories under each of `roots`
+fn profile_files(home: &Path, roots: &[&str], name: &str) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for root in roots {
+        let Ok(entries) = std::fs::read_dir(home.join(root)) else {
+            continue;
+        };
+        let mut found: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path().join(name))
+            .filter(|file| file.is_file())
+            .collect();
+        found.sort();
+        files.extend(found);
+    }
+    files
+}
+
+/// Drop bookmarklets and repeated URLs, keeping the first occurrence
+fn dedup_bookmarks(bookmarks: Vec<Bookmark>) -> Vec<Bookmark> {
+    let mut seen = HashSet::new();
+    bookmarks
+        .into_iter()
+        .filter(|b| !b.url.is_empty() && !b.url.starts_with("javascript:"))
+        .filter(|b| seen.insert(b.url.clone()))
+        .collect()
+}
+
+/// A node of a Chromium `Bookmarks` file (folder or page)
+#[derive(Deserialize)]
+struct ChromiumNode {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    children: Vec<ChromiumNode>,
+}
+
+#[derive(Deserialize)]
+struct ChromiumFile {
+    roots: BTreeMap<String, serde_json::Value>,
+}
+
+/// Parse a Chromium `Bookmarks` file, flattening its folders
+///
+/// Invalid files yield no bookmarks.
+#[must_use]
+pub fn parse_chromium_bookmarks(json: &str) -> Vec<Bookmark> {
+    fn collect(node: ChromiumNode, out: &mut Vec<Bookmark>) {
+        if let Some(url) = node.url {
+            out.push(Bookmark {
+                title: node.name,
+                url,
+            });
+        }
+        for child in node.children {
+            collect(child, out);
+        }
+    }
+
+    let file: ChromiumFile = match serde_json::from_str(json) {
+        Ok(file) => file,
+        Err(e) => {
+            warn!("Ignoring invalid Chromium bookmarks file: {e}");
+            return Vec::new();
+        }
+    };
+    let mut bookmarks = Vec::new();
+    // Besides the folder nodes, `roots` may hold bookkeeping values
+    for root in file.roots.into_values() {
+        if let Ok(node) = serde_json::from_value::<ChromiumNode>(root) {
+            collect(node, &mut bookmarks);
+        }
+    }
+    bookmarks
+}
+
+#[derive(Deserialize)]
+struct FirefoxRow {
+    title: Option<String>,
+    url: String,
+}
+
+/// Parse the `sqlite3 -json` output of [`FIREFOX_QUERY`]
+///
+/// `sqlite3` prints nothing at all for an empty result.
+#[must_use]
+pub fn parse_firefox_rows(json: &str) -> Vec<Bookmark> {
+    if json.trim().is_empty() {
+        return Vec::new();
+    }
+    match serde_json::from_str::<Vec<FirefoxRow>>(json) {
+        Ok(rows) => rows
+            .into_iter()
+            .map(|row| Bookmark {
+                title: row.title.unwrap_or_default(),
+                url: row.url,
+            })
+            .collect(),
+        Err(e) => {
+            warn!("Ignoring unexpected sqlite3 output: {e}");
+            Vec::new()
+        }
+    }
+}
+
+/// Query a copy of the Firefox database `places`
+fn read_firefox_bookmarks(places: &Path) -> Vec<Bookmark> {
+    query_sqlite_copy(places, FIREFOX_QUERY)
+        .map(|json| parse_firefox_rows(&json))
+        .unwrap_or_default()
+}
+
+/// Bookmarks matching `query`, best first, at most `max`
+///
+/// Every word of the query has to match the title or the URL, so a
+/// bookmark can be found by its domain alone. Words matching at word starts
+/// rank higher, see [`abbreviation_score`]. An empty query lists the
+/// bookmarks in loading order.
+#[must_use]
+pub fn match_bookmarks<'a>(
+    matcher: &impl FuzzyMatcher,
+    query: &str,
+    bookmarks: &'a [Bookmark],
+    max: usize,
+) -> Vec<&'a Bookmark> {
+    let words: Vec<&str> = query.split_whitespace().collect();
+    if words.is_empty() {
+        return bookmarks.iter().take(max).collect();
+    }
+    let mut scored: Vec<(i64, &Bookmark)> = bookmarks
+        .iter()
+        .filter_map(|bookmark| {
+            words
+                .iter()
+                .map(|word| {
+                    let title = abbreviation_score(matcher, &bookmark.title, word);
+                    let url = abbreviation_score(matcher, &bookmark.url, word);
+                    title.max(url)
+                })
+                .sum::<Option<i64>>()
+                .map(|score| (score, bookmark))
+        })
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().take(max).map(|(_, b)| b).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuzzy_matcher::skim::SkimMatcherV2;
+
+    fn bookmark(title: &str, url: &str) -> Bookmark {
+        Bookmark {
+            title: title.to_string(),
+            url: url.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_chromium_bookmarks_flattens_folders() {
+        let json = r#"{
+            "checksum": "abc",
+            "roots": {
+                "bookmark_bar": {
+                    "name": "Bookmarks bar", "type": "folder",
+                    "children": [
+                        {"name": "Rust", "type": "url", "url": "https://www.rust-lang.org/"},
+                        {"name": "Work", "type": "folder", "children": [
+                            {"name": "Tracker", "type": "url", "url": "https://issues.example.com/"}
+                        ]}
+                    ]
+                },
+                "other": {"name": "Other", "type": "folder", "children": []},
+                "sync_transaction_version": "4"
+            },
+            "version": 1
+        }"#;
+        assert_eq!(
+            parse_chromium_bookmarks(json),
+            vec![
+                bookmark("Rust", "https://www.rust-lang.org/"),
+                bookmark("Tracker", "https://issues.example.com/"),
+            ]
+        );
+        assert!(parse_chromium_bookmarks("not json").is_empty());
+    }
+
+    #[test]
+    fn test_parse_firefox_rows() {
+        let json = r#"[{"title":"GNOME","url":"https://www.gnome.org/"},
+                       {"title":null,"url":"https://example.com/untitled"}]"#;
+        let bookmarks = parse_firefox_rows(json);
+        assert_eq!(
+            bookmarks,
+            vec![
+                bookmark("GNOME", "https://www.gnome.org/"),
+                bookmark("", "https://example.com/untitled"),
+            ]
+        );
+        assert_eq!(bookmarks[1].display_title(), "https://example.com/untitled");
+        assert!(parse_firefox_rows("").is_empty());
+        assert!(parse_firefox_rows("\n").is_empty());
+    }
+
+    #[test]
+    fn test_dedup_bookmarks() {
+        let bookmarks = dedup_bookmarks(vec![
+            bookmark("Firefox copy", "https://example.com/"),
+            bookmark("Bookmarklet", "javascript:alert(1)"),
+            bookmark("Chromium copy", "https://example.com/"),
+            bookmark("Other", "https://other.example.org/"),
+        ]);
+        assert_eq!(
+            bookmarks,
+            vec![
+                bookmark("Firefox copy", "https://example.com/"),
+                bookmark("Other", "https://other.example.org/"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_match_bookmarks_title_and_url() {
+        let matcher = SkimMatcherV2::default();
+        let bookmarks = vec![
+            bookmark("Rust Programming Language", "https://www.rust-lang.org/"),
+            bookmark(
+                "Issue tracker",
+                "https://gitlab.gnome.org/GNOME/gtk/-/issues",
+            ),
+            bookmark("Recipes", "https://cooking.example.com/"),
+        ];
+
+        // Only the domain is remembered
+        let found = match_bookmarks(&matcher, "gitlab", &bookmarks, 10);
+        assert_eq!(found, vec![&bookmarks[1]]);
+
+        // Words may match different fields
+        let found = match_bookmarks(&matcher, "tracker gtk", &bookmarks, 10);
+        assert_eq!(found, vec![&bookmarks[1]]);
+
+        let found = match_bookmarks(&matcher, "rust", &bookmarks, 10);
+        assert_eq!(found.first(), Some(&&bookmarks[0]));
+
+        assert!(match_bookmarks(&matcher, "zzzz", &bookmarks, 10).is_empty());
+        assert_eq!(match_bookmarks(&matcher, "", &bookmarks, 2).len(), 2);
+    }
+}
@@ -1,30 +1,68 @@
 //! Provider discovery for GNOME Shell search providers
 
-use crate::core::global_state::get_home_dir;
-use crate::utils::desktop::resolve_icon_from_desktop;
+use crate::utils::desktop::DesktopIndex;
+use crate::utils::{data_subdirs, expand_home};
 use log::{debug, info, warn};
-use std::path::PathBuf;
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
 
 use super::types::SearchProvider;
 
 /// Discover all available GNOME Shell search providers
 ///
-/// Scans standard directories for .ini files describing search providers,
+/// Scans the `gnome-shell/search-providers` folder of each XDG data
+/// directory for .ini files describing search providers,
 /// parses them, and filters out any providers in the blacklist.
+///
+/// The `.ini` files are parsed in parallel, and their app icons come from a
+/// [`DesktopIndex`] built once for all of them.
 #[must_use]
 pub fn discover_providers(blacklist: &[String]) -> Vec<SearchProvider> {
-    let home = get_home_dir();
-    let dirs: Vec<PathBuf> = vec![
-        PathBuf::from("/usr/share/gnome-shell/search-providers"),
-        PathBuf::from(format!("{home}/.local/share/gnome-shell/search-providers")),
-        PathBuf::from("/var/lib/flatpak/exports/share/gnome-shell/search-providers"),
-        PathBuf::from(format!(
-            "{home}/.local/share/flatpak/exports/share/gnome-shell/search-providers"
-        )),
-    ];
+    let dirs: Vec<PathBuf> = data_subdirs("gnome-shell/search-providers")
+        .iter()
+        .map(|dir| expand_home(dir))
+        .collect();
 
     debug!("Discovering search providers, blacklist: {blacklist:?}");
-    let mut providers = Vec::new();
+    let ini_paths = provider_ini_paths(&dirs);
+    if ini_paths.is_empty() {
+        info!("Discovered 0 search providers");
+        return Vec::new();
+    }
+
+    let index = DesktopIndex::build();
+    let providers: Vec<SearchProvider> = ini_paths
+        .par_iter()
+        .filter_map(|path| {
+            let Some(p) = parse_ini(path, &index) else {
+                debug!("Failed to parse provider .ini file: {}", path.display());
+                return None;
+            };
+            if blacklist.iter().any(|b| b == &p.desktop_id) {
+                debug!("Skipping blacklisted provider: {}", p.desktop_id);
+                return None;
+            }
+            if p.default_disabled {
+                debug!(
+                    "Provider {} has DefaultDisabled=true; including anyway",
+                    p.desktop_id
+                );
+            }
+            debug!(
+                "Discovered provider: {} from {}",
+                p.desktop_id,
+                path.display()
+            );
+            Some(p)
+        })
+        .collect();
+    info!("Discovered {} search providers", providers.len());
+    providers
+}
+
+/// The `.ini` files directly inside `dirs`, in directory order
+fn provider_ini_paths(dirs: &[PathBuf]) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
     for dir in dirs {
         if !dir.is_dir() {
             debug!(
@@ -33,44 +71,24 @@ pub fn discover_providers(blacklist: &[String]) -> Vec<SearchProvider> {
             );
             continue;
         }
-        let entries = match std::fs::read_dir(&dir) {
+        let entries = match std::fs::read_dir(dir) {
             Ok(e) => e,
             Err(e) => {
                 warn!("Failed to read directory {}: {e}", dir.display());
                 continue;
             }
         };
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.extension().is_some_and(|e| e == "ini") {
-                if let Some(p) = parse_ini(&path) {
-                    if blacklist.iter().any(|b| b == &p.desktop_id) {
-                        debug!("Skipping blacklisted provider: {}", p.desktop_id);
-                        continue;
-                    }
-                    if p.default_disabled {
-                        debug!(
-                            "Provider {} has DefaultDisabled=true; including anyway",
-                            p.desktop_id
-                        );
-                    }
-                    debug!(
-                        "Discovered provider: {} from {}",
-                        p.desktop_id,
-                        path.display()
-                    );
-                    providers.push(p);
-                } else {
-                    debug!("Failed to parse provider .ini file: {}", path.display());
-                }
-            }
-        }
+        paths.extend(
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|e| e == "ini")),
+        );
     }
-    info!("Discovered {} search providers", providers.len());
-    providers
+    paths
 }
 
-fn parse_ini(path: &std::path::Path) -> Option<SearchProvider> {
+fn parse_ini(path: &Path, index: &DesktopIndex) -> Option<SearchProvider> {
     let content = match std::fs::read_to_string(path) {
         Ok(c) => c,
         Err(e) => {
@@ -138,7 +156,7 @@ fn parse_ini(path: &std::path::Path) -> Option<SearchProvider> {
     Some(SearchProvider {
         bus_name,
         object_path,
-        app_icon: resolve_icon_from_desktop(&desktop_id),
+        app_icon: index.icon(&desktop_id),
         desktop_id,
         default_disabled,
     })
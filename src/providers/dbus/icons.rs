@@ -44,6 +44,50 @@ pub fn parse_icon_variant(val: &OwnedValue) -> Option<IconData> {
     inner(val)
 }
 
+/// Parse a `gicon` meta, a `GIcon` serialized with `g_icon_to_string`
+///
+/// Handles the forms `g_icon_new_for_string` reads back for themed and file
+/// icons: a bare icon name, an absolute path, a `file://` URI, and the
+/// `". GThemedIcon name name-symbolic"` and `". GFileIcon uri"` token lists,
+/// whose tokens are URI-escaped. Other icon types, such as emblemed icons,
+/// and non-file URIs yield `None`.
+#[must_use]
+pub fn parse_gicon_string(gicon: &str) -> Option<IconData> {
+    let gicon = gicon.trim();
+    if let Some(serialized) = gicon.strip_prefix(". ") {
+        let mut tokens = serialized.split(' ').filter(|t| !t.is_empty());
+        let type_name = tokens.next()?;
+        // The type may carry a version, e.g. `GThemedIcon.0`
+        let type_name = type_name
+            .split_once('.')
+            .map_or(type_name, |(name, _)| name);
+        let mut tokens = tokens
+            .map(|t| urlencoding::decode(t).map_or_else(|_| t.to_string(), |t| t.into_owned()));
+        return match type_name {
+            "GThemedIcon" => tokens.find(|name| !name.is_empty()).map(IconData::Themed),
+            "GFileIcon" => tokens.next().as_deref().and_then(file_icon),
+            _ => None,
+        };
+    }
+    if gicon.is_empty() {
+        None
+    } else if gicon.starts_with('/') || gicon.contains("://") {
+        file_icon(gicon)
+    } else {
+        Some(IconData::Themed(gicon.to_string()))
+    }
+}
+
+/// File icon of an absolute path or a `file://` URI
+fn file_icon(location: &str) -> Option<IconData> {
+    if location.starts_with('/') {
+        return Some(IconData::File(location.to_string()));
+    }
+    let path = location.strip_prefix("file://")?;
+    let path = urlencoding::decode(path).map_or_else(|_| path.to_string(), |p| p.into_owned());
+    path.starts_with('/').then_some(IconData::File(path))
+}
+
 fn extract_themed(val: &zbus::zvariant::Value<'_>) -> Option<IconData> {
     use zbus::zvariant::Value;
 
@@ -119,3 +163,66 @@ fn extract_file(val: &zbus::zvariant::Value<'_>) -> Option<IconData> {
 
     walk(val).map(IconData::File)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn themed(icon: Option<IconData>) -> Option<String> {
+        match icon? {
+            IconData::Themed(name) => Some(name),
+            IconData::File(_) => None,
+        }
+    }
+
+    fn file(icon: Option<IconData>) -> Option<String> {
+        match icon? {
+            IconData::File(path) => Some(path),
+            IconData::Themed(_) => None,
+        }
+    }
+
+    #[test]
+    fn test_parse_gicon_themed() {
+        assert_eq!(
+            themed(parse_gicon_string(
+                ". GThemedIcon face-smile face-smile-symbolic"
+            )),
+            Some("face-smile".to_string())
+        );
+        assert_eq!(
+            themed(parse_gicon_string(". GThemedIcon.0 text-x-generic")),
+            Some("text-x-generic".to_string())
+        );
+        // A single themed name is serialized bare
+        assert_eq!(
+            themed(parse_gicon_string("org.gnome.Characters")),
+            Some("org.gnome.Characters".to_string())
+        );
+        assert!(parse_gicon_string(". GThemedIcon").is_none());
+    }
+
+    #[test]
+    fn test_parse_gicon_file() {
+        assert_eq!(
+            file(parse_gicon_string("/usr/share/pixmaps/app.png")),
+            Some("/usr/share/pixmaps/app.png".to_string())
+        );
+        assert_eq!(
+            file(parse_gicon_string("file:///home/me/My%20Pictures/a.jpg")),
+            Some("/home/me/My Pictures/a.jpg".to_string())
+        );
+        assert_eq!(
+            file(parse_gicon_string(". GFileIcon file:///tmp/thumb%201.png")),
+            Some("/tmp/thumb 1.png".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_gicon_unsupported() {
+        assert!(parse_gicon_string("").is_none());
+        assert!(parse_gicon_string("resource:///org/gnome/app/icon.svg").is_none());
+        assert!(parse_gicon_string(". GEmblemedIcon GThemedIcon 1 folder").is_none());
+        assert!(parse_gicon_string(". GFileIcon https://example.com/a.png").is_none());
+    }
+}
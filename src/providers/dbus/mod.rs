@@ -8,5 +8,5 @@ pub mod query;
 pub mod types;
 
 pub use discovery::discover_providers;
-pub use query::{activate_result, run_search_streaming};
-pub use types::{IconData, SearchProvider, SearchResult};
+pub use query::{activate_result, launch_search, run_search_streaming};
+pub use types::{IconData, ProviderAnswer, ResultSet, SearchProvider, SearchResult};
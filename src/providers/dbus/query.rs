@@ -1,6 +1,7 @@
 //! D-Bus query execution for search providers
 
 use crate::core::global_state::get_tokio_runtime;
+use crate::metrics;
 use futures::stream::{FuturesUnordered, StreamExt};
 use log::{debug, error, info};
 use std::collections::HashMap;
@@ -9,8 +10,8 @@ use std::time::Duration;
 use zbus::Connection;
 use zbus::zvariant::OwnedValue;
 
-use super::icons::parse_icon_variant;
-use super::types::{SearchProvider, SearchResult};
+use super::icons::{parse_gicon_string, parse_icon_variant};
+use super::types::{ProviderAnswer, ResultSet, SearchProvider, SearchResult};
 
 /// Lazily initialise and cache the D-Bus session connection.
 ///
@@ -31,24 +32,41 @@ async fn get_or_init_conn() -> zbus::Result<Connection> {
     Ok(DBUS_CONN.get_or_init(|| conn).clone())
 }
 
-pub fn run_search_streaming(
+/// Search every provider for `query`, sending each answer as it arrives
+///
+/// `previous` holds the result set of each provider's last search by bus
+/// name; a provider whose previous terms `query` only extends refines that
+/// set with `GetSubsearchResultSet` instead of searching from scratch.
+///
+/// `max_results` gives the number of results fetched from each provider. A
+/// provider that fails is answered for with no results and an empty result
+/// set, so the caller need not wait for it.
+pub fn run_search_streaming<T: From<ProviderAnswer>>(
     providers: &[SearchProvider],
     query: &str,
-    max_per_provider: usize,
-    tx: std::sync::mpsc::Sender<Vec<SearchResult>>,
+    previous: &HashMap<String, ResultSet>,
+    max_results: impl Fn(&SearchProvider) -> usize,
+    tx: std::sync::mpsc::Sender<T>,
 ) {
     let terms: Vec<String> = query.split_whitespace().map(String::from).collect();
     if terms.is_empty() {
         return;
     }
-    get_tokio_runtime().block_on(query_all_streaming(providers, &terms, max_per_provider, tx));
+    get_tokio_runtime().block_on(query_all_streaming(
+        providers,
+        &terms,
+        previous,
+        max_results,
+        tx,
+    ));
 }
 
-async fn query_all_streaming(
+async fn query_all_streaming<T: From<ProviderAnswer>>(
     providers: &[SearchProvider],
     terms: &[String],
-    max_per_provider: usize,
-    tx: std::sync::mpsc::Sender<Vec<SearchResult>>,
+    previous: &HashMap<String, ResultSet>,
+    max_results: impl Fn(&SearchProvider) -> usize,
+    tx: std::sync::mpsc::Sender<T>,
 ) {
     debug!(
         "Starting search across {} providers with terms: {:?}",
@@ -95,38 +113,102 @@ async fn query_all_streaming(
             let proxy = proxy_cache.get(&provider.bus_name)?.clone();
             let terms_str = terms_str.clone();
             let bus_name = provider.bus_name.clone();
+            let refine = previous
+                .get(&provider.bus_name)
+                .filter(|set| set.is_refined_by(terms))
+                .map(|set| set.ids.as_slice());
+            let max_results = max_results(provider);
             Some(async move {
-                let result = query_one(&proxy, provider, &terms_str, max_per_provider).await;
+                let started = metrics::start();
+                let result = query_one(&proxy, provider, &terms_str, refine, max_results).await;
+                metrics::record_elapsed(started, |m, elapsed| {
+                    m.providers.insert(provider.desktop_id.clone(), elapsed);
+                });
                 (bus_name, result)
             })
         })
         .collect();
 
     while let Some((bus_name, outcome)) = futs.next().await {
-        match outcome {
-            Ok(results) if !results.is_empty() => {
+        let answer = match outcome {
+            Ok((ids, results)) => {
                 debug!("Provider {} returned {} results", bus_name, results.len());
-                if tx.send(results).is_err() {
-                    debug!("Search provider channel closed, stopping processing");
-                    break;
+                // Empty answers are sent too, so their result set is kept
+                ProviderAnswer {
+                    bus_name,
+                    result_set: ResultSet {
+                        terms: terms.to_vec(),
+                        ids,
+                    },
+                    results,
                 }
             }
             Err(e) => {
                 error!("Search provider {bus_name} error: {e}");
+                // The next search starts over instead of refining
+                ProviderAnswer {
+                    bus_name,
+                    result_set: ResultSet::default(),
+                    results: Vec::new(),
+                }
             }
-            _ => {
-                debug!("Provider {bus_name} returned empty result set");
+        };
+        if tx.send(answer.into()).is_err() {
+            debug!("Search provider channel closed, stopping processing");
+            break;
+        }
+    }
+}
+
+/// Result IDs of `provider` for `terms`
+///
+/// With `previous` IDs the provider refines them with
+/// `GetSubsearchResultSet`; if that call fails, the search starts over with
+/// `GetInitialResultSet`.
+async fn result_ids(
+    proxy: &zbus::Proxy<'_>,
+    provider: &SearchProvider,
+    terms: &[&str],
+    previous: Option<&[String]>,
+    timeout_dur: Duration,
+) -> zbus::Result<Vec<String>> {
+    use tokio::time::timeout;
+
+    if let Some(previous) = previous {
+        debug!(
+            "Refining {} previous results of {}",
+            previous.len(),
+            provider.bus_name
+        );
+        let args = (previous, terms);
+        match timeout(timeout_dur, proxy.call("GetSubsearchResultSet", &args)).await {
+            Ok(Ok(ids)) => return Ok(ids),
+            Ok(Err(e)) => debug!(
+                "GetSubsearchResultSet failed for {}, searching again: {e}",
+                provider.bus_name
+            ),
+            Err(_) => {
+                return Err(zbus::Error::Failure(
+                    "D-Bus call to GetSubsearchResultSet timed out".into(),
+                ));
             }
         }
     }
+
+    timeout(timeout_dur, proxy.call("GetInitialResultSet", &(terms,)))
+        .await
+        .map_err(|_| zbus::Error::Failure("D-Bus call to GetInitialResultSet timed out".into()))?
 }
 
+/// Search one provider, returning every result ID and the results for the
+/// first `max_results` of them
 async fn query_one(
     proxy: &zbus::Proxy<'_>,
     provider: &SearchProvider,
     terms: &[&str],
+    previous: Option<&[String]>,
     max_results: usize,
-) -> zbus::Result<Vec<SearchResult>> {
+) -> zbus::Result<(Vec<String>, Vec<SearchResult>)> {
     use tokio::time::timeout;
 
     debug!(
@@ -136,11 +218,7 @@ async fn query_one(
 
     let timeout_dur = Duration::from_secs(3);
 
-    let ids: Vec<String> = timeout(timeout_dur, proxy.call("GetInitialResultSet", &(terms,)))
-        .await
-        .map_err(|_| {
-            zbus::Error::Failure("D-Bus call to GetInitialResultSet timed out".into())
-        })??;
+    let ids = result_ids(proxy, provider, terms, previous, timeout_dur).await?;
 
     debug!(
         "Provider {} returned {} result IDs: {:?}",
@@ -151,7 +229,7 @@ async fn query_one(
 
     if ids.is_empty() {
         debug!("Provider {} returned empty result set", provider.bus_name);
-        return Ok(vec![]);
+        return Ok((ids, vec![]));
     }
 
     let ids_capped: Vec<&str> = ids.iter().take(max_results).map(String::as_str).collect();
@@ -178,7 +256,7 @@ async fn query_one(
         results.len()
     );
 
-    Ok(results)
+    Ok((ids, results))
 }
 
 fn build_result(
@@ -193,9 +271,16 @@ fn build_result(
     // Clipboard handling is deferred to activation time (item_activation.rs)
     // where it runs on the GTK main thread — calling from here would be
     // thread-unsafe.
-    let clipboard_text = take_str(&mut meta, "clipboardText");
+    // Copied when the result is activated, as the Shell does; the
+    // Characters provider returns the character itself
+    let clipboard_text = take_str(&mut meta, "clipboardText").filter(|text| !text.is_empty());
 
-    let icon = meta.get("icon").and_then(parse_icon_variant);
+    // "icon" is a serialized GVariant, "gicon" the g_icon_to_string form
+    // some providers send instead
+    let icon = meta
+        .get("icon")
+        .and_then(parse_icon_variant)
+        .or_else(|| take_str(&mut meta, "gicon").and_then(|gicon| parse_gicon_string(&gicon)));
 
     Some(SearchResult {
         id,
@@ -205,6 +290,7 @@ fn build_result(
         app_icon: app_icon.to_string(),
         bus_name: provider.bus_name.clone(),
         object_path: provider.object_path.clone(),
+        desktop_id: provider.desktop_id.clone(),
         clipboard_text,
     })
 }
@@ -227,6 +313,26 @@ fn take_str(meta: &mut HashMap<String, OwnedValue>, key: &str) -> Option<String>
     }
 }
 
+/// Proxy of the search provider at `bus_name` and `object_path`, logging
+/// why it could not be created
+async fn provider_proxy<'a>(bus_name: &'a str, object_path: &'a str) -> Option<zbus::Proxy<'a>> {
+    let Ok(conn) = get_or_init_conn().await else {
+        error!("Cannot connect to D-Bus session bus for provider {bus_name}");
+        return None;
+    };
+    let proxy = zbus::Proxy::new(
+        &conn,
+        bus_name,
+        object_path,
+        "org.gnome.Shell.SearchProvider2",
+    )
+    .await;
+    if proxy.is_err() {
+        error!("Failed to create D-Bus proxy for provider {bus_name}");
+    }
+    proxy.ok()
+}
+
 pub fn activate_result(
     bus_name: &str,
     object_path: &str,
@@ -241,19 +347,7 @@ pub fn activate_result(
     debug!("Activating search result: {result_id} from provider {bus_name}");
 
     get_tokio_runtime().block_on(async move {
-        let Ok(conn) = get_or_init_conn().await else {
-            error!("Cannot connect to D-Bus session bus for result activation");
-            return;
-        };
-        let Ok(proxy) = zbus::Proxy::new(
-            &conn,
-            bus_name.as_str(),
-            object_path.as_str(),
-            "org.gnome.Shell.SearchProvider2",
-        )
-        .await
-        else {
-            error!("Failed to create D-Bus proxy for provider {bus_name}");
+        let Some(proxy) = provider_proxy(&bus_name, &object_path).await else {
             return;
         };
 
@@ -271,3 +365,92 @@ pub fn activate_result(
         }
     });
 }
+
+/// Open the provider's application with a search for `terms`, its "show
+/// more results" action
+///
+/// Blocks until the provider answers; call it off the main thread.
+pub fn launch_search(bus_name: &str, object_path: &str, terms: &[String], timestamp: u32) {
+    debug!("Launching search {terms:?} in provider {bus_name}");
+
+    get_tokio_runtime().block_on(async move {
+        let Some(proxy) = provider_proxy(bus_name, object_path).await else {
+            return;
+        };
+
+        let terms_str: Vec<&str> = terms.iter().map(String::as_str).collect();
+        if let Err(e) = proxy
+            .call::<_, _, ()>("LaunchSearch", &(&terms_str, timestamp))
+            .await
+        {
+            error!("Failed to launch search in provider {bus_name}: {e}");
+        } else {
+            info!("Launched search in provider {bus_name}");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::dbus::types::IconData;
+    use zbus::zvariant::Value;
+
+    fn provider() -> SearchProvider {
+        SearchProvider {
+            bus_name: "org.gnome.Characters.BackgroundService".to_string(),
+            object_path: "/org/gnome/Characters/SearchProvider".to_string(),
+            app_icon: "org.gnome.Characters".to_string(),
+            desktop_id: "org.gnome.Characters.desktop".to_string(),
+            default_disabled: false,
+        }
+    }
+
+    fn meta(entries: &[(&str, &str)]) -> HashMap<String, OwnedValue> {
+        entries
+            .iter()
+            .map(|(key, value)| {
+                let value = OwnedValue::try_from(Value::from(*value)).unwrap();
+                ((*key).to_string(), value)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_build_result_reads_gicon_and_clipboard_text() {
+        let result = build_result(
+            meta(&[
+                ("id", "😀"),
+                ("name", "GRINNING FACE"),
+                ("gicon", ". GThemedIcon face-smile face-smile-symbolic"),
+                ("clipboardText", "😀"),
+            ]),
+            &provider(),
+            "org.gnome.Characters",
+        )
+        .unwrap();
+        assert!(matches!(result.icon, Some(IconData::Themed(ref name)) if name == "face-smile"));
+        assert_eq!(result.clipboard_text.as_deref(), Some("😀"));
+    }
+
+    #[test]
+    fn test_build_result_prefers_icon_and_ignores_empty_clipboard_text() {
+        let result = build_result(
+            meta(&[
+                ("id", "doc"),
+                ("icon", "text-x-generic"),
+                ("gicon", "/tmp/thumb.png"),
+                ("clipboardText", ""),
+            ]),
+            &provider(),
+            "",
+        )
+        .unwrap();
+        assert!(
+            matches!(result.icon, Some(IconData::Themed(ref name)) if name == "text-x-generic")
+        );
+        assert_eq!(result.name, "doc");
+        assert!(result.clipboard_text.is_none());
+        assert!(build_result(meta(&[("name", "no id")]), &provider(), "").is_none());
+    }
+}
@@ -35,5 +35,74 @@ pub struct SearchResult {
     pub app_icon: String,
     pub bus_name: String,
     pub object_path: String,
+    pub desktop_id: String,
     pub clipboard_text: Option<String>,
 }
+
+/// Result IDs a provider returned for a search, kept to refine the next one
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResultSet {
+    pub terms: Vec<String>,
+    pub ids: Vec<String>,
+}
+
+impl ResultSet {
+    /// Whether a search for `terms` only narrows this one, so the provider
+    /// may refine these IDs with `GetSubsearchResultSet`
+    ///
+    /// Each earlier term must be a prefix of the term at its position; new
+    /// terms may follow. A rewritten or shortened query starts over.
+    #[must_use]
+    pub fn is_refined_by(&self, terms: &[String]) -> bool {
+        !self.terms.is_empty()
+            && terms.len() >= self.terms.len()
+            && self
+                .terms
+                .iter()
+                .zip(terms)
+                .all(|(old, new)| new.starts_with(old.as_str()))
+    }
+}
+
+/// One provider's answer to a search
+#[derive(Debug, Clone)]
+pub struct ProviderAnswer {
+    pub bus_name: String,
+    /// Every ID the provider returned, before capping to the result limit
+    pub result_set: ResultSet,
+    pub results: Vec<SearchResult>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(terms: &[&str]) -> ResultSet {
+        ResultSet {
+            terms: terms.iter().map(|t| (*t).to_string()).collect(),
+            ids: vec!["a".to_string()],
+        }
+    }
+
+    fn terms(terms: &[&str]) -> Vec<String> {
+        terms.iter().map(|t| (*t).to_string()).collect()
+    }
+
+    #[test]
+    fn test_result_set_is_refined_by_extended_terms() {
+        let previous = set(&["fire"]);
+        assert!(previous.is_refined_by(&terms(&["fire"])));
+        assert!(previous.is_refined_by(&terms(&["firefox"])));
+        assert!(previous.is_refined_by(&terms(&["fire", "fox"])));
+    }
+
+    #[test]
+    fn test_result_set_is_not_refined_by_rewritten_terms() {
+        let previous = set(&["fire", "fox"]);
+        assert!(!previous.is_refined_by(&terms(&["fire"])));
+        assert!(!previous.is_refined_by(&terms(&["fir", "fox"])));
+        assert!(!previous.is_refined_by(&terms(&["water", "fox"])));
+        assert!(!previous.is_refined_by(&terms(&["fox", "fire"])));
+        assert!(!ResultSet::default().is_refined_by(&terms(&["fire"])));
+    }
+}
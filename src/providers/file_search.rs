@@ -3,137 +3,611 @@
 //! This module provides file search and grep functionality by executing
 //! system commands (plocate, find, rg, grep) as subprocesses.
 //! Results are delivered asynchronously via channels.
+//!
+//! Each search has a preferred program and possibly a slower fallback, picked
+//! by [`choose_tool`]. When only the fallback is installed, the first search
+//! that uses it shows a one-time row saying so; when neither is installed,
+//! the search shows a row naming the missing programs instead of nothing.
+//!
+//! `:f` and `:ob` rank the file names they find with
+//! [`ranking::rank_paths`], which favours abbreviations of word starts. `:ob`
+//! lists the whole vault and filters it in-process, so `prjPlan` finds
+//! "ProjectPlanning.md"; `:f` falls back to a regular expression of the
+//! query's letters in order when the typed text is in no file name.
+//! Files and notes opened often then move up a few places, see
+//! [`ranking::boost_by_frecency`].
+//!
+//! Each output line becomes a [`CommandRow`] of the file or grep match it
+//! names. Grep output ends file names with a NUL byte rather than a colon,
+//! so files with colons in their names open at the right line.
 
-use std::path::Path;
-
-use gtk4::glib;
-use gtk4::prelude::ListModelExt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
-use crate::actions::which;
 use crate::core::global_state::get_home_dir;
-use crate::model::items::CommandItem;
+use crate::history::{file_key, now_secs, obsidian_key};
+use crate::model::command_row::CommandRow;
+use crate::model::items::{CommandItem, SectionHeaderItem};
 use crate::model::list_model::AppListModel;
-use crate::providers::{SubprocessRunner, spawn_subprocess};
+use crate::providers::ranking;
+use crate::providers::run_subprocess_with;
+use crate::providers::subprocess::{
+    CommandSpec, OutputLine, build_command, find_program, run_first_subprocess_with,
+};
+use gtk4::prelude::*;
+use log::{info, warn};
+
+/// Preferred programs whose fallback notice was already shown
+static NOTICES_SHOWN: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+/// The program a search runs, out of a preference list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolChoice {
+    /// The first program of the list is installed
+    Preferred(&'static str),
+    /// Only a later program is installed
+    Fallback {
+        program: &'static str,
+        /// The preferred program, which is missing
+        missing: &'static str,
+    },
+    /// None of the programs is installed
+    Missing,
+}
 
-/// Run a subprocess command and collect its output in a background thread
+/// Pick the first installed program of `programs`, most preferred first
 ///
-/// The command output is sent back to the main thread via a channel,
-/// then processed by a `SubprocessRunner` to update the UI.
-pub fn run_subprocess(model: &AppListModel, cmd: std::process::Command) {
-    let generation = model.state.task_gen();
-    let max_results = model.config.max_results.get();
-    let model_clone = model.clone();
+/// `installed` tells whether a program can be run; searches pass a lookup
+/// in the `PATH` of child processes.
+#[must_use]
+pub fn choose_tool(programs: &[&'static str], installed: impl Fn(&str) -> bool) -> ToolChoice {
+    match programs.iter().position(|p| installed(p)) {
+        Some(0) => ToolChoice::Preferred(programs[0]),
+        Some(i) => ToolChoice::Fallback {
+            program: programs[i],
+            missing: programs[0],
+        },
+        None => ToolChoice::Missing,
+    }
+}
 
-    let (tx, rx) = std::sync::mpsc::channel::<Vec<String>>();
+/// Name of a program as users know it
+fn tool_name(program: &str) -> &str {
+    match program {
+        "rg" => "ripgrep",
+        other => other,
+    }
+}
 
-    spawn_subprocess(move || cmd, max_results, tx);
+/// Row text shown while searching with a fallback
+#[must_use]
+pub fn fallback_notice(program: &str, missing: &str) -> String {
+    let name = tool_name(missing);
+    format!("{name} not found — using {program} (slower). Install {name} for better results.")
+}
 
-    let processor = |model: &AppListModel, _gen: u64, lines: Vec<String>| {
-        model.store.remove_all();
-        for line in lines {
-            model.store.append(&CommandItem::new(line));
-        }
-        if model.store.n_items() > 0 && model.selection.selected() == gtk4::INVALID_LIST_POSITION {
-            model.selection.set_selected(0);
-        }
+/// Row text shown when no program of `programs` is installed
+#[must_use]
+pub fn missing_notice(programs: &[&str]) -> String {
+    let names: Vec<&str> = programs.iter().map(|p| tool_name(p)).collect();
+    format!(
+        "{} not found — install {} to search",
+        names.join(" or "),
+        names.first().copied().unwrap_or_default()
+    )
+}
+
+/// Choose among `programs` installed in the `PATH` of child processes
+fn installed_tool(programs: &[&'static str]) -> ToolChoice {
+    choose_tool(programs, |p| find_program(p).is_some())
+}
+
+/// Notice for a search using `choice`, if it was not shown before
+fn notice_once(choice: ToolChoice) -> Option<String> {
+    let ToolChoice::Fallback { program, missing } = choice else {
+        return None;
     };
-    let runner = SubprocessRunner::new(rx, model_clone, generation, processor);
-    glib::idle_add_local_once(move || runner.poll());
+    let mut shown = NOTICES_SHOWN.lock().unwrap_or_else(|e| e.into_inner());
+    if shown.contains(&missing) {
+        return None;
+    }
+    shown.push(missing);
+    info!("{missing} not found, falling back to {program}");
+    Some(fallback_notice(program, missing))
 }
 
-/// Execute a file search command without using shell
-pub fn run_file_search(model: &AppListModel, argument: &str) {
-    let command = if which("plocate").is_some() {
-        let mut cmd = std::process::Command::new("plocate");
-        cmd.arg("-i")
+/// Replace the results with a single row explaining that no program is installed
+fn show_missing_tools(model: &AppListModel, programs: &[&str]) {
+    warn!("None of {programs:?} is installed");
+    model
+        .results
+        .replace_all(&[SectionHeaderItem::new(&missing_notice(programs)).upcast()]);
+}
+
+/// How output lines become rows
+#[derive(Debug, Clone)]
+enum LineFormat {
+    /// One file per line, a note if a vault is given
+    Paths(Option<PathBuf>),
+    /// Grep matches, in the notes of a vault if one is given
+    Grep {
+        vault: Option<PathBuf>,
+        /// Whether the command was run with `--column`
+        with_column: bool,
+    },
+}
+
+impl LineFormat {
+    /// The row of the output line `line`
+    fn row(&self, line: &OutputLine) -> CommandRow {
+        match self {
+            Self::Paths(vault) => CommandRow::from_listing(&line.raw, vault.as_deref()),
+            Self::Grep { vault, with_column } => {
+                CommandRow::from_grep(&line.raw, vault.as_deref(), *with_column)
+            }
+        }
+    }
+}
+
+/// Run a grep command in the background and show its matches
+///
+/// The command gets the normalized environment of [`build_command`]. The
+/// matches replace the current results, below an optional informational
+/// row.
+fn run_grep(model: &AppListModel, spec: &CommandSpec, format: LineFormat, notice: Option<String>) {
+    run_subprocess_with(model, build_command(spec), move |model, lines| {
+        show_lines(model, notice.as_deref(), &format, lines);
+    });
+}
+
+/// Output lines read from a file listing before it is ranked
+const RANKED_LINES_LIMIT: usize = 5_000;
+
+/// Run the first of `specs` that finds anything and show the listed files
+/// best first for `query`
+///
+/// Files used often move up a few places, looked up in the launch history
+/// by `history_key`.
+fn run_ranked_listing(
+    model: &AppListModel,
+    specs: &[CommandSpec],
+    query: &str,
+    notice: Option<String>,
+    format: LineFormat,
+    history_key: impl Fn(&str) -> Option<String> + 'static,
+) {
+    let cmds = specs.iter().map(build_command).collect();
+    let query = query.to_string();
+    run_first_subprocess_with(model, cmds, RANKED_LINES_LIMIT, move |model, lines| {
+        let mut ranked = ranking::rank_paths(
+            &*model.config.matcher.borrow(),
+            lines,
+            |line| line.display.as_str(),
+            &query,
+            model.config.max_results.get(),
+        );
+        let history = model.config.history.borrow();
+        let now = now_secs();
+        ranking::boost_by_frecency(&mut ranked, |line| {
+            history_key(&line.display).map_or(0.0, |key| history.frecency(&key, now))
+        });
+        drop(history);
+        show_lines(model, notice.as_deref(), &format, ranked);
+    });
+}
+
+/// Replace the results with the rows of `lines` in `format`, below an
+/// optional notice row
+fn show_lines(
+    model: &AppListModel,
+    notice: Option<&str>,
+    format: &LineFormat,
+    lines: Vec<OutputLine>,
+) {
+    model.results.remove_all();
+    if let Some(notice) = notice {
+        model.results.append(&SectionHeaderItem::new(notice));
+    }
+    for line in lines {
+        model.results.append(&CommandItem::new(format.row(&line)));
+    }
+    if notice.is_some() && model.results.n_items() > 1 {
+        model.results.set_selected(1);
+    } else {
+        model.results.select_first_if_unselected();
+    }
+}
+
+/// Regular expression body matching the letters and digits of `query` in
+/// order within one file name, or `None` for fewer than two of them
+///
+/// `usr_cfg` gives `u[^/]*s[^/]*r[^/]*c[^/]*f[^/]*g`; the callers anchor it
+/// to the last path component.
+fn abbreviation_pattern(query: &str) -> Option<String> {
+    let chars: Vec<String> = query
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .map(String::from)
+        .collect();
+    (chars.len() >= 2 && !query.contains(char::is_whitespace)).then(|| chars.join("[^/]*"))
+}
+
+/// File searches for `argument`: the substring search, then the
+/// abbreviation search used when the first finds nothing
+fn file_search_specs(choice: ToolChoice, argument: &str) -> Vec<CommandSpec> {
+    let plocate = matches!(choice, ToolChoice::Preferred(_));
+    let substring = if plocate {
+        CommandSpec::new("plocate")
+            .arg("-i")
             .arg("--")
             .arg(argument)
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::null());
-        cmd
     } else {
-        let home = get_home_dir();
-        let mut cmd = std::process::Command::new("find");
-        cmd.arg(home)
+        CommandSpec::new("find")
+            .arg(get_home_dir())
             .arg("-type")
             .arg("f")
             .arg("-iname")
             .arg(format!("*{argument}*"))
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::null());
-        cmd
     };
+    let Some(pattern) = abbreviation_pattern(argument) else {
+        return vec![substring];
+    };
+    let abbreviation = if plocate {
+        CommandSpec::new("plocate")
+            .arg("-i")
+            .arg("--regex")
+            .arg(format!("/[^/]*{pattern}[^/]*$"))
+    } else {
+        CommandSpec::new("find")
+            .arg(get_home_dir())
+            .arg("-type")
+            .arg("f")
+            .arg("-regextype")
+            .arg("posix-extended")
+            .arg("-iregex")
+            .arg(format!(".*/[^/]*{pattern}[^/]*"))
+    };
+    vec![substring, abbreviation]
+}
+
+/// Execute a file search command without using shell
+///
+/// Uses `plocate`, or `find` over the home directory without it.
+pub fn run_file_search(model: &AppListModel, argument: &str) {
+    const TOOLS: &[&str] = &["plocate", "find"];
+    let choice = installed_tool(TOOLS);
+    if choice == ToolChoice::Missing {
+        return show_missing_tools(model, TOOLS);
+    }
+    let specs = file_search_specs(choice, argument);
+    let notice = notice_once(choice);
+    run_ranked_listing(
+        model,
+        &specs,
+        argument,
+        notice,
+        LineFormat::Paths(None),
+        |path| Some(file_key(path)),
+    );
+}
 
-    run_subprocess(model, command);
+/// Whether the grep command of [`grep_spec`] for `choice` prints columns
+///
+/// Only `rg` is run with `--column`.
+fn grep_prints_column(choice: ToolChoice) -> bool {
+    matches!(choice, ToolChoice::Preferred(_))
 }
 
-/// Execute a file grep command without using shell
-pub fn run_file_grep(model: &AppListModel, argument: &str) {
-    let command = if which("rg").is_some() {
-        let home = get_home_dir();
-        let mut cmd = std::process::Command::new("rg");
-        cmd.arg("--with-filename")
+/// Grep `root` recursively with `rg`, or `grep` without it
+///
+/// `grep_include` limits the `grep` fallback to matching file names.
+/// `exclude` lists folders of `root` to skip; `grep` can only skip folders
+/// by name, so it skips every folder named like the last component. Both
+/// programs end file names with a NUL byte.
+fn grep_spec(
+    choice: ToolChoice,
+    pattern: &str,
+    root: &Path,
+    grep_include: Option<&str>,
+    exclude: &[String],
+) -> CommandSpec {
+    if grep_prints_column(choice) {
+        let spec = CommandSpec::new("rg")
+            .arg("-i")
+            .arg("--with-filename")
             .arg("--line-number")
+            .arg("--column")
             .arg("--no-heading")
-            .arg("-i")
-            .arg(argument)
-            .arg(home)
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::null());
-        cmd
-    } else {
-        let home = get_home_dir();
-        let mut cmd = std::process::Command::new("grep");
-        cmd.arg("-r")
-            .arg("-i")
-            .arg("-n")
-            .arg("-I")
-            .arg("-H")
-            .arg("--")
-            .arg(argument)
-            .arg(home)
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::null());
-        cmd
+            .arg("--null")
+            .arg("--color=never");
+        let spec = exclude.iter().fold(spec, |spec, folder| {
+            spec.arg("--glob").arg(format!("!{folder}/**"))
+        });
+        return spec.arg("--").arg(pattern).arg(root);
+    }
+    let spec = CommandSpec::new("grep")
+        .arg("-r")
+        .arg("-n")
+        .arg("-i")
+        .arg("-I")
+        .arg("-H")
+        .arg("-Z")
+        .arg("--color=never");
+    let spec = match grep_include {
+        Some(glob) => spec.arg(format!("--include={glob}")),
+        None => spec,
     };
+    let spec = exclude.iter().fold(spec, |spec, folder| {
+        let name = folder.rsplit('/').next().unwrap_or(folder);
+        spec.arg(format!("--exclude-dir={name}"))
+    });
+    spec.arg("--").arg(pattern).arg(root)
+}
 
-    run_subprocess(model, command);
+/// The grep programs, most preferred first
+const GREP_TOOLS: &[&str] = &["rg", "grep"];
+
+/// Execute a file grep command without using shell
+pub fn run_file_grep(model: &AppListModel, argument: &str) {
+    let choice = installed_tool(GREP_TOOLS);
+    if choice == ToolChoice::Missing {
+        return show_missing_tools(model, GREP_TOOLS);
+    }
+    let spec = grep_spec(choice, argument, Path::new(get_home_dir()), None, &[]);
+    let format = LineFormat::Grep {
+        vault: None,
+        with_column: grep_prints_column(choice),
+    };
+    run_grep(model, &spec, format, notice_once(choice));
 }
 
-/// Run `find` command to search for files in Obsidian vault
-pub fn run_find_in_vault(model: &AppListModel, vault_path: &Path, pattern: &str) {
-    let mut cmd = std::process::Command::new("find");
-    cmd.arg(vault_path)
+/// `find` listing the files of `vault`, skipping hidden folders such as
+/// `.obsidian` and the `exclude` folders
+fn vault_listing_spec(vault_path: &Path, exclude: &[String]) -> CommandSpec {
+    let spec = CommandSpec::new("find")
+        .arg(vault_path)
+        .arg("-mindepth")
+        .arg("1")
+        .arg("(")
+        .arg("-name")
+        .arg(".*");
+    let spec = exclude.iter().fold(spec, |spec, folder| {
+        spec.arg("-o").arg("-path").arg(vault_path.join(folder))
+    });
+    spec.arg(")")
+        .arg("-prune")
+        .arg("-o")
         .arg("-type")
         .arg("f")
-        .arg("-iname")
-        .arg(format!("*{pattern}*"));
-    run_subprocess(model, cmd);
+        .arg("-print")
+}
+
+/// List the files of the Obsidian vault and show those matching `pattern`
+///
+/// Hidden folders such as `.obsidian` and the `exclude` folders are
+/// skipped. The listing is filtered in-process, so abbreviations like
+/// `prjPlan` match.
+pub fn run_find_in_vault(
+    model: &AppListModel,
+    vault_path: &Path,
+    pattern: &str,
+    exclude: &[String],
+) {
+    const TOOLS: &[&str] = &["find"];
+    if installed_tool(TOOLS) == ToolChoice::Missing {
+        return show_missing_tools(model, TOOLS);
+    }
+    let spec = vault_listing_spec(vault_path, exclude);
+    let vault = vault_path.to_path_buf();
+    let format = LineFormat::Paths(Some(vault.clone()));
+    run_ranked_listing(model, &[spec], pattern, None, format, move |path| {
+        obsidian_key(&vault, path)
+    });
 }
 
 /// Run `rg` (ripgrep with grep fallback) command to search file contents in Obsidian vault
-pub fn run_rg_in_vault(model: &AppListModel, vault_path: &Path, pattern: &str) {
-    if which("rg").is_some() {
-        let mut cmd = std::process::Command::new("rg");
-        cmd.arg("-i")
-            .arg("--with-filename")
-            .arg("--line-number")
-            .arg("--no-heading")
-            .arg("--color=never")
-            .arg(pattern)
-            .arg(vault_path);
-        run_subprocess(model, cmd);
-    } else {
-        let mut cmd = std::process::Command::new("grep");
-        cmd.arg("-r")
-            .arg("-n")
-            .arg("-i")
-            .arg("-I")
-            .arg("-H")
-            .arg("--color=never")
-            .arg("--")
-            .arg(pattern)
-            .arg(vault_path);
-        run_subprocess(model, cmd);
+///
+/// The `grep` fallback only searches Markdown notes. Folders in `exclude`
+/// are skipped.
+pub fn run_rg_in_vault(model: &AppListModel, vault_path: &Path, pattern: &str, exclude: &[String]) {
+    let choice = installed_tool(GREP_TOOLS);
+    if choice == ToolChoice::Missing {
+        return show_missing_tools(model, GREP_TOOLS);
+    }
+    let spec = grep_spec(choice, pattern, vault_path, Some("*.md"), exclude);
+    let format = LineFormat::Grep {
+        vault: Some(vault_path.to_path_buf()),
+        with_column: grep_prints_column(choice),
+    };
+    run_grep(model, &spec, format, notice_once(choice));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_choose_tool() {
+        let tools = &["rg", "grep"];
+        assert_eq!(choose_tool(tools, |_| true), ToolChoice::Preferred("rg"));
+        assert_eq!(
+            choose_tool(tools, |p| p == "grep"),
+            ToolChoice::Fallback {
+                program: "grep",
+                missing: "rg"
+            }
+        );
+        assert_eq!(choose_tool(tools, |_| false), ToolChoice::Missing);
+        assert_eq!(choose_tool(&["find"], |_| false), ToolChoice::Missing);
+        assert_eq!(choose_tool(&[], |_| true), ToolChoice::Missing);
+    }
+
+    #[test]
+    fn test_notices() {
+        assert_eq!(
+            fallback_notice("grep", "rg"),
+            "ripgrep not found — using grep (slower). Install ripgrep for better results."
+        );
+        assert_eq!(
+            missing_notice(&["plocate", "find"]),
+            "plocate or find not found — install plocate to search"
+        );
+    }
+
+    #[test]
+    fn test_abbreviation_pattern() {
+        assert_eq!(
+            abbreviation_pattern("usr_cfg").as_deref(),
+            Some("u[^/]*s[^/]*r[^/]*c[^/]*f[^/]*g")
+        );
+        assert_eq!(abbreviation_pattern("a.b").as_deref(), Some("a[^/]*b"));
+        assert_eq!(abbreviation_pattern("x"), None);
+        assert_eq!(abbreviation_pattern("._"), None);
+        assert_eq!(abbreviation_pattern("invoice 2024"), None);
+    }
+
+    #[test]
+    fn test_file_search_specs() {
+        let args = |spec: &CommandSpec| -> Vec<String> {
+            spec.args
+                .iter()
+                .map(|a| a.to_string_lossy().into_owned())
+                .collect()
+        };
+        let specs = file_search_specs(ToolChoice::Preferred("plocate"), "usr_cfg");
+        assert_eq!(specs.len(), 2);
+        assert_eq!(args(&specs[0]), ["-i", "--", "usr_cfg"]);
+        assert_eq!(
+            args(&specs[1]),
+            [
+                "-i",
+                "--regex",
+                "/[^/]*u[^/]*s[^/]*r[^/]*c[^/]*f[^/]*g[^/]*$"
+            ]
+        );
+
+        let fallback = ToolChoice::Fallback {
+            program: "find",
+            missing: "plocate",
+        };
+        let specs = file_search_specs(fallback, "cv");
+        assert_eq!(specs[1].program, "find");
+        assert_eq!(args(&specs[1])[5..], ["-iregex", ".*/[^/]*c[^/]*v[^/]*"]);
+
+        assert_eq!(file_search_specs(fallback, "c").len(), 1);
+    }
+
+    #[test]
+    fn test_grep_spec_fallback_matches_rg_output() {
+        let vault = Path::new("/home/u/vault");
+        let rg = grep_spec(
+            ToolChoice::Preferred("rg"),
+            "todo",
+            vault,
+            Some("*.md"),
+            &[],
+        );
+        assert_eq!(rg.program, "rg");
+        assert!(!rg.args.iter().any(|a| a == "--include=*.md"));
+        assert!(rg.args.iter().any(|a| a == "--null"));
+        assert!(grep_prints_column(ToolChoice::Preferred("rg")));
+        assert!(rg.args.iter().any(|a| a == "--column"));
+
+        let fallback = ToolChoice::Fallback {
+            program: "grep",
+            missing: "rg",
+        };
+        let grep = grep_spec(fallback, "-todo", vault, Some("*.md"), &[]);
+        assert_eq!(grep.program, "grep");
+        assert!(!grep_prints_column(fallback));
+        let args: Vec<&str> = grep.args.iter().filter_map(|a| a.to_str()).collect();
+        assert_eq!(
+            args,
+            [
+                "-r",
+                "-n",
+                "-i",
+                "-I",
+                "-H",
+                "-Z",
+                "--color=never",
+                "--include=*.md",
+                "--",
+                "-todo",
+                "/home/u/vault"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_vault_excludes_in_argv() {
+        let vault = Path::new("/home/u/vault");
+        let exclude = ["Templates".to_string(), "Meta/Drafts".to_string()];
+        let args = |spec: &CommandSpec| -> Vec<String> {
+            spec.args
+                .iter()
+                .map(|a| a.to_string_lossy().into_owned())
+                .collect()
+        };
+
+        let rg = grep_spec(
+            ToolChoice::Preferred("rg"),
+            "todo",
+            vault,
+            Some("*.md"),
+            &exclude,
+        );
+        assert_eq!(
+            args(&rg)[7..],
+            [
+                "--glob",
+                "!Templates/**",
+                "--glob",
+                "!Meta/Drafts/**",
+                "--",
+                "todo",
+                "/home/u/vault"
+            ]
+        );
+
+        let fallback = ToolChoice::Fallback {
+            program: "grep",
+            missing: "rg",
+        };
+        let grep = grep_spec(fallback, "todo", vault, Some("*.md"), &exclude);
+        assert_eq!(
+            args(&grep)[7..10],
+            [
+                "--include=*.md",
+                "--exclude-dir=Templates",
+                "--exclude-dir=Drafts"
+            ]
+        );
+
+        let find = vault_listing_spec(vault, &exclude);
+        assert_eq!(find.program, "find");
+        assert_eq!(
+            args(&find),
+            [
+                "/home/u/vault",
+                "-mindepth",
+                "1",
+                "(",
+                "-name",
+                ".*",
+                "-o",
+                "-path",
+                "/home/u/vault/Templates",
+                "-o",
+                "-path",
+                "/home/u/vault/Meta/Drafts",
+                ")",
+                "-prune",
+                "-o",
+                "-type",
+                "f",
+                "-print"
+            ]
+        );
     }
 }
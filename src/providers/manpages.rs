@@ -0,0 +1,417 @@
+//! Manual pages for the `:man` mode
+//!
+//! The pages come from the `whatis` database through `apropos .`, the same
+//! listing `man -k .` prints, one line per page such as
+//! `ls (1) - list directory contents`. It is read once on a worker thread,
+//! the first time `:man` is used, and filtered in process for every query.
+//!
+//! Unlike other parsed commands, `apropos` and `man` keep the user's locale,
+//! so translated pages and descriptions are shown. The parser therefore
+//! only relies on the layout of a line: names with their section in
+//! parentheses, possibly several separated by commas as mandoc prints them,
+//! then a dash and the description.
+//!
+//! Opening a page renders it with `man -Tutf8` for the viewer of the
+//! launcher. The bold and underline that `grotty` adds, as overstrikes or
+//! escape sequences, are removed by [`strip_formatting`].
+
+use crate::providers::ranking::abbreviation_score;
+use crate::providers::subprocess::{CommandSpec, build_command};
+use fuzzy_matcher::FuzzyMatcher;
+use log::{debug, warn};
+use std::collections::HashSet;
+use std::ffi::OsString;
+
+/// Width pages are rendered at, in columns
+pub const RENDER_WIDTH: u32 = 80;
+
+/// Separators between the names and the description of an `apropos` line:
+/// man-db and mandoc print a hyphen, some translations a dash
+const SEPARATORS: [&str; 3] = [" - ", " — ", " – "];
+
+/// A manual page listed by `apropos`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ManPage {
+    pub name: String,
+    /// Section, e.g. `1`, `3p` or `n`
+    pub section: String,
+    pub description: String,
+}
+
+impl ManPage {
+    /// Name with its section, as `ls(1)`
+    #[must_use]
+    pub fn title(&self) -> String {
+        format!("{}({})", self.name, self.section)
+    }
+
+    /// Arguments of `man` that open this page
+    #[must_use]
+    pub fn man_args(&self) -> [String; 2] {
+        [self.section.clone(), self.name.clone()]
+    }
+}
+
+/// Manual pages loaded for the window
+#[derive(Debug, Default)]
+pub enum ManPageCache {
+    /// Nothing was requested yet
+    #[default]
+    Unloaded,
+    /// A worker is running `apropos`
+    Loading,
+    Loaded(Vec<ManPage>),
+}
+
+/// The user's `LC_ALL`, so that commands keep their translations
+///
+/// An empty value is the same as an unset one.
+fn user_locale() -> OsString {
+    std::env::var_os("LC_ALL").unwrap_or_default()
+}
+
+/// List every manual page of the `whatis` database
+///
+/// Returns nothing if `apropos` is missing or the database is empty.
+#[must_use]
+pub fn load_man_pages() -> Vec<ManPage> {
+    let spec = CommandSpec::new("apropos")
+        .arg(".")
+        .env("LC_ALL", user_locale());
+    match build_command(&spec).output() {
+        Ok(out) => {
+            let pages = parse_apropos(&String::from_utf8_lossy(&out.stdout));
+            debug!("[man] {} manual page(s) listed", pages.len());
+            pages
+        }
+        Err(e) => {
+            warn!("[man] Failed to run apropos: {e}");
+            Vec::new()
+        }
+    }
+}
+
+/// Parse `apropos` or `man -k` output into pages, in output order
+///
+/// A line naming several pages gives one page for each. Lines that are not
+/// page entries, such as a translated "nothing appropriate", are skipped,
+/// and so are pages already listed.
+#[must_use]
+pub fn parse_apropos(output: &str) -> Vec<ManPage> {
+    let mut seen = HashSet::new();
+    output
+        .lines()
+        .flat_map(parse_apropos_line)
+        .filter(|page| seen.insert((page.name.clone(), page.section.clone())))
+        .collect()
+}
+
+/// Pages of one `apropos` line
+///
+/// man-db prints `name (1) - description` and mandoc `name(1) - description`;
+/// mandoc also lists the other names of a page, `gzip, gunzip, zcat(1)`,
+/// where the section at the end applies to every name before it.
+fn parse_apropos_line(line: &str) -> Vec<ManPage> {
+    let Some((names, description)) = split_description(line) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<(&str, Option<&str>)> = names
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(split_section)
+        .collect();
+    // A name without a section takes the one of the next name that has it
+    let mut section = None;
+    for entry in entries.iter_mut().rev() {
+        if entry.1.is_some() {
+            section = entry.1;
+        } else {
+            entry.1 = section;
+        }
+    }
+    entries
+        .into_iter()
+        .filter_map(|(name, section)| {
+            Some(ManPage {
+                name: name.to_string(),
+                section: section?.to_string(),
+                description: description.to_string(),
+            })
+        })
+        .filter(|page| !page.name.is_empty())
+        .collect()
+}
+
+/// Split a line at the first separator that follows a section
+///
+/// Descriptions may contain dashes themselves, so only a separator right
+/// after a closing parenthesis counts.
+fn split_description(line: &str) -> Option<(&str, &str)> {
+    let mut best: Option<(usize, &str)> = None;
+    for sep in SEPARATORS {
+        let mut from = 0;
+        while let Some(offset) = line[from..].find(sep) {
+            let at = from + offset;
+            if line[..at].trim_end().ends_with(')') {
+                if best.is_none_or(|(b, _)| at < b) {
+                    best = Some((at, sep));
+                }
+                break;
+            }
+            from = at + sep.len();
+        }
+    }
+    let (at, sep) = best?;
+    Some((line[..at].trim(), line[at + sep.len()..].trim()))
+}
+
+/// Split `ls (1)` or `ls(1)` into name and section; a bare name has none
+fn split_section(entry: &str) -> (&str, Option<&str>) {
+    if let Some(rest) = entry.strip_suffix(')')
+        && let Some((name, section)) = rest.rsplit_once('(')
+        && !section.is_empty()
+        && !section.contains(char::is_whitespace)
+    {
+        return (name.trim_end(), Some(section));
+    }
+    (entry, None)
+}
+
+/// Pages matching `query`, best first, at most `max`
+///
+/// Every word has to match the page name or its description, the name
+/// counting more. An empty query lists the pages in `apropos` order.
+#[must_use]
+pub fn match_man_pages<'a>(
+    matcher: &impl FuzzyMatcher,
+    query: &str,
+    pages: &'a [ManPage],
+    max: usize,
+) -> Vec<&'a ManPage> {
+    let words: Vec<&str> = query.split_whitespace().collect();
+    if words.is_empty() {
+        return pages.iter().take(max).collect();
+    }
+    let mut scored: Vec<(i64, &ManPage)> = pages
+        .iter()
+        .filter_map(|page| {
+            words
+                .iter()
+                .map(|word| {
+                    let name = abbreviation_score(matcher, &page.name, word);
+                    // The description only helps, the name ranks first
+                    let description =
+                        abbreviation_score(matcher, &page.description, word).map(|score| score / 2);
+                    name.max(description)
+                })
+                .sum::<Option<i64>>()
+                .map(|score| (score, page))
+        })
+        .collect();
+    // Stable, so equal scores keep the section order of apropos
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().take(max).map(|(_, p)| p).collect()
+}
+
+/// Render `page` as plain text with `man -Tutf8`
+///
+/// Returns `None` if `man` is missing, fails or prints nothing.
+#[must_use]
+pub fn render_man_page(page: &ManPage) -> Option<String> {
+    let [section, name] = page.man_args();
+    let spec = CommandSpec::new("man")
+        .arg("-Tutf8")
+        .arg(section)
+        .arg(name)
+        .env("LC_ALL", user_locale())
+        .env("MANWIDTH", RENDER_WIDTH.to_string());
+    let out = match build_command(&spec).output() {
+        Ok(out) => out,
+        Err(e) => {
+            warn!("[man] Failed to run man: {e}");
+            return None;
+        }
+    };
+    if !out.status.success() {
+        warn!("[man] man {} exited with {}", page.title(), out.status);
+        return None;
+    }
+    let text = strip_formatting(&String::from_utf8_lossy(&out.stdout));
+    (!text.trim().is_empty()).then_some(text)
+}
+
+/// Remove terminal formatting from `grotty` output
+///
+/// Bold and underline come either as overstrikes, a character, a backspace
+/// and the character to print over it, or as escape sequences: SGR colors
+/// and OSC 8 hyperlinks. Only the printed characters are kept.
+#[must_use]
+pub fn strip_formatting(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            // Overstrike: the next character replaces the previous one
+            '\u{8}' => {
+                out.pop();
+            }
+            '\u{1b}' => match chars.next() {
+                // CSI, e.g. `ESC [ 1 m`, ends with a byte from `@` to `~`
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC, e.g. a hyperlink, ends with BEL or `ESC \`
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\u{7}' {
+                            break;
+                        }
+                        if c == '\u{1b}' {
+                            chars.next_if_eq(&'\\');
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuzzy_matcher::skim::SkimMatcherV2;
+
+    fn page(name: &str, section: &str, description: &str) -> ManPage {
+        ManPage {
+            name: name.to_string(),
+            section: section.to_string(),
+            description: description.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_apropos_man_db() {
+        let output = "\
+[ (1)                - check file types and compare values
+ls (1)               - list directory contents
+SSL_read (3ssl)      - read bytes from a TLS/SSL connection
+printf (1p)          - write formatted output
+after (n)            - Execute a command after a time delay
+";
+        assert_eq!(
+            parse_apropos(output),
+            vec![
+                page("[", "1", "check file types and compare values"),
+                page("ls", "1", "list directory contents"),
+                page("SSL_read", "3ssl", "read bytes from a TLS/SSL connection"),
+                page("printf", "1p", "write formatted output"),
+                page("after", "n", "Execute a command after a time delay"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_apropos_multiple_names() {
+        // mandoc: one section for every name
+        let pages = parse_apropos("gzip, gunzip, zcat(1) - compression/decompression tool\n");
+        assert_eq!(
+            pages,
+            vec![
+                page("gzip", "1", "compression/decompression tool"),
+                page("gunzip", "1", "compression/decompression tool"),
+                page("zcat", "1", "compression/decompression tool"),
+            ]
+        );
+        // Names with their own sections
+        let pages = parse_apropos("crontab (1), crontab (5) - tables for driving cron\n");
+        assert_eq!(
+            pages,
+            vec![
+                page("crontab", "1", "tables for driving cron"),
+                page("crontab", "5", "tables for driving cron"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_apropos_localized() {
+        let output = "\
+ls (1)               - Verzeichnisinhalte auflisten
+cp (1)               — copier des fichiers et des répertoires
+foo (8)              - (unbekannter Gegenstand)
+.: nichts passendes.
+";
+        assert_eq!(
+            parse_apropos(output),
+            vec![
+                page("ls", "1", "Verzeichnisinhalte auflisten"),
+                page("cp", "1", "copier des fichiers et des répertoires"),
+                page("foo", "8", "(unbekannter Gegenstand)"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_apropos_dash_in_description() {
+        let pages = parse_apropos(
+            "git-add (1)          - Add file contents to the index - staging area\n\
+             x - y without section\n\
+             git-add (1)          - Add file contents to the index - staging area\n",
+        );
+        assert_eq!(
+            pages,
+            vec![page(
+                "git-add",
+                "1",
+                "Add file contents to the index - staging area"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_strip_formatting() {
+        // Bold and underline as overstrikes
+        assert_eq!(strip_formatting("N\u{8}NA\u{8}AM\u{8}ME\u{8}E"), "NAME");
+        assert_eq!(strip_formatting("_\u{8}f_\u{8}i_\u{8}l_\u{8}e"), "file");
+        // SGR and an OSC 8 hyperlink
+        assert_eq!(
+            strip_formatting(
+                "\u{1b}[1mls\u{1b}[0m(1) \u{1b}]8;;man:ls(1)\u{1b}\\link\u{1b}]8;;\u{1b}\\"
+            ),
+            "ls(1) link"
+        );
+        assert_eq!(strip_formatting("é — ü\n"), "é — ü\n");
+    }
+
+    #[test]
+    fn test_match_man_pages() {
+        let pages = [
+            page("ls", "1", "list directory contents"),
+            page("lsblk", "8", "list block devices"),
+            page("printf", "3", "formatted output conversion"),
+            page("printf", "1", "format and print data"),
+        ];
+        let matcher = SkimMatcherV2::default();
+        assert_eq!(match_man_pages(&matcher, "", &pages, 2).len(), 2);
+
+        let found = match_man_pages(&matcher, "lsblk", &pages, 10);
+        assert_eq!(found[0].name, "lsblk");
+        let titles: Vec<String> = match_man_pages(&matcher, "printf", &pages, 10)
+            .iter()
+            .map(|p| p.title())
+            .collect();
+        assert_eq!(titles, ["printf(3)", "printf(1)"]);
+        let found = match_man_pages(&matcher, "block devices", &pages, 10);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "lsblk");
+    }
+}
@@ -0,0 +1,65 @@
+//! The fuzzy matcher shared by app search, `:b` and `:c`
+//!
+//! `SkimMatcherV2` keeps per-thread scratch buffers, so one instance is
+//! built at startup and reused for every keystroke instead of constructing
+//! a matcher per search. [`build_matcher`] applies the `[search]` settings
+//! explicitly rather than relying on the crate defaults; a settings change
+//! replaces the shared instance.
+
+use crate::core::config::FuzzyCase;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Matcher shared by the model and its providers, rebuilt on config changes
+pub type SharedMatcher = Rc<RefCell<SkimMatcherV2>>;
+
+/// Build a matcher with the given case sensitivity and element limit
+///
+/// `element_limit` caps the scoring matrix (query × text length); larger
+/// inputs fall back to a greedy match. 0 means no limit.
+#[must_use]
+pub fn build_matcher(case: FuzzyCase, element_limit: usize) -> SkimMatcherV2 {
+    let matcher = SkimMatcherV2::default()
+        .use_cache(true)
+        .element_limit(element_limit);
+    match case {
+        FuzzyCase::Smart => matcher.smart_case(),
+        FuzzyCase::Ignore => matcher.ignore_case(),
+        FuzzyCase::Respect => matcher.respect_case(),
+    }
+}
+
+/// A shared matcher with the default settings
+#[must_use]
+pub fn shared_matcher() -> SharedMatcher {
+    Rc::new(RefCell::new(build_matcher(FuzzyCase::default(), 0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuzzy_matcher::FuzzyMatcher;
+
+    #[test]
+    fn test_build_matcher_case() {
+        let smart = build_matcher(FuzzyCase::Smart, 0);
+        assert!(smart.fuzzy_match("Firefox", "fire").is_some());
+        assert!(smart.fuzzy_match("firefox", "Fire").is_none());
+
+        let ignore = build_matcher(FuzzyCase::Ignore, 0);
+        assert!(ignore.fuzzy_match("firefox", "Fire").is_some());
+
+        let respect = build_matcher(FuzzyCase::Respect, 0);
+        assert!(respect.fuzzy_match("Firefox", "fire").is_none());
+        assert!(respect.fuzzy_match("Firefox", "Fire").is_some());
+    }
+
+    #[test]
+    fn test_build_matcher_element_limit_still_matches() {
+        let text = "x".repeat(500) + "needle";
+        let limited = build_matcher(FuzzyCase::Smart, 100);
+        assert!(limited.fuzzy_match(&text, "needle").is_some());
+        assert!(limited.fuzzy_match(&text, "zzz").is_none());
+    }
+}
@@ -5,19 +5,33 @@
 //! This abstraction allows adding new search sources without modifying the core
 //! list model logic.
 
+pub mod bookmarks;
 pub mod dbus;
 pub mod file_search;
+pub mod manpages;
+pub mod matcher;
+pub mod pass;
+pub mod projects;
+pub mod ranking;
+pub mod recent;
+pub mod snippets;
+pub mod ssh;
 pub mod subprocess;
+pub mod web_search;
 
-pub use subprocess::{SubprocessRunner, spawn_subprocess};
+pub use subprocess::{
+    CommandSpec, OutputLine, build_command, command_output_lines, run_subprocess_with,
+};
 
-use crate::core::config::CommandConfig;
+use crate::core::config::{AngleUnit, CommandConfig};
+use crate::history::{LaunchHistory, app_key, now_secs};
 use crate::launcher::DesktopApp;
+use crate::model::command_row::CommandRow;
 use crate::model::items::{AppItem, CommandItem};
-use fuzzy_matcher::FuzzyMatcher;
-use fuzzy_matcher::skim::SkimMatcherV2;
 use gtk4::glib;
 use gtk4::prelude::Cast;
+use matcher::SharedMatcher;
+use ranking::FieldWeights;
 use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
@@ -37,6 +51,9 @@ pub trait SearchProvider {
 
     /// Update the maximum number of results to return
     fn set_max_results(&self, _max: usize) {}
+
+    /// Match or skip the descriptions of results
+    fn set_match_descriptions(&self, _enabled: bool) {}
 }
 
 /// Trait representing a command provider that can return commands
@@ -60,29 +77,62 @@ pub trait CommandProvider {
 
 pub struct AppProvider {
     all_apps: Rc<RefCell<Vec<DesktopApp>>>,
+    history: Rc<RefCell<LaunchHistory>>,
     max_results: Cell<usize>,
-    fuzzy_matcher: Rc<SkimMatcherV2>,
+    fuzzy_matcher: SharedMatcher,
+    field_weights: Cell<FieldWeights>,
 }
 
 impl AppProvider {
-    pub fn new(all_apps: Rc<RefCell<Vec<DesktopApp>>>, max_results: usize) -> Self {
+    pub fn new(
+        all_apps: Rc<RefCell<Vec<DesktopApp>>>,
+        history: Rc<RefCell<LaunchHistory>>,
+        fuzzy_matcher: SharedMatcher,
+        max_results: usize,
+    ) -> Self {
         Self {
             all_apps,
+            history,
             max_results: Cell::new(max_results),
-            fuzzy_matcher: Rc::new(SkimMatcherV2::default()),
+            fuzzy_matcher,
+            field_weights: Cell::new(FieldWeights::default()),
         }
     }
 
+    /// Order matches by score plus launch frecency and keep the best ones
+    fn rank_by_frecency<'a>(
+        &self,
+        mut scored: Vec<(u8, &'a DesktopApp)>,
+        max_results: usize,
+    ) -> Vec<(u8, &'a DesktopApp)> {
+        let history = self.history.borrow();
+        let now = now_secs();
+        ranking::sort_by_frecency(&mut scored, |app| {
+            history.frecency(&app_key(&app.desktop_id), now)
+        });
+        scored.truncate(max_results);
+        scored
+    }
+
     /// Optimized search that uses prefix matching for simple queries
+    ///
+    /// Each match is paired with a score from 0 to 100: prefix matches score
+    /// `PREFIX_MATCH_SCORE`, substring matches `SUBSTRING_MATCH_SCORE`,
+    /// generic name and keyword matches `FIELD_MATCH_SCORE`, and fuzzy
+    /// matches are scaled relative to the best fuzzy hit so they never
+    /// exceed `FUZZY_MATCH_MAX_SCORE`. Fuzzy matching is done per query word
+    /// across name, generic name, keywords and description (see [`ranking`]). Within the
+    /// results, frequently launched apps get a bounded bonus, and the empty
+    /// query lists the most used apps first.
     fn search_apps_optimized<'a>(
         &self,
         query: &str,
         apps: &'a [DesktopApp],
         max_results: usize,
-    ) -> Vec<&'a DesktopApp> {
-        // Fast path: empty query returns first N apps
+    ) -> Vec<(u8, &'a DesktopApp)> {
+        // Fast path: empty query returns the most used apps, then the rest
         if query.is_empty() {
-            return apps.iter().take(max_results).collect();
+            return self.rank_by_frecency(apps.iter().map(|app| (0, app)).collect(), max_results);
         }
 
         let query_lower = query.to_lowercase();
@@ -90,55 +140,75 @@ impl AppProvider {
         // Fast path: simple prefix match for short, single-word queries
         // This covers 80% of typical searches
         if !query.contains(char::is_whitespace) && query.len() < 15 {
-            let mut scored: Vec<_> = apps
-                .iter()
-                .filter_map(|app| {
-                    if app.name_lower.starts_with(&query_lower) {
-                        Some((100, app))
-                    } else if app.name_lower.contains(&query_lower) {
-                        Some((50, app))
-                    } else {
-                        None
-                    }
-                })
-                .collect();
-
-            scored.sort_unstable_by(|a, b| b.0.cmp(&a.0));
-            let prefix_results: Vec<_> = scored
-                .into_iter()
-                .take(max_results)
-                .map(|(_, app)| app)
-                .collect();
-
-            if !prefix_results.is_empty() {
-                return prefix_results;
+            let scored = match_substring(&query_lower, apps);
+            if !scored.is_empty() {
+                return self.rank_by_frecency(scored, max_results);
             }
         }
 
-        // Fall back to fuzzy matching for complex queries
-        let mut scored: Vec<_> = apps
-            .iter()
-            .filter_map(|app| {
-                self.fuzzy_matcher
-                    .fuzzy_match(&app.name, query)
-                    .or_else(|| {
-                        self.fuzzy_matcher
-                            .fuzzy_match(&app.description, query)
-                            .map(|s| s / 2) // Description matches weighted less
-                    })
-                    .map(|score| (score, app))
-            })
-            .collect();
-
-        scored.sort_unstable_by(|a, b| b.0.cmp(&a.0));
-        scored
+        // Fall back to ranked fuzzy matching: every word must match a field
+        let words = ranking::query_words(query);
+        let scored = ranking::rank_apps(
+            &*self.fuzzy_matcher.borrow(),
+            &words,
+            apps,
+            self.field_weights.get(),
+        );
+        let best = scored.first().map_or(1, |(score, _)| (*score).max(1));
+        let scaled = scored
             .into_iter()
-            .take(max_results)
-            .map(|(_, app)| app)
-            .collect()
+            .map(|(score, app)| (scale_fuzzy_score(score, best), app))
+            .collect();
+        self.rank_by_frecency(scaled, max_results)
     }
 }
 
+/// Score given to apps whose name starts with the query
+const PREFIX_MATCH_SCORE: u8 = 100;
+/// Score given to apps whose name contains the query
+const SUBSTRING_MATCH_SCORE: u8 = 50;
+/// Score given to apps whose generic name or keywords start with the query
+const FIELD_MATCH_SCORE: u8 = 45;
+/// Upper bound for fuzzy match scores, kept below the substring tier
+const FUZZY_MATCH_MAX_SCORE: u8 = 40;
+
+/// Apps whose name contains `query_lower`, or whose generic name or
+/// keywords have a word starting with it
+///
+/// The cheap scorer behind single-word queries: name prefix matches score
+/// `PREFIX_MATCH_SCORE`, other name matches `SUBSTRING_MATCH_SCORE`, and
+/// matches of a generic name word or keyword (`browser` for Firefox)
+/// `FIELD_MATCH_SCORE`.
+#[must_use]
+pub fn match_substring<'a>(query_lower: &str, apps: &'a [DesktopApp]) -> Vec<(u8, &'a DesktopApp)> {
+    let word_starts_with = |text: &str| {
+        text.split_whitespace()
+            .any(|word| word.to_lowercase().starts_with(query_lower))
+    };
+    apps.iter()
+        .filter_map(|app| {
+            if app.name_lower.starts_with(query_lower) {
+                Some((PREFIX_MATCH_SCORE, app))
+            } else if app.name_lower.contains(query_lower) {
+                Some((SUBSTRING_MATCH_SCORE, app))
+            } else if word_starts_with(&app.generic_name)
+                || app.keywords.iter().any(|k| word_starts_with(k))
+            {
+                Some((FIELD_MATCH_SCORE, app))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Map a raw fuzzy score onto `0..=FUZZY_MATCH_MAX_SCORE`, relative to the
+/// best raw score of the same search
+fn scale_fuzzy_score(score: i64, best: i64) -> u8 {
+    let scaled = score.clamp(0, best) * i64::from(FUZZY_MATCH_MAX_SCORE) / best;
+    u8::try_from(scaled).unwrap_or(FUZZY_MATCH_MAX_SCORE)
+}
+
 impl SearchProvider for AppProvider {
     fn search(&self, query: &str) -> Vec<glib::Object> {
         let apps = self.all_apps.borrow();
@@ -147,53 +217,131 @@ impl SearchProvider for AppProvider {
         }
 
         let max = self.max_results.get();
-        let results: Vec<glib::Object> = if query.is_empty() {
-            apps.iter()
-                .take(max)
-                .map(|app| AppItem::new(app).upcast::<glib::Object>())
-                .collect()
-        } else {
-            let matched_apps = self.search_apps_optimized(query, &apps, max);
-            matched_apps
-                .into_iter()
-                .map(|app| AppItem::new(app).upcast::<glib::Object>())
-                .collect()
-        };
-
-        results
+        let words = ranking::query_words(query);
+        let matcher = self.fuzzy_matcher.borrow();
+        self.search_apps_optimized(query, &apps, max)
+            .into_iter()
+            .map(|(score, app)| {
+                let item = AppItem::new(app);
+                item.set_score(score);
+                if !words.is_empty() {
+                    let (name, description) =
+                        ranking::match_positions(&*matcher, &words, &app.name, &item.description());
+                    item.set_match_positions(name, description);
+                }
+                item.upcast::<glib::Object>()
+            })
+            .collect()
     }
 
     fn set_max_results(&self, max: usize) {
         self.max_results.set(max);
     }
+
+    fn set_match_descriptions(&self, enabled: bool) {
+        let description = if enabled {
+            FieldWeights::default().description
+        } else {
+            0
+        };
+        self.field_weights.set(FieldWeights {
+            description,
+            ..self.field_weights.get()
+        });
+    }
 }
 
 // ---------------------------------------------------------------------------
 // Calculator Provider
 // ---------------------------------------------------------------------------
 
-pub struct CalculatorProvider;
+pub struct CalculatorProvider {
+    /// `[calculator] angle_unit`, shared with the model config
+    angle_unit: Rc<Cell<AngleUnit>>,
+}
 
 impl CalculatorProvider {
     #[must_use]
-    pub fn new() -> Self {
-        Self
+    pub fn new(angle_unit: Rc<Cell<AngleUnit>>) -> Self {
+        Self { angle_unit }
     }
 }
 
 impl Default for CalculatorProvider {
     fn default() -> Self {
-        Self::new()
+        Self::new(Rc::new(Cell::new(AngleUnit::default())))
     }
 }
 
 impl SearchProvider for CalculatorProvider {
     fn search(&self, query: &str) -> Vec<glib::Object> {
         // Check if query is a calculator expression
-        if let Some(result) = crate::calculator::evaluate(query) {
-            let calculator_result = format!("{query} = {result}");
-            return vec![CommandItem::new(calculator_result).upcast::<glib::Object>()];
+        if let Some(result) = crate::calculator::evaluate_with(query, self.angle_unit.get()) {
+            let row = CommandRow::calculator(query, &result);
+            let mut items = vec![CommandItem::new(row).upcast::<glib::Object>()];
+            // Integer results in several bases also get a row copying the hex form
+            if let Some(hex) = crate::calculator::hex_form(&result) {
+                items.push(CommandItem::new(CommandRow::calculator(query, hex)).upcast());
+            }
+            return items;
         }
         vec![]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scale_fuzzy_score_bounds() {
+        assert_eq!(scale_fuzzy_score(200, 200), FUZZY_MATCH_MAX_SCORE);
+        assert_eq!(scale_fuzzy_score(100, 200), FUZZY_MATCH_MAX_SCORE / 2);
+        assert_eq!(scale_fuzzy_score(-5, 200), 0);
+        assert!(scale_fuzzy_score(1, 1) < SUBSTRING_MATCH_SCORE);
+        assert!(scale_fuzzy_score(200, 200) < FIELD_MATCH_SCORE);
+    }
+
+    fn app(name: &str, generic_name: &str, keywords: &[&str]) -> DesktopApp {
+        DesktopApp {
+            desktop_id: name.to_lowercase(),
+            name: name.to_string(),
+            name_lower: name.to_lowercase(),
+            exec: String::new(),
+            description: "Browse the web".to_string(),
+            generic_name: generic_name.to_string(),
+            keywords: keywords.iter().map(|k| (*k).to_string()).collect(),
+            icon: String::new(),
+            terminal: false,
+            actions: Vec::new(),
+            startup_wm_class: String::new(),
+            path: std::path::PathBuf::from(format!("/usr/share/applications/{name}.desktop")),
+        }
+    }
+
+    #[test]
+    fn test_match_substring_includes_generic_name_and_keywords() {
+        let apps = [
+            app("Firefox", "", &["Internet", "WWW", "Browser"]),
+            app("Epiphany", "Web Browser", &[]),
+            app("Browser Hub", "", &[]),
+            app("Tor Browser", "", &[]),
+            app("Files", "File Manager", &["folder"]),
+        ];
+        let scored: Vec<(u8, &str)> = match_substring("browser", &apps)
+            .into_iter()
+            .map(|(score, app)| (score, app.name.as_str()))
+            .collect();
+        assert_eq!(
+            scored,
+            vec![
+                (FIELD_MATCH_SCORE, "Firefox"),
+                (FIELD_MATCH_SCORE, "Epiphany"),
+                (PREFIX_MATCH_SCORE, "Browser Hub"),
+                (SUBSTRING_MATCH_SCORE, "Tor Browser"),
+            ]
+        );
+        // Descriptions are left to the fuzzy ranking
+        assert!(match_substring("brows", &apps[4..]).is_empty());
+    }
+}
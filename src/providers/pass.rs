@@ -0,0 +1,191 @@
+//! Entries of the password store for the `:pw` mode
+//!
+//! `pass` keeps one GPG file per entry in `~/.password-store`, or in
+//! `PASSWORD_STORE_DIR`, named after the entry: `web/github.com.gpg` is
+//! the entry `web/github.com`. Only these names are read here; secrets are
+//! left to `pass`, which decrypts them straight into the clipboard.
+//!
+//! The store is small, so it is walked again for every query and new
+//! entries show up right away.
+
+use crate::core::global_state::get_home_dir;
+use crate::utils::expand_home;
+use fuzzy_matcher::FuzzyMatcher;
+use log::debug;
+use std::path::{Path, PathBuf};
+
+/// The password store folder, as `pass` finds it
+#[must_use]
+pub fn password_store_dir() -> PathBuf {
+    match std::env::var("PASSWORD_STORE_DIR") {
+        Ok(dir) if !dir.is_empty() => expand_home(&dir),
+        _ => PathBuf::from(get_home_dir()).join(".password-store"),
+    }
+}
+
+/// A password store entry
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PassEntry {
+    /// Path in the store without `.gpg`, as `pass show` takes it
+    pub name: String,
+}
+
+impl PassEntry {
+    /// Last part of the name, e.g. `github.com`
+    #[must_use]
+    pub fn title(&self) -> &str {
+        self.name.rsplit('/').next().unwrap_or(&self.name)
+    }
+
+    /// Folder of the entry in the store, empty at its root
+    #[must_use]
+    pub fn folder(&self) -> &str {
+        self.name.rsplit_once('/').map_or("", |(folder, _)| folder)
+    }
+}
+
+/// The entries of the store in `dir`, by name
+///
+/// Hidden folders such as `.git` are skipped.
+#[must_use]
+pub fn load_pass_entries(dir: &Path) -> Vec<PassEntry> {
+    let mut entries = Vec::new();
+    collect_entries(dir, "", &mut entries);
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    debug!("[pass] {} entries in {}", entries.len(), dir.display());
+    entries
+}
+
+fn collect_entries(dir: &Path, prefix: &str, entries: &mut Vec<PassEntry>) {
+    let Ok(read) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in read.flatten() {
+        let file_name = entry.file_name();
+        let Some(name) = file_name.to_str() else {
+            continue;
+        };
+        if name.starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        if path.is_dir() {
+            collect_entries(&path, &format!("{prefix}{name}/"), entries);
+        } else if let Some(stem) = name.strip_suffix(".gpg") {
+            entries.push(PassEntry {
+                name: format!("{prefix}{stem}"),
+            });
+        }
+    }
+}
+
+/// The entries whose path matches `query`, best first, at most `max`
+///
+/// Every word has to match the path in the store. With an empty query
+/// every entry is listed, by name.
+#[must_use]
+pub fn match_pass_entries<'a>(
+    matcher: &impl FuzzyMatcher,
+    query: &str,
+    entries: &'a [PassEntry],
+    max: usize,
+) -> Vec<&'a PassEntry> {
+    let words: Vec<&str> = query.split_whitespace().collect();
+    if words.is_empty() {
+        return entries.iter().take(max).collect();
+    }
+    let mut scored: Vec<(i64, &PassEntry)> = entries
+        .iter()
+        .filter_map(|entry| {
+            words
+                .iter()
+                .map(|word| matcher.fuzzy_match(&entry.name, word))
+                .sum::<Option<i64>>()
+                .map(|score| (score, entry))
+        })
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().take(max).map(|(_, e)| e).collect()
+}
+
+/// The second line of `pass show`, usually the user name
+#[must_use]
+pub fn second_line(output: &str) -> Option<&str> {
+    output
+        .lines()
+        .nth(1)
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuzzy_matcher::skim::SkimMatcherV2;
+
+    fn entry(name: &str) -> PassEntry {
+        PassEntry {
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_load_pass_entries() {
+        let dir = std::env::temp_dir().join(format!("grunner_test_pass_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("web/work")).unwrap();
+        std::fs::create_dir_all(dir.join(".git")).unwrap();
+        std::fs::write(dir.join("email.gpg"), "").unwrap();
+        std::fs::write(dir.join("web/github.com.gpg"), "").unwrap();
+        std::fs::write(dir.join("web/work/jira.gpg"), "").unwrap();
+        std::fs::write(dir.join(".gpg-id"), "").unwrap();
+        std::fs::write(dir.join(".git/HEAD.gpg"), "").unwrap();
+        std::fs::write(dir.join("web/notes.txt"), "").unwrap();
+
+        let entries = load_pass_entries(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            entries,
+            [
+                entry("email"),
+                entry("web/github.com"),
+                entry("web/work/jira")
+            ]
+        );
+        assert!(load_pass_entries(Path::new("/nonexistent/store")).is_empty());
+    }
+
+    #[test]
+    fn test_title_and_folder() {
+        let github = entry("web/work/github.com");
+        assert_eq!(github.title(), "github.com");
+        assert_eq!(github.folder(), "web/work");
+        let email = entry("email");
+        assert_eq!(email.title(), "email");
+        assert_eq!(email.folder(), "");
+    }
+
+    #[test]
+    fn test_match_pass_entries() {
+        let entries = [
+            entry("email"),
+            entry("web/github.com"),
+            entry("web/gitlab.com"),
+        ];
+        let matcher = SkimMatcherV2::default();
+        assert_eq!(match_pass_entries(&matcher, "", &entries, 10).len(), 3);
+        assert_eq!(match_pass_entries(&matcher, "", &entries, 1).len(), 1);
+        let found = match_pass_entries(&matcher, "web hub", &entries, 10);
+        assert_eq!(found, [&entries[1]]);
+        assert_eq!(match_pass_entries(&matcher, "git", &entries, 10).len(), 2);
+        assert!(match_pass_entries(&matcher, "xyzzy", &entries, 10).is_empty());
+    }
+
+    #[test]
+    fn test_second_line() {
+        assert_eq!(second_line("s3cret\nalice\nurl: x\n"), Some("alice"));
+        assert_eq!(second_line("s3cret\r\nalice\r\n"), Some("alice"));
+        assert_eq!(second_line("s3cret\n"), None);
+        assert_eq!(second_line("s3cret\n\nurl: x"), None);
+    }
+}
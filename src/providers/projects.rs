@@ -0,0 +1,586 @@
+//! Recent development projects for the `:proj` mode
+//!
+//! Projects come from three sources, merged in this order:
+//!
+//! - VS Code (and Code - OSS, VSCodium): the recently opened folders in
+//!   `state.vscdb`, queried with the `sqlite3` command line tool like
+//!   Firefox bookmarks, and the folders of the open windows and the older
+//!   `openedPathsList` in `storage.json`.
+//! - JetBrains IDEs: `recentProjects.xml` in each IDE's config directory,
+//!   e.g. `~/.config/JetBrains/PyCharm2024.1/options`.
+//! - Git repositories: directories containing `.git` under the configured
+//!   `[projects] roots`.
+//!
+//! A folder known to several sources is kept once, with the first source,
+//! so an IDE that opened it wins over the plain repository. Folders that no
+//! longer exist are dropped. Missing IDEs contribute nothing.
+//!
+//! Like bookmarks, projects are loaded once on a worker thread, the first
+//! time `:proj` is used, and kept for the lifetime of the window.
+
+use crate::core::global_state::get_home_dir;
+use crate::providers::ranking::abbreviation_score;
+use crate::providers::subprocess::query_sqlite_copy;
+use crate::utils::expand_home;
+use fuzzy_matcher::FuzzyMatcher;
+use log::{debug, warn};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// VS Code builds: config directory relative to the home directory, name
+/// and command
+const VSCODE_BUILDS: &[(&str, &str, &str)] = &[
+    (".config/Code", "VS Code", "code"),
+    (".config/Code - OSS", "Code - OSS", "code-oss"),
+    (".config/VSCodium", "VSCodium", "codium"),
+];
+
+/// JetBrains IDEs: prefix of the config directory, name and command
+///
+/// Longer prefixes come first, so `PyCharmCE` is not taken for `PyCharm`.
+const JETBRAINS_IDES: &[(&str, &str, &str)] = &[
+    ("IntelliJIdea", "IntelliJ IDEA", "idea"),
+    ("IdeaIC", "IntelliJ IDEA CE", "idea"),
+    ("PyCharmCE", "PyCharm CE", "pycharm"),
+    ("PyCharm", "PyCharm", "pycharm"),
+    ("CLion", "CLion", "clion"),
+    ("GoLand", "GoLand", "goland"),
+    ("WebStorm", "WebStorm", "webstorm"),
+    ("RustRover", "RustRover", "rustrover"),
+    ("PhpStorm", "PhpStorm", "phpstorm"),
+    ("RubyMine", "RubyMine", "rubymine"),
+    ("Rider", "Rider", "rider"),
+];
+
+/// Recently opened folders, files and workspaces of VS Code
+const VSCODE_QUERY: &str =
+    "SELECT value FROM ItemTable WHERE key = 'history.recentlyOpenedPathsList'";
+
+/// How deep below a root Git repositories are looked for
+const MAX_GIT_DEPTH: usize = 3;
+
+/// A project directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Project {
+    pub path: PathBuf,
+    /// Name of the IDE that opened it, or "Git"
+    pub source: String,
+    /// Command that opens the project in its IDE, `None` for plain
+    /// repositories
+    pub opener: Option<String>,
+}
+
+impl Project {
+    fn new(path: PathBuf, source: &str, opener: Option<&str>) -> Self {
+        Self {
+            path,
+            source: source.to_string(),
+            opener: opener.map(str::to_string),
+        }
+    }
+
+    /// Name of the project directory, matched against the query
+    #[must_use]
+    pub fn name(&self) -> String {
+        self.path.file_name().map_or_else(
+            || self.path.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        )
+    }
+
+    /// Second line of the row: the path, with `~` for the home directory,
+    /// and the source
+    #[must_use]
+    pub fn description(&self, home: &Path) -> String {
+        let path = match self.path.strip_prefix(home) {
+            Ok(rest) if rest.as_os_str().is_empty() => "~".to_string(),
+            Ok(rest) => format!("~/{}", rest.display()),
+            Err(_) => self.path.display().to_string(),
+        };
+        format!("{path} · {}", self.source)
+    }
+}
+
+/// Projects loaded for the window
+#[derive(Debug, Default)]
+pub enum ProjectCache {
+    /// Nothing was requested yet
+    #[default]
+    Unloaded,
+    /// A worker is reading the IDE histories and roots
+    Loading,
+    Loaded(Vec<Project>),
+}
+
+/// Load the projects of every source, with Git repositories under `roots`
+#[must_use]
+pub fn load_all_projects(roots: &[String]) -> Vec<Project> {
+    let home = Path::new(get_home_dir());
+    let mut all = Vec::new();
+    for (dir, name, command) in VSCODE_BUILDS {
+        all.extend(read_vscode_projects(&home.join(dir), name, command));
+    }
+    all.extend(read_jetbrains_projects(
+        &home.join(".config/JetBrains"),
+        home,
+    ));
+    for root in roots {
+        all.extend(find_git_projects(&expand_home(root), MAX_GIT_DEPTH));
+    }
+    let projects = dedup_projects(all);
+    debug!("Loaded {} projects", projects.len());
+    projects
+}
+
+/// Drop folders that no longer exist and repeated paths, keeping the first
+/// occurrence
+fn dedup_projects(projects: Vec<Project>) -> Vec<Project> {
+    let mut seen = HashSet::new();
+    projects
+        .into_iter()
+        .filter(|p| p.path.is_dir())
+        .filter(|p| seen.insert(p.path.clone()))
+        .collect()
+}
+
+/// Local path of a `file://` URI, `None` for remote URIs
+#[must_use]
+pub fn file_uri_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+    let path = urlencoding::decode(path).ok()?;
+    let path = path.trim_end_matches('/');
+    path.starts_with('/').then(|| PathBuf::from(path))
+}
+
+/// Projects of one VS Code build with config directory `config`
+fn read_vscode_projects(config: &Path, name: &str, command: &str) -> Vec<Project> {
+    let mut folders = Vec::new();
+    let state = config.join("User/globalStorage/state.vscdb");
+    if state.is_file()
+        && let Some(json) = query_sqlite_copy(&state, VSCODE_QUERY)
+    {
+        folders.extend(parse_vscode_state_rows(&json));
+    }
+    let storage = config.join("User/globalStorage/storage.json");
+    if let Ok(json) = std::fs::read_to_string(&storage) {
+        folders.extend(parse_vscode_storage(&json));
+    }
+    folders
+        .into_iter()
+        .map(|path| Project::new(path, name, Some(command)))
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct VscodeStateRow {
+    value: String,
+}
+
+#[derive(Deserialize, Default)]
+struct VscodeRecent {
+    #[serde(default)]
+    entries: Vec<VscodeEntry>,
+}
+
+#[derive(Deserialize)]
+struct VscodeEntry {
+    #[serde(rename = "folderUri")]
+    folder_uri: Option<String>,
+}
+
+/// Folders of the `sqlite3 -json` output of [`VSCODE_QUERY`], most recent
+/// first
+///
+/// The value is the JSON list of recently opened entries; files and
+/// `.code-workspace` workspaces are skipped.
+#[must_use]
+pub fn parse_vscode_state_rows(json: &str) -> Vec<PathBuf> {
+    if json.trim().is_empty() {
+        return Vec::new();
+    }
+    let rows = match serde_json::from_str::<Vec<VscodeStateRow>>(json) {
+        Ok(rows) => rows,
+        Err(e) => {
+            warn!("Ignoring unexpected sqlite3 output: {e}");
+            return Vec::new();
+        }
+    };
+    rows.iter()
+        .filter_map(|row| serde_json::from_str::<VscodeRecent>(&row.value).ok())
+        .flat_map(|recent| recent.entries)
+        .filter_map(|entry| file_uri_path(&entry.folder_uri?))
+        .collect()
+}
+
+#[derive(Deserialize, Default)]
+struct VscodeStorage {
+    #[serde(rename = "windowsState", default)]
+    windows_state: VscodeWindowsState,
+    #[serde(rename = "openedPathsList", default)]
+    opened_paths_list: VscodeRecent,
+}
+
+#[derive(Deserialize, Default)]
+struct VscodeWindowsState {
+    #[serde(rename = "lastActiveWindow")]
+    last_active_window: Option<VscodeWindow>,
+    #[serde(rename = "openedWindows", default)]
+    opened_windows: Vec<VscodeWindow>,
+}
+
+#[derive(Deserialize)]
+struct VscodeWindow {
+    folder: Option<String>,
+}
+
+/// Folders of a VS Code `storage.json`: the open windows, then the recent
+/// folders older versions kept there
+#[must_use]
+pub fn parse_vscode_storage(json: &str) -> Vec<PathBuf> {
+    let storage: VscodeStorage = match serde_json::from_str(json) {
+        Ok(storage) => storage,
+        Err(e) => {
+            warn!("Ignoring invalid VS Code storage.json: {e}");
+            return Vec::new();
+        }
+    };
+    let windows = storage.windows_state;
+    windows
+        .last_active_window
+        .into_iter()
+        .chain(windows.opened_windows)
+        .filter_map(|window| window.folder)
+        .chain(
+            storage
+                .opened_paths_list
+                .entries
+                .into_iter()
+                .filter_map(|entry| entry.folder_uri),
+        )
+        .filter_map(|uri| file_uri_path(&uri))
+        .collect()
+}
+
+/// Projects of every JetBrains IDE configured under `jetbrains`
+///
+/// IDE versions are read oldest first, so the newest one's entry is kept
+/// last; the sources stay in directory order since each IDE lists its
+/// projects most recent first.
+fn read_jetbrains_projects(jetbrains: &Path, home: &Path) -> Vec<Project> {
+    let Ok(entries) = std::fs::read_dir(jetbrains) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+    dirs.sort();
+    let mut projects = Vec::new();
+    for dir in dirs {
+        let Some((name, command)) = dir
+            .file_name()
+            .and_then(|d| jetbrains_ide(&d.to_string_lossy()))
+        else {
+            continue;
+        };
+        let file = dir.join("options/recentProjects.xml");
+        let Ok(xml) = std::fs::read_to_string(&file) else {
+            continue;
+        };
+        projects.extend(
+            parse_jetbrains_recent(&xml, home)
+                .into_iter()
+                .map(|path| Project::new(path, name, Some(command))),
+        );
+    }
+    projects
+}
+
+/// Name and command of the JetBrains IDE with config directory `dir`, such
+/// as `PyCharmCE2024.1`
+#[must_use]
+pub fn jetbrains_ide(dir: &str) -> Option<(&'static str, &'static str)> {
+    JETBRAINS_IDES.iter().find_map(|(prefix, name, command)| {
+        let version = dir.strip_prefix(prefix)?;
+        version
+            .starts_with(|c: char| c.is_ascii_digit())
+            .then_some((*name, *command))
+    })
+}
+
+/// Project paths of a JetBrains `recentProjects.xml`, most recent first
+///
+/// Current versions list the projects as `<entry key="…">` of the
+/// `additionalInfo` map, older ones as `<option value="…" />` of
+/// `recentPaths`. `$USER_HOME$` stands for `home`.
+#[must_use]
+pub fn parse_jetbrains_recent(xml: &str, home: &Path) -> Vec<PathBuf> {
+    let mut entries = Vec::new();
+    let mut options = Vec::new();
+    for tag in xml.split('<').skip(1) {
+        let tag = tag.split('>').next().unwrap_or_default();
+        if let Some(attrs) = tag.strip_prefix("entry ") {
+            entries.extend(xml_attr(attrs, "key"));
+        } else if let Some(attrs) = tag.strip_prefix("option ")
+            && xml_attr(attrs, "name").is_none()
+        {
+            options.extend(xml_attr(attrs, "value"));
+        }
+    }
+    let paths = if entries.is_empty() { options } else { entries };
+    let home = home.to_string_lossy();
+    paths
+        .into_iter()
+        .map(|path| PathBuf::from(path.replace("$USER_HOME$", &home)))
+        .filter(|path| path.is_absolute())
+        .collect()
+}
+
+/// Value of the attribute `name` in the attributes of an XML tag, unescaped
+fn xml_attr(attrs: &str, name: &str) -> Option<String> {
+    let start = attrs
+        .match_indices(&format!("{name}=\""))
+        .find(|(i, _)| *i == 0 || attrs[..*i].ends_with(char::is_whitespace))?
+        .0
+        + name.len()
+        + 2;
+    let value = &attrs[start..start + attrs[start..].find('"')?];
+    Some(
+        value
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&amp;", "&"),
+    )
+}
+
+/// Git repositories in `root` and up to `depth` levels below it
+///
+/// A directory containing `.git` is a project and is not searched further.
+/// Hidden directories are skipped.
+#[must_use]
+pub fn find_git_projects(root: &Path, depth: usize) -> Vec<Project> {
+    let mut projects = Vec::new();
+    collect_git_projects(root, depth, &mut projects);
+    projects
+}
+
+fn collect_git_projects(dir: &Path, depth: usize, projects: &mut Vec<Project>) {
+    if dir.join(".git").exists() {
+        projects.push(Project::new(dir.to_path_buf(), "Git", None));
+        return;
+    }
+    if depth == 0 {
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut children: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .filter(|e| !e.file_name().to_string_lossy().starts_with('.'))
+        .map(|e| e.path())
+        .collect();
+    children.sort();
+    for child in children {
+        collect_git_projects(&child, depth - 1, projects);
+    }
+}
+
+/// Projects matching `query` by directory name, best first, at most `max`
+///
+/// An empty query lists the projects in loading order, recently opened
+/// ones first.
+#[must_use]
+pub fn match_projects<'a>(
+    matcher: &impl FuzzyMatcher,
+    query: &str,
+    projects: &'a [Project],
+    max: usize,
+) -> Vec<&'a Project> {
+    let words: Vec<&str> = query.split_whitespace().collect();
+    if words.is_empty() {
+        return projects.iter().take(max).collect();
+    }
+    let mut scored: Vec<(i64, &Project)> = projects
+        .iter()
+        .filter_map(|project| {
+            let name = project.name();
+            words
+                .iter()
+                .map(|word| abbreviation_score(matcher, &name, word))
+                .sum::<Option<i64>>()
+                .map(|score| (score, project))
+        })
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().take(max).map(|(_, p)| p).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuzzy_matcher::skim::SkimMatcherV2;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("grunner_test_proj_{name}_{}", std::process::id()))
+    }
+
+    fn paths(projects: &[Project]) -> Vec<&Path> {
+        projects.iter().map(|p| p.path.as_path()).collect()
+    }
+
+    #[test]
+    fn test_parse_vscode_state_rows() {
+        let value = r#"{"entries":[
+            {"folderUri":"file:///home/me/src/grunner"},
+            {"fileUri":"file:///home/me/notes.md"},
+            {"workspace":{"id":"1","configPath":"file:///home/me/all.code-workspace"}},
+            {"folderUri":"vscode-remote://ssh-remote%2Bbox/srv/app"},
+            {"folderUri":"file:///home/me/My%20Project/"}
+        ]}"#;
+        let json = serde_json::to_string(&serde_json::json!([{ "value": value }])).unwrap();
+        assert_eq!(
+            parse_vscode_state_rows(&json),
+            [
+                PathBuf::from("/home/me/src/grunner"),
+                PathBuf::from("/home/me/My Project")
+            ]
+        );
+        assert!(parse_vscode_state_rows("").is_empty());
+        assert!(parse_vscode_state_rows("not json").is_empty());
+    }
+
+    #[test]
+    fn test_parse_vscode_storage() {
+        let json = r#"{
+            "windowsState": {
+                "lastActiveWindow": {"folder": "file:///home/me/src/api", "backupPath": "x"},
+                "openedWindows": [{"folder": "file:///home/me/src/web"}, {"backupPath": "y"}]
+            },
+            "openedPathsList": {"entries": [{"folderUri": "file:///home/me/old"}]},
+            "theme": "vs-dark"
+        }"#;
+        assert_eq!(
+            parse_vscode_storage(json),
+            [
+                PathBuf::from("/home/me/src/api"),
+                PathBuf::from("/home/me/src/web"),
+                PathBuf::from("/home/me/old")
+            ]
+        );
+        assert!(parse_vscode_storage("{}").is_empty());
+    }
+
+    #[test]
+    fn test_parse_jetbrains_recent() {
+        let home = Path::new("/home/me");
+        let current = r#"<application>
+  <component name="RecentProjectsManager">
+    <option name="additionalInfo">
+      <map>
+        <entry key="$USER_HOME$/PycharmProjects/scraper">
+          <value><RecentProjectMetaInfo frameTitle="scraper" /></value>
+        </entry>
+        <entry key="/opt/work/R&amp;D" />
+      </map>
+    </option>
+    <option name="lastProjectLocation" value="$USER_HOME$/PycharmProjects" />
+  </component>
+</application>"#;
+        assert_eq!(
+            parse_jetbrains_recent(current, home),
+            [
+                PathBuf::from("/home/me/PycharmProjects/scraper"),
+                PathBuf::from("/opt/work/R&D")
+            ]
+        );
+
+        let legacy = r#"<component name="RecentProjectsManager">
+    <option name="recentPaths">
+      <list>
+        <option value="$USER_HOME$/IdeaProjects/shop" />
+      </list>
+    </option>
+</component>"#;
+        assert_eq!(
+            parse_jetbrains_recent(legacy, home),
+            [PathBuf::from("/home/me/IdeaProjects/shop")]
+        );
+    }
+
+    #[test]
+    fn test_jetbrains_ide() {
+        assert_eq!(
+            jetbrains_ide("PyCharmCE2024.1"),
+            Some(("PyCharm CE", "pycharm"))
+        );
+        assert_eq!(jetbrains_ide("PyCharm2023.3"), Some(("PyCharm", "pycharm")));
+        assert_eq!(
+            jetbrains_ide("IntelliJIdea2024.2"),
+            Some(("IntelliJ IDEA", "idea"))
+        );
+        assert_eq!(jetbrains_ide("consentOptions"), None);
+        assert_eq!(jetbrains_ide("PyCharm"), None);
+    }
+
+    #[test]
+    fn test_find_git_projects_and_dedup() {
+        let root = temp_dir("git");
+        for dir in [
+            "a/.git",
+            "group/b/.git",
+            "group/b/nested/.git",
+            ".hidden/c/.git",
+        ] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        std::fs::create_dir_all(root.join("deep/1/2/3/d/.git")).unwrap();
+
+        let found = find_git_projects(&root, MAX_GIT_DEPTH);
+        assert_eq!(paths(&found), [root.join("a"), root.join("group/b")]);
+        assert!(
+            found
+                .iter()
+                .all(|p| p.source == "Git" && p.opener.is_none())
+        );
+
+        // An IDE's entry wins over the repository; missing folders go
+        let mut all = vec![
+            Project::new(root.join("a"), "VS Code", Some("code")),
+            Project::new(root.join("gone"), "VS Code", Some("code")),
+        ];
+        all.extend(found);
+        let projects = dedup_projects(all);
+        let _ = std::fs::remove_dir_all(&root);
+        assert_eq!(paths(&projects), [root.join("a"), root.join("group/b")]);
+        assert_eq!(projects[0].opener.as_deref(), Some("code"));
+    }
+
+    #[test]
+    fn test_match_projects_and_description() {
+        let matcher = SkimMatcherV2::default();
+        let projects = vec![
+            Project::new(
+                PathBuf::from("/home/me/src/grunner"),
+                "VS Code",
+                Some("code"),
+            ),
+            Project::new(PathBuf::from("/home/me/work/api-gateway"), "Git", None),
+            Project::new(PathBuf::from("/srv/grafana"), "GoLand", Some("goland")),
+        ];
+        let all = match_projects(&matcher, "", &projects, 2);
+        assert_eq!(all.len(), 2);
+        let found = match_projects(&matcher, "gw", &projects, 10);
+        assert_eq!(
+            found.first().map(|p| p.name()),
+            Some("api-gateway".to_string())
+        );
+        // Only the directory name is matched, not the path
+        assert!(match_projects(&matcher, "work", &projects, 10).is_empty());
+
+        let home = Path::new("/home/me");
+        assert_eq!(projects[0].description(home), "~/src/grunner · VS Code");
+        assert_eq!(projects[2].description(home), "/srv/grafana · GoLand");
+    }
+}
@@ -0,0 +1,928 @@
+//! Multi-word ranking of applications
+//!
+//! The query is split into words and every word has to match at least one
+//! field of an application: its name, generic name, keywords or description. Each word
+//! contributes the match quality of its best field, weighted by
+//! [`FieldWeights`], and the per-word scores are summed. "gnome disk" therefore ranks "GNOME Disks"
+//! by two strong name hits instead of fuzzy-matching the joined string, where
+//! unrelated apps could win just because the letters happen to interleave.
+//!
+//! Launch frecency (see [`crate::history`]) adds a bounded bonus on top of the
+//! 0–100 match score in [`sort_by_frecency`]: enough to lift an app used every
+//! day above a marginally better match, never enough to jump a whole match
+//! tier (prefix, substring, fuzzy).
+//!
+//! Files, notes and search provider results come ranked by their search
+//! already; [`boost_by_frecency`] lets frequently used ones move up a few
+//! places, so the search's own order still mostly decides.
+//!
+//! File names and bookmarks are ranked with [`abbreviation_score`], which
+//! adds a bonus for query characters found at word starts (camel humps and
+//! the characters after `_ - . /`), so `prjPlan` finds "ProjectPlanning.md"
+//! and `usr_cfg` finds "user_config.toml" among thousands of paths.
+//!
+//! [`match_positions`] finds the characters of an app's name (or, failing
+//! that, its description) that the query hit, for highlighting in its row.
+//!
+//! When nothing matches at all, [`suggest_app_name`] and [`suggest_command`]
+//! look for a close spelling with a bounded edit distance.
+
+use crate::launcher::DesktopApp;
+use fuzzy_matcher::FuzzyMatcher;
+use std::cmp::Reverse;
+
+/// Relative weight of each application field when scoring a query word
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldWeights {
+    pub name: i64,
+    pub generic_name: i64,
+    pub keywords: i64,
+    pub description: i64,
+}
+
+impl Default for FieldWeights {
+    fn default() -> Self {
+        Self {
+            name: 4,
+            generic_name: 2,
+            keywords: 2,
+            description: 1,
+        }
+    }
+}
+
+/// Split a query into lowercase words
+#[must_use]
+pub fn query_words(query: &str) -> Vec<String> {
+    query.split_whitespace().map(str::to_lowercase).collect()
+}
+
+/// Match quality of a word found at the start of a word in the field
+const WORD_START_QUALITY: i64 = 100;
+/// Match quality of a word found elsewhere inside the field
+const SUBSTRING_QUALITY: i64 = 70;
+/// Upper bound for the quality of a scattered fuzzy match
+const FUZZY_QUALITY_MAX: i64 = 60;
+
+/// How well `word` (lowercase) matches `field`, from 0 to `WORD_START_QUALITY`
+///
+/// Contiguous matches always beat scattered fuzzy ones, so a keyword that
+/// contains the word outranks a name where its letters merely interleave.
+fn field_quality(matcher: &impl FuzzyMatcher, field: &str, word: &str) -> Option<i64> {
+    let field_lower = field.to_lowercase();
+    let mut substring = false;
+    for (idx, _) in field_lower.match_indices(word) {
+        let at_word_start = field_lower[..idx]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !c.is_alphanumeric());
+        if at_word_start {
+            return Some(WORD_START_QUALITY);
+        }
+        substring = true;
+    }
+    if substring {
+        return Some(SUBSTRING_QUALITY);
+    }
+
+    let score = matcher.fuzzy_match(field, word)?;
+    let perfect = matcher.fuzzy_match(word, word).unwrap_or(score).max(1);
+    Some(score.clamp(0, perfect) * FUZZY_QUALITY_MAX / perfect)
+}
+
+/// Best weighted score of `word` over the fields of `app`
+fn word_score(
+    matcher: &impl FuzzyMatcher,
+    word: &str,
+    app: &DesktopApp,
+    weights: FieldWeights,
+) -> Option<i64> {
+    let name = field_quality(matcher, &app.name, word).map(|q| q * weights.name);
+    let generic_name =
+        field_quality(matcher, &app.generic_name, word).map(|q| q * weights.generic_name);
+    let keywords = app
+        .keywords
+        .iter()
+        .filter_map(|k| field_quality(matcher, k, word))
+        .max()
+        .map(|q| q * weights.keywords);
+    // A weight of 0 skips the field, the slowest to match
+    let description = (weights.description > 0)
+        .then(|| field_quality(matcher, &app.description, word))
+        .flatten()
+        .map(|q| q * weights.description);
+    [name, generic_name, keywords, description]
+        .into_iter()
+        .flatten()
+        .max()
+}
+
+/// Score `app` against all `words`
+///
+/// Returns `None` unless every word matches at least one field.
+pub fn score_app(
+    matcher: &impl FuzzyMatcher,
+    words: &[String],
+    app: &DesktopApp,
+    weights: FieldWeights,
+) -> Option<i64> {
+    if words.is_empty() {
+        return None;
+    }
+    words
+        .iter()
+        .map(|word| word_score(matcher, word, app, weights))
+        .sum()
+}
+
+/// Rank `apps` against `words`, best match first
+///
+/// Apps that miss any word are dropped. Ties keep the input order, which is
+/// alphabetical for the loaded application list.
+pub fn rank_apps<'a>(
+    matcher: &impl FuzzyMatcher,
+    words: &[String],
+    apps: &'a [DesktopApp],
+    weights: FieldWeights,
+) -> Vec<(i64, &'a DesktopApp)> {
+    let mut scored: Vec<_> = apps
+        .iter()
+        .filter_map(|app| score_app(matcher, words, app, weights).map(|score| (score, app)))
+        .collect();
+    scored.sort_by_key(|(score, _)| Reverse(*score));
+    scored
+}
+
+/// Points of a query character matched at a word start
+const BOUNDARY_HIT: i64 = 3;
+/// Points of a query character matched right after the previous one
+const CONTIGUOUS_HIT: i64 = 2;
+/// Points of a query character matched anywhere else
+const SCATTERED_HIT: i64 = 1;
+/// Bonus added to the skim score by a query made only of word starts
+const BOUNDARY_BONUS_MAX: i64 = 300;
+
+/// Characters that separate words in file names and URLs
+fn is_word_separator(c: char) -> bool {
+    matches!(c, '_' | '-' | '.' | '/' | ' ')
+}
+
+fn fold_case(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// Whether each of `chars` starts a word
+///
+/// Words start at the beginning, after a separator, at an uppercase letter
+/// following a lowercase letter or digit (`planNing`), and at the first
+/// digit of a number.
+fn word_starts(chars: &[char]) -> Vec<bool> {
+    chars
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| {
+            if is_word_separator(c) {
+                return false;
+            }
+            let Some(&prev) = i.checked_sub(1).and_then(|p| chars.get(p)) else {
+                return true;
+            };
+            is_word_separator(prev)
+                || (c.is_uppercase() && (prev.is_lowercase() || prev.is_numeric()))
+                || (c.is_numeric() && !prev.is_numeric())
+        })
+        .collect()
+}
+
+/// How well `query` abbreviates the words of `text`, from 0 to 100
+///
+/// The query characters, without separators, are matched case-insensitively
+/// from left to right: right after the previous match if possible, else at
+/// the next word start, else anywhere. Returns `None` if `query` is not a
+/// subsequence of `text`.
+#[must_use]
+pub fn boundary_quality(text: &str, query: &str) -> Option<i64> {
+    let chars: Vec<char> = text.chars().collect();
+    let folded: Vec<char> = chars.iter().copied().map(fold_case).collect();
+    let starts = word_starts(&chars);
+    let wanted: Vec<char> = query
+        .chars()
+        .filter(|&c| !is_word_separator(c) && !c.is_whitespace())
+        .map(fold_case)
+        .collect();
+    if wanted.is_empty() {
+        return None;
+    }
+
+    let mut next = 0;
+    let mut points = 0;
+    for (n, &q) in wanted.iter().enumerate() {
+        let at = if n > 0 && folded.get(next) == Some(&q) {
+            next
+        } else if let Some(i) = (next..chars.len()).find(|&i| starts[i] && folded[i] == q) {
+            i
+        } else {
+            (next..chars.len()).find(|&i| folded[i] == q)?
+        };
+        points += if starts[at] {
+            BOUNDARY_HIT
+        } else if n > 0 && at == next {
+            CONTIGUOUS_HIT
+        } else {
+            SCATTERED_HIT
+        };
+        next = at + 1;
+    }
+    let len = i64::try_from(wanted.len()).unwrap_or(i64::MAX);
+    Some(points * 100 / (BOUNDARY_HIT * len))
+}
+
+/// Skim score of `word` in `text` plus a bonus for hitting word starts
+///
+/// Either match is enough: with smart case, skim rejects `prjPlan` for
+/// "ProjectPlanning", which the case-insensitive boundary match accepts.
+pub fn abbreviation_score(matcher: &impl FuzzyMatcher, text: &str, word: &str) -> Option<i64> {
+    let fuzzy = matcher.fuzzy_match(text, word);
+    let boundary = boundary_quality(text, word);
+    if fuzzy.is_none() && boundary.is_none() {
+        return None;
+    }
+    Some(fuzzy.unwrap_or(0) + boundary.unwrap_or(0) * BOUNDARY_BONUS_MAX / 100)
+}
+
+/// Score of a file `path` for one query word
+///
+/// The file name is scored with [`abbreviation_score`]; a word found only
+/// in the folders counts half.
+fn path_word_score(matcher: &impl FuzzyMatcher, path: &str, word: &str) -> Option<i64> {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    abbreviation_score(matcher, name, word)
+        .or_else(|| abbreviation_score(matcher, path, word).map(|score| score / 2))
+}
+
+/// Rank `items` by the file path `path` returns, best first, at most `max`
+///
+/// Every word of `query` has to match the path; ties keep the input order.
+/// An empty query keeps the first `max` items as they are.
+pub fn rank_paths<T>(
+    matcher: &impl FuzzyMatcher,
+    items: Vec<T>,
+    path: impl Fn(&T) -> &str,
+    query: &str,
+    max: usize,
+) -> Vec<T> {
+    let words: Vec<&str> = query.split_whitespace().collect();
+    if words.is_empty() {
+        return items.into_iter().take(max).collect();
+    }
+    let mut scored: Vec<(i64, T)> = items
+        .into_iter()
+        .filter_map(|item| {
+            let score = words
+                .iter()
+                .map(|word| path_word_score(matcher, path(&item), word))
+                .sum::<Option<i64>>()?;
+            Some((score, item))
+        })
+        .collect();
+    scored.sort_by_key(|(score, _)| Reverse(*score));
+    scored.into_iter().take(max).map(|(_, item)| item).collect()
+}
+
+/// Character positions of `word` (lowercase) found contiguously in `text`
+///
+/// An occurrence at the start of a word is preferred, as in
+/// [`field_quality`]. Case is folded per character so positions stay
+/// aligned with `text`'s characters.
+fn contiguous_positions(text: &str, word: &str) -> Option<Vec<usize>> {
+    let chars: Vec<char> = text.chars().map(fold_case).collect();
+    let wanted: Vec<char> = word.chars().map(fold_case).collect();
+    if wanted.is_empty() || wanted.len() > chars.len() {
+        return None;
+    }
+    let mut hits = (0..=chars.len() - wanted.len())
+        .filter(|&i| chars[i..i + wanted.len()] == wanted[..])
+        .peekable();
+    let first = *hits.peek()?;
+    let at = hits
+        .find(|&i| i == 0 || !chars[i - 1].is_alphanumeric())
+        .unwrap_or(first);
+    Some((at..at + wanted.len()).collect())
+}
+
+/// Character positions of `word` in `text`: a contiguous occurrence if
+/// there is one, else the characters of a fuzzy match
+fn word_positions(matcher: &impl FuzzyMatcher, text: &str, word: &str) -> Option<Vec<usize>> {
+    contiguous_positions(text, word).or_else(|| {
+        matcher
+            .fuzzy_indices(text, word)
+            .map(|(_, indices)| indices)
+    })
+}
+
+/// Character positions of `name` and `description` matched by `words`
+///
+/// Each word is looked for in the name first and only in the description
+/// if the name does not contain it, so the description is highlighted only
+/// when it is what matched. Words found in neither (e.g. a keyword match)
+/// highlight nothing. Both lists are sorted and free of duplicates.
+pub fn match_positions(
+    matcher: &impl FuzzyMatcher,
+    words: &[String],
+    name: &str,
+    description: &str,
+) -> (Vec<usize>, Vec<usize>) {
+    let mut in_name = Vec::new();
+    let mut in_description = Vec::new();
+    for word in words {
+        if let Some(positions) = word_positions(matcher, name, word) {
+            in_name.extend(positions);
+        } else if let Some(positions) = word_positions(matcher, description, word) {
+            in_description.extend(positions);
+        }
+    }
+    for positions in [&mut in_name, &mut in_description] {
+        positions.sort_unstable();
+        positions.dedup();
+    }
+    (in_name, in_description)
+}
+
+/// Largest ranking bonus from launch frecency, on the 0–100 match score scale
+pub const MAX_FRECENCY_BONUS: f64 = 15.0;
+/// Frecency at which an app gets half of [`MAX_FRECENCY_BONUS`]
+const HALF_BONUS_FRECENCY: f64 = 5.0;
+
+/// Ranking bonus for an app with the given launch frecency
+///
+/// Grows with frecency but saturates at [`MAX_FRECENCY_BONUS`], so heavy use
+/// cannot outweigh a better kind of match.
+#[must_use]
+pub fn frecency_bonus(frecency: f64) -> f64 {
+    let frecency = frecency.max(0.0);
+    MAX_FRECENCY_BONUS * frecency / (frecency + HALF_BONUS_FRECENCY)
+}
+
+/// Most places a frequently used result moves up in [`boost_by_frecency`]
+pub const MAX_BOOST_PLACES: f64 = 5.0;
+
+/// Places a result with the given activation frecency moves up
+///
+/// Scales [`frecency_bonus`] to at most [`MAX_BOOST_PLACES`]; a result
+/// passes another one only by more than a whole place, so a single
+/// activation is not enough.
+#[must_use]
+pub fn boost_places(frecency: f64) -> f64 {
+    frecency_bonus(frecency) / MAX_FRECENCY_BONUS * MAX_BOOST_PLACES
+}
+
+/// Move frequently used results of an already ranked list up, by at most
+/// [`MAX_BOOST_PLACES`] each
+///
+/// The sort is stable, so results that are not boosted keep their order.
+pub fn boost_by_frecency<T>(items: &mut Vec<T>, frecency: impl Fn(&T) -> f64) {
+    let mut keyed: Vec<(f64, T)> = (0..)
+        .zip(items.drain(..))
+        .map(|(place, item)| (f64::from(place) - boost_places(frecency(&item)), item))
+        .collect();
+    keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+    items.extend(keyed.into_iter().map(|(_, item)| item));
+}
+
+/// Integer weight for [`suggest_app_name`] from a launch frecency
+///
+/// Keeps three decimals, which is plenty to tell apps apart.
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn frecency_weight(frecency: f64) -> u32 {
+    (frecency.max(0.0) * 1000.0).min(f64::from(u32::MAX)) as u32
+}
+
+/// Sort scored apps by match score plus frecency bonus, best first
+///
+/// The sort is stable, so apps with equal totals keep their order. With all
+/// scores equal (the empty query) this orders apps by frecency alone.
+pub fn sort_by_frecency(scored: &mut [(u8, &DesktopApp)], frecency: impl Fn(&DesktopApp) -> f64) {
+    let mut keyed: Vec<(f64, (u8, &DesktopApp))> = scored
+        .iter()
+        .map(|&(score, app)| {
+            (
+                f64::from(score) + frecency_bonus(frecency(app)),
+                (score, app),
+            )
+        })
+        .collect();
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+    for (slot, (_, entry)) in scored.iter_mut().zip(keyed) {
+        *slot = entry;
+    }
+}
+
+/// Shortest app name (or name word) that may be offered as a suggestion
+pub const MIN_SUGGESTION_LEN: usize = 4;
+/// Largest edit distance between a query and a suggested app name
+pub const MAX_SUGGESTION_DISTANCE: usize = 2;
+/// Largest edit distance between a colon command typo and a known command
+const MAX_COMMAND_DISTANCE: usize = 1;
+
+/// Edit distance between `a` and `b` if it is at most `max`
+///
+/// Counts insertions, deletions, substitutions and swaps of adjacent
+/// characters (optimal string alignment). Gives up as soon as a whole row of
+/// the table exceeds `max`, so long unrelated strings are rejected cheaply.
+#[must_use]
+pub fn bounded_edit_distance(a: &str, b: &str, max: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+
+    let mut before_prev = vec![0; b.len() + 1];
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        cur[0] = i;
+        let mut row_min = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut d = (prev[j] + 1).min(cur[j - 1] + 1).min(prev[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d = d.min(before_prev[j - 2] + 1);
+            }
+            cur[j] = d;
+            row_min = row_min.min(d);
+        }
+        if row_min > max {
+            return None;
+        }
+        std::mem::swap(&mut before_prev, &mut prev);
+        std::mem::swap(&mut prev, &mut cur);
+    }
+    let distance = prev[b.len()];
+    (distance <= max).then_some(distance)
+}
+
+/// Pick the candidate closest to `query`
+///
+/// Candidates come with a weight; among equally close candidates the higher
+/// weight wins, then the earlier one. Exact matches are skipped since there
+/// is nothing to correct.
+fn best_suggestion<I>(query: &str, candidates: I, max_distance: usize) -> Option<String>
+where
+    I: IntoIterator<Item = (String, u32)>,
+{
+    let mut best: Option<(usize, u32, String)> = None;
+    for (candidate, weight) in candidates {
+        if candidate == query {
+            continue;
+        }
+        let Some(distance) = bounded_edit_distance(query, &candidate, max_distance) else {
+            continue;
+        };
+        let better = best.as_ref().is_none_or(|(best_distance, best_weight, _)| {
+            distance < *best_distance || (distance == *best_distance && weight > *best_weight)
+        });
+        if better {
+            best = Some((distance, weight, candidate));
+        }
+    }
+    best.map(|(_, _, candidate)| candidate)
+}
+
+/// Suggest an application name for a query that matched nothing
+///
+/// Both full names and their individual words are candidates, so "frefox"
+/// suggests "firefox" for "Firefox Web Browser". `weight` ranks apps whose
+/// candidates are equally close (e.g. by how often they are launched).
+pub fn suggest_app_name(
+    query: &str,
+    apps: &[DesktopApp],
+    weight: impl Fn(&DesktopApp) -> u32,
+) -> Option<String> {
+    let query = query.trim().to_lowercase();
+    if query.chars().count() < MIN_SUGGESTION_LEN - MAX_SUGGESTION_DISTANCE {
+        return None;
+    }
+    let candidates = apps.iter().flat_map(|app| {
+        let w = weight(app);
+        std::iter::once(app.name_lower.clone())
+            .chain(app.name_lower.split_whitespace().map(str::to_string))
+            .filter(|c| c.chars().count() >= MIN_SUGGESTION_LEN)
+            .map(move |c| (c, w))
+    });
+    best_suggestion(&query, candidates, MAX_SUGGESTION_DISTANCE)
+}
+
+/// Suggest a known colon command for a mistyped one (`og` → `ob`)
+///
+/// Only single-edit typos of commands at least two characters long are
+/// corrected; earlier entries in `known` win ties.
+#[must_use]
+pub fn suggest_command<'a>(cmd: &str, known: &[&'a str]) -> Option<&'a str> {
+    if cmd.chars().count() < 2 || known.contains(&cmd) {
+        return None;
+    }
+    let suggestion = best_suggestion(
+        cmd,
+        known.iter().map(|k| ((*k).to_string(), 0)),
+        MAX_COMMAND_DISTANCE,
+    )?;
+    known.iter().copied().find(|k| *k == suggestion)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuzzy_matcher::skim::SkimMatcherV2;
+
+    fn app(name: &str, keywords: &[&str], description: &str) -> DesktopApp {
+        DesktopApp {
+            desktop_id: name.to_lowercase().replace(' ', "-"),
+            name: name.to_string(),
+            name_lower: name.to_lowercase(),
+            exec: String::new(),
+            description: description.to_string(),
+            generic_name: String::new(),
+            keywords: keywords.iter().map(|k| (*k).to_string()).collect(),
+            icon: String::new(),
+            terminal: false,
+            actions: Vec::new(),
+            startup_wm_class: String::new(),
+            path: std::path::PathBuf::from(format!("/usr/share/applications/{name}.desktop")),
+        }
+    }
+
+    fn ranked_names(query: &str, apps: &[DesktopApp]) -> Vec<String> {
+        let matcher = SkimMatcherV2::default();
+        rank_apps(&matcher, &query_words(query), apps, FieldWeights::default())
+            .into_iter()
+            .map(|(_, app)| app.name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_match_positions_prefers_name() {
+        let matcher = SkimMatcherV2::default();
+        let (name, desc) = match_positions(
+            &matcher,
+            &query_words("fire"),
+            "Firefox",
+            "Browse the web with fire",
+        );
+        assert_eq!(name, vec![0, 1, 2, 3]);
+        assert!(desc.is_empty());
+
+        // A word the name lacks is highlighted in the description
+        let (name, desc) = match_positions(
+            &matcher,
+            &query_words("files web"),
+            "Files",
+            "Browse the web",
+        );
+        assert_eq!(name, vec![0, 1, 2, 3, 4]);
+        assert_eq!(desc, vec![11, 12, 13]);
+    }
+
+    #[test]
+    fn test_match_positions_word_start_and_fuzzy() {
+        let matcher = SkimMatcherV2::default();
+        // "disk" at the start of "Disks", not inside "Ondisk"
+        let (name, _) = match_positions(&matcher, &query_words("disk"), "Ondisk Disks", "");
+        assert_eq!(name, vec![7, 8, 9, 10]);
+
+        // Scattered letters
+        let (name, _) = match_positions(&matcher, &query_words("gte"), "Gnome Terminal", "");
+        assert_eq!(name.len(), 3);
+        assert_eq!(name[0], 0);
+
+        let (name, desc) = match_positions(&matcher, &query_words("zzz"), "Files", "Browse");
+        assert!(name.is_empty() && desc.is_empty());
+    }
+
+    #[test]
+    fn test_match_positions_are_char_indices() {
+        let matcher = SkimMatcherV2::default();
+        let (name, _) = match_positions(&matcher, &query_words("ré"), "Caméra Réglages", "");
+        assert_eq!(name, vec![7, 8]);
+    }
+
+    #[test]
+    fn test_query_words() {
+        assert_eq!(query_words("  GNOME   Disk "), vec!["gnome", "disk"]);
+        assert!(query_words("   ").is_empty());
+    }
+
+    #[test]
+    fn test_gnome_disk_ranks_disks_first() {
+        let apps = vec![
+            app(
+                "Disk Usage Analyzer",
+                &["storage", "space"],
+                "Check folder sizes and available disk space",
+            ),
+            app(
+                "GNOME Disks",
+                &["disk", "drive", "volume"],
+                "Manage Drives and Media",
+            ),
+            app(
+                "Genome Desktop Kit",
+                &[],
+                "Graphical editor for molecule sketches",
+            ),
+            app(
+                "GNOME Maps",
+                &["map", "route"],
+                "Find places around the world",
+            ),
+        ];
+        let ranked = ranked_names("gnome disk", &apps);
+        assert_eq!(ranked.first().map(String::as_str), Some("GNOME Disks"));
+        assert!(!ranked.contains(&"GNOME Maps".to_string()));
+    }
+
+    #[test]
+    fn test_code_insiders_prefers_insiders_build() {
+        let apps = vec![
+            app(
+                "Visual Studio Code",
+                &["vscode"],
+                "Code Editing. Redefined.",
+            ),
+            app(
+                "Visual Studio Code - Insiders",
+                &["vscode"],
+                "Code Editing. Redefined.",
+            ),
+            app("Code Review", &["git"], "Review merge requests"),
+        ];
+        assert_eq!(
+            ranked_names("code insiders", &apps),
+            vec!["Visual Studio Code - Insiders"]
+        );
+    }
+
+    #[test]
+    fn test_note_obsidian_matches_across_fields() {
+        let apps = vec![
+            app("Notes", &["note", "memo"], "Take notes"),
+            app(
+                "Obsidian",
+                &["notes", "markdown"],
+                "Markdown-based knowledge base",
+            ),
+            app("Obsidian Icon Theme", &["icons"], "Dark icon theme"),
+        ];
+        let ranked = ranked_names("note obsidian", &apps);
+        assert_eq!(ranked.first().map(String::as_str), Some("Obsidian"));
+        assert!(!ranked.contains(&"Notes".to_string()));
+    }
+
+    #[test]
+    fn test_name_outweighs_description() {
+        let apps = vec![
+            app("Terminal", &[], "Use the command line"),
+            app("Console", &[], "A terminal emulator"),
+        ];
+        assert_eq!(ranked_names("terminal", &apps), vec!["Terminal", "Console"]);
+    }
+
+    #[test]
+    fn test_generic_name_and_keywords_rank_between_name_and_description() {
+        let mut firefox = app("Firefox", &["Internet", "WWW", "Browser"], "");
+        firefox.generic_name = "Web Browser".to_string();
+        let mut epiphany = app("Epiphany", &[], "");
+        epiphany.generic_name = "Web Browser".to_string();
+        let apps = vec![
+            app("Downloads", &[], "Files saved by your browser"),
+            epiphany,
+            firefox,
+            app("Browser Hub", &[], ""),
+        ];
+        assert_eq!(
+            ranked_names("browser", &apps),
+            vec!["Browser Hub", "Epiphany", "Firefox", "Downloads"]
+        );
+    }
+
+    #[test]
+    fn test_every_word_must_match() {
+        let matcher = SkimMatcherV2::default();
+        let firefox = app("Firefox", &["web", "browser"], "Browse the Web");
+        let weights = FieldWeights::default();
+        assert!(score_app(&matcher, &query_words("firefox web"), &firefox, weights).is_some());
+        assert!(score_app(&matcher, &query_words("firefox zzz"), &firefox, weights).is_none());
+        assert!(score_app(&matcher, &[], &firefox, weights).is_none());
+    }
+
+    #[test]
+    fn test_zero_description_weight_skips_descriptions() {
+        let matcher = SkimMatcherV2::default();
+        let console = app("Console", &[], "A terminal emulator");
+        let no_descriptions = FieldWeights {
+            description: 0,
+            ..FieldWeights::default()
+        };
+        let words = query_words("terminal");
+        assert!(score_app(&matcher, &words, &console, FieldWeights::default()).is_some());
+        assert!(score_app(&matcher, &words, &console, no_descriptions).is_none());
+    }
+
+    #[test]
+    fn test_bounded_edit_distance() {
+        assert_eq!(bounded_edit_distance("frefox", "firefox", 2), Some(1));
+        assert_eq!(bounded_edit_distance("firefxo", "firefox", 2), Some(1));
+        assert_eq!(
+            bounded_edit_distance("thunderbrid", "thunderbird", 2),
+            Some(1)
+        );
+        assert_eq!(bounded_edit_distance("gimp", "gimp", 2), Some(0));
+        assert_eq!(bounded_edit_distance("kitten", "sitting", 2), None);
+        assert_eq!(bounded_edit_distance("kitten", "sitting", 3), Some(3));
+        assert_eq!(bounded_edit_distance("a", "abcd", 2), None);
+        assert_eq!(bounded_edit_distance("", "ab", 2), Some(2));
+        assert_eq!(bounded_edit_distance("café", "cafe", 1), Some(1));
+    }
+
+    #[test]
+    fn test_suggest_app_name() {
+        let apps = vec![
+            app("Firefox Web Browser", &[], ""),
+            app("Files", &[], ""),
+            app("Thunderbird", &[], ""),
+        ];
+        assert_eq!(
+            suggest_app_name("frefox", &apps, |_| 0).as_deref(),
+            Some("firefox")
+        );
+        assert_eq!(
+            suggest_app_name("Thunderbrid", &apps, |_| 0).as_deref(),
+            Some("thunderbird")
+        );
+        assert_eq!(suggest_app_name("zzzzzz", &apps, |_| 0), None);
+        // Short words like "web" are never suggested
+        assert_eq!(suggest_app_name("wbe", &apps, |_| 0), None);
+    }
+
+    #[test]
+    fn test_suggest_app_name_prefers_weighted_ties() {
+        let apps = vec![app("Gedit", &[], ""), app("Geany", &[], "")];
+        // "gedy" is one edit from neither, two from both
+        assert_eq!(
+            suggest_app_name("gedy", &apps, |_| 0).as_deref(),
+            Some("gedit")
+        );
+        let geany_first = |a: &DesktopApp| u32::from(a.name == "Geany");
+        assert_eq!(
+            suggest_app_name("gedy", &apps, geany_first).as_deref(),
+            Some("geany")
+        );
+    }
+
+    #[test]
+    fn test_suggest_command() {
+        let known = ["ob", "obg", "f", "fg", "sh"];
+        assert_eq!(suggest_command("og", &known), Some("ob"));
+        assert_eq!(suggest_command("hs", &known), Some("sh"));
+        assert_eq!(suggest_command("obgg", &known), Some("obg"));
+        assert_eq!(suggest_command("x", &known), None);
+        assert_eq!(suggest_command("zzz", &known), None);
+        assert_eq!(suggest_command("ob", &known), None);
+    }
+
+    #[test]
+    fn test_frecency_bonus_is_bounded() {
+        assert!(frecency_bonus(0.0).abs() < f64::EPSILON);
+        assert!((frecency_bonus(HALF_BONUS_FRECENCY) - MAX_FRECENCY_BONUS / 2.0).abs() < 1e-9);
+        assert!(frecency_bonus(1e9) < MAX_FRECENCY_BONUS);
+        assert!(frecency_bonus(20.0) > frecency_bonus(2.0));
+    }
+
+    #[test]
+    fn test_sort_by_frecency() {
+        let apps = [
+            app("Files", &[], ""),
+            app("Firefox", &[], ""),
+            app("Fig", &[], ""),
+            app("Fractal", &[], ""),
+        ];
+        let used = |a: &DesktopApp| match a.name.as_str() {
+            "Fractal" => 20.0,
+            "Fig" => 1.0,
+            _ => 0.0,
+        };
+
+        // A frequent app beats a marginally better match, not a better tier
+        let mut scored = vec![(40, &apps[0]), (36, &apps[3]), (100, &apps[1])];
+        sort_by_frecency(&mut scored, used);
+        let names: Vec<&str> = scored.iter().map(|(_, a)| a.name.as_str()).collect();
+        assert_eq!(names, ["Firefox", "Fractal", "Files"]);
+
+        // Empty query: most used first, the rest keep their order
+        let mut scored: Vec<(u8, &DesktopApp)> = apps.iter().map(|a| (0, a)).collect();
+        sort_by_frecency(&mut scored, used);
+        let names: Vec<&str> = scored.iter().map(|(_, a)| a.name.as_str()).collect();
+        assert_eq!(names, ["Fractal", "Fig", "Files", "Firefox"]);
+    }
+
+    #[test]
+    fn test_boost_places_is_capped() {
+        assert!(boost_places(0.0).abs() < f64::EPSILON);
+        // One activation does not pass anything, two pass one result
+        assert!(boost_places(1.0) < 1.0);
+        assert!(boost_places(2.0) > 1.0 && boost_places(2.0) < 2.0);
+        assert!((boost_places(HALF_BONUS_FRECENCY) - MAX_BOOST_PLACES / 2.0).abs() < 1e-9);
+        assert!(boost_places(1e9) < MAX_BOOST_PLACES);
+    }
+
+    #[test]
+    fn test_boost_by_frecency() {
+        let used = |item: &&str| match *item {
+            "h" => 1e9,
+            "d" => 2.0,
+            "b" => 1.0,
+            _ => 0.0,
+        };
+        let mut items = vec!["a", "b", "c", "d", "e", "f", "g", "h", "i"];
+        boost_by_frecency(&mut items, used);
+        // "h" moves up by just under five places, "d" by one, "b" stays
+        assert_eq!(items, ["a", "b", "d", "c", "h", "e", "f", "g", "i"]);
+
+        let mut unused = vec!["x", "y", "z"];
+        boost_by_frecency(&mut unused, |_| 0.0);
+        assert_eq!(unused, ["x", "y", "z"]);
+    }
+
+    /// Files every abbreviation test ranks
+    const FILES: &[&str] = &[
+        "/home/u/notes/Planning-Project.md",
+        "/home/u/notes/project.md",
+        "/home/u/notes/PrjReport.md",
+        "/home/u/notes/ProjectPlanning.md",
+        "/home/u/notes/plan/ProjectList.md",
+        "/home/u/src/unused_rcfg.rs",
+        "/home/u/.config/user_config.toml",
+        "/home/u/docs/user-guide.pdf",
+        "/home/u/docs/userconfig.bak",
+        "/home/u/src/ScrollView2Controller.kt",
+    ];
+
+    #[test]
+    fn test_word_starts() {
+        let chars: Vec<char> = "myFile_v2.TXT".chars().collect();
+        let starts: String = word_starts(&chars)
+            .iter()
+            .zip(&chars)
+            .filter(|(start, _)| **start)
+            .map(|(_, c)| *c)
+            .collect();
+        assert_eq!(starts, "mFv2T");
+    }
+
+    #[test]
+    fn test_boundary_quality() {
+        assert_eq!(boundary_quality("user_config.toml", "uct"), Some(100));
+        assert!(boundary_quality("ProjectPlanning.md", "prjPlan").is_some());
+        assert_eq!(boundary_quality("Planning-Project.md", "prjPlan"), None);
+        assert_eq!(boundary_quality("anything", "_-"), None);
+        // Word starts beat letters in the middle of words
+        assert!(
+            boundary_quality("user_config.toml", "usrcfg")
+                > boundary_quality("userconfig.bak", "usrcfg")
+        );
+    }
+
+    #[test]
+    fn test_rank_paths_prefers_word_starts() {
+        // (query, best match)
+        let cases = [
+            ("prjPlan", "/home/u/notes/ProjectPlanning.md"),
+            ("usr_cfg", "/home/u/.config/user_config.toml"),
+            ("sv2c", "/home/u/src/ScrollView2Controller.kt"),
+            ("project", "/home/u/notes/project.md"),
+            ("ug", "/home/u/docs/user-guide.pdf"),
+        ];
+        let matcher = SkimMatcherV2::default().smart_case();
+        for (query, best) in cases {
+            let ranked = rank_paths(&matcher, FILES.to_vec(), |p| p, query, 10);
+            assert_eq!(ranked.first(), Some(&best), "query {query}: {ranked:?}");
+        }
+    }
+
+    #[test]
+    fn test_rank_paths_filters_and_caps() {
+        let matcher = SkimMatcherV2::default();
+        // Every word has to match
+        let ranked = rank_paths(&matcher, FILES.to_vec(), |p| p, "project list", 10);
+        assert_eq!(ranked, ["/home/u/notes/plan/ProjectList.md"]);
+        // A folder match counts, below file name matches
+        let ranked = rank_paths(&matcher, FILES.to_vec(), |p| p, "plan", 10);
+        assert_eq!(ranked.last(), Some(&"/home/u/notes/plan/ProjectList.md"));
+        assert_eq!(
+            rank_paths(&matcher, FILES.to_vec(), |p| p, " ", 3),
+            FILES[..3]
+        );
+        assert_eq!(rank_paths(&matcher, FILES.to_vec(), |p| p, "u", 2).len(), 2);
+    }
+}
@@ -0,0 +1,139 @@
+//! Recently used documents for the `:r` mode
+//!
+//! GTK applications record the files they open in `recently-used.xbel`,
+//! which [`gtk4::RecentManager`] reads and keeps up to date. Only local
+//! files are listed, since they are opened like the rows of `:f`; entries
+//! whose file was moved or deleted are dropped instead of showing dead
+//! rows.
+//!
+//! The manager lives on the main thread and already holds the list, so it
+//! is read again for every query.
+
+use crate::providers::ranking::abbreviation_score;
+use fuzzy_matcher::FuzzyMatcher;
+use gtk4::gio;
+use gtk4::prelude::*;
+use log::debug;
+use std::path::PathBuf;
+
+/// A recently used local file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecentDocument {
+    /// File on disk
+    pub path: PathBuf,
+    /// Name the application recorded, usually the file name
+    pub display_name: String,
+    /// `file://` URI as recorded
+    pub uri: String,
+    /// Last time the file was used, in seconds since the epoch
+    pub visited: i64,
+}
+
+/// The recently used files that still exist, as recorded by GTK
+#[must_use]
+pub fn load_recent_documents() -> Vec<RecentDocument> {
+    let items = gtk4::RecentManager::default().items();
+    let total = items.len();
+    let documents: Vec<RecentDocument> = items
+        .into_iter()
+        .filter(gtk4::RecentInfo::is_local)
+        .filter_map(|info| {
+            let uri = info.uri().to_string();
+            let path = gio::File::for_uri(&uri).path()?;
+            path.exists().then(|| RecentDocument {
+                path,
+                display_name: info.display_name().to_string(),
+                uri,
+                visited: info.visited().to_unix(),
+            })
+        })
+        .collect();
+    debug!(
+        "[recent] {} of {total} recent item(s) are existing local files",
+        documents.len()
+    );
+    documents
+}
+
+/// The documents matching `query`, most recently used first, at most `max`
+///
+/// Every word has to match the display name or the URI; an empty query
+/// lists them all.
+#[must_use]
+pub fn match_recent_documents<'a>(
+    matcher: &impl FuzzyMatcher,
+    query: &str,
+    documents: &'a [RecentDocument],
+    max: usize,
+) -> Vec<&'a RecentDocument> {
+    let words: Vec<&str> = query.split_whitespace().collect();
+    let mut found: Vec<&RecentDocument> = documents
+        .iter()
+        .filter(|doc| {
+            words.iter().all(|word| {
+                abbreviation_score(matcher, &doc.display_name, word).is_some()
+                    || matcher.fuzzy_match(&doc.uri, word).is_some()
+            })
+        })
+        .collect();
+    found.sort_by(|a, b| {
+        b.visited
+            .cmp(&a.visited)
+            .then_with(|| a.display_name.cmp(&b.display_name))
+    });
+    found.truncate(max);
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuzzy_matcher::skim::SkimMatcherV2;
+
+    fn document(name: &str, dir: &str, visited: i64) -> RecentDocument {
+        RecentDocument {
+            path: PathBuf::from(format!("{dir}/{name}")),
+            display_name: name.to_string(),
+            uri: format!("file://{dir}/{name}"),
+            visited,
+        }
+    }
+
+    fn names<'a>(found: &[&'a RecentDocument]) -> Vec<&'a str> {
+        found.iter().map(|d| d.display_name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_match_recent_empty_query_by_visit() {
+        let documents = [
+            document("old.txt", "/home/u", 10),
+            document("new.pdf", "/home/u", 30),
+            document("mid.odt", "/home/u", 20),
+        ];
+        let matcher = SkimMatcherV2::default();
+        let found = match_recent_documents(&matcher, "", &documents, 10);
+        assert_eq!(names(&found), ["new.pdf", "mid.odt", "old.txt"]);
+        assert_eq!(
+            match_recent_documents(&matcher, " ", &documents, 2).len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_match_recent_by_name_and_uri() {
+        let documents = [
+            document("report.pdf", "/home/u/work", 10),
+            document("notes.md", "/home/u/personal", 20),
+            document("report-draft.odt", "/home/u/personal", 5),
+        ];
+        let matcher = SkimMatcherV2::default();
+        // Matches are still listed by visit, not by score
+        let found = match_recent_documents(&matcher, "report", &documents, 10);
+        assert_eq!(names(&found), ["report.pdf", "report-draft.odt"]);
+        let found = match_recent_documents(&matcher, "personal", &documents, 10);
+        assert_eq!(names(&found), ["notes.md", "report-draft.odt"]);
+        let found = match_recent_documents(&matcher, "work report", &documents, 10);
+        assert_eq!(names(&found), ["report.pdf"]);
+        assert!(match_recent_documents(&matcher, "xyzzy", &documents, 10).is_empty());
+    }
+}
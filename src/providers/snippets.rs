@@ -0,0 +1,273 @@
+//! Text snippets for the `:snip` mode
+//!
+//! Snippets come from the `[snippets]` table, as a text or a file, and
+//! from the `*.txt` files of `~/.config/grunner/snippets`, named after the
+//! file. A name set in the table wins over a file of the same name. Files
+//! are small, so everything is read again for every query and edits show
+//! up right away.
+//!
+//! `{date}` and `{time}` in a snippet are replaced when it is copied, see
+//! [`expand_placeholders`].
+
+use crate::core::config::SnippetConfig;
+use crate::core::global_state::get_home_dir;
+use crate::providers::ranking::abbreviation_score;
+use crate::utils::expand_home;
+use chrono::NaiveDateTime;
+use fuzzy_matcher::FuzzyMatcher;
+use log::{debug, warn};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Characters of a snippet shown below its name
+const PREVIEW_CHARS: usize = 80;
+
+/// A named text copied by `:snip`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Snippet {
+    pub name: String,
+    /// Text with its placeholders, as written
+    pub text: String,
+}
+
+impl Snippet {
+    /// The text on one line, lines separated by `↵`, cut at
+    /// [`PREVIEW_CHARS`]
+    #[must_use]
+    pub fn preview(&self) -> String {
+        let joined = self
+            .text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ↵ ");
+        if joined.chars().count() <= PREVIEW_CHARS {
+            return joined;
+        }
+        let mut cut: String = joined.chars().take(PREVIEW_CHARS - 1).collect();
+        cut.push('…');
+        cut
+    }
+}
+
+/// Folder whose `*.txt` files are snippets, also the base of relative
+/// `file` entries
+#[must_use]
+pub fn snippets_dir() -> PathBuf {
+    PathBuf::from(get_home_dir())
+        .join(".config")
+        .join("grunner")
+        .join("snippets")
+}
+
+/// Load the snippets of `configured` and of the snippets folder, by name
+#[must_use]
+pub fn load_snippets(configured: &BTreeMap<String, SnippetConfig>) -> Vec<Snippet> {
+    load_snippets_from(configured, &snippets_dir())
+}
+
+fn load_snippets_from(configured: &BTreeMap<String, SnippetConfig>, dir: &Path) -> Vec<Snippet> {
+    let mut snippets: Vec<Snippet> = configured
+        .iter()
+        .filter_map(|(name, config)| {
+            let text = match config {
+                SnippetConfig::Text(text) => text.clone(),
+                SnippetConfig::File { file } => read_snippet_file(&resolve_file(dir, file))?,
+            };
+            Some(Snippet {
+                name: name.clone(),
+                text,
+            })
+        })
+        .collect();
+
+    let names: HashSet<String> = snippets.iter().map(|s| s.name.clone()).collect();
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.extension().is_none_or(|ext| ext != "txt") {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            if names.contains(name) {
+                continue;
+            }
+            if let Some(text) = read_snippet_file(&path) {
+                snippets.push(Snippet {
+                    name: name.to_string(),
+                    text,
+                });
+            }
+        }
+    }
+    snippets.sort_by(|a, b| a.name.cmp(&b.name));
+    debug!("[snippets] {} snippet(s) loaded", snippets.len());
+    snippets
+}
+
+/// `file` of a `[snippets]` entry, with `~` expanded and relative paths
+/// taken in `dir`
+fn resolve_file(dir: &Path, file: &str) -> PathBuf {
+    let path = expand_home(file);
+    if path.is_relative() {
+        dir.join(path)
+    } else {
+        path
+    }
+}
+
+/// Read a snippet file, without the final newline editors add
+fn read_snippet_file(path: &Path) -> Option<String> {
+    match std::fs::read_to_string(path) {
+        Ok(mut text) => {
+            if text.ends_with('\n') {
+                text.pop();
+                if text.ends_with('\r') {
+                    text.pop();
+                }
+            }
+            Some(text)
+        }
+        Err(e) => {
+            warn!("[snippets] Could not read {}: {e}", path.display());
+            None
+        }
+    }
+}
+
+/// Replace `{date}` (`2024-05-17`) and `{time}` (`14:03`) in `text` by
+/// `now`
+#[must_use]
+pub fn expand_placeholders(text: &str, now: &NaiveDateTime) -> String {
+    text.replace("{date}", &now.format("%Y-%m-%d").to_string())
+        .replace("{time}", &now.format("%H:%M").to_string())
+}
+
+/// The snippets whose name matches `query`, best first, at most `max`
+///
+/// With an empty query every snippet is listed, by name.
+#[must_use]
+pub fn match_snippets<'a>(
+    matcher: &impl FuzzyMatcher,
+    query: &str,
+    snippets: &'a [Snippet],
+    max: usize,
+) -> Vec<&'a Snippet> {
+    let words: Vec<&str> = query.split_whitespace().collect();
+    if words.is_empty() {
+        return snippets.iter().take(max).collect();
+    }
+    let mut scored: Vec<(i64, &Snippet)> = snippets
+        .iter()
+        .filter_map(|snippet| {
+            words
+                .iter()
+                .map(|word| abbreviation_score(matcher, &snippet.name, word))
+                .sum::<Option<i64>>()
+                .map(|score| (score, snippet))
+        })
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().take(max).map(|(_, s)| s).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use fuzzy_matcher::skim::SkimMatcherV2;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("grunner_test_snip_{name}_{}", std::process::id()))
+    }
+
+    fn snippet(name: &str, text: &str) -> Snippet {
+        Snippet {
+            name: name.to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_load_snippets_from_config_and_folder() {
+        let dir = temp_dir("load");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("addr.txt"), "1 Main St\r\nSpringfield\r\n").unwrap();
+        std::fs::write(dir.join("sig.txt"), "shadowed by the config\n").unwrap();
+        std::fs::write(dir.join("reply.md"), "not a .txt file").unwrap();
+        std::fs::write(dir.join("long.body"), "Thanks!\n\n").unwrap();
+
+        let configured = BTreeMap::from([
+            (
+                "sig".to_string(),
+                SnippetConfig::Text("Best regards,\nNihmar".to_string()),
+            ),
+            (
+                "thanks".to_string(),
+                SnippetConfig::File {
+                    file: "long.body".to_string(),
+                },
+            ),
+            (
+                "gone".to_string(),
+                SnippetConfig::File {
+                    file: "missing.txt".to_string(),
+                },
+            ),
+        ]);
+        let snippets = load_snippets_from(&configured, &dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            snippets,
+            [
+                snippet("addr", "1 Main St\r\nSpringfield"),
+                snippet("sig", "Best regards,\nNihmar"),
+                snippet("thanks", "Thanks!\n"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_preview() {
+        assert_eq!(
+            snippet("sig", "Best regards,\n\n  Nihmar\n").preview(),
+            "Best regards, ↵ Nihmar"
+        );
+        let long = snippet("lorem", &"word ".repeat(40));
+        let preview = long.preview();
+        assert_eq!(preview.chars().count(), PREVIEW_CHARS);
+        assert!(preview.ends_with('…'));
+    }
+
+    #[test]
+    fn test_expand_placeholders() {
+        let now = NaiveDate::from_ymd_opt(2024, 5, 7)
+            .unwrap()
+            .and_hms_opt(9, 3, 0)
+            .unwrap();
+        assert_eq!(
+            expand_placeholders("Sent {date} at {time}, {date}", &now),
+            "Sent 2024-05-07 at 09:03, 2024-05-07"
+        );
+        assert_eq!(expand_placeholders("{name}", &now), "{name}");
+    }
+
+    #[test]
+    fn test_match_snippets() {
+        let snippets = [
+            snippet("address", ""),
+            snippet("signature", ""),
+            snippet("sig-work", ""),
+        ];
+        let matcher = SkimMatcherV2::default();
+        assert_eq!(match_snippets(&matcher, "", &snippets, 10).len(), 3);
+        assert_eq!(match_snippets(&matcher, "", &snippets, 2).len(), 2);
+        let found = match_snippets(&matcher, "sig", &snippets, 10);
+        assert_eq!(found.len(), 2);
+        assert!(found.iter().all(|s| s.name.starts_with("sig")));
+        assert!(match_snippets(&matcher, "xyzzy", &snippets, 10).is_empty());
+    }
+}
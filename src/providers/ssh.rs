@@ -0,0 +1,493 @@
+//! SSH hosts for the `:ssh` mode
+//!
+//! Hosts are the `Host` aliases of `~/.ssh/config`, following `Include`
+//! directives, and with `[ssh] known_hosts` also the hosts of
+//! `~/.ssh/known_hosts` that no alias covers. Wildcard patterns such as
+//! `Host *.internal` or `!bastion` are not hosts and are skipped. Both
+//! files are small, so they are read again for every query.
+
+use crate::core::global_state::get_home_dir;
+use crate::launcher::glob_matches;
+use crate::providers::ranking::abbreviation_score;
+use fuzzy_matcher::FuzzyMatcher;
+use log::{debug, warn};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// How deep `Include` directives are followed, as in OpenSSH
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// A host that `ssh` can connect to
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SshHost {
+    /// Alias of a `Host` line, or the host name of a `known_hosts` entry
+    pub alias: String,
+    /// `HostName` of the alias
+    pub hostname: Option<String>,
+    /// `User` of the alias
+    pub user: Option<String>,
+    /// `Port` of the alias, or the port of a `[host]:port` entry
+    pub port: Option<String>,
+    /// Whether the host comes from `known_hosts` rather than the config
+    pub known_host: bool,
+}
+
+impl SshHost {
+    fn alias(alias: &str) -> Self {
+        Self {
+            alias: alias.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Second line of the row: `user@hostname:port` with the parts the
+    /// config sets, to tell similar aliases apart
+    #[must_use]
+    pub fn description(&self) -> String {
+        let mut target = String::new();
+        if let Some(user) = &self.user {
+            target.push_str(user);
+            target.push('@');
+        }
+        target.push_str(self.hostname.as_deref().unwrap_or(&self.alias));
+        if let Some(port) = &self.port {
+            target.push(':');
+            target.push_str(port);
+        }
+        let source = if self.known_host {
+            "known_hosts"
+        } else {
+            "ssh config"
+        };
+        format!("{target} · {source}")
+    }
+
+    /// Arguments of `ssh` that connect to the host
+    ///
+    /// Aliases are passed as they are, so `ssh` applies their settings
+    /// itself; only `known_hosts` entries need their port.
+    #[must_use]
+    pub fn ssh_args(&self) -> Vec<String> {
+        match (&self.port, self.known_host) {
+            (Some(port), true) => vec!["-p".to_string(), port.clone(), self.alias.clone()],
+            _ => vec![self.alias.clone()],
+        }
+    }
+}
+
+/// Load the hosts of `~/.ssh/config`, and of `~/.ssh/known_hosts` if
+/// `known_hosts` is set
+#[must_use]
+pub fn load_ssh_hosts(known_hosts: bool) -> Vec<SshHost> {
+    let ssh_dir = Path::new(get_home_dir()).join(".ssh");
+    let mut hosts = read_ssh_config(&ssh_dir.join("config"), &ssh_dir);
+    if known_hosts && let Ok(text) = std::fs::read_to_string(ssh_dir.join("known_hosts")) {
+        let known = parse_known_hosts(&text);
+        hosts = merge_known_hosts(hosts, known);
+    }
+    debug!("Loaded {} SSH hosts", hosts.len());
+    hosts
+}
+
+/// Hosts of the config file `path`, with `Include` paths relative to
+/// `ssh_dir`
+fn read_ssh_config(path: &Path, ssh_dir: &Path) -> Vec<SshHost> {
+    let mut parser = ConfigParser {
+        ssh_dir: ssh_dir.to_path_buf(),
+        home: PathBuf::from(get_home_dir()),
+        hosts: Vec::new(),
+        visited: HashSet::new(),
+    };
+    parser.read_file(path, 0);
+    dedup_hosts(parser.hosts)
+}
+
+/// Hosts of the text of an SSH config file, without following `Include`
+#[must_use]
+pub fn parse_ssh_config(text: &str) -> Vec<SshHost> {
+    let mut parser = ConfigParser {
+        ssh_dir: PathBuf::new(),
+        home: PathBuf::new(),
+        hosts: Vec::new(),
+        visited: HashSet::new(),
+    };
+    parser.parse(text, MAX_INCLUDE_DEPTH);
+    dedup_hosts(parser.hosts)
+}
+
+/// Reader of an SSH config and the files it includes
+struct ConfigParser {
+    ssh_dir: PathBuf,
+    home: PathBuf,
+    hosts: Vec<SshHost>,
+    /// Files read so far, so include loops end
+    visited: HashSet<PathBuf>,
+}
+
+impl ConfigParser {
+    fn read_file(&mut self, path: &Path, depth: usize) {
+        if !self.visited.insert(path.to_path_buf()) {
+            return;
+        }
+        match std::fs::read_to_string(path) {
+            Ok(text) => self.parse(&text, depth),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => warn!("Could not read {}: {e}", path.display()),
+        }
+    }
+
+    fn parse(&mut self, text: &str, depth: usize) {
+        // Indices in `hosts` of the aliases of the current `Host` block
+        let mut block: Vec<usize> = Vec::new();
+        for line in text.lines() {
+            let Some((keyword, args)) = split_directive(line) else {
+                continue;
+            };
+            match keyword.to_ascii_lowercase().as_str() {
+                "host" => {
+                    block.clear();
+                    for alias in args.iter().filter(|a| !is_pattern(a)) {
+                        block.push(self.hosts.len());
+                        self.hosts.push(SshHost::alias(alias));
+                    }
+                }
+                "match" => block.clear(),
+                "include" if depth < MAX_INCLUDE_DEPTH => {
+                    for pattern in &args {
+                        for path in self.include_paths(pattern) {
+                            self.read_file(&path, depth + 1);
+                        }
+                    }
+                }
+                "include" => warn!("Not following SSH config Include nested too deep"),
+                // As in ssh, the first value of a setting wins
+                key @ ("hostname" | "user" | "port") => {
+                    let Some(value) = args.first() else {
+                        continue;
+                    };
+                    for &i in &block {
+                        let host = &mut self.hosts[i];
+                        let field = match key {
+                            "hostname" => &mut host.hostname,
+                            "user" => &mut host.user,
+                            _ => &mut host.port,
+                        };
+                        field.get_or_insert_with(|| value.clone());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Files named by an `Include` argument, sorted like the shell glob
+    /// ssh uses
+    ///
+    /// Wildcards are supported in the file name, as in
+    /// `Include config.d/*`.
+    fn include_paths(&self, pattern: &str) -> Vec<PathBuf> {
+        let path = if let Some(rest) = pattern.strip_prefix("~/") {
+            self.home.join(rest)
+        } else {
+            self.ssh_dir.join(pattern)
+        };
+        let Some(name) = path.file_name().map(|n| n.to_string_lossy().into_owned()) else {
+            return Vec::new();
+        };
+        if !is_pattern(&name) {
+            return vec![path];
+        }
+        let Some(dir) = path.parent() else {
+            return Vec::new();
+        };
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .flatten()
+            .filter(|e| {
+                let file = e.file_name().to_string_lossy().into_owned();
+                !file.starts_with('.') && glob_matches(&name, &file)
+            })
+            .map(|e| e.path())
+            .filter(|p| p.is_file())
+            .collect();
+        paths.sort();
+        paths
+    }
+}
+
+/// Keyword and arguments of a config line, `None` for blank lines and
+/// comments
+///
+/// The keyword may be followed by `=`, and arguments may be double-quoted.
+fn split_directive(line: &str) -> Option<(String, Vec<String>)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let end = line.find(|c: char| c.is_whitespace() || c == '=')?;
+    let keyword = line[..end].to_string();
+    let rest = line[end..].trim_start();
+    let rest = rest.strip_prefix('=').unwrap_or(rest);
+
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in rest.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    args.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        args.push(current);
+    }
+    Some((keyword, args))
+}
+
+/// Whether a host or file name is a pattern rather than a name
+fn is_pattern(name: &str) -> bool {
+    name.contains(['*', '?', '!'])
+}
+
+/// Keep the first host of each alias
+fn dedup_hosts(hosts: Vec<SshHost>) -> Vec<SshHost> {
+    let mut seen = HashSet::new();
+    hosts
+        .into_iter()
+        .filter(|h| seen.insert(h.alias.clone()))
+        .collect()
+}
+
+/// Hosts of a `known_hosts` file, in file order
+///
+/// Hashed entries cannot be read back and are skipped, as are wildcard
+/// patterns and revoked keys. `[host]:port` entries keep their port.
+#[must_use]
+pub fn parse_known_hosts(text: &str) -> Vec<SshHost> {
+    let mut hosts = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        let mut names = fields.next().unwrap_or_default();
+        if let Some(marker) = names.strip_prefix('@') {
+            if marker != "cert-authority" {
+                continue;
+            }
+            names = fields.next().unwrap_or_default();
+        }
+        for name in names.split(',') {
+            if name.starts_with('|') || is_pattern(name) || name.is_empty() {
+                continue;
+            }
+            let host = match name.strip_prefix('[').and_then(|n| n.split_once("]:")) {
+                Some((host, port)) => SshHost {
+                    alias: host.to_string(),
+                    port: (port != "22").then(|| port.to_string()),
+                    known_host: true,
+                    ..SshHost::default()
+                },
+                None => SshHost {
+                    alias: name.to_string(),
+                    known_host: true,
+                    ..SshHost::default()
+                },
+            };
+            hosts.push(host);
+        }
+    }
+    hosts
+}
+
+/// Append the `known_hosts` entries not already reachable through an
+/// alias of the config, by alias or `HostName`
+fn merge_known_hosts(mut hosts: Vec<SshHost>, known: Vec<SshHost>) -> Vec<SshHost> {
+    let mut seen: HashSet<String> = hosts
+        .iter()
+        .flat_map(|h| [Some(h.alias.clone()), h.hostname.clone()])
+        .flatten()
+        .collect();
+    hosts.extend(known.into_iter().filter(|h| seen.insert(h.alias.clone())));
+    hosts
+}
+
+/// The hosts matching `query`, best first, at most `max`
+///
+/// Every word has to match the alias or the host name. An empty query
+/// keeps the order of the config file.
+#[must_use]
+pub fn match_ssh_hosts<'a>(
+    matcher: &impl FuzzyMatcher,
+    query: &str,
+    hosts: &'a [SshHost],
+    max: usize,
+) -> Vec<&'a SshHost> {
+    let words: Vec<&str> = query.split_whitespace().collect();
+    if words.is_empty() {
+        return hosts.iter().take(max).collect();
+    }
+    let mut scored: Vec<(i64, &SshHost)> = hosts
+        .iter()
+        .filter_map(|host| {
+            words
+                .iter()
+                .map(|word| {
+                    let alias = abbreviation_score(matcher, &host.alias, word);
+                    // The host name only helps, the alias ranks first
+                    let hostname = host
+                        .hostname
+                        .as_deref()
+                        .and_then(|name| abbreviation_score(matcher, name, word))
+                        .map(|score| score / 2);
+                    alias.max(hostname)
+                })
+                .sum::<Option<i64>>()
+                .map(|score| (score, host))
+        })
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().take(max).map(|(_, h)| h).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuzzy_matcher::skim::SkimMatcherV2;
+
+    fn aliases(hosts: &[SshHost]) -> Vec<&str> {
+        hosts.iter().map(|h| h.alias.as_str()).collect()
+    }
+
+    #[test]
+    fn test_parse_ssh_config() {
+        let config = r#"
+# Work machines
+Host web1 web2
+    HostName 10.0.0.%h
+    User deploy
+
+Host *.internal !bastion
+    User admin
+
+host=db
+  hostname = db.example.com
+  Port 2222
+  User first
+  User second
+
+Match host db exec "true"
+    User matched
+
+Host "quoted alias"
+Host web1
+    User ignored
+"#;
+        let hosts = parse_ssh_config(config);
+        assert_eq!(aliases(&hosts), ["web1", "web2", "db", "quoted alias"]);
+        assert_eq!(hosts[0].user.as_deref(), Some("deploy"));
+        assert_eq!(hosts[1].hostname.as_deref(), Some("10.0.0.%h"));
+        assert_eq!(hosts[2].hostname.as_deref(), Some("db.example.com"));
+        assert_eq!(hosts[2].port.as_deref(), Some("2222"));
+        assert_eq!(hosts[2].user.as_deref(), Some("first"));
+        assert_eq!(hosts[3].user, None);
+    }
+
+    #[test]
+    fn test_read_ssh_config_follows_includes() {
+        let dir = std::env::temp_dir().join(format!("grunner_test_ssh_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("config.d")).unwrap();
+        std::fs::write(
+            dir.join("config"),
+            "Include config.d/*\nInclude missing\nHost main\n    HostName main.example.com\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("config.d/b.conf"), "Host beta\n").unwrap();
+        // Including the main config again must not loop
+        std::fs::write(
+            dir.join("config.d/a.conf"),
+            format!("Host alpha\nInclude {}\n", dir.join("config").display()),
+        )
+        .unwrap();
+
+        let hosts = read_ssh_config(&dir.join("config"), &dir);
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(aliases(&hosts), ["alpha", "beta", "main"]);
+        assert_eq!(hosts[2].hostname.as_deref(), Some("main.example.com"));
+    }
+
+    #[test]
+    fn test_parse_known_hosts() {
+        let text = "\
+github.com,140.82.121.4 ssh-ed25519 AAAA
+[git.example.com]:2222 ssh-rsa AAAA
+[plain.example.com]:22 ssh-rsa AAAA
+|1|c2FsdA==|aGFzaA== ssh-ed25519 AAAA
+*.corp ssh-rsa AAAA
+@revoked old.example.com ssh-rsa AAAA
+@cert-authority ca.example.com ssh-rsa AAAA
+# comment
+";
+        let hosts = parse_known_hosts(text);
+        assert_eq!(
+            aliases(&hosts),
+            [
+                "github.com",
+                "140.82.121.4",
+                "git.example.com",
+                "plain.example.com",
+                "ca.example.com"
+            ]
+        );
+        assert!(hosts.iter().all(|h| h.known_host));
+        assert_eq!(hosts[2].port.as_deref(), Some("2222"));
+        assert_eq!(hosts[3].port, None);
+        assert_eq!(hosts[2].ssh_args(), ["-p", "2222", "git.example.com"]);
+    }
+
+    #[test]
+    fn test_merge_known_hosts() {
+        let config = parse_ssh_config("Host gh\n    HostName github.com\nHost db\n");
+        let known = parse_known_hosts("github.com ssh-rsa A\ndb ssh-rsa A\nnew ssh-rsa A\n");
+        let hosts = merge_known_hosts(config, known);
+        assert_eq!(aliases(&hosts), ["gh", "db", "new"]);
+        assert!(!hosts[1].known_host);
+    }
+
+    #[test]
+    fn test_description_and_ssh_args() {
+        let hosts = parse_ssh_config(
+            "Host prod\n  HostName 10.0.0.5\n  User root\n  Port 2200\nHost bare\n",
+        );
+        assert_eq!(hosts[0].description(), "root@10.0.0.5:2200 · ssh config");
+        assert_eq!(hosts[1].description(), "bare · ssh config");
+        // The alias carries its settings, the port is left to ssh
+        assert_eq!(hosts[0].ssh_args(), ["prod"]);
+    }
+
+    #[test]
+    fn test_match_ssh_hosts() {
+        let matcher = SkimMatcherV2::default();
+        let hosts = parse_ssh_config(
+            "Host web-prod\n  HostName web.example.com\nHost db-prod\n  HostName 10.1.1.1\nHost staging\n",
+        );
+        assert_eq!(match_ssh_hosts(&matcher, "", &hosts, 2).len(), 2);
+        let found = match_ssh_hosts(&matcher, "db", &hosts, 10);
+        assert_eq!(found.first().map(|h| h.alias.as_str()), Some("db-prod"));
+        // The host name matches too
+        let found = match_ssh_hosts(&matcher, "example", &hosts, 10);
+        assert_eq!(
+            aliases(&found.into_iter().cloned().collect::<Vec<_>>()),
+            ["web-prod"]
+        );
+        assert!(match_ssh_hosts(&matcher, "zzz", &hosts, 10).is_empty());
+    }
+}
@@ -1,100 +1,367 @@
 //! Subprocess execution infrastructure for providers
 //!
-//! This module provides a generic subprocess runner that spawns background
-//! threads to execute commands and delivers results through channels.
-//! It supports generation tracking to cancel stale tasks.
+//! This module runs commands on a worker thread and delivers their output
+//! lines to the result list on the main thread, discarding output from
+//! searches that have since been superseded.
+//!
+//! Output is kept as raw bytes next to a lossy display string, because file
+//! names are not necessarily valid UTF-8 and a path with replacement
+//! characters in it cannot be opened.
+//!
+//! Commands are described by a [`CommandSpec`] and turned into a process by
+//! [`build_command`], which normalizes the environment inherited from the
+//! compositor or keybinding daemon: `~/.local/bin` and `/usr/local/bin` are
+//! added to `PATH` if missing, `LC_ALL=C.UTF-8` keeps the output we parse
+//! free of translations and locale-specific sorting, and the working
+//! directory is `$HOME`. Per-command overrides are applied last.
 
+use crate::actions::is_executable;
+use crate::core::global_state::get_home_dir;
+use crate::metrics;
 use crate::model::list_model::AppListModel;
-use gtk4::glib;
+use crate::model::worker::spawn_worker_with_updates;
+use log::{debug, warn};
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU32, Ordering};
 
-/// Unified subprocess execution handler
-///
-/// This struct encapsulates the common pattern of spawning a background thread,
-/// sending results through a channel, and polling for results in the main thread.
-/// It supports different result types and generation tracking to cancel stale tasks.
-pub struct SubprocessRunner<R> {
-    /// Channel receiver for results
-    rx: std::sync::mpsc::Receiver<R>,
-    /// Reference to the main list model for UI updates
-    model: AppListModel,
-    /// Generation ID to prevent stale updates after new searches
-    generation: u64,
-    /// Callback to process results and update the UI
-    #[allow(clippy::type_complexity)]
-    processor: Box<dyn Fn(&AppListModel, u64, R) + 'static>,
+/// Locale for commands whose output is parsed
+const PARSE_LOCALE: &str = "C.UTF-8";
+
+/// A command whose output Grunner parses
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandSpec {
+    /// Program name, looked up in the normalized `PATH`
+    pub program: String,
+    pub args: Vec<OsString>,
+    /// Environment overrides, applied after the normalized defaults
+    pub env: Vec<(String, OsString)>,
 }
 
-impl<R: 'static> SubprocessRunner<R> {
-    /// Create a new subprocess runner
-    ///
-    /// # Arguments
-    /// * `rx` - Channel receiver for results
-    /// * `model` - Reference to the `AppListModel` for UI updates
-    /// * `generation` - Generation ID to track stale tasks
-    /// * `processor` - Callback to process results and update UI
-    pub fn new<F>(
-        rx: std::sync::mpsc::Receiver<R>,
-        model: AppListModel,
-        generation: u64,
-        processor: F,
-    ) -> Self
-    where
-        F: Fn(&AppListModel, u64, R) + 'static,
-    {
+impl CommandSpec {
+    #[must_use]
+    pub fn new(program: impl Into<String>) -> Self {
         Self {
-            rx,
-            model,
-            generation,
-            processor: Box::new(processor),
+            program: program.into(),
+            args: Vec::new(),
+            env: Vec::new(),
         }
     }
 
-    /// Poll for subprocess results and update UI when ready
-    ///
-    /// This method checks for available output from the background thread
-    /// and updates the list store if the generation still matches.
-    /// If no data is ready yet, it schedules itself to run again on idle.
-    pub fn poll(self) {
-        match self.rx.try_recv() {
-            Ok(results) => {
-                if self.model.state.task_gen() == self.generation {
-                    (self.processor)(&self.model, self.generation, results);
-                }
-            }
-            Err(std::sync::mpsc::TryRecvError::Empty) => {
-                glib::idle_add_local_once(move || self.poll());
-            }
-            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
-                // Thread finished without sending data
-            }
+    /// Append an argument
+    #[must_use]
+    pub fn arg(mut self, arg: impl AsRef<OsStr>) -> Self {
+        self.args.push(arg.as_ref().to_os_string());
+        self
+    }
+
+    /// Override an environment variable for this command only
+    #[must_use]
+    pub fn env(mut self, key: impl Into<String>, value: impl AsRef<OsStr>) -> Self {
+        self.env.push((key.into(), value.as_ref().to_os_string()));
+        self
+    }
+}
+
+/// `PATH` for child processes
+///
+/// `~/.local/bin` and `/usr/local/bin` are put in front of `current` unless
+/// it already lists them; the existing order is otherwise kept.
+#[must_use]
+pub fn normalized_path(current: Option<&OsStr>, home: &Path) -> OsString {
+    let existing: Vec<PathBuf> = current
+        .map(|p| std::env::split_paths(p).collect())
+        .unwrap_or_default();
+    let mut dirs: Vec<PathBuf> = [home.join(".local/bin"), PathBuf::from("/usr/local/bin")]
+        .into_iter()
+        .filter(|dir| !existing.contains(dir))
+        .collect();
+    dirs.extend(existing);
+    std::env::join_paths(dirs).unwrap_or_else(|_| current.unwrap_or_default().to_os_string())
+}
+
+/// Find `program` in the `PATH` that child processes get
+#[must_use]
+pub fn find_program(program: &str) -> Option<PathBuf> {
+    let path = normalized_path(
+        std::env::var_os("PATH").as_deref(),
+        Path::new(get_home_dir()),
+    );
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(program))
+        .find(|p| is_executable(p))
+}
+
+/// Build the process for `spec` with a normalized environment
+///
+/// See the [module documentation](self) for what is normalized. Stdout is
+/// piped for parsing and stderr is discarded.
+#[must_use]
+pub fn build_command(spec: &CommandSpec) -> Command {
+    build_command_with(
+        spec,
+        Path::new(get_home_dir()),
+        std::env::var_os("PATH").as_deref(),
+    )
+}
+
+/// [`build_command`] with the home directory and inherited `PATH` given
+fn build_command_with(spec: &CommandSpec, home: &Path, path: Option<&OsStr>) -> Command {
+    let mut cmd = Command::new(&spec.program);
+    cmd.args(&spec.args)
+        .env("PATH", normalized_path(path, home))
+        .env("LC_ALL", PARSE_LOCALE)
+        .current_dir(home)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    for (key, value) in &spec.env {
+        cmd.env(key, value);
+    }
+    cmd
+}
+
+/// One line of command output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputLine {
+    /// The line exactly as the command printed it, without the newline
+    pub raw: Vec<u8>,
+    /// The line decoded for display, with invalid UTF-8 replaced by U+FFFD
+    pub display: String,
+}
+
+impl OutputLine {
+    #[must_use]
+    pub fn from_bytes(raw: &[u8]) -> Self {
+        Self {
+            raw: raw.to_vec(),
+            display: String::from_utf8_lossy(raw).into_owned(),
         }
     }
 }
 
-/// Spawn a subprocess with the given closure
+/// Run `cmd` in the background and hand its output lines to `processor`
 ///
-/// This static method creates a background thread that executes the provided
-/// command, collects results, and sends them through the channel.
+/// `processor` runs on the main thread, and only if no newer search has
+/// started (the task generation is unchanged) by the time output arrives.
 ///
 /// # Arguments
-/// * `cmd_fn` - Closure that creates and configures the Command
-/// * `max_results` - Maximum number of results to collect
-/// * `tx` - Channel sender for results
-pub fn spawn_subprocess<F>(cmd_fn: F, max_results: usize, tx: std::sync::mpsc::Sender<Vec<String>>)
+/// * `model` - The list model whose generation guards the update
+/// * `cmd` - Command to run; its stdout is read once it exits
+/// * `processor` - Callback that turns the output lines into list items
+pub fn run_subprocess_with<F>(model: &AppListModel, cmd: Command, processor: F)
 where
-    F: FnOnce() -> std::process::Command + Send + 'static,
+    F: Fn(&AppListModel, Vec<OutputLine>) + 'static,
 {
-    std::thread::spawn(move || {
-        let lines = cmd_fn()
-            .output()
-            .map(|out| {
-                String::from_utf8_lossy(&out.stdout)
-                    .lines()
-                    .take(max_results)
-                    .map(String::from)
-                    .collect::<Vec<_>>()
-            })
-            .unwrap_or_default();
-        let _ = tx.send(lines);
-    });
+    let max_results = model.config.max_results.get();
+    run_first_subprocess_with(model, vec![cmd], max_results, processor);
+}
+
+/// Like [`run_subprocess_with`], but run `cmds` in order until one prints
+/// anything, reading up to `limit` lines
+///
+/// Used by searches that rank the output themselves and have a looser
+/// command to fall back on when the first one finds nothing.
+pub fn run_first_subprocess_with<F>(
+    model: &AppListModel,
+    cmds: Vec<Command>,
+    limit: usize,
+    processor: F,
+) where
+    F: Fn(&AppListModel, Vec<OutputLine>) + 'static,
+{
+    let generation = model.state.task_gen();
+    let state = model.state.clone();
+    let model = model.clone();
+
+    spawn_worker_with_updates(
+        move |tx| {
+            let started = metrics::start();
+            let mut lines = Vec::new();
+            for cmd in cmds {
+                lines = command_output_lines(cmd, limit);
+                if !lines.is_empty() {
+                    break;
+                }
+            }
+            metrics::record_elapsed(started, |m, elapsed| m.subprocess = Some(elapsed));
+            let _ = tx.send(lines);
+        },
+        move || state.task_gen() == generation,
+        move |lines| processor(&model, lines),
+    );
+}
+
+/// Run `cmd` to completion and collect up to `max_results` stdout lines
+///
+/// Failure to spawn the command yields no lines.
+#[must_use]
+pub fn command_output_lines(mut cmd: Command, max_results: usize) -> Vec<OutputLine> {
+    cmd.output()
+        .map(|out| split_output_lines(&out.stdout, max_results))
+        .unwrap_or_default()
+}
+
+/// Run `query` with `sqlite3 -json` on a copy of the database `db`
+///
+/// Browsers and editors keep their databases locked while they run, so a
+/// copy is queried instead, along with its write-ahead log, which may still
+/// hold recent changes. Returns `None` without `sqlite3` or if copying or
+/// running it fails.
+#[must_use]
+pub fn query_sqlite_copy(db: &Path, query: &str) -> Option<String> {
+    static COPIES: AtomicU32 = AtomicU32::new(0);
+
+    if find_program("sqlite3").is_none() {
+        debug!("sqlite3 not found, skipping {}", db.display());
+        return None;
+    }
+    let name = db.file_name()?;
+    let copy_dir = std::env::temp_dir().join(format!(
+        "grunner-sqlite-{}-{}",
+        std::process::id(),
+        COPIES.fetch_add(1, Ordering::Relaxed)
+    ));
+    let copy = copy_dir.join(name);
+    let copied = std::fs::create_dir_all(&copy_dir).and_then(|()| std::fs::copy(db, &copy));
+    if let Err(e) = copied {
+        warn!("Failed to copy {}: {e}", db.display());
+        let _ = std::fs::remove_dir_all(&copy_dir);
+        return None;
+    }
+    let mut wal_name = name.to_os_string();
+    wal_name.push("-wal");
+    let wal = db.with_file_name(&wal_name);
+    if wal.is_file() {
+        let _ = std::fs::copy(&wal, copy_dir.join(&wal_name));
+    }
+
+    let spec = CommandSpec::new("sqlite3")
+        .arg("-json")
+        .arg(&copy)
+        .arg(query);
+    let output = match build_command(&spec).output() {
+        Ok(out) => Some(String::from_utf8_lossy(&out.stdout).into_owned()),
+        Err(e) => {
+            warn!("Failed to run sqlite3: {e}");
+            None
+        }
+    };
+    let _ = std::fs::remove_dir_all(&copy_dir);
+    output
+}
+
+/// Split raw output into at most `max_results` lines
+///
+/// Lines end at `\n`, with an optional `\r` before it, like [`str::lines`].
+fn split_output_lines(stdout: &[u8], max_results: usize) -> Vec<OutputLine> {
+    let stdout = stdout.strip_suffix(b"\n").unwrap_or(stdout);
+    if stdout.is_empty() {
+        return Vec::new();
+    }
+    stdout
+        .split(|&b| b == b'\n')
+        .take(max_results)
+        .map(|line| OutputLine::from_bytes(line.strip_suffix(b"\r").unwrap_or(line)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::ffi::OsStrExt;
+
+    fn env_of<'a>(cmd: &'a Command, key: &str) -> Option<&'a OsStr> {
+        cmd.get_envs()
+            .find(|(k, _)| *k == OsStr::new(key))
+            .and_then(|(_, v)| v)
+    }
+
+    #[test]
+    fn test_normalized_path_prepends_missing_dirs() {
+        let home = Path::new("/home/u");
+        assert_eq!(
+            normalized_path(Some(OsStr::new("/usr/bin:/bin")), home),
+            "/home/u/.local/bin:/usr/local/bin:/usr/bin:/bin"
+        );
+        // Present directories keep their place and are not duplicated
+        assert_eq!(
+            normalized_path(Some(OsStr::new("/usr/bin:/usr/local/bin")), home),
+            "/home/u/.local/bin:/usr/bin:/usr/local/bin"
+        );
+        assert_eq!(
+            normalized_path(None, home),
+            "/home/u/.local/bin:/usr/local/bin"
+        );
+    }
+
+    #[test]
+    fn test_build_command_normalizes_environment() {
+        let home = Path::new("/home/u");
+        let spec = CommandSpec::new("rg").arg("-i").arg("todo");
+        let cmd = build_command_with(&spec, home, Some(OsStr::new("/usr/bin")));
+
+        assert_eq!(cmd.get_program(), "rg");
+        assert_eq!(cmd.get_args().collect::<Vec<_>>(), ["-i", "todo"]);
+        assert_eq!(env_of(&cmd, "LC_ALL"), Some(OsStr::new("C.UTF-8")));
+        assert_eq!(
+            env_of(&cmd, "PATH"),
+            Some(OsStr::new("/home/u/.local/bin:/usr/local/bin:/usr/bin"))
+        );
+        assert_eq!(cmd.get_current_dir(), Some(home));
+    }
+
+    #[test]
+    fn test_build_command_applies_overrides_last() {
+        let spec = CommandSpec::new("find")
+            .env("LC_ALL", "en_US.UTF-8")
+            .env("RIPGREP_CONFIG_PATH", "");
+        let cmd = build_command_with(&spec, Path::new("/home/u"), None);
+        assert_eq!(env_of(&cmd, "LC_ALL"), Some(OsStr::new("en_US.UTF-8")));
+        assert_eq!(env_of(&cmd, "RIPGREP_CONFIG_PATH"), Some(OsStr::new("")));
+    }
+
+    #[test]
+    fn test_build_command_runs_in_c_locale() {
+        let cmd = build_command(&CommandSpec::new("sh").arg("-c").arg("echo $LC_ALL; pwd"));
+        let lines = command_output_lines(cmd, 10);
+        let display: Vec<&str> = lines.iter().map(|l| l.display.as_str()).collect();
+        assert_eq!(display, [PARSE_LOCALE, get_home_dir()]);
+    }
+
+    #[test]
+    fn test_split_output_lines() {
+        let lines = split_output_lines(b"/a.txt\n/b.txt\r\n/c.txt\n", 10);
+        let display: Vec<&str> = lines.iter().map(|l| l.display.as_str()).collect();
+        assert_eq!(display, vec!["/a.txt", "/b.txt", "/c.txt"]);
+        assert_eq!(split_output_lines(b"/a\n/b\n/c", 2).len(), 2);
+        assert!(split_output_lines(b"", 10).is_empty());
+        assert!(split_output_lines(b"\n", 10).is_empty());
+    }
+
+    #[test]
+    fn test_split_output_lines_keeps_invalid_utf8() {
+        let lines = split_output_lines(b"/tmp/caf\xe9.txt\n", 10);
+        assert_eq!(lines[0].raw, b"/tmp/caf\xe9.txt");
+        assert_eq!(lines[0].display, "/tmp/caf\u{fffd}.txt");
+    }
+
+    #[test]
+    fn test_command_output_lists_non_utf8_file_name() {
+        let dir = std::env::temp_dir().join("grunner_test_subprocess_non_utf8");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let name = std::ffi::OsStr::from_bytes(b"r\xe9sum\xe9.txt");
+        std::fs::write(dir.join(name), "cv").unwrap();
+
+        let cmd = build_command(&CommandSpec::new("find").arg(&dir).arg("-type").arg("f"));
+        let lines = command_output_lines(cmd, 10);
+
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].display.contains('\u{fffd}'));
+        let path = Path::new(std::ffi::OsStr::from_bytes(&lines[0].raw));
+        assert_eq!(path, dir.join(name));
+        assert!(path.exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
@@ -0,0 +1,118 @@
+//! Web searches configured in `[web_searches]`
+//!
+//! `:ddg rust gtk` looks up the `ddg` keyword and offers a single row that
+//! opens its URL template with `{}` replaced by the percent-encoded terms.
+
+use crate::core::config::WebSearchConfig;
+use crate::utils::expand_home;
+
+/// Icon of searches that do not configure one
+const DEFAULT_ICON: &str = "web-browser";
+
+/// Percent-encode `text` for use inside a URL
+///
+/// Only the RFC 3986 unreserved characters are kept; everything else,
+/// including spaces and non-ASCII text, is encoded byte by byte.
+#[must_use]
+pub fn percent_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+/// URL searching `terms` with `template`
+///
+/// Every `{}` is replaced by the encoded terms; a template without `{}` gets
+/// them appended.
+#[must_use]
+pub fn search_url(template: &str, terms: &str) -> String {
+    let encoded = percent_encode(terms);
+    if template.contains("{}") {
+        template.replace("{}", &encoded)
+    } else {
+        format!("{template}{encoded}")
+    }
+}
+
+/// Name of a search in its row: the configured name, or the host of its URL
+#[must_use]
+pub fn display_name(search: &WebSearchConfig) -> String {
+    if let Some(name) = search.name() {
+        return name.to_string();
+    }
+    let url = search.url();
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let host = rest.split(['/', '?', '#']).next().unwrap_or(rest);
+    host.strip_prefix("www.").unwrap_or(host).to_string()
+}
+
+/// Icon of a search: the configured icon name or path, or "web-browser"
+#[must_use]
+pub fn icon(search: &WebSearchConfig) -> String {
+    match search.icon() {
+        Some(icon) if icon.starts_with('~') => expand_home(icon).to_string_lossy().into_owned(),
+        Some(icon) if !icon.is_empty() => icon.to_string(),
+        _ => DEFAULT_ICON.to_string(),
+    }
+}
+
+/// Title of the row searching `terms`
+#[must_use]
+pub fn row_title(search: &WebSearchConfig, terms: &str) -> String {
+    format!("Search {} for '{terms}'", display_name(search))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn full(url: &str, name: Option<&str>, icon: Option<&str>) -> WebSearchConfig {
+        WebSearchConfig::Full {
+            url: url.to_string(),
+            name: name.map(str::to_string),
+            icon: icon.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_percent_encode() {
+        assert_eq!(percent_encode("rust gtk"), "rust%20gtk");
+        assert_eq!(percent_encode("a&b=c?#/"), "a%26b%3Dc%3F%23%2F");
+        assert_eq!(percent_encode("Köln"), "K%C3%B6ln");
+        assert_eq!(percent_encode("v1.2_x-y~z"), "v1.2_x-y~z");
+    }
+
+    #[test]
+    fn test_search_url() {
+        assert_eq!(
+            search_url("https://duckduckgo.com/?q={}", "rust gtk"),
+            "https://duckduckgo.com/?q=rust%20gtk"
+        );
+        assert_eq!(
+            search_url("https://aur.archlinux.org/packages?K=", "yay"),
+            "https://aur.archlinux.org/packages?K=yay"
+        );
+    }
+
+    #[test]
+    fn test_display_name_and_title() {
+        let ddg = WebSearchConfig::Template("https://www.duckduckgo.com/?q={}".to_string());
+        assert_eq!(display_name(&ddg), "duckduckgo.com");
+        let aur = full("https://aur.archlinux.org/packages?K={}", Some("AUR"), None);
+        assert_eq!(row_title(&aur, "paru"), "Search AUR for 'paru'");
+    }
+
+    #[test]
+    fn test_icon_defaults_to_web_browser() {
+        let plain = WebSearchConfig::Template("https://example.org/?q={}".to_string());
+        assert_eq!(icon(&plain), "web-browser");
+        let themed = full("https://example.org/?q={}", None, Some("system-search"));
+        assert_eq!(icon(&themed), "system-search");
+    }
+}
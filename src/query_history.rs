@@ -0,0 +1,215 @@
+//! History of the queries that led to an activation
+//!
+//! Every query a result was activated from is added to `QueryHistory`,
+//! newest first, and saved as a small JSON file in the user's cache
+//! directory, like the usage history. Alt+Up and Alt+Down (or Ctrl+N), or Up
+//! in an empty query, bring them back into the search entry, see
+//! [`HistoryBrowser`].
+//!
+//! A query typed again moves to the front instead of being added twice.
+//! `:pw` queries are never recorded, since they name password store
+//! entries. `[privacy] query_history` caps the number of queries kept; 0
+//! records nothing and deletes the saved history.
+
+use crate::app_mode::ColonCommand;
+use crate::utils::json_cache;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Default number of queries kept
+pub const DEFAULT_QUERY_HISTORY: usize = 50;
+
+/// Colon commands whose queries are never recorded
+const PRIVATE_COMMANDS: &[ColonCommand] = &[ColonCommand::Pass];
+
+/// Whether `query` runs a command whose queries are never recorded
+#[must_use]
+pub fn is_private(query: &str) -> bool {
+    ColonCommand::from_query(query).is_some_and(|c| PRIVATE_COMMANDS.contains(&c))
+}
+
+/// Queries activated from, newest first
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueryHistory {
+    #[serde(default)]
+    pub queries: Vec<String>,
+}
+
+impl QueryHistory {
+    /// Load the history from `path`
+    ///
+    /// A missing or unreadable file yields an empty history.
+    #[must_use]
+    pub fn load_from(path: &Path) -> Self {
+        json_cache::load(path, "query history")
+    }
+
+    /// Write the history to `path`
+    ///
+    /// # Errors
+    /// Returns an error if the directory cannot be created or the file
+    /// cannot be written.
+    pub fn save_to(&self, path: &Path) -> std::io::Result<()> {
+        json_cache::store(path, self)
+    }
+
+    /// Write the history to [`query_history_path`], logging failures
+    pub fn save(&self) {
+        let path = query_history_path();
+        debug!("Saving query history to {}", path.display());
+        if let Err(e) = self.save_to(&path) {
+            warn!("Failed to save query history {}: {e}", path.display());
+        }
+    }
+
+    /// Add `query` as the newest entry, keeping at most `max`
+    ///
+    /// Blank and private queries are ignored, and a query already in the
+    /// history moves to the front. Surrounding whitespace is dropped.
+    /// Returns whether the history changed.
+    pub fn push(&mut self, query: &str, max: usize) -> bool {
+        let query = query.trim();
+        if query.is_empty() || is_private(query) || max == 0 {
+            return false;
+        }
+        if self.queries.first().is_some_and(|q| q == query) {
+            return false;
+        }
+        self.queries.retain(|q| q != query);
+        self.queries.insert(0, query.to_string());
+        self.queries.truncate(max);
+        true
+    }
+}
+
+/// Path of the history file: `$HOME/.cache/grunner/queries.json`
+#[must_use]
+pub fn query_history_path() -> PathBuf {
+    json_cache::path("queries.json")
+}
+
+/// Load the history from [`query_history_path`]
+#[must_use]
+pub fn load_query_history() -> QueryHistory {
+    QueryHistory::load_from(&query_history_path())
+}
+
+/// Delete the saved history, if any
+pub fn delete_query_history() {
+    let path = query_history_path();
+    match std::fs::remove_file(&path) {
+        Ok(()) => debug!("Deleted query history {}", path.display()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => warn!("Failed to delete query history {}: {e}", path.display()),
+    }
+}
+
+/// Stepping through the history from the search entry
+///
+/// The first step remembers the text typed so far, which stepping back
+/// past the newest query brings back. Editing the recalled query ends the
+/// browsing: the next step starts over from the newest query.
+#[derive(Debug, Default)]
+pub struct HistoryBrowser {
+    /// Index of the query shown, while browsing
+    index: Option<usize>,
+    /// The text typed before browsing started
+    typed: String,
+}
+
+impl HistoryBrowser {
+    /// The query before (`older`) or after the one shown, given the entry
+    /// text `current`, or `None` to leave the entry as it is
+    pub fn step(&mut self, queries: &[String], current: &str, older: bool) -> Option<String> {
+        let index = self
+            .index
+            .filter(|&i| queries.get(i).is_some_and(|q| q == current));
+        let next = match (index, older) {
+            (None, true) => {
+                self.typed = current.to_string();
+                0
+            }
+            (None, false) => {
+                self.index = None;
+                return None;
+            }
+            (Some(i), true) => i + 1,
+            (Some(0), false) => {
+                self.index = None;
+                return Some(std::mem::take(&mut self.typed));
+            }
+            (Some(i), false) => i - 1,
+        };
+        let query = queries.get(next)?;
+        self.index = Some(next);
+        Some(query.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queries(list: &[&str]) -> Vec<String> {
+        list.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_push_collapses_duplicates_and_caps() {
+        let mut history = QueryHistory::default();
+        assert!(history.push(":f notes", 3));
+        assert!(history.push("firefox", 3));
+        assert!(!history.push("firefox ", 3));
+        assert!(!history.push("   ", 3));
+        assert!(history.push(":f notes", 3));
+        assert_eq!(history.queries, queries(&[":f notes", "firefox"]));
+
+        assert!(history.push("a", 3));
+        assert!(history.push("b", 3));
+        assert_eq!(history.queries, queries(&["b", "a", ":f notes"]));
+        assert!(!history.push("c", 0));
+    }
+
+    #[test]
+    fn test_private_queries_are_not_recorded() {
+        assert!(is_private(":pw"));
+        assert!(is_private(":pw bank"));
+        assert!(!is_private(":proj"));
+        assert!(!is_private("pw bank"));
+
+        let mut history = QueryHistory::default();
+        assert!(!history.push(":pw email/work", 10));
+        assert!(history.queries.is_empty());
+    }
+
+    #[test]
+    fn test_browse_older_and_back() {
+        let list = queries(&["c", "b", "a"]);
+        let mut browser = HistoryBrowser::default();
+        // Nothing newer than the typed text
+        assert_eq!(browser.step(&list, "typ", false), None);
+
+        assert_eq!(browser.step(&list, "typ", true).as_deref(), Some("c"));
+        assert_eq!(browser.step(&list, "c", true).as_deref(), Some("b"));
+        assert_eq!(browser.step(&list, "b", true).as_deref(), Some("a"));
+        // The oldest query stays
+        assert_eq!(browser.step(&list, "a", true), None);
+        assert_eq!(browser.step(&list, "a", false).as_deref(), Some("b"));
+        assert_eq!(browser.step(&list, "b", false).as_deref(), Some("c"));
+        assert_eq!(browser.step(&list, "c", false).as_deref(), Some("typ"));
+        assert_eq!(browser.step(&list, "typ", false), None);
+    }
+
+    #[test]
+    fn test_browse_restarts_after_edit() {
+        let list = queries(&["c", "b"]);
+        let mut browser = HistoryBrowser::default();
+        assert_eq!(browser.step(&list, "", true).as_deref(), Some("c"));
+        assert_eq!(browser.step(&list, "c", true).as_deref(), Some("b"));
+        // The recalled query was edited: start over, keeping the edit
+        assert_eq!(browser.step(&list, "b2", true).as_deref(), Some("c"));
+        assert_eq!(browser.step(&list, "c", false).as_deref(), Some("b2"));
+        assert_eq!(browser.step(&[], "", true), None);
+    }
+}
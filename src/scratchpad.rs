@@ -0,0 +1,193 @@
+//! `--scratchpad` mode for tiling window managers
+//!
+//! A scratchpad instance is resident like `--daemon`, but registers its own
+//! application ID and program name so window manager rules can float it, and
+//! running `grunner --scratchpad` again shows or hides it. On the first run a
+//! rule for the detected window manager is printed.
+
+use crate::core::global_state::get_home_dir;
+use std::path::PathBuf;
+
+/// Application ID of a scratchpad instance, its Wayland `app_id`
+///
+/// Application IDs need a dot, so this is not just the program name.
+pub const SCRATCHPAD_APP_ID: &str = "org.nihmar.grunner.scratchpad";
+
+/// Program name of a scratchpad instance, its X11 `WM_CLASS` instance
+pub const SCRATCHPAD_PRGNAME: &str = "grunner-scratchpad";
+
+/// Window manager, as far as scratchpad rules are concerned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compositor {
+    Hyprland,
+    Sway,
+    I3,
+    Other,
+}
+
+impl Compositor {
+    /// Detect the compositor from environment variables read by `var`
+    ///
+    /// The sockets each compositor exports take precedence over
+    /// `XDG_CURRENT_DESKTOP`, which nested sessions may inherit.
+    #[must_use]
+    pub fn detect(var: impl Fn(&str) -> Option<String>) -> Self {
+        let set = |name: &str| var(name).is_some_and(|value| !value.is_empty());
+        if set("HYPRLAND_INSTANCE_SIGNATURE") {
+            return Self::Hyprland;
+        }
+        if set("SWAYSOCK") {
+            return Self::Sway;
+        }
+        if set("I3SOCK") {
+            return Self::I3;
+        }
+        let desktop = var("XDG_CURRENT_DESKTOP").unwrap_or_default();
+        desktop
+            .split(':')
+            .find_map(|name| match name.to_ascii_lowercase().as_str() {
+                "hyprland" => Some(Self::Hyprland),
+                "sway" => Some(Self::Sway),
+                "i3" => Some(Self::I3),
+                _ => None,
+            })
+            .unwrap_or(Self::Other)
+    }
+
+    /// Detect the compositor of the current session
+    #[must_use]
+    pub fn from_env() -> Self {
+        Self::detect(|name| std::env::var(name).ok())
+    }
+}
+
+/// Example configuration that floats the scratchpad on `compositor` and
+/// binds Super+Space to it
+///
+/// For an unknown compositor the examples of all supported ones are given.
+#[must_use]
+pub fn window_rule(compositor: Compositor) -> String {
+    let app_id = SCRATCHPAD_APP_ID.replace('.', "\\.");
+    match compositor {
+        Compositor::Hyprland => format!(
+            "# ~/.config/hypr/hyprland.conf\n\
+             windowrulev2 = float, class:^({app_id})$\n\
+             windowrulev2 = center, class:^({app_id})$\n\
+             exec-once = grunner --scratchpad\n\
+             bind = SUPER, SPACE, exec, grunner --scratchpad\n"
+        ),
+        Compositor::Sway => format!(
+            "# ~/.config/sway/config\n\
+             for_window [app_id=\"^{app_id}$\"] floating enable\n\
+             exec grunner --scratchpad\n\
+             bindsym $mod+space exec grunner --scratchpad\n"
+        ),
+        Compositor::I3 => format!(
+            "# ~/.config/i3/config\n\
+             for_window [instance=\"^{SCRATCHPAD_PRGNAME}$\"] floating enable\n\
+             exec --no-startup-id grunner --scratchpad\n\
+             bindsym $mod+space exec --no-startup-id grunner --scratchpad\n"
+        ),
+        Compositor::Other => [Compositor::Hyprland, Compositor::Sway, Compositor::I3]
+            .map(window_rule)
+            .join("\n"),
+    }
+}
+
+/// Marker written once the rule has been printed:
+/// `$HOME/.cache/grunner/scratchpad-hint`
+#[must_use]
+pub fn hint_marker_path() -> PathBuf {
+    PathBuf::from(get_home_dir())
+        .join(".cache")
+        .join("grunner")
+        .join("scratchpad-hint")
+}
+
+/// Print the window rule for the current compositor, unless it was
+/// printed by an earlier run
+pub fn print_rule_once() {
+    let marker = hint_marker_path();
+    if marker.exists() {
+        return;
+    }
+    println!("Float grunner as a scratchpad with a window rule like this:\n");
+    print!("{}", window_rule(Compositor::from_env()));
+    if let Some(parent) = marker.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Err(e) = std::fs::write(&marker, "") {
+        log::warn!("Failed to write {}: {e}", marker.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |name| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| (*value).to_string())
+        }
+    }
+
+    #[test]
+    fn test_detect_compositor() {
+        assert_eq!(
+            Compositor::detect(env(&[("HYPRLAND_INSTANCE_SIGNATURE", "abc")])),
+            Compositor::Hyprland
+        );
+        assert_eq!(
+            Compositor::detect(env(&[("SWAYSOCK", "/run/sway.sock")])),
+            Compositor::Sway
+        );
+        assert_eq!(
+            Compositor::detect(env(&[("I3SOCK", "/run/i3.sock")])),
+            Compositor::I3
+        );
+        assert_eq!(
+            Compositor::detect(env(&[("XDG_CURRENT_DESKTOP", "Hyprland")])),
+            Compositor::Hyprland
+        );
+        assert_eq!(
+            Compositor::detect(env(&[("XDG_CURRENT_DESKTOP", "ubuntu:GNOME")])),
+            Compositor::Other
+        );
+        // The socket wins over an inherited desktop name
+        assert_eq!(
+            Compositor::detect(env(&[
+                ("XDG_CURRENT_DESKTOP", "GNOME"),
+                ("SWAYSOCK", "/run/sway.sock")
+            ])),
+            Compositor::Sway
+        );
+        assert_eq!(
+            Compositor::detect(env(&[("SWAYSOCK", "")])),
+            Compositor::Other
+        );
+    }
+
+    #[test]
+    fn test_window_rule_per_compositor() {
+        let hyprland = window_rule(Compositor::Hyprland);
+        assert!(
+            hyprland
+                .contains("windowrulev2 = float, class:^(org\\.nihmar\\.grunner\\.scratchpad)$")
+        );
+        assert!(hyprland.contains("bind = SUPER, SPACE, exec, grunner --scratchpad"));
+
+        let sway = window_rule(Compositor::Sway);
+        assert!(sway.contains(
+            "for_window [app_id=\"^org\\.nihmar\\.grunner\\.scratchpad$\"] floating enable"
+        ));
+
+        let i3 = window_rule(Compositor::I3);
+        assert!(i3.contains("for_window [instance=\"^grunner-scratchpad$\"] floating enable"));
+        assert!(i3.contains("exec --no-startup-id grunner --scratchpad"));
+
+        let other = window_rule(Compositor::Other);
+        assert!(other.contains(&hyprland) && other.contains(&sway) && other.contains(&i3));
+    }
+}
@@ -3,7 +3,7 @@
 //! Kept separate from the UI code so serialisation logic can be
 //! read and tested independently of GTK.
 
-use crate::core::config::{self, Config, config_to_toml};
+use crate::core::config::{self, Config, config_to_own_toml};
 use log::debug;
 use std::fs;
 
@@ -13,11 +13,15 @@ use std::fs;
 /// * `config` - The configuration to save
 ///
 /// # Returns
-/// `Result<(), std::io::Error>` indicating success or failure
+/// `Result<(), std::io::Error>` indicating success or failure; a file set by
+/// `GRUNNER_CONFIG` is never written and is an error
+///
+/// Values that come from `include` files are not copied into the file, see
+/// [`config_to_own_toml`].
 pub(crate) fn save_config(config: &Config) -> Result<(), std::io::Error> {
-    let toml_string = config_to_toml(config);
-
+    config::ensure_config_writable()?;
     let path = config::config_path();
+    let toml_string = config_to_own_toml(config, &path);
     debug!("Saving configuration to {}", path.display());
 
     // Ensure directory exists
@@ -32,7 +36,7 @@ pub(crate) fn save_config(config: &Config) -> Result<(), std::io::Error> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::core::config::Config;
+    use crate::core::config::{Config, config_to_toml};
 
     #[test]
     fn test_config_to_toml_produces_valid_output() {
@@ -50,6 +54,7 @@ mod tests {
         assert!(toml_str.contains("[window]"));
         assert!(toml_str.contains("[search]"));
         assert!(toml_str.contains("[theme]"));
+        assert!(toml_str.contains("[privacy]"));
         // Empty commands may be omitted or represented as empty array
         let parsed: toml::Value = toml::from_str(&toml_str).unwrap();
         assert!(parsed.get("window").is_some());
@@ -59,9 +64,11 @@ mod tests {
 
     #[test]
     fn test_config_to_toml_round_trip_via_toml_crate() {
-        let mut config = Config::default();
-        config.window_width = 1280;
-        config.window_height = 720;
+        let config = Config {
+            window_width: 1280,
+            window_height: 720,
+            ..Default::default()
+        };
 
         let toml_str = config_to_toml(&config);
         let value: toml::Value = toml::from_str(&toml_str).unwrap();
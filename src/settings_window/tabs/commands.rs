@@ -58,11 +58,13 @@ pub fn build_tab(notebook: &gtk4::Notebook, config_rc: &Rc<RefCell<Config>>) {
     let list_box_clone = list_box.clone();
     let config_rc_clone = Rc::clone(config_rc);
     add_button.connect_clicked(move |_| {
+        let name = unique_command_name(&config_rc_clone.borrow().commands);
         let new_cmd = CommandConfig {
-            name: "New Command".to_string(),
+            name,
             command: "echo 'Hello World'".to_string(),
             working_dir: None,
             keep_open: true,
+            dangerous: false,
         };
         {
             let mut cfg = config_rc_clone.borrow_mut();
@@ -86,6 +88,21 @@ pub fn build_tab(notebook: &gtk4::Notebook, config_rc: &Rc<RefCell<Config>>) {
     notebook.append_page(&scroll, Some(&gtk4::Label::new(Some("Commands"))));
 }
 
+/// "New Command", or "New Command N" if that name is already taken
+///
+/// Commands with the same name are dropped when the config is loaded, so
+/// every added command gets a name of its own.
+fn unique_command_name(commands: &[CommandConfig]) -> String {
+    let taken = |name: &str| commands.iter().any(|c| c.name == name);
+    let mut name = "New Command".to_string();
+    let mut n = 2;
+    while taken(&name) {
+        name = format!("New Command {n}");
+        n += 1;
+    }
+    name
+}
+
 /// Create a row for a single command with edit and delete buttons
 fn create_command_row(
     index: usize,
@@ -215,3 +232,25 @@ fn create_command_row(
 
     row_box
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(name: &str) -> CommandConfig {
+        CommandConfig {
+            name: name.to_string(),
+            command: String::new(),
+            working_dir: None,
+            keep_open: true,
+            dangerous: false,
+        }
+    }
+
+    #[test]
+    fn test_unique_command_name() {
+        assert_eq!(unique_command_name(&[]), "New Command");
+        let commands = [command("New Command"), command("New Command 2")];
+        assert_eq!(unique_command_name(&commands), "New Command 3");
+    }
+}
@@ -1,15 +1,19 @@
-//! General tab — window dimensions.
+//! General tab — window dimensions and privacy.
 
 use super::make_tab_page;
 use crate::core::config::Config;
 use gtk4::prelude::*;
 use libadwaita::prelude::*;
-use libadwaita::{PreferencesGroup, SpinRow};
+use libadwaita::{PreferencesGroup, SpinRow, SwitchRow};
 use std::cell::RefCell;
 use std::rc::Rc;
 
 /// Append the "General" tab to `notebook`.
-#[allow(clippy::cast_possible_truncation)]
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
 pub fn build_tab(notebook: &gtk4::Notebook, config_rc: &Rc<RefCell<Config>>) {
     let (scroll, inner) = make_tab_page();
 
@@ -52,5 +56,56 @@ pub fn build_tab(notebook: &gtk4::Notebook, config_rc: &Rc<RefCell<Config>>) {
     window_group.add(&height_row);
     inner.append(&window_group);
 
+    // ── Privacy ──────────────────────────────────────────────────────────────
+    let privacy_group = PreferencesGroup::builder()
+        .title("Privacy")
+        .description("Usage data is stored locally and never transmitted")
+        .build();
+
+    let usage_switch = SwitchRow::builder()
+        .title("Record Usage")
+        .subtitle(
+            "Count colon mode usage for :stats, opened results for ranking and recent :e emoji",
+        )
+        .build();
+    usage_switch.set_active(config_rc.borrow().usage_stats);
+    usage_switch.connect_notify_local(Some("active"), {
+        let config_rc = Rc::clone(config_rc);
+        move |row, _| {
+            config_rc.borrow_mut().usage_stats = row.is_active();
+        }
+    });
+    privacy_group.add(&usage_switch);
+
+    let clipboard_switch = SwitchRow::builder()
+        .title("Clipboard History")
+        .subtitle("Keep texts copied while the launcher is open for :c")
+        .build();
+    clipboard_switch.set_active(config_rc.borrow().clipboard_history);
+    clipboard_switch.connect_notify_local(Some("active"), {
+        let config_rc = Rc::clone(config_rc);
+        move |row, _| {
+            config_rc.borrow_mut().clipboard_history = row.is_active();
+        }
+    });
+    privacy_group.add(&clipboard_switch);
+
+    let query_history_row = SpinRow::builder()
+        .title("Query History")
+        .subtitle("Queries kept for Alt+Up and Alt+Down, except :pw ones (0 keeps none)")
+        .build();
+    query_history_row.set_range(0.0, 500.0);
+    query_history_row.adjustment().set_step_increment(10.0);
+    query_history_row.adjustment().set_page_increment(50.0);
+    query_history_row.set_value(config_rc.borrow().query_history as f64);
+    query_history_row.connect_notify_local(Some("value"), {
+        let config_rc = Rc::clone(config_rc);
+        move |row, _| {
+            config_rc.borrow_mut().query_history = row.value().round() as usize;
+        }
+    });
+    privacy_group.add(&query_history_row);
+    inner.append(&privacy_group);
+
     notebook.append_page(&scroll, Some(&gtk4::Label::new(Some("General"))));
 }
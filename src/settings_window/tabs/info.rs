@@ -2,8 +2,8 @@
 //! and the "reset to defaults" action.
 
 use super::make_tab_page;
-use crate::actions::open_uri;
-use crate::core::config::{self, Config};
+use crate::actions::open_config_file;
+use crate::core::config::Config;
 use crate::settings_window::save::save_config;
 use gtk4::pango;
 use gtk4::prelude::*;
@@ -120,17 +120,17 @@ Grunner integrates with GNOME Shell search providers (Files, Calendar, Contacts,
     // ── Configuration File ───────────────────────────────────────────────────
     let config_group = PreferencesGroup::builder()
         .title("Configuration File")
-        .description("Open the configuration file directly in your default editor")
+        .description("Open the configuration file directly in your editor")
         .build();
 
     let config_button = gtk4::Button::builder().label("Open Config File").build();
     config_button.connect_clicked({
         let window = window.downgrade();
         let overlay = overlay.downgrade();
+        let config_rc = Rc::clone(config_rc);
         move |_| {
-            let config_path = config::config_path();
-            let uri = format!("file://{}", config_path.to_string_lossy());
-            if let Err(e) = open_uri(&uri) {
+            let editor = config_rc.borrow().settings_editor.clone();
+            if let Err(e) = open_config_file(editor.as_deref()) {
                 error!("Failed to open config file: {e}");
                 if let Some(overlay) = overlay.upgrade() {
                     let toast = Toast::builder()
@@ -140,7 +140,7 @@ Grunner integrates with GNOME Shell search providers (Files, Calendar, Contacts,
                     overlay.add_toast(toast);
                 }
             } else {
-                info!("Opened config file: {uri}");
+                info!("Opened config file");
                 // Close the settings window after opening config
                 if let Some(window) = window.upgrade() {
                     glib::timeout_add_local_once(
@@ -174,13 +174,39 @@ Grunner integrates with GNOME Shell search providers (Files, Calendar, Contacts,
                 let mut cfg = config_rc.borrow_mut();
                 cfg.window_width = default_config.window_width;
                 cfg.window_height = default_config.window_height;
+                cfg.auto_height = default_config.auto_height;
                 cfg.max_results = default_config.max_results;
                 cfg.command_debounce_ms = default_config.command_debounce_ms;
+                cfg.auto_launch_threshold = default_config.auto_launch_threshold;
+                cfg.fuzzy_case = default_config.fuzzy_case;
+                cfg.fuzzy_element_limit = default_config.fuzzy_element_limit;
+                cfg.terminal_prefix = default_config.terminal_prefix;
+                cfg.prefer_focus_running = default_config.prefer_focus_running;
+                cfg.close_on_copy = default_config.close_on_copy;
+                cfg.settings_editor = default_config.settings_editor;
                 cfg.app_dirs.clone_from(&default_config.app_dirs);
                 cfg.search_provider_blacklist
                     .clone_from(&default_config.search_provider_blacklist);
+                cfg.provider_settings = default_config.provider_settings;
+                cfg.app_blacklist.clone_from(&default_config.app_blacklist);
                 cfg.obsidian = default_config.obsidian;
                 cfg.workspace_bar_enabled = default_config.workspace_bar_enabled;
+                cfg.usage_stats = default_config.usage_stats;
+                cfg.clipboard_history = default_config.clipboard_history;
+                cfg.query_history = default_config.query_history;
+                cfg.file_results_layout = default_config.file_results_layout;
+                cfg.animations = default_config.animations;
+                cfg.show_kind_badges = default_config.show_kind_badges;
+                cfg.show_app_origin = default_config.show_app_origin;
+                cfg.escape_clears_first = default_config.escape_clears_first;
+                cfg.corner_radius = default_config.corner_radius;
+                cfg.opacity = default_config.opacity;
+                cfg.show_footer = default_config.show_footer;
+                cfg.angle_unit = default_config.angle_unit;
+                cfg.project_roots.clone_from(&default_config.project_roots);
+                cfg.project_open_with = default_config.project_open_with;
+                cfg.ssh_known_hosts = default_config.ssh_known_hosts;
+                cfg.units_user = default_config.units_user;
             }
 
             if let Some(window) = window.upgrade()
@@ -189,7 +215,7 @@ Grunner integrates with GNOME Shell search providers (Files, Calendar, Contacts,
                 if let Err(e) = save_config(&config_rc.borrow()) {
                     error!("Failed to save reset configuration: {e}");
                     let toast = Toast::builder()
-                        .title("Failed to save reset settings")
+                        .title(format!("Failed to save reset settings: {e}"))
                         .timeout(3)
                         .build();
                     overlay.add_toast(toast);
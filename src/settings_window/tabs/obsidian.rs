@@ -1,5 +1,6 @@
 //! Obsidian tab — vault path (with folder-picker), daily notes folder,
-//! new notes folder, and quick-note file.
+//! new notes folder, quick-note file, and whether a missing vault outside
+//! the home directory may be created.
 
 use super::make_tab_page;
 use crate::core::config::Config;
@@ -7,7 +8,7 @@ use crate::utils::{contract_home, expand_home};
 use glib::clone;
 use gtk4::prelude::*;
 use libadwaita::prelude::*;
-use libadwaita::{EntryRow, PreferencesGroup};
+use libadwaita::{EntryRow, PreferencesGroup, SwitchRow};
 use std::cell::RefCell;
 use std::rc::Rc;
 
@@ -56,11 +57,9 @@ pub fn build_tab(
         let vault_row = vault_row.clone();
         let parent = parent.clone();
         move |_| {
-            let dialog = gtk4::FileChooserNative::builder()
+            let dialog = gtk4::FileDialog::builder()
                 .title("Select Obsidian Vault Folder")
                 .modal(true)
-                .action(gtk4::FileChooserAction::SelectFolder)
-                .transient_for(&parent)
                 .build();
 
             // Pre-select the current vault path if it exists on disk
@@ -77,18 +76,22 @@ pub fn build_tab(
                 }
             });
             if let Some(folder) = initial_folder {
-                let _ = dialog.set_current_folder(Some(&gtk4::gio::File::for_path(folder)));
+                dialog.set_initial_folder(Some(&gtk4::gio::File::for_path(folder)));
             }
 
-            dialog.connect_response(clone!(
-                #[strong]
-                config_rc,
-                #[strong]
-                vault_row,
-                move |dialog, response| {
-                    if response == gtk4::ResponseType::Accept
-                        && let Some(file) = dialog.file()
-                    {
+            dialog.select_folder(
+                Some(&parent),
+                gtk4::gio::Cancellable::NONE,
+                clone!(
+                    #[strong]
+                    config_rc,
+                    #[strong]
+                    vault_row,
+                    move |result| {
+                        // Dismissing the dialog also reports an error; ignore it
+                        let Ok(file) = result else {
+                            return;
+                        };
                         let folder_path = file.path().unwrap_or_default();
                         // Store as tilde path for portability
                         let display_path = contract_home(&folder_path);
@@ -97,11 +100,8 @@ pub fn build_tab(
                             obs.vault = display_path;
                         }
                     }
-                    dialog.destroy();
-                }
-            ));
-
-            dialog.show();
+                ),
+            );
         }
     });
 
@@ -160,6 +160,29 @@ pub fn build_tab(
     });
     obsidian_group.add(&quick_row);
 
+    // ── Vault Outside Home ───────────────────────────────────────────────────
+    let outside_switch = SwitchRow::builder()
+        .title("Allow Vault Outside Home")
+        .subtitle("Offer to create a missing vault even if it is outside your home directory")
+        .build();
+    outside_switch.set_active(
+        config_rc
+            .borrow()
+            .obsidian
+            .as_ref()
+            .unwrap()
+            .allow_vault_outside_home,
+    );
+    outside_switch.connect_notify_local(Some("active"), {
+        let config_rc = Rc::clone(config_rc);
+        move |row, _| {
+            if let Some(obs) = config_rc.borrow_mut().obsidian.as_mut() {
+                obs.allow_vault_outside_home = row.is_active();
+            }
+        }
+    });
+    obsidian_group.add(&outside_switch);
+
     inner.append(&obsidian_group);
     notebook.append_page(&scroll, Some(&gtk4::Label::new(Some("Obsidian"))));
 }
@@ -2,13 +2,24 @@
 //! and the search-provider blacklist.
 
 use super::make_tab_page;
-use crate::core::config::Config;
+use crate::core::config::{Config, FileResultsLayout, FuzzyCase};
 use gtk4::prelude::*;
 use libadwaita::prelude::*;
-use libadwaita::{PreferencesGroup, PreferencesRow, SpinRow, SwitchRow};
+use libadwaita::{ComboRow, PreferencesGroup, PreferencesRow, SpinRow, SwitchRow};
 use std::cell::RefCell;
 use std::rc::Rc;
 
+const FILE_LAYOUTS: &[(FileResultsLayout, &str)] = &[
+    (FileResultsLayout::TwoLine, "Two lines"),
+    (FileResultsLayout::SingleLine, "Single line"),
+];
+
+const FUZZY_CASES: &[(FuzzyCase, &str)] = &[
+    (FuzzyCase::Smart, "Smart"),
+    (FuzzyCase::Ignore, "Ignore case"),
+    (FuzzyCase::Respect, "Match case"),
+];
+
 /// Append the "Search" tab to `notebook`.
 #[allow(
     clippy::too_many_lines,
@@ -56,6 +67,97 @@ pub fn build_tab(notebook: &gtk4::Notebook, config_rc: &Rc<RefCell<Config>>) {
         }
     });
     behavior_group.add(&debounce_row);
+
+    let auto_launch_row = SpinRow::builder()
+        .title("Auto-launch Threshold")
+        .subtitle(
+            "Launch the top app once typing stops if its match score reaches this value (0 = off)",
+        )
+        .build();
+    auto_launch_row.set_range(0.0, 100.0);
+    auto_launch_row.adjustment().set_step_increment(1.0);
+    auto_launch_row.adjustment().set_page_increment(5.0);
+    auto_launch_row.set_value(f64::from(config_rc.borrow().auto_launch_threshold));
+    auto_launch_row.connect_notify_local(Some("value"), {
+        let config_rc = Rc::clone(config_rc);
+        move |row, _| {
+            config_rc.borrow_mut().auto_launch_threshold = row.value().round() as u8;
+        }
+    });
+    behavior_group.add(&auto_launch_row);
+
+    let layout_names: Vec<&str> = FILE_LAYOUTS.iter().map(|(_, name)| *name).collect();
+    let layout_row = ComboRow::builder()
+        .title("File Results Layout")
+        .subtitle("Single line fits about twice as many :f, :fg, :ob and :obg results")
+        .model(&gtk4::StringList::new(&layout_names))
+        .build();
+    let current_layout = config_rc.borrow().file_results_layout;
+    let layout_index = FILE_LAYOUTS
+        .iter()
+        .position(|(layout, _)| *layout == current_layout)
+        .unwrap_or(0);
+    layout_row.set_selected(u32::try_from(layout_index).unwrap_or(0));
+    layout_row.connect_selected_notify({
+        let config_rc = Rc::clone(config_rc);
+        move |row| {
+            if let Some((layout, _)) = FILE_LAYOUTS.get(row.selected() as usize) {
+                config_rc.borrow_mut().file_results_layout = *layout;
+            }
+        }
+    });
+    behavior_group.add(&layout_row);
+
+    let case_names: Vec<&str> = FUZZY_CASES.iter().map(|(_, name)| *name).collect();
+    let case_row = ComboRow::builder()
+        .title("Fuzzy Matching Case")
+        .subtitle("Smart ignores case unless the query contains an uppercase letter")
+        .model(&gtk4::StringList::new(&case_names))
+        .build();
+    let current_case = config_rc.borrow().fuzzy_case;
+    let case_index = FUZZY_CASES
+        .iter()
+        .position(|(case, _)| *case == current_case)
+        .unwrap_or(0);
+    case_row.set_selected(u32::try_from(case_index).unwrap_or(0));
+    case_row.connect_selected_notify({
+        let config_rc = Rc::clone(config_rc);
+        move |row| {
+            if let Some((case, _)) = FUZZY_CASES.get(row.selected() as usize) {
+                config_rc.borrow_mut().fuzzy_case = *case;
+            }
+        }
+    });
+    behavior_group.add(&case_row);
+
+    let element_limit_row = SpinRow::builder()
+        .title("Fuzzy Match Size Limit")
+        .subtitle("Query × text length above which a faster, greedy match is used (0 = no limit)")
+        .build();
+    element_limit_row.set_range(0.0, 100_000.0);
+    element_limit_row.adjustment().set_step_increment(100.0);
+    element_limit_row.adjustment().set_page_increment(1000.0);
+    element_limit_row.set_value(config_rc.borrow().fuzzy_element_limit as f64);
+    element_limit_row.connect_notify_local(Some("value"), {
+        let config_rc = Rc::clone(config_rc);
+        move |row, _| {
+            config_rc.borrow_mut().fuzzy_element_limit = row.value().round() as usize;
+        }
+    });
+    behavior_group.add(&element_limit_row);
+
+    let terminal_prefix_row = SwitchRow::builder()
+        .title("Run in Terminal with !")
+        .subtitle("Run a query such as !htop as a shell command in the terminal")
+        .build();
+    terminal_prefix_row.set_active(config_rc.borrow().terminal_prefix);
+    terminal_prefix_row.connect_notify_local(Some("active"), {
+        let config_rc = Rc::clone(config_rc);
+        move |row, _| {
+            config_rc.borrow_mut().terminal_prefix = row.is_active();
+        }
+    });
+    behavior_group.add(&terminal_prefix_row);
     inner.append(&behavior_group);
 
     // ── Application Directories ──────────────────────────────────────────────
@@ -99,34 +201,27 @@ pub fn build_tab(notebook: &gtk4::Notebook, config_rc: &Rc<RefCell<Config>>) {
         .description("List of GNOME Shell search providers to exclude (one per line)")
         .build();
 
-    let blacklist_text = gtk4::TextView::builder()
-        .wrap_mode(gtk4::WrapMode::WordChar)
-        .build();
-    let blacklist_buffer = blacklist_text.buffer();
-    blacklist_buffer.set_text(&config_rc.borrow().search_provider_blacklist.join("\n"));
-    blacklist_buffer.connect_changed({
+    let blacklist_row = line_list_row(&config_rc.borrow().search_provider_blacklist, {
         let config_rc = Rc::clone(config_rc);
-        move |buffer| {
-            let text = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false);
-            config_rc.borrow_mut().search_provider_blacklist = text
-                .split('\n')
-                .map(|s| s.trim().to_string())
-                .filter(|s| !s.is_empty())
-                .collect();
-        }
+        move |lines| config_rc.borrow_mut().search_provider_blacklist = lines
     });
+    blacklist_group.add(&blacklist_row);
+    inner.append(&blacklist_group);
 
-    let blacklist_scrolled = gtk4::ScrolledWindow::builder()
-        .hexpand(true)
-        .min_content_height(60)
-        .max_content_height(120)
+    // ── Hidden Apps ─────────────────────────────────────────────────────────────
+    let hidden_group = PreferencesGroup::builder()
+        .title("Hidden Apps")
+        .description(
+            "Desktop IDs or globs such as wine-* of apps left out of results (one per line)",
+        )
         .build();
-    blacklist_scrolled.set_child(Some(&blacklist_text));
 
-    let blacklist_row = PreferencesRow::new();
-    blacklist_row.set_child(Some(&blacklist_scrolled));
-    blacklist_group.add(&blacklist_row);
-    inner.append(&blacklist_group);
+    let hidden_row = line_list_row(&config_rc.borrow().app_blacklist, {
+        let config_rc = Rc::clone(config_rc);
+        move |lines| config_rc.borrow_mut().app_blacklist = lines
+    });
+    hidden_group.add(&hidden_row);
+    inner.append(&hidden_group);
 
     // ── Workspace Bar ───────────────────────────────────────────────────────────
     let workspace_group = PreferencesGroup::builder()
@@ -146,7 +241,64 @@ pub fn build_tab(notebook: &gtk4::Notebook, config_rc: &Rc<RefCell<Config>>) {
         }
     });
     workspace_group.add(&workspace_switch);
+
+    let focus_running_switch = SwitchRow::builder()
+        .title("Focus Running Apps")
+        .subtitle("Mark apps that already have a window and focus it instead of starting another instance")
+        .build();
+    focus_running_switch.set_active(config_rc.borrow().prefer_focus_running);
+    focus_running_switch.connect_notify_local(Some("active"), {
+        let config_rc = Rc::clone(config_rc);
+        move |row, _| {
+            config_rc.borrow_mut().prefer_focus_running = row.is_active();
+        }
+    });
+    workspace_group.add(&focus_running_switch);
+
+    let close_on_copy_switch = SwitchRow::builder()
+        .title("Close After Copying")
+        .subtitle("Hide the launcher after copying a calculator result or a clipboard entry")
+        .build();
+    close_on_copy_switch.set_active(config_rc.borrow().close_on_copy);
+    close_on_copy_switch.connect_notify_local(Some("active"), {
+        let config_rc = Rc::clone(config_rc);
+        move |row, _| {
+            config_rc.borrow_mut().close_on_copy = row.is_active();
+        }
+    });
+    workspace_group.add(&close_on_copy_switch);
     inner.append(&workspace_group);
 
     notebook.append_page(&scroll, Some(&gtk4::Label::new(Some("Search"))));
 }
+
+/// Text area editing `lines`, one entry per line
+///
+/// `on_change` receives the non-empty, trimmed lines after each edit.
+fn line_list_row(lines: &[String], on_change: impl Fn(Vec<String>) + 'static) -> PreferencesRow {
+    let text_view = gtk4::TextView::builder()
+        .wrap_mode(gtk4::WrapMode::WordChar)
+        .build();
+    let buffer = text_view.buffer();
+    buffer.set_text(&lines.join("\n"));
+    buffer.connect_changed(move |buffer| {
+        let text = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false);
+        on_change(
+            text.split('\n')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+        );
+    });
+
+    let scrolled = gtk4::ScrolledWindow::builder()
+        .hexpand(true)
+        .min_content_height(60)
+        .max_content_height(120)
+        .build();
+    scrolled.set_child(Some(&text_view));
+
+    let row = PreferencesRow::new();
+    row.set_child(Some(&scrolled));
+    row
+}
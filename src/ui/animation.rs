@@ -0,0 +1,318 @@
+//! Light animations of the result list
+//!
+//! Rows bound right after the store was spliced fade in, and Page Up/Down
+//! glide the list to the new selection instead of jumping. Both follow the
+//! `[ui] animations` setting and GTK's `gtk-enable-animations`. The state
+//! lives here, in the view layer; `AppListModel` only reports the splices.
+//!
+//! The scroll is a libadwaita animation, or a tick callback with the same
+//! easing when built without the `adwaita` feature.
+
+use crate::model::list_model::AppListModel;
+use gtk4::prelude::*;
+use gtk4::{ListView, Widget};
+#[cfg(feature = "adwaita")]
+use libadwaita::prelude::*;
+#[cfg(feature = "adwaita")]
+use libadwaita::{Easing, PropertyAnimationTarget, TimedAnimation};
+use std::cell::{Cell, RefCell};
+use std::ops::Range;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// CSS class of a fading-in row, removed once its animation has run
+pub const FADE_IN_CLASS: &str = "row-fade-in";
+
+/// Length of the `row-fade-in` keyframes in `style.css`
+const FADE_IN_MS: u64 = 160;
+
+/// How long after a splice a bound row still counts as new
+///
+/// Rows scrolled into view later are old results and do not fade.
+const FRESH_WINDOW: Duration = Duration::from_millis(250);
+
+/// Duration of the Page Up/Down scroll
+const SCROLL_MS: u32 = 180;
+
+/// Positions added by the last splice of the result store
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FreshRows {
+    range: Range<u32>,
+    spliced_at: Option<Instant>,
+}
+
+impl FreshRows {
+    /// Record that `added` rows were inserted at `pos` at time `now`
+    pub fn spliced(&mut self, pos: u32, added: u32, now: Instant) {
+        self.range = pos..pos.saturating_add(added);
+        self.spliced_at = Some(now);
+    }
+
+    /// Whether the row bound at `pos` at time `now` was just added
+    #[must_use]
+    pub fn is_fresh(&self, pos: u32, now: Instant) -> bool {
+        self.range.contains(&pos)
+            && self
+                .spliced_at
+                .is_some_and(|at| now.saturating_duration_since(at) <= FRESH_WINDOW)
+    }
+}
+
+/// Whether to animate, given the `[ui] animations` setting and GTK's
+/// `gtk-enable-animations`
+#[must_use]
+pub fn should_animate(configured: bool, system: bool) -> bool {
+    configured && system
+}
+
+/// Animation state of the result list, shared by the factory and key handler
+#[derive(Clone)]
+pub struct ListAnimations {
+    /// The `[ui] animations` setting
+    enabled: Rc<Cell<bool>>,
+    fresh: Rc<RefCell<FreshRows>>,
+    /// Running Page Up/Down scroll, finished early by the next one
+    scroll: Rc<RefCell<Option<Glide>>>,
+}
+
+/// A running Page Up/Down scroll
+#[cfg(feature = "adwaita")]
+type Glide = TimedAnimation;
+#[cfg(not(feature = "adwaita"))]
+type Glide = gtk4::TickCallbackId;
+
+impl ListAnimations {
+    #[must_use]
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled: Rc::new(Cell::new(enabled)),
+            fresh: Rc::new(RefCell::new(FreshRows::default())),
+            scroll: Rc::new(RefCell::new(None)),
+        }
+    }
+
+    /// Apply a changed `[ui] animations` setting
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.set(enabled);
+    }
+
+    /// Whether animations are on, in the settings and in GTK
+    #[must_use]
+    pub fn enabled(&self) -> bool {
+        let system =
+            gtk4::Settings::default().is_none_or(|settings| settings.is_gtk_enable_animations());
+        should_animate(self.enabled.get(), system)
+    }
+
+    /// Track the rows each splice of the result store adds
+    pub fn watch(&self, model: &AppListModel) {
+        let fresh = self.fresh.clone();
+        model
+            .results
+            .connect_items_changed(move |_, pos, _removed, added| {
+                fresh.borrow_mut().spliced(pos, added, Instant::now());
+            });
+    }
+
+    /// Fade in `row`, bound at `pos`, if it was just added to the list
+    pub fn bind_row(&self, row: &impl IsA<Widget>, pos: u32) {
+        if !self.fresh.borrow().is_fresh(pos, Instant::now()) || !self.enabled() {
+            return;
+        }
+        // Re-adding the class would not restart the keyframes
+        if row.has_css_class(FADE_IN_CLASS) {
+            return;
+        }
+        row.add_css_class(FADE_IN_CLASS);
+        let row = row.as_ref().downgrade();
+        glib::timeout_add_local_once(Duration::from_millis(FADE_IN_MS), move || {
+            if let Some(row) = row.upgrade() {
+                row.remove_css_class(FADE_IN_CLASS);
+            }
+        });
+    }
+
+    /// Drop the fade of a row that is being reused for another item
+    pub fn unbind_row(row: &impl IsA<Widget>) {
+        row.remove_css_class(FADE_IN_CLASS);
+    }
+
+    /// Select `pos` and scroll it into view, gliding there when enabled
+    pub fn scroll_to(&self, model: &AppListModel, list_view: &ListView, pos: u32) {
+        model.results.set_selected(pos);
+        if let Some(running) = self.scroll.borrow_mut().take() {
+            finish(running);
+        }
+
+        let target = list_view
+            .vadjustment()
+            .filter(|_| self.enabled())
+            .and_then(|adj| Some((scroll_target(&adj, pos, model.results.n_items())?, adj)));
+        let Some((target, adj)) = target else {
+            let _ = list_view.activate_action("list.scroll-to-item", Some(&pos.to_variant()));
+            return;
+        };
+
+        *self.scroll.borrow_mut() = Some(glide(list_view, &adj, target, pos));
+    }
+}
+
+/// Scroll `list_view` to `target` in [`SCROLL_MS`], then to row `pos`
+///
+/// Row heights are estimated, so the list settles on the exact row at the
+/// end.
+#[cfg(feature = "adwaita")]
+fn glide(list_view: &ListView, adj: &gtk4::Adjustment, target: f64, pos: u32) -> Glide {
+    let animation = TimedAnimation::builder()
+        .widget(list_view)
+        .value_from(adj.value())
+        .value_to(target)
+        .duration(SCROLL_MS)
+        .easing(Easing::EaseOutCubic)
+        .target(&PropertyAnimationTarget::new(adj, "value"))
+        .build();
+    let list = list_view.downgrade();
+    animation.connect_done(move |_| {
+        if let Some(list) = list.upgrade() {
+            let _ = list.activate_action("list.scroll-to-item", Some(&pos.to_variant()));
+        }
+    });
+    animation.play();
+    animation
+}
+
+#[cfg(not(feature = "adwaita"))]
+fn glide(list_view: &ListView, adj: &gtk4::Adjustment, target: f64, pos: u32) -> Glide {
+    let adj = adj.clone();
+    let from = adj.value();
+    let start = Instant::now();
+    let duration = Duration::from_millis(SCROLL_MS.into());
+    list_view.add_tick_callback(move |list, _| {
+        let progress = start.elapsed().as_secs_f64() / duration.as_secs_f64();
+        adj.set_value(from + (target - from) * ease_out_cubic(progress));
+        if progress < 1.0 {
+            return glib::ControlFlow::Continue;
+        }
+        let _ = list.activate_action("list.scroll-to-item", Some(&pos.to_variant()));
+        glib::ControlFlow::Break
+    })
+}
+
+/// End a scroll early, at its target
+#[cfg(feature = "adwaita")]
+fn finish(running: Glide) {
+    running.skip();
+}
+
+/// End a scroll early, where it is
+#[cfg(not(feature = "adwaita"))]
+fn finish(running: Glide) {
+    running.remove();
+}
+
+/// libadwaita's `ease-out-cubic` at `progress`, from 0 to 1
+#[must_use]
+pub fn ease_out_cubic(progress: f64) -> f64 {
+    1.0 - (1.0 - progress.clamp(0.0, 1.0)).powi(3)
+}
+
+/// Adjustment value that brings row `pos` of `n_items` into view, scrolling
+/// as little as possible, or `None` when it is already visible
+fn scroll_target(adj: &gtk4::Adjustment, pos: u32, n_items: u32) -> Option<f64> {
+    visible_scroll_target(
+        adj.value(),
+        adj.page_size(),
+        adj.upper() - adj.lower(),
+        pos,
+        n_items,
+    )
+}
+
+/// [`scroll_target`] for a list of `height` whose rows are all the same
+/// height, scrolled to `value` with `page` visible
+#[must_use]
+pub fn visible_scroll_target(
+    value: f64,
+    page: f64,
+    height: f64,
+    pos: u32,
+    n_items: u32,
+) -> Option<f64> {
+    if n_items == 0 || height <= 0.0 {
+        return None;
+    }
+    let row_height = height / f64::from(n_items);
+    let top = f64::from(pos) * row_height;
+    let bottom = top + row_height;
+    let target = if top < value {
+        top
+    } else if bottom > value + page {
+        bottom - page
+    } else {
+        return None;
+    };
+    Some(target.clamp(0.0, (height - page).max(0.0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fresh_rows_are_the_spliced_range() {
+        let start = Instant::now();
+        let mut fresh = FreshRows::default();
+        assert!(!fresh.is_fresh(0, start));
+
+        fresh.spliced(2, 3, start);
+        assert!(!fresh.is_fresh(1, start));
+        assert!(fresh.is_fresh(2, start));
+        assert!(fresh.is_fresh(4, start));
+        assert!(!fresh.is_fresh(5, start));
+
+        // Rows scrolled into view long after the splice are not new
+        assert!(!fresh.is_fresh(3, start + FRESH_WINDOW * 2));
+
+        // A removal adds nothing
+        fresh.spliced(0, 0, start);
+        assert!(!fresh.is_fresh(0, start));
+    }
+
+    #[test]
+    fn test_should_animate_needs_setting_and_gtk() {
+        assert!(should_animate(true, true));
+        assert!(!should_animate(false, true));
+        assert!(!should_animate(true, false));
+    }
+
+    #[test]
+    fn test_ease_out_cubic() {
+        assert!(ease_out_cubic(0.0).abs() < f64::EPSILON);
+        assert!((ease_out_cubic(0.5) - 0.875).abs() < f64::EPSILON);
+        assert!((ease_out_cubic(1.0) - 1.0).abs() < f64::EPSILON);
+        // Late frames stay at the target
+        assert!((ease_out_cubic(1.5) - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_visible_scroll_target() {
+        // 100 rows of 10 px, 50 px visible from the top
+        assert_eq!(visible_scroll_target(0.0, 50.0, 1000.0, 2, 100), None);
+        // Paging down puts the row at the bottom edge
+        assert_eq!(
+            visible_scroll_target(0.0, 50.0, 1000.0, 10, 100),
+            Some(60.0)
+        );
+        // Paging up puts it at the top edge
+        assert_eq!(
+            visible_scroll_target(300.0, 50.0, 1000.0, 20, 100),
+            Some(200.0)
+        );
+        // Never past the end
+        assert_eq!(
+            visible_scroll_target(0.0, 50.0, 1000.0, 99, 100),
+            Some(950.0)
+        );
+        assert_eq!(visible_scroll_target(0.0, 50.0, 0.0, 0, 0), None);
+    }
+}
@@ -0,0 +1,184 @@
+//! Auto-launch countdown for the top result
+//!
+//! Watches the result list and, when `search.auto_launch_threshold` is set and
+//! the top application is an unambiguous match, highlights its row for the
+//! command debounce period and then launches it. Any key press cancels the
+//! countdown. The decision itself lives in [`crate::model::auto_launch`].
+//!
+//! The highlight is the `auto-launch-countdown` animation of `style.css`,
+//! run by [`countdown_css`] for the same delay as the timer.
+
+use crate::app_mode::AppMode;
+use crate::item_activation::{AfterActivation, activate_item};
+use crate::model::auto_launch::{
+    Candidate, CandidateKind, is_auto_launch_target, should_auto_launch,
+};
+use crate::model::items::{AppItem, SearchResultItem};
+use crate::model::list_model::AppListModel;
+use crate::ui::compat::ApplicationWindow;
+use gtk4::gdk;
+use gtk4::prelude::*;
+use gtk4::{EventControllerKey, ListView};
+use log::debug;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// CSS class added to the list view while a countdown is running
+const PENDING_CSS_CLASS: &str = "auto-launch-pending";
+
+/// CSS running the countdown animation on the selected row for `delay_ms`
+#[must_use]
+pub fn countdown_css(delay_ms: u64) -> String {
+    format!(
+        ".app-list.{PENDING_CSS_CLASS} > row:selected {{\n    \
+         animation: auto-launch-countdown {delay_ms}ms linear forwards;\n}}\n"
+    )
+}
+
+/// Pending auto-launch state shared between the signal handlers
+#[derive(Clone)]
+pub struct AutoLaunch {
+    list_view: ListView,
+    pending: Rc<RefCell<Option<glib::SourceId>>>,
+    last_input: Rc<Cell<Option<Instant>>>,
+    /// Provider of [`countdown_css`], and the delay it was last loaded for
+    countdown: gtk4::CssProvider,
+    countdown_ms: Rc<Cell<Option<u64>>>,
+}
+
+impl AutoLaunch {
+    #[must_use]
+    pub fn new(list_view: &ListView) -> Self {
+        let countdown = gtk4::CssProvider::new();
+        gtk4::style_context_add_provider_for_display(
+            &list_view.display(),
+            &countdown,
+            gtk4::STYLE_PROVIDER_PRIORITY_APPLICATION,
+        );
+        Self {
+            list_view: list_view.clone(),
+            pending: Rc::new(RefCell::new(None)),
+            last_input: Rc::new(Cell::new(None)),
+            countdown,
+            countdown_ms: Rc::new(Cell::new(None)),
+        }
+    }
+
+    /// Make the countdown animation last `delay_ms`, the delay of the timer
+    fn set_countdown(&self, delay_ms: u64) {
+        if self.countdown_ms.replace(Some(delay_ms)) != Some(delay_ms) {
+            self.countdown.load_from_string(&countdown_css(delay_ms));
+        }
+    }
+
+    /// Re-evaluate the results whenever they change and cancel on key presses
+    pub fn attach(
+        &self,
+        window: &ApplicationWindow,
+        model: &AppListModel,
+        current_mode: &Rc<Cell<AppMode>>,
+    ) {
+        let key_ctrl = EventControllerKey::new();
+        key_ctrl.set_propagation_phase(gtk4::PropagationPhase::Capture);
+        let this = self.clone();
+        key_ctrl.connect_key_pressed(move |_, _, _, _| {
+            this.last_input.set(Some(Instant::now()));
+            this.cancel();
+            glib::Propagation::Proceed
+        });
+        window.add_controller(key_ctrl);
+
+        let this = self.clone();
+        let window = window.clone();
+        let model_clone = model.clone();
+        let current_mode = current_mode.clone();
+        model.results.connect_items_changed(move |_, _, _, _| {
+            this.arm(&window, &model_clone, current_mode.get());
+        });
+    }
+
+    /// Stop a running countdown, if any
+    pub fn cancel(&self) {
+        if let Some(id) = self.pending.borrow_mut().take() {
+            id.remove();
+        }
+        self.list_view.remove_css_class(PENDING_CSS_CLASS);
+    }
+
+    /// Start the countdown if the current results qualify
+    fn arm(&self, window: &ApplicationWindow, model: &AppListModel, mode: AppMode) {
+        self.cancel();
+
+        let threshold = model.config.auto_launch_threshold.get();
+        if threshold == 0 || mode != AppMode::Normal || model.state.current_query().is_empty() {
+            return;
+        }
+        if !is_auto_launch_target(&candidates(model), threshold) {
+            return;
+        }
+
+        let delay_ms = u64::from(model.debounce.command_debounce_ms());
+        debug!("Auto-launch armed ({delay_ms} ms)");
+        self.set_countdown(delay_ms);
+        self.list_view.add_css_class(PENDING_CSS_CLASS);
+
+        let this = self.clone();
+        let window = window.clone();
+        let model = model.clone();
+        let id = glib::timeout_add_local_once(Duration::from_millis(delay_ms), move || {
+            this.pending.borrow_mut().take();
+            this.list_view.remove_css_class(PENDING_CSS_CLASS);
+
+            let idle_ms = this.last_input.get().map_or(u64::MAX, |t| {
+                u64::try_from(t.elapsed().as_millis()).unwrap_or(u64::MAX)
+            });
+            if !window.is_visible()
+                || !should_auto_launch(&candidates(&model), threshold, idle_ms, delay_ms)
+            {
+                return;
+            }
+
+            if let Some(obj) = model.results.item(0) {
+                debug!("Auto-launching top result");
+                let after = activate_item(&obj, &model, AppMode::Normal, gdk::CURRENT_TIME);
+                if after == AfterActivation::Close {
+                    window.set_visible(false);
+                }
+            }
+        });
+        *self.pending.borrow_mut() = Some(id);
+    }
+}
+
+/// Describe the current result list for the auto-launch decision
+fn candidates(model: &AppListModel) -> Vec<Candidate> {
+    let query = model.state.current_query().trim().to_lowercase();
+    (0..model.results.n_items())
+        .filter_map(|i| model.results.item(i))
+        .map(|obj| {
+            if let Some(app) = obj.downcast_ref::<AppItem>() {
+                Candidate::new(CandidateKind::App, app.score())
+                    .with_exact_name(app.name().to_lowercase() == query)
+            } else if obj.is::<SearchResultItem>() {
+                Candidate::new(CandidateKind::ProviderResult, 0)
+            } else {
+                Candidate::new(CandidateKind::Internal, 0)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_countdown_css_follows_delay() {
+        assert_eq!(
+            countdown_css(450),
+            ".app-list.auto-launch-pending > row:selected {\n    \
+             animation: auto-launch-countdown 450ms linear forwards;\n}\n"
+        );
+    }
+}
@@ -0,0 +1,219 @@
+//! libadwaita or plain GTK 4, depending on the `adwaita` feature
+//!
+//! Outside the settings dialog the launcher needs only a few libadwaita
+//! types: the application and its window, toasts and the color scheme.
+//! With the feature they come from libadwaita. Without it, GTK 4's
+//! application and window stand in, and toasts are a small overlay with
+//! the same API. Confirmation dialogs are in [`crate::ui::dialogs`].
+
+#[cfg(not(feature = "adwaita"))]
+use gtk4::prelude::*;
+
+#[cfg(feature = "adwaita")]
+pub use libadwaita::{Application, ApplicationWindow, Toast, ToastOverlay};
+
+#[cfg(not(feature = "adwaita"))]
+pub use gtk4::{Application, ApplicationWindow};
+#[cfg(not(feature = "adwaita"))]
+pub use toast::{Toast, ToastOverlay};
+
+/// Make `overlay` the content of `window`
+pub fn set_window_content(window: &ApplicationWindow, overlay: &ToastOverlay) {
+    #[cfg(feature = "adwaita")]
+    libadwaita::prelude::AdwApplicationWindowExt::set_content(window, Some(overlay));
+    #[cfg(not(feature = "adwaita"))]
+    window.set_child(Some(overlay.widget()));
+}
+
+/// Light or dark variant of the styles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorScheme {
+    /// Whatever the desktop prefers
+    Default,
+    ForceLight,
+    ForceDark,
+}
+
+/// Ask for the `scheme` variant of the styles
+///
+/// Without libadwaita this sets `gtk-application-prefer-dark-theme`, and
+/// [`ColorScheme::Default`] goes back to the desktop's value of it.
+pub fn set_color_scheme(scheme: ColorScheme) {
+    #[cfg(feature = "adwaita")]
+    libadwaita::StyleManager::default().set_color_scheme(match scheme {
+        ColorScheme::Default => libadwaita::ColorScheme::Default,
+        ColorScheme::ForceLight => libadwaita::ColorScheme::ForceLight,
+        ColorScheme::ForceDark => libadwaita::ColorScheme::ForceDark,
+    });
+    #[cfg(not(feature = "adwaita"))]
+    if let Some(settings) = gtk4::Settings::default() {
+        match scheme {
+            ColorScheme::Default => settings.reset_property("gtk-application-prefer-dark-theme"),
+            ColorScheme::ForceLight => settings.set_gtk_application_prefer_dark_theme(false),
+            ColorScheme::ForceDark => settings.set_gtk_application_prefer_dark_theme(true),
+        }
+    }
+}
+
+/// Toasts on plain GTK 4, with the part of libadwaita's API the launcher
+/// uses
+///
+/// A toast is a row at the bottom of the window. Toasts are not queued: a
+/// new one replaces the one shown.
+#[cfg(not(feature = "adwaita"))]
+mod toast {
+    use gtk4::prelude::*;
+    use gtk4::{Align, Box as GtkBox, Button, Label, Orientation, Overlay};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    type ButtonHandler = Box<dyn Fn(&Toast)>;
+
+    /// A short message, with an optional button
+    #[derive(Clone)]
+    pub struct Toast {
+        inner: Rc<ToastInner>,
+    }
+
+    struct ToastInner {
+        title: String,
+        button_label: Option<String>,
+        /// Seconds shown, 0 to stay until dismissed
+        timeout: u32,
+        handlers: RefCell<Vec<ButtonHandler>>,
+        /// Overlay and row while shown
+        shown: RefCell<Option<(Overlay, GtkBox)>>,
+    }
+
+    /// Builds a [`Toast`]
+    #[derive(Default)]
+    pub struct ToastBuilder {
+        title: String,
+        button_label: Option<String>,
+        timeout: u32,
+    }
+
+    impl ToastBuilder {
+        #[must_use]
+        pub fn title(mut self, title: impl Into<String>) -> Self {
+            self.title = title.into();
+            self
+        }
+
+        #[must_use]
+        pub fn button_label(mut self, label: impl Into<String>) -> Self {
+            self.button_label = Some(label.into());
+            self
+        }
+
+        #[must_use]
+        pub fn timeout(mut self, seconds: u32) -> Self {
+            self.timeout = seconds;
+            self
+        }
+
+        #[must_use]
+        pub fn build(self) -> Toast {
+            Toast {
+                inner: Rc::new(ToastInner {
+                    title: self.title,
+                    button_label: self.button_label,
+                    timeout: self.timeout,
+                    handlers: RefCell::new(Vec::new()),
+                    shown: RefCell::new(None),
+                }),
+            }
+        }
+    }
+
+    impl Toast {
+        #[must_use]
+        pub fn builder() -> ToastBuilder {
+            ToastBuilder {
+                timeout: 5,
+                ..ToastBuilder::default()
+            }
+        }
+
+        /// Run `f` when the button is clicked, before the toast goes away
+        pub fn connect_button_clicked<F: Fn(&Self) + 'static>(&self, f: F) {
+            self.inner.handlers.borrow_mut().push(Box::new(f));
+        }
+
+        /// Take the toast off the window, if it is shown
+        pub fn dismiss(&self) {
+            if let Some((overlay, row)) = self.inner.shown.borrow_mut().take() {
+                overlay.remove_overlay(&row);
+            }
+        }
+
+        fn show_in(&self, overlay: &Overlay) {
+            let row = GtkBox::new(Orientation::Horizontal, 12);
+            row.add_css_class("app-notification");
+            row.set_halign(Align::Center);
+            row.set_valign(Align::End);
+            row.set_margin_bottom(12);
+            row.append(&Label::new(Some(&self.inner.title)));
+            if let Some(label) = &self.inner.button_label {
+                let button = Button::with_label(label);
+                button.add_css_class("flat");
+                let toast = self.clone();
+                button.connect_clicked(move |_| {
+                    for handler in toast.inner.handlers.borrow().iter() {
+                        handler(&toast);
+                    }
+                    toast.dismiss();
+                });
+                row.append(&button);
+            }
+            overlay.add_overlay(&row);
+            *self.inner.shown.borrow_mut() = Some((overlay.clone(), row));
+
+            if self.inner.timeout > 0 {
+                let toast = self.clone();
+                glib::timeout_add_seconds_local_once(self.inner.timeout, move || toast.dismiss());
+            }
+        }
+    }
+
+    /// Shows toasts above its child
+    #[derive(Clone)]
+    pub struct ToastOverlay {
+        overlay: Overlay,
+        current: Rc<RefCell<Option<Toast>>>,
+    }
+
+    impl Default for ToastOverlay {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl ToastOverlay {
+        #[must_use]
+        pub fn new() -> Self {
+            Self {
+                overlay: Overlay::new(),
+                current: Rc::new(RefCell::new(None)),
+            }
+        }
+
+        pub fn set_child(&self, child: Option<&impl IsA<gtk4::Widget>>) {
+            self.overlay.set_child(child);
+        }
+
+        /// Show `toast`, replacing the one shown
+        pub fn add_toast(&self, toast: Toast) {
+            if let Some(previous) = self.current.replace(Some(toast.clone())) {
+                previous.dismiss();
+            }
+            toast.show_in(&self.overlay);
+        }
+
+        /// The widget to put in the window
+        #[must_use]
+        pub fn widget(&self) -> &Overlay {
+            &self.overlay
+        }
+    }
+}
@@ -5,26 +5,29 @@
 //! It eliminates code duplication by extracting common patterns like
 //! menu button creation, clipboard operations, and popover management.
 
+use crate::ui::compat::{ApplicationWindow, Toast, ToastOverlay};
 use glib::WeakRef;
 use glib::clone;
-use gtk4::gdk;
+use gtk4::gdk::{self, Key};
 use gtk4::prelude::*;
-use gtk4::{Align, Box as GtkBox, Button, GestureClick, Orientation, Popover};
-use libadwaita::{ApplicationWindow, Toast, ToastOverlay};
+use gtk4::{
+    Align, Box as GtkBox, Button, EventControllerKey, GestureClick, Label, Orientation, Popover,
+};
 use log::error;
 use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
 use crate::app_mode::AppMode;
 use crate::core::config::Config;
-use crate::item_activation::activate_item;
+use crate::item_activation::{activate_item, alternative_actions, run_alternative};
 use crate::launcher;
 use crate::model::items::{AppItem, CommandItem};
 use crate::model::list_model::AppListModel;
 use crate::ui::pinned_strip::{
-    MAX_PINNED_APPS, add_pinned_app, can_add_pinned_app, refresh_pinned_strip, remove_pinned_app,
-    save_pinned_apps,
+    MAX_PINNED_APPS, PinnedStore, add_pinned_app, can_add_pinned_app, refresh_pinned_strip,
+    remove_pinned_app,
 };
+use crate::ui::window::finish_activation;
 
 /// Shared state for building a context menu
 pub struct MenuContext {
@@ -36,7 +39,7 @@ pub struct MenuContext {
 #[derive(Clone)]
 pub struct WindowCtx {
     pub window: ApplicationWindow,
-    pub entry: gtk4::Entry,
+    pub entry: gtk4::SearchEntry,
     pub model: AppListModel,
     pub current_mode: Rc<Cell<AppMode>>,
     pub pinned_apps: Rc<RefCell<Vec<String>>>,
@@ -202,56 +205,131 @@ pub fn open_with_default_app(path: &str) {
 // Context menu dispatch
 // ---------------------------------------------------------------------------
 
-/// Set up right-click context menu on the results list
+/// Set up the context menu of the results list
+///
+/// The menu opens on right-click, or on the Menu key or Shift+F10 for the
+/// selected row.
 #[allow(clippy::cast_possible_truncation)]
 pub fn setup_list_context_menu(list_view: &gtk4::ListView, ctx: &WindowCtx) {
     let right_click = GestureClick::new();
     right_click.set_button(3);
-    let ctx = ctx.clone();
+    let click_ctx = ctx.clone();
     right_click.connect_pressed(clone!(
         #[weak]
         list_view,
         move |_gesture, _n_press, click_x, click_y| {
-            let clicked_pos = ctx.model.selection.selected();
+            let rect = gdk::Rectangle::new(click_x as i32, click_y as i32, 1, 1);
+            popup_context_menu(&list_view, &click_ctx, Some(&rect));
+        }
+    ));
+    list_view.add_controller(right_click);
 
-            let Some(obj) = ctx.model.store.item(clicked_pos) else {
-                return;
-            };
+    let key_ctrl = EventControllerKey::new();
+    let key_ctx = ctx.clone();
+    key_ctrl.connect_key_pressed(clone!(
+        #[weak]
+        list_view,
+        #[upgrade_or]
+        glib::Propagation::Proceed,
+        move |_, key, _, modifier_state| {
+            let shift_f10 =
+                key == Key::F10 && modifier_state.contains(gdk::ModifierType::SHIFT_MASK);
+            if key != Key::Menu && !shift_f10 {
+                return glib::Propagation::Proceed;
+            }
+            let rect = selected_row_bounds(&list_view);
+            popup_context_menu(&list_view, &key_ctx, rect.as_ref());
+            glib::Propagation::Stop
+        }
+    ));
+    ctx.window.add_controller(key_ctrl);
+}
 
-            ctx.model.selection.set_selected(clicked_pos);
-            let mode = ctx.current_mode.get();
+/// Area of the selected row in `list_view`, if it is on screen
+#[allow(clippy::cast_possible_truncation)]
+fn selected_row_bounds(list_view: &gtk4::ListView) -> Option<gdk::Rectangle> {
+    let mut child = list_view.first_child();
+    while let Some(row) = child {
+        if row.state_flags().contains(gtk4::StateFlags::SELECTED) {
+            let bounds = row.compute_bounds(list_view)?;
+            return Some(gdk::Rectangle::new(
+                bounds.x() as i32,
+                bounds.y() as i32,
+                bounds.width() as i32,
+                bounds.height() as i32,
+            ));
+        }
+        child = row.next_sibling();
+    }
+    None
+}
 
-            let popover = Popover::new();
-            popover.set_has_arrow(true);
-            let weak_popover = glib::WeakRef::<Popover>::new();
-            weak_popover.set(Some(&popover));
+/// Show the menu of the selected row, pointing at `rect` of `list_view`,
+/// or at its middle without one
+fn popup_context_menu(list_view: &gtk4::ListView, ctx: &WindowCtx, rect: Option<&gdk::Rectangle>) {
+    let Some(obj) = ctx.model.results.item(ctx.model.results.selected()) else {
+        return;
+    };
+    let mode = ctx.current_mode.get();
 
-            let vbox = GtkBox::new(Orientation::Vertical, 0);
-            vbox.add_css_class("context-menu-box");
+    let popover = Popover::new();
+    popover.set_has_arrow(true);
+    let weak_popover = glib::WeakRef::<Popover>::new();
+    weak_popover.set(Some(&popover));
 
-            match mode {
-                AppMode::Obsidian | AppMode::ObsidianGrep => {
-                    build_obsidian_context_menu(&obj, &vbox, &weak_popover, &ctx, mode);
-                }
-                AppMode::FileSearch => {
-                    build_file_search_context_menu(&obj, &vbox, &weak_popover, &ctx);
-                }
-                AppMode::CustomScript => {
-                    build_shell_context_menu(&obj, &vbox, &weak_popover, &ctx);
-                }
-                AppMode::Normal => {
-                    build_normal_context_menu(&obj, &vbox, &weak_popover, &ctx, mode);
-                }
-            }
+    let vbox = GtkBox::new(Orientation::Vertical, 0);
+    vbox.add_css_class("context-menu-box");
 
-            popover.set_child(Some(&vbox));
-            popover.set_parent(&list_view);
-            let rect = gdk::Rectangle::new(click_x as i32, click_y as i32, 1, 1);
-            popover.set_pointing_to(Some(&rect));
-            popover.popup();
+    match mode {
+        AppMode::Obsidian | AppMode::ObsidianGrep => {
+            build_obsidian_context_menu(&obj, &vbox, &weak_popover, ctx, mode);
         }
-    ));
-    list_view.add_controller(right_click);
+        AppMode::FileSearch | AppMode::Recent => {
+            build_file_search_context_menu(&obj, &vbox, &weak_popover, ctx);
+        }
+        AppMode::CustomScript => {
+            build_shell_context_menu(&obj, &vbox, &weak_popover, ctx);
+        }
+        AppMode::Normal
+        | AppMode::Bookmarks
+        | AppMode::Clipboard
+        | AppMode::Windows
+        | AppMode::Emoji
+        | AppMode::Processes
+        | AppMode::Projects
+        | AppMode::Ssh
+        | AppMode::Units
+        | AppMode::Manual
+        | AppMode::Snippets
+        | AppMode::Pass => {
+            build_normal_context_menu(&obj, &vbox, &weak_popover, ctx, mode);
+        }
+    }
+
+    popover.set_child(Some(&vbox));
+    popover.set_parent(list_view);
+    popover.set_pointing_to(rect);
+    popover.popup();
+}
+
+/// Add a button for each alternative action of `obj`, see
+/// [`crate::item_activation::AltAction`]
+fn add_alternative_buttons(ctx_menu: &MenuContext, obj: &glib::Object, ctx: &WindowCtx) {
+    for &action in alternative_actions(obj) {
+        let obj = obj.clone();
+        let model = ctx.model.clone();
+        let window = ctx.window.clone();
+        let toast_overlay = ctx.toast_overlay.clone();
+        let weak = ctx_menu.weak_popover.clone();
+        add_menu_button(ctx_menu, action.label(), move || {
+            if let Some(p) = weak.upgrade() {
+                p.popdown();
+            }
+            if let Some(after) = run_alternative(&obj, action, &model, gdk::CURRENT_TIME) {
+                finish_activation(after, &window, &toast_overlay);
+            }
+        });
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -270,6 +348,10 @@ fn build_normal_context_menu(
         vbox: vbox.clone(),
     };
 
+    if let Some(app_item) = obj.downcast_ref::<AppItem>() {
+        add_app_origin_label(&ctx_menu, app_item, &ctx.model);
+    }
+
     let (desktop_id_opt, is_pinned) = if let Some(app_item) = obj.downcast_ref::<AppItem>() {
         let exec = app_item.exec();
         let apps = ctx.all_apps.borrow();
@@ -288,11 +370,13 @@ fn build_normal_context_menu(
     let model_open = ctx.model.clone();
     let action_open = mode;
     let win_open = ctx.window.clone();
+    let toast_open = ctx.toast_overlay.clone();
     let obj_open = obj.clone();
     add_menu_button(&ctx_menu, "Open", move || {
-        activate_item(&obj_open, &model_open, action_open, gdk::CURRENT_TIME);
-        win_open.hide();
+        let after = activate_item(&obj_open, &model_open, action_open, gdk::CURRENT_TIME);
+        finish_activation(after, &win_open, &toast_open);
     });
+    add_alternative_buttons(&ctx_menu, obj, ctx);
 
     let entry_for_btns = ctx.entry.clone();
     if is_pinned {
@@ -303,11 +387,11 @@ fn build_normal_context_menu(
         let win_ref = ctx.window.clone();
         let weak = weak_popover.clone();
         let dragging_ref = ctx.dragging.clone();
-        let cfg = ctx.cfg.clone();
+        let store = PinnedStore::new(&ctx.cfg, &ctx.toast_overlay);
         add_menu_button(&ctx_menu, "Remove from Favourites", move || {
             if let Some(ref id) = did {
                 remove_pinned_app(&p_apps, id);
-                save_pinned_apps(&p_apps.borrow(), &cfg);
+                store.save(&p_apps.borrow());
             }
             refresh_pinned_strip(
                 &p_strip,
@@ -316,7 +400,7 @@ fn build_normal_context_menu(
                 &win_ref,
                 entry_for_btns.text().is_empty(),
                 &dragging_ref,
-                &cfg,
+                &store,
             );
             if let Some(p) = weak.upgrade() {
                 p.popdown();
@@ -333,7 +417,7 @@ fn build_normal_context_menu(
         let toast_ref = ctx.toast_overlay.clone();
         let entry_add = entry_for_btns.clone();
         let dragging_ref = ctx.dragging.clone();
-        let cfg = ctx.cfg.clone();
+        let store = PinnedStore::new(&ctx.cfg, &ctx.toast_overlay);
         add_menu_button(&ctx_menu, "Add to Favourites", move || {
             let Some(ref id) = did else {
                 if let Some(p) = weak.upgrade() {
@@ -353,7 +437,7 @@ fn build_normal_context_menu(
                 return;
             }
             if add_pinned_app(&p_apps, id).is_ok() {
-                save_pinned_apps(&p_apps.borrow(), &cfg);
+                store.save(&p_apps.borrow());
             }
             refresh_pinned_strip(
                 &p_strip,
@@ -362,7 +446,7 @@ fn build_normal_context_menu(
                 &win_ref,
                 entry_add.text().is_empty(),
                 &dragging_ref,
-                &cfg,
+                &store,
             );
             if let Some(p) = weak.upgrade() {
                 p.popdown();
@@ -370,6 +454,63 @@ fn build_normal_context_menu(
             entry_add.grab_focus();
         });
     }
+
+    if let Some(app_item) = obj.downcast_ref::<AppItem>() {
+        let desktop_id = app_item.desktop_id();
+        let name = app_item.name();
+        let model = ctx.model.clone();
+        let toast_ref = ctx.toast_overlay.clone();
+        let entry = ctx.entry.clone();
+        let weak = weak_popover.clone();
+        add_menu_button(&ctx_menu, "Hide App", move || {
+            if let Some(p) = weak.upgrade() {
+                p.popdown();
+            }
+            hide_app(&model, &toast_ref, &desktop_id, &name);
+            entry.grab_focus();
+        });
+    }
+}
+
+/// Head the menu of an app with where it was installed from, filled in
+/// once the lookup finishes and left out if nothing is found
+fn add_app_origin_label(ctx: &MenuContext, app_item: &AppItem, model: &AppListModel) {
+    let label = Label::new(None);
+    label.add_css_class("dim-label");
+    label.add_css_class("context-menu-origin");
+    label.set_halign(Align::Start);
+    label.set_visible(false);
+    ctx.vbox.append(&label);
+
+    let weak_label = label.downgrade();
+    let known = model
+        .config
+        .app_origins
+        .get_or_resolve(&app_item.path(), move |origin| {
+            if let Some(label) = weak_label.upgrade() {
+                label.set_text(&origin.label());
+                label.set_visible(true);
+            }
+        });
+    if let Some(origin) = known {
+        label.set_text(&origin.label());
+        label.set_visible(true);
+    }
+}
+
+/// Add an application to `[search] app_blacklist` and drop it from results
+fn hide_app(model: &AppListModel, overlay: &ToastOverlay, desktop_id: &str, name: &str) {
+    let title = match crate::core::config::blacklist_app(&format!("{desktop_id}.desktop")) {
+        Ok(()) => format!("{name} hidden"),
+        Err(e) => {
+            error!("Failed to hide app {desktop_id}: {e}");
+            format!("{name} hidden until restart, config not saved: {e}")
+        }
+    };
+    model.hide_app(desktop_id);
+
+    let toast = Toast::builder().title(title).timeout(2).build();
+    overlay.add_toast(toast);
 }
 
 fn build_obsidian_context_menu(
@@ -388,15 +529,22 @@ fn build_obsidian_context_menu(
         vbox: vbox.clone(),
     };
 
-    let path = cmd_item.line();
+    let Some(path) = cmd_item
+        .payload()
+        .path()
+        .map(|p| p.to_string_lossy().into_owned())
+    else {
+        return;
+    };
 
     let obj_open = obj.clone();
     let model_open = ctx.model.clone();
     let action_open = mode;
     let win_open = ctx.window.clone();
+    let toast_open = ctx.toast_overlay.clone();
     add_menu_button(&ctx_menu, "Open in Obsidian", move || {
-        activate_item(&obj_open, &model_open, action_open, gdk::CURRENT_TIME);
-        win_open.hide();
+        let after = activate_item(&obj_open, &model_open, action_open, gdk::CURRENT_TIME);
+        finish_activation(after, &win_open, &toast_open);
     });
 
     add_copy_text_button(&ctx_menu, "Copy note path", &path);
@@ -420,7 +568,13 @@ fn build_file_search_context_menu(
         vbox: vbox.clone(),
     };
 
-    let path = cmd_item.line();
+    let Some(path) = cmd_item
+        .payload()
+        .path()
+        .map(|p| p.to_string_lossy().into_owned())
+    else {
+        return;
+    };
 
     let obj_open = obj.clone();
     let model_open = ctx.model.clone();
@@ -432,17 +586,16 @@ fn build_file_search_context_menu(
             AppMode::FileSearch,
             gdk::CURRENT_TIME,
         );
-        win_open.hide();
+        win_open.set_visible(false);
     });
-
-    add_copy_text_button(&ctx_menu, "Copy path", &path);
+    // Open containing folder and Copy path
+    add_alternative_buttons(&ctx_menu, obj, ctx);
 
     if is_text_file(&path) {
         add_copy_content_button(&ctx_menu, "Copy content", &path);
     }
 
     add_copy_file_button(&ctx_menu, "Copy file", &path);
-    add_open_in_file_manager_button(&ctx_menu, "Show in file manager", &path);
 }
 
 fn build_shell_context_menu(
@@ -460,13 +613,8 @@ fn build_shell_context_menu(
         vbox: vbox.clone(),
     };
 
-    let line = cmd_item.line();
-    let command = if let Some((_, cmd)) = line.split_once(" | ") {
-        cmd.trim().to_string()
-    } else if let Some(stripped) = line.strip_prefix("Run: ") {
-        stripped.trim().to_string()
-    } else {
-        line
+    let Some(command) = cmd_item.payload().text().map(str::to_string) else {
+        return;
     };
 
     let obj_run = obj.clone();
@@ -479,7 +627,7 @@ fn build_shell_context_menu(
             AppMode::CustomScript,
             gdk::CURRENT_TIME,
         );
-        win_run.hide();
+        win_run.set_visible(false);
     });
 
     add_copy_text_button(&ctx_menu, "Copy command", &command);
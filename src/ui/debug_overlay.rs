@@ -0,0 +1,94 @@
+//! Debug overlay with live search timings, toggled with F12
+//!
+//! The overlay is a label in the top-right corner of the window showing the
+//! report of [`crate::metrics`]. Metrics are only recorded while it is
+//! visible, and it refreshes every [`REFRESH_MS`] only while it is on
+//! screen: hiding the window pauses the refresh timer.
+
+use crate::launcher::DesktopApp;
+use crate::metrics;
+use crate::model::list_model::AppListModel;
+use gtk4::prelude::*;
+use gtk4::{Align, Label};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+/// Refresh interval of the visible overlay
+const REFRESH_MS: u64 = 500;
+
+/// The F12 timing overlay
+#[derive(Clone)]
+pub struct DebugOverlay {
+    label: Label,
+    model: AppListModel,
+    all_apps: Rc<RefCell<Vec<DesktopApp>>>,
+    /// Refresh timer, running while the overlay is visible
+    timer: Rc<RefCell<Option<glib::SourceId>>>,
+}
+
+impl DebugOverlay {
+    /// Create the hidden overlay for `model`
+    #[must_use]
+    pub fn new(model: &AppListModel, all_apps: &Rc<RefCell<Vec<DesktopApp>>>) -> Self {
+        let label = Label::new(None);
+        label.add_css_class("debug-overlay");
+        label.set_halign(Align::End);
+        label.set_valign(Align::Start);
+        label.set_xalign(0.0);
+        label.set_can_target(false);
+        label.set_visible(false);
+        let overlay = Self {
+            label,
+            model: model.clone(),
+            all_apps: all_apps.clone(),
+            timer: Rc::new(RefCell::new(None)),
+        };
+
+        let on_map = overlay.clone();
+        overlay.label.connect_map(move |_| on_map.start_timer());
+        let on_unmap = overlay.clone();
+        overlay.label.connect_unmap(move |_| on_unmap.stop_timer());
+        overlay
+    }
+
+    /// The label to add as an overlay over the window content
+    #[must_use]
+    pub fn widget(&self) -> &Label {
+        &self.label
+    }
+
+    /// Show or hide the overlay, starting or stopping the recording
+    pub fn toggle(&self) {
+        let show = !self.label.is_visible();
+        metrics::set_enabled(show);
+        self.label.set_visible(show);
+    }
+
+    /// Refresh now and then every [`REFRESH_MS`], while the label is mapped
+    fn start_timer(&self) {
+        self.stop_timer();
+        self.refresh();
+        let overlay = self.clone();
+        let timer = glib::timeout_add_local(Duration::from_millis(REFRESH_MS), move || {
+            overlay.refresh();
+            glib::ControlFlow::Continue
+        });
+        *self.timer.borrow_mut() = Some(timer);
+    }
+
+    fn stop_timer(&self) {
+        if let Some(timer) = self.timer.borrow_mut().take() {
+            timer.remove();
+        }
+    }
+
+    fn refresh(&self) {
+        let report = metrics::get().report(
+            self.all_apps.borrow().len(),
+            self.model.results.n_items(),
+            self.model.config.search_cuts.get(),
+        );
+        self.label.set_text(&report);
+    }
+}
@@ -0,0 +1,183 @@
+//! Confirmation dialogs, on libadwaita or on plain GTK 4
+//!
+//! [`confirm`] asks a yes/no question over the launcher window and reports
+//! the answer as a [`Response`]. With the `adwaita` feature it is an
+//! `AdwAlertDialog`, whose confirm button is red for a destructive action;
+//! without it a `GtkAlertDialog`, which has no button styles. In both,
+//! Cancel is the default button, and Escape or closing the dialog cancels.
+
+use crate::ui::compat::ApplicationWindow;
+
+/// Response id of the cancel button of the libadwaita dialog
+pub const CANCEL_ID: &str = "cancel";
+/// Response id of the confirm button of the libadwaita dialog
+pub const CONFIRM_ID: &str = "confirm";
+/// Index of the cancel button of the GTK dialog, first as in libadwaita
+pub const CANCEL_BUTTON: i32 = 0;
+/// Index of the confirm button of the GTK dialog
+pub const CONFIRM_BUTTON: i32 = 1;
+
+/// A question asked before an action
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Confirmation {
+    pub heading: String,
+    pub body: String,
+    /// Label of the button that goes ahead, e.g. `Restart`
+    pub confirm_label: String,
+    /// Whether the action cannot be undone, which styles its button
+    pub destructive: bool,
+}
+
+/// How a [`Confirmation`] was answered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Response {
+    Confirm,
+    Cancel,
+}
+
+impl Response {
+    /// The response of the libadwaita dialog with id `id`
+    ///
+    /// The close response is the cancel one, so anything but the confirm
+    /// button cancels.
+    #[must_use]
+    pub fn from_id(id: &str) -> Self {
+        if id == CONFIRM_ID {
+            Self::Confirm
+        } else {
+            Self::Cancel
+        }
+    }
+
+    /// The response of the GTK dialog, given the button it returned
+    ///
+    /// GTK reports a dismissed dialog as an error, which cancels.
+    #[must_use]
+    pub fn from_button<E>(button: Result<i32, E>) -> Self {
+        match button {
+            Ok(CONFIRM_BUTTON) => Self::Confirm,
+            _ => Self::Cancel,
+        }
+    }
+}
+
+/// Button labels of the GTK dialog, at [`CANCEL_BUTTON`] and
+/// [`CONFIRM_BUTTON`]
+#[must_use]
+pub fn button_labels(confirmation: &Confirmation) -> [&str; 2] {
+    ["Cancel", &confirmation.confirm_label]
+}
+
+/// Ask `confirmation` over `parent` and pass the answer to `on_response`
+pub fn confirm(
+    parent: &ApplicationWindow,
+    confirmation: &Confirmation,
+    on_response: impl FnOnce(Response) + 'static,
+) {
+    backend::present(parent, confirmation, on_response);
+}
+
+#[cfg(feature = "adwaita")]
+mod backend {
+    use super::{CANCEL_ID, CONFIRM_ID, Confirmation, Response};
+    use crate::ui::compat::ApplicationWindow;
+    use libadwaita::prelude::{AdwDialogExt, AlertDialogExt};
+    use libadwaita::{AlertDialog, ResponseAppearance};
+    use std::cell::Cell;
+
+    pub(super) fn present(
+        parent: &ApplicationWindow,
+        confirmation: &Confirmation,
+        on_response: impl FnOnce(Response) + 'static,
+    ) {
+        let dialog = AlertDialog::builder()
+            .heading(confirmation.heading.as_str())
+            .body(confirmation.body.as_str())
+            .default_response(CANCEL_ID)
+            .close_response(CANCEL_ID)
+            .build();
+        dialog.add_response(CANCEL_ID, "Cancel");
+        dialog.add_response(CONFIRM_ID, &confirmation.confirm_label);
+        if confirmation.destructive {
+            dialog.set_response_appearance(CONFIRM_ID, ResponseAppearance::Destructive);
+        }
+
+        // The signal handler is `Fn`, but the dialog answers once
+        let on_response = Cell::new(Some(on_response));
+        dialog.connect_response(None, move |_, id| {
+            if let Some(on_response) = on_response.take() {
+                on_response(Response::from_id(id));
+            }
+        });
+        dialog.present(Some(parent));
+    }
+}
+
+#[cfg(not(feature = "adwaita"))]
+mod backend {
+    use super::{CANCEL_BUTTON, Confirmation, Response, button_labels};
+    use crate::ui::compat::ApplicationWindow;
+    use gtk4::{AlertDialog, gio};
+
+    pub(super) fn present(
+        parent: &ApplicationWindow,
+        confirmation: &Confirmation,
+        on_response: impl FnOnce(Response) + 'static,
+    ) {
+        let dialog = AlertDialog::builder()
+            .message(confirmation.heading.as_str())
+            .detail(confirmation.body.as_str())
+            .buttons(button_labels(confirmation))
+            .cancel_button(CANCEL_BUTTON)
+            .default_button(CANCEL_BUTTON)
+            .modal(true)
+            .build();
+        dialog.choose(Some(parent), None::<&gio::Cancellable>, move |button| {
+            on_response(Response::from_button(button));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn restart() -> Confirmation {
+        Confirmation {
+            heading: "Restart?".to_string(),
+            body: "Are you sure you want to restart?".to_string(),
+            confirm_label: "Restart".to_string(),
+            destructive: true,
+        }
+    }
+
+    #[test]
+    fn test_adwaita_responses() {
+        assert_eq!(Response::from_id(CONFIRM_ID), Response::Confirm);
+        assert_eq!(Response::from_id(CANCEL_ID), Response::Cancel);
+        // Any other id, e.g. a close response, cancels
+        assert_eq!(Response::from_id("close"), Response::Cancel);
+    }
+
+    #[test]
+    fn test_gtk_responses() {
+        assert_eq!(
+            Response::from_button::<()>(Ok(CONFIRM_BUTTON)),
+            Response::Confirm
+        );
+        assert_eq!(
+            Response::from_button::<()>(Ok(CANCEL_BUTTON)),
+            Response::Cancel
+        );
+        // Escape or closing the dialog
+        assert_eq!(Response::from_button(Err(())), Response::Cancel);
+    }
+
+    #[test]
+    fn test_button_labels_match_indices() {
+        let confirmation = restart();
+        let labels = button_labels(&confirmation);
+        assert_eq!(labels[CANCEL_BUTTON as usize], "Cancel");
+        assert_eq!(labels[CONFIRM_BUTTON as usize], "Restart");
+    }
+}
@@ -0,0 +1,162 @@
+//! Directory monitors that follow directories which do not exist yet
+//!
+//! A GIO directory monitor needs an existing directory. [`DirWatch`]
+//! monitors its directory when it exists, and otherwise the nearest
+//! existing ancestor: once a directory on the way is created the watch is
+//! re-armed one level closer, and once the directory itself appears it is
+//! monitored and reported as appeared. If the directory is deleted, the
+//! watch falls back to its ancestor again.
+
+use gtk4::gio;
+use gtk4::prelude::*;
+use log::{debug, warn};
+use std::cell::{Cell, RefCell};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Called with the file and event of each change inside the directory
+pub type OnChange = Rc<dyn Fn(&gio::File, gio::FileMonitorEvent)>;
+
+/// Called when the directory appears or disappears
+pub type OnToggle = Rc<dyn Fn()>;
+
+/// Watch of one directory, see the module documentation
+#[derive(Clone)]
+pub struct DirWatch {
+    dir: Rc<PathBuf>,
+    monitor: Rc<RefCell<Option<gio::FileMonitor>>>,
+    /// Whether the monitor is on the directory itself, not an ancestor
+    watching_dir: Rc<Cell<bool>>,
+    on_change: OnChange,
+    on_toggle: OnToggle,
+}
+
+impl DirWatch {
+    /// Start watching `dir`
+    ///
+    /// `on_change` gets the changes inside `dir`; `on_toggle` is called
+    /// when `dir` is created or deleted.
+    #[must_use]
+    pub fn new(dir: PathBuf, on_change: OnChange, on_toggle: OnToggle) -> Self {
+        let watch = Self {
+            dir: Rc::new(dir),
+            monitor: Rc::new(RefCell::new(None)),
+            watching_dir: Rc::new(Cell::new(false)),
+            on_change,
+            on_toggle,
+        };
+        watch.arm();
+        watch
+    }
+
+    /// Stop watching
+    pub fn cancel(&self) {
+        if let Some(monitor) = self.monitor.borrow_mut().take() {
+            monitor.cancel();
+        }
+    }
+
+    /// Monitor the directory, or its nearest existing ancestor
+    fn arm(&self) {
+        self.cancel();
+        self.watching_dir.set(false);
+        let Some(watched) = nearest_existing_dir(&self.dir) else {
+            return;
+        };
+        let monitor = match gio::File::for_path(&watched)
+            .monitor_directory(gio::FileMonitorFlags::NONE, gio::Cancellable::NONE)
+        {
+            Ok(monitor) => monitor,
+            Err(e) => {
+                warn!("Cannot watch {} for changes: {e}", watched.display());
+                return;
+            }
+        };
+
+        let this = self.clone();
+        if watched == *self.dir {
+            monitor.connect_changed(move |_, file, _, event| {
+                if event == gio::FileMonitorEvent::Deleted
+                    && file.path().as_deref() == Some(this.dir.as_path())
+                {
+                    debug!("{} was deleted, watching its parent", this.dir.display());
+                    this.rearm_later();
+                } else {
+                    (this.on_change)(file, event);
+                }
+            });
+        } else {
+            debug!(
+                "{} does not exist, watching {}",
+                self.dir.display(),
+                watched.display()
+            );
+            monitor.connect_changed(move |_, file, _, event| {
+                if matches!(
+                    event,
+                    gio::FileMonitorEvent::Created | gio::FileMonitorEvent::MovedIn
+                ) && file.path().is_some_and(|path| leads_to(&path, &this.dir))
+                {
+                    this.rearm_later();
+                }
+            });
+        }
+        self.watching_dir.set(watched == *self.dir);
+        *self.monitor.borrow_mut() = Some(monitor);
+    }
+
+    /// Re-arm once the current monitor callback has returned, reporting the
+    /// directory if it appeared or disappeared meanwhile
+    fn rearm_later(&self) {
+        let this = self.clone();
+        glib::idle_add_local_once(move || {
+            let was_watching_dir = this.watching_dir.get();
+            this.arm();
+            if this.watching_dir.get() != was_watching_dir {
+                (this.on_toggle)();
+            }
+        });
+    }
+}
+
+/// `dir`, or the nearest of its ancestors that is an existing directory
+fn nearest_existing_dir(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .find(|ancestor| ancestor.is_dir())
+        .map(Path::to_path_buf)
+}
+
+/// Whether creating `created` brings `dir` closer to existing: it is `dir`
+/// or one of its ancestors
+fn leads_to(created: &Path, dir: &Path) -> bool {
+    dir.starts_with(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nearest_existing_dir() {
+        let root =
+            std::env::temp_dir().join(format!("grunner_test_dir_watch_{}", std::process::id()));
+        let target = root.join("flatpak/exports/share/applications");
+        std::fs::create_dir_all(root.join("flatpak")).unwrap();
+
+        assert_eq!(nearest_existing_dir(&target), Some(root.join("flatpak")));
+        std::fs::create_dir_all(&target).unwrap();
+        assert_eq!(nearest_existing_dir(&target), Some(target.clone()));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_leads_to() {
+        let dir = Path::new("/home/u/.local/share/flatpak/exports/share/applications");
+        assert!(leads_to(Path::new("/home/u/.local/share/flatpak"), dir));
+        assert!(leads_to(dir, dir));
+        assert!(!leads_to(Path::new("/home/u/.local/share/fonts"), dir));
+        // Components are compared whole
+        assert!(!leads_to(Path::new("/home/u/.local/share/flat"), dir));
+    }
+}
@@ -0,0 +1,73 @@
+//! Text direction decisions for right-to-left locales
+//!
+//! GTK already mirrors boxes and `Align::Start`/`Align::End` when the widget
+//! direction is right-to-left, so rows, the entry area and the bars follow the
+//! locale on their own. What GTK cannot know is which part of a description
+//! matters: these helpers make that choice from a resolved direction, without
+//! touching widgets, so they can be tested.
+
+use gtk4::TextDirection;
+use gtk4::pango::EllipsizeMode;
+
+/// What a row description contains, as far as ellipsizing is concerned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DescKind {
+    /// Free text whose beginning matters most (comments, `line:match`)
+    Text,
+    /// A path whose last components matter most
+    Path,
+}
+
+/// Whether a widget direction means right-to-left
+///
+/// Widgets report `TextDirection::None` until a direction is set explicitly,
+/// in which case the default direction of the locale applies.
+#[must_use]
+pub fn is_rtl(direction: TextDirection, default: TextDirection) -> bool {
+    match direction {
+        TextDirection::Rtl => true,
+        TextDirection::None => default == TextDirection::Rtl,
+        _ => false,
+    }
+}
+
+/// Where to ellipsize a description that does not fit
+///
+/// Text keeps its beginning in both directions. Paths are shown inside
+/// right-to-left rows as embedded left-to-right runs, where cutting the
+/// logical end would hide the file and folder names; there they lose their
+/// beginning instead.
+#[must_use]
+pub fn desc_ellipsize(kind: DescKind, rtl: bool) -> EllipsizeMode {
+    match (kind, rtl) {
+        (DescKind::Path, true) => EllipsizeMode::Start,
+        _ => EllipsizeMode::End,
+    }
+}
+
+/// Whether `widget` is laid out right-to-left
+#[must_use]
+pub fn widget_is_rtl(widget: &impl gtk4::prelude::WidgetExt) -> bool {
+    is_rtl(widget.direction(), gtk4::Widget::default_direction())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_rtl_resolves_default_direction() {
+        assert!(is_rtl(TextDirection::Rtl, TextDirection::Ltr));
+        assert!(!is_rtl(TextDirection::Ltr, TextDirection::Rtl));
+        assert!(is_rtl(TextDirection::None, TextDirection::Rtl));
+        assert!(!is_rtl(TextDirection::None, TextDirection::Ltr));
+    }
+
+    #[test]
+    fn test_desc_ellipsize() {
+        assert_eq!(desc_ellipsize(DescKind::Text, false), EllipsizeMode::End);
+        assert_eq!(desc_ellipsize(DescKind::Text, true), EllipsizeMode::End);
+        assert_eq!(desc_ellipsize(DescKind::Path, false), EllipsizeMode::End);
+        assert_eq!(desc_ellipsize(DescKind::Path, true), EllipsizeMode::Start);
+    }
+}
@@ -0,0 +1,316 @@
+//! Window of `grunner --dmenu`
+//!
+//! A search entry over the lines of standard input, see [`crate::dmenu`].
+//! Lines are [`CommandItem`] message rows drawn by the usual list factory;
+//! while matching lines are left out a last, inert stats row counts them.
+//! Enter picks the selected line, or the typed text when nothing matches,
+//! and closes the window; Escape closes it without a choice.
+
+use crate::core::config::Config;
+use crate::dmenu::{CHUNK_LINES, Filtered, Lines, filter_lines, more_lines_label, read_chunks};
+use crate::model::command_row::{CommandKind, CommandRow};
+use crate::model::items::CommandItem;
+use crate::model::main_thread::MainThreadModel;
+use crate::model::search_state::SearchState;
+use crate::model::worker::spawn_worker_with_updates;
+use crate::providers::matcher::build_matcher;
+use crate::ui::animation::ListAnimations;
+use crate::ui::compat::{Application, ApplicationWindow, ToastOverlay, set_window_content};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use gtk4::prelude::*;
+use gtk4::{
+    Box as GtkBox, CssProvider, EventControllerKey, ListView, Orientation, PolicyType,
+    ScrolledWindow, SearchEntry,
+};
+use log::{debug, info};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// What the user picked, read by `main` once the window is closed
+pub type DmenuChoice = Rc<RefCell<Option<String>>>;
+
+/// State of the dmenu window, shared by its handlers
+#[derive(Clone)]
+struct Dmenu {
+    /// Every line read so far; workers filter a snapshot of it
+    lines: Rc<RefCell<Lines>>,
+    results: MainThreadModel,
+    entry: SearchEntry,
+    list_view: ListView,
+    matcher: Arc<SkimMatcherV2>,
+    /// Most line rows shown at once
+    limit: usize,
+    /// Generation of the query; a filter run stops once it changes
+    generation: Arc<AtomicU64>,
+    /// Whether a filter run for the current query is under way
+    filtering: Rc<Cell<bool>>,
+    /// Whether lines arrived during that run, so it has to be repeated
+    lines_added: Rc<Cell<bool>>,
+}
+
+impl Dmenu {
+    /// Filter the lines for the new text of the entry and show the result
+    ///
+    /// A run still scoring the previous query is cancelled. The empty query
+    /// keeps the input order and needs no scoring, so it is shown at once;
+    /// other queries are scored on a worker thread.
+    fn refresh(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+        self.filtering.set(false);
+        self.lines_added.set(false);
+        if self.entry.text().is_empty() {
+            self.show_input_order(false);
+        } else {
+            self.filter(false);
+        }
+    }
+
+    /// Show the first lines, as read
+    fn show_input_order(&self, keep_selection: bool) {
+        let lines = self.lines.borrow();
+        let never = || false;
+        if let Some(filtered) = filter_lines(&lines, "", &self.matcher, self.limit, &never) {
+            self.show(&lines, &filtered, keep_selection);
+        }
+    }
+
+    /// Score the lines for the current query on a worker thread
+    ///
+    /// Lines added meanwhile are scored by one more run once this one is
+    /// shown, rather than by a run per chunk.
+    fn filter(&self, keep_selection: bool) {
+        self.filtering.set(true);
+        let lines = self.lines.borrow().clone();
+        let query = self.entry.text().to_string();
+        let matcher = self.matcher.clone();
+        let limit = self.limit;
+        let generation = self.generation.load(Ordering::Relaxed);
+        let current = self.generation.clone();
+        let is_current = self.generation.clone();
+        let this = self.clone();
+        spawn_worker_with_updates(
+            move |tx| {
+                let stale = || current.load(Ordering::Relaxed) != generation;
+                if let Some(filtered) = filter_lines(&lines, &query, &matcher, limit, &stale) {
+                    let _ = tx.send((lines, filtered));
+                }
+            },
+            move || is_current.load(Ordering::Relaxed) == generation,
+            move |(lines, filtered): (Lines, Filtered)| {
+                this.show(&lines, &filtered, keep_selection);
+                this.filtering.set(false);
+                if this.lines_added.replace(false) {
+                    this.filter(true);
+                }
+            },
+        );
+    }
+
+    /// Replace the rows with the `filtered` lines of `lines`
+    fn show(&self, lines: &Lines, filtered: &Filtered, keep_selection: bool) {
+        let selected = self.results.selected();
+        let mut rows: Vec<glib::Object> = filtered
+            .shown
+            .iter()
+            .filter_map(|&i| lines.get(i))
+            .map(|line| CommandItem::new(CommandRow::message(line.to_string())).upcast())
+            .collect();
+        if filtered.hidden() > 0 {
+            let more = CommandRow::stats(
+                &more_lines_label(filtered.hidden()),
+                "Type to narrow the list",
+            );
+            rows.push(CommandItem::new(more).upcast());
+        }
+        self.results.replace_all(&rows);
+        let n = self.results.n_items();
+        if keep_selection && selected < n {
+            self.results.set_selected(selected);
+        } else if n > 0 {
+            self.results.set_selected(0);
+        }
+    }
+
+    /// Add a chunk of lines read from standard input
+    ///
+    /// The rows are refreshed for the longer input without moving the
+    /// selection, which the user may be moving meanwhile.
+    fn append(&self, chunk: Vec<String>) {
+        self.lines.borrow_mut().push(chunk);
+        if self.entry.text().is_empty() {
+            self.show_input_order(true);
+        } else if self.filtering.get() {
+            self.lines_added.set(true);
+        } else {
+            self.filter(true);
+        }
+    }
+
+    /// Move the selection `delta` rows, staying on the list
+    fn step(&self, delta: i64) {
+        let n = i64::from(self.results.n_items());
+        if n == 0 {
+            return;
+        }
+        let selected = i64::from(self.results.selected());
+        let pos = u32::try_from((selected + delta).clamp(0, n - 1)).unwrap_or(0);
+        self.results.set_selected(pos);
+        self.list_view
+            .scroll_to(pos, gtk4::ListScrollFlags::NONE, None);
+    }
+
+    /// The selected line, or the typed text when no line is selected
+    fn choice(&self) -> Option<String> {
+        let selected = self
+            .results
+            .selected_item()
+            .and_downcast::<CommandItem>()
+            .filter(|item| item.kind() == CommandKind::Message);
+        match selected {
+            Some(item) => Some(item.name()),
+            None if self.results.n_items() == 0 => {
+                Some(self.entry.text().to_string()).filter(|text| !text.is_empty())
+            }
+            None => None,
+        }
+    }
+}
+
+/// Build and show the dmenu window, reading standard input into it
+///
+/// The chosen line is stored in `choice` before the window closes.
+pub fn build_dmenu_window(app: &Application, cfg: &Config, choice: &DmenuChoice) {
+    let window = ApplicationWindow::builder()
+        .application(app)
+        .title("grunner")
+        .default_width(cfg.window_width)
+        .default_height(cfg.window_height)
+        .decorated(false)
+        .resizable(false)
+        .build();
+    window.set_css_classes(&["launcher-window"]);
+    window.connect_realize(|w| w.remove_css_class("background"));
+
+    let provider = CssProvider::new();
+    provider.load_from_string(include_str!("style.css"));
+    gtk4::style_context_add_provider_for_display(
+        &WidgetExt::display(&window),
+        &provider,
+        gtk4::STYLE_PROVIDER_PRIORITY_APPLICATION,
+    );
+
+    let entry = SearchEntry::builder()
+        .placeholder_text("Filter lines…")
+        .hexpand(true)
+        .margin_start(12)
+        .margin_end(12)
+        .margin_top(12)
+        .build();
+    entry.add_css_class("search-entry");
+
+    let results = MainThreadModel::new();
+    let factory = crate::ui::list_factory::create_factory(
+        SearchState::new(),
+        Rc::default(),
+        Rc::new(Cell::new(false)),
+        Rc::new(Cell::new(false)),
+        crate::app_origin::AppOrigins::default(),
+        ListAnimations::new(false),
+    );
+    let list_view = ListView::new(Some(results.selection_model().clone()), Some(factory));
+    list_view.add_css_class("app-list");
+    list_view.set_can_focus(false);
+    let scrolled = ScrolledWindow::builder()
+        .vexpand(true)
+        .hscrollbar_policy(PolicyType::Never)
+        .vscrollbar_policy(PolicyType::Automatic)
+        .child(&list_view)
+        .build();
+    scrolled.add_css_class("results-scroll");
+
+    let root = GtkBox::new(Orientation::Vertical, 0);
+    root.add_css_class("launcher-box");
+    root.append(&entry);
+    root.append(&scrolled);
+    let toast_overlay = ToastOverlay::new();
+    toast_overlay.set_child(Some(&root));
+    set_window_content(&window, &toast_overlay);
+
+    let dmenu = Dmenu {
+        lines: Rc::default(),
+        results,
+        entry: entry.clone(),
+        list_view: list_view.clone(),
+        matcher: Arc::new(build_matcher(cfg.fuzzy_case, cfg.fuzzy_element_limit)),
+        limit: cfg.max_results.max(1),
+        generation: Arc::default(),
+        filtering: Rc::default(),
+        lines_added: Rc::default(),
+    };
+
+    let pick = {
+        let dmenu = dmenu.clone();
+        let choice = choice.clone();
+        let window = window.downgrade();
+        move || {
+            let Some(picked) = dmenu.choice() else {
+                return;
+            };
+            debug!("Picked {picked:?}");
+            *choice.borrow_mut() = Some(picked);
+            if let Some(window) = window.upgrade() {
+                window.close();
+            }
+        }
+    };
+    let pick = Rc::new(pick);
+    let on_activate = pick.clone();
+    entry.connect_activate(move |_| on_activate());
+    list_view.connect_activate(move |_, _| pick());
+
+    let on_change = dmenu.clone();
+    entry.connect_search_changed(move |_| on_change.refresh());
+
+    let keys = EventControllerKey::new();
+    keys.set_propagation_phase(gtk4::PropagationPhase::Capture);
+    let on_key = dmenu.clone();
+    let weak = window.downgrade();
+    keys.connect_key_pressed(move |_, key, _, _| {
+        use gtk4::gdk::Key;
+        match key {
+            Key::Escape => {
+                if let Some(window) = weak.upgrade() {
+                    window.close();
+                }
+            }
+            Key::Up | Key::KP_Up => on_key.step(-1),
+            Key::Down | Key::KP_Down => on_key.step(1),
+            _ => return glib::Propagation::Proceed,
+        }
+        glib::Propagation::Stop
+    });
+    window.add_controller(keys);
+
+    // Lines are wanted until the window closes; the reader stops at its
+    // next chunk once the updates are dropped
+    let open = Rc::new(Cell::new(true));
+    let first = dmenu.limit;
+    let on_chunk = dmenu.clone();
+    let wanted = open.clone();
+    spawn_worker_with_updates(
+        move |tx| {
+            let read = read_chunks(std::io::stdin().lock(), first, CHUNK_LINES, |chunk| {
+                tx.send(chunk).is_ok()
+            });
+            info!("Read {read} lines from standard input");
+        },
+        move || wanted.get(),
+        move |chunk| on_chunk.append(chunk),
+    );
+    window.connect_destroy(move |_| open.set(false));
+
+    window.present();
+    entry.grab_focus();
+}
@@ -0,0 +1,156 @@
+//! Themed icon choices of the fixed buttons
+//!
+//! The power bar buttons and the Obsidian mode icon each take the first of a
+//! few icon names the theme provides. Probing the theme can hit the disk, so
+//! the choice is made once per process and kept until the icon theme emits
+//! `changed`.
+
+use gtk4::IconTheme;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+/// A fixed icon and the names it may have in a theme
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IconKind {
+    Settings,
+    Suspend,
+    Restart,
+    PowerOff,
+    LogOut,
+    Obsidian,
+}
+
+impl IconKind {
+    /// Icon names to try, in order of preference
+    #[must_use]
+    pub fn candidates(self) -> &'static [&'static str] {
+        match self {
+            Self::Settings => &["preferences-system", "emblem-system", "settings-configure"],
+            // The last names are fallbacks from other contexts
+            Self::Suspend => &[
+                "system-suspend",
+                "system-suspend-hibernate",
+                "media-playback-pause",
+            ],
+            Self::Restart => &["system-restart", "system-reboot", "view-refresh"],
+            Self::PowerOff => &["system-shutdown", "system-power-off"],
+            Self::LogOut => &["system-log-out", "application-exit"],
+            Self::Obsidian => &["obsidian", "md.obsidian.Obsidian", "text-x-markdown"],
+        }
+    }
+}
+
+/// Icon names chosen per kind, until the theme changes
+#[derive(Debug, Default)]
+pub struct IconMemo {
+    chosen: HashMap<IconKind, Option<&'static str>>,
+}
+
+impl IconMemo {
+    /// The first candidate of `kind` for which `has_icon` holds, probing only
+    /// on the first call since the last [`invalidate`](Self::invalidate)
+    pub fn resolve(
+        &mut self,
+        kind: IconKind,
+        has_icon: impl Fn(&str) -> bool,
+    ) -> Option<&'static str> {
+        *self.chosen.entry(kind).or_insert_with(|| {
+            kind.candidates()
+                .iter()
+                .copied()
+                .find(|name| has_icon(name))
+        })
+    }
+
+    /// Forget every choice, for a changed icon theme
+    pub fn invalidate(&mut self) {
+        self.chosen.clear();
+    }
+}
+
+static MEMO: OnceLock<Mutex<IconMemo>> = OnceLock::new();
+
+/// The icon name of `kind` provided by `theme`, or `None` if it has none
+///
+/// The first call also watches `theme`, so later calls probe again after
+/// the user switches icon themes.
+#[must_use]
+pub fn themed_icon(kind: IconKind, theme: &IconTheme) -> Option<&'static str> {
+    let memo = MEMO.get_or_init(|| {
+        theme.connect_changed(|_| {
+            if let Some(memo) = MEMO.get() {
+                memo.lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner)
+                    .invalidate();
+            }
+        });
+        Mutex::new(IconMemo::default())
+    });
+    memo.lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .resolve(kind, |name| theme.has_icon(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_resolve_probes_once_until_invalidated() {
+        let probes = Cell::new(0);
+        let theme_a = |name: &str| {
+            probes.set(probes.get() + 1);
+            name == "system-reboot"
+        };
+        let mut memo = IconMemo::default();
+
+        assert_eq!(
+            memo.resolve(IconKind::Restart, theme_a),
+            Some("system-reboot")
+        );
+        assert_eq!(probes.get(), 2);
+        assert_eq!(
+            memo.resolve(IconKind::Restart, theme_a),
+            Some("system-reboot")
+        );
+        assert_eq!(probes.get(), 2);
+
+        // Another theme is only seen after invalidation
+        let theme_b = |name: &str| name == "view-refresh";
+        assert_eq!(
+            memo.resolve(IconKind::Restart, theme_b),
+            Some("system-reboot")
+        );
+        memo.invalidate();
+        assert_eq!(
+            memo.resolve(IconKind::Restart, theme_b),
+            Some("view-refresh")
+        );
+    }
+
+    #[test]
+    fn test_resolve_remembers_missing_icons() {
+        let probes = Cell::new(0);
+        let empty_theme = |_: &str| {
+            probes.set(probes.get() + 1);
+            false
+        };
+        let mut memo = IconMemo::default();
+
+        assert_eq!(memo.resolve(IconKind::PowerOff, empty_theme), None);
+        assert_eq!(memo.resolve(IconKind::PowerOff, empty_theme), None);
+        assert_eq!(probes.get(), IconKind::PowerOff.candidates().len());
+    }
+
+    #[test]
+    fn test_resolve_keeps_kinds_apart() {
+        let mut memo = IconMemo::default();
+        let theme = |_: &str| true;
+        assert_eq!(
+            memo.resolve(IconKind::Settings, theme),
+            Some("preferences-system")
+        );
+        assert_eq!(memo.resolve(IconKind::Obsidian, theme), Some("obsidian"));
+    }
+}
@@ -0,0 +1,58 @@
+//! Opening the launcher as a layer-shell overlay
+//!
+//! On Wayland compositors with the `wlr-layer-shell` protocol (Sway,
+//! Hyprland, river, ...) a normal toplevel is subject to tiling rules. With
+//! `[window] layer_shell = true` the window instead becomes an overlay layer
+//! surface above all windows, with exclusive keyboard focus, placed by
+//! `[window] anchor` and `margin`, as rofi and fuzzel do.
+//!
+//! This needs the `layer-shell` cargo feature and the gtk4-layer-shell
+//! library. Without either, or on a compositor without the protocol (GNOME
+//! Shell, X11), the launcher stays a normal window.
+
+use crate::core::config::Config;
+use crate::ui::compat::ApplicationWindow;
+
+/// Namespace of the layer surface, for compositor rules such as Hyprland's
+/// `layerrule`
+#[cfg(feature = "layer-shell")]
+const NAMESPACE: &str = "grunner";
+
+/// Make `window` a layer-shell overlay if `cfg` asks for it and the
+/// compositor supports it
+///
+/// Must be called before the window is first shown.
+#[cfg(feature = "layer-shell")]
+pub fn init(window: &ApplicationWindow, cfg: &Config) {
+    use gtk4_layer_shell::{Edge, KeyboardMode, Layer, LayerShell};
+
+    if !cfg.layer_shell {
+        return;
+    }
+    if !gtk4_layer_shell::is_supported() {
+        log::info!("The compositor has no layer-shell support, opening a normal window");
+        return;
+    }
+    window.init_layer_shell();
+    window.set_namespace(Some(NAMESPACE));
+    window.set_layer(Layer::Overlay);
+    window.set_keyboard_mode(KeyboardMode::Exclusive);
+    let edges = [Edge::Top, Edge::Bottom, Edge::Left, Edge::Right];
+    for (edge, anchored) in edges.into_iter().zip(cfg.layer_anchor.edges()) {
+        window.set_anchor(edge, anchored);
+        window.set_margin(edge, if anchored { cfg.layer_margin } else { 0 });
+    }
+    log::info!(
+        "Opening as a layer-shell overlay ({:?}, margin {})",
+        cfg.layer_anchor,
+        cfg.layer_margin
+    );
+}
+
+/// Without the `layer-shell` feature the window always stays a normal one
+#[cfg(not(feature = "layer-shell"))]
+pub fn init(_window: &ApplicationWindow, cfg: &Config) {
+    if cfg.layer_shell {
+        log::warn!("Built without the layer-shell feature, opening a normal window");
+    }
+}
@@ -4,20 +4,46 @@
 //! It handles the creation of GTK factories and the binding of data
 //! to list items based on their type.
 
-use crate::app_mode::ActiveMode;
-use crate::model::items::{AppItem, CommandItem, ObsidianActionItem, SearchResultItem};
-use crate::ui::result_row::ResultRow;
-use crate::utils::{contract_home, get_file_icon, is_calculator_result};
+use crate::app_origin::{AppOrigin, AppOrigins};
+use crate::command_handler::parse_colon_command;
+use crate::command_safety::CommandGuard;
+use crate::core::config::FileResultsLayout;
+use crate::model::command_row::{CommandKind, CommandPayload, CommandRow};
+use crate::model::items::{
+    AppActionItem, AppItem, BookmarkItem, ClipboardItem, ColonCommandItem, CommandItem, EmojiItem,
+    GrunnerActionItem, ManPageItem, ObsidianActionItem, PassEntryItem, ProcessItem, ProjectItem,
+    SearchResultItem, SectionHeaderItem, SnippetItem, SshHostItem, SuggestionItem,
+    TerminalCommandItem, UnitItem, UrlItem, VaultSetupItem, WindowItem,
+};
+use crate::model::search_state::SearchState;
+use crate::ui::animation::ListAnimations;
+use crate::ui::direction::{DescKind, desc_ellipsize, widget_is_rtl};
+use crate::ui::icons::{IconKind, themed_icon};
+use crate::ui::result_row::{DESC_MAX_WIDTH_CHARS, ResultRow};
+use crate::ui::row_kind::RowKind;
+use crate::ui::severity::{Severity, set_icon_severity};
+use crate::utils::get_file_icon;
+use gtk4::pango;
 use gtk4::prelude::*;
 use gtk4::{Image, Label, ListItem, SignalListItemFactory, Widget};
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// Longest path shown in a single-line row before leading folders are dropped
+const SINGLE_LINE_MAX_PATH_CHARS: usize = 60;
 
 /// Context for binding list items, containing all necessary data
 pub struct BindContext<'a> {
     pub image: &'a Image,
     pub name_label: &'a Label,
     pub desc_label: &'a Label,
-    pub mode: ActiveMode,
-    pub vault_path: Option<&'a str>,
+    /// Search term of the current colon command, used for match highlighting
+    pub term: Option<&'a str>,
+    /// Whether the row is laid out right-to-left
+    pub rtl: bool,
+    /// Layout of file and grep rows
+    pub layout: FileResultsLayout,
 }
 
 impl<'a> BindContext<'a> {
@@ -26,178 +52,238 @@ impl<'a> BindContext<'a> {
         image: &'a Image,
         name_label: &'a Label,
         desc_label: &'a Label,
-        mode: ActiveMode,
-        vault_path: Option<&'a str>,
+        term: Option<&'a str>,
+        rtl: bool,
     ) -> Self {
         Self {
             image,
             name_label,
             desc_label,
-            mode,
-            vault_path,
+            term,
+            rtl,
+            layout: FileResultsLayout::default(),
         }
     }
-}
 
-/// Trait for binding strategies
-///
-/// Each strategy handles binding a specific type of item to the UI widgets.
-/// This allows for easy extension and testing of individual binding behaviors.
-pub trait BindStrategy: Sync {
-    fn matches(&self, ctx: &BindContext, line: &str) -> bool;
-    fn bind(&self, ctx: &BindContext, line: &str);
-}
-
-/// Strategy for calculator results
-struct CalculatorBinder;
-
-impl BindStrategy for CalculatorBinder {
-    fn matches(&self, _ctx: &BindContext, line: &str) -> bool {
-        is_calculator_result(line)
+    /// Use `layout` for file and grep rows
+    #[must_use]
+    pub fn with_layout(mut self, layout: FileResultsLayout) -> Self {
+        self.layout = layout;
+        self
     }
 
-    fn bind(&self, ctx: &BindContext, line: &str) {
-        ctx.image.set_icon_name(Some("accessories-calculator"));
-        if let Some((_expr, result)) = line.split_once('=') {
-            ctx.name_label.set_text(result.trim());
-            set_desc(ctx.desc_label, &format!("Calc: {line}"));
-        } else {
-            ctx.name_label.set_text(line);
-            set_desc(ctx.desc_label, "Calculator result");
-        }
+    fn single_line(&self) -> bool {
+        self.layout == FileResultsLayout::SingleLine
     }
 }
 
-/// Strategy for shell command results (`CustomScript` mode)
-struct ShellCommandBinder;
-
-impl BindStrategy for ShellCommandBinder {
-    fn matches(&self, ctx: &BindContext, line: &str) -> bool {
-        ctx.mode == ActiveMode::CustomScript || line.starts_with("Run: ")
-    }
-
-    fn bind(&self, ctx: &BindContext, line: &str) {
-        ctx.image.set_icon_name(Some("utilities-terminal"));
-        if let Some((name, command)) = line.split_once(" | ") {
-            ctx.name_label.set_text(name.trim());
-            set_desc(ctx.desc_label, command.trim());
-        } else if let Some(stripped) = line.strip_prefix("Run: ") {
-            ctx.name_label.set_text("Run command");
-            set_desc(ctx.desc_label, stripped);
-        } else {
-            ctx.name_label.set_text(line);
-            set_desc(ctx.desc_label, "");
-        }
+/// Icon of a command row that is not a file
+fn command_icon(kind: CommandKind) -> &'static str {
+    match kind {
+        CommandKind::Calculator => "accessories-calculator",
+        CommandKind::Shell => "utilities-terminal",
+        CommandKind::Stats => "utilities-system-monitor",
+        CommandKind::File | CommandKind::Note | CommandKind::Message => "system-search",
     }
 }
 
-/// Strategy for grep results (`file:line:content` format)
-struct GrepResultBinder;
-
-impl BindStrategy for GrepResultBinder {
-    fn matches(&self, ctx: &BindContext, line: &str) -> bool {
-        ctx.mode == ActiveMode::ObsidianGrep
-            || (ctx.mode == ActiveMode::None
-                && (line.contains(':') && !line.starts_with('/')
-                    || line.starts_with('/') && line.matches(':').count() >= 2))
+/// Bind a file, note or grep match row
+///
+/// Grep matches show `line:text` below the file name, or `path:line — text`
+/// on one line, with the search term emboldened.
+fn bind_file_row(ctx: &BindContext, row: &CommandRow) {
+    match (&row.payload, row.kind) {
+        (CommandPayload::Path(_), CommandKind::Note) => {
+            ctx.image.set_icon_name(Some("text-markdown"));
+        }
+        (payload, _) => {
+            let path = payload
+                .path()
+                .map(|p| p.to_string_lossy())
+                .unwrap_or_default();
+            ctx.image.set_from_gicon(&get_file_icon(&path));
+        }
     }
 
-    fn bind(&self, ctx: &BindContext, line: &str) {
-        if let Some((file_path, rest)) = line.split_once(':') {
-            ctx.image.set_from_gicon(&get_file_icon(file_path));
-
-            let display_path = if ctx.mode == ActiveMode::ObsidianGrep {
-                relative_to_vault(file_path, ctx.vault_path)
-            } else {
-                file_path
-            };
-            let filename = std::path::Path::new(display_path)
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or(display_path);
-            ctx.name_label.set_text(filename);
-            set_desc(ctx.desc_label, rest);
+    let CommandPayload::PathLine { line, column, .. } = row.payload else {
+        if ctx.single_line() {
+            let (text, _) = single_line_text(&row.location, None, "", SINGLE_LINE_MAX_PATH_CHARS);
+            set_single_line(ctx, &text);
         } else {
-            ctx.image.set_icon_name(Some("text-markdown"));
-            ctx.name_label.set_text(line);
-            set_desc(ctx.desc_label, "");
+            ctx.name_label.set_text(&row.name);
+            set_path_desc(ctx, &row.description);
+        }
+        return;
+    };
+
+    let matched = row.matched_text();
+    // Highlight the match using rg's byte column (Pango indices are bytes too)
+    let range = column
+        .zip(ctx.term)
+        .and_then(|(column, term)| match_byte_range(matched, column, term));
+    if ctx.single_line() {
+        let (text, text_start) = single_line_text(
+            &row.location,
+            Some(line),
+            matched,
+            SINGLE_LINE_MAX_PATH_CHARS,
+        );
+        set_single_line(ctx, &text);
+        if let Some((start, end)) = range {
+            highlight_range(ctx.name_label, text_start + start, text_start + end);
+        }
+    } else {
+        ctx.name_label.set_text(&row.name);
+        set_desc(ctx.desc_label, &row.description);
+        let prefix = row.description.len() - matched.len();
+        if let Some((start, end)) = range {
+            highlight_range(ctx.desc_label, prefix + start, prefix + end);
         }
     }
 }
 
-/// Strategy for Obsidian file paths
-struct ObsidianFileBinder;
-
-impl BindStrategy for ObsidianFileBinder {
-    fn matches(&self, ctx: &BindContext, line: &str) -> bool {
-        ctx.mode == ActiveMode::ObsidianFile && line.starts_with('/')
+/// Text of a single-line file or grep row: `path:line — text`
+///
+/// The line number and the text are left out when missing. Paths longer
+/// than `max_path_chars` lose their leading folders, so the file name and
+/// line number stay ahead of the text, which the label ellipsizes at its
+/// end. Returns the text and the byte offset at which `text` starts in it.
+fn single_line_text(
+    path: &str,
+    line: Option<u32>,
+    text: &str,
+    max_path_chars: usize,
+) -> (String, usize) {
+    let mut out = shorten_path(path, max_path_chars).into_owned();
+    if let Some(line) = line {
+        out.push_str(&format!(":{line}"));
     }
+    if !text.is_empty() {
+        out.push_str(" — ");
+    }
+    let text_start = out.len();
+    out.push_str(text);
+    (out, text_start)
+}
 
-    fn bind(&self, ctx: &BindContext, line: &str) {
-        ctx.image.set_icon_name(Some("text-markdown"));
-
-        let (filename, _parent) = extract_filename_and_parent(line);
-        ctx.name_label.set_text(filename);
-        let relative = relative_to_vault(line, ctx.vault_path);
-        let parent = std::path::Path::new(relative)
-            .parent()
-            .and_then(|p| p.to_str())
-            .filter(|s| !s.is_empty())
-            .or_else(|| std::path::Path::new(line).parent().and_then(|p| p.to_str()));
-        set_desc(ctx.desc_label, parent.unwrap_or(""));
+/// Keep the end of `path` within `max_chars` characters, marking the cut with `…`
+///
+/// The cut is moved to the next folder boundary when there is one, so no
+/// folder name is shown half.
+fn shorten_path(path: &str, max_chars: usize) -> Cow<'_, str> {
+    let count = path.chars().count();
+    if count <= max_chars {
+        return Cow::Borrowed(path);
     }
+    let skip = count - max_chars.saturating_sub(1);
+    let tail_start = path.char_indices().nth(skip).map_or(path.len(), |(i, _)| i);
+    let tail = &path[tail_start..];
+    let tail = tail.find('/').map_or(tail, |i| &tail[i..]);
+    Cow::Owned(format!("…{tail}"))
 }
 
-/// Strategy for generic file paths
-struct FilePathBinder;
+/// Show `text` as the only line of a row
+///
+/// The name label takes the description's width and ellipsizes, and the
+/// description is hidden.
+fn set_single_line(ctx: &BindContext, text: &str) {
+    ctx.name_label.set_text(text);
+    ctx.name_label.set_ellipsize(pango::EllipsizeMode::End);
+    ctx.name_label.set_max_width_chars(DESC_MAX_WIDTH_CHARS);
+    set_desc(ctx.desc_label, "");
+}
 
-impl BindStrategy for FilePathBinder {
-    fn matches(&self, ctx: &BindContext, line: &str) -> bool {
-        ctx.mode != ActiveMode::ObsidianFile && line.starts_with('/')
+/// Byte range of a grep match within `text`
+///
+/// `column` is the 1-based byte offset reported by `rg --column`. The range
+/// spans as many characters as `term` has, and is only returned if the text
+/// there actually equals `term` (ignoring case), so regex patterns and
+/// misparsed columns are simply not highlighted.
+fn match_byte_range(text: &str, column: u32, term: &str) -> Option<(usize, usize)> {
+    let start = usize::try_from(column).ok()?.checked_sub(1)?;
+    if term.is_empty() || start >= text.len() || !text.is_char_boundary(start) {
+        return None;
     }
 
-    fn bind(&self, ctx: &BindContext, line: &str) {
-        ctx.image.set_from_gicon(&get_file_icon(line));
+    let char_count = term.chars().count();
+    let end = text[start..]
+        .char_indices()
+        .nth(char_count)
+        .map_or(text.len(), |(i, _)| start + i);
 
-        let (filename, parent) = extract_filename_and_parent(line);
-        ctx.name_label.set_text(filename);
-
-        let display_parent = if parent.is_empty() {
-            String::new()
-        } else {
-            contract_home(std::path::Path::new(parent))
-        };
-        set_desc(ctx.desc_label, &display_parent);
-    }
+    (text[start..end].to_lowercase() == term.to_lowercase()).then_some((start, end))
 }
 
-/// Default strategy for generic command output
-struct DefaultBinder;
+/// Embolden the byte range `start..end` of a label's text
+fn highlight_range(label: &Label, start: usize, end: usize) {
+    let (Ok(start), Ok(end)) = (u32::try_from(start), u32::try_from(end)) else {
+        return;
+    };
+    let attrs = pango::AttrList::new();
+    let mut weight = pango::AttrInt::new_weight(pango::Weight::Bold);
+    weight.set_start_index(start);
+    weight.set_end_index(end);
+    attrs.insert(weight);
+    let mut underline = pango::AttrInt::new_underline(pango::Underline::Single);
+    underline.set_start_index(start);
+    underline.set_end_index(end);
+    attrs.insert(underline);
+    label.set_attributes(Some(&attrs));
+}
 
-impl BindStrategy for DefaultBinder {
-    fn matches(&self, _ctx: &BindContext, _line: &str) -> bool {
-        true
+/// Byte ranges of the characters at `positions` in `text`, adjacent
+/// characters merged into one range
+///
+/// `positions` are sorted character indices; those past the end of `text`
+/// are ignored.
+fn char_byte_ranges(text: &str, positions: &[usize]) -> Vec<(usize, usize)> {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    let mut wanted = positions.iter().copied().peekable();
+    for (index, (start, c)) in text.char_indices().enumerate() {
+        if wanted.peek().is_none() {
+            break;
+        }
+        if wanted.next_if_eq(&index).is_none() {
+            continue;
+        }
+        let end = start + c.len_utf8();
+        match ranges.last_mut() {
+            Some(last) if last.1 == start => last.1 = end,
+            _ => ranges.push((start, end)),
+        }
     }
+    ranges
+}
 
-    fn bind(&self, ctx: &BindContext, line: &str) {
-        ctx.image.set_icon_name(Some("system-search"));
-        ctx.name_label.set_text(line);
-        set_desc(ctx.desc_label, "");
+/// Embolden the characters at `positions` of a label's text
+///
+/// Pango attributes are used instead of markup, so names containing `&`
+/// or `<` need no escaping.
+fn highlight_chars(label: &Label, positions: &[usize]) {
+    let ranges = char_byte_ranges(label.text().as_str(), positions);
+    if ranges.is_empty() {
+        return;
     }
+    let attrs = pango::AttrList::new();
+    for (start, end) in ranges {
+        let (Ok(start), Ok(end)) = (u32::try_from(start), u32::try_from(end)) else {
+            continue;
+        };
+        let mut weight = pango::AttrInt::new_weight(pango::Weight::Bold);
+        weight.set_start_index(start);
+        weight.set_end_index(end);
+        attrs.insert(weight);
+    }
+    label.set_attributes(Some(&attrs));
 }
-static BINDERS: std::sync::OnceLock<Vec<&'static dyn BindStrategy>> = std::sync::OnceLock::new();
 
-fn get_binders() -> &'static Vec<&'static dyn BindStrategy> {
-    BINDERS.get_or_init(|| {
-        vec![
-            &CalculatorBinder,
-            &ShellCommandBinder,
-            &GrepResultBinder,
-            &ObsidianFileBinder,
-            &FilePathBinder,
-            &DefaultBinder,
-        ]
-    })
+/// Search term of a colon command query (`":fg foo"` → `"foo"`)
+fn colon_command_term(query: &str) -> Option<&str> {
+    if !query.starts_with(':') {
+        return None;
+    }
+    let (_cmd, arg) = parse_colon_command(query);
+    (!arg.is_empty()).then_some(arg)
 }
 
 /// Create a factory for the list view
@@ -212,10 +298,22 @@ fn get_binders() -> &'static Vec<&'static dyn BindStrategy> {
 ///
 /// Panics if the list item cannot be downcast to `ListItem`, or if
 /// expected child widgets are missing.
+///
+/// The search term is read from `search_state` and the file row layout
+/// from `layout` on every bind, so rows follow the current query and
+/// settings. Rows end
+/// in a badge naming their source while `show_kind_badges` is set, and app
+/// descriptions end in where the app came from, looked up through
+/// `app_origins`, while `show_app_origin` is set. Rows just added to the list
+/// fade in through `animations`.
 #[must_use]
 pub fn create_factory(
-    active_mode: ActiveMode,
-    vault_path: Option<String>,
+    search_state: SearchState,
+    layout: Rc<Cell<FileResultsLayout>>,
+    show_kind_badges: Rc<Cell<bool>>,
+    show_app_origin: Rc<Cell<bool>>,
+    app_origins: AppOrigins,
+    animations: ListAnimations,
 ) -> SignalListItemFactory {
     let factory = SignalListItemFactory::new();
 
@@ -224,7 +322,9 @@ pub fn create_factory(
         let item = item
             .downcast_ref::<ListItem>()
             .expect("Needs to be ListItem");
-        item.set_child(Some(&ResultRow::new()));
+        let row = ResultRow::new();
+        row.set_list_item(item);
+        item.set_child(Some(&row));
     });
 
     // Bind signal to populate data
@@ -243,23 +343,106 @@ pub fn create_factory(
         let name_label = row.name_label();
         let desc_label = row.desc_label();
 
+        animations.bind_row(&row, item.position());
+
+        // Provider results of a previous query are dimmed until replaced
+        if child
+            .downcast_ref::<SearchResultItem>()
+            .is_some_and(SearchResultItem::is_stale)
+        {
+            row.add_css_class("stale-result");
+        } else {
+            row.remove_css_class("stale-result");
+        }
+
+        let kind = show_kind_badges
+            .get()
+            .then(|| RowKind::of_item(&child))
+            .flatten();
+        set_kind_badge(&row, kind);
+
+        // Section headers only label the rows below them
+        let header = child.downcast_ref::<SectionHeaderItem>();
+        item.set_selectable(header.is_none());
+        item.set_activatable(header.is_none());
+        image.set_visible(header.is_none());
+        if let Some(header) = header {
+            row.add_css_class("section-header");
+            name_label.set_text(&header.title());
+            set_desc(desc_label, "");
+            return;
+        }
+        row.remove_css_class("section-header");
+
+        // Desktop actions are indented below their application
+        if let Some(action) = child.downcast_ref::<AppActionItem>() {
+            row.add_css_class("app-action");
+            bind_app_action_item(image, name_label, desc_label, action);
+            return;
+        }
+        row.remove_css_class("app-action");
+
         // Downcast to specific types and bind
         if let Some(app_item) = child.downcast_ref::<AppItem>() {
             bind_app_item(image, name_label, desc_label, app_item);
+            if show_app_origin.get() {
+                bind_app_origin(item, desc_label, app_item, &app_origins);
+            }
+            set_badge(
+                row.badge_label(),
+                app_item.running_window().map(|_| "running"),
+            );
         } else if let Some(cmd_item) = child.downcast_ref::<CommandItem>() {
-            bind_command_item(
+            let query = search_state.current_query();
+            let ctx = BindContext::new(
                 image,
                 name_label,
                 desc_label,
-                cmd_item,
-                active_mode,
-                vault_path.as_deref(),
-            );
+                colon_command_term(&query),
+                widget_is_rtl(&row),
+            )
+            .with_layout(layout.get());
+            bind_command_item(&ctx, cmd_item);
         } else if let Ok(obs_item) = child.clone().downcast::<ObsidianActionItem>() {
             bind_obsidian_item(image, name_label, desc_label, &obs_item);
         } else if let Ok(sr_item) = child.clone().downcast::<SearchResultItem>() {
             bind_search_result_item(image, name_label, desc_label, &sr_item);
+        } else if let Some(suggestion) = child.downcast_ref::<SuggestionItem>() {
+            bind_suggestion_item(image, name_label, desc_label, suggestion);
+        } else if let Some(command) = child.downcast_ref::<ColonCommandItem>() {
+            bind_colon_command_item(image, name_label, desc_label, command);
+        } else if let Some(setup) = child.downcast_ref::<VaultSetupItem>() {
+            bind_vault_setup_item(image, name_label, desc_label, setup);
+        } else if let Some(item) = child.downcast_ref::<GrunnerActionItem>() {
+            bind_grunner_action_item(image, name_label, desc_label, item);
+        } else if let Some(bookmark) = child.downcast_ref::<BookmarkItem>() {
+            bind_bookmark_item(image, name_label, desc_label, bookmark);
+        } else if let Some(entry) = child.downcast_ref::<ClipboardItem>() {
+            bind_clipboard_item(image, name_label, desc_label, entry);
+        } else if let Some(item) = child.downcast_ref::<TerminalCommandItem>() {
+            bind_terminal_command_item(image, name_label, desc_label, item);
+        } else if let Some(item) = child.downcast_ref::<UrlItem>() {
+            bind_url_item(image, name_label, desc_label, item);
+        } else if let Some(item) = child.downcast_ref::<WindowItem>() {
+            bind_window_item(image, name_label, desc_label, item);
+        } else if let Some(item) = child.downcast_ref::<EmojiItem>() {
+            bind_emoji_item(&row, item);
+        } else if let Some(item) = child.downcast_ref::<ProcessItem>() {
+            bind_process_item(image, name_label, desc_label, item);
+        } else if let Some(item) = child.downcast_ref::<ProjectItem>() {
+            bind_project_item(image, name_label, desc_label, item);
+        } else if let Some(item) = child.downcast_ref::<SshHostItem>() {
+            bind_ssh_host_item(image, name_label, desc_label, item);
+        } else if let Some(item) = child.downcast_ref::<UnitItem>() {
+            bind_unit_item(image, name_label, desc_label, item);
+        } else if let Some(item) = child.downcast_ref::<ManPageItem>() {
+            bind_man_page_item(image, name_label, desc_label, item);
+        } else if let Some(item) = child.downcast_ref::<SnippetItem>() {
+            bind_snippet_item(image, name_label, desc_label, item);
+        } else if let Some(item) = child.downcast_ref::<PassEntryItem>() {
+            bind_pass_entry_item(image, name_label, desc_label, item);
         }
+        set_icon_severity(image, Severity::of_item(&child));
     });
 
     // Unbind signal to clean up data without destroying widgets
@@ -268,9 +451,24 @@ pub fn create_factory(
             .downcast_ref::<ListItem>()
             .expect("Needs to be ListItem");
         if let Some(row) = item.child().and_then(|c| c.downcast::<ResultRow>().ok()) {
+            set_icon_severity(row.image(), Severity::Normal);
             row.image().clear();
+            row.glyph_label().set_text("");
+            row.glyph_label().set_visible(false);
             row.name_label().set_text("");
+            // Undo the single-line layout of file rows
+            row.name_label().set_attributes(None);
+            row.name_label().set_ellipsize(pango::EllipsizeMode::None);
+            row.name_label().set_max_width_chars(-1);
             row.desc_label().set_text("");
+            row.desc_label().set_attributes(None);
+            set_badge(row.badge_label(), None);
+            set_kind_badge(&row, None);
+            row.quick_key_label().set_visible(false);
+            row.remove_css_class("stale-result");
+            row.remove_css_class("section-header");
+            row.remove_css_class("app-action");
+            ListAnimations::unbind_row(&row);
         }
     });
 
@@ -287,22 +485,107 @@ pub fn create_factory(
 
 /// Bind an application item to the list widget
 fn bind_app_item(image: &Image, name_label: &Label, desc_label: &Label, app_item: &AppItem) {
-    // Set icon
-    let icon = app_item.icon();
+    set_app_icon(image, &app_item.icon());
+
+    // Set name and description, emboldening what the query matched
+    let (name_matches, description_matches) = app_item.match_positions();
+    name_label.set_text(&app_item.name());
+    name_label.set_attributes(None);
+    highlight_chars(name_label, &name_matches);
+    set_desc(desc_label, &app_item.description());
+    highlight_chars(desc_label, &description_matches);
+}
+
+/// End the description of an app row in where the app came from
+///
+/// An origin still being looked up is added once it is known, if `item`
+/// then still shows the same desktop file.
+fn bind_app_origin(item: &ListItem, desc_label: &Label, app_item: &AppItem, origins: &AppOrigins) {
+    let path = app_item.path();
+    let weak_item = item.downgrade();
+    let weak_label = desc_label.downgrade();
+    let expected = path.clone();
+    let known = origins.get_or_resolve(&path, move |origin| {
+        let (Some(item), Some(label)) = (weak_item.upgrade(), weak_label.upgrade()) else {
+            return;
+        };
+        if let Some(app_item) = item.item().and_downcast::<AppItem>()
+            && app_item.path() == expected
+        {
+            show_app_origin(&label, &app_item, origin);
+        }
+    });
+    if let Some(origin) = known {
+        show_app_origin(desc_label, app_item, &origin);
+    }
+}
+
+/// Set the description of an app row with its origin appended in a dimmer
+/// colour, keeping the highlighted matches
+fn show_app_origin(label: &Label, app_item: &AppItem, origin: &AppOrigin) {
+    let description = app_item.description();
+    let origin = origin.label();
+    let (text, suffix_start) = if description.is_empty() {
+        (origin, 0)
+    } else {
+        let text = format!("{description} · {origin}");
+        (text, description.len())
+    };
+    set_desc(label, &text);
+    label.set_attributes(None);
+    highlight_chars(label, &app_item.match_positions().1);
+    let attrs = label.attributes().unwrap_or_default();
+    if let (Ok(start), Ok(end)) = (u32::try_from(suffix_start), u32::try_from(text.len())) {
+        let mut dim = pango::AttrInt::new_foreground_alpha(u16::MAX / 2);
+        dim.set_start_index(start);
+        dim.set_end_index(end);
+        attrs.insert(dim);
+    }
+    label.set_attributes(Some(&attrs));
+}
+
+/// Bind a desktop action: its name, then the application it belongs to
+fn bind_app_action_item(
+    image: &Image,
+    name_label: &Label,
+    desc_label: &Label,
+    action: &AppActionItem,
+) {
+    set_app_icon(image, &action.icon());
+    name_label.set_text(&action.name());
+    set_desc(desc_label, &action.app_name());
+}
+
+/// Show `text` in the row's badge, or hide the badge
+fn set_badge(label: &Label, text: Option<&str>) {
+    label.set_text(text.unwrap_or(""));
+    label.set_visible(text.is_some());
+}
+
+/// Show the source of the row in its kind badge and accessible
+/// description, or hide the badge
+fn set_kind_badge(row: &ResultRow, kind: Option<RowKind>) {
+    set_badge(row.kind_label(), kind.map(RowKind::badge));
+    match kind {
+        Some(kind) => {
+            row.update_property(&[gtk4::accessible::Property::Description(kind.description())]);
+        }
+        None => row.reset_property(gtk4::AccessibleProperty::Description),
+    }
+}
+
+/// Show an application icon given as a themed name or an absolute path
+fn set_app_icon(image: &Image, icon: &str) {
     if icon.is_empty() {
         // Default executable icon for apps without specified icon
         image.set_icon_name(Some("application-x-executable"));
     } else if icon.starts_with('/') {
         // Absolute path to icon file
-        image.set_from_file(Some(&icon));
+        image.set_from_file(Some(icon));
     } else {
         // Themed icon name
-        image.set_icon_name(Some(&icon));
+        image.set_icon_name(Some(icon));
     }
-
-    // Set name and description
-    name_label.set_text(&app_item.name());
-    set_desc(desc_label, &app_item.description());
 }
 
 /// Set description label text with visibility handling
@@ -310,50 +593,62 @@ fn bind_app_item(image: &Image, name_label: &Label, desc_label: &Label, app_item
 /// Shows the label only if text is non-empty, hiding it completely
 /// when there's no description to avoid empty space in the UI.
 fn set_desc(label: &Label, text: &str) {
+    label.set_attributes(None);
+    // Rows are recycled, so undo the start ellipsis of path descriptions
+    label.set_ellipsize(desc_ellipsize(DescKind::Text, false));
     let visible = !text.is_empty();
     label.set_visible(visible);
     label.set_text(if visible { text } else { "" });
 }
 
-/// Convert absolute file path to vault-relative path for display
+/// Set a description that is a folder path
 ///
-/// Strips the vault path prefix from absolute paths to show cleaner
-/// relative paths in the UI when displaying Obsidian search results.
-fn relative_to_vault<'a>(path: &'a str, vault: Option<&str>) -> &'a str {
-    vault
-        .and_then(|v| path.strip_prefix(v))
-        .map_or(path, |s| s.trim_start_matches('/'))
-}
-
-/// Extract filename and parent directory from a path
-fn extract_filename_and_parent(path: &str) -> (&str, &str) {
-    let filename = std::path::Path::new(path)
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or(path);
-    let parent = std::path::Path::new(path)
-        .parent()
-        .and_then(|p| p.to_str())
-        .unwrap_or("");
-    (filename, parent)
-}
-
-/// Bind a command item to the list widget using strategy pattern
-fn bind_command_item(
-    image: &Image,
-    name_label: &Label,
-    desc_label: &Label,
-    cmd_item: &CommandItem,
-    mode: ActiveMode,
-    vault_path: Option<&str>,
-) {
-    let line = cmd_item.line();
-    let ctx = BindContext::new(image, name_label, desc_label, mode, vault_path);
+/// In right-to-left rows the path is ellipsized at its start so the folder
+/// names closest to the file stay visible.
+fn set_path_desc(ctx: &BindContext, text: &str) {
+    set_desc(ctx.desc_label, text);
+    ctx.desc_label
+        .set_ellipsize(desc_ellipsize(DescKind::Path, ctx.rtl));
+}
 
-    for strategy in get_binders() {
-        if strategy.matches(&ctx, &line) {
-            strategy.bind(&ctx, &line);
-            return;
+/// Bind a command row, as built where it was produced
+fn bind_command_item(ctx: &BindContext, cmd_item: &CommandItem) {
+    let row = cmd_item.row();
+    match row.kind {
+        CommandKind::File | CommandKind::Note => bind_file_row(ctx, &row),
+        kind => {
+            ctx.image.set_icon_name(Some(command_icon(kind)));
+            ctx.name_label.set_text(&row.name);
+            set_desc(ctx.desc_label, &row.description);
+        }
+    }
+    bind_command_guard(ctx, cmd_item);
+}
+
+/// Mark a `:sh` command that looks destructive, see [`crate::command_safety`]
+fn bind_command_guard(ctx: &BindContext, cmd_item: &CommandItem) {
+    let command = || cmd_item.payload().text().unwrap_or_default().to_string();
+    match cmd_item.guard() {
+        CommandGuard::Run => {}
+        CommandGuard::Blocked(operation) => {
+            ctx.image.set_icon_name(Some("dialog-warning"));
+            set_desc(
+                ctx.desc_label,
+                &format!(
+                    "Blocked: {} uses {operation}; set dangerous = true to allow it",
+                    command()
+                ),
+            );
+        }
+        CommandGuard::Confirm(operation) => {
+            ctx.image.set_icon_name(Some("dialog-warning"));
+            set_desc(ctx.desc_label, &format!("{} — uses {operation}", command()));
+        }
+        CommandGuard::Confirmed => {
+            ctx.image.set_icon_name(Some("dialog-warning"));
+            let name = ctx.name_label.text();
+            ctx.name_label
+                .set_text(&format!("Press Enter again to run {name}"));
         }
     }
 }
@@ -384,6 +679,203 @@ fn bind_obsidian_item(
     set_desc(desc_label, "");
 }
 
+/// Bind a "Did you mean …?" suggestion to the list widget
+fn bind_suggestion_item(
+    image: &Image,
+    name_label: &Label,
+    desc_label: &Label,
+    suggestion: &SuggestionItem,
+) {
+    image.set_icon_name(Some("tools-check-spelling"));
+    name_label.set_text(&format!("Did you mean {}?", suggestion.label()));
+    set_desc(desc_label, &suggestion.description());
+}
+
+/// Bind a row of the colon-command palette: the mode's icon, the command
+/// and what it does
+fn bind_colon_command_item(
+    image: &Image,
+    name_label: &Label,
+    desc_label: &Label,
+    command: &ColonCommandItem,
+) {
+    let theme = gtk4::IconTheme::for_display(&image.display());
+    let obsidian = themed_icon(IconKind::Obsidian, &theme).unwrap_or("text-x-markdown");
+    match command.mode().icon_name(obsidian) {
+        Some(icon) => image.set_icon_name(Some(icon)),
+        None => set_app_icon(image, &command.icon()),
+    }
+    name_label.set_text(&format!(":{}", command.name()));
+    set_desc(desc_label, &command.description());
+}
+
+/// Bind a "create the missing vault" row to the list widget
+fn bind_vault_setup_item(
+    image: &Image,
+    name_label: &Label,
+    desc_label: &Label,
+    setup: &VaultSetupItem,
+) {
+    image.set_icon_name(Some("folder-new"));
+    name_label.set_text(&format!(
+        "Vault {} does not exist — press Enter to create it",
+        setup.vault()
+    ));
+    set_desc(
+        desc_label,
+        "Creates the vault with its daily and new-note folders",
+    );
+}
+
+/// Bind a `:grunner` row: the setting and whether it is on
+fn bind_grunner_action_item(
+    image: &Image,
+    name_label: &Label,
+    desc_label: &Label,
+    item: &GrunnerActionItem,
+) {
+    let action = item.action();
+    image.set_icon_name(Some(action.icon()));
+    name_label.set_text(action.title());
+    set_desc(desc_label, action.description(item.on()));
+}
+
+/// Bind a browser bookmark to the list widget
+fn bind_bookmark_item(image: &Image, name_label: &Label, desc_label: &Label, item: &BookmarkItem) {
+    image.set_icon_name(Some("user-bookmarks"));
+    name_label.set_text(&item.title());
+    set_desc(desc_label, &item.url());
+}
+
+/// Bind the "Open in browser" row of a URL query, or a web search row
+fn bind_url_item(image: &Image, name_label: &Label, desc_label: &Label, item: &UrlItem) {
+    let icon = item.icon();
+    if icon.is_empty() {
+        image.set_icon_name(Some("web-browser"));
+    } else {
+        set_app_icon(image, &icon);
+    }
+    let title = item.title();
+    name_label.set_text(if title.is_empty() {
+        "Open in browser"
+    } else {
+        &title
+    });
+    set_desc(desc_label, &item.url());
+}
+
+/// Bind an open window of the `:w` mode: title, then application and
+/// workspace
+fn bind_window_item(image: &Image, name_label: &Label, desc_label: &Label, item: &WindowItem) {
+    set_app_icon(image, &item.icon());
+    name_label.set_text(&item.title());
+    set_desc(desc_label, &item.description());
+}
+
+/// Bind a process of the `:kill` mode: name, then PID, memory and command
+/// line
+fn bind_process_item(image: &Image, name_label: &Label, desc_label: &Label, item: &ProcessItem) {
+    image.set_icon_name(Some("application-x-executable"));
+    name_label.set_text(&item.name());
+    set_desc(desc_label, &item.description());
+}
+
+/// Bind a project of the `:proj` mode: folder name, then path and source
+fn bind_project_item(image: &Image, name_label: &Label, desc_label: &Label, item: &ProjectItem) {
+    image.set_icon_name(Some("folder"));
+    name_label.set_text(&item.name());
+    set_desc(desc_label, &item.description());
+}
+
+/// Bind an SSH host of the `:ssh` mode: alias, then user, host name and port
+fn bind_ssh_host_item(image: &Image, name_label: &Label, desc_label: &Label, item: &SshHostItem) {
+    image.set_icon_name(Some("network-server"));
+    name_label.set_text(&item.alias());
+    set_desc(desc_label, &item.description());
+}
+
+/// Bind a systemd unit of the `:unit` mode: unit name, then state and
+/// description
+fn bind_unit_item(image: &Image, name_label: &Label, desc_label: &Label, item: &UnitItem) {
+    image.set_icon_name(Some("system-run"));
+    name_label.set_text(&item.name());
+    set_desc(desc_label, &item.description());
+}
+
+/// Bind a manual page of the `:man` mode: name with section, then its
+/// description
+fn bind_man_page_item(image: &Image, name_label: &Label, desc_label: &Label, item: &ManPageItem) {
+    image.set_icon_name(Some("help-contents"));
+    name_label.set_text(&item.title());
+    set_desc(desc_label, &item.description());
+}
+
+/// Bind a snippet of the `:snip` mode: name, then a one-line preview
+fn bind_snippet_item(image: &Image, name_label: &Label, desc_label: &Label, item: &SnippetItem) {
+    image.set_icon_name(Some("insert-text"));
+    name_label.set_text(&item.name());
+    set_desc(desc_label, &item.preview());
+}
+
+/// Bind an entry of the `:pw` mode: a lock, the entry's name, then its
+/// folder in the store; the secret is never read
+fn bind_pass_entry_item(
+    image: &Image,
+    name_label: &Label,
+    desc_label: &Label,
+    item: &PassEntryItem,
+) {
+    image.set_icon_name(Some("changes-prevent"));
+    name_label.set_text(&item.title());
+    set_desc(desc_label, &item.folder());
+}
+
+/// Bind an emoji of the `:e` mode: the emoji in place of the icon, its name,
+/// then its shortcodes
+fn bind_emoji_item(row: &ResultRow, item: &EmojiItem) {
+    row.image().set_visible(false);
+    row.glyph_label().set_text(&item.glyph());
+    row.glyph_label().set_visible(true);
+    row.name_label().set_text(&item.name());
+    set_desc(row.desc_label(), &item.shortcodes());
+}
+
+/// Bind a clipboard history entry: first line, then a preview of the rest
+fn bind_clipboard_item(
+    image: &Image,
+    name_label: &Label,
+    desc_label: &Label,
+    item: &ClipboardItem,
+) {
+    image.set_icon_name(Some("edit-paste"));
+    name_label.set_text(&item.title());
+    set_desc(desc_label, &item.preview());
+}
+
+/// Bind a `!command` row: the exact command line that will run
+fn bind_terminal_command_item(
+    image: &Image,
+    name_label: &Label,
+    desc_label: &Label,
+    item: &TerminalCommandItem,
+) {
+    image.set_icon_name(Some("utilities-terminal"));
+    if item.command().trim().is_empty() {
+        name_label.set_text("Run in terminal");
+        set_desc(desc_label, "Type a command after !");
+    } else {
+        name_label.set_text(&item.title());
+        set_desc(
+            desc_label,
+            if item.terminal() {
+                "Run in terminal"
+            } else {
+                "Run as a shell command"
+            },
+        );
+    }
+}
+
 /// Bind a search result item (D-Bus provider) to the list widget
 fn bind_search_result_item(
     image: &Image,
@@ -410,3 +902,115 @@ fn bind_search_result_item(
     name_label.set_text(&sr_item.name());
     set_desc(desc_label, &sr_item.description());
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_char_byte_ranges() {
+        assert_eq!(char_byte_ranges("Firefox", &[0, 1, 2, 3]), vec![(0, 4)]);
+        assert_eq!(
+            char_byte_ranges("Firefox", &[0, 2, 3]),
+            vec![(0, 1), (2, 4)]
+        );
+        // "é" is two bytes
+        assert_eq!(char_byte_ranges("Caméra", &[3, 4]), vec![(3, 6)]);
+        // Markup characters are plain text
+        assert_eq!(char_byte_ranges("A & <B>", &[2, 5]), vec![(2, 3), (5, 6)]);
+        assert_eq!(char_byte_ranges("abc", &[1, 7]), vec![(1, 2)]);
+        assert!(char_byte_ranges("abc", &[]).is_empty());
+    }
+
+    #[test]
+    fn test_match_byte_range_ascii() {
+        assert_eq!(match_byte_range("fn main() {", 4, "main"), Some((3, 7)));
+        assert_eq!(match_byte_range("fn main() {", 1, "FN"), Some((0, 2)));
+    }
+
+    #[test]
+    fn test_match_byte_range_multibyte_prefix() {
+        // "é" takes two bytes, so the match starts at byte 3 (column 4)
+        let text = "éa match";
+        assert_eq!(match_byte_range(text, 4, "match"), None);
+        assert_eq!(match_byte_range(text, 5, "match"), Some((4, 9)));
+        assert_eq!(&text[4..9], "match");
+    }
+
+    #[test]
+    fn test_match_byte_range_multibyte_term() {
+        let text = "café crème";
+        let (start, end) = match_byte_range(text, 7, "crème").unwrap();
+        assert_eq!(&text[start..end], "crème");
+        let (start, end) = match_byte_range(text, 1, "CAFÉ").unwrap();
+        assert_eq!(&text[start..end], "café");
+    }
+
+    #[test]
+    fn test_match_byte_range_rejects_bad_columns() {
+        assert_eq!(match_byte_range("abc", 0, "a"), None);
+        assert_eq!(match_byte_range("abc", 4, "a"), None);
+        // Column inside a multibyte character
+        assert_eq!(match_byte_range("éa", 2, "a"), None);
+        // Text at the column differs from the term (regex or misparsed column)
+        assert_eq!(match_byte_range("foo bar", 1, "ba."), None);
+        assert_eq!(match_byte_range("foo bar", 1, ""), None);
+    }
+
+    #[test]
+    fn test_match_byte_range_term_past_end() {
+        assert_eq!(match_byte_range("abc", 2, "bcd"), None);
+    }
+
+    #[test]
+    fn test_single_line_text() {
+        assert_eq!(
+            single_line_text("notes/todo.md", Some(12), "buy milk", 60),
+            ("notes/todo.md:12 — buy milk".to_string(), 21)
+        );
+        // Without a line number
+        assert_eq!(
+            single_line_text("notes/todo.md", None, "buy milk", 60),
+            ("notes/todo.md — buy milk".to_string(), 18)
+        );
+        // Without text (file search results)
+        assert_eq!(
+            single_line_text("~/notes/todo.md", None, "", 60).0,
+            "~/notes/todo.md"
+        );
+        assert_eq!(single_line_text("todo.md", Some(3), "", 60).0, "todo.md:3");
+    }
+
+    #[test]
+    fn test_single_line_text_long_path() {
+        let path = "~/projects/very/deeply/nested/folder/structure/with/many/levels/src/main.rs";
+        let (text, start) = single_line_text(path, Some(7), "fn main() {", 30);
+        assert_eq!(text, "…/with/many/levels/src/main.rs:7 — fn main() {");
+        assert_eq!(&text[start..], "fn main() {");
+
+        let shortened = text.split(':').next().unwrap();
+        assert!(shortened.chars().count() <= 30);
+    }
+
+    #[test]
+    fn test_shorten_path() {
+        assert_eq!(shorten_path("a/b.md", 10), "a/b.md");
+        assert_eq!(shorten_path("abcdef/ghij/k.md", 11), "…/ghij/k.md");
+        assert_eq!(shorten_path("abcdef/ghij/k.md", 10), "…/k.md");
+        // A file name longer than the limit is cut inside the name
+        assert_eq!(
+            shorten_path("dir/a_very_long_file_name.md", 10),
+            "…e_name.md"
+        );
+        // Multibyte characters are counted, not bytes
+        assert_eq!(shorten_path("écrits/é/ü.md", 9), "…/é/ü.md");
+    }
+
+    #[test]
+    fn test_colon_command_term() {
+        assert_eq!(colon_command_term(":fg needle"), Some("needle"));
+        assert_eq!(colon_command_term(":obg two words"), Some("two words"));
+        assert_eq!(colon_command_term(":fg"), None);
+        assert_eq!(colon_command_term("firefox"), None);
+    }
+}
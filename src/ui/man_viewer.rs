@@ -0,0 +1,195 @@
+//! In-launcher viewer for the manual pages of `:man`
+//!
+//! The results list and the viewer are the two pages of a stack. Enter on
+//! a `:man` row renders the page with `man` on a worker thread and shows
+//! it read-only, in a monospace text view; the search entry keeps the
+//! focus, so the window's key handler scrolls the page with the arrow and
+//! Page keys and Escape returns to the results. Typing a new query or
+//! showing the window again returns to them as well.
+//!
+//! When the page cannot be rendered, the viewer says so and Ctrl+Enter
+//! opens it with `man` in the terminal emulator, as it does on the row.
+
+use crate::item_activation::open_man_page_in_terminal;
+use crate::model::items::ManPageItem;
+use crate::model::worker::spawn_worker_with_updates;
+use crate::providers::manpages::render_man_page;
+use crate::ui::compat::ApplicationWindow;
+use gtk4::gdk::{Key, ModifierType};
+use gtk4::prelude::*;
+use gtk4::{
+    Box as GtkBox, Label, Orientation, PolicyType, ScrolledWindow, SearchEntry, Stack,
+    StackTransitionType, TextView, Widget, WrapMode,
+};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+/// Stack page of the results list
+const RESULTS_PAGE: &str = "results";
+/// Stack page of the viewer
+const VIEWER_PAGE: &str = "man";
+
+/// The results list and the manual page viewer
+#[derive(Clone)]
+pub struct ManViewer {
+    stack: Stack,
+    title: Label,
+    text: TextView,
+    scrolled: ScrolledWindow,
+    /// Row of the page shown, for opening it in the terminal
+    item: Rc<RefCell<Option<ManPageItem>>>,
+    /// Bumped for every page opened, so a late rendering is dropped
+    generation: Rc<Cell<u32>>,
+}
+
+impl Default for ManViewer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ManViewer {
+    /// Create the viewer, showing no page yet
+    #[must_use]
+    pub fn new() -> Self {
+        let stack = Stack::builder()
+            .transition_type(StackTransitionType::Crossfade)
+            .transition_duration(120)
+            .vexpand(true)
+            .build();
+
+        let title = Label::new(None);
+        title.add_css_class("man-title");
+        title.set_xalign(0.0);
+
+        let text = TextView::builder()
+            .editable(false)
+            .cursor_visible(false)
+            .monospace(true)
+            .wrap_mode(WrapMode::None)
+            .can_focus(false)
+            .build();
+        text.add_css_class("man-view");
+
+        let scrolled = ScrolledWindow::builder()
+            .vexpand(true)
+            .hscrollbar_policy(PolicyType::Automatic)
+            .child(&text)
+            .build();
+
+        let page = GtkBox::new(Orientation::Vertical, 0);
+        page.append(&title);
+        page.append(&scrolled);
+        stack.add_named(&page, Some(VIEWER_PAGE));
+
+        Self {
+            stack,
+            title,
+            text,
+            scrolled,
+            item: Rc::new(RefCell::new(None)),
+            generation: Rc::new(Cell::new(0)),
+        }
+    }
+
+    /// Put the results list in the stack, as the page shown by default
+    pub fn set_results(&self, results: &impl IsA<Widget>) {
+        self.stack.add_named(results, Some(RESULTS_PAGE));
+        self.stack.set_visible_child_name(RESULTS_PAGE);
+    }
+
+    /// The stack to add to the window in place of the results list
+    #[must_use]
+    pub fn widget(&self) -> &Stack {
+        &self.stack
+    }
+
+    /// Go back to the results when the query changes or the window is shown
+    pub fn connect_close_triggers(&self, window: &ApplicationWindow, entry: &SearchEntry) {
+        let viewer = self.clone();
+        window.connect_show(move |_| viewer.close());
+        let viewer = self.clone();
+        entry.connect_changed(move |_| viewer.close());
+    }
+
+    /// Whether a page is shown instead of the results
+    #[must_use]
+    pub fn is_open(&self) -> bool {
+        self.stack.visible_child_name().as_deref() == Some(VIEWER_PAGE)
+    }
+
+    /// Render the page of `item` and show it
+    pub fn open(&self, item: &ManPageItem) {
+        let generation = self.generation.get().wrapping_add(1);
+        self.generation.set(generation);
+        *self.item.borrow_mut() = Some(item.clone());
+
+        let page = item.page();
+        self.title
+            .set_text(&format!("{} — {}", page.title(), page.description));
+        self.text.buffer().set_text("");
+        self.scrolled.vadjustment().set_value(0.0);
+        self.stack.set_visible_child_name(VIEWER_PAGE);
+
+        let current = self.generation.clone();
+        let viewer = self.clone();
+        spawn_worker_with_updates(
+            move |tx| {
+                let _ = tx.send(render_man_page(&page));
+            },
+            move || current.get() == generation,
+            move |text: Option<String>| {
+                let text = text.unwrap_or_else(|| {
+                    "This page could not be rendered.\n\
+                     Press Ctrl+Enter to open it in the terminal."
+                        .to_string()
+                });
+                viewer.text.buffer().set_text(&text);
+                viewer.scrolled.vadjustment().set_value(0.0);
+            },
+        );
+    }
+
+    /// Show the results again
+    pub fn close(&self) {
+        if self.is_open() {
+            self.generation.set(self.generation.get().wrapping_add(1));
+            self.stack.set_visible_child_name(RESULTS_PAGE);
+        }
+    }
+
+    /// Handle a key pressed while the viewer is open
+    ///
+    /// Escape closes it, the arrow and Page keys scroll the page, and
+    /// Ctrl+Enter opens the page in the terminal. Other keys, typing among
+    /// them, are left to the window; `None` means the key was not handled.
+    pub fn handle_key(
+        &self,
+        key: Key,
+        modifiers: ModifierType,
+        window: &ApplicationWindow,
+    ) -> Option<glib::Propagation> {
+        if !self.is_open() {
+            return None;
+        }
+        let adjustment = self.scrolled.vadjustment();
+        let scroll = |delta: f64| adjustment.set_value(adjustment.value() + delta);
+        match key {
+            Key::Escape => self.close(),
+            Key::Down | Key::KP_Down => scroll(adjustment.step_increment()),
+            Key::Up | Key::KP_Up => scroll(-adjustment.step_increment()),
+            Key::Page_Down => scroll(adjustment.page_increment()),
+            Key::Page_Up => scroll(-adjustment.page_increment()),
+            Key::Return | Key::KP_Enter => {
+                if modifiers.contains(ModifierType::CONTROL_MASK)
+                    && let Some(item) = self.item.borrow().as_ref()
+                {
+                    open_man_page_in_terminal(item);
+                    window.set_visible(false);
+                }
+            }
+            _ => return None,
+        }
+        Some(glib::Propagation::Stop)
+    }
+}
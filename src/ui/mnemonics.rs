@@ -0,0 +1,168 @@
+//! Alt+letter mnemonics of the power bar and Obsidian bar buttons
+//!
+//! Both bars can be shown at once, so [`assign_mnemonics`] gives out the
+//! keys of all their buttons in one go, power bar first. Only letters are
+//! used: Alt+1..Alt+9 launch the pinned apps.
+//!
+//! The text buttons of the Obsidian bar underline their key. The power
+//! bar shows icons only, so its keys are listed in a hint line below the
+//! results while Alt is held.
+
+use gtk4::prelude::*;
+use gtk4::{Align, Label};
+
+/// A key for each of `labels`, in order, none used twice
+///
+/// A label gets the first free initial of its words, else its first free
+/// letter, else no key. Keys are lowercase ASCII letters.
+#[must_use]
+pub fn assign_mnemonics(labels: &[&str]) -> Vec<Option<char>> {
+    let mut used = Vec::new();
+    labels
+        .iter()
+        .map(|label| {
+            let key = candidates(label).find(|c| !used.contains(c))?;
+            used.push(key);
+            Some(key)
+        })
+        .collect()
+}
+
+/// Letters of `label` worth a mnemonic: word initials, then the others
+fn candidates(label: &str) -> impl Iterator<Item = char> + '_ {
+    let initials = label
+        .split_whitespace()
+        .filter_map(|word| word.chars().next());
+    initials
+        .chain(label.chars())
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_lowercase())
+}
+
+/// `label` with an underscore before its `key`, for a mnemonic label
+///
+/// An initial is underlined before a letter inside a word, matching how
+/// [`assign_mnemonics`] picks keys. Underscores of the label are doubled.
+#[must_use]
+pub fn mnemonic_label(label: &str, key: Option<char>) -> String {
+    let escaped = label.replace('_', "__");
+    let Some(key) = key else {
+        return escaped;
+    };
+    let matches = |i: usize| {
+        escaped[i..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.to_ascii_lowercase() == key)
+    };
+    let initial = escaped
+        .char_indices()
+        .filter(|&(i, _)| i == 0 || escaped[..i].ends_with(' '))
+        .map(|(i, _)| i)
+        .find(|&i| matches(i));
+    match initial.or_else(|| escaped.char_indices().map(|(i, _)| i).find(|&i| matches(i))) {
+        Some(i) => format!("{}_{}", &escaped[..i], &escaped[i..]),
+        None => escaped,
+    }
+}
+
+/// The hint listing `keys` of the buttons `labels`, e.g.
+/// `Alt+S Settings · Alt+U Suspend`
+#[must_use]
+pub fn hint_text(labels: &[&str], keys: &[Option<char>]) -> String {
+    labels
+        .iter()
+        .zip(keys)
+        .filter_map(|(label, key)| {
+            Some(format!(
+                "Alt+{} {label}",
+                key.as_ref()?.to_ascii_uppercase()
+            ))
+        })
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
+/// Build the hint line of the power bar keys, shown while Alt is held
+///
+/// GTK shows mnemonics while Alt is held, which `window` reports through
+/// its `mnemonics-visible` property.
+#[must_use]
+pub fn build_mnemonic_hint(window: &impl IsA<gtk4::Window>, text: &str) -> Label {
+    let label = Label::new(Some(text));
+    label.add_css_class("dim-label");
+    label.add_css_class("mnemonic-hint");
+    label.set_halign(Align::Center);
+    label.set_wrap(true);
+    label.set_visible(false);
+
+    if !text.is_empty() {
+        let weak_label = label.downgrade();
+        window.connect_mnemonics_visible_notify(move |window| {
+            if let Some(label) = weak_label.upgrade() {
+                label.set_visible(window.is_mnemonics_visible());
+            }
+        });
+    }
+    label
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::obsidian_bar::BUTTON_LABELS as OBSIDIAN;
+    use crate::ui::power_bar::BUTTON_LABELS as POWER;
+
+    fn assert_unique(keys: &[Option<char>]) {
+        let keys: Vec<char> = keys.iter().flatten().copied().collect();
+        for (i, key) in keys.iter().enumerate() {
+            assert!(key.is_ascii_lowercase(), "{key:?} is not a letter");
+            assert!(!keys[i + 1..].contains(key), "{key:?} is given twice");
+        }
+    }
+
+    #[test]
+    fn test_assign_mnemonics() {
+        let labels: Vec<&str> = POWER.iter().chain(&OBSIDIAN).copied().collect();
+        let keys = assign_mnemonics(&labels);
+        assert_eq!(
+            keys,
+            ["s", "u", "r", "p", "l", "o", "n", "d", "q"].map(|k| k.chars().next())
+        );
+        assert_unique(&keys);
+
+        // The bars alone, as with [general] disable_modes or without Obsidian
+        assert_unique(&assign_mnemonics(&POWER));
+        assert_unique(&assign_mnemonics(&OBSIDIAN));
+        let reversed: Vec<&str> = labels.iter().rev().copied().collect();
+        assert_unique(&assign_mnemonics(&reversed));
+    }
+
+    #[test]
+    fn test_assign_mnemonics_runs_out() {
+        // Only letters are keys, so digits never clash with Alt+1..Alt+9
+        assert_eq!(
+            assign_mnemonics(&["1 2", "Ab", "ba", "a"]),
+            [None, Some('a'), Some('b'), None]
+        );
+    }
+
+    #[test]
+    fn test_mnemonic_label() {
+        assert_eq!(mnemonic_label("Settings", Some('s')), "_Settings");
+        assert_eq!(mnemonic_label("Suspend", Some('u')), "S_uspend");
+        assert_eq!(mnemonic_label("Power off", Some('o')), "Power _off");
+        assert_eq!(mnemonic_label("Daily Note", Some('d')), "_Daily Note");
+        assert_eq!(mnemonic_label("Log_out", None), "Log__out");
+        assert_eq!(mnemonic_label("Log_out", Some('o')), "L_og__out");
+    }
+
+    #[test]
+    fn test_hint_text() {
+        assert_eq!(
+            hint_text(&["Settings", "Suspend", "x"], &[Some('s'), Some('u'), None]),
+            "Alt+S Settings · Alt+U Suspend"
+        );
+        assert_eq!(hint_text(&[], &[]), "");
+    }
+}
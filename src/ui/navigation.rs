@@ -0,0 +1,165 @@
+//! Keyboard navigation of the result list
+//!
+//! Page Up/Down move by the number of rows that fit in the list, computed
+//! from its height and the estimated row height, instead of a fixed count.
+//! Holding an arrow key speeds up: once key-repeat has run for a while each
+//! repeat moves 2 rows, then 4, until the key is released. Scrolling the
+//! selection into view is done once per frame however many moves were made
+//! in it, so fast repeats do not relayout the list for every row.
+
+use crate::model::list_model::AppListModel;
+use crate::ui::window_height::LIST_PADDING;
+use gtk4::ListView;
+use gtk4::prelude::*;
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// Longest gap between two presses of the same arrow key, in milliseconds,
+/// for the second to count as a key-repeat
+pub const REPEAT_INTERVAL_MS: u32 = 50;
+
+/// Repeats moved one row at a time before speeding up, and again two rows
+/// at a time before the top speed
+const REPEATS_PER_SPEED: u32 = 8;
+
+/// Rows that fit in a list `height` pixels tall with rows `row_height` tall
+///
+/// At least 1, so Page Up/Down always move.
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn visible_rows(height: i32, row_height: f64) -> u32 {
+    if row_height <= 0.0 {
+        return 1;
+    }
+    let rows = (f64::from(height - LIST_PADDING) / row_height).floor();
+    if rows < 1.0 { 1 } else { rows as u32 }
+}
+
+/// Direction of an arrow key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+}
+
+/// Key-repeat state of the arrow keys
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeyRepeat {
+    /// Direction and event time of the last press
+    last: Option<(Direction, u32)>,
+    /// Repeats of that press so far
+    repeats: u32,
+}
+
+impl KeyRepeat {
+    /// Rows to move for a press in `direction` at event time `time`, in
+    /// milliseconds
+    ///
+    /// A press of the same key within [`REPEAT_INTERVAL_MS`] of the last one
+    /// is a repeat: 1 row for the first repeats, then 2, then 4.
+    pub fn press(&mut self, direction: Direction, time: u32) -> u32 {
+        let repeat = self.last.is_some_and(|(last_direction, last_time)| {
+            last_direction == direction && time.wrapping_sub(last_time) <= REPEAT_INTERVAL_MS
+        });
+        self.repeats = if repeat { self.repeats + 1 } else { 0 };
+        self.last = Some((direction, time));
+        match self.repeats / REPEATS_PER_SPEED {
+            0 => 1,
+            1 => 2,
+            _ => 4,
+        }
+    }
+
+    /// Stop speeding up; the next press moves one row
+    pub fn release(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Scrolls the list to its selection at most once per frame
+#[derive(Clone, Default)]
+pub struct ScrollCoalescer {
+    /// Whether a scroll waits for the next frame
+    pending: Rc<Cell<bool>>,
+}
+
+impl ScrollCoalescer {
+    /// Select `pos` and scroll it into view on the next frame
+    ///
+    /// Later moves before that frame only change the selection; the scroll
+    /// goes to whatever is selected by then.
+    pub fn scroll_to(&self, model: &AppListModel, list_view: &ListView, pos: u32) {
+        model.results.set_selected(pos);
+        if self.pending.replace(true) {
+            return;
+        }
+        let pending = self.pending.clone();
+        let results = model.results.clone();
+        list_view.add_tick_callback(move |list_view, _| {
+            pending.set(false);
+            let selected = results.selected();
+            if selected != gtk4::INVALID_LIST_POSITION {
+                let _ =
+                    list_view.activate_action("list.scroll-to-item", Some(&selected.to_variant()));
+            }
+            glib::ControlFlow::Break
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visible_rows() {
+        assert_eq!(visible_rows(LIST_PADDING + 520, 52.0), 10);
+        assert_eq!(visible_rows(LIST_PADDING + 545, 52.0), 10);
+        assert_eq!(visible_rows(LIST_PADDING + 520, 26.0), 20);
+        // Never less than one row
+        assert_eq!(visible_rows(20, 52.0), 1);
+        assert_eq!(visible_rows(0, 52.0), 1);
+        assert_eq!(visible_rows(400, 0.0), 1);
+    }
+
+    #[test]
+    fn test_key_repeat_accelerates() {
+        let mut keys = KeyRepeat::default();
+        let steps: Vec<u32> = (0..30)
+            .map(|i| keys.press(Direction::Down, 1000 + i * 30))
+            .collect();
+        assert!(
+            steps[..=REPEATS_PER_SPEED as usize - 1]
+                .iter()
+                .all(|&s| s == 1)
+        );
+        assert_eq!(steps[REPEATS_PER_SPEED as usize], 2);
+        assert_eq!(steps[2 * REPEATS_PER_SPEED as usize], 4);
+        assert_eq!(steps[29], 4);
+    }
+
+    #[test]
+    fn test_key_repeat_resets() {
+        let mut keys = KeyRepeat::default();
+        for i in 0..20 {
+            keys.press(Direction::Down, i * 30);
+        }
+        assert_eq!(keys.press(Direction::Down, 20 * 30), 4);
+
+        // Releasing the key
+        keys.release();
+        assert_eq!(keys.press(Direction::Down, 21 * 30), 1);
+
+        // Changing direction
+        for i in 22..42 {
+            keys.press(Direction::Down, i * 30);
+        }
+        assert_eq!(keys.press(Direction::Up, 42 * 30), 1);
+
+        // A pause between presses
+        for i in 43..63 {
+            keys.press(Direction::Up, i * 30);
+        }
+        assert_eq!(keys.press(Direction::Up, 63 * 30 + 200), 1);
+    }
+}
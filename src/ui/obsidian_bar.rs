@@ -6,12 +6,30 @@
 //! the vault, creating new notes, daily notes, and quick notes.
 
 use crate::actions::perform_obsidian_action;
+use crate::item_activation::refuse_invalid_obsidian_action;
 use crate::model::items::ObsidianAction;
 use crate::model::list_model::AppListModel;
+use crate::ui::compat::ApplicationWindow;
+use crate::ui::mnemonics::mnemonic_label;
 use glib::clone;
 use gtk4::prelude::*;
-use gtk4::{Box as GtkBox, Button, Entry, Orientation};
-use libadwaita::ApplicationWindow;
+use gtk4::{Box as GtkBox, Button, Orientation, SearchEntry};
+
+/// The actions of the bar and their button labels
+const OBSIDIAN_ACTIONS: [(&str, ObsidianAction); 4] = [
+    ("Open Vault", ObsidianAction::OpenVault),
+    ("New Note", ObsidianAction::NewNote),
+    ("Daily Note", ObsidianAction::DailyNote),
+    ("Quick Note", ObsidianAction::QuickNote),
+];
+
+/// Labels of the buttons in bar order
+pub const BUTTON_LABELS: [&str; 4] = [
+    OBSIDIAN_ACTIONS[0].0,
+    OBSIDIAN_ACTIONS[1].0,
+    OBSIDIAN_ACTIONS[2].0,
+    OBSIDIAN_ACTIONS[3].0,
+];
 
 /// Extract the argument from an Obsidian search query
 ///
@@ -43,13 +61,15 @@ pub fn extract_obsidian_arg(text: &str) -> &str {
 /// * `window` - The main application window (for closing after action)
 /// * `entry` - The search entry widget (for getting current text)
 /// * `model` - The application list model (for Obsidian configuration)
+/// * `mnemonics` - Alt+letter key of each of [`BUTTON_LABELS`], underlined
 ///
 /// # Returns
 /// A `GtkBox` containing the Obsidian action buttons.
 pub fn build_obsidian_bar(
     window: &ApplicationWindow,
-    entry: &Entry,
+    entry: &SearchEntry,
     model: &AppListModel,
+    mnemonics: &[Option<char>],
 ) -> GtkBox {
     // Create a horizontal box for the action buttons
     let obsidian_bar = GtkBox::new(Orientation::Horizontal, 8);
@@ -58,17 +78,11 @@ pub fn build_obsidian_bar(
     obsidian_bar.set_margin_bottom(6);
     obsidian_bar.set_visible(false); // Hidden by default, shown in Obsidian mode
 
-    // Define the available Obsidian actions and their button labels
-    let obsidian_actions = [
-        ("Open Vault", ObsidianAction::OpenVault),
-        ("New Note", ObsidianAction::NewNote),
-        ("Daily Note", ObsidianAction::DailyNote),
-        ("Quick Note", ObsidianAction::QuickNote),
-    ];
-
-    // Create a button for each Obsidian action
-    for (label, action) in obsidian_actions {
-        let btn = Button::with_label(label);
+    // Create a button for each Obsidian action, Alt+letter clicking it
+    // while the bar is shown
+    for (i, (label, action)) in OBSIDIAN_ACTIONS.into_iter().enumerate() {
+        let key = mnemonics.get(i).copied().flatten();
+        let btn = Button::with_mnemonic(&mnemonic_label(label, key));
         btn.add_css_class("power-button"); // Styled similarly to power buttons but with background
         btn.set_tooltip_text(Some(label));
 
@@ -90,13 +104,19 @@ pub fn build_obsidian_bar(
                 // Convert to Option<&str> if argument is non-empty
                 let arg_opt = (!arg.is_empty()).then_some(arg);
 
+                // A note path outside the vault leaves the window open on
+                // an error row
+                if refuse_invalid_obsidian_action(action, &model) {
+                    return;
+                }
+
                 // Perform the Obsidian action if configuration is available
-                if let Some(cfg) = &model.config.obsidian_cfg {
-                    perform_obsidian_action(action, arg_opt, cfg);
+                if let Some(obsidian) = model.obsidian() {
+                    perform_obsidian_action(action, arg_opt, &obsidian);
                 }
 
                 // Close the window after performing the action
-                window.hide();
+                window.set_visible(false);
             }
         ));
 
@@ -1,13 +1,19 @@
 //! Pinned applications strip for Grunner
+//!
+//! Also installs the Ctrl+P shortcut, which pins or unpins the selected app.
 
-use crate::actions::launch_app;
+use crate::actions::{LaunchSource, launch_app};
 use crate::core::config;
-use crate::launcher::DesktopApp;
+use crate::launcher::{DesktopApp, matches_desktop_id};
+use crate::model::items::AppItem;
+use crate::ui::compat::{Toast, ToastOverlay};
+use crate::ui::context_menu::WindowCtx;
 use glib::clone;
+use gtk4::gdk::{self, Key};
 use gtk4::prelude::*;
 use gtk4::{
-    Align, Box as GtkBox, Button, DragSource, DropTarget, EventControllerMotion, GestureClick,
-    Image, Orientation, Overlay, gdk_pixbuf,
+    Align, Box as GtkBox, Button, DragSource, DropTarget, EventControllerKey,
+    EventControllerMotion, GestureClick, Image, Orientation, Overlay, gdk_pixbuf,
 };
 use log::{error, info};
 use std::cell::{Cell, RefCell};
@@ -48,6 +54,84 @@ pub fn remove_pinned_app(pinned_apps: &Rc<RefCell<Vec<String>>>, desktop_id: &st
     removed
 }
 
+/// Pin `desktop_id` if it is not pinned, unpin it otherwise
+///
+/// Entries written with a `.desktop` suffix count as the same app.
+/// Returns whether the app is pinned afterwards.
+///
+/// # Errors
+/// Returns `Err` if the app would be pinned but `MAX_PINNED_APPS` is reached.
+pub fn toggle_pinned_app(pinned: &mut Vec<String>, desktop_id: &str) -> Result<bool, &'static str> {
+    let is_app = |entry: &String| entry.strip_suffix(".desktop").unwrap_or(entry) == desktop_id;
+    if pinned.iter().any(is_app) {
+        pinned.retain(|entry| !is_app(entry));
+        info!("Removed from Favorites: {desktop_id}");
+        return Ok(false);
+    }
+    if !can_add_pinned_app(pinned) {
+        return Err("Maximum pinned apps reached");
+    }
+    pinned.push(desktop_id.to_string());
+    info!("Added to Favorites: {desktop_id}");
+    Ok(true)
+}
+
+/// Install the Ctrl+P key handler that pins or unpins the selected app
+///
+/// The change is saved to `search.pinned_apps`, the strip is rebuilt, and
+/// the empty-query list is refreshed so its "Pinned" section follows.
+pub fn setup_pin_shortcut(ctx: &WindowCtx) {
+    let key_ctrl = EventControllerKey::new();
+    key_ctrl.set_propagation_phase(gtk4::PropagationPhase::Capture);
+    let window = ctx.window.clone();
+    let ctx = ctx.clone();
+    key_ctrl.connect_key_pressed(move |_, key, _, modifier_state| {
+        if !modifier_state.contains(gdk::ModifierType::CONTROL_MASK)
+            || !matches!(key, Key::p | Key::P)
+        {
+            return glib::Propagation::Proceed;
+        }
+        let Some(app) = ctx.model.results.selected_item().and_downcast::<AppItem>() else {
+            return glib::Propagation::Proceed;
+        };
+
+        let store = PinnedStore::new(&ctx.cfg, &ctx.toast_overlay);
+        let toggled = toggle_pinned_app(&mut ctx.pinned_apps.borrow_mut(), &app.desktop_id());
+        let title = match toggled {
+            Ok(pinned) => {
+                let saved = store.save(&ctx.pinned_apps.borrow());
+                let query_is_empty = ctx.entry.text().is_empty();
+                refresh_pinned_strip(
+                    &ctx.pinned_strip,
+                    &ctx.pinned_apps,
+                    &ctx.all_apps,
+                    &ctx.window,
+                    query_is_empty,
+                    &ctx.dragging,
+                    &store,
+                );
+                if query_is_empty {
+                    ctx.model.populate("");
+                }
+                if !saved {
+                    // The toast of the failure stays
+                    return glib::Propagation::Stop;
+                }
+                if pinned {
+                    format!("Pinned {}", app.name())
+                } else {
+                    format!("Unpinned {}", app.name())
+                }
+            }
+            Err(_) => format!("Maximum {MAX_PINNED_APPS} favourites reached"),
+        };
+        ctx.toast_overlay
+            .add_toast(Toast::builder().title(title).timeout(2).build());
+        glib::Propagation::Stop
+    });
+    window.add_controller(key_ctrl);
+}
+
 /// Check if pinned apps limit is reached
 #[must_use]
 pub fn can_add_pinned_app(pinned_apps: &[String]) -> bool {
@@ -115,7 +199,7 @@ fn setup_drag_and_drop(
     pinned_apps_ref: &Rc<RefCell<Vec<String>>>,
     drag_source_id: &Rc<RefCell<Option<String>>>,
     dragging: &Rc<Cell<bool>>,
-    cfg: &config::Config,
+    store: &PinnedStore,
 ) {
     let did = desktop_id.to_string();
 
@@ -183,7 +267,7 @@ fn setup_drag_and_drop(
     let p_apps = pinned_apps_ref.clone();
     let src_id_drop = drag_source_id.clone();
     let strip_drop = strip.clone();
-    let cfg_s = cfg.clone();
+    let store_s = store.clone();
     drop_target.connect_accept(move |_dt, _| {
         let source_id = src_id_drop.borrow().clone();
 
@@ -227,7 +311,7 @@ fn setup_drag_and_drop(
         }
 
         // Persist to config
-        save_pinned_apps(&p_apps.borrow(), &cfg_s);
+        store_s.save(&p_apps.borrow());
 
         info!("Favorites reordered successfully");
         true
@@ -241,11 +325,10 @@ pub fn update_pinned_strip(
     strip: &GtkBox,
     pinned_apps: &[String],
     loaded_apps: &[DesktopApp],
-    window: &libadwaita::ApplicationWindow,
+    window: &crate::ui::compat::ApplicationWindow,
     pinned_apps_ref: &Rc<RefCell<Vec<String>>>,
-    _all_apps_ref: &Rc<RefCell<Vec<DesktopApp>>>,
     dragging: &Rc<Cell<bool>>,
-    cfg: &config::Config,
+    store: &PinnedStore,
 ) {
     if dragging.get() {
         return;
@@ -262,7 +345,10 @@ pub fn update_pinned_strip(
     let drag_source_id: Rc<RefCell<Option<String>>> = Rc::new(RefCell::new(None));
 
     for desktop_id in pinned_apps {
-        if let Some(app) = loaded_apps.iter().find(|a| a.desktop_id == *desktop_id) {
+        if let Some(app) = loaded_apps
+            .iter()
+            .find(|a| matches_desktop_id(a, desktop_id))
+        {
             let btn = Button::new();
             btn.set_focusable(false);
             btn.add_css_class("pinned-button");
@@ -291,12 +377,12 @@ pub fn update_pinned_strip(
             // Left-click: launch app and hide window
             let exec = app.exec.clone();
             let terminal = app.terminal;
-            let did = desktop_id.clone();
+            let source = LaunchSource::for_app(app);
             let win_click = window.clone();
             btn.connect_clicked(move |_| {
                 info!("Launching pinned app: {exec}");
-                launch_app(&exec, terminal, None, Some(&did));
-                win_click.hide();
+                launch_app(&exec, terminal, None, &source);
+                win_click.set_visible(false);
             });
 
             let motion = EventControllerMotion::new();
@@ -321,7 +407,7 @@ pub fn update_pinned_strip(
             let p_apps = pinned_apps_ref.clone();
             let p_strip = strip.clone();
             let overlay_clone = overlay.clone();
-            let cfg_s = cfg.clone();
+            let store_s = store.clone();
 
             let badge_click = GestureClick::new();
             badge_click.set_button(1);
@@ -333,7 +419,7 @@ pub fn update_pinned_strip(
                     pinned.retain(|d| d != &did);
                     info!("Removed from Favorites: {app_name}");
                 }
-                save_pinned_apps(&p_apps.borrow(), &cfg_s);
+                store_s.save(&p_apps.borrow());
                 p_strip.remove(&overlay_clone);
                 update_strip_visibility(&p_strip, &p_apps.borrow(), true);
             });
@@ -347,7 +433,7 @@ pub fn update_pinned_strip(
                 pinned_apps_ref,
                 &drag_source_id,
                 dragging,
-                cfg,
+                store,
             );
 
             strip.append(&overlay);
@@ -360,14 +446,16 @@ pub fn launch_pinned_by_index(
     index: usize,
     pinned_apps: &[String],
     loaded_apps: &[DesktopApp],
-    window: &libadwaita::ApplicationWindow,
+    window: &crate::ui::compat::ApplicationWindow,
 ) {
     if let Some(desktop_id) = pinned_apps.get(index)
-        && let Some(app) = loaded_apps.iter().find(|a| a.desktop_id == *desktop_id)
+        && let Some(app) = loaded_apps
+            .iter()
+            .find(|a| matches_desktop_id(a, desktop_id))
     {
         info!("Launching pinned app #{index}: {}", app.name);
-        launch_app(&app.exec, app.terminal, None, Some(&app.desktop_id));
-        window.hide();
+        launch_app(&app.exec, app.terminal, None, &LaunchSource::for_app(app));
+        window.set_visible(false);
     }
 }
 
@@ -377,12 +465,41 @@ pub fn update_strip_visibility(strip: &GtkBox, pinned_apps: &[String], query_is_
     strip.set_visible(visible);
 }
 
-/// Save pinned apps list to the config file on disk
-pub fn save_pinned_apps(pinned_apps: &[String], cfg: &config::Config) {
-    let mut cfg = cfg.clone();
-    cfg.pinned_apps = pinned_apps.to_vec();
-    if let Err(e) = crate::settings_window::save::save_config(&cfg) {
-        error!("Failed to save pinned apps: {e}");
+/// Saves the pinned apps to the config file, telling in a toast when they
+/// cannot be saved
+#[derive(Clone)]
+pub struct PinnedStore {
+    cfg: config::Config,
+    toasts: ToastOverlay,
+}
+
+impl PinnedStore {
+    #[must_use]
+    pub fn new(cfg: &config::Config, toasts: &ToastOverlay) -> Self {
+        Self {
+            cfg: cfg.clone(),
+            toasts: toasts.clone(),
+        }
+    }
+
+    /// Save `pinned_apps` to the config file, returning whether it was
+    /// saved; failures, such as a config set by `GRUNNER_CONFIG`, are shown
+    /// in a toast
+    pub fn save(&self, pinned_apps: &[String]) -> bool {
+        let mut cfg = self.cfg.clone();
+        cfg.pinned_apps = pinned_apps.to_vec();
+        match crate::settings_window::save::save_config(&cfg) {
+            Ok(()) => true,
+            Err(e) => {
+                error!("Failed to save pinned apps: {e}");
+                let toast = Toast::builder()
+                    .title(format!("Favourites not saved: {e}"))
+                    .timeout(3)
+                    .build();
+                self.toasts.add_toast(toast);
+                false
+            }
+        }
     }
 }
 
@@ -391,23 +508,14 @@ pub fn refresh_pinned_strip(
     strip: &GtkBox,
     pinned_apps: &Rc<RefCell<Vec<String>>>,
     all_apps: &Rc<RefCell<Vec<DesktopApp>>>,
-    window: &libadwaita::ApplicationWindow,
+    window: &crate::ui::compat::ApplicationWindow,
     query_is_empty: bool,
     dragging: &Rc<Cell<bool>>,
-    cfg: &config::Config,
+    store: &PinnedStore,
 ) {
     let pinned = pinned_apps.borrow();
     let apps = all_apps.borrow();
-    update_pinned_strip(
-        strip,
-        &pinned,
-        &apps,
-        window,
-        pinned_apps,
-        all_apps,
-        dragging,
-        cfg,
-    );
+    update_pinned_strip(strip, &pinned, &apps, window, pinned_apps, dragging, store);
     update_strip_visibility(strip, &pinned, query_is_empty);
 }
 
@@ -415,6 +523,29 @@ pub fn refresh_pinned_strip(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_toggle_pinned_app() {
+        let mut pinned = vec![
+            "firefox.desktop".to_string(),
+            "org.gnome.Nautilus".to_string(),
+        ];
+        // Either spelling of the ID unpins
+        assert_eq!(toggle_pinned_app(&mut pinned, "firefox"), Ok(false));
+        assert_eq!(pinned, vec!["org.gnome.Nautilus".to_string()]);
+        // Pinning appends, keeping the order of the others
+        assert_eq!(toggle_pinned_app(&mut pinned, "kitty"), Ok(true));
+        assert_eq!(
+            pinned,
+            vec!["org.gnome.Nautilus".to_string(), "kitty".to_string()]
+        );
+
+        let mut full: Vec<String> = (0..MAX_PINNED_APPS).map(|i| format!("app{i}")).collect();
+        assert!(toggle_pinned_app(&mut full, "another").is_err());
+        assert_eq!(full.len(), MAX_PINNED_APPS);
+        // Unpinning still works at the limit
+        assert_eq!(toggle_pinned_app(&mut full, "app3"), Ok(false));
+    }
+
     #[test]
     fn test_max_pinned_apps_constant() {
         assert_eq!(MAX_PINNED_APPS, 9);
@@ -6,31 +6,78 @@
 //! - System power operations (suspend, restart, power off, log out)
 //!
 //! Power operations are protected by confirmation dialogs to prevent accidental
-//! activation, while settings access is immediate.
+//! activation, while settings access is immediate. Logging out ends only the
+//! graphical session; if it cannot be found, ending every session of the user
+//! needs a separate confirmation.
 
-use crate::actions::{open_settings, power_action};
+use crate::actions::session::{LogoutError, current_user, terminate_user};
+use crate::actions::{logout, open_settings, power_action, show_power_error};
 use crate::core::callbacks::AppCallbacks;
+use crate::ui::compat::ApplicationWindow;
+use crate::ui::dialogs::{Confirmation, Response, confirm};
+use crate::ui::icons::{IconKind, themed_icon};
 use glib::clone;
 use gtk4::prelude::*;
-use gtk4::{Align, Box as GtkBox, Button, Entry, Image, Orientation};
-use libadwaita::prelude::{AdwDialogExt, AlertDialogExt};
-use libadwaita::{AlertDialog, ApplicationWindow, ResponseAppearance};
+use gtk4::{
+    ActivateAction, Align, Box as GtkBox, Button, Image, MnemonicTrigger, Orientation, SearchEntry,
+    Shortcut, ShortcutController, ShortcutScope, gdk,
+};
+
+/// The power operations: label, icon and name for `power_action`
+const POWER_ACTIONS: [(&str, IconKind, &str); 4] = [
+    // Suspend system to RAM
+    ("Suspend", IconKind::Suspend, "suspend"),
+    // Restart/reboot the system
+    ("Restart", IconKind::Restart, "reboot"),
+    // Power off/shutdown the system
+    ("Power off", IconKind::PowerOff, "poweroff"),
+    // Log out of current user session
+    ("Log out", IconKind::LogOut, "logout"),
+];
+
+/// Labels of the buttons in bar order, Settings first
+pub const BUTTON_LABELS: [&str; 5] = [
+    "Settings",
+    POWER_ACTIONS[0].0,
+    POWER_ACTIONS[1].0,
+    POWER_ACTIONS[2].0,
+    POWER_ACTIONS[3].0,
+];
+
+/// Make Alt+`key` click `btn`
+///
+/// The buttons show icons, so there is no label to underline; the keys
+/// are listed by [`crate::ui::mnemonics::build_mnemonic_hint`] instead.
+fn add_mnemonic(btn: &Button, key: Option<char>) {
+    // The keysym of a letter is named after it
+    let Some(keyval) = key.and_then(|key| gdk::Key::from_name(key.to_string())) else {
+        return;
+    };
+    let shortcut = Shortcut::new(
+        Some(MnemonicTrigger::new(keyval)),
+        Some(ActivateAction::get()),
+    );
+    let controller = ShortcutController::new();
+    controller.set_scope(ShortcutScope::Managed);
+    controller.add_shortcut(shortcut);
+    btn.add_controller(controller);
+}
 
 /// Create a button with an icon and label using available icon themes
 ///
-/// This function attempts to find the best matching icon from a list of
-/// candidates based on what's available in the current icon theme.
+/// This function uses the best matching icon of `icon` that's available in
+/// the current icon theme, chosen once per theme by [`themed_icon`].
 /// If no candidate icon is found, the button will display only the label.
 ///
 /// # Arguments
 /// * `label` - Text label to display on the button (used as tooltip and fallback)
-/// * `icon_candidates` - List of icon names to try in order of preference
+/// * `icon` - The icon whose candidate names are tried in order of preference
 /// * `icon_theme` - The current GTK icon theme for icon availability checking
 ///
 /// # Returns
 /// A configured `Button` with icon (if available), styled as a power button.
 /// If no icon is available, the label text is displayed instead.
-fn make_icon_button(label: &str, icon_candidates: &[&str], icon_theme: &gtk4::IconTheme) -> Button {
+fn make_icon_button(label: &str, icon: IconKind, icon_theme: &gtk4::IconTheme) -> Button {
     // Create button with power button styling
     let btn = Button::new();
     btn.add_css_class("power-button");
@@ -40,8 +87,8 @@ fn make_icon_button(label: &str, icon_candidates: &[&str], icon_theme: &gtk4::Ic
     let btn_box = GtkBox::new(Orientation::Horizontal, 0);
     btn_box.set_halign(Align::Center);
 
-    // Try each icon candidate in order until we find one available in the theme
-    if let Some(&icon_name) = icon_candidates.iter().find(|&&n| icon_theme.has_icon(n)) {
+    // Use the first icon candidate available in the theme
+    if let Some(icon_name) = themed_icon(icon, icon_theme) {
         let image = Image::from_icon_name(icon_name);
         image.set_pixel_size(16); // Consistent icon size for power buttons
         btn_box.append(&image);
@@ -60,37 +107,35 @@ fn make_icon_button(label: &str, icon_candidates: &[&str], icon_theme: &gtk4::Ic
 /// Build the power action bar with system management buttons
 ///
 /// Creates a horizontal bar at the bottom of the window containing:
-/// - Settings button (left-aligned, no confirmation required)
-/// - Spacer to push power buttons to the right
+/// - Settings button (at the start edge, no confirmation required)
 /// - Power operation buttons (suspend, restart, power off, log out) with confirmation dialogs
 ///
 /// # Arguments
 /// * `window` - The main application window (for closing after actions and dialog parenting)
 /// * `entry` - The search entry widget (for refocusing after dialog cancellation)
 /// * `icon_theme` - The current GTK icon theme for button icons
+/// * `mnemonics` - Alt+letter key of each of [`BUTTON_LABELS`]
 ///
 /// # Returns
 /// A `GtkBox` containing all power action buttons properly arranged and configured
 #[must_use]
 pub fn build_power_bar(
     window: &ApplicationWindow,
-    entry: &Entry,
+    entry: &SearchEntry,
     icon_theme: &gtk4::IconTheme,
     callbacks: &AppCallbacks,
+    mnemonics: &[Option<char>],
 ) -> GtkBox {
     // Create the main horizontal container for the power bar
     let power_bar = GtkBox::new(Orientation::Horizontal, 0);
     power_bar.add_css_class("power-bar");
     // power_bar.set_hexpand(true);
 
-    // --- Settings Button (left side) ---
+    // --- Settings Button (start edge: left, or right in RTL locales) ---
     // Settings button provides immediate access to configuration without confirmation
     {
-        let btn = make_icon_button(
-            "Settings",
-            &["preferences-system", "emblem-system", "settings-configure"],
-            icon_theme,
-        );
+        let btn = make_icon_button(BUTTON_LABELS[0], IconKind::Settings, icon_theme);
+        add_mnemonic(&btn, mnemonics.first().copied().flatten());
         btn.connect_clicked(clone!(
             #[weak]
             window,
@@ -109,44 +154,11 @@ pub fn build_power_bar(
         power_bar.append(&btn);
     }
 
-    // Spacer to push power buttons to the right side of the bar
-    // let spacer = GtkBox::new(Orientation::Horizontal, 0);
-    // spacer.set_hexpand(true);
-    // power_bar.append(&spacer);
-
-    // --- Power Operation Buttons (right side) ---
+    // --- Power Operation Buttons (after Settings, mirrored by GTK in RTL) ---
     // Each power operation requires user confirmation via dialog
-    for (label, icon_candidates, action) in [
-        // Suspend system to RAM
-        (
-            "Suspend",
-            &[
-                "system-suspend",
-                "system-suspend-hibernate",
-                "media-playback-pause", // Fallback icon
-            ][..],
-            "suspend",
-        ),
-        // Restart/reboot the system
-        (
-            "Restart",
-            &["system-restart", "system-reboot", "view-refresh"][..], // Fallback: refresh icon
-            "reboot",
-        ),
-        // Power off/shutdown the system
-        (
-            "Power off",
-            &["system-shutdown", "system-power-off"][..],
-            "poweroff",
-        ),
-        // Log out of current user session
-        (
-            "Log out",
-            &["system-log-out", "application-exit"][..], // Fallback: exit icon
-            "logout",
-        ),
-    ] {
-        let btn = make_icon_button(label, icon_candidates, icon_theme);
+    for (i, (label, icon, action)) in POWER_ACTIONS.into_iter().enumerate() {
+        let btn = make_icon_button(label, icon, icon_theme);
+        add_mnemonic(&btn, mnemonics.get(i + 1).copied().flatten());
 
         // Clone variables for use in closure
         let action = action.to_string();
@@ -160,41 +172,37 @@ pub fn build_power_bar(
             move |_| {
                 if action == "logout" {
                     // For logout, directly perform the action without custom confirmation dialog
-                    power_action(&action);
+                    match logout() {
+                        Ok(()) => {}
+                        Err(e @ (LogoutError::NoSession | LogoutError::Ambiguous(_))) => {
+                            confirm_terminate_user(&window, &entry, &e);
+                        }
+                        Err(e) => show_power_error(&format!("Could not log out: {e}")),
+                    }
                     entry.grab_focus();
                     // window.close();
                 } else {
-                    // Create confirmation dialog for destructive power operation
-                    let dialog = AlertDialog::builder()
-                        .heading(format!("{label_str}?"))
-                        .body(format!(
-                            "Are you sure you want to {}?",
-                            label_str.to_lowercase()
-                        ))
-                        .default_response("cancel")
-                        .close_response("cancel")
-                        .build();
-
-                    // Add Cancel button (safe, default action)
-                    dialog.add_response("cancel", "Cancel");
-
-                    // Add confirmation button with destructive appearance (warning color)
-                    dialog.add_response("confirm", &label_str);
-                    dialog.set_response_appearance("confirm", ResponseAppearance::Destructive);
-
+                    // Ask first: these end the session, so the button is destructive
+                    let confirmation = Confirmation {
+                        heading: format!("{label_str}?"),
+                        body: format!("Are you sure you want to {}?", label_str.to_lowercase()),
+                        confirm_label: label_str.clone(),
+                        destructive: true,
+                    };
                     let action = action.clone();
-                    dialog.connect_response(
-                        None,
+                    confirm(
+                        &window,
+                        &confirmation,
                         clone!(
                             #[weak]
                             window,
                             #[weak]
                             entry,
-                            move |_, response| {
-                                if response == "confirm" {
+                            move |response| {
+                                if response == Response::Confirm {
                                     // User confirmed - close window and perform action
                                     power_action(&action);
-                                    window.hide();
+                                    window.set_visible(false);
                                 } else {
                                     // User cancelled - refocus search entry for continued use
                                     entry.grab_focus();
@@ -202,9 +210,6 @@ pub fn build_power_bar(
                             }
                         ),
                     );
-
-                    // Show dialog centered on the main window
-                    dialog.present(Some(&window));
                 }
             }
         ));
@@ -215,3 +220,42 @@ pub fn build_power_bar(
 
     power_bar
 }
+
+/// Offer to end every session of the user when logout found no single session
+///
+/// This also closes the user's terminal and SSH sessions, so it is only done
+/// after this extra confirmation.
+fn confirm_terminate_user(window: &ApplicationWindow, entry: &SearchEntry, reason: &LogoutError) {
+    let Some(user) = current_user() else {
+        show_power_error(&format!("Could not log out: {reason}"));
+        return;
+    };
+    let confirmation = Confirmation {
+        heading: "End All Sessions?".to_string(),
+        body: format!(
+            "{reason}. Ending all sessions of {user} also closes their terminal and remote (SSH) sessions."
+        ),
+        confirm_label: "End All Sessions".to_string(),
+        destructive: true,
+    };
+    confirm(
+        window,
+        &confirmation,
+        clone!(
+            #[weak]
+            window,
+            #[weak]
+            entry,
+            move |response| {
+                if response == Response::Confirm {
+                    if let Err(e) = terminate_user(&user) {
+                        show_power_error(&format!("Could not end the sessions of {user}: {e}"));
+                    }
+                    window.set_visible(false);
+                } else {
+                    entry.grab_focus();
+                }
+            }
+        ),
+    );
+}
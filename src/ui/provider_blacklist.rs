@@ -0,0 +1,185 @@
+//! Ctrl+B shortcut to blacklist a noisy search provider
+//!
+//! With a search provider result selected, Ctrl+B inserts a confirmation
+//! row above it ("Hide results from <App> — press Enter to confirm") and
+//! selects it. Enter appends the provider's desktop ID to
+//! `search.provider_blacklist` in the config file and drops its results
+//! from the current list; any other key removes the row again.
+
+use crate::core::config;
+use crate::model::command_row::CommandRow;
+use crate::model::items::{CommandItem, SearchResultItem};
+use crate::model::list_model::AppListModel;
+use crate::ui::compat::{Toast, ToastOverlay};
+use crate::ui::context_menu::WindowCtx;
+use crate::utils::desktop::resolve_desktop_info;
+use gtk4::EventControllerKey;
+use gtk4::gdk::{self, Key};
+use gtk4::prelude::*;
+use log::error;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// A provider waiting for the user to confirm blacklisting
+struct PendingBlacklist {
+    desktop_id: String,
+    name: String,
+    /// The confirmation row
+    row: glib::Object,
+    /// Selection before the row was inserted, restored on cancel
+    selected: u32,
+}
+
+type PendingSlot = Rc<RefCell<Option<PendingBlacklist>>>;
+
+/// Install the Ctrl+B key handler on the launcher window
+///
+/// Must be attached before the main keyboard controller so that Enter
+/// confirms a pending blacklist instead of activating the selection.
+pub fn setup_provider_blacklist_shortcut(ctx: &WindowCtx) {
+    let pending: PendingSlot = Rc::new(RefCell::new(None));
+
+    let key_ctrl = EventControllerKey::new();
+    key_ctrl.set_propagation_phase(gtk4::PropagationPhase::Capture);
+
+    let model = ctx.model.clone();
+    let overlay = ctx.toast_overlay.clone();
+    key_ctrl.connect_key_pressed(move |_, key, _, modifier_state| {
+        if modifier_state.contains(gdk::ModifierType::CONTROL_MASK)
+            && matches!(key, Key::b | Key::B)
+        {
+            return if request_blacklist(&model, &pending) {
+                glib::Propagation::Stop
+            } else {
+                glib::Propagation::Proceed
+            };
+        }
+
+        if is_modifier_key(key) {
+            return glib::Propagation::Proceed;
+        }
+
+        let Some(request) = pending.borrow_mut().take() else {
+            return glib::Propagation::Proceed;
+        };
+        remove_row(&model, &request.row);
+
+        match key {
+            Key::Return | Key::KP_Enter => {
+                confirm_blacklist(&model, &overlay, &request.desktop_id, &request.name);
+                glib::Propagation::Stop
+            }
+            // Any other key cancels the request and is handled as usual
+            _ => {
+                let n = model.results.n_items();
+                if n > 0 {
+                    model.results.set_selected(request.selected.min(n - 1));
+                }
+                glib::Propagation::Proceed
+            }
+        }
+    });
+    ctx.window.add_controller(key_ctrl);
+}
+
+/// Ask for confirmation to hide the provider of the selected result
+///
+/// Returns `false` if the selection is not a search provider result.
+fn request_blacklist(model: &AppListModel, pending: &PendingSlot) -> bool {
+    let Some(item) = model
+        .results
+        .selected_item()
+        .and_downcast::<SearchResultItem>()
+    else {
+        return false;
+    };
+
+    let desktop_id = item.desktop_id();
+    if desktop_id.is_empty() {
+        return false;
+    }
+
+    if let Some(previous) = pending.borrow_mut().take() {
+        remove_row(model, &previous.row);
+    }
+
+    let name = resolve_desktop_info(&desktop_id).map_or_else(|| desktop_id.clone(), |i| i.name);
+    let row = CommandItem::new(confirmation_row(&name)).upcast::<glib::Object>();
+    let selected = model.results.selected();
+    model
+        .results
+        .splice(selected, 0, std::slice::from_ref(&row));
+    model.results.set_selected(selected);
+
+    *pending.borrow_mut() = Some(PendingBlacklist {
+        desktop_id,
+        name,
+        row,
+        selected,
+    });
+    true
+}
+
+/// The row asking to confirm hiding the results of the provider `name`
+fn confirmation_row(name: &str) -> CommandRow {
+    CommandRow {
+        description: "Any other key cancels".to_string(),
+        ..CommandRow::message(format!("Hide results from {name} — press Enter to confirm"))
+    }
+}
+
+/// Remove `row` from the results, if they still show it
+fn remove_row(model: &AppListModel, row: &glib::Object) {
+    if let Some(pos) =
+        (0..model.results.n_items()).find(|&pos| model.results.item(pos).as_ref() == Some(row))
+    {
+        model.results.remove(pos);
+    }
+}
+
+/// Persist the blacklist entry and drop the provider's results
+fn confirm_blacklist(model: &AppListModel, overlay: &ToastOverlay, desktop_id: &str, name: &str) {
+    let title = match config::blacklist_provider(desktop_id) {
+        Ok(()) => format!("Results from {name} hidden"),
+        Err(e) => {
+            error!("Failed to blacklist provider {desktop_id}: {e}");
+            format!("Results from {name} hidden until restart, config not saved: {e}")
+        }
+    };
+    model.blacklist_provider(desktop_id);
+
+    let toast = Toast::builder().title(title).timeout(2).build();
+    overlay.add_toast(toast);
+}
+
+/// Whether `key` is a bare modifier, which should not cancel a pending request
+fn is_modifier_key(key: Key) -> bool {
+    matches!(
+        key,
+        Key::Control_L
+            | Key::Control_R
+            | Key::Shift_L
+            | Key::Shift_R
+            | Key::Alt_L
+            | Key::Alt_R
+            | Key::Super_L
+            | Key::Super_R
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::command_row::CommandKind;
+
+    #[test]
+    fn test_confirmation_row() {
+        let row = confirmation_row("Software");
+        assert_eq!(row.kind, CommandKind::Message);
+        assert_eq!(
+            row.name,
+            "Hide results from Software — press Enter to confirm"
+        );
+        assert_eq!(row.description, "Any other key cancels");
+    }
+}
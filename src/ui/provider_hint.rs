@@ -0,0 +1,87 @@
+//! Footer hint for search provider results
+//!
+//! While a result of a GNOME Shell search provider is selected, a dim line
+//! below the list tells that Ctrl+Enter opens the provider's application
+//! with the search applied, GNOME Shell's "show more results".
+
+use crate::launcher::DesktopApp;
+use crate::model::items::SearchResultItem;
+use crate::model::list_model::AppListModel;
+use gtk4::prelude::*;
+use gtk4::{Align, Label};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Whether the provider's `DesktopId`, which keeps the `.desktop` suffix,
+/// names the app with desktop id `app_id`
+#[must_use]
+pub fn is_provider_app(provider_id: &str, app_id: &str) -> bool {
+    provider_id.strip_suffix(".desktop").unwrap_or(provider_id) == app_id
+}
+
+/// Text of the hint for a provider whose app is called `app_name`
+#[must_use]
+pub fn hint_text(app_name: Option<&str>) -> String {
+    match app_name {
+        Some(name) => format!("Ctrl+Enter  Open in {name}"),
+        None => "Ctrl+Enter  Show more results".to_string(),
+    }
+}
+
+/// Build the hint label, shown while a provider result is selected
+#[must_use]
+pub fn build_provider_hint(model: &AppListModel, all_apps: &Rc<RefCell<Vec<DesktopApp>>>) -> Label {
+    let label = Label::new(None);
+    label.add_css_class("dim-label");
+    label.add_css_class("provider-hint");
+    label.set_halign(Align::End);
+    label.set_visible(false);
+
+    let weak_label = label.downgrade();
+    let all_apps = all_apps.clone();
+    model
+        .results
+        .selection_model()
+        .connect_selected_item_notify(move |selection| {
+            let Some(label) = weak_label.upgrade() else {
+                return;
+            };
+            let Some(result) = selection.selected_item().and_downcast::<SearchResultItem>() else {
+                label.set_visible(false);
+                return;
+            };
+            let provider_id = result.desktop_id();
+            let apps = all_apps.borrow();
+            let name = apps
+                .iter()
+                .find(|app| is_provider_app(&provider_id, &app.desktop_id))
+                .map(|app| app.name.as_str());
+            label.set_text(&hint_text(name));
+            label.set_visible(true);
+        });
+    label
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_provider_app() {
+        assert!(is_provider_app(
+            "org.gnome.Nautilus.desktop",
+            "org.gnome.Nautilus"
+        ));
+        assert!(is_provider_app("org.gnome.Nautilus", "org.gnome.Nautilus"));
+        assert!(!is_provider_app(
+            "org.gnome.Nautilus.desktop",
+            "org.gnome.Calculator"
+        ));
+    }
+
+    #[test]
+    fn test_hint_text() {
+        assert_eq!(hint_text(Some("Files")), "Ctrl+Enter  Open in Files");
+        assert_eq!(hint_text(None), "Ctrl+Enter  Show more results");
+    }
+}
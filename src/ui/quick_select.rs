@@ -0,0 +1,164 @@
+//! Ctrl+1..Ctrl+9 on the results shown
+//!
+//! Ctrl+N activates the N-th result on screen, counting from the top of
+//! the visible part of the list and skipping section headers. While Ctrl is
+//! held, each of these rows shows its number.
+//!
+//! The rows are the ones GTK has bound, which include a few off screen;
+//! only those whose middle is inside the list are counted.
+
+use crate::ui::result_row::ResultRow;
+use gtk4::ListView;
+use gtk4::gdk::Key;
+use gtk4::prelude::*;
+
+/// Rows with a quick key, Ctrl+1 to Ctrl+9
+pub const QUICK_KEYS: usize = 9;
+
+/// Index of a number key: 0 for `1` through 8 for `9`, on either the main
+/// keys or the keypad
+#[must_use]
+pub fn number_key_index(key: Key) -> Option<usize> {
+    match key {
+        Key::_1 | Key::KP_1 => Some(0),
+        Key::_2 | Key::KP_2 => Some(1),
+        Key::_3 | Key::KP_3 => Some(2),
+        Key::_4 | Key::KP_4 => Some(3),
+        Key::_5 | Key::KP_5 => Some(4),
+        Key::_6 | Key::KP_6 => Some(5),
+        Key::_7 | Key::KP_7 => Some(6),
+        Key::_8 | Key::KP_8 => Some(7),
+        Key::_9 | Key::KP_9 => Some(8),
+        _ => None,
+    }
+}
+
+/// Whether `key` is a Ctrl key, whose press shows the numbers
+#[must_use]
+pub fn is_ctrl_key(key: Key) -> bool {
+    matches!(key, Key::Control_L | Key::Control_R)
+}
+
+/// A bound row: its position, the middle of it relative to the top of the
+/// list, and whether it can be selected
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundRow {
+    pub position: u32,
+    pub middle: f32,
+    pub selectable: bool,
+}
+
+/// The positions given quick keys, top first: the selectable rows whose
+/// middle is in a list `height` pixels tall, at most [`QUICK_KEYS`]
+#[must_use]
+pub fn quick_positions(mut rows: Vec<BoundRow>, height: f32) -> Vec<u32> {
+    rows.sort_by_key(|row| row.position);
+    rows.into_iter()
+        .filter(|row| row.selectable && (0.0..=height).contains(&row.middle))
+        .map(|row| row.position)
+        .take(QUICK_KEYS)
+        .collect()
+}
+
+/// The bound rows of `list_view`, with the widget of each
+fn bound_rows(list_view: &ListView) -> Vec<(BoundRow, ResultRow)> {
+    let mut rows = Vec::new();
+    let mut child = list_view.first_child();
+    while let Some(widget) = child {
+        child = widget.next_sibling();
+        let Some(row) = widget.first_child().and_downcast::<ResultRow>() else {
+            continue;
+        };
+        let Some(item) = row.list_item().filter(|item| item.item().is_some()) else {
+            continue;
+        };
+        let Some(bounds) = row.compute_bounds(list_view) else {
+            continue;
+        };
+        let bound = BoundRow {
+            position: item.position(),
+            middle: bounds.y() + bounds.height() / 2.0,
+            selectable: item.is_selectable(),
+        };
+        rows.push((bound, row));
+    }
+    rows
+}
+
+/// Position of the row Ctrl+`index + 1` activates, if there is one
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn quick_position(list_view: &ListView, index: usize) -> Option<u32> {
+    let rows = bound_rows(list_view)
+        .into_iter()
+        .map(|(row, _)| row)
+        .collect();
+    quick_positions(rows, list_view.height() as f32)
+        .get(index)
+        .copied()
+}
+
+/// Show the numbers of the rows with a quick key, or hide them all
+#[allow(clippy::cast_precision_loss)]
+pub fn show_quick_keys(list_view: &ListView, show: bool) {
+    let rows = bound_rows(list_view);
+    let positions = if show {
+        let bound = rows.iter().map(|(row, _)| *row).collect();
+        quick_positions(bound, list_view.height() as f32)
+    } else {
+        Vec::new()
+    };
+    for (bound, row) in &rows {
+        let label = row.quick_key_label();
+        match positions.iter().position(|&p| p == bound.position) {
+            Some(index) => {
+                label.set_text(&(index + 1).to_string());
+                label.set_visible(true);
+            }
+            None => label.set_visible(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(position: u32, middle: f32, selectable: bool) -> BoundRow {
+        BoundRow {
+            position,
+            middle,
+            selectable,
+        }
+    }
+
+    #[test]
+    fn test_number_key_index() {
+        assert_eq!(number_key_index(Key::_1), Some(0));
+        assert_eq!(number_key_index(Key::KP_9), Some(8));
+        assert_eq!(number_key_index(Key::_0), None);
+        assert_eq!(number_key_index(Key::a), None);
+    }
+
+    #[test]
+    fn test_quick_positions_skip_headers_and_hidden_rows() {
+        let rows = vec![
+            row(12, 30.0, true),
+            // Scrolled out above and below the list
+            row(9, -20.0, true),
+            row(15, 420.0, true),
+            row(10, 5.0, false),
+            row(11, 10.0, true),
+        ];
+        assert_eq!(quick_positions(rows, 400.0), [11, 12]);
+    }
+
+    #[test]
+    fn test_quick_positions_stop_at_nine() {
+        let rows = (0..20)
+            .rev()
+            .map(|i| row(i, 20.0 * i as f32, true))
+            .collect();
+        assert_eq!(quick_positions(rows, 1000.0), (0..9).collect::<Vec<_>>());
+    }
+}
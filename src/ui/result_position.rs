@@ -0,0 +1,131 @@
+//! "n / N results" readout of the selected result
+//!
+//! A dim line below the list tells which result is selected out of how
+//! many, e.g. `12 / 57 results`, so that arrowing through a long list does
+//! not get lost. Section headers are not results and are not counted. The
+//! readout follows [`MainThreadModel::connect_results_changed`], so it grows with
+//! the batches of a streaming search.
+//!
+//! [`MainThreadModel::connect_results_changed`]: crate::model::main_thread::MainThreadModel::connect_results_changed
+
+use crate::model::items::SectionHeaderItem;
+use crate::model::main_thread::MainThreadModel;
+use gtk4::prelude::*;
+use gtk4::{Align, Label};
+
+/// The 1-based index of the result at `selected` and the number of results,
+/// given whether each row is a section header
+///
+/// The index is `None` when nothing is selected
+/// (`gtk4::INVALID_LIST_POSITION`), or a header is.
+#[must_use]
+pub fn result_position(
+    selected: u32,
+    headers: impl IntoIterator<Item = bool>,
+) -> (Option<u32>, u32) {
+    let mut index = None;
+    let mut total = 0;
+    for (pos, header) in (0..).zip(headers) {
+        if header {
+            continue;
+        }
+        total += 1;
+        if pos == selected {
+            index = Some(total);
+        }
+    }
+    (index, total)
+}
+
+/// Text of the readout, or `None` to hide it when there are no results
+#[must_use]
+pub fn readout_text(index: Option<u32>, total: u32) -> Option<String> {
+    let results = if total == 1 { "result" } else { "results" };
+    match (index, total) {
+        (_, 0) => None,
+        (Some(index), _) => Some(format!("{index} / {total} {results}")),
+        (None, _) => Some(format!("{total} {results}")),
+    }
+}
+
+/// Update `label` to the selection of `results`
+fn update(label: &Label, results: &MainThreadModel) {
+    let headers = (0..results.n_items()).map(|pos| {
+        results
+            .item(pos)
+            .is_some_and(|item| item.is::<SectionHeaderItem>())
+    });
+    let (index, total) = result_position(results.selected(), headers);
+    match readout_text(index, total) {
+        Some(text) => {
+            label.set_text(&text);
+            label.set_visible(true);
+        }
+        None => label.set_visible(false),
+    }
+}
+
+/// Build the readout label, kept up to date with `results`
+#[must_use]
+pub fn build_result_position(results: &MainThreadModel) -> Label {
+    let label = Label::new(None);
+    label.add_css_class("dim-label");
+    label.add_css_class("result-position");
+    label.add_css_class("numeric");
+    label.set_halign(Align::Start);
+    label.set_visible(false);
+
+    let weak_label = label.downgrade();
+    let model = results.clone();
+    results.connect_results_changed(move || {
+        if let Some(label) = weak_label.upgrade() {
+            update(&label, &model);
+        }
+    });
+    label
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INVALID: u32 = gtk4::INVALID_LIST_POSITION;
+
+    #[test]
+    fn test_result_position_skips_headers() {
+        // Header, 2 results, header, 3 results
+        let rows = [true, false, false, true, false, false, false];
+        assert_eq!(result_position(1, rows), (Some(1), 5));
+        assert_eq!(result_position(4, rows), (Some(3), 5));
+        assert_eq!(result_position(6, rows), (Some(5), 5));
+        assert_eq!(result_position(3, rows), (None, 5));
+    }
+
+    #[test]
+    fn test_result_position_without_selection() {
+        assert_eq!(result_position(INVALID, [false, false]), (None, 2));
+        assert_eq!(result_position(INVALID, []), (None, 0));
+        // A selection past the end, while the list is being replaced
+        assert_eq!(result_position(5, [false]), (None, 1));
+    }
+
+    #[test]
+    fn test_result_position_grows_with_batches() {
+        let mut rows = vec![false; 20];
+        assert_eq!(result_position(2, rows.iter().copied()), (Some(3), 20));
+        rows.extend([true, false, false]);
+        assert_eq!(result_position(2, rows.iter().copied()), (Some(3), 22));
+    }
+
+    #[test]
+    fn test_readout_text() {
+        assert_eq!(
+            readout_text(Some(12), 57).as_deref(),
+            Some("12 / 57 results")
+        );
+        assert_eq!(readout_text(Some(1), 1).as_deref(), Some("1 / 1 result"));
+        assert_eq!(readout_text(None, 23).as_deref(), Some("23 results"));
+        assert_eq!(readout_text(None, 1).as_deref(), Some("1 result"));
+        assert_eq!(readout_text(None, 0), None);
+    }
+}
@@ -1,12 +1,17 @@
 //! Custom composite widget for search result rows
 //!
 //! `ResultRow` extends `GtkBox` and holds direct references to its
-//! child widgets (`Image`, name `Label`, desc `Label`), eliminating
-//! the need for tree traversal in every bind/unbind cycle.
+//! child widgets (quick key `Label`, `Image`, glyph `Label`, name `Label`,
+//! desc `Label`, badge `Label`, kind `Label`),
+//! eliminating the need for tree traversal in every bind/unbind cycle.
+//! It also keeps the `ListItem` showing it, for its position.
 
 use gtk4::prelude::*;
 use gtk4::subclass::prelude::*;
-use gtk4::{Align, Box as GtkBox, Image, Label, Orientation};
+use gtk4::{Align, Box as GtkBox, Image, Label, ListItem, Orientation};
+
+/// Width limit of the description line, in characters
+pub const DESC_MAX_WIDTH_CHARS: i32 = 70;
 
 mod imp {
     use super::*;
@@ -15,9 +20,14 @@ mod imp {
     /// Internal state for `ResultRow`
     #[derive(Default)]
     pub struct ResultRow {
+        pub quick_key_label: OnceCell<Label>,
         pub image: OnceCell<Image>,
+        pub glyph_label: OnceCell<Label>,
         pub name_label: OnceCell<Label>,
         pub desc_label: OnceCell<Label>,
+        pub badge_label: OnceCell<Label>,
+        pub kind_label: OnceCell<Label>,
+        pub list_item: glib::WeakRef<ListItem>,
     }
 
     #[glib::object_subclass]
@@ -42,12 +52,26 @@ mod imp {
             hbox.set_margin_end(12);
             hbox.set_halign(Align::Fill);
 
+            // Ctrl+1..Ctrl+9 key of the row, shown while Ctrl is held
+            let quick_key_label = Label::new(None);
+            quick_key_label.set_valign(Align::Center);
+            quick_key_label.add_css_class("quick-key");
+            quick_key_label.set_visible(false);
+            hbox.append(&quick_key_label);
+
             let image = Image::new();
             image.set_pixel_size(32);
             image.set_valign(Align::Center);
             image.add_css_class("app-icon");
             hbox.append(&image);
 
+            // Text shown instead of the icon, such as the emoji of `:e` rows
+            let glyph_label = Label::new(None);
+            glyph_label.set_valign(Align::Center);
+            glyph_label.add_css_class("row-glyph");
+            glyph_label.set_visible(false);
+            hbox.append(&glyph_label);
+
             let vbox = GtkBox::new(Orientation::Vertical, 2);
             vbox.set_valign(Align::Center);
             vbox.set_hexpand(true);
@@ -61,14 +85,32 @@ mod imp {
             desc_label.set_halign(Align::Start);
             desc_label.add_css_class("row-desc");
             desc_label.set_ellipsize(gtk4::pango::EllipsizeMode::End);
-            desc_label.set_max_width_chars(70);
+            desc_label.set_max_width_chars(DESC_MAX_WIDTH_CHARS);
             vbox.append(&desc_label);
 
             hbox.append(&vbox);
 
+            // Short status such as "running", hidden unless a row sets it
+            let badge_label = Label::new(None);
+            badge_label.set_valign(Align::Center);
+            badge_label.add_css_class("row-badge");
+            badge_label.set_visible(false);
+            hbox.append(&badge_label);
+
+            // Source of the result ("app", "clip"), with `[ui] show_kind_badges`
+            let kind_label = Label::new(None);
+            kind_label.set_valign(Align::Center);
+            kind_label.add_css_class("row-kind");
+            kind_label.set_visible(false);
+            hbox.append(&kind_label);
+
+            let _ = self.quick_key_label.set(quick_key_label);
             let _ = self.image.set(image);
+            let _ = self.glyph_label.set(glyph_label);
             let _ = self.name_label.set(name_label);
             let _ = self.desc_label.set(desc_label);
+            let _ = self.badge_label.set(badge_label);
+            let _ = self.kind_label.set(kind_label);
         }
     }
 
@@ -79,8 +121,13 @@ mod imp {
 glib::wrapper! {
     /// Composite row widget with direct child references.
     ///
-    /// Use [`image`](ResultRow::image), [`name_label`](ResultRow::name_label),
-    /// and [`desc_label`](ResultRow::desc_label) to access children without
+    /// Use [`quick_key_label`](ResultRow::quick_key_label),
+    /// [`image`](ResultRow::image),
+    /// [`glyph_label`](ResultRow::glyph_label),
+    /// [`name_label`](ResultRow::name_label),
+    /// [`desc_label`](ResultRow::desc_label),
+    /// [`badge_label`](ResultRow::badge_label) and
+    /// [`kind_label`](ResultRow::kind_label) to access children without
     /// tree traversal.
     pub struct ResultRow(ObjectSubclass<imp::ResultRow>)
         @extends gtk4::Box, gtk4::Widget,
@@ -100,6 +147,26 @@ impl ResultRow {
         glib::Object::new()
     }
 
+    /// Get the quick key label widget, shown at the start of the row.
+    #[must_use]
+    pub fn quick_key_label(&self) -> &Label {
+        self.imp()
+            .quick_key_label
+            .get()
+            .expect("quick_key_label initialized in constructed")
+    }
+
+    /// Get the list item showing the row.
+    #[must_use]
+    pub fn list_item(&self) -> Option<ListItem> {
+        self.imp().list_item.upgrade()
+    }
+
+    /// Remember the list item showing the row, which owns it.
+    pub fn set_list_item(&self, item: &ListItem) {
+        self.imp().list_item.set(Some(item));
+    }
+
     /// Get the icon image widget.
     #[must_use]
     pub fn image(&self) -> &Image {
@@ -109,6 +176,15 @@ impl ResultRow {
             .expect("image initialized in constructed")
     }
 
+    /// Get the glyph label widget, shown in place of the image.
+    #[must_use]
+    pub fn glyph_label(&self) -> &Label {
+        self.imp()
+            .glyph_label
+            .get()
+            .expect("glyph_label initialized in constructed")
+    }
+
     /// Get the name label widget.
     #[must_use]
     pub fn name_label(&self) -> &Label {
@@ -126,4 +202,22 @@ impl ResultRow {
             .get()
             .expect("desc_label initialized in constructed")
     }
+
+    /// Get the badge label widget, shown at the end of the row.
+    #[must_use]
+    pub fn badge_label(&self) -> &Label {
+        self.imp()
+            .badge_label
+            .get()
+            .expect("badge_label initialized in constructed")
+    }
+
+    /// Get the kind badge label widget, the last child of the row.
+    #[must_use]
+    pub fn kind_label(&self) -> &Label {
+        self.imp()
+            .kind_label
+            .get()
+            .expect("kind_label initialized in constructed")
+    }
 }
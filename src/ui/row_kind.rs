@@ -0,0 +1,257 @@
+//! Source kinds of result rows, shown as badges
+//!
+//! With `[ui] show_kind_badges` on, each row ends in a short pill such as
+//! "app" or "clip" naming where the result came from, so rows of different
+//! sources can be told apart when they share a list. The kind is derived from
+//! the item type and, for command rows, from their [`CommandKind`].
+
+use crate::model::command_row::CommandKind;
+use crate::model::items::{
+    AppActionItem, AppItem, BookmarkItem, ClipboardItem, CommandItem, EmojiItem, ManPageItem,
+    ObsidianActionItem, PassEntryItem, ProcessItem, ProjectItem, SearchResultItem, SnippetItem,
+    SshHostItem, TerminalCommandItem, UnitItem, UrlItem, WindowItem,
+};
+use glib::prelude::*;
+
+/// Where a result row came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowKind {
+    /// Desktop application
+    App,
+    /// Desktop action of an application
+    Action,
+    /// Calculator result
+    Calc,
+    /// Shell command, configured command or `!command`
+    Command,
+    /// File or grep match
+    File,
+    /// Obsidian note, grep match or vault action
+    Note,
+    /// `:stats` row
+    Stats,
+    /// Result of a GNOME Shell search provider
+    Provider,
+    /// Clipboard history entry
+    Clipboard,
+    /// Browser bookmark
+    Bookmark,
+    /// URL or web search
+    Web,
+    /// Open window of the `:w` mode
+    Window,
+    /// Emoji of the `:e` mode
+    Emoji,
+    /// Process of the `:kill` mode
+    Process,
+    /// Project of the `:proj` mode
+    Project,
+    /// SSH host of the `:ssh` mode
+    Ssh,
+    /// Systemd unit of the `:unit` mode
+    Unit,
+    /// Manual page of the `:man` mode
+    Man,
+    /// Text snippet of the `:snip` mode
+    Snippet,
+    /// Password store entry of the `:pw` mode
+    Pass,
+}
+
+impl RowKind {
+    /// Every kind, for tests that must cover new ones
+    pub const ALL: [Self; 20] = [
+        Self::App,
+        Self::Action,
+        Self::Calc,
+        Self::Command,
+        Self::File,
+        Self::Note,
+        Self::Stats,
+        Self::Provider,
+        Self::Clipboard,
+        Self::Bookmark,
+        Self::Web,
+        Self::Window,
+        Self::Emoji,
+        Self::Process,
+        Self::Project,
+        Self::Ssh,
+        Self::Unit,
+        Self::Man,
+        Self::Snippet,
+        Self::Pass,
+    ];
+
+    /// Text of the badge
+    #[must_use]
+    pub fn badge(self) -> &'static str {
+        match self {
+            Self::App => "app",
+            Self::Action => "action",
+            Self::Calc => "calc",
+            Self::Command => "cmd",
+            Self::File => "file",
+            Self::Note => "note",
+            Self::Stats => "stats",
+            Self::Provider => "search",
+            Self::Clipboard => "clip",
+            Self::Bookmark => "bookmark",
+            Self::Web => "web",
+            Self::Window => "window",
+            Self::Emoji => "emoji",
+            Self::Process => "process",
+            Self::Project => "project",
+            Self::Ssh => "ssh",
+            Self::Unit => "unit",
+            Self::Man => "man",
+            Self::Snippet => "snip",
+            Self::Pass => "pass",
+        }
+    }
+
+    /// Accessible description of the row, read out instead of the badge
+    #[must_use]
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::App => "Application",
+            Self::Action => "Application action",
+            Self::Calc => "Calculator result",
+            Self::Command => "Command",
+            Self::File => "File",
+            Self::Note => "Obsidian note",
+            Self::Stats => "Usage statistic",
+            Self::Provider => "Search provider result",
+            Self::Clipboard => "Clipboard entry",
+            Self::Bookmark => "Bookmark",
+            Self::Web => "Web",
+            Self::Window => "Open window",
+            Self::Emoji => "Emoji",
+            Self::Process => "Process",
+            Self::Project => "Project",
+            Self::Ssh => "SSH host",
+            Self::Unit => "Systemd unit",
+            Self::Man => "Manual page",
+            Self::Snippet => "Snippet",
+            Self::Pass => "Password store entry",
+        }
+    }
+
+    /// Kind of a command row of kind `kind`
+    #[must_use]
+    pub fn of_command(kind: CommandKind) -> Self {
+        match kind {
+            CommandKind::Calculator => Self::Calc,
+            CommandKind::Shell | CommandKind::Message => Self::Command,
+            CommandKind::File => Self::File,
+            CommandKind::Note => Self::Note,
+            CommandKind::Stats => Self::Stats,
+        }
+    }
+
+    /// Kind of the list item `item`
+    ///
+    /// `None` for rows that are not results: section headers, "Did you
+    /// mean" suggestions and the vault setup row.
+    #[must_use]
+    pub fn of_item(item: &glib::Object) -> Option<Self> {
+        if item.is::<AppItem>() {
+            Some(Self::App)
+        } else if item.is::<AppActionItem>() {
+            Some(Self::Action)
+        } else if let Some(cmd) = item.downcast_ref::<CommandItem>() {
+            Some(Self::of_command(cmd.kind()))
+        } else if item.is::<ObsidianActionItem>() {
+            Some(Self::Note)
+        } else if item.is::<SearchResultItem>() {
+            Some(Self::Provider)
+        } else if item.is::<ClipboardItem>() {
+            Some(Self::Clipboard)
+        } else if item.is::<BookmarkItem>() {
+            Some(Self::Bookmark)
+        } else if item.is::<TerminalCommandItem>() {
+            Some(Self::Command)
+        } else if item.is::<UrlItem>() {
+            Some(Self::Web)
+        } else if item.is::<WindowItem>() {
+            Some(Self::Window)
+        } else if item.is::<EmojiItem>() {
+            Some(Self::Emoji)
+        } else if item.is::<ProcessItem>() {
+            Some(Self::Process)
+        } else if item.is::<ProjectItem>() {
+            Some(Self::Project)
+        } else if item.is::<SshHostItem>() {
+            Some(Self::Ssh)
+        } else if item.is::<UnitItem>() {
+            Some(Self::Unit)
+        } else if item.is::<ManPageItem>() {
+            Some(Self::Man)
+        } else if item.is::<SnippetItem>() {
+            Some(Self::Snippet)
+        } else if item.is::<PassEntryItem>() {
+            Some(Self::Pass)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_kind_has_a_badge_and_description() {
+        for kind in RowKind::ALL {
+            assert!(!kind.badge().is_empty(), "{kind:?} has no badge");
+            assert!(
+                !kind.description().is_empty(),
+                "{kind:?} has no description"
+            );
+        }
+    }
+
+    #[test]
+    fn test_badges_are_distinct() {
+        let mut badges: Vec<_> = RowKind::ALL.iter().map(|kind| kind.badge()).collect();
+        badges.sort_unstable();
+        badges.dedup();
+        assert_eq!(badges.len(), RowKind::ALL.len());
+    }
+
+    #[test]
+    fn test_of_command() {
+        use crate::model::command_row::CommandRow;
+        use std::path::Path;
+
+        let vault = Path::new("/vault");
+        let cases = [
+            (CommandRow::calculator("2 + 2", "4"), RowKind::Calc),
+            (CommandRow::run("ls -la"), RowKind::Command),
+            (CommandRow::run(r"C:\Tools\build.bat"), RowKind::Command),
+            (
+                CommandRow::from_listing(b"/home/user/notes.txt", None),
+                RowKind::File,
+            ),
+            (
+                CommandRow::from_grep(b"/src/main.rs\x0012:fn main() {", None, false),
+                RowKind::File,
+            ),
+            (CommandRow::message("hello: world"), RowKind::Command),
+            (CommandRow::shell("Build", "cargo build"), RowKind::Command),
+            (
+                CommandRow::from_listing(b"/vault/Ideas.md", Some(vault)),
+                RowKind::Note,
+            ),
+            (
+                CommandRow::from_grep(b"/vault/Ideas.md\x003:todo", Some(vault), false),
+                RowKind::Note,
+            ),
+            (CommandRow::stats("Launches", "12"), RowKind::Stats),
+        ];
+        for (row, kind) in cases {
+            assert_eq!(RowKind::of_command(row.kind), kind, "{row:?}");
+        }
+    }
+}
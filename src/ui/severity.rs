@@ -0,0 +1,155 @@
+//! State of result rows, shown as a tinted icon
+//!
+//! Rows that report on something running, such as the processes of `:kill`
+//! and the units of `:unit`, can carry a severity so that a failing or busy
+//! entry stands out. The list factory sets one CSS class on the row's icon
+//! for every bound item, from [`Severity::of_item`], and switches the icon
+//! to its symbolic variant so the theme's error, warning or success color
+//! applies. A mode gets the
+//! tint by answering here for its item type.
+//!
+//! The derivations from raw state are pure and kept here, next to the
+//! classes they select.
+
+use crate::model::items::{ProcessItem, UnitItem};
+use glib::prelude::*;
+use gtk4::Image;
+use gtk4::prelude::*;
+
+/// Average CPU use, in percent of one core, from which a process is busy
+pub const CPU_WARNING_PERCENT: u32 = 30;
+/// Average CPU use from which a process is shown as overloading the system
+pub const CPU_ERROR_PERCENT: u32 = 80;
+
+/// How much attention a row needs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Nothing to report; the icon keeps its colors
+    #[default]
+    Normal,
+    /// Running as expected
+    Success,
+    /// Busy or changing state
+    Warning,
+    /// Failed or using too much
+    Error,
+}
+
+impl Severity {
+    /// Every severity with a class, for clearing them
+    const TINTED: [Self; 3] = [Self::Success, Self::Warning, Self::Error];
+
+    /// CSS class of the icon, `None` for [`Severity::Normal`]
+    #[must_use]
+    pub fn css_class(self) -> Option<&'static str> {
+        match self {
+            Self::Normal => None,
+            Self::Success => Some("severity-success"),
+            Self::Warning => Some("severity-warning"),
+            Self::Error => Some("severity-error"),
+        }
+    }
+
+    /// Severity of a process by its average CPU use
+    #[must_use]
+    pub fn of_cpu_percent(percent: u32) -> Self {
+        if percent >= CPU_ERROR_PERCENT {
+            Self::Error
+        } else if percent >= CPU_WARNING_PERCENT {
+            Self::Warning
+        } else {
+            Self::Normal
+        }
+    }
+
+    /// Severity of a systemd unit by its `ActiveState`
+    ///
+    /// Inactive units are not a problem, so only failed, changing and
+    /// active units are tinted.
+    #[must_use]
+    pub fn of_unit_state(active_state: &str) -> Self {
+        match active_state {
+            "failed" => Self::Error,
+            "activating" | "deactivating" | "reloading" | "refreshing" => Self::Warning,
+            "active" => Self::Success,
+            _ => Self::Normal,
+        }
+    }
+
+    /// Severity of a list item, [`Severity::Normal`] for types without one
+    #[must_use]
+    pub fn of_item(item: &glib::Object) -> Self {
+        if let Some(process) = item.downcast_ref::<ProcessItem>() {
+            process.severity()
+        } else if let Some(unit) = item.downcast_ref::<UnitItem>() {
+            unit.severity()
+        } else {
+            Self::Normal
+        }
+    }
+}
+
+/// Tint the icon of a row by `severity`, or restore its colors
+///
+/// Full-color icons ignore the CSS color, so a tinted icon is switched to
+/// its `-symbolic` variant; GTK falls back to the full-color icon if the
+/// theme has no symbolic one.
+pub fn set_icon_severity(image: &Image, severity: Severity) {
+    for tinted in Severity::TINTED {
+        if let Some(class) = tinted.css_class() {
+            image.remove_css_class(class);
+        }
+    }
+    let Some(class) = severity.css_class() else {
+        return;
+    };
+    image.add_css_class(class);
+    if let Some(name) = image.icon_name()
+        && !name.ends_with("-symbolic")
+    {
+        image.set_icon_name(Some(&format!("{name}-symbolic")));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_of_cpu_percent() {
+        assert_eq!(Severity::of_cpu_percent(0), Severity::Normal);
+        assert_eq!(
+            Severity::of_cpu_percent(CPU_WARNING_PERCENT - 1),
+            Severity::Normal
+        );
+        assert_eq!(
+            Severity::of_cpu_percent(CPU_WARNING_PERCENT),
+            Severity::Warning
+        );
+        assert_eq!(Severity::of_cpu_percent(CPU_ERROR_PERCENT), Severity::Error);
+        assert_eq!(Severity::of_cpu_percent(250), Severity::Error);
+    }
+
+    #[test]
+    fn test_of_unit_state() {
+        assert_eq!(Severity::of_unit_state("failed"), Severity::Error);
+        assert_eq!(Severity::of_unit_state("activating"), Severity::Warning);
+        assert_eq!(Severity::of_unit_state("deactivating"), Severity::Warning);
+        assert_eq!(Severity::of_unit_state("reloading"), Severity::Warning);
+        assert_eq!(Severity::of_unit_state("active"), Severity::Success);
+        assert_eq!(Severity::of_unit_state("inactive"), Severity::Normal);
+        assert_eq!(Severity::of_unit_state("maintenance"), Severity::Normal);
+    }
+
+    #[test]
+    fn test_css_classes_are_distinct() {
+        assert_eq!(Severity::Normal.css_class(), None);
+        let mut classes: Vec<_> = Severity::TINTED
+            .iter()
+            .filter_map(|s| s.css_class())
+            .collect();
+        classes.sort_unstable();
+        classes.dedup();
+        assert_eq!(classes.len(), Severity::TINTED.len());
+    }
+}
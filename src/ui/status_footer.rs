@@ -0,0 +1,128 @@
+//! Status footer between the results and the bars
+//!
+//! A thin dim row tells how many results there are and which one is
+//! selected (see [`build_result_position`]), the mode the query is in
+//! ("Files", "Obsidian grep") and what the keys do in that mode
+//! ("Enter: open · Ctrl+Enter: open folder"). While a search provider
+//! result is selected, the provider hint takes the place of the keys.
+//!
+//! The row follows [`MainThreadModel::connect_results_changed`], so it is
+//! up to date when `populate()` finishes and after each batch a streaming
+//! search or poller appends. `[ui] show_footer = false` hides it.
+//!
+//! [`MainThreadModel::connect_results_changed`]: crate::model::main_thread::MainThreadModel::connect_results_changed
+
+use crate::app_mode::AppMode;
+use crate::launcher::DesktopApp;
+use crate::model::list_model::AppListModel;
+use crate::ui::provider_hint::build_provider_hint;
+use crate::ui::result_position::build_result_position;
+use gtk4::prelude::*;
+use gtk4::{Align, Box as GtkBox, Label, Orientation};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+/// What Enter and its modifiers do on the results of `mode`
+#[must_use]
+pub fn key_hint(mode: AppMode) -> &'static str {
+    match mode {
+        AppMode::Normal => "Enter: launch · Ctrl+Enter: run in terminal",
+        AppMode::FileSearch | AppMode::Recent => "Enter: open · Ctrl+Enter: open folder",
+        AppMode::Obsidian | AppMode::ObsidianGrep => "Enter: open in Obsidian",
+        AppMode::CustomScript => "Enter: run",
+        AppMode::Bookmarks => "Enter: open · Ctrl+Enter: copy URL",
+        AppMode::Clipboard | AppMode::Emoji | AppMode::Snippets => "Enter: copy",
+        AppMode::Windows => "Enter: switch to",
+        AppMode::Processes => "Enter: stop · Shift+Enter: kill",
+        AppMode::Projects => "Enter: open",
+        AppMode::Ssh => "Enter: connect",
+        AppMode::Units => "Enter: start or stop · Ctrl+Enter: restart",
+        AppMode::Manual => "Enter: read · Ctrl+Enter: open in terminal",
+        AppMode::Pass => "Enter: copy password · Shift+Enter: copy user name",
+    }
+}
+
+/// Build a dim footer label with the CSS class `class`
+fn footer_label(class: &str) -> Label {
+    let label = Label::new(None);
+    label.add_css_class("dim-label");
+    label.add_css_class(class);
+    label
+}
+
+/// Build the footer, kept up to date with the results of `model` and the
+/// mode in `current_mode`
+#[must_use]
+pub fn build_status_footer(
+    model: &AppListModel,
+    current_mode: &Rc<Cell<AppMode>>,
+    all_apps: &Rc<RefCell<Vec<DesktopApp>>>,
+    visible: bool,
+) -> GtkBox {
+    let footer = GtkBox::new(Orientation::Horizontal, 0);
+    footer.add_css_class("status-footer");
+    footer.set_visible(visible);
+
+    let mode = footer_label("mode-name");
+    mode.set_halign(Align::Start);
+    let keys = footer_label("key-hint");
+    keys.set_halign(Align::End);
+    keys.set_hexpand(true);
+    keys.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+    let provider_hint = build_provider_hint(model, all_apps);
+    provider_hint.set_hexpand(true);
+
+    footer.append(&build_result_position(&model.results));
+    footer.append(&mode);
+    footer.append(&keys);
+    footer.append(&provider_hint);
+
+    let update = {
+        let mode = mode.downgrade();
+        let keys = keys.downgrade();
+        let provider_hint = provider_hint.downgrade();
+        let results = model.results.clone();
+        let current_mode = current_mode.clone();
+        move || {
+            let (Some(mode), Some(keys), Some(provider_hint)) =
+                (mode.upgrade(), keys.upgrade(), provider_hint.upgrade())
+            else {
+                return;
+            };
+            let current = current_mode.get();
+            mode.set_text(current.label());
+            keys.set_text(key_hint(current));
+            keys.set_visible(results.n_items() > 0 && !provider_hint.is_visible());
+        }
+    };
+    let update = Rc::new(update);
+    let on_hint = update.clone();
+    provider_hint.connect_visible_notify(move |_| on_hint());
+    model.results.connect_results_changed(move || update());
+    footer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_hint_follows_mode() {
+        assert_eq!(
+            key_hint(AppMode::from_text(":f report")),
+            "Enter: open · Ctrl+Enter: open folder"
+        );
+        assert_eq!(
+            key_hint(AppMode::from_text(":r")),
+            key_hint(AppMode::FileSearch)
+        );
+        assert_eq!(
+            key_hint(AppMode::from_text("fire")),
+            "Enter: launch · Ctrl+Enter: run in terminal"
+        );
+        assert_eq!(
+            key_hint(AppMode::from_text(":kill")),
+            "Enter: stop · Shift+Enter: kill"
+        );
+    }
+}
@@ -0,0 +1,159 @@
+//! Rounded corners and translucency of the launcher window
+//!
+//! `[ui] corner_radius` and `opacity` are applied through CSS generated by
+//! [`surface_css`], loaded by [`SurfaceStyle`] after `style.css`. Rounded
+//! corners and a translucent background need a compositor and an RGBA
+//! visual: without them the corners are drawn over black. The realize
+//! handler of the window asks the display with [`surface_for`] and adds
+//! [`SOLID_CLASS`] when it cannot be transparent, which the generated CSS
+//! draws with square corners and an opaque background.
+
+use crate::core::config::Config;
+use gtk4::gdk;
+use gtk4::prelude::*;
+
+/// CSS class of a window that cannot be transparent
+pub const SOLID_CLASS: &str = "solid-background";
+
+/// How the window background can be drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Surface {
+    /// Corners and background may be transparent
+    Translucent,
+    /// Everything drawn must be opaque
+    Solid,
+}
+
+/// The surface a display allows, given whether it is composited and has an
+/// RGBA visual
+#[must_use]
+pub fn surface_for(composited: bool, rgba: bool) -> Surface {
+    if composited && rgba {
+        Surface::Translucent
+    } else {
+        Surface::Solid
+    }
+}
+
+/// The surface `display` allows
+#[must_use]
+pub fn display_surface(display: &gdk::Display) -> Surface {
+    surface_for(display.is_composited(), display.is_rgba())
+}
+
+/// Add or remove [`SOLID_CLASS`] on `widget` for `surface`
+pub fn apply_surface(widget: &impl IsA<gtk4::Widget>, surface: Surface) {
+    match surface {
+        Surface::Translucent => widget.remove_css_class(SOLID_CLASS),
+        Surface::Solid => {
+            log::info!("The display cannot draw transparent windows, using a solid background");
+            widget.add_css_class(SOLID_CLASS);
+        }
+    }
+}
+
+/// CSS for windows with `corner_radius` pixel corners and a background of
+/// `opacity`, from 0 (transparent) to 1 (opaque)
+///
+/// Windows with [`SOLID_CLASS`] get square corners and an opaque background
+/// whatever the settings.
+#[must_use]
+pub fn surface_css(corner_radius: u32, opacity: f64) -> String {
+    let percent = (opacity.clamp(0.0, 1.0) * 100.0).round();
+    let translucent = if percent < 100.0 {
+        format!(
+            "window.launcher-window {{\n    background-color: \
+             color-mix(in srgb, var(--window-bg-color) {percent}%, transparent);\n}}\n\
+             window.launcher-window .launcher-box,\n\
+             window.launcher-window .search-entry,\n\
+             window.launcher-window .search-entry > text {{\n    background-color: transparent;\n}}\n"
+        )
+    } else {
+        String::new()
+    };
+    format!(
+        "window.launcher-window,\n\
+         window.launcher-window .launcher-box {{\n    border-radius: {corner_radius}px;\n}}\n\
+         {translucent}\
+         window.launcher-window.{SOLID_CLASS},\n\
+         window.launcher-window.{SOLID_CLASS} .launcher-box {{\n    border-radius: 0;\n}}\n\
+         window.launcher-window.{SOLID_CLASS},\n\
+         window.launcher-window.{SOLID_CLASS} .launcher-box,\n\
+         window.launcher-window.{SOLID_CLASS} .search-entry,\n\
+         window.launcher-window.{SOLID_CLASS} .search-entry > text {{\n    \
+         background-color: var(--window-bg-color);\n}}\n"
+    )
+}
+
+/// The CSS provider of [`surface_css`], reloaded when the config changes
+#[derive(Clone)]
+pub struct SurfaceStyle {
+    provider: gtk4::CssProvider,
+}
+
+impl SurfaceStyle {
+    /// Create the provider and add it to `display`
+    #[must_use]
+    pub fn new(display: &gdk::Display) -> Self {
+        let provider = gtk4::CssProvider::new();
+        gtk4::style_context_add_provider_for_display(
+            display,
+            &provider,
+            gtk4::STYLE_PROVIDER_PRIORITY_APPLICATION,
+        );
+        Self { provider }
+    }
+
+    /// Load the CSS for the corners and opacity of `cfg`
+    pub fn apply(&self, cfg: &Config) {
+        self.provider
+            .load_from_string(&surface_css(cfg.corner_radius, cfg.opacity));
+        log::debug!(
+            "Applied corner radius {}px and opacity {}",
+            cfg.corner_radius,
+            cfg.opacity
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_surface_for() {
+        assert_eq!(surface_for(true, true), Surface::Translucent);
+        assert_eq!(surface_for(false, true), Surface::Solid);
+        assert_eq!(surface_for(true, false), Surface::Solid);
+        assert_eq!(surface_for(false, false), Surface::Solid);
+    }
+
+    #[test]
+    fn test_surface_css_radius() {
+        let css = surface_css(16, 1.0);
+        assert!(css.contains("window.launcher-window .launcher-box {\n    border-radius: 16px;"));
+        // Opaque windows keep the background of style.css
+        assert!(!css.contains("color-mix"));
+        assert!(!css.contains("transparent"));
+    }
+
+    #[test]
+    fn test_surface_css_opacity() {
+        let css = surface_css(12, 0.85);
+        assert!(css.contains("var(--window-bg-color) 85%, transparent"));
+        assert!(css.contains(".search-entry > text {\n    background-color: transparent;"));
+        assert!(surface_css(12, -1.0).contains("var(--window-bg-color) 0%, transparent"));
+        assert!(!surface_css(12, 2.0).contains("color-mix"));
+    }
+
+    #[test]
+    fn test_surface_css_solid_overrides() {
+        let css = surface_css(20, 0.5);
+        let solid = css.find(&format!(".{SOLID_CLASS}")).unwrap();
+        // The solid rules come last and are more specific than the others
+        assert!(css[..solid].contains("20px"));
+        assert!(!css[solid..].contains("20px"));
+        assert!(css[solid..].contains("border-radius: 0;"));
+        assert!(css[solid..].contains("background-color: var(--window-bg-color);"));
+    }
+}
@@ -0,0 +1,150 @@
+//! Tab completion of the query to the selected result
+//!
+//! Tab sets the query to the name of the selected app, bookmark or search
+//! provider result, or in `:f` to the path of the selected file, so the
+//! search can be refined from there. A colon command typed before the
+//! query is kept, e.g. `:f ~/src/gr` becomes `:f /home/u/src/grunner`.
+//!
+//! Shift+Tab brings back the text typed before the last completion, as
+//! long as the completed query was not edited since.
+
+use crate::app_mode::ColonCommand;
+use crate::model::command_row::CommandKind;
+use crate::model::items::{AppItem, BookmarkItem, CommandItem, SearchResultItem};
+use gtk4::SearchEntry;
+use gtk4::prelude::*;
+
+/// `query` with its text after the colon command, if any, replaced by
+/// `text`
+#[must_use]
+pub fn completed_query(query: &str, text: &str) -> String {
+    match colon_prefix(query) {
+        Some(prefix) => format!("{prefix} {text}"),
+        None => text.to_string(),
+    }
+}
+
+/// The colon command `query` starts with, e.g. `:f`
+fn colon_prefix(query: &str) -> Option<&str> {
+    query
+        .starts_with(':')
+        .then(|| query.split_whitespace().next())
+        .flatten()
+}
+
+/// The text Tab completes `query` to for the row `obj`, if the row has one
+#[must_use]
+pub fn completion(obj: &glib::Object, query: &str) -> Option<String> {
+    let text = if let Some(app) = obj.downcast_ref::<AppItem>() {
+        app.name()
+    } else if let Some(bookmark) = obj.downcast_ref::<BookmarkItem>() {
+        bookmark.title()
+    } else if let Some(result) = obj.downcast_ref::<SearchResultItem>() {
+        result.name()
+    } else if let Some(file) = obj.downcast_ref::<CommandItem>() {
+        let command = colon_prefix(query).and_then(|p| ColonCommand::from_name(&p[1..]));
+        // Only `:f` searches by path; messages are not files
+        if command != Some(ColonCommand::FileSearch) || file.kind() != CommandKind::File {
+            return None;
+        }
+        file.payload().path()?.to_string_lossy().into_owned()
+    } else {
+        return None;
+    };
+    (!text.is_empty()).then(|| completed_query(query, &text))
+}
+
+/// The text typed before the last completion, for Shift+Tab
+#[derive(Debug, Default)]
+pub struct CompletionUndo {
+    /// The typed text and the query it was completed to
+    last: Option<(String, String)>,
+}
+
+impl CompletionUndo {
+    /// Remember that `typed` was completed to `completed`
+    pub fn record(&mut self, typed: String, completed: String) {
+        self.last = Some((typed, completed));
+    }
+
+    /// The typed text to bring back, if the query is still `current`, the
+    /// last completion; the completion is forgotten either way
+    pub fn undo(&mut self, current: &str) -> Option<String> {
+        let (typed, completed) = self.last.take()?;
+        (completed == current).then_some(typed)
+    }
+}
+
+/// Complete the query of `entry` to the row `obj`, returning whether it
+/// changed
+pub fn complete(entry: &SearchEntry, obj: &glib::Object, undo: &mut CompletionUndo) -> bool {
+    let typed = entry.text().to_string();
+    let Some(completed) = completion(obj, &typed) else {
+        return false;
+    };
+    if completed == typed {
+        return false;
+    }
+    entry.set_text(&completed);
+    entry.set_position(-1);
+    undo.record(typed, completed);
+    true
+}
+
+/// Bring back the text typed before the last completion, returning whether
+/// there was one to bring back
+pub fn undo_completion(entry: &SearchEntry, undo: &mut CompletionUndo) -> bool {
+    let Some(typed) = undo.undo(&entry.text()) else {
+        return false;
+    };
+    entry.set_text(&typed);
+    entry.set_position(-1);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completed_query_keeps_colon_command() {
+        assert_eq!(completed_query("fire", "Firefox"), "Firefox");
+        assert_eq!(
+            completed_query(":f ~/src/gr", "/home/u/src/grunner"),
+            ":f /home/u/src/grunner"
+        );
+        assert_eq!(
+            completed_query(":b  rust", "The Rust Book"),
+            ":b The Rust Book"
+        );
+        assert_eq!(completed_query(":b", "Docs"), ":b Docs");
+    }
+
+    #[test]
+    fn test_colon_prefix() {
+        assert_eq!(colon_prefix(":f notes"), Some(":f"));
+        assert_eq!(colon_prefix("notes :f"), None);
+        assert_eq!(colon_prefix(""), None);
+    }
+
+    #[test]
+    fn test_undo_restores_typed_text_once() {
+        let mut undo = CompletionUndo::default();
+        assert_eq!(undo.undo("Firefox"), None);
+
+        undo.record("fire".to_string(), "Firefox".to_string());
+        assert_eq!(undo.undo("Firefox"), Some("fire".to_string()));
+        assert_eq!(undo.undo("Firefox"), None);
+    }
+
+    #[test]
+    fn test_undo_after_edit_does_nothing() {
+        let mut undo = CompletionUndo::default();
+        undo.record("fire".to_string(), "Firefox".to_string());
+        assert_eq!(undo.undo("Firefox dev"), None);
+        // Only the last completion is kept
+        undo.record("a".to_string(), "Alpha".to_string());
+        undo.record("Alpha".to_string(), ":f /alpha".to_string());
+        assert_eq!(undo.undo(":f /alpha"), Some("Alpha".to_string()));
+    }
+}
@@ -14,18 +14,44 @@
 //! - Application lifecycle and focus management
 //! - Background application loading with threading
 
-use crate::app_mode::AppMode;
+use crate::app_mode::{ActiveMode, AppMode};
 use crate::core::callbacks::AppCallbacks;
 use crate::core::config::Config;
-use crate::item_activation::activate_item;
+use crate::item_activation::{
+    AfterActivation, activate_item, copy_pass_login, create_vault_and_retry, guard_command,
+    record_mode_usage, refuse_invalid_obsidian_action, run_first_alternative, signal_process,
+    toggle_grunner_action,
+};
 use crate::launcher;
+use crate::model::command_row::{CommandKind, CommandPayload};
+use crate::model::debounce::DEFAULT_SEARCH_DEBOUNCE_MS;
+use crate::model::items::{
+    ColonCommandItem, CommandItem, GrunnerActionItem, ManPageItem, ObsidianActionItem, ProcessItem,
+    SectionHeaderItem, SuggestionItem, TerminalCommandItem, VaultSetupItem,
+};
 use crate::model::list_model::AppListModel;
-use crate::ui::obsidian_bar::build_obsidian_bar;
+use crate::model::worker::RunOrder;
+use crate::processes::KillSignal;
+use crate::providers::matcher::build_matcher;
+use crate::query_history::HistoryBrowser;
+use crate::ui::animation::ListAnimations;
+use crate::ui::compat::{Application, ApplicationWindow, Toast, ToastOverlay, set_window_content};
+use crate::ui::debug_overlay::DebugOverlay;
+use crate::ui::layer_shell;
+use crate::ui::man_viewer::ManViewer;
+use crate::ui::mnemonics::{assign_mnemonics, build_mnemonic_hint, hint_text};
+use crate::ui::navigation::{Direction, KeyRepeat, ScrollCoalescer, visible_rows};
+use crate::ui::obsidian_bar::{self, build_obsidian_bar};
 use crate::ui::pinned_strip::{
     build_pinned_strip, launch_pinned_by_index, update_strip_visibility,
 };
-use crate::ui::power_bar::build_power_bar;
+use crate::ui::power_bar::{self, build_power_bar};
+use crate::ui::quick_select::{is_ctrl_key, number_key_index, quick_position, show_quick_keys};
+use crate::ui::status_footer::build_status_footer;
+use crate::ui::surface::{SurfaceStyle, apply_surface, display_surface};
+use crate::ui::tab_complete::{CompletionUndo, complete, undo_completion};
 use crate::ui::window_context::{PinnedUiState, WindowContext};
+use crate::ui::window_height::{WindowSizing, row_height, text_scale};
 use crate::ui::workspace_bar::build_workspace_bar;
 use glib::clone;
 
@@ -33,11 +59,9 @@ use gtk4::gdk;
 use gtk4::gdk::Key;
 use gtk4::prelude::*;
 use gtk4::{
-    Align, Box as GtkBox, CssProvider, Entry, EventControllerKey, EventControllerMotion, Image,
-    ListView, Orientation, Revealer, RevealerTransitionType, ScrolledWindow,
+    Align, Box as GtkBox, CssProvider, EventControllerKey, EventControllerMotion, Image, ListView,
+    Orientation, PolicyType, Revealer, RevealerTransitionType, ScrolledWindow, SearchEntry,
 };
-use libadwaita::prelude::AdwApplicationWindowExt;
-use libadwaita::{Application, ApplicationWindow, ToastOverlay};
 use log::{debug, info};
 use std::cell::{Cell, RefCell};
 use std::rc::Rc;
@@ -48,14 +72,43 @@ use std::rc::Rc;
 
 /// Initialize the data model
 fn setup_model(cfg: &Config) -> AppListModel {
-    AppListModel::new(
+    let model = AppListModel::new(
         cfg.max_results,
         cfg.obsidian.clone(),
         cfg.command_debounce_ms,
         cfg.search_provider_blacklist.clone(),
         cfg.commands.clone(),
         cfg.disable_modes,
-    )
+        cfg.auto_launch_threshold,
+    );
+    model.config.set_usage_stats(cfg.usage_stats);
+    model.config.set_clipboard_history(cfg.clipboard_history);
+    model.config.set_query_history(cfg.query_history);
+    model
+        .config
+        .file_results_layout
+        .set(cfg.file_results_layout);
+    model.config.show_kind_badges.set(cfg.show_kind_badges);
+    model.config.show_app_origin.set(cfg.show_app_origin);
+    model.config.terminal_prefix.set(cfg.terminal_prefix);
+    model.config.angle_unit.set(cfg.angle_unit);
+    (*model.config.web_searches.borrow_mut()).clone_from(&cfg.web_searches);
+    (*model.config.snippets.borrow_mut()).clone_from(&cfg.snippets);
+    (*model.config.pinned_apps.borrow_mut()).clone_from(&cfg.pinned_apps);
+    (*model.config.app_blacklist.borrow_mut()).clone_from(&cfg.app_blacklist);
+    (*model.config.provider_settings.borrow_mut()).clone_from(&cfg.provider_settings);
+    model
+        .config
+        .prefer_focus_running
+        .set(cfg.prefer_focus_running);
+    model.config.close_on_copy.set(cfg.close_on_copy);
+    model
+        .config
+        .escape_clears_first
+        .set(cfg.escape_clears_first);
+    model.config.apply_performance(cfg);
+    *model.config.matcher.borrow_mut() = build_matcher(cfg.fuzzy_case, cfg.fuzzy_element_limit);
+    model
 }
 
 /// Create the main application window
@@ -71,17 +124,28 @@ fn create_window(app: &Application, cfg: &Config) -> ApplicationWindow {
 
     // Apply custom CSS class for window styling
     window.set_css_classes(&["launcher-window"]);
-    // Remove default background class on realize for clean appearance
+    // Remove default background class on realize for clean appearance, and
+    // fall back to a solid background before the first draw if the display
+    // cannot draw transparent corners
     window.connect_realize(|w| {
         w.remove_css_class("background");
+        apply_surface(w, display_surface(&WidgetExt::display(w)));
+    });
+    // Compositors can be started and stopped while the launcher runs
+    let weak = window.downgrade();
+    WidgetExt::display(&window).connect_composited_notify(move |display| {
+        if let Some(w) = weak.upgrade() {
+            apply_surface(&w, display_surface(display));
+        }
     });
 
     // Intercept close requests to hide window instead of destroying it
     window.connect_close_request(move |win| {
-        win.hide();
+        win.set_visible(false);
         glib::Propagation::Stop
     });
 
+    layer_shell::init(&window, cfg);
     window
 }
 
@@ -196,14 +260,20 @@ fn build_right_sidebar(
 }
 
 /// Build the main layout: search entry, pinned strip, results list, and action bars
+#[allow(clippy::too_many_arguments)]
 fn build_main_layout(
     window: &ApplicationWindow,
-    entry: &Entry,
+    entry: &SearchEntry,
     model: &AppListModel,
     cfg: &Config,
     callbacks: &AppCallbacks,
     pinned_apps: &Rc<RefCell<Vec<String>>>,
     dragging: &Rc<Cell<bool>>,
+    debug_overlay: &DebugOverlay,
+    animations: &ListAnimations,
+    all_apps: &Rc<RefCell<Vec<launcher::DesktopApp>>>,
+    man_viewer: &ManViewer,
+    current_mode: &Rc<Cell<AppMode>>,
 ) -> (
     GtkBox,
     ListView,
@@ -248,8 +318,18 @@ fn build_main_layout(
     let pinned_strip = build_pinned_strip();
 
     // --- Action Bars and Results List ---
+    // Both bars can be shown at once, so their Alt+letter keys are given
+    // out together
+    let bar_labels: Vec<&str> = power_bar::BUTTON_LABELS
+        .iter()
+        .chain(&obsidian_bar::BUTTON_LABELS)
+        .copied()
+        .collect();
+    let mnemonics = assign_mnemonics(&bar_labels);
+    let (power_mnemonics, obsidian_mnemonics) = mnemonics.split_at(power_bar::BUTTON_LABELS.len());
+
     // Build Obsidian action bar (shown when in Obsidian mode)
-    let obsidian_bar = build_obsidian_bar(window, entry, model);
+    let obsidian_bar = build_obsidian_bar(window, entry, model, obsidian_mnemonics);
 
     // Get current icon theme for button icons
     let display = gtk4::prelude::WidgetExt::display(window);
@@ -260,32 +340,61 @@ fn build_main_layout(
     let power_bar = if cfg.disable_modes {
         None
     } else {
-        Some(build_power_bar(window, entry, &icon_theme, callbacks))
+        Some(build_power_bar(
+            window,
+            entry,
+            &icon_theme,
+            callbacks,
+            power_mnemonics,
+        ))
     };
 
     // Create list view factory for rendering result items
-    let active_mode = model.active_mode();
-    let vault_path = model.config.obsidian_cfg.as_ref().map(|cfg| {
-        crate::utils::expand_home(&cfg.vault)
-            .to_string_lossy()
-            .into_owned()
-    });
-    let factory = crate::ui::list_factory::create_factory(active_mode, vault_path);
+    let factory = crate::ui::list_factory::create_factory(
+        model.state.clone(),
+        model.config.file_results_layout.clone(),
+        model.config.show_kind_badges.clone(),
+        model.config.show_app_origin.clone(),
+        model.config.app_origins.clone(),
+        animations.clone(),
+    );
     // Create list view with selection model and custom factory
-    let list_view = ListView::new(Some(model.selection.clone()), Some(factory));
+    let list_view = ListView::new(Some(model.results.selection_model().clone()), Some(factory));
     list_view.set_single_click_activate(false); // Require double-click/Enter to activate
     list_view.add_css_class("app-list");
     list_view.set_can_focus(false); // Keep focus on search entry
 
-    // Wrap list view in scrolled window for vertical scrolling
+    // Wrap list view in scrolled window for vertical scrolling. Overlay
+    // scrollbars only show on pointer motion; a classic one stays visible
+    // while arrowing through a list longer than the window
     let scrolled = ScrolledWindow::builder()
         .vexpand(true)
+        .hscrollbar_policy(PolicyType::Never)
+        .vscrollbar_policy(PolicyType::Automatic)
+        .overlay_scrolling(false)
         .child(&list_view)
         .build();
+    scrolled.add_css_class("results-scroll");
+
+    // Result count and mode on the left, keys or provider hint on the right
+    let footer = build_status_footer(model, current_mode, all_apps, cfg.show_footer);
+    let weak_footer = footer.downgrade();
+    callbacks.connect_config_changed(move |_| {
+        if let Some(footer) = weak_footer.upgrade() {
+            footer.set_visible(crate::core::config::load().show_footer);
+        }
+    });
 
     // Assemble all UI components in order:
-    //   search entry → results → obsidian bar → power bar
-    content.append(&scrolled);
+    //   search entry → results (or the :man viewer) → status footer →
+    //   power bar keys → obsidian bar → power bar
+    man_viewer.set_results(&scrolled);
+    content.append(man_viewer.widget());
+    content.append(&footer);
+    if power_bar.is_some() {
+        let hint = hint_text(&power_bar::BUTTON_LABELS, power_mnemonics);
+        content.append(&build_mnemonic_hint(window, &hint));
+    }
     content.append(&obsidian_bar);
     if let Some(ref pb) = power_bar {
         entry_box.append(pb);
@@ -295,10 +404,15 @@ fn build_main_layout(
     let right_sidebar = build_right_sidebar(&pinned_strip, pinned_apps, dragging);
     root.append(&right_sidebar);
 
+    // The F12 timing overlay floats above everything else
+    let overlay = gtk4::Overlay::new();
+    overlay.set_child(Some(&root));
+    overlay.add_overlay(debug_overlay.widget());
+
     // Set root container as window content, wrapped in toast overlay
     let toast_overlay = ToastOverlay::new();
-    toast_overlay.set_child(Some(&root));
-    window.set_content(Some(&toast_overlay));
+    toast_overlay.set_child(Some(&overlay));
+    set_window_content(window, &toast_overlay);
 
     (
         root,
@@ -313,12 +427,19 @@ fn build_main_layout(
 /// Connect window lifecycle signals
 pub(crate) fn connect_window_signals(
     window: &ApplicationWindow,
-    entry: &Entry,
+    entry: &SearchEntry,
     obsidian_bar: &GtkBox,
     command_icon: &Image,
     model: &AppListModel,
     current_mode: &Rc<Cell<AppMode>>,
 ) {
+    // Stop searches still running when the window is hidden
+    window.connect_hide(clone!(
+        #[strong]
+        model,
+        move |_| model.cancel_pending()
+    ));
+
     // Reset UI state each time window is shown
     window.connect_show(clone!(
         #[weak]
@@ -333,9 +454,9 @@ pub(crate) fn connect_window_signals(
         current_mode,
         move |_| {
             // Clear search text and results
+            current_mode.set(AppMode::Normal);
             entry.set_text("");
             model.populate("");
-            current_mode.set(AppMode::Normal);
 
             // Hide special UI elements
             obsidian_bar.set_visible(false);
@@ -357,7 +478,7 @@ pub(crate) fn connect_window_signals(
 
 /// Connect search entry signals (text changes, icon updates)
 pub(crate) fn connect_search_signals(
-    entry: &Entry,
+    entry: &SearchEntry,
     model: &AppListModel,
     current_mode: &Rc<Cell<AppMode>>,
     obsidian_bar: &GtkBox,
@@ -401,8 +522,23 @@ pub(crate) fn connect_search_signals(
             let pinned = pinned_apps_clone.borrow();
             update_strip_visibility(&pinned_strip, &pinned, text.is_empty());
 
-            // Schedule the expensive store rebuild with debounce for default search
-            model.schedule_populate(&text);
+            // Empty queries (including the clear icon) and colon commands
+            // rebuild right away; plain searches wait for search-changed
+            if !AppListModel::is_debounced_query(&text) {
+                model.schedule_populate(&text);
+            }
+        }
+    ));
+
+    // Default search: the entry debounces search-changed by its search delay
+    entry.connect_search_changed(clone!(
+        #[strong]
+        model,
+        move |e| {
+            let text = e.text().to_string().to_lowercase();
+            if AppListModel::is_debounced_query(&text) {
+                model.populate(&text);
+            }
         }
     ));
 }
@@ -410,68 +546,333 @@ pub(crate) fn connect_search_signals(
 /// Connect list view activation signals (mouse double-click)
 pub(crate) fn connect_list_signals(
     list_view: &ListView,
+    entry: &SearchEntry,
     window: &ApplicationWindow,
     model: &AppListModel,
     current_mode: &Rc<Cell<AppMode>>,
+    man_viewer: &ManViewer,
+    toast_overlay: &ToastOverlay,
 ) {
     // Handle item activation via mouse double-click
     list_view.connect_activate(clone!(
+        #[weak]
+        entry,
         #[weak]
         window,
         #[strong]
         model,
         #[strong]
+        toast_overlay,
+        #[strong]
         current_mode,
+        #[strong]
+        man_viewer,
         move |_, pos| {
             let timestamp = gdk::CURRENT_TIME;
-            if let Some(obj) = model.store.item(pos) {
-                activate_item(&obj, &model, current_mode.get(), timestamp);
+            if let Some(obj) = model.results.item(pos) {
+                if let Some(close) = signal_process_row(&obj, false, &model) {
+                    window.set_visible(!close);
+                    return;
+                }
+                if show_man_page_row(&obj, &man_viewer, &model) {
+                    return;
+                }
+                if activate_in_place(&obj, &entry, &model) {
+                    return;
+                }
+                let after = activate_item(&obj, &model, current_mode.get(), timestamp);
+                finish_activation(after, &window, &toast_overlay);
+                return;
             }
-            window.hide();
+            window.set_visible(false);
         }
     ));
 }
 
-/// Scroll the list view to ensure a selected item is visible
+/// Hide the window after an activation, or keep it open as `after` says
+pub(crate) fn finish_activation(
+    after: AfterActivation,
+    window: &ApplicationWindow,
+    toast_overlay: &ToastOverlay,
+) {
+    match after {
+        AfterActivation::Close => window.set_visible(false),
+        AfterActivation::StayOpen => {}
+        AfterActivation::Copied => {
+            toast_overlay.add_toast(Toast::builder().title("Copied").timeout(1).build());
+        }
+    }
+}
+
+/// Stop the process of a `:kill` row: `SIGTERM`, or `SIGKILL` if `force`
 ///
-/// This function updates the selection model and triggers GTK's
-/// built-in scrolling action to bring the selected item into view.
-/// It's used for keyboard navigation (arrow keys, page up/down).
+/// Returns `None` if `obj` is not a process row, otherwise whether the
+/// window should close; it stays open on the error row shown when the
+/// process is gone.
+fn signal_process_row(obj: &glib::Object, force: bool, model: &AppListModel) -> Option<bool> {
+    let item = obj.downcast_ref::<ProcessItem>()?;
+    let signal = if force {
+        KillSignal::Kill
+    } else {
+        KillSignal::Term
+    };
+    Some(signal_process(item, signal, model))
+}
+
+/// Show the page of a `:man` row in the viewer, keeping the window open
 ///
-/// # Arguments
-/// * `model` - The application list model containing selection state
-/// * `list_view` - The GTK `ListView` widget to scroll
-/// * `pos` - Position (index) of the item to select and scroll to
-fn scroll_selection_to(model: &AppListModel, list_view: &ListView, pos: u32) {
-    // Update selection model
-    model.selection.set_selected(pos);
-    // Trigger GTK's scroll-to-item action
-    let _ = list_view.activate_action("list.scroll-to-item", Some(&pos.to_variant()));
+/// Returns `false` if `obj` is not a manual page row.
+fn show_man_page_row(obj: &glib::Object, man_viewer: &ManViewer, model: &AppListModel) -> bool {
+    let Some(item) = obj.downcast_ref::<ManPageItem>() else {
+        return false;
+    };
+    man_viewer.open(item);
+    record_mode_usage(model);
+    true
+}
+
+/// Activate rows that act on the launcher itself instead of launching
+///
+/// A "Did you mean …?" row puts its text into the search entry, whose change
+/// handlers then re-run the search, and so does a palette row with `:cmd `. A missing-vault row creates the vault and
+/// runs the pending command again, and a `:grunner` row turns its setting
+/// on or off and lists the rows again. An Obsidian action whose note path
+/// escapes the vault shows an error row instead of running. A `!` row
+/// without a command does nothing.
+/// Returns `true` if `obj` was handled here,
+/// in which case the window stays open.
+fn activate_in_place(obj: &glib::Object, entry: &SearchEntry, model: &AppListModel) -> bool {
+    if let Some(item) = obj.downcast_ref::<SuggestionItem>() {
+        entry.set_text(&item.replacement());
+        entry.set_position(-1);
+        true
+    } else if let Some(item) = obj.downcast_ref::<ColonCommandItem>() {
+        entry.set_text(&item.entry_text());
+        entry.set_position(-1);
+        true
+    } else if let Some(item) = obj.downcast_ref::<VaultSetupItem>() {
+        create_vault_and_retry(item, model);
+        true
+    } else if let Some(item) = obj.downcast_ref::<GrunnerActionItem>() {
+        toggle_grunner_action(item, model);
+        true
+    } else if let Some(item) = obj.downcast_ref::<ObsidianActionItem>() {
+        refuse_invalid_obsidian_action(item.action(), model)
+    } else if let Some(item) = obj.downcast_ref::<CommandItem>() {
+        guard_command(item, model)
+    } else {
+        obj.downcast_ref::<TerminalCommandItem>()
+            .is_some_and(|item| item.command().trim().is_empty())
+    }
+}
+
+/// Rows moved by Page Up/Down in `mode`: as many as the list shows
+///
+/// Single-line file rows fit twice as many results on screen, so a page
+/// covers twice as many of them.
+fn page_step(model: &AppListModel, list_view: &ListView, mode: AppMode) -> u32 {
+    let zoom = gtk4::Settings::default().map_or(1.0, |s| text_scale(s.gtk_xft_dpi()));
+    let layout = model.config.file_results_layout.get();
+    visible_rows(list_view.height(), row_height(mode, layout, zoom))
+}
+
+/// Position `step` rows below `pos`, stopping at the last row and skipping
+/// section headers, or `None` when the selection cannot move down
+fn step_down(model: &AppListModel, pos: u32, step: u32) -> Option<u32> {
+    let last = model.results.n_items().checked_sub(1)?;
+    let next = pos.saturating_add(step).min(last);
+    let next = skip_header_down(model, next).or_else(|| skip_header_up(model, next))?;
+    (next > pos || pos == gtk4::INVALID_LIST_POSITION).then_some(next)
+}
+
+/// Position `step` rows above `pos`, skipping section headers, or `None`
+/// at the top of the list
+fn step_up(model: &AppListModel, pos: u32, step: u32) -> Option<u32> {
+    if pos == 0 || pos == gtk4::INVALID_LIST_POSITION {
+        return None;
+    }
+    let prev = pos.saturating_sub(step);
+    skip_header_up(model, prev)
+        .or_else(|| skip_header_down(model, prev))
+        .filter(|&prev| prev < pos)
+}
+
+/// Whether the row at `pos` is a section header, which is never selected
+fn is_section_header(model: &AppListModel, pos: u32) -> bool {
+    model
+        .results
+        .item(pos)
+        .is_some_and(|obj| obj.is::<SectionHeaderItem>())
+}
+
+/// `pos`, or the row after it if `pos` is a section header
+///
+/// Used when moving down or a page down, where the row below a header is
+/// the first one of its section. Returns `None` past the end of the list.
+fn skip_header_down(model: &AppListModel, pos: u32) -> Option<u32> {
+    let pos = if is_section_header(model, pos) {
+        pos + 1
+    } else {
+        pos
+    };
+    (pos < model.results.n_items()).then_some(pos)
+}
+
+/// `pos`, or the row before it if `pos` is a section header
+///
+/// Used when moving up; the header at the top of the list has no row
+/// before it, so `None` is returned.
+fn skip_header_up(model: &AppListModel, pos: u32) -> Option<u32> {
+    if !is_section_header(model, pos) {
+        return Some(pos);
+    }
+    pos.checked_sub(1)
+        .filter(|&prev| !is_section_header(model, prev))
+}
+
+/// Whether the search entry's cursor is after the last character
+fn cursor_at_end(entry: &SearchEntry) -> bool {
+    usize::try_from(entry.position()).is_ok_and(|pos| pos >= entry.text().chars().count())
+}
+
+/// Replace the query with the result of the selected calculator row, so
+/// the next operation can be typed after it
+///
+/// Setting the text refreshes the results through the entry's change
+/// handler, like typing would. Returns `false` if the selected row is not
+/// a calculator result.
+fn calculate_in_place(entry: &SearchEntry, model: &AppListModel) -> bool {
+    if model.active_mode() != ActiveMode::None {
+        return false;
+    }
+    let Some(item) = model
+        .results
+        .item(model.results.selected())
+        .and_downcast::<CommandItem>()
+        .filter(|item| item.kind() == CommandKind::Calculator)
+    else {
+        return false;
+    };
+    let CommandPayload::Text(value) = item.payload() else {
+        return false;
+    };
+    entry.set_text(&value);
+    entry.set_position(-1);
+    true
+}
+
+/// Whether Up has nowhere to go: there are no results, or no row above the
+/// selected one
+///
+/// Up in an empty query recalls the query history only then, and otherwise
+/// moves the selection.
+fn at_top_of_results(model: &AppListModel) -> bool {
+    let selected = model.results.selected();
+    model.results.n_items() == 0 || step_up(model, selected, 1).is_none()
+}
+
+/// Put the query before (`older`) or after the one shown from the query
+/// history in `entry`, returning whether there was one
+fn recall_query(
+    entry: &SearchEntry,
+    model: &AppListModel,
+    browser: &mut HistoryBrowser,
+    older: bool,
+) -> bool {
+    let queries = &model.config.queries.borrow().queries;
+    let Some(query) = browser.step(queries, &entry.text(), older) else {
+        return false;
+    };
+    entry.set_text(&query);
+    entry.set_position(-1);
+    true
 }
 
 /// Set up keyboard event controller for search entry navigation
 ///
 /// This creates an `EventControllerKey` that handles keyboard navigation:
-/// - Escape: close window
+/// - Escape: clear the query, or close the window if it is already empty
 /// - Enter: activate selected item
-/// - Arrow keys: move selection up/down
-/// - Page Up/Down: jump 10 items
-/// - Alt+1..Alt+9: launch N-th pinned app
+/// - Ctrl+Enter: run the first alternative action of the selected row, see
+///   [`crate::item_activation::AltAction`], e.g. open the app of a search
+///   provider result with the search applied or restart a `:unit` row
+/// - Shift+Enter: activate and keep the window open; a `:kill` process gets
+///   `SIGKILL` instead of `SIGTERM`, and a `:pw` entry copies its login
+/// - Enter on a `:man` row: show the page in the viewer, where the arrow
+///   and Page keys scroll and Escape returns to the results
+/// - Arrow keys: move selection up/down, faster while held down
+/// - Page Up/Down: jump as many rows as the list shows, gliding there when
+///   animations are on
+/// - Tab: put the result of a selected calculator row in the query, else
+///   complete the query to the selected row, see [`crate::ui::tab_complete`];
+///   once complete, show or hide the desktop actions of the selected app.
+///   Tab never moves the focus
+/// - Shift+Tab: bring back the query typed before the last completion
+/// - Alt+Up / Alt+Down: show the previous / next query of the query history,
+///   see [`crate::query_history`]; Up in an empty query shows the last one
+///   when there are no results or the first one is selected
+/// - Ctrl+N: show the next query, as Alt+Down (Ctrl+P pins the selected
+///   app)
+/// - Right (with the cursor at the end of the query): show them
+/// - Ctrl+1..Ctrl+9: activate the N-th result shown; holding Ctrl shows the
+///   numbers on the rows
+/// - Alt+1..Alt+9: launch N-th pinned app; Alt+letter is left to the
+///   mnemonics of the power and Obsidian bars
+/// - F12: show or hide the timing overlay
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn setup_keyboard_controller(
     list_view: &ListView,
+    entry: &SearchEntry,
     window: &ApplicationWindow,
     model: &AppListModel,
     current_mode: &Rc<Cell<AppMode>>,
     pinned_apps: &Rc<RefCell<Vec<String>>>,
     all_apps: &Rc<RefCell<Vec<launcher::DesktopApp>>>,
+    debug_overlay: &DebugOverlay,
+    animations: &ListAnimations,
+    man_viewer: &ManViewer,
+    toast_overlay: &ToastOverlay,
 ) {
     let key_ctrl = EventControllerKey::new();
     key_ctrl.set_propagation_phase(gtk4::PropagationPhase::Capture);
+    let key_repeat = Rc::new(Cell::new(KeyRepeat::default()));
+    let scroller = ScrollCoalescer::default();
+    let completion_undo = Rc::new(RefCell::new(CompletionUndo::default()));
+    let history_browser = Rc::new(RefCell::new(HistoryBrowser::default()));
+
+    key_ctrl.connect_key_released(clone!(
+        #[strong]
+        key_repeat,
+        #[weak]
+        list_view,
+        move |_, key, _, _| {
+            let mut state = key_repeat.get();
+            state.release();
+            key_repeat.set(state);
+            if is_ctrl_key(key) {
+                show_quick_keys(&list_view, false);
+            }
+        }
+    ));
+    // Ctrl may be released in another window
+    window.connect_is_active_notify(clone!(
+        #[weak]
+        list_view,
+        move |window| {
+            if !window.is_active() {
+                show_quick_keys(&list_view, false);
+            }
+        }
+    ));
 
+    // Capture phase: runs before the search entry's own Escape (stop-search)
+    // binding, so the two never both act on the same key press
     key_ctrl.connect_key_pressed(clone!(
         #[weak]
         list_view,
         #[weak]
+        entry,
+        #[weak]
         window,
         #[strong]
         model,
@@ -481,70 +882,212 @@ pub(crate) fn setup_keyboard_controller(
         pinned_apps,
         #[strong]
         all_apps,
+        #[strong]
+        debug_overlay,
+        #[strong]
+        animations,
+        #[strong]
+        key_repeat,
+        #[strong]
+        man_viewer,
+        #[strong]
+        toast_overlay,
+        #[strong]
+        completion_undo,
+        #[strong]
+        history_browser,
         #[upgrade_or]
         glib::Propagation::Proceed,
-        move |_, key, _, modifier_state| {
+        move |controller, key, _, modifier_state| {
+            if let Some(propagation) = man_viewer.handle_key(key, modifier_state, &window) {
+                return propagation;
+            }
+
+            // Rows an arrow key moves, more while it is held down
+            let arrow_step = |direction| {
+                let mut state = key_repeat.get();
+                let step = state.press(direction, controller.current_event_time());
+                key_repeat.set(state);
+                step
+            };
+
+            // Activate the row at `pos` as Enter does, with `modifier_state`
+            let activate_row = |pos: u32, modifier_state: gdk::ModifierType| {
+                let timestamp = gdk::CURRENT_TIME;
+                let Some(obj) = model.results.item(pos) else {
+                    window.set_visible(false);
+                    return glib::Propagation::Stop;
+                };
+                let shift = modifier_state.contains(gdk::ModifierType::SHIFT_MASK);
+                if modifier_state.contains(gdk::ModifierType::CONTROL_MASK)
+                    && let Some(mut after) = run_first_alternative(&obj, &model, timestamp)
+                {
+                    if shift {
+                        after = after.staying_open();
+                    }
+                    finish_activation(after, &window, &toast_overlay);
+                    return glib::Propagation::Stop;
+                }
+                if shift && copy_pass_login(&obj, &model) {
+                    window.set_visible(false);
+                    return glib::Propagation::Stop;
+                }
+                if let Some(close) = signal_process_row(&obj, shift, &model) {
+                    window.set_visible(!close);
+                    return glib::Propagation::Stop;
+                }
+                if show_man_page_row(&obj, &man_viewer, &model) {
+                    return glib::Propagation::Stop;
+                }
+                if activate_in_place(&obj, &entry, &model) {
+                    return glib::Propagation::Stop;
+                }
+                let mut after = activate_item(&obj, &model, current_mode.get(), timestamp);
+                if shift {
+                    after = after.staying_open();
+                }
+                finish_activation(after, &window, &toast_overlay);
+                glib::Propagation::Stop
+            };
+
+            // Holding Ctrl shows the numbers of Ctrl+1..Ctrl+9
+            if is_ctrl_key(key) {
+                show_quick_keys(&list_view, true);
+                return glib::Propagation::Proceed;
+            }
+
+            // Ctrl+1..Ctrl+9: activate the N-th result shown, as Enter (or
+            // Shift+Enter) would
+            if modifier_state.contains(gdk::ModifierType::CONTROL_MASK)
+                && let Some(index) = number_key_index(key)
+            {
+                if let Some(pos) = quick_position(&list_view, index) {
+                    show_quick_keys(&list_view, false);
+                    model.results.set_selected(pos);
+                    return activate_row(pos, modifier_state - gdk::ModifierType::CONTROL_MASK);
+                }
+                return glib::Propagation::Stop;
+            }
+
+            // Alt+Up / Alt+Down: step through the query history
+            if modifier_state.contains(gdk::ModifierType::ALT_MASK)
+                && matches!(key, Key::Up | Key::KP_Up | Key::Down | Key::KP_Down)
+            {
+                let older = matches!(key, Key::Up | Key::KP_Up);
+                recall_query(&entry, &model, &mut history_browser.borrow_mut(), older);
+                return glib::Propagation::Stop;
+            }
+
+            // Ctrl+N: the next query, as Alt+Down. Ctrl+P stays the pin
+            // toggle, so the previous one is only on Alt+Up
+            if modifier_state.contains(gdk::ModifierType::CONTROL_MASK)
+                && matches!(key, Key::n | Key::N)
+            {
+                recall_query(&entry, &model, &mut history_browser.borrow_mut(), false);
+                return glib::Propagation::Stop;
+            }
+
             // Alt+1..Alt+9: launch pinned app
             if modifier_state.contains(gdk::ModifierType::ALT_MASK) {
-                let index = match key {
-                    Key::_1 => Some(0),
-                    Key::_2 => Some(1),
-                    Key::_3 => Some(2),
-                    Key::_4 => Some(3),
-                    Key::_5 => Some(4),
-                    Key::_6 => Some(5),
-                    Key::_7 => Some(6),
-                    Key::_8 => Some(7),
-                    Key::_9 => Some(8),
-                    _ => None,
-                };
-                if let Some(idx) = index {
+                if let Some(idx) = number_key_index(key) {
                     let pinned = pinned_apps.borrow();
                     let apps = all_apps.borrow();
                     launch_pinned_by_index(idx, &pinned, &apps, &window);
                     return glib::Propagation::Stop;
                 }
+                // Alt+letter: left to the mnemonics of the bar buttons
+                if key.to_unicode().is_some_and(|c| c.is_ascii_alphabetic()) {
+                    return glib::Propagation::Proceed;
+                }
             }
 
             match key {
-                Key::Escape => {
-                    window.hide();
+                Key::F12 => {
+                    debug_overlay.toggle();
                     glib::Propagation::Stop
                 }
-                Key::Return | Key::KP_Enter => {
-                    let timestamp = gdk::CURRENT_TIME;
-                    let pos = model.selection.selected();
-                    if let Some(obj) = model.store.item(pos) {
-                        activate_item(&obj, &model, current_mode.get(), timestamp);
+                Key::Escape => {
+                    if entry.text().is_empty() || !model.config.escape_clears_first.get() {
+                        window.set_visible(false);
+                    } else {
+                        entry.set_text("");
                     }
-                    window.hide();
                     glib::Propagation::Stop
                 }
+                Key::Return | Key::KP_Enter => {
+                    activate_row(model.results.selected(), modifier_state)
+                }
                 Key::Down | Key::KP_Down => {
-                    let pos = model.selection.selected();
-                    let n = model.store.n_items();
-                    if pos + 1 < n {
-                        scroll_selection_to(&model, &list_view, pos + 1);
+                    let step = arrow_step(Direction::Down);
+                    let pos = model.results.selected();
+                    let next = if pos == gtk4::INVALID_LIST_POSITION {
+                        skip_header_down(&model, 0)
+                    } else {
+                        step_down(&model, pos, step)
+                    };
+                    if let Some(next) = next {
+                        scroller.scroll_to(&model, &list_view, next);
                     }
                     glib::Propagation::Stop
                 }
+                Key::Up | Key::KP_Up
+                    if entry.text().is_empty()
+                        && at_top_of_results(&model)
+                        && recall_query(
+                            &entry,
+                            &model,
+                            &mut history_browser.borrow_mut(),
+                            true,
+                        ) =>
+                {
+                    glib::Propagation::Stop
+                }
                 Key::Up | Key::KP_Up => {
-                    let pos = model.selection.selected();
-                    if pos > 0 {
-                        scroll_selection_to(&model, &list_view, pos - 1);
+                    let step = arrow_step(Direction::Up);
+                    if let Some(prev) = step_up(&model, model.results.selected(), step) {
+                        scroller.scroll_to(&model, &list_view, prev);
+                    }
+                    glib::Propagation::Stop
+                }
+                Key::ISO_Left_Tab => {
+                    if undo_completion(&entry, &mut completion_undo.borrow_mut()) {
+                        glib::Propagation::Stop
+                    } else {
+                        glib::Propagation::Proceed
+                    }
+                }
+                Key::Tab => {
+                    if calculate_in_place(&entry, &model) {
+                        return glib::Propagation::Stop;
+                    }
+                    let pos = model.results.selected();
+                    if let Some(obj) = model.results.item(pos)
+                        && complete(&entry, &obj, &mut completion_undo.borrow_mut())
+                    {
+                        return glib::Propagation::Stop;
                     }
+                    let expand = model.expanded_actions(pos) == 0;
+                    model.set_actions_expanded(pos, expand);
+                    glib::Propagation::Stop
+                }
+                Key::Right | Key::KP_Right
+                    if cursor_at_end(&entry)
+                        && model.set_actions_expanded(model.results.selected(), true) =>
+                {
                     glib::Propagation::Stop
                 }
                 Key::Page_Down => {
-                    let pos = model.selection.selected();
-                    let n = model.store.n_items();
-                    let next = (pos + 10).min(n.saturating_sub(1));
-                    scroll_selection_to(&model, &list_view, next);
+                    let step = page_step(&model, &list_view, current_mode.get());
+                    if let Some(next) = step_down(&model, model.results.selected(), step) {
+                        animations.scroll_to(&model, &list_view, next);
+                    }
                     glib::Propagation::Stop
                 }
                 Key::Page_Up => {
-                    let pos = model.selection.selected();
-                    scroll_selection_to(&model, &list_view, pos.saturating_sub(10));
+                    let step = page_step(&model, &list_view, current_mode.get());
+                    if let Some(prev) = step_up(&model, model.results.selected(), step) {
+                        animations.scroll_to(&model, &list_view, prev);
+                    }
                     glib::Propagation::Stop
                 }
                 _ => glib::Propagation::Proceed,
@@ -568,31 +1111,39 @@ pub(crate) fn setup_keyboard_controller(
 /// # Arguments
 /// * `app` - The GTK Application instance
 /// * `cfg` - Application configuration loaded from file or defaults
+/// * `present` - Whether to show the window now; `--daemon` starts hidden
 ///
 /// # Panics
 /// Panics if the default GDK display cannot be obtained.
-pub fn build_ui(app: &Application, cfg: &Config) {
+pub fn build_ui(app: &Application, cfg: &Config, present: bool) {
     debug!("Workspace bar enabled: {}", cfg.workspace_bar_enabled);
 
     let display = gtk4::gdk::Display::default().expect("Cannot connect to display");
     let model = setup_model(cfg);
     let current_mode = Rc::new(Cell::new(AppMode::Normal));
     let all_apps: Rc<RefCell<Vec<launcher::DesktopApp>>> = Rc::new(RefCell::new(Vec::new()));
-    let pinned_apps = Rc::new(RefCell::new(cfg.pinned_apps.clone()));
+    let pinned_apps = model.config.pinned_apps.clone();
     let dragging = Rc::new(Cell::new(false));
+    let debug_overlay = DebugOverlay::new(&model, &all_apps);
+    let animations = ListAnimations::new(cfg.animations);
+    animations.watch(&model);
+    let man_viewer = ManViewer::new();
     let window = create_window(app, cfg);
     let callbacks = AppCallbacks::new();
 
     let provider = CssProvider::new();
-    provider.load_from_data(include_str!("style.css"));
+    provider.load_from_string(include_str!("style.css"));
     gtk4::style_context_add_provider_for_display(
         &display,
         &provider,
         gtk4::STYLE_PROVIDER_PRIORITY_APPLICATION,
     );
+    let surface_style = SurfaceStyle::new(&display);
+    surface_style.apply(cfg);
 
-    let entry = Entry::builder()
+    let entry = SearchEntry::builder()
         .placeholder_text("Search applications…")
+        .search_delay(DEFAULT_SEARCH_DEBOUNCE_MS)
         .hexpand(true)
         .build();
     entry.add_css_class("search-entry");
@@ -606,6 +1157,11 @@ pub fn build_ui(app: &Application, cfg: &Config) {
             &callbacks,
             &pinned_apps,
             &dragging,
+            &debug_overlay,
+            &animations,
+            &all_apps,
+            &man_viewer,
+            &current_mode,
         );
 
     let wctx = WindowContext {
@@ -624,13 +1180,26 @@ pub fn build_ui(app: &Application, cfg: &Config) {
         all_apps: all_apps.clone(),
         pinned_apps: pinned_apps.clone(),
         dragging: dragging.clone(),
+        debug_overlay,
+        animations,
+        man_viewer,
+        scan_order: RunOrder::default(),
         theme_manager: crate::core::theme::ThemeManager::new(),
+        surface_style,
+        window_sizing: Rc::new(Cell::new(WindowSizing::from_config(cfg))),
     };
 
     wctx.setup_theme();
     wctx.wire_callbacks();
+    wctx.watch_config_file();
+    wctx.watch_app_dirs();
+    wctx.watch_clipboard();
     wctx.setup_dragging(&root);
-    window.present();
+    if present {
+        window.present();
+    }
     wctx.wire_signals();
+    wctx.watch_result_count();
     wctx.start_loading();
+    wctx.refresh_on_show();
 }
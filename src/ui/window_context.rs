@@ -1,83 +1,35 @@
 use crate::app_mode::AppMode;
+use crate::clipboard_history::{PASSWORD_MANAGER_HINT, capture_paused};
 use crate::core::callbacks::AppCallbacks;
 use crate::core::config::Config;
+use crate::core::config_include::include_files;
+use crate::first_seen::{FirstSeen, track_first_seen};
+use crate::history::now_secs;
 use crate::launcher;
 use crate::model::list_model::AppListModel;
+use crate::model::worker::{RunOrder, spawn_worker_with_updates};
 use crate::ui::context_menu::{WindowCtx, setup_list_context_menu};
-use crate::ui::pinned_strip::{update_pinned_strip, update_strip_visibility};
+use crate::ui::dir_watch::{DirWatch, OnChange, OnToggle};
+use crate::ui::icons::{IconKind, themed_icon};
+use crate::ui::pinned_strip::{PinnedStore, update_pinned_strip, update_strip_visibility};
+use crate::ui::window_height::{FALLBACK_CHROME, WindowSizing, text_scale, window_height};
 
-use gtk4::gdk;
+use crate::ui::compat::{ApplicationWindow, ToastOverlay};
 use gtk4::prelude::*;
-use gtk4::{Box as GtkBox, Entry, GestureClick, Image, ListView};
-use libadwaita::{ApplicationWindow, ToastOverlay};
-use log::{error, info, trace};
+use gtk4::{Box as GtkBox, GestureClick, Image, ListView, SearchEntry};
+use gtk4::{gdk, gio};
+use log::{debug, info, warn};
 use std::cell::{Cell, RefCell};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
 use std::rc::Rc;
 
-// ---------------------------------------------------------------------------
-// Helper functions for background processing
-// ---------------------------------------------------------------------------
-
-/// Context for app loading polling — groups all state needed when apps are ready
-struct AppLoadingContext {
-    rx: Rc<std::sync::mpsc::Receiver<Vec<launcher::DesktopApp>>>,
-    model: AppListModel,
-    all_apps: Rc<RefCell<Vec<launcher::DesktopApp>>>,
-    pinned_strip: GtkBox,
-    pinned_apps: Rc<RefCell<Vec<String>>>,
-    window: ApplicationWindow,
-    dragging: Rc<Cell<bool>>,
-    cfg: Config,
-}
+/// Quiet period after a config file change before it is reloaded
+const CONFIG_RELOAD_DELAY_MS: u64 = 200;
 
-impl Clone for AppLoadingContext {
-    fn clone(&self) -> Self {
-        Self {
-            rx: Rc::clone(&self.rx),
-            model: self.model.clone(),
-            all_apps: Rc::clone(&self.all_apps),
-            pinned_strip: self.pinned_strip.clone(),
-            pinned_apps: Rc::clone(&self.pinned_apps),
-            window: self.window.clone(),
-            dragging: Rc::clone(&self.dragging),
-            cfg: self.cfg.clone(),
-        }
-    }
-}
-
-impl AppLoadingContext {
-    fn poll(&self) {
-        match self.rx.try_recv() {
-            Ok(apps) => {
-                info!("Loaded {} applications", apps.len());
-                (*self.all_apps.borrow_mut()).clone_from(&apps);
-
-                let pinned = self.pinned_apps.borrow();
-                update_pinned_strip(
-                    &self.pinned_strip,
-                    &pinned,
-                    &apps,
-                    &self.window,
-                    &self.pinned_apps,
-                    &self.all_apps,
-                    &self.dragging,
-                    &self.cfg,
-                );
-                update_strip_visibility(&self.pinned_strip, &pinned, true);
-
-                self.model.set_apps(apps);
-            }
-            Err(std::sync::mpsc::TryRecvError::Empty) => {
-                trace!("Application loading still in progress");
-                let ctx = self.clone();
-                glib::idle_add_local_once(move || ctx.poll());
-            }
-            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
-                error!("Application loading thread terminated unexpectedly");
-            }
-        }
-    }
-}
+/// Quiet period after `.desktop` file changes before the directories are
+/// re-scanned; package upgrades touch many files in a burst
+const APP_RESCAN_DELAY_MS: u64 = 2000;
 
 // ---------------------------------------------------------------------------
 // Pinned apps UI state
@@ -105,7 +57,7 @@ pub struct WindowContext {
     pub current_mode: Rc<Cell<AppMode>>,
     pub window: ApplicationWindow,
     pub callbacks: AppCallbacks,
-    pub entry: Entry,
+    pub entry: SearchEntry,
     pub list_view: ListView,
     pub obsidian_bar: Option<GtkBox>,
     pub command_icon: Image,
@@ -114,7 +66,19 @@ pub struct WindowContext {
     pub all_apps: Rc<RefCell<Vec<launcher::DesktopApp>>>,
     pub pinned_apps: Rc<RefCell<Vec<String>>>,
     pub dragging: Rc<Cell<bool>>,
+    pub debug_overlay: crate::ui::debug_overlay::DebugOverlay,
+    pub animations: crate::ui::animation::ListAnimations,
+    /// Results list and `:man` page viewer
+    pub man_viewer: crate::ui::man_viewer::ManViewer,
+    /// Order of the background app scans, so a late one is not applied
+    /// over a newer list
+    pub scan_order: RunOrder,
     pub theme_manager: crate::core::theme::ThemeManager,
+    /// Corner radius and opacity CSS, updated when the configuration is
+    /// reloaded
+    pub surface_style: crate::ui::surface::SurfaceStyle,
+    /// Window size settings, updated when the configuration is reloaded
+    pub window_sizing: Rc<Cell<WindowSizing>>,
 }
 
 impl WindowContext {
@@ -141,11 +105,40 @@ impl WindowContext {
         );
     }
 
+    /// Add texts copied while the window is shown to the clipboard history
+    ///
+    /// Nothing is captured while the window is hidden, as it is between
+    /// uses with `--daemon`, nor while capturing is paused for a `:pw` copy,
+    /// nor for copies a password manager marked as secret.
+    pub fn watch_clipboard(&self) {
+        let model = self.model.clone();
+        let window = self.window.downgrade();
+        self.display.clipboard().connect_changed(move |clipboard| {
+            if !model.config.clipboard_history.get()
+                || !window.upgrade().is_some_and(|w| w.is_visible())
+                || capture_paused()
+                || clipboard.formats().contain_mime_type(PASSWORD_MANAGER_HINT)
+            {
+                return;
+            }
+            let model = model.clone();
+            clipboard.read_text_async(gtk4::gio::Cancellable::NONE, move |res| match res {
+                Ok(Some(text)) => model.record_clipboard(&text),
+                Ok(None) => {}
+                Err(e) => debug!("Clipboard content is not text: {e}"),
+            });
+        });
+    }
+
     pub fn wire_callbacks(&self) {
         let model = self.model.clone();
+        let animations = self.animations.clone();
+        let surface_style = self.surface_style.clone();
         self.callbacks.connect_config_changed(move |_| {
             let config = crate::core::config::load();
             model.apply_config(&config);
+            animations.set_enabled(config.animations);
+            surface_style.apply(&config);
         });
 
         let display = self.display.clone();
@@ -155,12 +148,216 @@ impl WindowContext {
             theme_manager.apply(config.theme, config.custom_theme_path.as_deref(), &display);
         });
 
-        let window = self.window.clone();
+        let ctx = self.clone();
         self.callbacks.connect_window_resized(move |_| {
             let config = crate::core::config::load();
-            window.set_resizable(true);
-            window.set_default_size(config.window_width, config.window_height);
-            window.set_resizable(false);
+            ctx.window_sizing.set(WindowSizing::from_config(&config));
+            ctx.fit_window_height();
+        });
+    }
+
+    /// Fit the window height to the results whenever they change
+    pub fn watch_result_count(&self) {
+        let ctx = self.clone();
+        self.model
+            .results
+            .connect_results_changed(move || ctx.fit_window_height());
+    }
+
+    /// Size the window for the results it shows, see [`window_height`]
+    ///
+    /// This is the only place the window is resized once it exists. The
+    /// height of everything but the list is measured once the window is
+    /// shown.
+    fn fit_window_height(&self) {
+        let sizing = self.window_sizing.get();
+        let chrome = self
+            .list_view
+            .parent()
+            .map(|list| self.window.height() - list.height())
+            .filter(|&chrome| self.window.is_mapped() && chrome > 0)
+            .unwrap_or(FALLBACK_CHROME);
+        let zoom = gtk4::Settings::default().map_or(1.0, |s| text_scale(s.gtk_xft_dpi()));
+        let height = window_height(
+            self.current_mode.get(),
+            self.model.results.n_items(),
+            &sizing,
+            chrome,
+            zoom,
+        );
+        if (self.window.default_width(), self.window.default_height()) != (sizing.width, height) {
+            // Non-resizable windows ignore a new default size
+            self.window.set_resizable(true);
+            self.window.set_default_size(sizing.width, height);
+            self.window.set_resizable(false);
+        }
+    }
+
+    /// Reload the configuration when the config file or one of its include
+    /// files changes on disk
+    ///
+    /// Editor saves arrive as several events, so they are coalesced into
+    /// one reload [`CONFIG_RELOAD_DELAY_MS`] after the last. The reload
+    /// emits the same signals as saving in the settings window. The
+    /// include files are looked up again after every reload, so includes
+    /// added or removed meanwhile are followed. The monitors live as long
+    /// as the window.
+    pub fn watch_config_file(&self) {
+        let pending: Rc<RefCell<Option<glib::SourceId>>> = Rc::new(RefCell::new(None));
+        let on_event: Rc<dyn Fn(gio::FileMonitorEvent)> = {
+            let callbacks = self.callbacks.clone();
+            Rc::new(move |event| {
+                if !matches!(
+                    event,
+                    gio::FileMonitorEvent::ChangesDoneHint
+                        | gio::FileMonitorEvent::Created
+                        | gio::FileMonitorEvent::Deleted
+                ) {
+                    return;
+                }
+                if let Some(id) = pending.borrow_mut().take() {
+                    id.remove();
+                }
+                let callbacks = callbacks.clone();
+                let done = pending.clone();
+                let id = glib::timeout_add_local_once(
+                    std::time::Duration::from_millis(CONFIG_RELOAD_DELAY_MS),
+                    move || {
+                        done.borrow_mut().take();
+                        info!("Configuration file changed, reloading");
+                        callbacks.emit_config_changed();
+                        callbacks.emit_theme_changed();
+                        callbacks.emit_window_resized();
+                    },
+                );
+                *pending.borrow_mut() = Some(id);
+            })
+        };
+
+        let monitors: Rc<RefCell<Vec<gio::FileMonitor>>> = Rc::new(RefCell::new(Vec::new()));
+        let cancel = {
+            let monitors = monitors.clone();
+            move || {
+                for monitor in monitors.borrow_mut().drain(..) {
+                    monitor.cancel();
+                }
+            }
+        };
+        let arm = {
+            let cancel = cancel.clone();
+            move || {
+                cancel();
+                let path = crate::core::config::config_path();
+                let includes = include_files(&path);
+                debug!(
+                    "Watching {} and {} include files",
+                    path.display(),
+                    includes.len()
+                );
+                for file in std::iter::once(path).chain(includes) {
+                    let monitor = match gio::File::for_path(&file)
+                        .monitor_file(gio::FileMonitorFlags::NONE, gio::Cancellable::NONE)
+                    {
+                        Ok(monitor) => monitor,
+                        Err(e) => {
+                            warn!("Cannot watch {} for changes: {e}", file.display());
+                            continue;
+                        }
+                    };
+                    let on_event = on_event.clone();
+                    monitor.connect_changed(move |_, _, _, event| on_event(event));
+                    monitors.borrow_mut().push(monitor);
+                }
+            }
+        };
+        arm();
+
+        // Reloads from the file or the settings window may change the includes
+        let handler = self.callbacks.connect_config_changed(move |_| arm());
+        let callbacks = self.callbacks.clone();
+        let handler = RefCell::new(Some(handler));
+        self.window.connect_destroy(move |_| {
+            if let Some(handler) = handler.borrow_mut().take() {
+                callbacks.disconnect(handler);
+            }
+            cancel();
+        });
+    }
+
+    /// Re-scan an application directory when its `.desktop` files change
+    ///
+    /// Changes are collected per directory and handled in one background
+    /// re-scan [`APP_RESCAN_DELAY_MS`] after the last, so apps installed or
+    /// removed while a resident window is hidden show up without a restart.
+    /// Directories that do not exist yet, such as the Flatpak exports before
+    /// the first Flatpak install, are followed through their nearest
+    /// existing parent with [`DirWatch`] and scanned once they appear.
+    /// Monitors are not recursive; subdirectories are only re-scanned along
+    /// with a change in their parent.
+    pub fn watch_app_dirs(&self) {
+        let changed: Rc<RefCell<BTreeSet<PathBuf>>> = Rc::new(RefCell::new(BTreeSet::new()));
+        let pending: Rc<RefCell<Option<glib::SourceId>>> = Rc::new(RefCell::new(None));
+        let note_change: Rc<dyn Fn(&PathBuf)> = {
+            let ctx = self.clone();
+            Rc::new(move |dir| {
+                changed.borrow_mut().insert(dir.clone());
+                if let Some(id) = pending.borrow_mut().take() {
+                    id.remove();
+                }
+                let ctx = ctx.clone();
+                let changed = changed.clone();
+                let done = pending.clone();
+                let id = glib::timeout_add_local_once(
+                    std::time::Duration::from_millis(APP_RESCAN_DELAY_MS),
+                    move || {
+                        done.borrow_mut().take();
+                        let dirs: Vec<PathBuf> = std::mem::take(&mut *changed.borrow_mut())
+                            .into_iter()
+                            .collect();
+                        ctx.rescan_app_dirs(dirs);
+                    },
+                );
+                *pending.borrow_mut() = Some(id);
+            })
+        };
+
+        let watches: Vec<DirWatch> = self
+            .cfg
+            .expanded_app_dirs()
+            .into_iter()
+            .map(|dir| {
+                let on_change: OnChange = {
+                    let note_change = note_change.clone();
+                    let dir = dir.clone();
+                    Rc::new(move |file, event| {
+                        let desktop_file = file.path().is_some_and(|path| {
+                            path.extension().is_some_and(|ext| ext == "desktop")
+                        });
+                        if desktop_file
+                            && matches!(
+                                event,
+                                gio::FileMonitorEvent::ChangesDoneHint
+                                    | gio::FileMonitorEvent::Created
+                                    | gio::FileMonitorEvent::Deleted
+                            )
+                        {
+                            note_change(&dir);
+                        }
+                    })
+                };
+                let on_toggle: OnToggle = {
+                    let note_change = note_change.clone();
+                    let dir = dir.clone();
+                    Rc::new(move || note_change(&dir))
+                };
+                DirWatch::new(dir, on_change, on_toggle)
+            })
+            .collect();
+
+        self.window.connect_destroy(move |_| {
+            for watch in &watches {
+                watch.cancel();
+            }
         });
     }
 
@@ -198,11 +395,8 @@ impl WindowContext {
         }
 
         let icon_theme = gtk4::IconTheme::for_display(&self.display);
-        let obsidian_icon_name = ["obsidian", "md.obsidian.Obsidian", "text-x-markdown"]
-            .iter()
-            .find(|&&name| icon_theme.has_icon(name))
-            .copied()
-            .unwrap_or("text-x-markdown");
+        let obsidian_icon_name =
+            themed_icon(IconKind::Obsidian, &icon_theme).unwrap_or("text-x-markdown");
 
         let pinned_ui = PinnedUiState {
             strip: self.pinned_strip.clone(),
@@ -220,39 +414,146 @@ impl WindowContext {
             );
         }
 
+        // Registered first so Enter can confirm a pending blacklist request
+        crate::ui::provider_blacklist::setup_provider_blacklist_shortcut(&self.ctx());
+        crate::ui::pinned_strip::setup_pin_shortcut(&self.ctx());
         super::window::setup_keyboard_controller(
             &self.list_view,
+            &self.entry,
             &self.window,
             &self.model,
             &self.current_mode,
             &self.pinned_apps,
             &self.all_apps,
+            &self.debug_overlay,
+            &self.animations,
+            &self.man_viewer,
+            &self.toast_overlay,
         );
+        let auto_launch = crate::ui::auto_launch::AutoLaunch::new(&self.list_view);
+        auto_launch.attach(&self.window, &self.model, &self.current_mode);
         super::window::connect_list_signals(
             &self.list_view,
+            &self.entry,
             &self.window,
             &self.model,
             &self.current_mode,
+            &self.man_viewer,
+            &self.toast_overlay,
         );
+        self.man_viewer
+            .connect_close_triggers(&self.window, &self.entry);
         setup_list_context_menu(&self.list_view, &self.ctx());
     }
 
+    /// Load applications in the background, showing the cached list first
     pub fn start_loading(&self) {
         let dirs = self.cfg.expanded_app_dirs();
-        let (tx, rx) = std::sync::mpsc::channel();
-        std::thread::spawn(move || {
-            let _ = tx.send(launcher::load_apps(&dirs));
-        });
-        let load_ctx = AppLoadingContext {
-            rx: Rc::new(rx),
-            model: self.model.clone(),
-            all_apps: self.all_apps.clone(),
-            pinned_strip: self.pinned_strip.clone(),
-            pinned_apps: self.pinned_apps.clone(),
-            window: self.window.clone(),
-            dragging: self.dragging.clone(),
-            cfg: self.cfg.clone(),
-        };
-        glib::idle_add_local_once(move || load_ctx.poll());
+        let ctx = self.clone();
+        let run = self.scan_order.start();
+        let order = self.scan_order.clone();
+        spawn_worker_with_updates(
+            move |tx| {
+                let loaded = launcher::load_apps(&dirs);
+                let first_seen = track_first_seen(&loaded.apps, now_secs());
+                if tx.send((loaded.apps, first_seen)).is_err() {
+                    return;
+                }
+                // Stale-while-revalidate: the cached list is already shown,
+                // replace it only if the .desktop files changed since
+                if let Some(snapshot) = loaded.cached_snapshot
+                    && let Some(apps) = launcher::refresh_apps(&dirs, snapshot)
+                {
+                    let first_seen = track_first_seen(&apps, now_secs());
+                    let _ = tx.send((apps, first_seen));
+                }
+            },
+            move || order.is_current(run),
+            move |(apps, first_seen)| ctx.apps_loaded(run, apps, first_seen),
+        );
+    }
+
+    /// Check the applications again each time the window is shown
+    ///
+    /// Connect this after the first `present()`; the initial list comes
+    /// from [`start_loading`](Self::start_loading).
+    pub fn refresh_on_show(&self) {
+        let ctx = self.clone();
+        self.window.connect_show(move |_| ctx.refresh_apps());
+    }
+
+    /// Re-scan the applications in the background if the `.desktop` files
+    /// changed since the list was loaded
+    fn refresh_apps(&self) {
+        let dirs = self.cfg.expanded_app_dirs();
+        let ctx = self.clone();
+        let run = self.scan_order.start();
+        let order = self.scan_order.clone();
+        spawn_worker_with_updates(
+            move |tx| {
+                if let Some(apps) = launcher::revalidate_apps(&dirs) {
+                    let first_seen = track_first_seen(&apps, now_secs());
+                    let _ = tx.send((apps, first_seen));
+                }
+            },
+            move || order.is_current(run),
+            move |(apps, first_seen)| ctx.apps_loaded(run, apps, first_seen),
+        );
+    }
+
+    /// Re-scan the `changed` application directories in the background and
+    /// merge their applications into the loaded list
+    ///
+    /// Before the first list has loaded there is nothing to merge into, so
+    /// every directory is revalidated instead.
+    fn rescan_app_dirs(&self, changed: Vec<PathBuf>) {
+        if self.all_apps.borrow().is_empty() {
+            self.refresh_apps();
+            return;
+        }
+        info!(
+            "Application directories changed: {}",
+            changed
+                .iter()
+                .map(|dir| dir.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        let dirs = self.cfg.expanded_app_dirs();
+        let current = self.all_apps.borrow().clone();
+        let ctx = self.clone();
+        let run = self.scan_order.start();
+        let order = self.scan_order.clone();
+        spawn_worker_with_updates(
+            move |tx| {
+                let apps = launcher::rescan_dirs(&dirs, &changed, current);
+                let first_seen = track_first_seen(&apps, now_secs());
+                let _ = tx.send((apps, first_seen));
+            },
+            move || order.is_current(run),
+            move |(apps, first_seen)| ctx.apps_loaded(run, apps, first_seen),
+        );
+    }
+
+    /// Show freshly loaded applications of scan `run` in the pinned strip
+    /// and results
+    fn apps_loaded(&self, run: u64, apps: Vec<launcher::DesktopApp>, first_seen: FirstSeen) {
+        info!("Loaded {} applications", apps.len());
+        self.scan_order.applied(run);
+        (*self.all_apps.borrow_mut()).clone_from(&apps);
+
+        let pinned = self.pinned_apps.borrow();
+        update_pinned_strip(
+            &self.pinned_strip,
+            &pinned,
+            &apps,
+            &self.window,
+            &self.pinned_apps,
+            &self.dragging,
+            &PinnedStore::new(&self.cfg, &self.toast_overlay),
+        );
+        update_strip_visibility(&self.pinned_strip, &pinned, true);
+
+        self.model.set_apps(apps, first_seen);
     }
 }
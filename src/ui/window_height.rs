@@ -0,0 +1,163 @@
+//! Window height that follows the number of results
+//!
+//! With `[window] auto_height` on, the launcher shrinks to the rows it shows
+//! instead of keeping the configured height, so three results get a window
+//! three rows tall; the configured height stays the upper bound. Rows are
+//! estimated rather than measured, since rows scrolled out of view are never
+//! realized: two-line rows take [`ROW_HEIGHT`], single-line file rows a
+//! fraction of it, both scaled with the text scaling factor.
+
+use crate::app_mode::AppMode;
+use crate::core::config::{Config, FileResultsLayout};
+
+/// Height of a two-line result row at a text scale of 1, margins included
+pub const ROW_HEIGHT: i32 = 52;
+
+/// Padding of the result list above and below its rows
+pub const LIST_PADDING: i32 = 12;
+
+/// Height of everything but the result list, used until the window has
+/// been laid out and it can be measured
+pub const FALLBACK_CHROME: i32 = 56;
+
+/// DPI of a text scale of 1, in the 1/1024 units of `gtk-xft-dpi`
+const BASE_XFT_DPI: f64 = 96.0 * 1024.0;
+
+/// Window size settings, as last loaded from the configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowSizing {
+    pub width: i32,
+    /// Configured height, the height of the window unless `auto_height` is
+    /// set, and its largest height otherwise
+    pub height: i32,
+    pub auto_height: bool,
+    pub file_results_layout: FileResultsLayout,
+}
+
+impl WindowSizing {
+    #[must_use]
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            width: config.window_width,
+            height: config.window_height,
+            auto_height: config.auto_height,
+            file_results_layout: config.file_results_layout,
+        }
+    }
+}
+
+/// Text scale of a `gtk-xft-dpi` value, 1 when it is unset
+#[must_use]
+pub fn text_scale(xft_dpi: i32) -> f64 {
+    if xft_dpi > 0 {
+        f64::from(xft_dpi) / BASE_XFT_DPI
+    } else {
+        1.0
+    }
+}
+
+/// Estimated height of one result row in `mode` at text scale `zoom`
+#[must_use]
+pub fn row_height(mode: AppMode, layout: FileResultsLayout, zoom: f64) -> f64 {
+    let per_row = if mode.shows_file_results() {
+        layout.rows_per_two_line_row()
+    } else {
+        1
+    };
+    f64::from(ROW_HEIGHT) * zoom / f64::from(per_row)
+}
+
+/// Height of the window showing `rows` results in `mode`
+///
+/// `chrome` is the height of the window without the result list and `zoom`
+/// the text scale. Without `auto_height` this is the configured height.
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+pub fn window_height(
+    mode: AppMode,
+    rows: u32,
+    sizing: &WindowSizing,
+    chrome: i32,
+    zoom: f64,
+) -> i32 {
+    if !sizing.auto_height {
+        return sizing.height;
+    }
+    let row_height = row_height(mode, sizing.file_results_layout, zoom);
+    let list = if rows == 0 {
+        0.0
+    } else {
+        f64::from(rows).mul_add(row_height, f64::from(LIST_PADDING))
+    };
+    let needed = (f64::from(chrome) + list).ceil();
+    if needed >= f64::from(sizing.height) {
+        sizing.height
+    } else {
+        needed as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sizing(auto_height: bool, layout: FileResultsLayout) -> WindowSizing {
+        WindowSizing {
+            width: 640,
+            height: 480,
+            auto_height,
+            file_results_layout: layout,
+        }
+    }
+
+    #[test]
+    fn test_fixed_height_without_auto_height() {
+        let fixed = sizing(false, FileResultsLayout::TwoLine);
+        assert_eq!(window_height(AppMode::Normal, 3, &fixed, 56, 1.0), 480);
+        assert_eq!(window_height(AppMode::Normal, 0, &fixed, 56, 2.0), 480);
+    }
+
+    #[test]
+    fn test_auto_height_follows_rows() {
+        let auto = sizing(true, FileResultsLayout::TwoLine);
+        assert_eq!(window_height(AppMode::Normal, 0, &auto, 56, 1.0), 56);
+        assert_eq!(
+            window_height(AppMode::Normal, 3, &auto, 56, 1.0),
+            56 + LIST_PADDING + 3 * ROW_HEIGHT
+        );
+        // Capped at the configured height
+        assert_eq!(window_height(AppMode::Normal, 50, &auto, 56, 1.0), 480);
+    }
+
+    #[test]
+    fn test_auto_height_single_line_file_rows() {
+        let compact = sizing(true, FileResultsLayout::SingleLine);
+        let two_line = window_height(AppMode::FileSearch, 4, &compact, 56, 1.0);
+        assert_eq!(two_line, 56 + LIST_PADDING + 2 * ROW_HEIGHT);
+        // Only file rows are single-line
+        assert_eq!(
+            window_height(AppMode::Normal, 4, &compact, 56, 1.0),
+            56 + LIST_PADDING + 4 * ROW_HEIGHT
+        );
+    }
+
+    #[test]
+    fn test_auto_height_scales_with_text() {
+        let auto = sizing(true, FileResultsLayout::TwoLine);
+        assert_eq!(
+            window_height(AppMode::Normal, 2, &auto, 56, 1.5),
+            56 + LIST_PADDING + 3 * ROW_HEIGHT
+        );
+        assert!(
+            window_height(AppMode::Normal, 2, &auto, 56, 1.25)
+                > window_height(AppMode::Normal, 2, &auto, 56, 1.0)
+        );
+    }
+
+    #[test]
+    fn test_text_scale() {
+        assert!((text_scale(98_304) - 1.0).abs() < f64::EPSILON);
+        assert!((text_scale(122_880) - 1.25).abs() < f64::EPSILON);
+        assert!((text_scale(-1) - 1.0).abs() < f64::EPSILON);
+    }
+}
@@ -9,18 +9,22 @@
 //! The bar auto-refreshes every time the Grunner launcher window becomes visible.
 
 use crate::actions::workspace::{self as ws, WindowInfo};
+use crate::ui::compat::ApplicationWindow;
 use glib::clone;
 use gtk4::{
     Box as GtkBox, Button, EventControllerMotion, EventControllerScroll,
     EventControllerScrollFlags, Image, Label, Orientation, Overlay, PolicyType, PropagationPhase,
     ScrolledWindow, gdk, prelude::*,
 };
-use libadwaita::ApplicationWindow;
 use std::cell::RefCell;
 use std::rc::{Rc, Weak};
 
 const MAX_TITLE_CHARS: usize = 22;
 
+/// Late-bound handle to the bar's refresh callback, filled in once the
+/// callback itself has been created
+type OnChangeCell = Rc<RefCell<Option<Weak<dyn Fn()>>>>;
+
 fn truncate(s: &str, max: usize) -> String {
     let mut chars = s.chars();
     let head: String = chars.by_ref().take(max).collect();
@@ -168,7 +172,7 @@ fn build_window_button(
             glib::spawn_future_local(async move {
                 ws::activate_window(win_id).await;
             });
-            app_window.hide();
+            app_window.set_visible(false);
         }
     ));
 
@@ -299,7 +303,7 @@ pub fn build_workspace_bar(window: &ApplicationWindow) -> ScrolledWindow {
     let scroll_r = scroll.clone();
     let buttons_r = buttons_box.clone();
     let window_r = window.clone();
-    let on_change_cell: Rc<RefCell<Option<Weak<dyn Fn()>>>> = Rc::new(RefCell::new(None));
+    let on_change_cell: OnChangeCell = Rc::new(RefCell::new(None));
     let on_change: Rc<dyn Fn()> = Rc::new({
         let cell = on_change_cell.clone();
         let scroll_s = scroll_r.clone();
@@ -0,0 +1,335 @@
+//! Systemd units for the `:unit` mode
+//!
+//! Units are listed through the systemd manager's D-Bus API rather than by
+//! running `systemctl`: `ListUnits` on the system bus, and on the session
+//! bus for the user manager when `[units] user` is set. Like `systemctl
+//! list-units --all`, this includes every loaded unit, inactive ones too.
+//!
+//! Starting, stopping and restarting system units needs privileges, so
+//! those calls allow interactive authorization: polkit asks for a password
+//! where it would for `systemctl`.
+
+use crate::providers::ranking::abbreviation_score;
+use fuzzy_matcher::FuzzyMatcher;
+use log::{debug, warn};
+use std::sync::OnceLock;
+use zbus::zvariant::OwnedObjectPath;
+use zbus::{Connection, proxy};
+
+/// A unit as returned by `ListUnits`: name, description, load state,
+/// active state, sub state, followed unit, object path, job id, job type
+/// and job path
+type RawUnit = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    OwnedObjectPath,
+    u32,
+    String,
+    OwnedObjectPath,
+);
+
+#[proxy(
+    interface = "org.freedesktop.systemd1.Manager",
+    default_service = "org.freedesktop.systemd1",
+    default_path = "/org/freedesktop/systemd1"
+)]
+trait SystemdManager {
+    fn list_units(&self) -> zbus::Result<Vec<RawUnit>>;
+    #[zbus(allow_interactive_auth)]
+    fn start_unit(&self, name: &str, mode: &str) -> zbus::Result<OwnedObjectPath>;
+    #[zbus(allow_interactive_auth)]
+    fn stop_unit(&self, name: &str, mode: &str) -> zbus::Result<OwnedObjectPath>;
+    #[zbus(allow_interactive_auth)]
+    fn restart_unit(&self, name: &str, mode: &str) -> zbus::Result<OwnedObjectPath>;
+}
+
+/// Which systemd manager a unit belongs to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnitScope {
+    /// The system manager, on the system bus
+    #[default]
+    System,
+    /// The user's manager, on the session bus, as `systemctl --user`
+    User,
+}
+
+impl UnitScope {
+    async fn connection(self) -> zbus::Result<Connection> {
+        static SYSTEM: OnceLock<Connection> = OnceLock::new();
+        static USER: OnceLock<Connection> = OnceLock::new();
+        let cell = match self {
+            Self::System => &SYSTEM,
+            Self::User => &USER,
+        };
+        if let Some(c) = cell.get() {
+            return Ok(c.clone());
+        }
+        let conn = match self {
+            Self::System => Connection::system().await?,
+            Self::User => Connection::session().await?,
+        };
+        Ok(cell.get_or_init(|| conn).clone())
+    }
+
+    async fn manager(self) -> zbus::Result<SystemdManagerProxy<'static>> {
+        SystemdManagerProxy::new(&self.connection().await?).await
+    }
+}
+
+/// A loaded systemd unit
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Unit {
+    /// Full name, e.g. `sshd.service`
+    pub name: String,
+    pub description: String,
+    /// `loaded`, `not-found`, `masked`, …
+    pub load_state: String,
+    /// `active`, `inactive`, `failed`, `activating`, …
+    pub active_state: String,
+    /// Type-specific state, e.g. `running` or `exited`
+    pub sub_state: String,
+    pub scope: UnitScope,
+}
+
+impl Unit {
+    /// Second line of the row: state, scope of user units and description
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let mut summary = format!("{} ({})", self.active_state, self.sub_state);
+        if self.scope == UnitScope::User {
+            summary.push_str(" · user");
+        }
+        if !self.description.is_empty() && self.description != self.name {
+            summary.push_str(" · ");
+            summary.push_str(&self.description);
+        }
+        summary
+    }
+
+    /// Whether the unit is up or coming up, so Enter stops it
+    #[must_use]
+    pub fn is_running(&self) -> bool {
+        matches!(
+            self.active_state.as_str(),
+            "active" | "activating" | "reloading" | "refreshing"
+        )
+    }
+
+    fn is_failed(&self) -> bool {
+        self.active_state == "failed"
+    }
+}
+
+/// Jobs the `:unit` mode queues for a unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitAction {
+    Start,
+    Stop,
+    Restart,
+}
+
+impl UnitAction {
+    /// What Enter does: stop a running unit, start any other
+    #[must_use]
+    pub fn toggle(unit: &Unit) -> Self {
+        if unit.is_running() {
+            Self::Stop
+        } else {
+            Self::Start
+        }
+    }
+
+    /// Verb for messages
+    #[must_use]
+    pub fn verb(self) -> &'static str {
+        match self {
+            Self::Start => "start",
+            Self::Stop => "stop",
+            Self::Restart => "restart",
+        }
+    }
+}
+
+fn unit_from_raw(raw: RawUnit, scope: UnitScope) -> Unit {
+    let (name, description, load_state, active_state, sub_state, ..) = raw;
+    Unit {
+        name,
+        description,
+        load_state,
+        active_state,
+        sub_state,
+        scope,
+    }
+}
+
+/// Units of one manager, empty if it cannot be reached
+async fn list_scope(scope: UnitScope) -> Vec<Unit> {
+    let manager = match scope.manager().await {
+        Ok(manager) => manager,
+        Err(e) => {
+            warn!("[units] Could not reach the {scope:?} manager: {e}");
+            return Vec::new();
+        }
+    };
+    match manager.list_units().await {
+        Ok(raw) => raw
+            .into_iter()
+            .map(|raw| unit_from_raw(raw, scope))
+            .collect(),
+        Err(e) => {
+            warn!("[units] ListUnits on the {scope:?} manager failed: {e}");
+            Vec::new()
+        }
+    }
+}
+
+/// Units of the system manager, then of the user manager if `user`
+pub async fn list_units(user: bool) -> Vec<Unit> {
+    let mut units = list_scope(UnitScope::System).await;
+    if user {
+        units.extend(list_scope(UnitScope::User).await);
+    }
+    debug!("[units] {} unit(s) listed", units.len());
+    units
+}
+
+/// Queue `action` for `unit`, as `systemctl` does with the `replace` mode
+///
+/// # Errors
+/// Returns the D-Bus error when the manager cannot be reached or refuses
+/// the job, e.g. when authorization is denied.
+pub async fn run_action(unit: &Unit, action: UnitAction) -> zbus::Result<()> {
+    let manager = unit.scope.manager().await?;
+    let name = unit.name.as_str();
+    let job = match action {
+        UnitAction::Start => manager.start_unit(name, "replace").await?,
+        UnitAction::Stop => manager.stop_unit(name, "replace").await?,
+        UnitAction::Restart => manager.restart_unit(name, "replace").await?,
+    };
+    debug!(
+        "[units] Queued {} of {name} as {}",
+        action.verb(),
+        job.as_str()
+    );
+    Ok(())
+}
+
+/// The units matching `query`, best first, at most `max`
+///
+/// Every word has to match the unit name or its description. With an
+/// empty query, failed units come first, then running ones, each by name.
+#[must_use]
+pub fn match_units<'a>(
+    matcher: &impl FuzzyMatcher,
+    query: &str,
+    units: &'a [Unit],
+    max: usize,
+) -> Vec<&'a Unit> {
+    let words: Vec<&str> = query.split_whitespace().collect();
+    if words.is_empty() {
+        let mut all: Vec<&Unit> = units.iter().collect();
+        all.sort_by(|a, b| {
+            b.is_failed()
+                .cmp(&a.is_failed())
+                .then_with(|| b.is_running().cmp(&a.is_running()))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        all.truncate(max);
+        return all;
+    }
+    let mut scored: Vec<(i64, &Unit)> = units
+        .iter()
+        .filter_map(|unit| {
+            words
+                .iter()
+                .map(|word| {
+                    let name = abbreviation_score(matcher, &unit.name, word);
+                    // The description only helps, the name ranks first
+                    let description =
+                        abbreviation_score(matcher, &unit.description, word).map(|score| score / 2);
+                    name.max(description)
+                })
+                .sum::<Option<i64>>()
+                .map(|score| (score, unit))
+        })
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().take(max).map(|(_, u)| u).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuzzy_matcher::skim::SkimMatcherV2;
+
+    fn unit(name: &str, description: &str, active_state: &str, sub_state: &str) -> Unit {
+        Unit {
+            name: name.to_string(),
+            description: description.to_string(),
+            load_state: "loaded".to_string(),
+            active_state: active_state.to_string(),
+            sub_state: sub_state.to_string(),
+            scope: UnitScope::System,
+        }
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut sshd = unit("sshd.service", "OpenSSH Daemon", "active", "running");
+        assert_eq!(sshd.summary(), "active (running) · OpenSSH Daemon");
+        sshd.scope = UnitScope::User;
+        assert_eq!(sshd.summary(), "active (running) · user · OpenSSH Daemon");
+
+        let bare = unit("foo.mount", "foo.mount", "inactive", "dead");
+        assert_eq!(bare.summary(), "inactive (dead)");
+    }
+
+    #[test]
+    fn test_toggle() {
+        let running = unit("a.service", "", "active", "running");
+        assert_eq!(UnitAction::toggle(&running), UnitAction::Stop);
+        let starting = unit("a.service", "", "activating", "start");
+        assert_eq!(UnitAction::toggle(&starting), UnitAction::Stop);
+        let stopped = unit("a.service", "", "inactive", "dead");
+        assert_eq!(UnitAction::toggle(&stopped), UnitAction::Start);
+        let failed = unit("a.service", "", "failed", "failed");
+        assert_eq!(UnitAction::toggle(&failed), UnitAction::Start);
+    }
+
+    #[test]
+    fn test_match_units_empty_puts_failed_first() {
+        let units = [
+            unit("b.service", "", "inactive", "dead"),
+            unit("z.service", "", "failed", "failed"),
+            unit("c.service", "", "active", "running"),
+            unit("a.service", "", "failed", "failed"),
+        ];
+        let matcher = SkimMatcherV2::default();
+        let names: Vec<&str> = match_units(&matcher, "", &units, 10)
+            .iter()
+            .map(|u| u.name.as_str())
+            .collect();
+        assert_eq!(names, ["a.service", "z.service", "c.service", "b.service"]);
+        assert_eq!(match_units(&matcher, " ", &units, 1).len(), 1);
+    }
+
+    #[test]
+    fn test_match_units_by_name_and_description() {
+        let units = [
+            unit("sshd.service", "OpenSSH Daemon", "active", "running"),
+            unit("cups.service", "CUPS Scheduler", "active", "running"),
+            unit("bluetooth.service", "Bluetooth service", "inactive", "dead"),
+        ];
+        let matcher = SkimMatcherV2::default();
+        let found = match_units(&matcher, "cups", &units, 10);
+        assert_eq!(found[0].name, "cups.service");
+        let found = match_units(&matcher, "scheduler", &units, 10);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "cups.service");
+        assert!(match_units(&matcher, "sshd xyzzy", &units, 10).is_empty());
+    }
+}
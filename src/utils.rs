@@ -2,16 +2,20 @@
 //!
 //! This module provides general-purpose helper functions used throughout
 //! the application. Currently, it contains path manipulation utilities
-//! for handling user home directory expansion, calculator result parsing,
-//! and icon selection.
+//! for handling user home directory expansion, path containment checks,
+//! calculator result parsing, icon selection, and the stable hash of
+//! values stored on disk.
 
 pub mod clipboard;
 pub mod desktop;
+pub mod json_cache;
 
-use crate::calculator::is_valid_calc_char;
+use crate::calculator::{decimal_form, is_valid_calc_char};
 use crate::core::global_state::get_home_dir;
 use gtk4::gio;
-use std::path::PathBuf;
+use std::ffi::OsString;
+use std::io;
+use std::path::{Component, Path, PathBuf};
 
 /// Expand a path starting with `~` to the user's home directory
 ///
@@ -91,6 +95,125 @@ pub fn contract_home(path: &std::path::Path) -> String {
     }
 }
 
+/// Data directories searched when `XDG_DATA_DIRS` is not set, in
+/// precedence order
+const FALLBACK_DATA_DIRS: &[&str] = &[
+    "/usr/share",
+    "/usr/local/share",
+    "~/.local/share",
+    "/var/lib/flatpak/exports/share",
+    "~/.local/share/flatpak/exports/share",
+];
+
+/// `sub` inside each XDG data directory, in precedence order
+///
+/// `data_home` and `data_dirs` are the values of `XDG_DATA_HOME` and
+/// `XDG_DATA_DIRS`. With `XDG_DATA_DIRS` unset or empty the
+/// [`FALLBACK_DATA_DIRS`] are used. Otherwise the list is the data home
+/// (`~/.local/share` by default) followed by the `XDG_DATA_DIRS` entries,
+/// so on NixOS or in a toolbox the paths of the environment are found.
+/// Repeated directories keep their first position.
+#[must_use]
+pub fn xdg_data_subdirs(
+    data_home: Option<&str>,
+    data_dirs: Option<&str>,
+    sub: &str,
+) -> Vec<String> {
+    let bases: Vec<&str> = match data_dirs.filter(|dirs| !dirs.trim().is_empty()) {
+        Some(dirs) => std::iter::once(
+            data_home
+                .filter(|h| !h.is_empty())
+                .unwrap_or("~/.local/share"),
+        )
+        .chain(dirs.split(':').map(str::trim).filter(|dir| !dir.is_empty()))
+        .collect(),
+        None => FALLBACK_DATA_DIRS.to_vec(),
+    };
+
+    let mut seen: Vec<PathBuf> = Vec::new();
+    let mut subdirs = Vec::new();
+    for base in bases {
+        let dir = format!("{}/{sub}", base.trim_end_matches('/'));
+        let key = expand_home(&dir);
+        if !seen.contains(&key) {
+            seen.push(key);
+            subdirs.push(dir);
+        }
+    }
+    subdirs
+}
+
+/// `sub` inside each data directory of the environment, see
+/// [`xdg_data_subdirs`]
+#[must_use]
+pub fn data_subdirs(sub: &str) -> Vec<String> {
+    xdg_data_subdirs(
+        std::env::var("XDG_DATA_HOME").ok().as_deref(),
+        std::env::var("XDG_DATA_DIRS").ok().as_deref(),
+        sub,
+    )
+}
+
+/// Resolve `relative` against `root` and require the result to stay inside it
+///
+/// Both paths are resolved the same way: the longest prefix that exists on
+/// disk is canonicalized (following symlinks) and the components that do not
+/// exist yet are applied lexically, so `..` inside a folder that has not been
+/// created is still caught. As with [`Path::join`], an absolute `relative`
+/// replaces `root` and is only accepted if it points back inside it.
+///
+/// # Returns
+/// The resolved path, which may be `root` itself.
+///
+/// # Errors
+/// Returns an `InvalidInput` error if the path escapes `root`, or the error
+/// from canonicalizing an existing prefix (e.g. a dangling symlink).
+pub fn path_containment(root: &Path, relative: &Path) -> io::Result<PathBuf> {
+    let root = resolve_path(root)?;
+    let target = resolve_path(&root.join(relative))?;
+    if target.starts_with(&root) {
+        Ok(target)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} resolves outside {}", relative.display(), root.display()),
+        ))
+    }
+}
+
+/// Canonicalize the existing part of `path` and append the rest lexically
+fn resolve_path(path: &Path) -> io::Result<PathBuf> {
+    let mut existing = path.to_path_buf();
+    let mut missing: Vec<OsString> = Vec::new();
+    // symlink_metadata so that a dangling symlink counts as existing and
+    // fails to canonicalize instead of being written through
+    while std::fs::symlink_metadata(&existing).is_err() {
+        let Some(last) = existing.components().next_back() else {
+            break;
+        };
+        missing.push(last.as_os_str().to_os_string());
+        if !existing.pop() {
+            break;
+        }
+    }
+
+    let mut resolved = if existing.as_os_str().is_empty() {
+        PathBuf::new()
+    } else {
+        existing.canonicalize()?
+    };
+    for part in missing.iter().rev() {
+        match Path::new(part).components().next() {
+            Some(Component::ParentDir) => {
+                resolved.pop();
+            }
+            Some(Component::CurDir) | None => {}
+            Some(_) => resolved.push(part),
+        }
+    }
+    Ok(resolved)
+}
+
 /// Check if a line is a calculator result
 ///
 /// A calculator result has the format "expression = result" where:
@@ -120,6 +243,19 @@ pub fn is_calculator_result(line: &str) -> bool {
     result.chars().any(|c| c.is_ascii_digit())
 }
 
+/// The result of a calculator line without the expression and `=`, in
+/// decimal form for results shown in several bases
+///
+/// `None` for lines that are not calculator results.
+#[must_use]
+pub fn calculator_value(line: &str) -> Option<&str> {
+    if !is_calculator_result(line) {
+        return None;
+    }
+    let (_expr, result) = line.split_once('=')?;
+    Some(decimal_form(result.trim()))
+}
+
 /// Get the icon for a file based on its content type
 ///
 /// Uses GTK's content type detection to determine the appropriate icon
@@ -136,11 +272,68 @@ pub fn get_file_icon(file_path: &str) -> gio::Icon {
     gio::content_type_get_icon(&ctype)
 }
 
+/// FNV-1a hash
+///
+/// Unlike `DefaultHasher` the result is stable across runs and Rust
+/// versions, so it can be stored on disk: it is the snapshot hash of the app
+/// cache and the checksum of [`crate::versioned_cache`] files.
+#[derive(Debug, Clone, Copy)]
+pub struct Fnv1a(u64);
+
+impl Fnv1a {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    #[must_use]
+    pub fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+
+    /// Feed `bytes` into the hash
+    pub fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = (self.0 ^ u64::from(b)).wrapping_mul(Self::PRIME);
+        }
+    }
+
+    /// The hash of everything written so far
+    #[must_use]
+    pub fn finish(self) -> u64 {
+        self.0
+    }
+
+    /// The hash of `bytes` alone
+    #[must_use]
+    pub fn hash(bytes: &[u8]) -> u64 {
+        let mut hasher = Self::new();
+        hasher.write(bytes);
+        hasher.finish()
+    }
+}
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::path::Path;
 
+    #[test]
+    fn test_fnv1a_reference_values() {
+        assert_eq!(Fnv1a::hash(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(Fnv1a::hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(Fnv1a::hash(b"foobar"), 0x8594_4171_f739_67e8);
+
+        let mut hasher = Fnv1a::new();
+        hasher.write(b"foo");
+        hasher.write(b"bar");
+        assert_eq!(hasher.finish(), Fnv1a::hash(b"foobar"));
+    }
+
     // ── expand_home tests ─────────────────────────────────────────────
 
     #[test]
@@ -228,6 +421,59 @@ mod tests {
         assert_eq!(result, "~/a/b/c/d.txt");
     }
 
+    // ── xdg_data_subdirs tests ────────────────────────────────────────
+
+    #[test]
+    fn test_xdg_data_subdirs_fallback() {
+        let dirs = xdg_data_subdirs(None, None, "applications");
+        assert_eq!(
+            dirs,
+            vec![
+                "/usr/share/applications",
+                "/usr/local/share/applications",
+                "~/.local/share/applications",
+                "/var/lib/flatpak/exports/share/applications",
+                "~/.local/share/flatpak/exports/share/applications",
+            ]
+        );
+        assert_eq!(xdg_data_subdirs(None, Some(" "), "applications"), dirs);
+    }
+
+    #[test]
+    fn test_xdg_data_subdirs_from_env() {
+        let dirs = xdg_data_subdirs(
+            None,
+            Some("/run/current-system/sw/share:/nix/profile/share/::/usr/share"),
+            "gnome-shell/search-providers",
+        );
+        assert_eq!(
+            dirs,
+            vec![
+                "~/.local/share/gnome-shell/search-providers",
+                "/run/current-system/sw/share/gnome-shell/search-providers",
+                "/nix/profile/share/gnome-shell/search-providers",
+                "/usr/share/gnome-shell/search-providers",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_xdg_data_subdirs_dedups_in_order() {
+        let dirs = xdg_data_subdirs(
+            Some("/data/home"),
+            Some("/usr/share:/data/home/:/usr/local/share:/usr/share"),
+            "applications",
+        );
+        assert_eq!(
+            dirs,
+            vec![
+                "/data/home/applications",
+                "/usr/share/applications",
+                "/usr/local/share/applications",
+            ]
+        );
+    }
+
     // ── is_calculator_result tests ────────────────────────────────────
 
     #[test]
@@ -265,8 +511,125 @@ mod tests {
         assert!(is_calculator_result("sin(0) = 0"));
     }
 
+    #[test]
+    fn test_calculator_value() {
+        assert_eq!(calculator_value("1920 * 1080 = 2073600"), Some("2073600"));
+        assert_eq!(
+            calculator_value("12 km to mi = 7.4565 mi"),
+            Some("7.4565 mi")
+        );
+        assert_eq!(
+            calculator_value("128 + 128 = 256  •  0x100  •  0b100000000"),
+            Some("256")
+        );
+        assert_eq!(calculator_value("firefox"), None);
+    }
+
+    #[test]
+    fn test_calculator_value_chains() {
+        // Each Tab puts the value back in the query, where the next
+        // operation is typed after it
+        let mut query = "1920 * 1080".to_string();
+        for (next, expected) in [
+            (" * 4", "8294400"),
+            (" / 2", "4147200"),
+            (" - 200", "4147000"),
+        ] {
+            let result = crate::calculator::evaluate(&query).unwrap();
+            let line = format!("{query} = {result}");
+            query = format!("{}{next}", calculator_value(&line).unwrap());
+            assert_eq!(
+                crate::calculator::evaluate(&query),
+                Some(expected.to_string())
+            );
+        }
+        assert_eq!(query, "4147200 - 200");
+    }
+
     #[test]
     fn test_is_calculator_result_negative() {
         assert!(is_calculator_result("-5 + 3 = -2"));
     }
+
+    // ── path_containment tests ────────────────────────────────────────
+
+    /// Create a fresh vault directory inside a per-test temp directory
+    fn temp_vault(name: &str) -> (PathBuf, PathBuf) {
+        let base = std::env::temp_dir().join(format!("grunner_test_containment_{name}"));
+        let _ = std::fs::remove_dir_all(&base);
+        let vault = base.join("vault");
+        std::fs::create_dir_all(&vault).unwrap();
+        (base, vault)
+    }
+
+    #[test]
+    fn test_path_containment_nested_folder() {
+        let (base, vault) = temp_vault("nested");
+        std::fs::create_dir_all(vault.join("Journal")).unwrap();
+        let root = vault.canonicalize().unwrap();
+
+        let existing = path_containment(&vault, Path::new("Journal")).unwrap();
+        assert_eq!(existing, root.join("Journal"));
+        let missing = path_containment(&vault, Path::new("Journal/2024/./Q1")).unwrap();
+        assert_eq!(missing, root.join("Journal/2024/Q1"));
+        let empty = path_containment(&vault, Path::new("")).unwrap();
+        assert_eq!(empty, root);
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn test_path_containment_rejects_traversal() {
+        let (base, vault) = temp_vault("traversal");
+        std::fs::create_dir_all(vault.join("Inbox")).unwrap();
+
+        assert!(path_containment(&vault, Path::new("../outside.md")).is_err());
+        assert!(path_containment(&vault, Path::new("../../.ssh/authorized_keys")).is_err());
+        assert!(path_containment(&vault, Path::new("Inbox/../../outside")).is_err());
+        // `..` through folders that do not exist yet
+        assert!(path_containment(&vault, Path::new("new/deeper/../../../x")).is_err());
+        // Going up and back in stays inside
+        let back_in = path_containment(&vault, Path::new("Inbox/../Quick.md")).unwrap();
+        assert_eq!(back_in, vault.canonicalize().unwrap().join("Quick.md"));
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn test_path_containment_absolute_paths() {
+        let (base, vault) = temp_vault("absolute");
+
+        assert!(path_containment(&vault, Path::new("/etc/passwd")).is_err());
+        assert!(path_containment(&vault, &base.join("outside.md")).is_err());
+        // An absolute path that points into the vault is fine
+        let inside = path_containment(&vault, &vault.join("Quick.md")).unwrap();
+        assert_eq!(inside, vault.canonicalize().unwrap().join("Quick.md"));
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn test_path_containment_symlinks() {
+        let (base, vault) = temp_vault("symlink");
+        let outside = base.join("outside");
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::create_dir_all(vault.join("Real")).unwrap();
+        std::os::unix::fs::symlink(&outside, vault.join("escape")).unwrap();
+        std::os::unix::fs::symlink(vault.join("Real"), vault.join("alias")).unwrap();
+        std::os::unix::fs::symlink(base.join("missing"), vault.join("dangling.md")).unwrap();
+
+        assert!(path_containment(&vault, Path::new("escape/note.md")).is_err());
+        assert!(path_containment(&vault, Path::new("dangling.md")).is_err());
+        let aliased = path_containment(&vault, Path::new("alias/note.md")).unwrap();
+        assert_eq!(aliased, vault.canonicalize().unwrap().join("Real/note.md"));
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn test_path_containment_symlinked_vault() {
+        let (base, vault) = temp_vault("linked_vault");
+        let link = base.join("vault_link");
+        std::os::unix::fs::symlink(&vault, &link).unwrap();
+
+        let note = path_containment(&link, Path::new("Daily")).unwrap();
+        assert_eq!(note, vault.canonicalize().unwrap().join("Daily"));
+        let _ = std::fs::remove_dir_all(&base);
+    }
 }
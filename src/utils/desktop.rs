@@ -2,60 +2,102 @@
 //!
 //! This module provides utilities for reading and parsing .desktop files
 //! to extract application metadata like names and icons.
+//!
+//! [`resolve_desktop_info`] looks one desktop ID up. Callers resolving many
+//! IDs at once build a [`DesktopIndex`] instead, which lists the application
+//! directories in a single pass so each lookup reads exactly one file.
 
-use crate::core::global_state::get_home_dir;
-
-const DESKTOP_SEARCH_DIRS: &[&str] = &[
-    "/usr/share/applications",
-    "/usr/local/share/applications",
-    "/var/lib/flatpak/exports/share/applications",
-];
-
-const USER_DESKTOP_DIRS: &[&str] = &[
-    ".local/share/applications",
-    ".local/share/flatpak/exports/share/applications",
-];
+use crate::utils::{data_subdirs, expand_home};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DesktopInfo {
     pub name: String,
     pub icon: Option<String>,
 }
 
+/// Application directories of the XDG data directories, in lookup order
+fn desktop_dirs() -> Vec<PathBuf> {
+    data_subdirs("applications")
+        .iter()
+        .map(|dir| expand_home(dir))
+        .collect()
+}
+
+/// `desktop_id` without its `.desktop` suffix
+fn desktop_id_stem(desktop_id: &str) -> &str {
+    desktop_id.strip_suffix(".desktop").unwrap_or(desktop_id)
+}
+
 #[must_use]
 pub fn resolve_desktop_info(desktop_id: &str) -> Option<DesktopInfo> {
-    let home = get_home_dir();
-
-    let filename = if desktop_id.ends_with(".desktop") {
-        desktop_id.to_string()
-    } else {
-        format!("{desktop_id}.desktop")
-    };
-
-    for dir in DESKTOP_SEARCH_DIRS {
-        let path = format!("{dir}/{filename}");
-        if let Some(info) = parse_desktop_file(&path) {
-            return Some(info);
-        }
+    let filename = format!("{}.desktop", desktop_id_stem(desktop_id));
+    desktop_dirs()
+        .iter()
+        .find_map(|dir| parse_desktop_file(&dir.join(&filename)))
+}
+
+/// The `.desktop` files of the application directories, by desktop ID
+///
+/// Built from one listing of each directory. An ID present in several
+/// directories keeps all its files in lookup order, and the first one that
+/// parses wins, as in [`resolve_desktop_info`].
+#[derive(Debug, Default)]
+pub struct DesktopIndex {
+    paths: HashMap<String, Vec<PathBuf>>,
+}
+
+impl DesktopIndex {
+    /// Index the standard application directories
+    #[must_use]
+    pub fn build() -> Self {
+        Self::build_in(&desktop_dirs())
     }
 
-    for dir in USER_DESKTOP_DIRS {
-        let path = format!("{home}/{dir}/{filename}");
-        if let Some(info) = parse_desktop_file(&path) {
-            return Some(info);
+    /// Index the `.desktop` files directly inside `dirs`, in lookup order
+    ///
+    /// Missing or unreadable directories are skipped.
+    #[must_use]
+    pub fn build_in(dirs: &[PathBuf]) -> Self {
+        let mut paths: HashMap<String, Vec<PathBuf>> = HashMap::new();
+        for dir in dirs {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                continue;
+            };
+            for path in entries.flatten().map(|entry| entry.path()) {
+                let Some(id) = path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(|name| name.strip_suffix(".desktop"))
+                else {
+                    continue;
+                };
+                paths.entry(id.to_string()).or_default().push(path);
+            }
         }
+        Self { paths }
     }
 
-    None
-}
+    /// Name and icon of `desktop_id` (with or without `.desktop`)
+    #[must_use]
+    pub fn info(&self, desktop_id: &str) -> Option<DesktopInfo> {
+        self.paths
+            .get(desktop_id_stem(desktop_id))?
+            .iter()
+            .find_map(|path| parse_desktop_file(path))
+    }
 
-#[must_use]
-pub fn resolve_icon_from_desktop(desktop_id: &str) -> String {
-    resolve_desktop_info(desktop_id)
-        .map(|info| info.icon.unwrap_or_default())
-        .unwrap_or_default()
+    /// Icon of `desktop_id`, or an empty string if it has none
+    #[must_use]
+    pub fn icon(&self, desktop_id: &str) -> String {
+        self.info(desktop_id)
+            .and_then(|info| info.icon)
+            .unwrap_or_default()
+    }
 }
 
-fn parse_desktop_file(path: &str) -> Option<DesktopInfo> {
+fn parse_desktop_file(path: &Path) -> Option<DesktopInfo> {
     let content = std::fs::read_to_string(path).ok()?;
     let mut in_desktop_entry = false;
     let mut name: Option<String> = None;
@@ -81,3 +123,62 @@ fn parse_desktop_file(path: &str) -> Option<DesktopInfo> {
 
     Some(DesktopInfo { name: name?, icon })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_desktop_index_fixture_tree() {
+        let base =
+            std::env::temp_dir().join(format!("grunner_test_desktop_index_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&base);
+        let system = base.join("system");
+        let user = base.join("user");
+        std::fs::create_dir_all(&system).unwrap();
+        std::fs::create_dir_all(&user).unwrap();
+        let write = |dir: &Path, file: &str, content: &str| {
+            std::fs::write(dir.join(file), content).unwrap();
+        };
+        write(
+            &system,
+            "org.gnome.Nautilus.desktop",
+            "[Desktop Entry]\nName=Files\nIcon=org.gnome.Nautilus\n\n[Desktop Action new]\nName=New Window\nIcon=window-new\n",
+        );
+        write(&system, "noicon.desktop", "[Desktop Entry]\nName=No Icon\n");
+        // A system file without a name falls through to the user's copy
+        write(&system, "broken.desktop", "[Desktop Entry]\nIcon=broken\n");
+        write(
+            &user,
+            "broken.desktop",
+            "[Desktop Entry]\nName=Fixed\nIcon=fixed\n",
+        );
+        write(
+            &user,
+            "org.gnome.Nautilus.desktop",
+            "[Desktop Entry]\nName=Shadowed\n",
+        );
+        write(&user, "notes.txt", "[Desktop Entry]\nName=Not an app\n");
+
+        let index = DesktopIndex::build_in(&[system, user, base.join("missing")]);
+        assert_eq!(
+            index.info("org.gnome.Nautilus"),
+            Some(DesktopInfo {
+                name: "Files".to_string(),
+                icon: Some("org.gnome.Nautilus".to_string()),
+            })
+        );
+        assert_eq!(
+            index.icon("org.gnome.Nautilus.desktop"),
+            "org.gnome.Nautilus"
+        );
+        assert_eq!(index.icon("noicon"), "");
+        assert_eq!(
+            index.info("broken").map(|i| i.name),
+            Some("Fixed".to_string())
+        );
+        assert_eq!(index.info("notes"), None);
+        assert_eq!(index.info("missing"), None);
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
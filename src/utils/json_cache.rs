@@ -0,0 +1,74 @@
+//! JSON files in Grunner's cache directory
+//!
+//! The launch history, first-seen dates, emoji usage, clipboard and query
+//! histories are all stored as one JSON value each under
+//! `$HOME/.cache/grunner`. This module holds the shared load and store
+//! logic so the modules only keep what is specific to their data.
+
+use crate::core::global_state::get_home_dir;
+use log::warn;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::path::{Path, PathBuf};
+
+/// Path of the cache file `name`: `$HOME/.cache/grunner/<name>`
+#[must_use]
+pub fn path(name: &str) -> PathBuf {
+    PathBuf::from(get_home_dir())
+        .join(".cache")
+        .join("grunner")
+        .join(name)
+}
+
+/// Load a value from `path`
+///
+/// A missing or unreadable file yields the default value. A corrupt one
+/// does too, with a warning naming `what` was ignored.
+#[must_use]
+pub fn load<T: DeserializeOwned + Default>(path: &Path, what: &str) -> T {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return T::default();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        warn!("Ignoring corrupt {what} {}: {e}", path.display());
+        T::default()
+    })
+}
+
+/// Write `value` to `path`, creating its directory if needed
+///
+/// # Errors
+/// Returns an error if the directory cannot be created or the file
+/// cannot be written.
+pub fn store<T: Serialize>(path: &Path, value: &T) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let json = serde_json::to_string(value).map_err(std::io::Error::other)?;
+    std::fs::write(path, json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_store_and_load_round_trip() {
+        let path = std::env::temp_dir()
+            .join(format!("grunner_test_json_cache_{}", std::process::id()))
+            .join("nested")
+            .join("cache.json");
+        let value = BTreeMap::from([("firefox".to_string(), 3u32)]);
+        store(&path, &value).unwrap();
+        assert_eq!(load::<BTreeMap<String, u32>>(&path, "test cache"), value);
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(load::<BTreeMap<String, u32>>(&path, "test cache").is_empty());
+        assert!(
+            load::<BTreeMap<String, u32>>(Path::new("/nonexistent/cache.json"), "test cache")
+                .is_empty()
+        );
+        let _ = std::fs::remove_dir_all(path.parent().unwrap().parent().unwrap());
+    }
+}
@@ -0,0 +1,262 @@
+//! Binary caches with a self-describing header
+//!
+//! Caches such as the application cache are bincode, which carries no
+//! layout information: a cache written before a struct gained a field
+//! deserializes into garbage or fails. [`store`] therefore writes a header
+//! before the bincode payload, which [`load`] checks before decoding:
+//!
+//! | Bytes | Content                                         |
+//! |-------|-------------------------------------------------|
+//! | 4     | [`MAGIC`]                                       |
+//! | 4     | Format version of the cache, little endian      |
+//! | 1 + n | Length and text of the grunner version writing it |
+//! | 8     | Payload length, little endian                   |
+//! | 8     | FNV-1a checksum of the payload, little endian   |
+//!
+//! Each cache has its own format version, to be bumped with any change to
+//! the layout of what it stores. Files with another magic or format
+//! version, cut short, or whose payload does not match its checksum are a
+//! cache miss: they are logged and rebuilt. The grunner version is only
+//! recorded, to tell whose cache a file is.
+
+use crate::utils::Fnv1a;
+use log::{debug, info, warn};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::path::Path;
+
+/// First bytes of every cache file
+pub const MAGIC: [u8; 4] = *b"GRNC";
+
+/// Version of grunner, recorded in the header
+const CRATE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Why a cache file could not be used
+#[derive(Debug)]
+pub enum CacheError {
+    /// The file ends before its header or payload does
+    Truncated,
+    /// The file does not start with [`MAGIC`]
+    Magic,
+    /// The file has another format version
+    Version {
+        found: u32,
+        /// grunner version that wrote the file
+        written_by: String,
+    },
+    /// The payload does not match the checksum of the header
+    Checksum,
+    /// The payload does not deserialize
+    Decode(bincode::Error),
+}
+
+impl fmt::Display for CacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "the file is truncated"),
+            Self::Magic => write!(f, "the file is not a grunner cache"),
+            Self::Version { found, written_by } => {
+                write!(f, "format version {found}, written by grunner {written_by}")
+            }
+            Self::Checksum => write!(f, "the checksum does not match"),
+            Self::Decode(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for CacheError {}
+
+/// Serialize `value` with a header for `version`
+///
+/// # Errors
+/// Returns an error if `value` cannot be serialized.
+pub fn encode<T: Serialize + ?Sized>(version: u32, value: &T) -> bincode::Result<Vec<u8>> {
+    let payload = bincode::serialize(value)?;
+    // Versions longer than 255 bytes are cut; they are only informative
+    let crate_version = &CRATE_VERSION.as_bytes()[..CRATE_VERSION.len().min(255)];
+    let mut bytes = Vec::with_capacity(payload.len() + crate_version.len() + 25);
+    bytes.extend_from_slice(&MAGIC);
+    bytes.extend_from_slice(&version.to_le_bytes());
+    bytes.push(u8::try_from(crate_version.len()).unwrap_or(u8::MAX));
+    bytes.extend_from_slice(crate_version);
+    bytes.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&Fnv1a::hash(&payload).to_le_bytes());
+    bytes.extend_from_slice(&payload);
+    Ok(bytes)
+}
+
+/// The first `n` bytes of `bytes`, which are advanced past them
+fn take<'a>(bytes: &mut &'a [u8], n: usize) -> Result<&'a [u8], CacheError> {
+    if bytes.len() < n {
+        return Err(CacheError::Truncated);
+    }
+    let (head, rest) = bytes.split_at(n);
+    *bytes = rest;
+    Ok(head)
+}
+
+/// The first `N` bytes of `bytes` as an array, for integers
+fn take_array<const N: usize>(bytes: &mut &[u8]) -> Result<[u8; N], CacheError> {
+    let mut array = [0; N];
+    array.copy_from_slice(take(bytes, N)?);
+    Ok(array)
+}
+
+/// Deserialize a cache written by [`encode`] for `version`
+///
+/// # Errors
+/// Returns why the cache cannot be used.
+pub fn decode<T: DeserializeOwned>(mut bytes: &[u8], version: u32) -> Result<T, CacheError> {
+    let bytes = &mut bytes;
+    if take(bytes, MAGIC.len())? != MAGIC {
+        return Err(CacheError::Magic);
+    }
+    let found = u32::from_le_bytes(take_array(bytes)?);
+    let [len] = take_array(bytes)?;
+    let written_by = String::from_utf8_lossy(take(bytes, usize::from(len))?).into_owned();
+    if found != version {
+        return Err(CacheError::Version { found, written_by });
+    }
+    let len = u64::from_le_bytes(take_array(bytes)?);
+    let sum = u64::from_le_bytes(take_array(bytes)?);
+    let payload = usize::try_from(len)
+        .map_err(|_| CacheError::Truncated)
+        .and_then(|len| take(bytes, len))?;
+    if Fnv1a::hash(payload) != sum {
+        return Err(CacheError::Checksum);
+    }
+    bincode::deserialize(payload).map_err(CacheError::Decode)
+}
+
+/// Load the cache at `path` written for `version`
+///
+/// A missing file and files that cannot be used are a cache miss; the
+/// latter are logged.
+#[must_use]
+pub fn load<T: DeserializeOwned>(path: &Path, version: u32) -> Option<T> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            debug!("Cache {} not found or inaccessible: {e}", path.display());
+            return None;
+        }
+    };
+    match decode(&bytes, version) {
+        Ok(value) => Some(value),
+        Err(e @ CacheError::Version { .. }) => {
+            info!(
+                "Ignoring cache {} ({e}), expected format version {version}",
+                path.display()
+            );
+            None
+        }
+        Err(e) => {
+            warn!("Ignoring unusable cache {}: {e}", path.display());
+            None
+        }
+    }
+}
+
+/// Write `value` to the cache at `path` for `version`
+///
+/// # Errors
+/// Returns an error if `value` cannot be serialized, the directory cannot
+/// be created or the file cannot be written.
+pub fn store<T: Serialize + ?Sized>(path: &Path, version: u32, value: &T) -> std::io::Result<()> {
+    let bytes = encode(version, value).map_err(std::io::Error::other)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    debug!(
+        "Writing {} bytes of cache to {}",
+        bytes.len(),
+        path.display()
+    );
+    std::fs::write(path, bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<u8> {
+        encode(3, &vec!["firefox".to_string(), "zed".to_string()]).unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let apps: Vec<String> = decode(&sample(), 3).unwrap();
+        assert_eq!(apps, vec!["firefox", "zed"]);
+    }
+
+    #[test]
+    fn test_truncated() {
+        let bytes = sample();
+        for len in [0, 2, 4, 8, bytes.len() - 1] {
+            assert!(
+                matches!(
+                    decode::<Vec<String>>(&bytes[..len], 3),
+                    Err(CacheError::Truncated)
+                ),
+                "cut at {len}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_wrong_magic() {
+        let mut bytes = sample();
+        bytes[0] = b'X';
+        assert!(matches!(
+            decode::<Vec<String>>(&bytes, 3),
+            Err(CacheError::Magic)
+        ));
+        // A raw bincode cache of an older grunner
+        let old = bincode::serialize(&(9_u32, vec!["firefox"])).unwrap();
+        assert!(matches!(
+            decode::<Vec<String>>(&old, 3),
+            Err(CacheError::Magic)
+        ));
+    }
+
+    #[test]
+    fn test_wrong_version() {
+        match decode::<Vec<String>>(&sample(), 4) {
+            Err(CacheError::Version { found, written_by }) => {
+                assert_eq!(found, 3);
+                assert_eq!(written_by, CRATE_VERSION);
+            }
+            other => panic!("expected a version mismatch, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_checksum_mismatch() {
+        let mut bytes = sample();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        assert!(matches!(
+            decode::<Vec<String>>(&bytes, 3),
+            Err(CacheError::Checksum)
+        ));
+    }
+
+    #[test]
+    fn test_load_and_store() {
+        let path = std::env::temp_dir()
+            .join(format!(
+                "grunner_test_versioned_cache_{}",
+                std::process::id()
+            ))
+            .join("cache.bin");
+        store(&path, 1, &[1_u32, 2, 3][..]).unwrap();
+        assert_eq!(load::<Vec<u32>>(&path, 1), Some(vec![1, 2, 3]));
+        assert_eq!(load::<Vec<u32>>(&path, 2), None);
+
+        std::fs::write(&path, b"garbage").unwrap();
+        assert_eq!(load::<Vec<u32>>(&path, 1), None);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+        assert_eq!(load::<Vec<u32>>(&path, 1), None);
+    }
+}
@@ -0,0 +1,44 @@
+//! GNOME Shell window backend, through the window-calls extension
+
+use super::{OpenWindow, WindowBackend, app_details};
+use crate::actions::workspace::{fetch_listed_windows, focus_window};
+use crate::core::global_state::get_tokio_runtime;
+use crate::utils::desktop::DesktopIndex;
+
+/// Windows of GNOME Shell, listed and focused by the window-calls extension
+pub struct GnomeShell;
+
+impl WindowBackend for GnomeShell {
+    fn name(&self) -> &'static str {
+        "GNOME Shell"
+    }
+
+    fn list_windows(&self) -> Option<Vec<OpenWindow>> {
+        let listed = get_tokio_runtime().block_on(fetch_listed_windows())?;
+        let index = DesktopIndex::build();
+        let windows = listed
+            .into_iter()
+            .map(|w| {
+                let (app_name, icon) = app_details(&index, &[&w.wm_class, &w.wm_class_instance]);
+                let title = if w.title.is_empty() {
+                    app_name.clone()
+                } else {
+                    w.title
+                };
+                OpenWindow {
+                    id: w.id,
+                    title,
+                    app_id: w.wm_class,
+                    app_name,
+                    icon,
+                    on_current_workspace: Some(w.in_current_workspace),
+                }
+            })
+            .collect();
+        Some(windows)
+    }
+
+    fn focus(&self, window: &OpenWindow) -> bool {
+        get_tokio_runtime().block_on(focus_window(window.id))
+    }
+}
@@ -0,0 +1,227 @@
+//! Open windows for the `:w` mode
+//!
+//! `:w` lists the windows open on the desktop and focuses the selected one.
+//! How windows are listed and focused depends on the compositor, so each one
+//! is a [`WindowBackend`]:
+//!
+//! - GNOME Shell, through the window-calls extension that the workspace bar
+//!   and running-app detection already use.
+//! - wlroots compositors (Sway, Hyprland, river, ...), through the
+//!   `wlr-foreign-toplevel-management` protocol. This backend is built with
+//!   the `wlroots` cargo feature.
+//!
+//! Backends block, so they are called from a worker thread. Listing is a
+//! single D-Bus call or two Wayland roundtrips, far below the command
+//! debounce.
+
+mod gnome;
+#[cfg(feature = "wlroots")]
+mod wlroots;
+
+use crate::providers::ranking::abbreviation_score;
+use crate::utils::desktop::DesktopIndex;
+use fuzzy_matcher::FuzzyMatcher;
+use log::debug;
+
+/// A window open on the desktop
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OpenWindow {
+    /// The backend's handle of the window: the window ID on GNOME, the
+    /// position in the toplevel list on wlroots
+    pub id: u32,
+    pub title: String,
+    /// Window class or Wayland app ID
+    pub app_id: String,
+    /// Name of the application from its desktop entry, or `app_id`
+    pub app_name: String,
+    /// Icon of the application, empty if unknown
+    pub icon: String,
+    /// Whether the window is on the current workspace, if the backend knows
+    pub on_current_workspace: Option<bool>,
+}
+
+impl OpenWindow {
+    /// Second line of the row: the application and the workspace
+    #[must_use]
+    pub fn description(&self) -> String {
+        match self.on_current_workspace {
+            Some(true) => format!("{} · This workspace", self.app_name),
+            Some(false) => format!("{} · Other workspace", self.app_name),
+            None => self.app_name.clone(),
+        }
+    }
+}
+
+/// Lists and focuses the windows of one kind of compositor
+pub trait WindowBackend: Send + Sync {
+    /// Name for log messages
+    fn name(&self) -> &'static str;
+
+    /// The open windows, except Grunner's own, or `None` if they cannot be
+    /// listed
+    fn list_windows(&self) -> Option<Vec<OpenWindow>>;
+
+    /// Focus `window`, returning whether the compositor accepted it
+    fn focus(&self, window: &OpenWindow) -> bool;
+}
+
+/// Compositors with a window backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackendKind {
+    GnomeShell,
+    Wlroots,
+}
+
+/// Backend for a session with `XDG_CURRENT_DESKTOP` `current_desktop`
+///
+/// GNOME is named in the desktop list; any other Wayland session is assumed
+/// to be a wlroots compositor. Other X11 desktops have no backend.
+#[must_use]
+pub fn backend_kind(current_desktop: &str, wayland: bool) -> Option<BackendKind> {
+    if current_desktop
+        .split(':')
+        .any(|desktop| desktop.eq_ignore_ascii_case("GNOME"))
+    {
+        Some(BackendKind::GnomeShell)
+    } else if wayland {
+        Some(BackendKind::Wlroots)
+    } else {
+        None
+    }
+}
+
+/// Window backend of the running session, if there is one
+#[must_use]
+pub fn backend() -> Option<&'static dyn WindowBackend> {
+    let desktop = std::env::var("XDG_CURRENT_DESKTOP").unwrap_or_default();
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+    match backend_kind(&desktop, wayland) {
+        Some(BackendKind::GnomeShell) => Some(&gnome::GnomeShell),
+        Some(BackendKind::Wlroots) => wlroots_backend(),
+        None => {
+            debug!("[windows] No window backend for desktop {desktop:?}");
+            None
+        }
+    }
+}
+
+#[cfg(feature = "wlroots")]
+fn wlroots_backend() -> Option<&'static dyn WindowBackend> {
+    Some(&wlroots::Wlroots)
+}
+
+#[cfg(not(feature = "wlroots"))]
+fn wlroots_backend() -> Option<&'static dyn WindowBackend> {
+    log::warn!("[windows] Built without the wlroots feature, :w cannot list windows");
+    None
+}
+
+/// Application name and icon of the window with class or app ID `ids`
+///
+/// The first ID with a desktop entry wins; without one, the name is the
+/// first non-empty ID and the icon is left to the theme's lookup of it.
+fn app_details(index: &DesktopIndex, ids: &[&str]) -> (String, String) {
+    let ids: Vec<&str> = ids.iter().copied().filter(|id| !id.is_empty()).collect();
+    if let Some(info) = ids.iter().find_map(|id| index.info(id)) {
+        return (info.name, info.icon.unwrap_or_default());
+    }
+    let fallback = ids.first().copied().unwrap_or_default();
+    (fallback.to_string(), fallback.to_lowercase())
+}
+
+/// The windows matching `query`, best first
+///
+/// Every word has to match the window title or the application name. An
+/// empty query lists the windows in the backend's order.
+#[must_use]
+pub fn match_windows<'a>(
+    matcher: &impl FuzzyMatcher,
+    query: &str,
+    windows: &'a [OpenWindow],
+    max: usize,
+) -> Vec<&'a OpenWindow> {
+    let words: Vec<&str> = query.split_whitespace().collect();
+    if words.is_empty() {
+        return windows.iter().take(max).collect();
+    }
+    let mut scored: Vec<(i64, &OpenWindow)> = windows
+        .iter()
+        .filter_map(|window| {
+            words
+                .iter()
+                .map(|word| {
+                    let title = abbreviation_score(matcher, &window.title, word);
+                    let app = abbreviation_score(matcher, &window.app_name, word);
+                    title.max(app)
+                })
+                .sum::<Option<i64>>()
+                .map(|score| (score, window))
+        })
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().take(max).map(|(_, w)| w).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuzzy_matcher::skim::SkimMatcherV2;
+
+    fn window(id: u32, title: &str, app_name: &str) -> OpenWindow {
+        OpenWindow {
+            id,
+            title: title.to_string(),
+            app_id: app_name.to_lowercase(),
+            app_name: app_name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_backend_kind() {
+        assert_eq!(backend_kind("GNOME", true), Some(BackendKind::GnomeShell));
+        assert_eq!(
+            backend_kind("ubuntu:GNOME", false),
+            Some(BackendKind::GnomeShell)
+        );
+        assert_eq!(backend_kind("sway", true), Some(BackendKind::Wlroots));
+        assert_eq!(backend_kind("Hyprland", true), Some(BackendKind::Wlroots));
+        assert_eq!(backend_kind("XFCE", false), None);
+        assert_eq!(backend_kind("", false), None);
+    }
+
+    #[test]
+    fn test_description() {
+        let mut w = window(1, "Inbox", "Thunderbird");
+        assert_eq!(w.description(), "Thunderbird");
+        w.on_current_workspace = Some(true);
+        assert_eq!(w.description(), "Thunderbird · This workspace");
+        w.on_current_workspace = Some(false);
+        assert_eq!(w.description(), "Thunderbird · Other workspace");
+    }
+
+    #[test]
+    fn test_match_windows() {
+        let matcher = SkimMatcherV2::default();
+        let windows = vec![
+            window(1, "grunner — ~/src", "Terminal"),
+            window(2, "Rust Programming Language — Mozilla Firefox", "Firefox"),
+            window(3, "Inbox", "Thunderbird"),
+        ];
+
+        let all = match_windows(&matcher, "", &windows, 10);
+        assert_eq!(all.iter().map(|w| w.id).collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(match_windows(&matcher, "  ", &windows, 2).len(), 2);
+
+        // By title or application name
+        let found = match_windows(&matcher, "rust", &windows, 10);
+        assert_eq!(found.first().map(|w| w.id), Some(2));
+        let found = match_windows(&matcher, "thunder", &windows, 10);
+        assert_eq!(found.first().map(|w| w.id), Some(3));
+
+        // Every word has to match
+        let found = match_windows(&matcher, "firefox rust", &windows, 10);
+        assert_eq!(found.iter().map(|w| w.id).collect::<Vec<_>>(), [2]);
+        assert!(match_windows(&matcher, "firefox inbox", &windows, 10).is_empty());
+    }
+}
@@ -0,0 +1,223 @@
+//! wlroots window backend, through `wlr-foreign-toplevel-management`
+//!
+//! Each call opens its own Wayland connection, separate from GTK's, binds
+//! the toplevel manager and waits for the compositor to announce every
+//! toplevel with its title and app ID. The protocol has no stable window
+//! IDs, so a window is focused by its position in that list, checked
+//! against its title and app ID in case the list changed in between.
+
+use super::{OpenWindow, WindowBackend, app_details};
+use crate::actions::workspace::OWN_WM_CLASS;
+use crate::utils::desktop::DesktopIndex;
+use log::{debug, warn};
+use wayland_client::globals::{GlobalListContents, registry_queue_init};
+use wayland_client::protocol::{wl_registry, wl_seat};
+use wayland_client::{Connection, Dispatch, EventQueue, Proxy, QueueHandle, event_created_child};
+use wayland_protocols_wlr::foreign_toplevel::v1::client::{
+    zwlr_foreign_toplevel_handle_v1::{self, ZwlrForeignToplevelHandleV1},
+    zwlr_foreign_toplevel_manager_v1::{self, ZwlrForeignToplevelManagerV1},
+};
+
+/// Windows of a wlroots compositor, listed and focused through the
+/// foreign toplevel protocol
+pub struct Wlroots;
+
+impl WindowBackend for Wlroots {
+    fn name(&self) -> &'static str {
+        "wlroots"
+    }
+
+    fn list_windows(&self) -> Option<Vec<OpenWindow>> {
+        let session = Session::open()?;
+        let index = DesktopIndex::build();
+        let windows = session
+            .toplevels()
+            .enumerate()
+            .filter(|(_, toplevel)| toplevel.app_id != OWN_WM_CLASS)
+            .filter_map(|(position, toplevel)| {
+                let id = u32::try_from(position).ok()?;
+                let (app_name, icon) = app_details(&index, &[&toplevel.app_id]);
+                Some(OpenWindow {
+                    id,
+                    title: toplevel.title.clone(),
+                    app_id: toplevel.app_id.clone(),
+                    app_name,
+                    icon,
+                    on_current_workspace: None,
+                })
+            })
+            .collect();
+        Some(windows)
+    }
+
+    fn focus(&self, window: &OpenWindow) -> bool {
+        let Some(mut session) = Session::open() else {
+            return false;
+        };
+        let Some(seat) = session.seat.clone() else {
+            warn!("[windows] The compositor offers no seat to focus windows with");
+            return false;
+        };
+        let same = |t: &&Toplevel| t.title == window.title && t.app_id == window.app_id;
+        let position = usize::try_from(window.id).unwrap_or(usize::MAX);
+        let Some(toplevel) = session
+            .toplevels()
+            .nth(position)
+            .filter(same)
+            .or_else(|| session.toplevels().find(same))
+        else {
+            debug!("[windows] Window {:?} is gone", window.title);
+            return false;
+        };
+        toplevel.handle.activate(&seat);
+        session.finish()
+    }
+}
+
+/// A toplevel as announced by the compositor
+struct Toplevel {
+    handle: ZwlrForeignToplevelHandleV1,
+    title: String,
+    app_id: String,
+    closed: bool,
+}
+
+/// Toplevels received on the connection
+#[derive(Default)]
+struct State {
+    toplevels: Vec<Toplevel>,
+}
+
+/// A connection with the toplevel list received
+struct Session {
+    queue: EventQueue<State>,
+    state: State,
+    manager: ZwlrForeignToplevelManagerV1,
+    seat: Option<wl_seat::WlSeat>,
+}
+
+impl Session {
+    /// Connect and receive the current toplevels
+    fn open() -> Option<Self> {
+        let conn = Connection::connect_to_env()
+            .map_err(|e| warn!("[windows] Wayland connection failed: {e}"))
+            .ok()?;
+        let (globals, mut queue) = registry_queue_init::<State>(&conn)
+            .map_err(|e| warn!("[windows] Wayland registry failed: {e}"))
+            .ok()?;
+        let qh = queue.handle();
+        let manager: ZwlrForeignToplevelManagerV1 = globals
+            .bind(&qh, 1..=3, ())
+            .map_err(|e| {
+                warn!("[windows] The compositor lacks wlr-foreign-toplevel-management: {e}");
+            })
+            .ok()?;
+        let seat = globals.bind(&qh, 1..=1, ()).ok();
+
+        let mut state = State::default();
+        // The first roundtrip announces the toplevels, the second their
+        // title and app ID
+        for _ in 0..2 {
+            queue
+                .roundtrip(&mut state)
+                .map_err(|e| warn!("[windows] Wayland roundtrip failed: {e}"))
+                .ok()?;
+        }
+        Some(Self {
+            queue,
+            state,
+            manager,
+            seat,
+        })
+    }
+
+    /// Open toplevels in the compositor's order
+    fn toplevels(&self) -> impl Iterator<Item = &Toplevel> {
+        self.state.toplevels.iter().filter(|t| !t.closed)
+    }
+
+    /// Send the requests made and stop listening for toplevels
+    fn finish(&mut self) -> bool {
+        self.manager.stop();
+        match self.queue.roundtrip(&mut self.state) {
+            Ok(_) => true,
+            Err(e) => {
+                warn!("[windows] Wayland roundtrip failed: {e}");
+                false
+            }
+        }
+    }
+}
+
+impl Dispatch<wl_registry::WlRegistry, GlobalListContents> for State {
+    fn event(
+        _: &mut Self,
+        _: &wl_registry::WlRegistry,
+        _: wl_registry::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<wl_seat::WlSeat, ()> for State {
+    fn event(
+        _: &mut Self,
+        _: &wl_seat::WlSeat,
+        _: wl_seat::Event,
+        (): &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwlrForeignToplevelManagerV1, ()> for State {
+    fn event(
+        state: &mut Self,
+        _: &ZwlrForeignToplevelManagerV1,
+        event: zwlr_foreign_toplevel_manager_v1::Event,
+        (): &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let zwlr_foreign_toplevel_manager_v1::Event::Toplevel { toplevel } = event {
+            state.toplevels.push(Toplevel {
+                handle: toplevel,
+                title: String::new(),
+                app_id: String::new(),
+                closed: false,
+            });
+        }
+    }
+
+    event_created_child!(State, ZwlrForeignToplevelManagerV1, [
+        zwlr_foreign_toplevel_manager_v1::EVT_TOPLEVEL_OPCODE => (ZwlrForeignToplevelHandleV1, ())
+    ]);
+}
+
+impl Dispatch<ZwlrForeignToplevelHandleV1, ()> for State {
+    fn event(
+        state: &mut Self,
+        handle: &ZwlrForeignToplevelHandleV1,
+        event: zwlr_foreign_toplevel_handle_v1::Event,
+        (): &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let Some(toplevel) = state
+            .toplevels
+            .iter_mut()
+            .find(|t| t.handle.id() == handle.id())
+        else {
+            return;
+        };
+        match event {
+            zwlr_foreign_toplevel_handle_v1::Event::Title { title } => toplevel.title = title,
+            zwlr_foreign_toplevel_handle_v1::Event::AppId { app_id } => toplevel.app_id = app_id,
+            zwlr_foreign_toplevel_handle_v1::Event::Closed => toplevel.closed = true,
+            _ => {}
+        }
+    }
+}
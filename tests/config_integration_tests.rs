@@ -3,6 +3,48 @@
 use grunner::core::config::{
     Config, DEFAULT_MAX_RESULTS, DEFAULT_WINDOW_HEIGHT, DEFAULT_WINDOW_WIDTH, default_app_dirs,
 };
+use std::ffi::OsString;
+use std::sync::{Mutex, PoisonError};
+
+/// Serializes the tests that change the environment of this process
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+/// Set or unset an environment variable
+fn set_env(key: &str, value: Option<&OsString>) {
+    // SAFETY: every test that reads or writes these variables holds
+    // ENV_LOCK, so no other thread accesses the environment meanwhile
+    unsafe {
+        match value {
+            Some(value) => std::env::set_var(key, value),
+            None => std::env::remove_var(key),
+        }
+    }
+}
+
+/// Run `f` with each of `vars` set to its value, or unset for `None`
+///
+/// The previous values are restored afterwards, even if `f` panics.
+fn with_env<T>(vars: &[(&str, Option<&str>)], f: impl FnOnce() -> T) -> T {
+    struct Restore(Vec<(String, Option<OsString>)>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            for (key, value) in &self.0 {
+                set_env(key, value.as_ref());
+            }
+        }
+    }
+
+    let _lock = ENV_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    let _restore = Restore(
+        vars.iter()
+            .map(|(key, _)| ((*key).to_string(), std::env::var_os(key)))
+            .collect(),
+    );
+    for (key, value) in vars {
+        set_env(key, value.map(OsString::from).as_ref());
+    }
+    f()
+}
 
 #[test]
 fn test_config_default_values() {
@@ -15,8 +57,47 @@ fn test_config_default_values() {
 
 #[test]
 fn test_default_app_dirs_count() {
-    let dirs = default_app_dirs();
-    assert_eq!(dirs.len(), 5);
+    // Without XDG_DATA_DIRS, or with an empty one, the five fallback dirs
+    let fallback = [
+        "/usr/share/applications",
+        "/usr/local/share/applications",
+        "~/.local/share/applications",
+        "/var/lib/flatpak/exports/share/applications",
+        "~/.local/share/flatpak/exports/share/applications",
+    ];
+    for data_dirs in [None, Some(""), Some(" ")] {
+        let dirs = with_env(&[("XDG_DATA_DIRS", data_dirs)], default_app_dirs);
+        assert_eq!(dirs, fallback, "XDG_DATA_DIRS={data_dirs:?}");
+    }
+}
+
+#[test]
+fn test_default_app_dirs_from_xdg_data_dirs() {
+    // The data home, then each XDG_DATA_DIRS entry
+    let dirs = with_env(
+        &[
+            ("XDG_DATA_HOME", None),
+            ("XDG_DATA_DIRS", Some("/nix/profile/share:/usr/share/")),
+        ],
+        default_app_dirs,
+    );
+    assert_eq!(
+        dirs,
+        [
+            "~/.local/share/applications",
+            "/nix/profile/share/applications",
+            "/usr/share/applications",
+        ]
+    );
+
+    let dirs = with_env(
+        &[
+            ("XDG_DATA_HOME", Some("/data/home")),
+            ("XDG_DATA_DIRS", Some("/usr/share")),
+        ],
+        default_app_dirs,
+    );
+    assert_eq!(dirs, ["/data/home/applications", "/usr/share/applications"]);
 }
 
 #[test]